@@ -472,4 +472,8 @@ impl Render for ActivityIndicator {
 
 impl StatusItemView for ActivityIndicator {
     fn set_active_pane_item(&mut self, _: Option<&dyn ItemHandle>, _: &mut ViewContext<Self>) {}
+
+    fn status_item_id(&self, _: &AppContext) -> Option<&'static str> {
+        Some("activity_indicator")
+    }
 }
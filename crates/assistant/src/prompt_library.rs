@@ -874,6 +874,10 @@ impl PromptLibrary {
                                                         color: Some(cx.theme().status().predictive),
                                                         ..HighlightStyle::default()
                                                     },
+                                                    inline_values_style: HighlightStyle {
+                                                        color: Some(cx.theme().status().info),
+                                                        ..HighlightStyle::default()
+                                                    },
                                                 },
                                             )),
                                     ),
@@ -15,7 +15,10 @@ use markdown_preview::markdown_preview_view::{MarkdownPreviewMode, MarkdownPrevi
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_derive::Serialize;
-use smol::{fs, io::AsyncReadExt};
+use smol::{
+    fs,
+    io::{AsyncReadExt, AsyncWriteExt},
+};
 
 use settings::{Settings, SettingsSources, SettingsStore};
 use smol::{fs::File, process::Command};
@@ -35,7 +38,7 @@ use std::{
 use update_notification::UpdateNotification;
 use util::ResultExt;
 use workspace::notifications::NotificationId;
-use workspace::Workspace;
+use workspace::{ui::UpdateState, RestartToUpdate, Workspace};
 
 const SHOULD_SHOW_UPDATE_NOTIFICATION_KEY: &str = "auto-updater-should-show-updated-notification";
 const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
@@ -75,6 +78,8 @@ impl AutoUpdateStatus {
 
 pub struct AutoUpdater {
     status: AutoUpdateStatus,
+    /// The fraction of the update downloaded so far, between 0 and 1.
+    download_progress: f32,
     current_version: SemanticVersion,
     http_client: Arc<HttpClientWithUrl>,
     pending_poll: Option<Task<Option<()>>>,
@@ -124,7 +129,7 @@ struct ReleaseNotesBody {
 pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut AppContext) {
     AutoUpdateSetting::register(cx);
 
-    cx.observe_new_views(|workspace: &mut Workspace, _cx| {
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
         workspace.register_action(|_, action: &Check, cx| check(action, cx));
 
         workspace.register_action(|_, action, cx| {
@@ -134,6 +139,31 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut AppContext) {
         workspace.register_action(|workspace, _: &ViewReleaseNotesLocally, cx| {
             view_release_notes_locally(workspace, cx);
         });
+
+        workspace.register_action(|_, _: &RestartToUpdate, cx| {
+            if let Some(AutoUpdateStatus::Updated { binary_path }) =
+                AutoUpdater::get(cx).map(|updater| updater.read(cx).status())
+            {
+                workspace::reload(
+                    &workspace::Reload {
+                        binary_path: Some(binary_path),
+                    },
+                    cx,
+                );
+            }
+        });
+
+        if let Some(updater) = AutoUpdater::get(cx) {
+            let status_bar = workspace.status_bar().clone();
+            status_bar.update(cx, |status_bar, cx| {
+                status_bar.update_status(updater.read(cx).update_state(), cx)
+            });
+            cx.observe(&updater, move |_, updater, cx| {
+                let state = updater.read(cx).update_state();
+                status_bar.update(cx, |status_bar, cx| status_bar.update_status(state, cx));
+            })
+            .detach();
+        }
     })
     .detach();
 
@@ -334,6 +364,7 @@ impl AutoUpdater {
     fn new(current_version: SemanticVersion, http_client: Arc<HttpClientWithUrl>) -> Self {
         Self {
             status: AutoUpdateStatus::Idle,
+            download_progress: 0.,
             current_version,
             http_client,
             pending_poll: None,
@@ -375,6 +406,20 @@ impl AutoUpdater {
         self.status.clone()
     }
 
+    /// The state of the update, as shown by the status bar's update indicator.
+    pub fn update_state(&self) -> UpdateState {
+        match &self.status {
+            AutoUpdateStatus::Idle => UpdateState::None,
+            AutoUpdateStatus::Checking => UpdateState::Checking,
+            AutoUpdateStatus::Downloading => UpdateState::Downloading(self.download_progress),
+            AutoUpdateStatus::Installing => UpdateState::Installing,
+            AutoUpdateStatus::Updated { .. } => UpdateState::ReadyToRestart,
+            AutoUpdateStatus::Errored => {
+                UpdateState::Errored("Failed to update Zed. See the log for details.".into())
+            }
+        }
+    }
+
     pub fn dismiss_error(&mut self, cx: &mut ModelContext<Self>) {
         self.status = AutoUpdateStatus::Idle;
         cx.notify();
@@ -435,13 +480,15 @@ impl AutoUpdater {
 
         this.update(&mut cx, |this, cx| {
             this.status = AutoUpdateStatus::Downloading;
+            this.download_progress = 0.;
             cx.notify();
         })?;
 
         let temp_dir = tempfile::Builder::new()
             .prefix("zed-auto-update")
             .tempdir()?;
-        let downloaded_asset = download_release(&temp_dir, release, &asset, client, &cx).await?;
+        let downloaded_asset =
+            download_release(&temp_dir, release, &asset, client, &this, &mut cx).await?;
 
         this.update(&mut cx, |this, cx| {
             this.status = AutoUpdateStatus::Installing;
@@ -505,7 +552,8 @@ async fn download_release(
     release: JsonRelease,
     target_filename: &str,
     client: Arc<HttpClientWithUrl>,
-    cx: &AsyncAppContext,
+    this: &Model<AutoUpdater>,
+    cx: &mut AsyncAppContext,
 ) -> Result<PathBuf> {
     let target_path = temp_dir.path().join(target_filename);
     let mut target_file = File::create(&target_path).await?;
@@ -526,7 +574,36 @@ async fn download_release(
     })?);
 
     let mut response = client.get(&release.url, request_body, true).await?;
-    smol::io::copy(response.body_mut(), &mut target_file).await?;
+    let total_len = response
+        .headers()
+        .get("content-length")
+        .and_then(|len| len.to_str().ok())
+        .and_then(|len| len.parse::<u64>().ok())
+        .filter(|len| *len > 0);
+    let mut buffer = vec![0; 64 * 1024];
+    let mut downloaded_len = 0;
+    let mut reported_percent = 0;
+    loop {
+        let len = response.body_mut().read(&mut buffer).await?;
+        if len == 0 {
+            break;
+        }
+        target_file.write_all(&buffer[..len]).await?;
+        downloaded_len += len as u64;
+
+        // Only report whole percents, to avoid notifying observers for every chunk.
+        if let Some(total_len) = total_len {
+            let percent = (downloaded_len * 100 / total_len).min(100);
+            if percent > reported_percent {
+                reported_percent = percent;
+                this.update(cx, |this, cx| {
+                    this.download_progress = percent as f32 / 100.;
+                    cx.notify();
+                })?;
+            }
+        }
+    }
+    target_file.flush().await?;
     log::info!("downloaded update. path:{:?}", target_path);
 
     Ok(target_path)
@@ -33,13 +33,27 @@ impl EventEmitter<ToolbarItemEvent> for Breadcrumbs {}
 impl Render for Breadcrumbs {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         const MAX_SEGMENTS: usize = 12;
-        let element = h_flex().text_ui(cx);
+        let element = h_flex().min_w_0().text_ui(cx);
         let Some(active_item) = self.active_item.as_ref() else {
             return element;
         };
         let Some(mut segments) = active_item.breadcrumbs(cx.theme(), cx) else {
             return element;
         };
+        let editor = active_item.downcast::<Editor>();
+        let is_file_path = editor.as_ref().map_or(false, |editor| {
+            editor.read(cx).breadcrumb_header().is_none()
+        });
+        let editor = editor.map(|editor| editor.downgrade());
+
+        // An editor's first breadcrumb is the path of its file, unless the editor shows a
+        // header in its place. It's shown on its own so that it can be shortened to fit the
+        // toolbar.
+        let path = if editor.is_some() && !segments.is_empty() {
+            Some(segments.remove(0))
+        } else {
+            None
+        };
 
         let prefix_end_ix = cmp::min(segments.len(), MAX_SEGMENTS / 2);
         let suffix_start_ix = cmp::max(
@@ -57,6 +71,7 @@ impl Render for Breadcrumbs {
             );
         }
 
+        let has_symbols = !segments.is_empty();
         let highlighted_segments = segments.into_iter().map(|segment| {
             let mut text_style = cx.text_style();
             if let Some(font) = segment.font {
@@ -76,27 +91,31 @@ impl Render for Breadcrumbs {
         });
 
         let breadcrumbs_stack = h_flex().gap_1().children(breadcrumbs);
-        match active_item
-            .downcast::<Editor>()
-            .map(|editor| editor.downgrade())
-        {
-            Some(editor) => element.child(
-                ButtonLike::new("toggle outline view")
-                    .child(breadcrumbs_stack)
-                    .style(ButtonStyle::Transparent)
-                    .on_click(move |_, cx| {
-                        if let Some(editor) = editor.upgrade() {
-                            outline::toggle(editor, &editor::actions::ToggleOutline, cx)
-                        }
-                    })
-                    .tooltip(|cx| {
-                        Tooltip::for_action(
-                            "Show symbol outline",
-                            &editor::actions::ToggleOutline,
-                            cx,
+        match editor {
+            Some(editor) => element
+                .gap_1()
+                .children(path.map(|path| render_path(path, is_file_path)))
+                .when(has_symbols, |element| {
+                    element
+                        .child(Label::new("›").color(Color::Placeholder))
+                        .child(
+                            ButtonLike::new("toggle outline view")
+                                .child(breadcrumbs_stack)
+                                .style(ButtonStyle::Transparent)
+                                .on_click(move |_, cx| {
+                                    if let Some(editor) = editor.upgrade() {
+                                        outline::toggle(editor, &editor::actions::ToggleOutline, cx)
+                                    }
+                                })
+                                .tooltip(|cx| {
+                                    Tooltip::for_action(
+                                        "Show symbol outline",
+                                        &editor::actions::ToggleOutline,
+                                        cx,
+                                    )
+                                }),
                         )
-                    }),
-            ),
+                }),
             None => element
                 // Match the height of the `ButtonLike` in the other arm.
                 .h(rems_from_px(22.))
@@ -105,6 +124,43 @@ impl Render for Breadcrumbs {
     }
 }
 
+/// Renders the first breadcrumb of an editor. The path of its file is split into its
+/// directories, so that the ones in the middle can be collapsed when it doesn't fit, while a
+/// header shown in its place is kept whole.
+fn render_path(path: BreadcrumbText, is_file_path: bool) -> impl IntoElement {
+    let components = if is_file_path {
+        path.text
+            .split(std::path::MAIN_SEPARATOR)
+            .filter(|component| !component.is_empty())
+            .map(str::to_string)
+            .collect()
+    } else {
+        vec![path.text]
+    };
+    let last_ix = components.len().saturating_sub(1);
+    let segments = components
+        .into_iter()
+        .enumerate()
+        .map(|(ix, component)| {
+            if ix == last_ix {
+                ui::BreadcrumbSegment::file(component)
+            } else {
+                ui::BreadcrumbSegment::directory(component)
+            }
+        })
+        .collect();
+
+    div()
+        .min_w_0()
+        .h(rems_from_px(22.))
+        .flex()
+        .items_center()
+        .child(
+            ui::Breadcrumbs::new("path", segments)
+                .when_some(path.font, |breadcrumbs, font| breadcrumbs.font(font)),
+        )
+}
+
 impl ToolbarItemView for Breadcrumbs {
     fn set_active_pane_item(
         &mut self,
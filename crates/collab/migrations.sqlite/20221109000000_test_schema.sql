@@ -115,6 +115,17 @@ CREATE TABLE "worktree_settings_files" (
 CREATE INDEX "index_worktree_settings_files_on_project_id" ON "worktree_settings_files" ("project_id");
 CREATE INDEX "index_worktree_settings_files_on_project_id_and_worktree_id" ON "worktree_settings_files" ("project_id", "worktree_id");
 
+CREATE TABLE "annotation_threads" (
+    "project_id" INTEGER NOT NULL,
+    "id" INTEGER NOT NULL,
+    "worktree_id" INTEGER NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "thread" BLOB NOT NULL,
+    PRIMARY KEY(project_id, id),
+    FOREIGN KEY(project_id, worktree_id) REFERENCES worktrees (project_id, id) ON DELETE CASCADE
+);
+CREATE INDEX "index_annotation_threads_on_project_id_and_worktree_id_and_path" ON "annotation_threads" ("project_id", "worktree_id", "path");
+
 CREATE TABLE "worktree_diagnostic_summaries" (
     "project_id" INTEGER NOT NULL,
     "worktree_id" INTEGER NOT NULL,
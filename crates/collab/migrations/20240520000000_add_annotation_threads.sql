@@ -0,0 +1,10 @@
+CREATE TABLE "annotation_threads" (
+    "project_id" INTEGER NOT NULL,
+    "id" INTEGER NOT NULL,
+    "worktree_id" INTEGER NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "thread" BYTEA NOT NULL,
+    PRIMARY KEY(project_id, id),
+    FOREIGN KEY(project_id, worktree_id) REFERENCES worktrees (project_id, id) ON DELETE CASCADE
+);
+CREATE INDEX "index_annotation_threads_on_project_id_and_worktree_id_and_path" ON "annotation_threads" ("project_id", "worktree_id", "path");
@@ -1,6 +1,7 @@
 use super::*;
 
 pub mod access_tokens;
+pub mod annotation_threads;
 pub mod buffers;
 pub mod channels;
 pub mod contacts;
@@ -0,0 +1,96 @@
+use super::*;
+use prost::Message;
+
+impl Database {
+    /// Stores an annotation thread sent by the host of a project, returning the connections of
+    /// the guests it should be forwarded to.
+    pub async fn update_annotation_thread(
+        &self,
+        update: &proto::UpdateAnnotationThread,
+        connection: ConnectionId,
+    ) -> Result<TransactionGuard<Vec<ConnectionId>>> {
+        let project_id = ProjectId::from_proto(update.project_id);
+        let thread = update
+            .thread
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing thread"))?;
+        self.project_transaction(project_id, |tx| async move {
+            // Ensure the update comes from the host.
+            let project = project::Entity::find_by_id(project_id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such project"))?;
+            if project.host_connection()? != connection {
+                return Err(anyhow!("can't update a project hosted by someone else"))?;
+            }
+
+            annotation_thread::Entity::insert(annotation_thread::ActiveModel {
+                project_id: ActiveValue::Set(project_id),
+                id: ActiveValue::Set(thread.id as i64),
+                worktree_id: ActiveValue::Set(thread.worktree_id as i64),
+                path: ActiveValue::Set(thread.path.clone()),
+                thread: ActiveValue::Set(thread.encode_to_vec()),
+            })
+            .on_conflict(
+                OnConflict::columns([
+                    annotation_thread::Column::ProjectId,
+                    annotation_thread::Column::Id,
+                ])
+                .update_columns([
+                    annotation_thread::Column::WorktreeId,
+                    annotation_thread::Column::Path,
+                    annotation_thread::Column::Thread,
+                ])
+                .to_owned(),
+            )
+            .exec(&*tx)
+            .await?;
+
+            let connection_ids = self.project_guest_connection_ids(project_id, &tx).await?;
+            Ok(connection_ids)
+        })
+        .await
+    }
+
+    /// Returns the annotation threads stored for the given path of a shared project.
+    pub async fn get_annotation_threads(
+        &self,
+        project_id: ProjectId,
+        worktree_id: u64,
+        path: &str,
+        connection_id: ConnectionId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::AnnotationThread>> {
+        self.project_transaction(project_id, |tx| async move {
+            self.access_project(
+                project_id,
+                connection_id,
+                PrincipalId::UserId(user_id),
+                Capability::ReadOnly,
+                &tx,
+            )
+            .await?;
+
+            let rows = annotation_thread::Entity::find()
+                .filter(
+                    annotation_thread::Column::ProjectId
+                        .eq(project_id)
+                        .and(annotation_thread::Column::WorktreeId.eq(worktree_id as i64))
+                        .and(annotation_thread::Column::Path.eq(path)),
+                )
+                .order_by_asc(annotation_thread::Column::Id)
+                .all(&*tx)
+                .await?;
+            let threads = rows
+                .into_iter()
+                .map(|row| {
+                    proto::AnnotationThread::decode(row.thread.as_slice())
+                        .map_err(|error| anyhow!("{}", error).into())
+                })
+                .collect::<Result<_>>()?;
+            Ok(threads)
+        })
+        .await
+        .map(|guard| guard.into_inner())
+    }
+}
@@ -1,4 +1,5 @@
 pub mod access_token;
+pub mod annotation_thread;
 pub mod buffer;
 pub mod buffer_operation;
 pub mod buffer_snapshot;
@@ -602,6 +602,17 @@ impl Server {
                 forward_mutating_project_request::<proto::SynchronizeContexts>,
             ))
             .add_message_handler(broadcast_project_message_from_host::<proto::AdvertiseContexts>)
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::CreateAnnotationThread>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::ReplyToAnnotationThread>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::ResolveAnnotationThread>,
+            ))
+            .add_message_handler(update_annotation_thread)
+            .add_request_handler(user_handler(get_annotation_threads))
             .add_message_handler(update_context)
             .add_streaming_request_handler({
                 let app_state = app_state.clone();
@@ -2833,6 +2844,51 @@ async fn update_worktree_settings(
     Ok(())
 }
 
+/// Store an annotation thread and forward it to the guests of the project.
+async fn update_annotation_thread(
+    message: proto::UpdateAnnotationThread,
+    session: Session,
+) -> Result<()> {
+    let guest_connection_ids = session
+        .db()
+        .await
+        .update_annotation_thread(&message, session.connection_id)
+        .await?;
+
+    broadcast(
+        Some(session.connection_id),
+        guest_connection_ids.iter().copied(),
+        |connection_id| {
+            session
+                .peer
+                .forward_send(session.connection_id, connection_id, message.clone())
+        },
+    );
+
+    Ok(())
+}
+
+/// Return the annotation threads stored for a path of a shared project.
+async fn get_annotation_threads(
+    request: proto::GetAnnotationThreads,
+    response: Response<proto::GetAnnotationThreads>,
+    session: UserSession,
+) -> Result<()> {
+    let threads = session
+        .db()
+        .await
+        .get_annotation_threads(
+            ProjectId::from_proto(request.project_id),
+            request.worktree_id,
+            &request.path,
+            session.connection_id,
+            session.user_id(),
+        )
+        .await?;
+    response.send(proto::GetAnnotationThreadsResponse { threads })?;
+    Ok(())
+}
+
 /// Notify other participants that a  language server has started.
 async fn start_language_server(
     request: proto::StartLanguageServer,
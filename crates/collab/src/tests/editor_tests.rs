@@ -15,15 +15,15 @@ use editor::{
         editor_test_context::{AssertionContextManager, EditorTestContext},
         expanded_hunks, expanded_hunks_background_highlights,
     },
-    Editor,
+    Anchor, Editor, ToOffset, CURSORS_VISIBLE_FOR,
 };
 use futures::StreamExt;
 use git::diff::DiffHunkStatus;
-use gpui::{TestAppContext, UpdateGlobal, VisualContext, VisualTestContext};
+use gpui::{TestAppContext, UpdateGlobal, ViewContext, VisualContext, VisualTestContext};
 use indoc::indoc;
 use language::{
     language_settings::{AllLanguageSettings, InlayHintSettings},
-    FakeLspAdapter,
+    FakeLspAdapter, ReplicaId,
 };
 use multi_buffer::MultiBufferRow;
 use project::{
@@ -2438,6 +2438,109 @@ async fn test_git_blame_is_forwarded(cx_a: &mut TestAppContext, cx_b: &mut TestA
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_remote_cursors_converge(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let executor = cx_a.executor();
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/dir", json!({ "a.txt": "one\ntwo\nthree\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    let replica_id_a = project_a.read_with(cx_a, |project, _| project.replica_id());
+    let replica_id_b = project_b.read_with(cx_b, |project, _| project.replica_id());
+
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+    let editor_a = workspace_a
+        .update(cx_a, |workspace, cx| {
+            workspace.open_path((worktree_id, "a.txt"), None, true, cx)
+        })
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let editor_b = workspace_b
+        .update(cx_b, |workspace, cx| {
+            workspace.open_path((worktree_id, "a.txt"), None, true, cx)
+        })
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    executor.run_until_parked();
+    executor.advance_clock(CURSORS_VISIBLE_FOR);
+    executor.run_until_parked();
+    editor_a.update(cx_a, |editor, _| {
+        assert!(!editor.remote_cursor_moved_recently(replica_id_b));
+    });
+
+    // Client B moves their cursor, and client A sees it move along with its name.
+    editor_b.update(cx_b, |editor, cx| {
+        editor.change_selections(None, cx, |selections| selections.select_ranges([5..5]));
+    });
+    executor.run_until_parked();
+    editor_a.update(cx_a, |editor, cx| {
+        assert_eq!(remote_cursors(editor, cx), [(replica_id_b, 5)]);
+        assert!(editor.remote_cursor_moved_recently(replica_id_b));
+    });
+
+    // Both clients move their cursors at once, and see each other's final positions.
+    editor_a.update(cx_a, |editor, cx| {
+        editor.change_selections(None, cx, |selections| selections.select_ranges([1..1]));
+        editor.change_selections(None, cx, |selections| selections.select_ranges([9..9]));
+    });
+    editor_b.update(cx_b, |editor, cx| {
+        editor.change_selections(None, cx, |selections| selections.select_ranges([2..2]));
+    });
+    executor.run_until_parked();
+    editor_a.update(cx_a, |editor, cx| {
+        assert_eq!(remote_cursors(editor, cx), [(replica_id_b, 2)]);
+    });
+    editor_b.update(cx_b, |editor, cx| {
+        assert_eq!(remote_cursors(editor, cx), [(replica_id_a, 9)]);
+        assert!(editor.remote_cursor_moved_recently(replica_id_a));
+    });
+
+    // The names are hidden once the cursors stop moving, but the cursors stay in place.
+    executor.advance_clock(CURSORS_VISIBLE_FOR);
+    executor.run_until_parked();
+    editor_a.update(cx_a, |editor, cx| {
+        assert_eq!(remote_cursors(editor, cx), [(replica_id_b, 2)]);
+        assert!(!editor.remote_cursor_moved_recently(replica_id_b));
+    });
+    editor_b.update(cx_b, |editor, _| {
+        assert!(!editor.remote_cursor_moved_recently(replica_id_a));
+    });
+}
+
+fn remote_cursors(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> Vec<(ReplicaId, usize)> {
+    let snapshot = editor.snapshot(cx);
+    let range = Anchor::min()..Anchor::max();
+    snapshot
+        .remote_selections_in_range(&range, editor.collaboration_hub().unwrap(), cx)
+        .map(|selection| {
+            let head = selection.selection.head();
+            (
+                selection.replica_id,
+                head.to_offset(&snapshot.buffer_snapshot),
+            )
+        })
+        .collect()
+}
+
 fn extract_hint_labels(editor: &Editor) -> Vec<String> {
     let mut labels = Vec::new();
     for hint in editor.inlay_hint_cache().hints() {
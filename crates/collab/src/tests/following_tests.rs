@@ -3,9 +3,9 @@ use call::{ActiveCall, ParticipantLocation};
 use client::ChannelId;
 use collab_ui::{
     channel_view::ChannelView,
-    notifications::project_shared_notification::ProjectSharedNotification,
+    notifications::project_shared_notification::ProjectSharedNotification, FollowIndicator,
 };
-use editor::{Editor, ExcerptRange, MultiBuffer};
+use editor::{scroll::REMOTE_SCROLL_DURATION, Editor, ExcerptRange, MultiBuffer};
 use gpui::{
     point, BackgroundExecutor, BorrowAppContext, Context, Entity, SharedString, TestAppContext,
     View, VisualContext, VisualTestContext,
@@ -1309,6 +1309,132 @@ async fn test_auto_unfollowing(cx_a: &mut TestAppContext, cx_b: &mut TestAppCont
     );
 }
 
+#[gpui::test]
+async fn test_follow_mode(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/a",
+            json!({
+                "1.txt": "one\n".repeat(50),
+                "2.txt": "two",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    active_call_a
+        .update(cx_a, |call, cx| call.set_location(Some(&project_a), cx))
+        .await
+        .unwrap();
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    active_call_b
+        .update(cx_b, |call, cx| call.set_location(Some(&project_b), cx))
+        .await
+        .unwrap();
+
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+    let follow_indicator_b = workspace_b.update(cx_b, |workspace, cx| {
+        let workspace_handle = cx.view().clone();
+        let follow_indicator = cx.new_view(|cx| FollowIndicator::new(&workspace_handle, cx));
+        workspace.status_bar().update(cx, |status_bar, cx| {
+            status_bar.add_right_item(follow_indicator.clone(), cx)
+        });
+        follow_indicator
+    });
+
+    let editor_a1 = workspace_a
+        .update(cx_a, |workspace, cx| {
+            workspace.open_path((worktree_id, "1.txt"), None, true, cx)
+        })
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+
+    // Client B follows client A, which the status bar shows.
+    let pane_b = workspace_b.update(cx_b, |workspace, _| workspace.active_pane().clone());
+    let leader_id = project_b.update(cx_b, |project, _| {
+        project.collaborators().values().next().unwrap().peer_id
+    });
+    workspace_b.update(cx_b, |workspace, cx| workspace.follow(leader_id, cx));
+    executor.run_until_parked();
+    assert_eq!(
+        workspace_b.update(cx_b, |workspace, _| workspace.leader_for_pane(&pane_b)),
+        Some(leader_id)
+    );
+    follow_indicator_b.update(cx_b, |follow_indicator, _| {
+        assert_eq!(follow_indicator.leader_name().unwrap().as_ref(), "user_a");
+    });
+    let editor_b1 = workspace_b.update(cx_b, |workspace, cx| {
+        workspace.active_item_as::<Editor>(cx).unwrap()
+    });
+
+    // When client A scrolls, client B's editor glides to the same scroll position, and keeps
+    // following client A on the way.
+    editor_a1.update(cx_a, |editor, cx| {
+        editor.set_scroll_position(point(0., 20.), cx)
+    });
+    executor.advance_clock(workspace::item::LEADER_UPDATE_THROTTLE);
+    executor.run_until_parked();
+    executor.advance_clock(REMOTE_SCROLL_DURATION / 2);
+    let scroll_position = editor_b1.update(cx_b, |editor, cx| editor.scroll_position(cx));
+    assert!(scroll_position.y > 0. && scroll_position.y < 20.);
+    assert_eq!(
+        workspace_b.update(cx_b, |workspace, _| workspace.leader_for_pane(&pane_b)),
+        Some(leader_id)
+    );
+    executor.advance_clock(REMOTE_SCROLL_DURATION);
+    assert_eq!(
+        editor_b1.update(cx_b, |editor, cx| editor.scroll_position(cx)),
+        point(0., 20.)
+    );
+
+    // When client A opens another file, client B opens it too.
+    workspace_a
+        .update(cx_a, |workspace, cx| {
+            workspace.open_path((worktree_id, "2.txt"), None, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let editor_b2 = workspace_b.update(cx_b, |workspace, cx| {
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "2.txt");
+        editor.downcast::<Editor>().unwrap()
+    });
+
+    // When client B navigates, it stops following client A.
+    editor_b2.update(cx_b, |editor, cx| {
+        editor.move_down(&editor::actions::MoveDown, cx)
+    });
+    executor.run_until_parked();
+    assert_eq!(
+        workspace_b.update(cx_b, |workspace, _| workspace.leader_for_pane(&pane_b)),
+        None
+    );
+    follow_indicator_b.update(cx_b, |follow_indicator, _| {
+        assert_eq!(follow_indicator.leader_name(), None);
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_peers_simultaneously_following_each_other(
     cx_a: &mut TestAppContext,
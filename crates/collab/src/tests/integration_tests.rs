@@ -20,7 +20,7 @@ use gpui::{
 use language::{
     language_settings::{AllLanguageSettings, Formatter, PrettierSettings},
     tree_sitter_rust, Diagnostic, DiagnosticEntry, FakeLspAdapter, Language, LanguageConfig,
-    LanguageMatcher, LineEnding, OffsetRangeExt, Point, Rope,
+    LanguageMatcher, LineEnding, OffsetRangeExt, Point, Rope, ToOffset, ToPoint,
 };
 use live_kit_client::MacOSDisplay;
 use lsp::LanguageServerId;
@@ -3585,6 +3585,201 @@ async fn test_editing_while_guest_opens_buffer(
     buffer_b.read_with(cx_b, |buf, _| assert_eq!(buf.text(), text));
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_annotation_threads(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/dir", json!({ "a.txt": "one\ntwo\nthree" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+
+    // Start a thread as client A before client B has opened the buffer.
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let anchor = buffer_a.read_with(cx_a, |buffer, _| buffer.anchor_before(4));
+    let thread_id = project_a
+        .update(cx_a, |project, cx| {
+            project.create_annotation_thread(&buffer_a, anchor, "Rename this?".into(), cx)
+        })
+        .await
+        .unwrap();
+
+    // Client B loads the thread from the server when opening the buffer.
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let buffer_id = buffer_b.read_with(cx_b, |buffer, _| buffer.remote_id());
+    project_b.read_with(cx_b, |project, cx| {
+        let thread = project
+            .annotation_threads(buffer_id)
+            .and_then(|threads| threads.get(thread_id))
+            .unwrap();
+        let buffer = buffer_b.read(cx);
+        assert_eq!(thread.anchor.to_offset(buffer), 4);
+        assert_eq!(
+            thread
+                .comments
+                .iter()
+                .map(|comment| (comment.author.as_str(), comment.body.as_str()))
+                .collect::<Vec<_>>(),
+            [("user_a", "Rename this?")]
+        );
+        assert!(!thread.resolved);
+    });
+
+    // Client B replies and resolves the thread, which is reflected on both clients.
+    project_b
+        .update(cx_b, |project, cx| {
+            project.reply_to_annotation_thread(&buffer_b, thread_id, "Done.".into(), cx)
+        })
+        .await
+        .unwrap();
+    project_b
+        .update(cx_b, |project, cx| {
+            project.resolve_annotation_thread(&buffer_b, thread_id, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    for (project, cx) in [(&project_a, &*cx_a), (&project_b, &*cx_b)] {
+        project.read_with(cx, |project, _| {
+            let thread = project
+                .annotation_threads(buffer_id)
+                .and_then(|threads| threads.get(thread_id))
+                .unwrap();
+            assert_eq!(
+                thread
+                    .comments
+                    .iter()
+                    .map(|comment| (comment.author.as_str(), comment.body.as_str()))
+                    .collect::<Vec<_>>(),
+                [("user_a", "Rename this?"), ("user_b", "Done.")]
+            );
+            assert!(thread.resolved);
+        });
+    }
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_edits_made_while_offline(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/dir", json!({ "a.txt": "one\ntwo\nthree\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // Drop client B's connection.
+    server.forbid_connections();
+    server.disconnect_client(client_b.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+
+    // Client B keeps editing while disconnected, and its edits are queued.
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(0..0, "zero\n")], None, cx);
+        buffer.edit([(13..18, "THREE")], None, cx);
+    });
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        assert_eq!(project.pending_edit_count(), 2)
+    });
+
+    // Meanwhile, client A edits the same line as client B, and another one.
+    buffer_a.update(cx_a, |buffer, cx| {
+        buffer.edit([(4..7, "TWO")], None, cx);
+        buffer.edit([(8..13, "3")], None, cx);
+    });
+    executor.run_until_parked();
+
+    // Client B reconnects, and none of the edits are lost.
+    server.allow_connections();
+    client_b
+        .authenticate_and_connect(false, &cx_b.to_async())
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let text = buffer_a.read_with(cx_a, |buffer, _| buffer.text());
+    assert!(
+        text == "zero\none\nTWO\nTHREE3\n" || text == "zero\none\nTWO\n3THREE\n",
+        "unexpected text {text:?}"
+    );
+    buffer_b.read_with(cx_b, |buffer, _| assert_eq!(buffer.text(), text));
+
+    // The line that both clients edited is flagged as a conflict for client B.
+    let buffer_id = buffer_b.read_with(cx_b, |buffer, _| buffer.remote_id());
+    project_b.read_with(cx_b, |project, cx| {
+        assert_eq!(project.pending_edit_count(), 0);
+        let buffer = buffer_b.read(cx);
+        assert_eq!(
+            project
+                .offline_edits()
+                .conflicts(buffer_id)
+                .iter()
+                .map(|range| range.start.to_point(buffer).row..=range.end.to_point(buffer).row)
+                .collect::<Vec<_>>(),
+            [3..=3]
+        );
+    });
+    project_a.read_with(cx_a, |project, _| {
+        assert!(project.offline_edits().conflicts(buffer_id).is_empty())
+    });
+
+    project_b.update(cx_b, |project, cx| {
+        project.dismiss_offline_edit_conflicts(buffer_id, cx)
+    });
+    project_b.read_with(cx_b, |project, _| {
+        assert!(project.offline_edits().conflicts(buffer_id).is_empty())
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_leaving_worktree_while_opening_buffer(
     executor: BackgroundExecutor,
@@ -5196,13 +5391,13 @@ async fn test_lsp_hover(
     }
 
     // Request hover information as the guest.
-    let mut hovers = project_b
+    let hovers = project_b
         .update(cx_b, |p, cx| p.hover(&buffer_b, 22, cx))
         .await;
     assert_eq!(
         hovers.len(),
-        2,
-        "Expected two hovers from both language servers, but got: {hovers:?}"
+        1,
+        "Expected the hovers of both language servers to be merged, but got: {hovers:?}"
     );
 
     let _: Vec<()> = futures::future::join_all(servers_with_hover_requests.into_values().map(
@@ -5215,18 +5410,9 @@ async fn test_lsp_hover(
     ))
     .await;
 
-    hovers.sort_by_key(|hover| hover.contents.len());
-    let first_hover = hovers.first().cloned().unwrap();
-    assert_eq!(
-        first_hover.contents,
-        vec![project::HoverBlock {
-            text: "CrabLang-ls hover".to_string(),
-            kind: HoverBlockKind::Markdown,
-        },]
-    );
-    let second_hover = hovers.last().cloned().unwrap();
+    let hover = hovers.first().cloned().unwrap();
     assert_eq!(
-        second_hover.contents,
+        hover.contents,
         vec![
             project::HoverBlock {
                 text: "Test hover content.".to_string(),
@@ -5237,12 +5423,20 @@ async fn test_lsp_hover(
                 kind: HoverBlockKind::Code {
                     language: "Rust".to_string()
                 },
-            }
+            },
+            project::HoverBlock {
+                text: "---".to_string(),
+                kind: HoverBlockKind::Markdown,
+            },
+            project::HoverBlock {
+                text: "CrabLang-ls hover".to_string(),
+                kind: HoverBlockKind::Markdown,
+            },
         ]
     );
     buffer_b.read_with(cx_b, |buffer, _| {
         let snapshot = buffer.snapshot();
-        assert_eq!(second_hover.range.unwrap().to_offset(&snapshot), 22..29);
+        assert_eq!(hover.range.unwrap().to_offset(&snapshot), 22..29);
     });
 }
 
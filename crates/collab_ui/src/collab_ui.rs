@@ -1,6 +1,7 @@
 pub mod channel_view;
 pub mod chat_panel;
 pub mod collab_panel;
+mod follow_indicator;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
@@ -8,6 +9,7 @@ mod panel_settings;
 use std::{rc::Rc, sync::Arc};
 
 pub use collab_panel::CollabPanel;
+pub use follow_indicator::FollowIndicator;
 use gpui::{
     point, AppContext, Pixels, PlatformDisplay, Size, WindowBackgroundAppearance, WindowBounds,
     WindowDecorations, WindowKind, WindowOptions,
@@ -0,0 +1,75 @@
+use call::ActiveCall;
+use gpui::{AppContext, Subscription, View, WeakView};
+use ui::{prelude::*, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView, Workspace};
+
+/// Shows who the active pane is following in the status bar, and stops following when clicked.
+pub struct FollowIndicator {
+    leader_name: Option<SharedString>,
+    workspace: WeakView<Workspace>,
+    _observe_workspace: Subscription,
+}
+
+impl FollowIndicator {
+    pub fn new(workspace: &View<Workspace>, cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            leader_name: None,
+            workspace: workspace.downgrade(),
+            _observe_workspace: cx.observe(workspace, |this, _, cx| this.update_leader(cx)),
+        }
+    }
+
+    pub fn leader_name(&self) -> Option<&SharedString> {
+        self.leader_name.as_ref()
+    }
+
+    fn update_leader(&mut self, cx: &mut ViewContext<Self>) {
+        let leader_name = self.workspace.upgrade().and_then(|workspace| {
+            let workspace = workspace.read(cx);
+            let leader_id = workspace.leader_for_pane(workspace.active_pane())?;
+            let room = ActiveCall::global(cx).read(cx).room()?.read(cx);
+            let leader = room.remote_participant_for_peer_id(leader_id)?;
+            Some(SharedString::from(leader.user.github_login.clone()))
+        });
+        if leader_name != self.leader_name {
+            self.leader_name = leader_name;
+            cx.notify();
+        }
+    }
+}
+
+impl Render for FollowIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div().when_some(self.leader_name.clone(), |el, leader_name| {
+            el.child(
+                Button::new("follow-indicator", format!("Following {leader_name}"))
+                    .label_size(LabelSize::Small)
+                    .color(Color::Accent)
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(workspace) = this.workspace.upgrade() {
+                            workspace.update(cx, |workspace, cx| {
+                                let pane = workspace.active_pane().clone();
+                                workspace.unfollow(&pane, cx);
+                            });
+                        }
+                    }))
+                    .tooltip(|cx| Tooltip::text("Stop Following", cx)),
+            )
+        })
+    }
+}
+
+impl StatusItemView for FollowIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        // The workspace is still being updated when it activates an item.
+        cx.defer(|this, cx| this.update_leader(cx));
+    }
+
+    fn status_item_id(&self, _: &AppContext) -> Option<&'static str> {
+        Some("follow_indicator")
+    }
+}
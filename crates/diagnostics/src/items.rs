@@ -1,16 +1,20 @@
 use editor::Editor;
 use gpui::{
-    rems, EventEmitter, IntoElement, ParentElement, Render, Styled, Subscription, View,
-    ViewContext, WeakView,
+    rems, Action, AppContext, EventEmitter, IntoElement, ParentElement, Render, Styled,
+    Subscription, View, ViewContext, WeakView,
 };
 use language::Diagnostic;
-use ui::{h_flex, prelude::*, Button, ButtonLike, Color, Icon, IconName, Label, Tooltip};
+use settings::{Settings, SettingsStore};
+use ui::{
+    h_flex, prelude::*, Button, ButtonLike, Color, ContextMenu, Icon, IconName, Label, Tooltip,
+};
 use workspace::{item::ItemHandle, StatusItemView, ToolbarItemEvent, Workspace};
 
-use crate::{Deploy, ProjectDiagnosticsEditor};
+use crate::{Deploy, ProjectDiagnosticsEditor, ProjectDiagnosticsSettings};
 
 pub struct DiagnosticIndicator {
     summary: project::DiagnosticSummary,
+    suppressed_count: usize,
     active_editor: Option<WeakView<Editor>>,
     workspace: WeakView<Workspace>,
     current_diagnostic: Option<Diagnostic>,
@@ -76,20 +80,22 @@ impl Render for DiagnosticIndicator {
             None
         };
 
+        let suppressed = (self.suppressed_count > 0
+            && ProjectDiagnosticsSettings::get_global(cx).show_suppressed_count)
+            .then(|| {
+                Label::new(format!("({} suppressed)", self.suppressed_count))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            });
+
         h_flex()
             .h(rems(1.375))
             .gap_2()
             .child(
                 ButtonLike::new("diagnostic-indicator")
-                    .child(diagnostic_indicator)
+                    .child(diagnostic_indicator.children(suppressed))
                     .tooltip(|cx| Tooltip::for_action("Project Diagnostics", &Deploy, cx))
-                    .on_click(cx.listener(|this, _, cx| {
-                        if let Some(workspace) = this.workspace.upgrade() {
-                            workspace.update(cx, |workspace, cx| {
-                                ProjectDiagnosticsEditor::deploy(workspace, &Default::default(), cx)
-                            })
-                        }
-                    })),
+                    .on_click(cx.listener(|this, _, cx| this.deploy_project_diagnostics(cx))),
             )
             .children(status)
     }
@@ -106,20 +112,25 @@ impl DiagnosticIndicator {
             project::Event::DiskBasedDiagnosticsFinished { .. }
             | project::Event::LanguageServerRemoved(_) => {
                 this.summary = project.read(cx).diagnostic_summary(false, cx);
+                this.suppressed_count = project.read(cx).suppressed_diagnostic_count(cx);
                 cx.notify();
             }
 
             project::Event::DiagnosticsUpdated { .. } => {
                 this.summary = project.read(cx).diagnostic_summary(false, cx);
+                this.suppressed_count = project.read(cx).suppressed_diagnostic_count(cx);
                 cx.notify();
             }
 
             _ => {}
         })
         .detach();
+        cx.observe_global::<SettingsStore>(|_, cx| cx.notify())
+            .detach();
 
         Self {
             summary: project.read(cx).diagnostic_summary(false, cx),
+            suppressed_count: project.read(cx).suppressed_diagnostic_count(cx),
             active_editor: None,
             workspace: workspace.weak_handle(),
             current_diagnostic: None,
@@ -127,6 +138,14 @@ impl DiagnosticIndicator {
         }
     }
 
+    fn deploy_project_diagnostics(&self, cx: &mut WindowContext) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            workspace.update(cx, |workspace, cx| {
+                ProjectDiagnosticsEditor::deploy(workspace, &Default::default(), cx)
+            })
+        }
+    }
+
     fn go_to_next_diagnostic(&mut self, cx: &mut ViewContext<Self>) {
         if let Some(editor) = self.active_editor.as_ref().and_then(|e| e.upgrade()) {
             editor.update(cx, |editor, cx| {
@@ -171,4 +190,34 @@ impl StatusItemView for DiagnosticIndicator {
         }
         cx.notify();
     }
+
+    fn context_menu(&mut self, cx: &mut ViewContext<Self>) -> Option<View<ContextMenu>> {
+        let has_next_diagnostic = self.active_editor.is_some()
+            && (self.summary.error_count > 0 || self.summary.warning_count > 0);
+        let this = cx.view().downgrade();
+        Some(ContextMenu::build(cx, move |menu, _| {
+            menu.entry("Project Diagnostics", Some(Deploy.boxed_clone()), {
+                let this = this.clone();
+                move |cx| {
+                    this.update(cx, |this, cx| this.deploy_project_diagnostics(cx))
+                        .ok();
+                }
+            })
+            .map(|menu| {
+                let action = editor::actions::GoToDiagnostic.boxed_clone();
+                if has_next_diagnostic {
+                    menu.entry("Next Diagnostic", Some(action), move |cx| {
+                        this.update(cx, |this, cx| this.go_to_next_diagnostic(cx))
+                            .ok();
+                    })
+                } else {
+                    menu.disabled_entry("Next Diagnostic", Some(action))
+                }
+            })
+        }))
+    }
+
+    fn status_item_id(&self, _: &AppContext) -> Option<&'static str> {
+        Some("diagnostics")
+    }
 }
@@ -7,6 +7,7 @@ use settings::{Settings, SettingsSources};
 #[derive(Deserialize, Debug)]
 pub struct ProjectDiagnosticsSettings {
     pub include_warnings: bool,
+    pub show_suppressed_count: bool,
 }
 
 /// Diagnostics configuration.
@@ -16,6 +17,11 @@ pub struct ProjectDiagnosticsSettingsContent {
     ///
     /// Default: true
     include_warnings: Option<bool>,
+    /// Whether to show how many diagnostics were dropped by the language servers'
+    /// `diagnostic_filters` in the status bar.
+    ///
+    /// Default: false
+    show_suppressed_count: Option<bool>,
 }
 
 impl Settings for ProjectDiagnosticsSettings {
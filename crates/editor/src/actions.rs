@@ -163,9 +163,12 @@ impl_actions!(
 gpui::actions!(
     editor,
     [
+        AcceptBoth,
         AcceptPartialCopilotSuggestion,
         AcceptInlineCompletion,
+        AcceptOurs,
         AcceptPartialInlineCompletion,
+        AcceptTheirs,
         AddSelectionAbove,
         AddSelectionBelow,
         Backspace,
@@ -240,6 +243,7 @@ gpui::actions!(
         MoveToPreviousWordStart,
         MoveToStartOfParagraph,
         MoveUp,
+        NewAnnotationThread,
         Newline,
         NewlineAbove,
         NewlineBelow,
@@ -257,6 +261,8 @@ gpui::actions!(
         Redo,
         RedoSelection,
         Rename,
+        ReplyToThread,
+        ResolveThread,
         RestartLanguageServer,
         RevealInFileManager,
         ReverseLines,
@@ -286,11 +292,13 @@ gpui::actions!(
         SelectPageUp,
         ShowCharacterPalette,
         ShowInlineCompletion,
+        ShowResolvedThreads,
         ShowSignatureHelp,
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
         SplitSelectionIntoLines,
+        StageHunk,
         Tab,
         TabPrev,
         ToggleAutoSignatureHelp,
@@ -309,6 +317,7 @@ gpui::actions!(
         UnfoldLines,
         UniqueLinesCaseInsensitive,
         UniqueLinesCaseSensitive,
+        UnstageHunk,
     ]
 );
 
@@ -0,0 +1,409 @@
+use crate::{
+    actions::{NewAnnotationThread, ReplyToThread, ResolveThread, ShowResolvedThreads},
+    display_map::{DisplayRow, ToDisplayPoint},
+    Anchor, Editor, EditorElement, EditorSnapshot, EditorStyle,
+};
+use collections::BTreeMap;
+use gpui::{
+    AnyElement, AppContext, Model, MouseButton, Pixels, Size, View, ViewContext, WindowContext,
+};
+use language::Buffer;
+use multi_buffer::{MultiBufferRow, ToPoint};
+use project::{Comment, ThreadId};
+use text::BufferId;
+use ui::{prelude::*, IconButtonShape, Tooltip};
+
+/// An annotation thread shown in the gutter of an editor.
+#[derive(Clone, Debug)]
+pub(crate) struct AnnotationIndicator {
+    pub anchor: Anchor,
+    pub buffer_id: BufferId,
+    pub thread_id: ThreadId,
+}
+
+/// The popover showing an expanded annotation thread, or the first comment
+/// of a thread that hasn't been created yet.
+pub(crate) struct AnnotationPopover {
+    anchor: Anchor,
+    buffer: Model<Buffer>,
+    thread_id: Option<ThreadId>,
+    reply_editor: View<Editor>,
+}
+
+impl Editor {
+    /// Returns the annotation threads to show in the gutter, keyed by the display row they're on.
+    /// Only the oldest thread is kept when several threads start on the same row.
+    pub(crate) fn annotation_indicators(
+        &self,
+        snapshot: &EditorSnapshot,
+        cx: &AppContext,
+    ) -> BTreeMap<DisplayRow, AnnotationIndicator> {
+        let mut indicators = BTreeMap::default();
+        let Some(project) = self.project.as_ref() else {
+            return indicators;
+        };
+        let project = project.read(cx);
+        for (excerpt_id, buffer, range) in snapshot.buffer_snapshot.excerpts() {
+            let buffer_id = buffer.remote_id();
+            let Some(threads) = project.annotation_threads(buffer_id) else {
+                continue;
+            };
+            for thread in threads.iter() {
+                if (thread.resolved && !self.show_resolved_threads)
+                    || !buffer.can_resolve(&thread.anchor)
+                    || thread.anchor.cmp(&range.context.start, buffer).is_lt()
+                    || thread.anchor.cmp(&range.context.end, buffer).is_gt()
+                {
+                    continue;
+                }
+                let Some(anchor) = snapshot
+                    .buffer_snapshot
+                    .anchor_in_excerpt(excerpt_id, thread.anchor)
+                else {
+                    continue;
+                };
+                let multibuffer_point = anchor.to_point(&snapshot.buffer_snapshot);
+                if snapshot.is_line_folded(MultiBufferRow(multibuffer_point.row)) {
+                    continue;
+                }
+                let display_row = multibuffer_point.to_display_point(snapshot).row();
+                indicators
+                    .entry(display_row)
+                    .or_insert(AnnotationIndicator {
+                        anchor,
+                        buffer_id,
+                        thread_id: thread.id,
+                    });
+            }
+        }
+        indicators
+    }
+
+    pub(crate) fn render_annotation_indicator(
+        &self,
+        row: DisplayRow,
+        indicator: AnnotationIndicator,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        let is_active = self.annotation_popover.as_ref().map_or(false, |popover| {
+            popover.thread_id == Some(indicator.thread_id)
+                && popover.buffer.read(cx).remote_id() == indicator.buffer_id
+        });
+        IconButton::new(
+            ("annotation_indicator", row.0 as usize),
+            IconName::MessageBubbles,
+        )
+        .shape(IconButtonShape::Square)
+        .icon_size(IconSize::XSmall)
+        .icon_color(Color::Muted)
+        .selected(is_active)
+        .on_click(cx.listener(move |editor, _e, cx| {
+            editor.focus(cx);
+            editor.toggle_annotation_thread(indicator.clone(), cx);
+        }))
+    }
+
+    pub(crate) fn render_annotation_popover(
+        &self,
+        style: &EditorStyle,
+        max_size: Size<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<(Anchor, AnyElement)> {
+        let popover = self.annotation_popover.as_ref()?;
+        let project = self.project.as_ref()?.read(cx);
+        let buffer_id = popover.buffer.read(cx).remote_id();
+        let thread = popover
+            .thread_id
+            .and_then(|thread_id| project.annotation_threads(buffer_id)?.get(thread_id));
+        let comments = thread
+            .map(|thread| thread.comments.clone())
+            .unwrap_or_default();
+        let resolved = thread.map_or(false, |thread| thread.resolved);
+
+        let reply_style = EditorStyle {
+            background: cx.theme().system().transparent,
+            ..style.clone()
+        };
+        let element = v_flex()
+            .id("annotation_popover")
+            .elevation_2(cx)
+            .w(max_size.width)
+            .max_h(max_size.height)
+            .overflow_y_scroll()
+            .p_2()
+            .gap_2()
+            .on_mouse_move(|_, cx| cx.stop_propagation())
+            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+            .children(
+                comments
+                    .iter()
+                    .map(|comment| render_annotation_comment(comment, cx)),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex_1()
+                            .px_1()
+                            .border_1()
+                            .border_color(cx.theme().colors().border_variant)
+                            .rounded_md()
+                            .child(EditorElement::new(&popover.reply_editor, reply_style)),
+                    )
+                    .when(popover.thread_id.is_some(), |this| {
+                        this.child(
+                            IconButton::new(
+                                "resolve_annotation_thread",
+                                if resolved {
+                                    IconName::ArrowCircle
+                                } else {
+                                    IconName::Check
+                                },
+                            )
+                            .icon_size(IconSize::Small)
+                            .tooltip(move |cx| {
+                                Tooltip::for_action(
+                                    if resolved {
+                                        "Reopen Thread"
+                                    } else {
+                                        "Resolve Thread"
+                                    },
+                                    &ResolveThread,
+                                    cx,
+                                )
+                            })
+                            .on_click(cx.listener(|editor, _, cx| {
+                                editor.resolve_thread(&ResolveThread, cx)
+                            })),
+                        )
+                    }),
+            )
+            .into_any_element();
+        Some((popover.anchor, element))
+    }
+
+    pub fn new_annotation_thread(&mut self, _: &NewAnnotationThread, cx: &mut ViewContext<Self>) {
+        if self.project.is_none() {
+            return;
+        }
+        let anchor = self.selections.newest_anchor().head();
+        let Some(buffer) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(anchor, cx)
+            .map(|(buffer, _)| buffer)
+        else {
+            return;
+        };
+        self.show_annotation_popover(anchor, buffer, None, cx);
+    }
+
+    pub fn reply_to_thread(&mut self, _: &ReplyToThread, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            cx.propagate();
+            return;
+        };
+        let Some(popover) = self.annotation_popover.as_ref() else {
+            cx.propagate();
+            return;
+        };
+        let body = popover.reply_editor.read(cx).text(cx).trim().to_string();
+        if body.is_empty() {
+            return;
+        }
+        popover
+            .reply_editor
+            .update(cx, |editor, cx| editor.clear(cx));
+
+        let buffer = popover.buffer.clone();
+        if let Some(thread_id) = popover.thread_id {
+            project
+                .update(cx, |project, cx| {
+                    project.reply_to_annotation_thread(&buffer, thread_id, body, cx)
+                })
+                .detach_and_log_err(cx);
+        } else {
+            let Some((_, anchor)) = self
+                .buffer
+                .read(cx)
+                .text_anchor_for_position(popover.anchor, cx)
+            else {
+                return;
+            };
+            let create = project.update(cx, |project, cx| {
+                project.create_annotation_thread(&buffer, anchor, body, cx)
+            });
+            cx.spawn(|this, mut cx| async move {
+                let thread_id = create.await?;
+                this.update(&mut cx, |this, cx| {
+                    if let Some(popover) = this.annotation_popover.as_mut() {
+                        if popover.thread_id.is_none() && popover.buffer == buffer {
+                            popover.thread_id = Some(thread_id);
+                            popover
+                                .reply_editor
+                                .update(cx, |editor, cx| editor.set_placeholder_text("Reply…", cx));
+                            cx.notify();
+                        }
+                    }
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    pub fn resolve_thread(&mut self, _: &ResolveThread, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let (buffer, thread_id) = if let Some(popover) = self.annotation_popover.as_ref() {
+            let Some(thread_id) = popover.thread_id else {
+                return;
+            };
+            (popover.buffer.clone(), thread_id)
+        } else {
+            let snapshot = self.snapshot(cx);
+            let row = self
+                .selections
+                .newest_anchor()
+                .head()
+                .to_display_point(&snapshot)
+                .row();
+            let Some(indicator) = self.annotation_indicators(&snapshot, cx).remove(&row) else {
+                return;
+            };
+            let Some(buffer) = self.buffer.read(cx).buffer(indicator.buffer_id) else {
+                return;
+            };
+            (buffer, indicator.thread_id)
+        };
+
+        let buffer_id = buffer.read(cx).remote_id();
+        let resolved = project
+            .read(cx)
+            .annotation_threads(buffer_id)
+            .and_then(|threads| threads.get(thread_id))
+            .map_or(false, |thread| thread.resolved);
+        if !resolved && !self.show_resolved_threads {
+            self.take_annotation_popover(cx);
+        }
+        project
+            .update(cx, |project, cx| {
+                project.resolve_annotation_thread(&buffer, thread_id, !resolved, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    pub fn show_resolved_threads(&mut self, _: &ShowResolvedThreads, cx: &mut ViewContext<Self>) {
+        self.show_resolved_threads = !self.show_resolved_threads;
+        cx.notify();
+    }
+
+    pub fn annotation_popover_visible(&self) -> bool {
+        self.annotation_popover.is_some()
+    }
+
+    pub(crate) fn take_annotation_popover(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(popover) = self.annotation_popover.take() else {
+            return false;
+        };
+        if popover.reply_editor.focus_handle(cx).is_focused(cx) {
+            cx.focus(&self.focus_handle);
+        }
+        cx.notify();
+        true
+    }
+
+    fn toggle_annotation_thread(
+        &mut self,
+        indicator: AnnotationIndicator,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let is_open = self.annotation_popover.as_ref().map_or(false, |popover| {
+            popover.thread_id == Some(indicator.thread_id)
+                && popover.buffer.read(cx).remote_id() == indicator.buffer_id
+        });
+        if is_open {
+            self.take_annotation_popover(cx);
+        } else if let Some(buffer) = self.buffer.read(cx).buffer(indicator.buffer_id) {
+            self.show_annotation_popover(indicator.anchor, buffer, Some(indicator.thread_id), cx);
+        }
+    }
+
+    fn show_annotation_popover(
+        &mut self,
+        anchor: Anchor,
+        buffer: Model<Buffer>,
+        thread_id: Option<ThreadId>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.take_annotation_popover(cx);
+        let reply_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text(
+                if thread_id.is_some() {
+                    "Reply…"
+                } else {
+                    "Add a comment…"
+                },
+                cx,
+            );
+            editor
+        });
+        cx.focus_view(&reply_editor);
+        self.annotation_popover = Some(AnnotationPopover {
+            anchor,
+            buffer,
+            thread_id,
+            reply_editor,
+        });
+        cx.notify();
+    }
+
+    pub(crate) fn annotation_threads_changed(
+        &mut self,
+        buffer_id: BufferId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.buffer.read(cx).buffer(buffer_id).is_some() {
+            cx.notify();
+        }
+    }
+}
+
+fn render_annotation_comment(comment: &Comment, cx: &WindowContext) -> impl IntoElement {
+    let author = if comment.author.is_empty() {
+        "You".to_string()
+    } else {
+        comment.author.clone()
+    };
+    let timestamp = time_format::format_localized_timestamp(
+        time::OffsetDateTime::from(comment.timestamp),
+        time::OffsetDateTime::now_utc(),
+        cx.local_timezone(),
+        time_format::TimestampFormat::Relative,
+    );
+    v_flex()
+        .child(
+            h_flex()
+                .gap_2()
+                .child(
+                    Label::new(author)
+                        .size(LabelSize::Small)
+                        .color(Color::Accent),
+                )
+                .child(
+                    Label::new(timestamp)
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                ),
+        )
+        .child(Label::new(comment.body.clone()))
+}
+
+impl AnnotationPopover {
+    #[cfg(test)]
+    pub(crate) fn reply_editor(&self) -> &View<Editor> {
+        &self.reply_editor
+    }
+}
@@ -478,6 +478,7 @@ pub(crate) struct Highlights<'a> {
 pub struct HighlightStyles {
     pub inlay_hint: Option<HighlightStyle>,
     pub suggestion: Option<HighlightStyle>,
+    pub inline_value: Option<HighlightStyle>,
 }
 
 pub struct HighlightedChunk<'a> {
@@ -696,6 +697,7 @@ impl DisplaySnapshot {
             HighlightStyles {
                 inlay_hint: Some(editor_style.inlay_hints_style),
                 suggestion: Some(editor_style.suggestions_style),
+                inline_value: Some(editor_style.inline_values_style),
             },
         )
         .map(|chunk| {
@@ -103,6 +103,8 @@ pub struct BlockContext<'a, 'b> {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BlockDisposition {
     Above,
+    /// Above the anchor line, and also above the excerpt header when the line starts an excerpt.
+    AboveExcerptHeader,
     Below,
 }
 
@@ -417,7 +419,9 @@ impl BlockMap {
                     .map(|block| {
                         let mut position = block.position.to_point(buffer);
                         match block.disposition {
-                            BlockDisposition::Above => position.column = 0,
+                            BlockDisposition::Above | BlockDisposition::AboveExcerptHeader => {
+                                position.column = 0
+                            }
                             BlockDisposition::Below => {
                                 position.column = buffer.line_len(MultiBufferRow(position.row))
                             }
@@ -445,7 +449,7 @@ impl BlockMap {
             // and then insert the block itself.
             for (block_row, block) in blocks_in_edit.drain(..) {
                 let insertion_row = match block.disposition() {
-                    BlockDisposition::Above => block_row,
+                    BlockDisposition::Above | BlockDisposition::AboveExcerptHeader => block_row,
                     BlockDisposition::Below => block_row + 1,
                 };
                 let extent_before_block = insertion_row - new_transforms.summary().input_rows;
@@ -502,11 +506,16 @@ impl BlockMap {
     {
         buffer
             .excerpt_boundaries_in_range(range)
-            .flat_map(move |excerpt_boundary| {
-                let wrap_row = wrap_snapshot
-                    .make_wrap_point(Point::new(excerpt_boundary.row.0, 0), Bias::Left)
-                    .row();
-
+            .filter_map(move |excerpt_boundary| {
+                let wrap_point = wrap_snapshot
+                    .make_wrap_point(Point::new(excerpt_boundary.row.0, 0), Bias::Left);
+                // Boundaries within folds, like those of collapsed groups of excerpts, have no
+                // headers or footers.
+                let folded =
+                    wrap_snapshot.to_point(wrap_point, Bias::Left).row != excerpt_boundary.row.0;
+                (!folded).then_some((excerpt_boundary, wrap_point.row()))
+            })
+            .flat_map(move |(excerpt_boundary, wrap_row)| {
                 [
                     show_excerpt_controls
                         .then(|| {
@@ -553,20 +562,27 @@ impl BlockMap {
     }
 
     pub(crate) fn sort_blocks<B: BlockLike>(blocks: &mut Vec<(u32, B)>) {
-        // Place excerpt headers and footers above custom blocks on the same row
+        // Place excerpt headers and footers above custom blocks on the same row, except for
+        // custom blocks that must appear above the excerpt header.
+        fn placement<B: BlockLike>(block: &B) -> u8 {
+            match (block.block_type(), block.disposition()) {
+                (BlockType::Footer, _) => 0,
+                (BlockType::Custom(_), BlockDisposition::AboveExcerptHeader) => 1,
+                (BlockType::Header, _) => 2,
+                (BlockType::Custom(_), _) => 3,
+            }
+        }
+
         blocks.sort_unstable_by(|(row_a, block_a), (row_b, block_b)| {
             row_a.cmp(row_b).then_with(|| {
                 block_a
                     .disposition()
-                    .cmp(&block_b.disposition())
+                    .is_below()
+                    .cmp(&block_b.disposition().is_below())
+                    .then_with(|| placement(block_a).cmp(&placement(block_b)))
                     .then_with(|| match ((block_a.block_type()), (block_b.block_type())) {
-                        (BlockType::Footer, BlockType::Footer) => Ordering::Equal,
-                        (BlockType::Footer, _) => Ordering::Less,
-                        (_, BlockType::Footer) => Ordering::Greater,
-                        (BlockType::Header, BlockType::Header) => Ordering::Equal,
-                        (BlockType::Header, _) => Ordering::Less,
-                        (_, BlockType::Header) => Ordering::Greater,
                         (BlockType::Custom(a_id), BlockType::Custom(b_id)) => a_id.cmp(&b_id),
+                        _ => Ordering::Equal,
                     })
             })
         });
@@ -995,7 +1011,9 @@ impl BlockSnapshot {
         cursor.seek(&BlockRow(block_point.row), Bias::Right, &());
         if let Some(transform) = cursor.item() {
             match transform.block.as_ref().map(|b| b.disposition()) {
-                Some(BlockDisposition::Above) => WrapPoint::new(cursor.start().1 .0, 0),
+                Some(BlockDisposition::Above | BlockDisposition::AboveExcerptHeader) => {
+                    WrapPoint::new(cursor.start().1 .0, 0)
+                }
                 Some(BlockDisposition::Below) => {
                     let wrap_row = cursor.start().1 .0 - 1;
                     WrapPoint::new(wrap_row, self.wrap_snapshot.line_len(wrap_row))
@@ -1562,6 +1580,62 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_blocks_above_excerpt_headers(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| init_test(cx));
+
+        let buffer = cx.update(|cx| {
+            MultiBuffer::build_multi(
+                [
+                    ("aaa\nbbb", vec![Point::new(0, 0)..Point::new(1, 3)]),
+                    ("ccc\nddd", vec![Point::new(0, 0)..Point::new(1, 3)]),
+                ],
+                cx,
+            )
+        });
+        let buffer_snapshot = cx.update(|cx| buffer.read(cx).snapshot(cx));
+        let (_, inlay_snapshot) = InlayMap::new(buffer_snapshot.clone());
+        let (_, fold_snapshot) = FoldMap::new(inlay_snapshot);
+        let (_, tab_snapshot) = TabMap::new(fold_snapshot, 4.try_into().unwrap());
+        let (_, wraps_snapshot) =
+            cx.update(|cx| WrapMap::new(tab_snapshot, font("Helvetica"), px(14.0), None, cx));
+        let mut block_map = BlockMap::new(wraps_snapshot.clone(), false, 2, 1, 0);
+
+        let mut writer = block_map.write(wraps_snapshot.clone(), Default::default());
+        let block_ids = writer.insert(vec![
+            BlockProperties {
+                style: BlockStyle::Fixed,
+                position: buffer_snapshot.anchor_after(Point::new(2, 0)),
+                height: 1,
+                disposition: BlockDisposition::Above,
+                render: Box::new(|_| div().into_any()),
+            },
+            BlockProperties {
+                style: BlockStyle::Fixed,
+                position: buffer_snapshot.anchor_after(Point::new(2, 1)),
+                height: 1,
+                disposition: BlockDisposition::AboveExcerptHeader,
+                render: Box::new(|_| div().into_any()),
+            },
+        ]);
+
+        // Blocks placed above the excerpt header precede it, while other blocks follow it.
+        let snapshot = block_map.read(wraps_snapshot, Default::default());
+        assert_eq!(snapshot.text(), "\n\naaa\nbbb\n\n\n\n\nccc\nddd");
+        assert_eq!(
+            snapshot
+                .blocks_in_range(0..snapshot.max_point().row + 1)
+                .map(|(row, block)| (row, block.as_custom().map(|block| block.id)))
+                .collect::<Vec<_>>(),
+            &[
+                (0, None),
+                (4, Some(block_ids[1])),
+                (5, None),
+                (7, Some(block_ids[0])),
+            ]
+        );
+    }
+
     #[gpui::test(iterations = 100)]
     fn test_random_blocks(cx: &mut gpui::TestAppContext, mut rng: StdRng) {
         cx.update(|cx| init_test(cx));
@@ -1724,7 +1798,7 @@ mod tests {
             expected_blocks.extend(custom_blocks.iter().map(|(id, block)| {
                 let mut position = block.position.to_point(&buffer_snapshot);
                 match block.disposition {
-                    BlockDisposition::Above => {
+                    BlockDisposition::Above | BlockDisposition::AboveExcerptHeader => {
                         position.column = 0;
                     }
                     BlockDisposition::Below => {
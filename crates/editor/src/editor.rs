@@ -13,6 +13,7 @@
 //!
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behaviour.
 pub mod actions;
+mod annotations;
 mod blame_entry_tooltip;
 mod blink_manager;
 mod debounced_delay;
@@ -27,6 +28,7 @@ mod hunk_diff;
 mod indent_guides;
 mod inlay_hint_cache;
 mod inline_completion_provider;
+mod inline_values;
 pub mod items;
 mod linked_editing_ranges;
 mod mouse_context_menu;
@@ -82,6 +84,7 @@ pub(crate) use hunk_diff::HunkToExpand;
 use indent_guides::ActiveIndentGuidesState;
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
 pub use inline_completion_provider::*;
+pub use inline_values::StoppedFrame;
 pub use items::MAX_TAB_TITLE_LEN;
 use itertools::Itertools;
 use language::{
@@ -166,7 +169,8 @@ const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 const MAX_LINE_LEN: usize = 1024;
 const MIN_NAVIGATION_HISTORY_ROW_DELTA: i64 = 10;
 const MAX_SELECTION_HISTORY_LEN: usize = 1024;
-pub(crate) const CURSORS_VISIBLE_FOR: Duration = Duration::from_millis(2000);
+#[doc(hidden)]
+pub const CURSORS_VISIBLE_FOR: Duration = Duration::from_millis(2000);
 #[doc(hidden)]
 pub const CODE_ACTIONS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
 #[doc(hidden)]
@@ -241,6 +245,7 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    InlineValue(usize),
 }
 
 impl InlayId {
@@ -248,6 +253,7 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::InlineValue(id) => *id,
         }
     }
 }
@@ -364,6 +370,7 @@ pub struct EditorStyle {
     pub status: StatusColors,
     pub inlay_hints_style: HighlightStyle,
     pub suggestions_style: HighlightStyle,
+    pub inline_values_style: HighlightStyle,
 }
 
 impl Default for EditorStyle {
@@ -380,6 +387,7 @@ impl Default for EditorStyle {
             status: StatusColors::dark(),
             inlay_hints_style: HighlightStyle::default(),
             suggestions_style: HighlightStyle::default(),
+            inline_values_style: HighlightStyle::default(),
         }
     }
 }
@@ -483,6 +491,7 @@ pub struct Editor {
     blink_manager: Model<BlinkManager>,
     show_cursor_names: bool,
     hovered_cursors: HashMap<HoveredCursor, Task<()>>,
+    recently_moved_cursors: HashMap<ReplicaId, Task<()>>,
     pub show_local_selections: bool,
     mode: EditorMode,
     show_breadcrumbs: bool,
@@ -514,7 +523,13 @@ pub struct Editor {
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
+    inline_values: inline_values::InlineValues,
+    staged_hunks: Vec<(Range<u32>, ::git::staging::StagedStatus)>,
+    staged_hunks_task: Option<Task<()>>,
+    merge_conflicts: ::git::merge_conflict::MergeConflictDetector,
     pending_rename: Option<RenameState>,
+    annotation_popover: Option<annotations::AnnotationPopover>,
+    show_resolved_threads: bool,
     searchable: bool,
     cursor_shape: CursorShape,
     current_line_highlight: Option<CurrentLineHighlight>,
@@ -550,6 +565,9 @@ pub struct Editor {
     show_git_blame_inline: bool,
     show_git_blame_inline_delay_task: Option<Task<()>>,
     git_blame_inline_enabled: bool,
+    /// The last value of the `git.show_blame` setting, so that the blame gutter toggled by hand
+    /// is only reset when the setting itself changes.
+    git_blame_gutter_setting: bool,
     show_selection_menu: Option<bool>,
     blame: Option<Model<GitBlame>>,
     blame_subscription: Option<Subscription>,
@@ -1740,6 +1758,10 @@ impl Editor {
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
                     if let project::Event::RefreshInlayHints = event {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    } else if let project::Event::AnnotationThreadsChanged(buffer_id) = event {
+                        editor.annotation_threads_changed(*buffer_id, cx);
+                    } else if let project::Event::OfflineEditConflicts(buffer_id) = event {
+                        editor.refresh_offline_edit_conflicts(*buffer_id, cx);
                     } else if let project::Event::SnippetEdit(id, snippet_edits) = event {
                         if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                             let focus_handle = editor.focus_handle(cx);
@@ -1835,6 +1857,8 @@ impl Editor {
             document_highlights_task: Default::default(),
             linked_editing_range_task: Default::default(),
             pending_rename: Default::default(),
+            annotation_popover: None,
+            show_resolved_threads: false,
             searchable: true,
             cursor_shape: Default::default(),
             current_line_highlight: None,
@@ -1864,6 +1888,7 @@ impl Editor {
             style: None,
             show_cursor_names: false,
             hovered_cursors: Default::default(),
+            recently_moved_cursors: Default::default(),
             next_editor_action_id: EditorActionId::default(),
             editor_actions: Rc::default(),
             vim_replace_map: Default::default(),
@@ -1874,6 +1899,7 @@ impl Editor {
             show_selection_menu: None,
             show_git_blame_inline_delay_task: None,
             git_blame_inline_enabled: ProjectSettings::get_global(cx).git.inline_blame_enabled(),
+            git_blame_gutter_setting: ProjectSettings::get_global(cx).git.show_blame(),
             blame: None,
             blame_subscription: None,
             file_header_size,
@@ -1899,6 +1925,12 @@ impl Editor {
             ],
             tasks_update_task: None,
             linked_edit_ranges: Default::default(),
+            inline_values: inline_values::InlineValues::new(
+                EditorSettings::get_global(cx).show_inline_values,
+            ),
+            staged_hunks: Vec::new(),
+            staged_hunks_task: None,
+            merge_conflicts: Default::default(),
             previous_search_ranges: None,
             breadcrumb_header: None,
         };
@@ -1907,6 +1939,7 @@ impl Editor {
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
+        this.refresh_merge_conflicts(cx);
 
         if mode == EditorMode::Full {
             let should_auto_hide_scrollbars = cx.should_auto_hide_scrollbars();
@@ -1916,6 +1949,9 @@ impl Editor {
                 this.git_blame_inline_enabled = true;
                 this.start_git_blame_inline(false, cx);
             }
+            if this.git_blame_gutter_setting {
+                this.set_show_git_blame_gutter(true, false, cx);
+            }
         }
 
         this.report_editor_event("open", None, cx);
@@ -1945,6 +1981,9 @@ impl Editor {
         if self.pending_rename.is_some() {
             key_context.add("renaming");
         }
+        if self.annotation_popover.is_some() {
+            key_context.add("annotating");
+        }
         if self.context_menu_visible() {
             match self.context_menu.read().as_ref() {
                 Some(ContextMenu::Completions(_)) => {
@@ -2306,6 +2345,7 @@ impl Editor {
         self.snippet_stack
             .invalidate(&self.selections.disjoint_anchors(), buffer);
         self.take_rename(false, cx);
+        self.take_annotation_popover(cx);
 
         let new_cursor_position = self.selections.newest_anchor().head();
 
@@ -2882,6 +2922,10 @@ impl Editor {
             return true;
         }
 
+        if self.take_annotation_popover(cx) {
+            return true;
+        }
+
         if hide_hover(self, cx) {
             return true;
         }
@@ -2903,6 +2947,10 @@ impl Editor {
         }
 
         if self.mode == EditorMode::Full {
+            if self.dismiss_offline_edit_conflicts(cx) {
+                return true;
+            }
+
             if self.active_diagnostics.is_some() {
                 self.dismiss_diagnostics(cx);
                 return true;
@@ -4875,6 +4923,27 @@ impl Editor {
         self.show_cursor_names(cx);
     }
 
+    /// Whether the cursors of the given collaborator moved recently enough for their name to be
+    /// shown next to them.
+    pub fn remote_cursor_moved_recently(&self, replica_id: ReplicaId) -> bool {
+        self.recently_moved_cursors.contains_key(&replica_id)
+    }
+
+    fn remote_cursor_moved(&mut self, replica_id: ReplicaId, cx: &mut ViewContext<Self>) {
+        self.recently_moved_cursors.insert(
+            replica_id,
+            cx.spawn(|this, mut cx| async move {
+                cx.background_executor().timer(CURSORS_VISIBLE_FOR).await;
+                this.update(&mut cx, |this, cx| {
+                    this.recently_moved_cursors.remove(&replica_id);
+                    cx.notify()
+                })
+                .ok();
+            }),
+        );
+        cx.notify();
+    }
+
     fn show_cursor_names(&mut self, cx: &mut ViewContext<Self>) {
         self.show_cursor_names = true;
         cx.notify();
@@ -9545,6 +9614,10 @@ impl Editor {
                                                     color: Some(cx.theme().status().predictive),
                                                     ..HighlightStyle::default()
                                                 },
+                                                inline_values_style: HighlightStyle {
+                                                    color: Some(cx.theme().status().info),
+                                                    ..HighlightStyle::default()
+                                                },
                                             },
                                         ))
                                         .into_any_element()
@@ -10403,13 +10476,21 @@ impl Editor {
     }
 
     pub fn toggle_git_blame(&mut self, _: &ToggleGitBlame, cx: &mut ViewContext<Self>) {
-        self.show_git_blame_gutter = !self.show_git_blame_gutter;
+        self.set_show_git_blame_gutter(!self.show_git_blame_gutter, true, cx);
+        cx.notify();
+    }
+
+    fn set_show_git_blame_gutter(
+        &mut self,
+        show: bool,
+        user_triggered: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.show_git_blame_gutter = show;
 
         if self.show_git_blame_gutter && !self.has_blame_entries(cx) {
-            self.start_git_blame(true, cx);
+            self.start_git_blame(user_triggered, cx);
         }
-
-        cx.notify();
     }
 
     pub fn toggle_git_blame_inline(
@@ -10750,6 +10831,11 @@ impl Editor {
         self.breadcrumb_header = Some(new_header);
     }
 
+    /// The text shown in place of the path of the editor's file in its breadcrumbs, if any.
+    pub fn breadcrumb_header(&self) -> Option<&str> {
+        self.breadcrumb_header.as_deref()
+    }
+
     pub fn clear_search_within_ranges(&mut self, cx: &mut ViewContext<Self>) {
         self.clear_background_highlights::<SearchWithinRange>(cx);
     }
@@ -11127,6 +11213,9 @@ impl Editor {
                 self.active_indent_guides_state.dirty = true;
                 self.refresh_active_diagnostics(cx);
                 self.refresh_code_actions(cx);
+                if self.has_merge_conflicts() {
+                    self.refresh_merge_conflicts(cx);
+                }
                 if self.has_active_inline_completion(cx) {
                     self.update_visible_inline_completion(cx);
                 }
@@ -11202,7 +11291,9 @@ impl Editor {
             }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
             multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
-            multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
+            multi_buffer::Event::FileHandleChanged => cx.emit(EditorEvent::TitleChanged),
+            multi_buffer::Event::Reloaded => {
+                self.refresh_merge_conflicts(cx);
                 cx.emit(EditorEvent::TitleChanged)
             }
             multi_buffer::Event::DiffBaseChanged => {
@@ -11212,6 +11303,7 @@ impl Editor {
             }
             multi_buffer::Event::DiffUpdated { buffer } => {
                 self.sync_expanded_diff_hunks(buffer.clone(), cx);
+                self.refresh_staged_hunks(cx);
                 cx.notify();
             }
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
@@ -11220,6 +11312,9 @@ impl Editor {
                 self.scrollbar_marker_state.dirty = true;
                 cx.notify();
             }
+            multi_buffer::Event::RemoteSelectionsChanged { replica_id } => {
+                self.remote_cursor_moved(*replica_id, cx);
+            }
             _ => {}
         };
     }
@@ -11242,12 +11337,19 @@ impl Editor {
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
+        self.inline_values_settings_changed(cx);
 
         if self.mode == EditorMode::Full {
             let inline_blame_enabled = ProjectSettings::get_global(cx).git.inline_blame_enabled();
             if self.git_blame_inline_enabled != inline_blame_enabled {
                 self.toggle_git_blame_inline_internal(false, cx);
             }
+
+            let show_blame = ProjectSettings::get_global(cx).git.show_blame();
+            if self.git_blame_gutter_setting != show_blame {
+                self.git_blame_gutter_setting = show_blame;
+                self.set_show_git_blame_gutter(show_blame, false, cx);
+            }
         }
 
         cx.notify();
@@ -12274,6 +12376,7 @@ impl Render for Editor {
                 color: cx.theme().colors().editor_foreground,
                 font_family: settings.ui_font.family.clone(),
                 font_features: settings.ui_font.features.clone(),
+                font_axes: settings.ui_font.axes.clone(),
                 font_size: rems(0.875).into(),
                 font_weight: settings.ui_font.weight,
                 font_style: FontStyle::Normal,
@@ -12287,6 +12390,7 @@ impl Render for Editor {
                 color: cx.theme().colors().editor_foreground,
                 font_family: settings.buffer_font.family.clone(),
                 font_features: settings.buffer_font.features.clone(),
+                font_axes: settings.buffer_font.axes.clone(),
                 font_size: settings.buffer_font_size(cx).into(),
                 font_weight: settings.buffer_font.weight,
                 font_style: FontStyle::Normal,
@@ -12321,6 +12425,10 @@ impl Render for Editor {
                     color: Some(cx.theme().status().predictive),
                     ..HighlightStyle::default()
                 },
+                inline_values_style: HighlightStyle {
+                    color: Some(cx.theme().status().info),
+                    ..HighlightStyle::default()
+                },
             },
         )
     }
@@ -28,6 +28,7 @@ pub struct EditorSettings {
     pub search_wrap: bool,
     pub auto_signature_help: bool,
     pub show_signature_help_after_edits: bool,
+    pub show_inline_values: bool,
     #[serde(default)]
     pub jupyter: Jupyter,
 }
@@ -246,6 +247,12 @@ pub struct EditorSettingsContent {
     /// Default: true
     pub show_signature_help_after_edits: Option<bool>,
 
+    /// Whether to show the values of variables next to the code while a debug
+    /// session is stopped at a breakpoint.
+    ///
+    /// Default: true
+    pub show_inline_values: Option<bool>,
+
     /// Jupyter REPL settings.
     pub jupyter: Option<Jupyter>,
 }
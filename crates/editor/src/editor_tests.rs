@@ -2,7 +2,7 @@ use super::*;
 use crate::{
     scroll::scroll_amount::ScrollAmount,
     test::{
-        assert_text_with_selections, build_editor, editor_hunks,
+        assert_text_with_selections, build_editor, build_editor_with_project, editor_hunks,
         editor_lsp_test_context::EditorLspTestContext, editor_test_context::EditorTestContext,
         expanded_hunks, expanded_hunks_background_highlights, select_ranges,
     },
@@ -8626,6 +8626,83 @@ fn test_highlighted_ranges(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_annotation_threads(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file("/file.rs", "one\ntwo\nthree\n".into()).await;
+    let project = Project::test(fs, ["/file.rs".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/file.rs", cx))
+        .await
+        .unwrap();
+    let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+    let multibuffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) =
+        cx.add_window_view(|cx| build_editor_with_project(project.clone(), multibuffer, cx));
+
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(1, 1)..Point::new(1, 1)])
+        });
+        editor.new_annotation_thread(&NewAnnotationThread, cx);
+        assert!(editor.annotation_popover_visible());
+
+        let reply_editor = editor
+            .annotation_popover
+            .as_ref()
+            .unwrap()
+            .reply_editor()
+            .clone();
+        reply_editor.update(cx, |reply_editor, cx| reply_editor.set_text("Typo?", cx));
+        editor.reply_to_thread(&ReplyToThread, cx);
+        assert_eq!(reply_editor.read(cx).text(cx), "");
+    });
+    cx.run_until_parked();
+
+    project.read_with(cx, |project, _| {
+        let threads = project.annotation_threads(buffer_id).unwrap();
+        let thread = threads.iter().next().unwrap();
+        assert_eq!(
+            thread
+                .comments
+                .iter()
+                .map(|comment| comment.body.as_str())
+                .collect::<Vec<_>>(),
+            ["Typo?"]
+        );
+        assert!(!thread.resolved);
+    });
+
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.snapshot(cx);
+        assert_eq!(
+            editor
+                .annotation_indicators(&snapshot, cx)
+                .into_keys()
+                .collect::<Vec<_>>(),
+            [DisplayRow(1)]
+        );
+
+        // Resolved threads are hidden unless explicitly shown.
+        editor.resolve_thread(&ResolveThread, cx);
+        assert!(!editor.annotation_popover_visible());
+        let snapshot = editor.snapshot(cx);
+        assert!(editor.annotation_indicators(&snapshot, cx).is_empty());
+
+        editor.show_resolved_threads(&ShowResolvedThreads, cx);
+        let snapshot = editor.snapshot(cx);
+        assert_eq!(
+            editor
+                .annotation_indicators(&snapshot, cx)
+                .into_keys()
+                .collect::<Vec<_>>(),
+            [DisplayRow(1)]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_following(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -8714,6 +8791,15 @@ async fn test_following(cx: &mut gpui::TestAppContext) {
         .unwrap()
         .await
         .unwrap();
+    // The follower glides towards the leader's scroll position.
+    cx.executor()
+        .advance_clock(scroll::REMOTE_SCROLL_DURATION / 2);
+    let scroll_position = follower
+        .update(cx, |follower, cx| follower.scroll_position(cx))
+        .unwrap();
+    assert!(scroll_position.y > 0. && scroll_position.y < 3.5);
+    cx.executor()
+        .advance_clock(scroll::REMOTE_SCROLL_DURATION / 2);
     assert_eq!(
         follower
             .update(cx, |follower, cx| follower.scroll_position(cx))
@@ -9459,6 +9545,9 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             LspSettings {
                 binary: None,
                 settings: None,
+                restart_policy: None,
+                semantic_tokens_edit_threshold: None,
+                diagnostic_filters: Vec::new(),
                 initialization_options: Some(json!({
                     "some other init value": false
                 })),
@@ -9478,6 +9567,9 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             LspSettings {
                 binary: None,
                 settings: None,
+                restart_policy: None,
+                semantic_tokens_edit_threshold: None,
+                diagnostic_filters: Vec::new(),
                 initialization_options: Some(json!({
                     "anotherInitValue": false
                 })),
@@ -9497,6 +9589,9 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             LspSettings {
                 binary: None,
                 settings: None,
+                restart_policy: None,
+                semantic_tokens_edit_threshold: None,
+                diagnostic_filters: Vec::new(),
                 initialization_options: Some(json!({
                     "anotherInitValue": false
                 })),
@@ -9516,6 +9611,9 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             LspSettings {
                 binary: None,
                 settings: None,
+                restart_policy: None,
+                semantic_tokens_edit_threshold: None,
+                diagnostic_filters: Vec::new(),
                 initialization_options: None,
             },
         );
@@ -1,5 +1,6 @@
 use crate::editor_settings::ScrollBeyondLastLine;
 use crate::{
+    annotations::AnnotationIndicator,
     blame_entry_tooltip::{blame_entry_relative_timestamp, BlameEntryTooltip},
     display_map::{
         BlockContext, BlockStyle, DisplaySnapshot, HighlightedChunk, ToDisplayPoint, TransformBlock,
@@ -326,6 +327,10 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_line_numbers);
         register_action(view, cx, Editor::toggle_indent_guides);
         register_action(view, cx, Editor::toggle_inlay_hints);
+        register_action(view, cx, Editor::new_annotation_thread);
+        register_action(view, cx, Editor::reply_to_thread);
+        register_action(view, cx, Editor::resolve_thread);
+        register_action(view, cx, Editor::show_resolved_threads);
         register_action(view, cx, hover_popover::hover);
         register_action(view, cx, Editor::reveal_in_finder);
         register_action(view, cx, Editor::copy_path);
@@ -396,6 +401,11 @@ impl EditorElement {
         register_action(view, cx, Editor::accept_partial_inline_completion);
         register_action(view, cx, Editor::accept_inline_completion);
         register_action(view, cx, Editor::revert_selected_hunks);
+        register_action(view, cx, Editor::stage_hunk);
+        register_action(view, cx, Editor::unstage_hunk);
+        register_action(view, cx, Editor::accept_ours);
+        register_action(view, cx, Editor::accept_theirs);
+        register_action(view, cx, Editor::accept_both);
         register_action(view, cx, Editor::open_active_item_in_terminal)
     }
 
@@ -452,6 +462,20 @@ impl EditorElement {
         let mut click_count = event.click_count;
         let mut modifiers = event.modifiers;
 
+        // Alt-clicking a changed row in the gutter stages or unstages its changes.
+        if modifiers.alt && gutter_hitbox.is_hovered(cx) {
+            let point_for_position =
+                position_map.point_for_position(text_hitbox.bounds, event.position);
+            let row = point_for_position
+                .previous_valid
+                .to_point(&position_map.snapshot.display_snapshot)
+                .row;
+            if editor.toggle_staged_row(row, cx) {
+                cx.notify();
+                return;
+            }
+        }
+
         if let Some(hovered_hunk) = hovered_hunk {
             editor.expand_diff_hunk(None, hovered_hunk, cx);
             cx.notify();
@@ -848,8 +872,9 @@ impl EditorElement {
                     selection_id: selection.selection.id,
                 };
 
-                let is_shown =
-                    editor.show_cursor_names || editor.hovered_cursors.contains_key(&key);
+                let is_shown = editor.show_cursor_names
+                    || editor.hovered_cursors.contains_key(&key)
+                    || editor.remote_cursor_moved_recently(selection.replica_id);
 
                 remote_selections
                     .entry(selection.replica_id)
@@ -1564,6 +1589,7 @@ impl EditorElement {
         gutter_dimensions: &GutterDimensions,
         gutter_hitbox: &Hitbox,
         snapshot: &EditorSnapshot,
+        annotation_indicators: &BTreeMap<DisplayRow, AnnotationIndicator>,
         cx: &mut WindowContext,
     ) -> Vec<AnyElement> {
         self.editor.update(cx, |editor, cx| {
@@ -1592,6 +1618,9 @@ impl EditorElement {
                         return None;
                     }
                     let display_row = multibuffer_point.to_display_point(snapshot).row();
+                    if annotation_indicators.contains_key(&display_row) {
+                        return None;
+                    }
                     let button = editor.render_run_indicator(
                         &self.style,
                         Some(display_row) == active_task_indicator_row,
@@ -1614,6 +1643,36 @@ impl EditorElement {
         })
     }
 
+    fn layout_annotation_indicators(
+        &self,
+        annotation_indicators: BTreeMap<DisplayRow, AnnotationIndicator>,
+        rows: Range<DisplayRow>,
+        line_height: Pixels,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            annotation_indicators
+                .into_iter()
+                .filter(|(display_row, _)| rows.contains(display_row))
+                .map(|(display_row, indicator)| {
+                    let button = editor.render_annotation_indicator(display_row, indicator, cx);
+                    prepaint_gutter_button(
+                        button,
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        cx,
+                    )
+                })
+                .collect_vec()
+        })
+    }
+
     fn layout_code_actions_indicator(
         &self,
         line_height: Pixels,
@@ -2637,6 +2696,54 @@ impl EditorElement {
     }
 
     #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::too_many_arguments)]
+    fn layout_annotation_popover(
+        &self,
+        snapshot: &EditorSnapshot,
+        hitbox: &Hitbox,
+        text_hitbox: &Hitbox,
+        content_origin: gpui::Point<Pixels>,
+        rows: Range<DisplayRow>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        em_width: Pixels,
+        gutter_overshoot: Pixels,
+        cx: &mut WindowContext,
+    ) {
+        let max_size = size(
+            (60. * em_width)
+                .min(hitbox.size.width / 2.)
+                .max(MIN_POPOVER_CHARACTER_WIDTH * em_width),
+            (16. * line_height)
+                .min(hitbox.size.height / 2.)
+                .max(MIN_POPOVER_LINE_HEIGHT * line_height),
+        );
+        let Some((anchor, mut element)) = self.editor.update(cx, |editor, cx| {
+            editor.render_annotation_popover(&self.style, max_size, cx)
+        }) else {
+            return;
+        };
+        let row = anchor.to_display_point(snapshot).row();
+        if !rows.contains(&row) {
+            return;
+        }
+
+        let size = element.layout_as_root(Size::<AvailableSpace>::default(), cx);
+        let y = row.next_row().as_f32() * line_height - scroll_pixel_position.y;
+        let mut origin = content_origin + point(-gutter_overshoot, y);
+
+        // Show the popover above the thread's line if it would overflow the editor.
+        if origin.y + size.height > text_hitbox.lower_right().y {
+            origin.y -= line_height + size.height;
+        }
+        let window_size = cx.viewport_size();
+        if origin.x + size.width > window_size.width {
+            origin.x = (window_size.width - size.width).max(Pixels::ZERO);
+        }
+
+        cx.defer_draw(element, origin, 1);
+    }
+
     fn layout_signature_help(
         &self,
         hitbox: &Hitbox,
@@ -3087,6 +3194,10 @@ impl EditorElement {
                 test_indicators.paint(cx);
             }
 
+            for annotation_indicator in layout.annotation_indicators.iter_mut() {
+                annotation_indicator.paint(cx);
+            }
+
             if let Some(indicator) = layout.code_actions_indicator.as_mut() {
                 indicator.paint(cx);
             }
@@ -3157,36 +3268,31 @@ impl EditorElement {
     }
 
     fn paint_text(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
-        cx.with_content_mask(
-            Some(ContentMask {
-                bounds: layout.text_hitbox.bounds,
-            }),
-            |cx| {
-                let cursor_style = if self
-                    .editor
-                    .read(cx)
-                    .hovered_link_state
-                    .as_ref()
-                    .is_some_and(|hovered_link_state| !hovered_link_state.links.is_empty())
-                {
-                    CursorStyle::PointingHand
-                } else {
-                    CursorStyle::IBeam
-                };
-                cx.set_cursor_style(cursor_style, &layout.text_hitbox);
-
-                let invisible_display_ranges = self.paint_highlights(layout, cx);
-                self.paint_lines(&invisible_display_ranges, layout, cx);
-                self.paint_redactions(layout, cx);
-                self.paint_cursors(layout, cx);
-                self.paint_inline_blame(layout, cx);
-                cx.with_element_namespace("crease_trailers", |cx| {
-                    for trailer in layout.crease_trailers.iter_mut().flatten() {
-                        trailer.element.paint(cx);
-                    }
-                });
-            },
-        )
+        cx.with_content_mask(Some(ContentMask::new(layout.text_hitbox.bounds)), |cx| {
+            let cursor_style = if self
+                .editor
+                .read(cx)
+                .hovered_link_state
+                .as_ref()
+                .is_some_and(|hovered_link_state| !hovered_link_state.links.is_empty())
+            {
+                CursorStyle::PointingHand
+            } else {
+                CursorStyle::IBeam
+            };
+            cx.set_cursor_style(cursor_style, &layout.text_hitbox);
+
+            let invisible_display_ranges = self.paint_highlights(layout, cx);
+            self.paint_lines(&invisible_display_ranges, layout, cx);
+            self.paint_redactions(layout, cx);
+            self.paint_cursors(layout, cx);
+            self.paint_inline_blame(layout, cx);
+            cx.with_element_namespace("crease_trailers", |cx| {
+                for trailer in layout.crease_trailers.iter_mut().flatten() {
+                    trailer.element.paint(cx);
+                }
+            });
+        })
     }
 
     fn paint_highlights(
@@ -4672,7 +4778,7 @@ impl Element for EditorElement {
         let rem_size = self.rem_size(cx);
         cx.with_rem_size(rem_size, |cx| {
             cx.with_text_style(Some(text_style), |cx| {
-                cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                cx.with_content_mask(Some(ContentMask::new(bounds)), |cx| {
                     let mut snapshot = self.editor.update(cx, |editor, cx| editor.snapshot(cx));
                     let style = self.style.clone();
 
@@ -5079,6 +5185,9 @@ impl Element for EditorElement {
 
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
+                    let annotation_indicators =
+                        self.editor.read(cx).annotation_indicators(&snapshot, cx);
+
                     let mut _context_menu_visible = false;
                     let mut code_actions_indicator = None;
                     if let Some(newest_selection_head) = newest_selection_head {
@@ -5111,7 +5220,10 @@ impl Element for EditorElement {
                                     let has_test_indicator =
                                         self.editor.read(cx).tasks.contains_key(&(buffer_id, row));
 
-                                    if !has_test_indicator {
+                                    let has_annotation_indicator = annotation_indicators
+                                        .contains_key(&newest_selection_head.row());
+
+                                    if !has_test_indicator && !has_annotation_indicator {
                                         code_actions_indicator = self
                                             .layout_code_actions_indicator(
                                                 line_height,
@@ -5134,12 +5246,36 @@ impl Element for EditorElement {
                             &gutter_dimensions,
                             &gutter_hitbox,
                             &snapshot,
+                            &annotation_indicators,
                             cx,
                         )
                     } else {
                         vec![]
                     };
 
+                    let annotation_indicator_elements = self.layout_annotation_indicators(
+                        annotation_indicators,
+                        start_row..end_row,
+                        line_height,
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        cx,
+                    );
+
+                    self.layout_annotation_popover(
+                        &snapshot,
+                        &hitbox,
+                        &text_hitbox,
+                        content_origin,
+                        start_row..end_row,
+                        scroll_pixel_position,
+                        line_height,
+                        em_width,
+                        gutter_dimensions.width - gutter_dimensions.left_padding,
+                        cx,
+                    );
+
                     self.layout_signature_help(
                         &hitbox,
                         content_origin,
@@ -5250,6 +5386,7 @@ impl Element for EditorElement {
                         selections,
                         mouse_context_menu,
                         test_indicators,
+                        annotation_indicators: annotation_indicator_elements,
                         code_actions_indicator,
                         gutter_fold_toggles,
                         crease_trailers,
@@ -5315,7 +5452,7 @@ impl Element for EditorElement {
         let rem_size = self.rem_size(cx);
         cx.with_rem_size(rem_size, |cx| {
             cx.with_text_style(Some(text_style), |cx| {
-                cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                cx.with_content_mask(Some(ContentMask::new(bounds)), |cx| {
                     self.paint_mouse_listeners(layout, hovered_hunk, cx);
                     self.paint_background(layout, cx);
                     self.paint_indent_guides(layout, cx);
@@ -5382,6 +5519,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
     test_indicators: Vec<AnyElement>,
+    annotation_indicators: Vec<AnyElement>,
     gutter_fold_toggles: Vec<Option<AnyElement>>,
     crease_trailers: Vec<Option<CreaseTrailerLayout>>,
     mouse_context_menu: Option<AnyElement>,
@@ -1,4 +1,6 @@
 pub mod blame;
+mod merge_conflicts;
+mod staging;
 
 use std::ops::Range;
 
@@ -519,8 +519,10 @@ async fn parse_markdown(text: &str, language_registry: &Arc<LanguageRegistry>) -
 #[cfg(test)]
 mod tests {
     use super::*;
-    use gpui::Context;
+    use crate::{actions::ToggleGitBlame, Editor};
+    use gpui::{Context, UpdateGlobal};
     use language::{Point, Rope};
+    use project::project_settings::ProjectSettings;
     use project::FakeFs;
     use rand::prelude::*;
     use serde_json::json;
@@ -942,6 +944,77 @@ mod tests {
         blame_entries
     }
 
+    #[gpui::test]
+    async fn test_show_blame_setting(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let set_show_blame = |show_blame, cx: &mut gpui::TestAppContext| {
+            cx.update(|cx| {
+                SettingsStore::update_global(cx, |store, cx| {
+                    store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                        settings.git.show_blame = Some(show_blame);
+                    });
+                });
+            });
+        };
+        set_show_blame(true, cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/my-repo",
+            json!({
+                ".git": {},
+                "file.txt": "AAA Line 1\nBBB Line 2\n",
+            }),
+        )
+        .await;
+        fs.set_blame_for_repo(
+            Path::new("/my-repo/.git"),
+            vec![(
+                Path::new("file.txt"),
+                Blame {
+                    entries: vec![blame_entry("1b1b1b", 0..2)],
+                    ..Default::default()
+                },
+            )],
+        );
+        let project = Project::test(fs, ["/my-repo".as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/my-repo/file.txt", cx)
+            })
+            .await
+            .unwrap();
+        let editor = cx.add_window(|cx| Editor::for_buffer(buffer, Some(project), cx));
+        cx.executor().run_until_parked();
+        let shows_blame = |cx: &mut gpui::TestAppContext| {
+            editor
+                .update(cx, |editor, cx| editor.render_git_blame_gutter(cx))
+                .unwrap()
+        };
+
+        // Editors show the blame gutter when they're opened.
+        assert!(shows_blame(cx));
+
+        // Toggling it by hand isn't undone by unrelated settings changes...
+        editor
+            .update(cx, |editor, cx| {
+                editor.toggle_git_blame(&ToggleGitBlame, cx)
+            })
+            .unwrap();
+        assert!(!shows_blame(cx));
+        set_show_blame(true, cx);
+        assert!(!shows_blame(cx));
+
+        // ...but changing the setting applies to open editors.
+        set_show_blame(false, cx);
+        assert!(!shows_blame(cx));
+        set_show_blame(true, cx);
+        cx.executor().run_until_parked();
+        assert!(shows_blame(cx));
+        set_show_blame(false, cx);
+        assert!(!shows_blame(cx));
+    }
+
     fn blame_entry(sha: &str, range: Range<u32>) -> BlameEntry {
         BlameEntry {
             sha: sha.parse().unwrap(),
@@ -0,0 +1,311 @@
+use std::ops::Range;
+
+use git::merge_conflict::MergeConflictResolution;
+use gpui::{Hsla, ViewContext};
+use language::{BufferId, Point, ToPoint as _};
+use theme::ActiveTheme as _;
+
+use crate::{
+    actions::{AcceptBoth, AcceptOurs, AcceptTheirs},
+    Editor,
+};
+
+/// Row highlights of the current branch's side of merge conflicts.
+enum OursConflictHighlight {}
+
+/// Row highlights of the merged branch's side of merge conflicts.
+enum TheirsConflictHighlight {}
+
+/// Row highlights of regions that a collaborator edited while we were editing them offline.
+enum OfflineEditConflictHighlight {}
+
+impl Editor {
+    pub fn accept_ours(&mut self, _: &AcceptOurs, cx: &mut ViewContext<Self>) {
+        self.resolve_merge_conflicts(MergeConflictResolution::Ours, cx);
+    }
+
+    pub fn accept_theirs(&mut self, _: &AcceptTheirs, cx: &mut ViewContext<Self>) {
+        self.resolve_merge_conflicts(MergeConflictResolution::Theirs, cx);
+    }
+
+    pub fn accept_both(&mut self, _: &AcceptBoth, cx: &mut ViewContext<Self>) {
+        self.resolve_merge_conflicts(MergeConflictResolution::Both, cx);
+    }
+
+    /// Rescans the buffer for conflict markers and highlights both sides of each conflict.
+    pub(crate) fn refresh_merge_conflicts(&mut self, cx: &mut ViewContext<Self>) {
+        // Only files can have been left with conflicts by git.
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        if buffer.read(cx).file().is_none() {
+            return;
+        }
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        if !self.merge_conflicts.refresh(&buffer_snapshot) {
+            return;
+        }
+
+        self.clear_row_highlights::<OursConflictHighlight>();
+        self.clear_row_highlights::<TheirsConflictHighlight>();
+        let ours_color = conflict_color(cx.theme().status().git().created);
+        let theirs_color = conflict_color(cx.theme().status().info);
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut ours = Vec::new();
+        let mut theirs = Vec::new();
+        for region in self.merge_conflicts.regions() {
+            ours.push(
+                region.ours.start.to_point(&buffer_snapshot)
+                    ..region.ours.end.to_point(&buffer_snapshot),
+            );
+            theirs.push(
+                region.theirs.start.to_point(&buffer_snapshot)
+                    ..region.theirs.end.to_point(&buffer_snapshot),
+            );
+        }
+        // Each side ends at the start of the marker following it, so its last row is the one
+        // before that, and a side without any lines isn't highlighted.
+        let to_row_range = |range: Range<Point>| {
+            (range.start.row < range.end.row).then(|| {
+                snapshot.anchor_before(Point::new(range.start.row, 0))
+                    ..=snapshot.anchor_after(Point::new(range.end.row - 1, 0))
+            })
+        };
+        for rows in ours.into_iter().filter_map(&to_row_range) {
+            self.highlight_rows::<OursConflictHighlight>(rows, Some(ours_color), false, cx);
+        }
+        for rows in theirs.into_iter().filter_map(&to_row_range) {
+            self.highlight_rows::<TheirsConflictHighlight>(rows, Some(theirs_color), false, cx);
+        }
+        cx.notify();
+    }
+
+    /// Whether the buffer had conflict markers when it was last scanned, in which case edits
+    /// rescan it so that conflicts resolved by hand lose their highlights.
+    pub(crate) fn has_merge_conflicts(&self) -> bool {
+        !self.merge_conflicts.regions().is_empty()
+    }
+
+    /// Highlights the regions where a collaborator's edits were merged with edits made while
+    /// disconnected, so that the result can be reviewed.
+    pub(crate) fn refresh_offline_edit_conflicts(
+        &mut self,
+        buffer_id: BufferId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        if buffer.read(cx).remote_id() != buffer_id {
+            return;
+        }
+
+        self.clear_row_highlights::<OfflineEditConflictHighlight>();
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let rows = project
+            .read(cx)
+            .offline_edits()
+            .conflicts(buffer_id)
+            .iter()
+            .map(|range| {
+                let start = range.start.to_point(&buffer_snapshot);
+                let end = range.end.to_point(&buffer_snapshot);
+                snapshot.anchor_before(Point::new(start.row, 0))
+                    ..=snapshot.anchor_after(Point::new(end.row, 0))
+            })
+            .collect::<Vec<_>>();
+        let color = conflict_color(cx.theme().status().conflict);
+        for rows in rows {
+            self.highlight_rows::<OfflineEditConflictHighlight>(rows, Some(color), false, cx);
+        }
+        cx.notify();
+    }
+
+    /// Clears the highlights of conflicts between offline and remote edits, returning whether
+    /// there were any.
+    pub(crate) fn dismiss_offline_edit_conflicts(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(project) = self.project.clone() else {
+            return false;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return false;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        if project
+            .read(cx)
+            .offline_edits()
+            .conflicts(buffer_id)
+            .is_empty()
+        {
+            return false;
+        }
+        project.update(cx, |project, cx| {
+            project.dismiss_offline_edit_conflicts(buffer_id, cx)
+        });
+        true
+    }
+
+    /// Replaces the conflicts containing a cursor with the given sides, removing their markers.
+    fn resolve_merge_conflicts(
+        &mut self,
+        resolution: MergeConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        self.refresh_merge_conflicts(cx);
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let mut edits = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            let Some(region) = self
+                .merge_conflicts
+                .region_at(selection.head(), &buffer_snapshot)
+            else {
+                continue;
+            };
+            let edit = region.resolve(resolution, &buffer_snapshot);
+            if !edits.contains(&edit) {
+                edits.push(edit);
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+        edits.sort_by_key(|(range, _)| range.start);
+
+        self.transact(cx, |editor, cx| editor.edit(edits, cx));
+        self.refresh_merge_conflicts(cx);
+    }
+}
+
+fn conflict_color(mut color: Hsla) -> Hsla {
+    color.fade_out(0.7);
+    color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{actions::Undo, editor_tests::init_test, ToPoint as _};
+    use gpui::{AppContext, TestAppContext};
+    use language::{Buffer, TestFile};
+    use multi_buffer::MultiBuffer;
+    use std::{ops::RangeInclusive, path::Path, sync::Arc};
+    use unindent::Unindent as _;
+
+    fn highlighted_rows<T: 'static>(editor: &Editor, cx: &AppContext) -> Vec<RangeInclusive<u32>> {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        editor
+            .highlighted_rows::<T>()
+            .into_iter()
+            .flatten()
+            .map(|(rows, _)| {
+                rows.start().to_point(&snapshot).row..=rows.end().to_point(&snapshot).row
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    fn test_resolve_merge_conflicts(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let text = "
+            one
+            <<<<<<< HEAD
+            two
+            =======
+            TWO
+            >>>>>>> feature
+            three
+            <<<<<<< HEAD
+            four
+            ||||||| base
+            4
+            =======
+            FOUR
+            >>>>>>> feature
+        "
+        .unindent();
+        // Buffers that aren't files aren't scanned for conflicts.
+        let buffer = cx.new_model(|cx| Buffer::local(text.clone(), cx));
+        let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+        let editor = cx.add_window(|cx| Editor::for_multibuffer(buffer, None, true, cx));
+        _ = editor.update(cx, |editor, _| {
+            assert!(editor.merge_conflicts.regions().is_empty())
+        });
+
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(text.clone(), cx);
+            let file = TestFile {
+                path: Path::new("conflicted.rs").into(),
+                root_name: String::new(),
+            };
+            buffer.file_updated(Arc::new(file), cx);
+            buffer
+        });
+        let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+        let editor = cx.add_window(|cx| Editor::for_multibuffer(buffer, None, true, cx));
+
+        _ = editor.update(cx, |editor, cx| {
+            assert_eq!(editor.merge_conflicts.regions().len(), 2);
+            assert_eq!(
+                highlighted_rows::<OursConflictHighlight>(editor, cx),
+                [2..=2, 8..=8]
+            );
+            assert_eq!(
+                highlighted_rows::<TheirsConflictHighlight>(editor, cx),
+                [4..=4, 12..=12]
+            );
+
+            // Cursors outside of a conflict are ignored.
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+            });
+            editor.accept_both(&AcceptBoth, cx);
+            assert_eq!(editor.text(cx), text);
+
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(4, 1)..Point::new(4, 1)])
+            });
+            editor.accept_both(&AcceptBoth, cx);
+            assert_eq!(
+                editor.text(cx),
+                text.replace(
+                    "<<<<<<< HEAD\ntwo\n=======\nTWO\n>>>>>>> feature\n",
+                    "two\nTWO\n"
+                )
+            );
+            assert_eq!(editor.merge_conflicts.regions().len(), 1);
+            assert_eq!(
+                highlighted_rows::<OursConflictHighlight>(editor, cx),
+                [5..=5]
+            );
+            assert_eq!(
+                highlighted_rows::<TheirsConflictHighlight>(editor, cx),
+                [9..=9]
+            );
+
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(5, 0)..Point::new(5, 0)])
+            });
+            editor.accept_theirs(&AcceptTheirs, cx);
+            assert_eq!(editor.text(cx), "one\ntwo\nTWO\nthree\nFOUR\n");
+            assert!(editor.merge_conflicts.regions().is_empty());
+            assert!(highlighted_rows::<OursConflictHighlight>(editor, cx).is_empty());
+            assert!(highlighted_rows::<TheirsConflictHighlight>(editor, cx).is_empty());
+
+            // Markers restored by undoing are picked up when resolving again.
+            editor.undo(&Undo, cx);
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(5, 0)..Point::new(5, 0)])
+            });
+            editor.accept_ours(&AcceptOurs, cx);
+            assert_eq!(editor.text(cx), "one\ntwo\nTWO\nthree\nfour\n");
+        });
+    }
+}
@@ -0,0 +1,234 @@
+use std::ops::Range;
+
+use git::staging::StagedStatus;
+use gpui::ViewContext;
+use language::{Bias, Point};
+use multi_buffer::MultiBufferRow;
+use theme::ActiveTheme as _;
+use util::ResultExt as _;
+
+use crate::{
+    actions::{StageHunk, UnstageHunk},
+    Editor,
+};
+
+/// Gutter highlights of changes that have been staged in full.
+enum StagedHunks {}
+
+/// Gutter highlights of changes that have further unstaged changes on top of the staged ones.
+enum PartiallyStagedHunks {}
+
+impl Editor {
+    pub fn stage_hunk(&mut self, _: &StageHunk, cx: &mut ViewContext<Self>) {
+        let rows = self.selected_rows(cx);
+        self.update_index(rows, true, cx);
+    }
+
+    pub fn unstage_hunk(&mut self, _: &UnstageHunk, cx: &mut ViewContext<Self>) {
+        let rows = self.selected_rows(cx);
+        self.update_index(rows, false, cx);
+    }
+
+    /// Stages the changes on a row if it has any unstaged changes, and unstages them otherwise.
+    /// Returns false if the row has no changes to stage or unstage.
+    pub(crate) fn toggle_staged_row(&mut self, row: u32, cx: &mut ViewContext<Self>) -> bool {
+        let has_unstaged_changes = self
+            .buffer
+            .read(cx)
+            .snapshot(cx)
+            .git_diff_hunks_in_range(MultiBufferRow(row)..MultiBufferRow(row + 1))
+            .next()
+            .is_some();
+        let has_staged_changes = self
+            .staged_hunks
+            .iter()
+            .any(|(rows, _)| rows.start <= row && row < rows.end.max(rows.start + 1));
+        if has_unstaged_changes {
+            self.update_index(vec![row..row + 1], true, cx);
+        } else if has_staged_changes {
+            self.update_index(vec![row..row + 1], false, cx);
+        }
+        has_unstaged_changes || has_staged_changes
+    }
+
+    /// Looks up which of the buffer's changes have been staged, to highlight them in the gutter.
+    pub(crate) fn refresh_staged_hunks(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let staged_hunks = project.read(cx).staged_hunks(&buffer, cx);
+        self.staged_hunks_task = Some(cx.spawn(|editor, mut cx| async move {
+            let Some(staged_hunks) = staged_hunks.await.log_err() else {
+                return;
+            };
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.set_staged_hunks(staged_hunks, cx)
+                })
+                .ok();
+        }));
+    }
+
+    fn set_staged_hunks(
+        &mut self,
+        staged_hunks: Vec<(Range<u32>, StagedStatus)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut staged = Vec::new();
+        let mut partially_staged = Vec::new();
+        for (rows, status) in &staged_hunks {
+            // Gutter highlights include their last row, and changes that only remove lines are
+            // highlighted on the row following them.
+            let last_row = rows.end.max(rows.start + 1) - 1;
+            let start = snapshot.clip_point(Point::new(rows.start, 0), Bias::Left);
+            let end = snapshot.clip_point(Point::new(last_row, 0), Bias::Left);
+            let range = snapshot.anchor_before(start)..snapshot.anchor_after(end);
+            match status {
+                StagedStatus::Staged => staged.push(range),
+                StagedStatus::PartiallyStaged => partially_staged.push(range),
+            }
+        }
+        self.staged_hunks = staged_hunks;
+        self.highlight_gutter::<StagedHunks>(&staged, |cx| cx.theme().status().success, cx);
+        self.highlight_gutter::<PartiallyStagedHunks>(
+            &partially_staged,
+            |cx| cx.theme().status().warning,
+            cx,
+        );
+    }
+
+    fn selected_rows(&self, cx: &mut ViewContext<Self>) -> Vec<Range<u32>> {
+        self.selections
+            .all::<Point>(cx)
+            .into_iter()
+            .map(|selection| selection.start.row..selection.end.row + 1)
+            .collect()
+    }
+
+    /// Stages or unstages the changes touching each of the row ranges in turn, as each of them
+    /// updates the same index entry.
+    fn update_index(&mut self, rows: Vec<Range<u32>>, stage: bool, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        cx.spawn(|editor, mut cx| async move {
+            for rows in rows {
+                project
+                    .update(&mut cx, |project, cx| {
+                        if stage {
+                            project.stage_hunks(&buffer, rows, cx)
+                        } else {
+                            project.unstage_hunks(&buffer, rows, cx)
+                        }
+                    })?
+                    .await?;
+            }
+            editor.update(&mut cx, |editor, cx| editor.refresh_staged_hunks(cx))
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor_tests::init_test;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use std::path::Path;
+
+    #[gpui::test]
+    async fn test_stage_and_unstage_hunks(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/my-repo",
+            json!({
+                ".git": {},
+                "file.txt": "one\nTWO\nthree\nFOUR\n",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/my-repo/.git");
+        let committed_text = "one\ntwo\nthree\nfour\n".to_string();
+        fs.set_head_for_repo(dot_git, &[(Path::new("file.txt"), committed_text.clone())]);
+        fs.set_index_for_repo(dot_git, &[(Path::new("file.txt"), committed_text.clone())]);
+
+        let project = Project::test(fs.clone(), ["/my-repo".as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/my-repo/file.txt", cx)
+            })
+            .await
+            .unwrap();
+        let editor = cx.add_window(|cx| Editor::for_buffer(buffer, Some(project), cx));
+        cx.executor().run_until_parked();
+
+        let index_text = || {
+            let mut text = None;
+            fs.with_git_state(dot_git, false, |state| {
+                text = state.index_contents.get(Path::new("file.txt")).cloned();
+            });
+            text.unwrap()
+        };
+        let staged_hunks = |cx: &mut TestAppContext| {
+            editor
+                .update(cx, |editor, _| editor.staged_hunks.clone())
+                .unwrap()
+        };
+        assert!(staged_hunks(cx).is_empty());
+
+        // The action stages the hunks touched by the selections.
+        editor
+            .update(cx, |editor, cx| {
+                editor.change_selections(None, cx, |s| {
+                    s.select_ranges([Point::new(1, 1)..Point::new(1, 1)])
+                });
+                editor.stage_hunk(&StageHunk, cx);
+            })
+            .unwrap();
+        cx.executor().run_until_parked();
+        assert_eq!(index_text(), "one\nTWO\nthree\nfour\n");
+        assert_eq!(staged_hunks(cx), [(1..2, StagedStatus::Staged)]);
+
+        // Clicking a changed row stages it, and clicking it again unstages it.
+        let toggle_row = |row, cx: &mut TestAppContext| {
+            let toggled = editor
+                .update(cx, |editor, cx| editor.toggle_staged_row(row, cx))
+                .unwrap();
+            cx.executor().run_until_parked();
+            toggled
+        };
+        assert!(toggle_row(3, cx));
+        assert_eq!(index_text(), "one\nTWO\nthree\nFOUR\n");
+        assert_eq!(
+            staged_hunks(cx),
+            [(1..2, StagedStatus::Staged), (3..4, StagedStatus::Staged)]
+        );
+        assert!(toggle_row(1, cx));
+        assert_eq!(index_text(), "one\ntwo\nthree\nFOUR\n");
+        assert_eq!(staged_hunks(cx), [(3..4, StagedStatus::Staged)]);
+        assert!(!toggle_row(2, cx));
+
+        editor
+            .update(cx, |editor, cx| {
+                editor.change_selections(None, cx, |s| {
+                    s.select_ranges([Point::new(0, 0)..Point::new(3, 0)])
+                });
+                editor.unstage_hunk(&UnstageHunk, cx);
+            })
+            .unwrap();
+        cx.executor().run_until_parked();
+        assert_eq!(index_text(), committed_text);
+        assert!(staged_hunks(cx).is_empty());
+    }
+}
@@ -0,0 +1,340 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use gpui::{AppContext, Task, ViewContext};
+use language::{point_from_lsp, point_to_lsp, Bias, BufferSnapshot, Point, PointUtf16};
+use lsp::{InlineValue, InlineValueProvider, TextDocumentIdentifier, Url};
+use multi_buffer::{Anchor, ExcerptId};
+use project::File;
+use settings::Settings;
+use util::{post_inc, ResultExt};
+
+use crate::{display_map::Inlay, Editor, EditorSettings, InlayId};
+
+/// A stack frame of a debug session that stopped at a breakpoint, in which the values shown
+/// inline in the editor are evaluated.
+pub trait StoppedFrame: 'static {
+    /// The debugger's id for this frame.
+    fn id(&self) -> i32;
+
+    /// Evaluates a variable name or expression in this frame, returning its value formatted
+    /// for display.
+    fn evaluate(&self, expression: &str, cx: &mut AppContext) -> Task<Result<String>>;
+}
+
+pub(super) struct InlineValues {
+    enabled: bool,
+    stopped: Option<(Rc<dyn StoppedFrame>, Anchor)>,
+    inlay_ids: Vec<InlayId>,
+    refresh_task: Option<Task<()>>,
+}
+
+impl InlineValues {
+    pub(super) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            stopped: None,
+            inlay_ids: Vec::new(),
+            refresh_task: None,
+        }
+    }
+}
+
+impl Editor {
+    /// Shows the values of the variables around `position`, as reported by the buffer's
+    /// language server and evaluated in `frame`. Debugger integrations call this each time
+    /// the session stops at a breakpoint in this editor, and with `None` once it continues.
+    pub fn set_stopped_frame(
+        &mut self,
+        stopped: Option<(Rc<dyn StoppedFrame>, Anchor)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.inline_values.stopped = stopped;
+        self.refresh_inline_values(cx);
+    }
+
+    pub(super) fn inline_values_settings_changed(&mut self, cx: &mut ViewContext<Self>) {
+        let enabled = EditorSettings::get_global(cx).show_inline_values;
+        if self.inline_values.enabled != enabled {
+            self.inline_values.enabled = enabled;
+            self.refresh_inline_values(cx);
+        }
+    }
+
+    fn refresh_inline_values(&mut self, cx: &mut ViewContext<Self>) {
+        self.inline_values.refresh_task = None;
+        let stopped = self
+            .inline_values
+            .stopped
+            .clone()
+            .filter(|_| self.inline_values.enabled);
+        let Some((frame, position)) = stopped else {
+            self.clear_inline_values(cx);
+            return;
+        };
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some((buffer, anchor)) = self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let Some(uri) = File::from_dyn(buffer.file())
+            .and_then(|file| Url::from_file_path(file.abs_path(cx)).ok())
+        else {
+            return;
+        };
+        let Some(provider) = project
+            .read(cx)
+            .language_servers_for_buffer(buffer, cx)
+            .find_map(|(_, server)| InlineValueProvider::new(server.clone()))
+        else {
+            return;
+        };
+
+        // Servers narrow the values down to the scope of the stopped location, so the range
+        // only has to exclude the code that hasn't run yet.
+        let snapshot = buffer.snapshot();
+        let stopped_at = snapshot.summary_for_anchor::<PointUtf16>(&anchor);
+        let line_end =
+            snapshot.clip_point_utf16(PointUtf16::new(stopped_at.row, u32::MAX).into(), Bias::Left);
+        let request = provider.inline_values(
+            TextDocumentIdentifier::new(uri),
+            lsp::Range::new(point_to_lsp(PointUtf16::zero()), point_to_lsp(line_end)),
+            frame.id(),
+            lsp::Range::new(point_to_lsp(stopped_at), point_to_lsp(stopped_at)),
+        );
+
+        let excerpt_id = position.excerpt_id;
+        self.inline_values.refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+            let Some(values) = request.await.log_err() else {
+                return;
+            };
+            let Ok(labels) = editor.update(&mut cx, |_, cx| {
+                values
+                    .into_iter()
+                    .map(|value| inline_value_label(value, &snapshot, frame.as_ref(), cx))
+                    .collect::<Vec<_>>()
+            }) else {
+                return;
+            };
+            let mut values = Vec::with_capacity(labels.len());
+            for (row, label) in labels {
+                if let Some(label) = label.await {
+                    values.push((row, label));
+                }
+            }
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.show_inline_values(excerpt_id, &snapshot, values, cx)
+                })
+                .ok();
+        }));
+    }
+
+    /// Replaces the inline values shown with the given labels, placed at the end of their rows
+    /// in the buffer of an excerpt.
+    fn show_inline_values(
+        &mut self,
+        excerpt_id: ExcerptId,
+        buffer: &BufferSnapshot,
+        mut values: Vec<(u32, String)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        values.sort_by_key(|(row, _)| *row);
+        let multi_buffer = self.buffer.read(cx).snapshot(cx);
+        let to_insert = values
+            .into_iter()
+            .filter_map(|(row, label)| {
+                let row = row.min(buffer.max_point().row);
+                let line_end = buffer.anchor_after(Point::new(row, buffer.line_len(row)));
+                let position = multi_buffer.anchor_in_excerpt(excerpt_id, line_end)?;
+                Some(Inlay::inline_value(
+                    post_inc(&mut self.next_inlay_id),
+                    position,
+                    format!(" {label}"),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let to_remove = std::mem::replace(
+            &mut self.inline_values.inlay_ids,
+            to_insert.iter().map(|inlay| inlay.id).collect(),
+        );
+        self.splice_inlays(to_remove, to_insert, cx);
+    }
+
+    fn clear_inline_values(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.inline_values.inlay_ids.is_empty() {
+            let to_remove = std::mem::take(&mut self.inline_values.inlay_ids);
+            self.splice_inlays(to_remove, Vec::new(), cx);
+        }
+    }
+}
+
+/// Returns the row an inline value is shown on, and the text to show there once the value has
+/// been looked up in the stopped frame.
+fn inline_value_label(
+    value: InlineValue,
+    buffer: &BufferSnapshot,
+    frame: &dyn StoppedFrame,
+    cx: &mut AppContext,
+) -> (u32, Task<Option<String>>) {
+    let range = lsp::inline_value_range(&value);
+    let row = range.start.line;
+    let expression = match value {
+        InlineValue::Text(text) => return (row, Task::ready(Some(text.text))),
+        InlineValue::VariableLookup(lookup) => lookup.variable_name,
+        InlineValue::EvaluatableExpression(expression) => expression.expression,
+    }
+    .unwrap_or_else(|| {
+        let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+        let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+        buffer.text_for_range(start..end).collect()
+    });
+    let evaluation = frame.evaluate(&expression, cx);
+    let label = cx.background_executor().spawn(async move {
+        let value = evaluation.await.log_err()?;
+        Some(format!("{expression} = {value}"))
+    });
+    (row, label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use futures::StreamExt as _;
+    use gpui::{TestAppContext, UpdateGlobal};
+    use indoc::indoc;
+    use lsp::{InlineValueText, InlineValueVariableLookup};
+    use multi_buffer::ToPoint as _;
+    use settings::SettingsStore;
+
+    struct FakeFrame;
+
+    impl StoppedFrame for FakeFrame {
+        fn id(&self) -> i32 {
+            7
+        }
+
+        fn evaluate(&self, expression: &str, _: &mut AppContext) -> Task<Result<String>> {
+            Task::ready(match expression {
+                "count" => Ok("3".into()),
+                "total" => Ok("12".into()),
+                _ => Err(anyhow::anyhow!("unknown variable {expression}")),
+            })
+        }
+    }
+
+    fn inline_value_labels(cx: &mut EditorLspTestContext) -> Vec<(u32, String)> {
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.buffer.read(cx).snapshot(cx);
+            editor
+                .display_map
+                .read(cx)
+                .current_inlays()
+                .filter(|inlay| matches!(inlay.id, InlayId::InlineValue(_)))
+                .map(|inlay| {
+                    (
+                        inlay.position.to_point(&snapshot).row,
+                        inlay.text.to_string(),
+                    )
+                })
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_inline_values(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                inline_value_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.set_state(indoc! {"
+            fn main() {
+                let count = 3;
+                let total = count * 4;
+                ˇprintln!(\"{total}\");
+            }
+        "});
+
+        let mut requests = cx.handle_request::<lsp::request::InlineValueRequest, _, _>(
+            |_, params, _| async move {
+                assert_eq!(params.context.frame_id, 7);
+                assert_eq!(
+                    params.context.stopped_location.start,
+                    lsp::Position::new(3, 4)
+                );
+                assert_eq!(params.range.end, lsp::Position::new(3, 24));
+                Ok(Some(vec![
+                    InlineValue::VariableLookup(InlineValueVariableLookup {
+                        range: lsp::Range::new(lsp::Position::new(1, 8), lsp::Position::new(1, 13)),
+                        variable_name: None,
+                        case_sensitive_lookup: true,
+                    }),
+                    InlineValue::VariableLookup(InlineValueVariableLookup {
+                        range: lsp::Range::new(lsp::Position::new(2, 8), lsp::Position::new(2, 13)),
+                        variable_name: Some("total".into()),
+                        case_sensitive_lookup: true,
+                    }),
+                    InlineValue::VariableLookup(InlineValueVariableLookup {
+                        range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+                        variable_name: None,
+                        case_sensitive_lookup: true,
+                    }),
+                    InlineValue::Text(InlineValueText {
+                        range: lsp::Range::new(lsp::Position::new(3, 4), lsp::Position::new(3, 12)),
+                        text: "stopped here".into(),
+                    }),
+                ]))
+            },
+        );
+
+        let stopped_at = cx.update_editor(|editor, _| editor.selections.newest_anchor().head());
+        cx.update_editor(|editor, cx| {
+            editor.set_stopped_frame(Some((Rc::new(FakeFrame), stopped_at)), cx)
+        });
+        requests.next().await;
+        cx.run_until_parked();
+        // Values that can't be evaluated in the frame are left out.
+        assert_eq!(
+            inline_value_labels(&mut cx),
+            [
+                (1, " count = 3".to_string()),
+                (2, " total = 12".to_string()),
+                (3, " stopped here".to_string()),
+            ]
+        );
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.show_inline_values = Some(false);
+                });
+            });
+        });
+        cx.run_until_parked();
+        assert!(inline_value_labels(&mut cx).is_empty());
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.show_inline_values = Some(true);
+                });
+            });
+        });
+        requests.next().await;
+        cx.run_until_parked();
+        assert_eq!(inline_value_labels(&mut cx).len(), 3);
+
+        // Once the session continues, the values are removed.
+        cx.update_editor(|editor, cx| editor.set_stopped_frame(None, cx));
+        assert!(inline_value_labels(&mut cx).is_empty());
+    }
+}
@@ -11,7 +11,9 @@ use crate::{
     InlayHintRefreshReason, MultiBufferSnapshot, RowExt, ToPoint,
 };
 pub use autoscroll::{Autoscroll, AutoscrollStrategy};
-use gpui::{point, px, AppContext, Entity, Global, Pixels, Task, ViewContext, WindowContext};
+use gpui::{
+    ease_in_out, point, px, AppContext, Entity, Global, Pixels, Task, ViewContext, WindowContext,
+};
 use language::{Bias, Point};
 pub use scroll_amount::ScrollAmount;
 use settings::Settings;
@@ -24,6 +26,9 @@ use workspace::{ItemId, WorkspaceId};
 
 pub const SCROLL_EVENT_SEPARATION: Duration = Duration::from_millis(28);
 const SCROLLBAR_SHOW_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a following editor takes to scroll to its leader's scroll position.
+pub const REMOTE_SCROLL_DURATION: Duration = Duration::from_millis(160);
+const REMOTE_SCROLL_FRAME_INTERVAL: Duration = Duration::from_millis(16);
 
 #[derive(Default)]
 pub struct ScrollbarAutoHide(pub bool);
@@ -136,6 +141,7 @@ pub struct ScrollManager {
     last_autoscroll: Option<(gpui::Point<f32>, f32, f32, AutoscrollStrategy)>,
     show_scrollbars: bool,
     hide_scrollbar_task: Option<Task<()>>,
+    remote_scroll_task: Option<Task<()>>,
     dragging_scrollbar: bool,
     visible_line_count: Option<f32>,
     forbid_vertical_scroll: bool,
@@ -150,6 +156,7 @@ impl ScrollManager {
             autoscroll_request: None,
             show_scrollbars: true,
             hide_scrollbar_task: None,
+            remote_scroll_task: None,
             dragging_scrollbar: false,
             last_autoscroll: None,
             visible_line_count: None,
@@ -256,6 +263,9 @@ impl ScrollManager {
         if self.forbid_vertical_scroll {
             return;
         }
+        if local {
+            self.remote_scroll_task.take();
+        }
         self.anchor = anchor;
         cx.emit(EditorEvent::ScrollPositionChanged { local, autoscroll });
         self.show_scrollbar(cx);
@@ -454,13 +464,51 @@ impl Editor {
         cx: &mut ViewContext<Self>,
     ) {
         hide_hover(self, cx);
-        let workspace_id = self.workspace.as_ref().and_then(|workspace| workspace.1);
         let snapshot = &self.buffer().read(cx).snapshot(cx);
         if !scroll_anchor.anchor.is_valid(snapshot) {
             log::warn!("Invalid scroll anchor: {:?}", scroll_anchor);
             return;
         }
-        let top_row = scroll_anchor.anchor.to_point(snapshot).row;
+
+        // Glide towards the leader's scroll position rather than jumping to it, and land
+        // exactly on its anchor.
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let start = self.scroll_manager.anchor.scroll_position(&display_map);
+        let end = scroll_anchor.scroll_position(&display_map);
+        if start == end {
+            self.scroll_manager.remote_scroll_task.take();
+            self.finish_remote_scroll(scroll_anchor, cx);
+            return;
+        }
+
+        let frame_count =
+            (REMOTE_SCROLL_DURATION.as_millis() / REMOTE_SCROLL_FRAME_INTERVAL.as_millis()) as u32;
+        self.scroll_manager.remote_scroll_task = Some(cx.spawn(|editor, mut cx| async move {
+            for frame in 1..=frame_count {
+                cx.background_executor()
+                    .timer(REMOTE_SCROLL_FRAME_INTERVAL)
+                    .await;
+                editor
+                    .update(&mut cx, |editor, cx| {
+                        if frame == frame_count {
+                            editor.finish_remote_scroll(scroll_anchor, cx);
+                        } else {
+                            let progress = ease_in_out(frame as f32 / frame_count as f32);
+                            let position = start + (end - start) * progress;
+                            editor.set_scroll_position_internal(position, false, false, cx);
+                        }
+                    })
+                    .ok();
+            }
+        }));
+    }
+
+    fn finish_remote_scroll(&mut self, scroll_anchor: ScrollAnchor, cx: &mut ViewContext<Self>) {
+        let workspace_id = self.workspace.as_ref().and_then(|workspace| workspace.1);
+        let top_row = scroll_anchor
+            .anchor
+            .to_point(&self.buffer().read(cx).snapshot(cx))
+            .row;
         self.scroll_manager
             .set_anchor(scroll_anchor, top_row, false, false, workspace_id, cx);
     }
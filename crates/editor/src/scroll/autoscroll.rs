@@ -292,6 +292,7 @@ impl Editor {
         cx: &mut ViewContext<Self>,
     ) {
         self.scroll_manager.autoscroll_request = Some((autoscroll, false));
+        self.scroll_manager.remote_scroll_task.take();
         cx.notify();
     }
 }
@@ -1,9 +1,16 @@
+use std::rc::Rc;
+
 use gpui::{actions, AppContext, ClipboardItem, PromptLevel};
 use system_specs::SystemSpecs;
 use util::ResultExt;
 use workspace::Workspace;
 
+mod feedback_button;
 pub mod feedback_modal;
+mod feedback_submitter;
+
+pub use feedback_button::FeedbackButton;
+pub use feedback_submitter::{ClientFeedbackSubmitter, Feedback, FeedbackSubmitter};
 
 actions!(feedback, [GiveFeedback, SubmitFeedback]);
 
@@ -34,7 +41,9 @@ fn file_bug_report_url(specs: &SystemSpecs) -> String {
     )
 }
 
-pub fn init(cx: &mut AppContext) {
+pub fn init(submitter: impl FeedbackSubmitter, cx: &mut AppContext) {
+    feedback_submitter::set_global(Rc::new(submitter), cx);
+
     cx.observe_new_views(|workspace: &mut Workspace, cx| {
         feedback_modal::FeedbackModal::register(workspace, cx);
         workspace
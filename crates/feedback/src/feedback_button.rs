@@ -0,0 +1,25 @@
+use gpui::{AppContext, Render, ViewContext};
+use ui::{prelude::*, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView};
+
+use crate::GiveFeedback;
+
+/// A status bar button that opens the feedback modal.
+pub struct FeedbackButton;
+
+impl Render for FeedbackButton {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        IconButton::new("give-feedback", IconName::Envelope)
+            .icon_size(IconSize::Small)
+            .tooltip(|cx| Tooltip::for_action("Give Feedback", &GiveFeedback, cx))
+            .on_click(|_, cx| cx.dispatch_action(Box::new(GiveFeedback)))
+    }
+}
+
+impl StatusItemView for FeedbackButton {
+    fn set_active_pane_item(&mut self, _: Option<&dyn ItemHandle>, _: &mut ViewContext<Self>) {}
+
+    fn status_item_id(&self, _: &AppContext) -> Option<&'static str> {
+        Some("feedback")
+    }
+}
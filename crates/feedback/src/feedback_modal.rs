@@ -1,38 +1,22 @@
-use std::{ops::RangeInclusive, sync::Arc, time::Duration};
+use std::ops::RangeInclusive;
 
-use anyhow::{anyhow, bail};
+use anyhow::anyhow;
 use bitflags::bitflags;
-use client::Client;
 use db::kvp::KEY_VALUE_STORE;
 use editor::{Editor, EditorEvent};
-use futures::AsyncReadExt;
 use gpui::{
-    div, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
-    PromptLevel, Render, Task, View, ViewContext,
+    div, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Global, Model,
+    Render, Task, View, ViewContext,
 };
-use http::HttpClient;
-use isahc::Request;
 use language::Buffer;
 use project::Project;
 use regex::Regex;
-use serde_derive::Serialize;
 use ui::{prelude::*, Button, ButtonStyle, IconPosition, Tooltip};
 use util::ResultExt;
 use workspace::notifications::NotificationId;
 use workspace::{DismissDecision, ModalView, Toast, Workspace};
 
-use crate::{system_specs::SystemSpecs, GiveFeedback, OpenZedRepo};
-
-// For UI testing purposes
-const SEND_SUCCESS_IN_DEV_MODE: bool = true;
-const SEND_TIME_IN_DEV_MODE: Duration = Duration::from_secs(2);
-
-// Temporary, until tests are in place
-#[cfg(debug_assertions)]
-const DEV_MODE: bool = true;
-
-#[cfg(not(debug_assertions))]
-const DEV_MODE: bool = false;
+use crate::{feedback_submitter, Feedback, GiveFeedback, OpenZedRepo};
 
 const DATABASE_KEY_NAME: &str = "email_address";
 const EMAIL_REGEX: &str = r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b";
@@ -40,15 +24,11 @@ const FEEDBACK_CHAR_LIMIT: RangeInclusive<i32> = 10..=5000;
 const FEEDBACK_SUBMISSION_ERROR_TEXT: &str =
     "Feedback failed to submit, see error log for details.";
 
-#[derive(Serialize)]
-struct FeedbackRequestBody<'a> {
-    feedback_text: &'a str,
-    email: Option<String>,
-    metrics_id: Option<Arc<str>>,
-    installation_id: Option<Arc<str>>,
-    system_specs: SystemSpecs,
-    is_staff: bool,
-}
+/// The feedback that was being written when the modal was last closed, which
+/// is restored the next time it's opened.
+struct FeedbackDraft(String);
+
+impl Global for FeedbackDraft {}
 
 bitflags! {
     #[derive(Debug, Clone, PartialEq)]
@@ -68,14 +48,14 @@ enum CannotSubmitReason {
 enum SubmissionState {
     CanSubmit,
     CannotSubmit { reason: CannotSubmitReason },
+    Submitted,
 }
 
 pub struct FeedbackModal {
-    system_specs: SystemSpecs,
     feedback_editor: View<Editor>,
     email_address_editor: View<Editor>,
     submission_state: Option<SubmissionState>,
-    dismiss_modal: bool,
+    submission_error: Option<SharedString>,
     character_count: i32,
 }
 
@@ -90,29 +70,16 @@ impl ModalView for FeedbackModal {
     fn on_before_dismiss(&mut self, cx: &mut ViewContext<Self>) -> DismissDecision {
         self.update_email_in_store(cx);
 
-        if self.dismiss_modal {
-            return DismissDecision::Dismiss(true);
-        }
-
-        let has_feedback = self.feedback_editor.read(cx).text_option(cx).is_some();
-        if !has_feedback {
-            return DismissDecision::Dismiss(true);
-        }
-
-        let answer = cx.prompt(PromptLevel::Info, "Discard feedback?", None, &["Yes", "No"]);
-
-        cx.spawn(move |this, mut cx| async move {
-            if answer.await.ok() == Some(0) {
-                this.update(&mut cx, |this, cx| {
-                    this.dismiss_modal = true;
-                    cx.emit(DismissEvent)
-                })
-                .log_err();
-            }
-        })
-        .detach();
+        // Keep unsent feedback around so that it isn't lost when the modal is
+        // closed by accident.
+        let draft = if self.is_submitted() {
+            String::new()
+        } else {
+            self.feedback_editor.read(cx).text(cx)
+        };
+        cx.set_global(FeedbackDraft(draft));
 
-        DismissDecision::Pending
+        DismissDecision::Dismiss(true)
     }
 }
 
@@ -141,18 +108,14 @@ impl FeedbackModal {
                 return;
             }
 
-            let system_specs = SystemSpecs::new(cx);
             cx.spawn(|workspace, mut cx| async move {
                 let markdown = markdown.await.log_err();
                 let buffer = project.update(&mut cx, |project, cx| {
                     project.create_local_buffer("", markdown, cx)
                 })?;
-                let system_specs = system_specs.await;
 
                 workspace.update(&mut cx, |workspace, cx| {
-                    workspace.toggle_modal(cx, move |cx| {
-                        FeedbackModal::new(system_specs, project, buffer, cx)
-                    });
+                    workspace.toggle_modal(cx, move |cx| FeedbackModal::new(project, buffer, cx));
                 })?;
 
                 anyhow::Ok(())
@@ -161,12 +124,7 @@ impl FeedbackModal {
         });
     }
 
-    pub fn new(
-        system_specs: SystemSpecs,
-        project: Model<Project>,
-        buffer: Model<Buffer>,
-        cx: &mut ViewContext<Self>,
-    ) -> Self {
+    pub fn new(project: Model<Project>, buffer: Model<Buffer>, cx: &mut ViewContext<Self>) -> Self {
         let email_address_editor = cx.new_view(|cx| {
             let mut editor = Editor::single_line(cx);
             editor.set_placeholder_text("Email address (optional)", cx);
@@ -189,6 +147,10 @@ impl FeedbackModal {
             editor.set_show_inline_completions(false);
             editor.set_vertical_scroll_margin(5, cx);
             editor.set_use_modal_editing(false);
+            if let Some(FeedbackDraft(draft)) = cx.try_global::<FeedbackDraft>() {
+                let draft = draft.clone();
+                editor.set_text(draft, cx);
+            }
             editor
         });
 
@@ -207,124 +169,58 @@ impl FeedbackModal {
         })
         .detach();
 
+        let character_count = feedback_editor.read(cx).text(cx).len() as i32;
+
         Self {
-            system_specs: system_specs.clone(),
             feedback_editor,
             email_address_editor,
             submission_state: None,
-            dismiss_modal: false,
-            character_count: 0,
+            submission_error: None,
+            character_count,
         }
     }
 
     pub fn submit(&mut self, cx: &mut ViewContext<Self>) -> Task<anyhow::Result<()>> {
-        let feedback_text = self.feedback_editor.read(cx).text(cx).trim().to_string();
-        let email = self.email_address_editor.read(cx).text_option(cx);
+        self.update_submission_state(cx);
+        if !self.can_submit() {
+            return Task::ready(Ok(()));
+        }
+
+        let feedback = Feedback {
+            text: self.feedback_editor.read(cx).text(cx).trim().to_string(),
+            email: self.email_address_editor.read(cx).text_option(cx),
+        };
+        let submission = match feedback_submitter::global(cx) {
+            Some(submitter) => submitter.submit(feedback, cx),
+            None => Task::ready(Err(anyhow!("no feedback submitter has been registered"))),
+        };
+
+        self.submission_state = Some(SubmissionState::CannotSubmit {
+            reason: CannotSubmitReason::AwaitingSubmission,
+        });
+        self.submission_error = None;
+        cx.notify();
 
-        let answer = cx.prompt(
-            PromptLevel::Info,
-            "Ready to submit your feedback?",
-            None,
-            &["Yes, Submit!", "No"],
-        );
-        let client = Client::global(cx).clone();
-        let specs = self.system_specs.clone();
         cx.spawn(|this, mut cx| async move {
-            let answer = answer.await.ok();
-            if answer == Some(0) {
-                this.update(&mut cx, |this, cx| {
-                    this.submission_state = Some(SubmissionState::CannotSubmit {
-                        reason: CannotSubmitReason::AwaitingSubmission,
-                    });
-                    cx.notify();
-                })
-                .log_err();
-
-                let res =
-                    FeedbackModal::submit_feedback(&feedback_text, email, client, specs).await;
-
-                match res {
-                    Ok(_) => {
-                        this.update(&mut cx, |this, cx| {
-                            this.dismiss_modal = true;
-                            cx.notify();
-                            cx.emit(DismissEvent)
-                        })
-                        .ok();
+            let result = submission.await;
+            this.update(&mut cx, |this, cx| {
+                match result {
+                    Ok(()) => {
+                        this.submission_state = Some(SubmissionState::Submitted);
                     }
                     Err(error) => {
                         log::error!("{}", error);
-                        this.update(&mut cx, |this, cx| {
-                            let prompt = cx.prompt(
-                                PromptLevel::Critical,
-                                FEEDBACK_SUBMISSION_ERROR_TEXT,
-                                None,
-                                &["OK"],
-                            );
-                            cx.spawn(|_, _cx| async move {
-                                prompt.await.ok();
-                            })
-                            .detach();
-
-                            this.submission_state = Some(SubmissionState::CanSubmit);
-                            cx.notify();
-                        })
-                        .log_err();
+                        this.submission_state = Some(SubmissionState::CanSubmit);
+                        this.submission_error = Some(FEEDBACK_SUBMISSION_ERROR_TEXT.into());
                     }
                 }
-            }
+                cx.notify();
+            })
         })
-        .detach();
-
-        Task::ready(Ok(()))
-    }
-
-    async fn submit_feedback(
-        feedback_text: &str,
-        email: Option<String>,
-        zed_client: Arc<Client>,
-        system_specs: SystemSpecs,
-    ) -> anyhow::Result<()> {
-        if DEV_MODE {
-            smol::Timer::after(SEND_TIME_IN_DEV_MODE).await;
-
-            if SEND_SUCCESS_IN_DEV_MODE {
-                return Ok(());
-            } else {
-                return Err(anyhow!("Error submitting feedback"));
-            }
-        }
-
-        let telemetry = zed_client.telemetry();
-        let metrics_id = telemetry.metrics_id();
-        let installation_id = telemetry.installation_id();
-        let is_staff = telemetry.is_staff();
-        let http_client = zed_client.http_client();
-        let feedback_endpoint = http_client.build_url("/api/feedback");
-        let request = FeedbackRequestBody {
-            feedback_text: &feedback_text,
-            email,
-            metrics_id,
-            installation_id,
-            system_specs,
-            is_staff: is_staff.unwrap_or(false),
-        };
-        let json_bytes = serde_json::to_vec(&request)?;
-        let request = Request::post(feedback_endpoint)
-            .header("content-type", "application/json")
-            .body(json_bytes.into())?;
-        let mut response = http_client.send(request).await?;
-        let mut body = String::new();
-        response.body_mut().read_to_string(&mut body).await?;
-        let response_status = response.status();
-        if !response_status.is_success() {
-            bail!("Feedback API failed with error: {}", response_status)
-        }
-        Ok(())
     }
 
     fn update_submission_state(&mut self, cx: &mut ViewContext<Self>) {
-        if self.awaiting_submission() {
+        if self.awaiting_submission() || self.is_submitted() {
             return;
         }
 
@@ -406,6 +302,10 @@ impl FeedbackModal {
         matches!(self.submission_state, Some(SubmissionState::CanSubmit))
     }
 
+    fn is_submitted(&self) -> bool {
+        matches!(self.submission_state, Some(SubmissionState::Submitted))
+    }
+
     fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
         cx.emit(DismissEvent)
     }
@@ -415,8 +315,14 @@ impl Render for FeedbackModal {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         self.update_submission_state(cx);
 
+        if self.is_submitted() {
+            return self.render_submitted(cx).into_any_element();
+        }
+
         let submit_button_text = if self.awaiting_submission() {
             "Submitting..."
+        } else if self.submission_error.is_some() {
+            "Retry"
         } else {
             "Submit"
         };
@@ -451,6 +357,11 @@ impl Render for FeedbackModal {
                     Color::Error
                 }),
             )
+            .children(
+                self.submission_error
+                    .clone()
+                    .map(|error| Label::new(error).size(LabelSize::Small).color(Color::Error)),
+            )
             .child(
                 div()
                     .flex_1()
@@ -524,7 +435,154 @@ impl Render for FeedbackModal {
                             ),
                     ),
             )
+            .into_any_element()
+    }
+}
+
+impl FeedbackModal {
+    fn render_submitted(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_3(cx)
+            .key_context("GiveFeedback")
+            .on_action(cx.listener(Self::cancel))
+            .min_w(rems(40.))
+            .p_4()
+            .gap_2()
+            .child(Headline::new("Feedback Sent"))
+            .child(Label::new(
+                "Thanks for taking the time to share your feedback!",
+            ))
+            .child(
+                h_flex().justify_end().child(
+                    Button::new("close_feedback", "Close")
+                        .style(ButtonStyle::Filled)
+                        .on_click(cx.listener(|_, _, cx| cx.emit(DismissEvent))),
+                ),
+            )
     }
 }
 
-// TODO: Testing of various button states, dismissal prompts, etc. :)
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+        sync::Arc,
+    };
+
+    use gpui::{TestAppContext, VisualTestContext};
+    use workspace::AppState;
+
+    use super::*;
+    use crate::FeedbackSubmitter;
+
+    #[derive(Clone, Default)]
+    struct RecordingSubmitter {
+        submissions: Rc<RefCell<Vec<Feedback>>>,
+        fail: Rc<Cell<bool>>,
+    }
+
+    impl FeedbackSubmitter for RecordingSubmitter {
+        fn submit(&self, feedback: Feedback, _: &mut AppContext) -> Task<anyhow::Result<()>> {
+            self.submissions.borrow_mut().push(feedback);
+            if self.fail.get() {
+                Task::ready(Err(anyhow!("network error")))
+            } else {
+                Task::ready(Ok(()))
+            }
+        }
+    }
+
+    #[gpui::test]
+    async fn test_submitting_feedback(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let submitter = RecordingSubmitter::default();
+        cx.update(|cx| feedback_submitter::set_global(Rc::new(submitter.clone()), cx));
+        let (modal, cx) = build_modal(&app_state, cx).await;
+
+        set_feedback_text(&modal, "Too short", cx);
+        modal.update(cx, |modal, cx| modal.submit(cx).detach());
+        cx.run_until_parked();
+        assert!(submitter.submissions.borrow().is_empty());
+
+        submitter.fail.set(true);
+        set_feedback_text(&modal, "Split panes are great", cx);
+        modal.update(cx, |modal, cx| modal.submit(cx).detach());
+        cx.run_until_parked();
+        modal.update(cx, |modal, _| {
+            assert!(modal.submission_error.is_some());
+            assert!(modal.can_submit());
+        });
+
+        submitter.fail.set(false);
+        modal.update(cx, |modal, cx| modal.submit(cx).detach());
+        cx.run_until_parked();
+        modal.update(cx, |modal, _| {
+            assert!(modal.submission_error.is_none());
+            assert!(modal.is_submitted());
+        });
+
+        let feedback = Feedback {
+            text: "Split panes are great".into(),
+            email: None,
+        };
+        assert_eq!(
+            *submitter.submissions.borrow(),
+            vec![feedback.clone(), feedback]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_draft_is_restored_after_dismissal(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let (modal, cx) = build_modal(&app_state, cx).await;
+
+        set_feedback_text(&modal, "Unfinished thoughts", cx);
+        modal.update(cx, |modal, cx| {
+            assert!(matches!(
+                modal.on_before_dismiss(cx),
+                DismissDecision::Dismiss(true)
+            ))
+        });
+
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let buffer = project.update(cx, |project, cx| project.create_local_buffer("", None, cx));
+        let modal = cx.new_view(|cx| FeedbackModal::new(project, buffer, cx));
+        modal.update(cx, |modal, cx| {
+            assert_eq!(
+                modal.feedback_editor.read(cx).text(cx),
+                "Unfinished thoughts"
+            );
+            assert_eq!(modal.character_count, 19);
+        });
+    }
+
+    async fn build_modal<'a>(
+        app_state: &Arc<AppState>,
+        cx: &'a mut TestAppContext,
+    ) -> (View<FeedbackModal>, &'a mut VisualTestContext) {
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let buffer = project.update(cx, |project, cx| project.create_local_buffer("", None, cx));
+        cx.add_window_view(|cx| FeedbackModal::new(project, buffer, cx))
+    }
+
+    fn set_feedback_text(modal: &View<FeedbackModal>, text: &str, cx: &mut VisualTestContext) {
+        modal.update(cx, |modal, cx| {
+            modal
+                .feedback_editor
+                .update(cx, |editor, cx| editor.set_text(text, cx))
+        });
+        cx.run_until_parked();
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
@@ -0,0 +1,119 @@
+use std::{rc::Rc, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, bail, Result};
+use client::Client;
+use futures::AsyncReadExt;
+use gpui::{AppContext, Global, Task};
+use http::HttpClient;
+use isahc::Request;
+use serde_derive::Serialize;
+
+use crate::system_specs::SystemSpecs;
+
+// For UI testing purposes
+const SEND_SUCCESS_IN_DEV_MODE: bool = true;
+const SEND_TIME_IN_DEV_MODE: Duration = Duration::from_secs(2);
+
+// Temporary, until tests are in place
+#[cfg(debug_assertions)]
+const DEV_MODE: bool = true;
+
+#[cfg(not(debug_assertions))]
+const DEV_MODE: bool = false;
+
+/// Feedback written by the user in the feedback modal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Feedback {
+    pub text: String,
+    pub email: Option<String>,
+}
+
+/// Sends feedback to wherever it is collected.
+///
+/// The submitter is provided by the application when initializing this crate,
+/// which keeps the feedback modal independent of the transport and allows
+/// tests to record submissions instead of sending them.
+pub trait FeedbackSubmitter: 'static {
+    fn submit(&self, feedback: Feedback, cx: &mut AppContext) -> Task<Result<()>>;
+}
+
+struct GlobalFeedbackSubmitter(Rc<dyn FeedbackSubmitter>);
+
+impl Global for GlobalFeedbackSubmitter {}
+
+pub(crate) fn set_global(submitter: Rc<dyn FeedbackSubmitter>, cx: &mut AppContext) {
+    cx.set_global(GlobalFeedbackSubmitter(submitter));
+}
+
+pub(crate) fn global(cx: &AppContext) -> Option<Rc<dyn FeedbackSubmitter>> {
+    cx.try_global::<GlobalFeedbackSubmitter>()
+        .map(|submitter| submitter.0.clone())
+}
+
+#[derive(Serialize)]
+struct FeedbackRequestBody<'a> {
+    feedback_text: &'a str,
+    email: Option<String>,
+    metrics_id: Option<Arc<str>>,
+    installation_id: Option<Arc<str>>,
+    system_specs: SystemSpecs,
+    is_staff: bool,
+}
+
+/// Submits feedback to the Zed team's feedback API, along with the specs of
+/// the user's system.
+pub struct ClientFeedbackSubmitter;
+
+impl FeedbackSubmitter for ClientFeedbackSubmitter {
+    fn submit(&self, feedback: Feedback, cx: &mut AppContext) -> Task<Result<()>> {
+        let client = Client::global(cx).clone();
+        let system_specs = SystemSpecs::new(cx);
+        cx.background_executor().spawn(async move {
+            let system_specs = system_specs.await;
+            submit_feedback(feedback, client, system_specs).await
+        })
+    }
+}
+
+async fn submit_feedback(
+    feedback: Feedback,
+    zed_client: Arc<Client>,
+    system_specs: SystemSpecs,
+) -> Result<()> {
+    if DEV_MODE {
+        smol::Timer::after(SEND_TIME_IN_DEV_MODE).await;
+
+        if SEND_SUCCESS_IN_DEV_MODE {
+            return Ok(());
+        } else {
+            return Err(anyhow!("Error submitting feedback"));
+        }
+    }
+
+    let telemetry = zed_client.telemetry();
+    let metrics_id = telemetry.metrics_id();
+    let installation_id = telemetry.installation_id();
+    let is_staff = telemetry.is_staff();
+    let http_client = zed_client.http_client();
+    let feedback_endpoint = http_client.build_url("/api/feedback");
+    let request = FeedbackRequestBody {
+        feedback_text: &feedback.text,
+        email: feedback.email,
+        metrics_id,
+        installation_id,
+        system_specs,
+        is_staff: is_staff.unwrap_or(false),
+    };
+    let json_bytes = serde_json::to_vec(&request)?;
+    let request = Request::post(feedback_endpoint)
+        .header("content-type", "application/json")
+        .body(json_bytes.into())?;
+    let mut response = http_client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+    let response_status = response.status();
+    if !response_status.is_success() {
+        bail!("Feedback API failed with error: {}", response_status)
+    }
+    Ok(())
+}
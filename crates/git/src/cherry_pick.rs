@@ -0,0 +1,166 @@
+use crate::command::{run_git, run_git_checked};
+use crate::repository::GitRepository;
+use crate::Oid;
+use anyhow::{anyhow, Result};
+use gpui::{BackgroundExecutor, Task};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The result of cherry-picking a commit onto the current branch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CherryPickOutcome {
+    /// The commit's changes were committed to the current branch.
+    Committed,
+    /// The commit's changes were applied and staged, but not committed.
+    Staged,
+    /// The commit's changes conflict with the current branch, and the cherry-pick waits for the
+    /// conflicts to be resolved and committed, or for it to be aborted.
+    Conflicted {
+        /// Absolute paths of the files with conflict markers.
+        paths: Vec<PathBuf>,
+    },
+}
+
+/// Cherry-picks commits onto the current branch, running the git commands on the background
+/// executor.
+#[derive(Clone)]
+pub struct CherryPickProvider {
+    repository: Arc<dyn GitRepository>,
+    executor: BackgroundExecutor,
+}
+
+impl CherryPickProvider {
+    pub fn new(repository: Arc<dyn GitRepository>, executor: BackgroundExecutor) -> Self {
+        Self {
+            repository,
+            executor,
+        }
+    }
+
+    /// Applies the changes introduced by a commit to the current branch, committing them unless
+    /// `no_commit` is true.
+    pub fn cherry_pick(&self, hash: Oid, no_commit: bool) -> Task<Result<CherryPickOutcome>> {
+        let repository = self.repository.clone();
+        self.executor
+            .spawn(async move { repository.cherry_pick(hash, no_commit) })
+    }
+
+    /// Whether a cherry-pick stopped on conflicts and hasn't been committed or aborted yet.
+    pub fn is_in_progress(&self) -> bool {
+        self.repository.cherry_pick_in_progress()
+    }
+
+    /// Returns the diff of the staged changes against HEAD.
+    pub fn staged_diff(&self) -> Task<Result<String>> {
+        let repository = self.repository.clone();
+        self.executor.spawn(async move { repository.staged_diff() })
+    }
+
+    /// Commits the changes staged by a cherry-pick, with the message and author of the
+    /// cherry-picked commit.
+    pub fn commit(&self, hash: Oid) -> Task<Result<()>> {
+        let repository = self.repository.clone();
+        self.executor
+            .spawn(async move { repository.commit_reusing_message(hash) })
+    }
+
+    /// Aborts the cherry-pick in progress, restoring the branch and working directory to their
+    /// state before it.
+    pub fn abort(&self) -> Task<Result<()>> {
+        let repository = self.repository.clone();
+        self.executor
+            .spawn(async move { repository.abort_cherry_pick() })
+    }
+}
+
+pub(crate) fn cherry_pick(
+    git_binary: &Path,
+    working_directory: &Path,
+    hash: Oid,
+    no_commit: bool,
+) -> Result<CherryPickOutcome> {
+    let output = run_git(git_binary, working_directory, |command| {
+        command.arg("cherry-pick");
+        if no_commit {
+            command.arg("--no-commit");
+        }
+        command.arg(hash.to_string());
+    })?;
+    if output.status.success() {
+        return Ok(if no_commit {
+            CherryPickOutcome::Staged
+        } else {
+            CherryPickOutcome::Committed
+        });
+    }
+
+    // Any other failure, like local changes that would be overwritten, leaves no unmerged paths.
+    let paths = conflicted_paths(git_binary, working_directory)?;
+    if paths.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git cherry-pick process failed: {}", stderr.trim()));
+    }
+    Ok(CherryPickOutcome::Conflicted {
+        paths: paths
+            .into_iter()
+            .map(|path| working_directory.join(path))
+            .collect(),
+    })
+}
+
+pub(crate) fn abort_cherry_pick(git_binary: &Path, working_directory: &Path) -> Result<()> {
+    run_git_checked(git_binary, working_directory, |command| {
+        command.args(["cherry-pick", "--abort"]);
+    })?;
+    Ok(())
+}
+
+pub(crate) fn staged_diff(git_binary: &Path, working_directory: &Path) -> Result<String> {
+    run_git_checked(git_binary, working_directory, |command| {
+        command.args(["diff", "--cached"]);
+    })
+}
+
+pub(crate) fn commit_reusing_message(
+    git_binary: &Path,
+    working_directory: &Path,
+    hash: Oid,
+) -> Result<()> {
+    run_git_checked(git_binary, working_directory, |command| {
+        command.arg("commit").arg(format!("--reuse-message={hash}"));
+    })?;
+    Ok(())
+}
+
+/// Returns the paths with unresolved conflicts, relative to the working directory.
+fn conflicted_paths(git_binary: &Path, working_directory: &Path) -> Result<Vec<PathBuf>> {
+    let output = run_git_checked(git_binary, working_directory, |command| {
+        command.args(["diff", "--name-only", "--diff-filter=U", "-z"]);
+    })?;
+    Ok(parse_paths(&output))
+}
+
+fn parse_paths(output: &str) -> Vec<PathBuf> {
+    output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_paths() {
+        assert_eq!(
+            parse_paths("src/main.rs\0src/lib name.rs\0"),
+            [
+                PathBuf::from("src/main.rs"),
+                PathBuf::from("src/lib name.rs")
+            ]
+        );
+        assert!(parse_paths("").is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+/// Builds a git command that runs in the given working directory with its output captured and
+/// without a console window. Paths in its output are left unquoted.
+pub(crate) fn git_command(git_binary: &Path, working_directory: &Path) -> Command {
+    let mut command = Command::new(git_binary);
+    command
+        .current_dir(working_directory)
+        .args(["-c", "core.quotePath=false"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+    }
+
+    command
+}
+
+/// Runs a git command with the arguments added by `add_args`, returning its output whether or
+/// not it succeeded.
+pub(crate) fn run_git(
+    git_binary: &Path,
+    working_directory: &Path,
+    add_args: impl FnOnce(&mut Command),
+) -> Result<Output> {
+    let mut command = git_command(git_binary, working_directory);
+    add_args(&mut command);
+    command
+        .output()
+        .map_err(|e| anyhow!("Failed to start git process: {}", e))
+}
+
+/// Runs a git command with the arguments added by `add_args`, returning its standard output if
+/// it succeeded.
+pub(crate) fn run_git_checked(
+    git_binary: &Path,
+    working_directory: &Path,
+    add_args: impl FnOnce(&mut Command),
+) -> Result<String> {
+    let output = run_git(git_binary, working_directory, add_args)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git process failed: {}", stderr.trim()));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
@@ -0,0 +1,196 @@
+use crate::command::run_git;
+use crate::repository::GitRepository;
+use crate::Oid;
+use anyhow::{anyhow, Context, Result};
+use gpui::{BackgroundExecutor, Task};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A commit that changed a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitSummary {
+    pub hash: Oid,
+    pub author: String,
+    /// Time the commit was authored, normalized to Unix Epoch format.
+    pub date: i64,
+    pub message_subject: String,
+    /// The file's path in this commit, relative to the repository's working directory, which
+    /// differs from its current path if it was renamed since.
+    pub path: PathBuf,
+}
+
+/// Loads the commits that changed a file, and the file's contents in them, running the git
+/// commands on the background executor.
+#[derive(Clone)]
+pub struct FileHistoryProvider {
+    repository: Arc<dyn GitRepository>,
+    executor: BackgroundExecutor,
+}
+
+impl FileHistoryProvider {
+    pub fn new(repository: Arc<dyn GitRepository>, executor: BackgroundExecutor) -> Self {
+        Self {
+            repository,
+            executor,
+        }
+    }
+
+    /// Returns up to `max_commits` of the commits that changed the file at the given path,
+    /// relative to the repository's working directory, most recent first. The history
+    /// continues past renames.
+    pub fn history(&self, path: &Path, max_commits: usize) -> Task<Result<Vec<CommitSummary>>> {
+        let repository = self.repository.clone();
+        let path = path.to_path_buf();
+        self.executor
+            .spawn(async move { repository.file_history(&path, max_commits) })
+    }
+
+    /// Returns the contents of the file changed by a commit, as of that commit.
+    pub fn file_at_commit(&self, commit: &CommitSummary) -> Task<Result<String>> {
+        let repository = self.repository.clone();
+        let commit = commit.clone();
+        self.executor.spawn(async move {
+            repository
+                .file_at_revision(&commit.path, &commit.hash.to_string())?
+                .with_context(|| format!("{:?} does not exist in {}", commit.path, commit.hash))
+        })
+    }
+
+    /// Returns the contents of the file changed by a commit, as of the commit's first parent,
+    /// or `None` if the commit added it.
+    pub fn file_before_commit(&self, commit: &CommitSummary) -> Task<Result<Option<String>>> {
+        let repository = self.repository.clone();
+        let commit = commit.clone();
+        self.executor.spawn(async move {
+            repository.file_at_revision(&commit.path, &format!("{}^", commit.hash))
+        })
+    }
+}
+
+/// Separates the commits in the output of `git log`.
+const LOG_RECORD_SEPARATOR: char = '\x1e';
+
+pub(crate) fn file_history(
+    git_binary: &Path,
+    working_directory: &Path,
+    path: &Path,
+    max_commits: usize,
+) -> Result<Vec<CommitSummary>> {
+    let output = run_git(git_binary, working_directory, |command| {
+        command
+            .args(["log", "--follow", "--name-only"])
+            .arg(format!("--max-count={max_commits}"))
+            .arg("--format=%x1e%H%x00%an%x00%at%x00%s")
+            .arg("--")
+            .arg(path);
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git log process failed: {}", stderr.trim()));
+    }
+    parse_file_history(&String::from_utf8(output.stdout)?, path)
+}
+
+/// Returns the contents of a file at the given revision, or `None` if it doesn't exist there.
+pub(crate) fn file_at_revision(
+    git_binary: &Path,
+    working_directory: &Path,
+    path: &Path,
+    revision: &str,
+) -> Result<Option<String>> {
+    // Paths in `<revision>:<path>` are always separated by forward slashes.
+    let path = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let object = format!("{revision}:{path}");
+    let output = run_git(git_binary, working_directory, |command| {
+        command.arg("show").arg(object);
+    })?;
+    if output.status.success() {
+        return Ok(Some(String::from_utf8(output.stdout)?));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error = stderr.to_lowercase();
+    let is_missing = [
+        "does not exist",
+        "exists on disk, but not in",
+        "invalid object name",
+    ]
+    .iter()
+    .any(|message| error.contains(message));
+    if is_missing {
+        Ok(None)
+    } else {
+        Err(anyhow!("git show process failed: {}", stderr.trim()))
+    }
+}
+
+fn parse_file_history(output: &str, path: &Path) -> Result<Vec<CommitSummary>> {
+    output
+        .split(LOG_RECORD_SEPARATOR)
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| {
+            let mut lines = record.lines();
+            let header = lines.next().unwrap_or_default();
+            let mut fields = header.splitn(4, '\0');
+            let (Some(hash), Some(author), Some(date), Some(message_subject)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(anyhow!("invalid git log entry {:?}", header));
+            };
+            Ok(CommitSummary {
+                hash: hash.parse()?,
+                author: author.to_string(),
+                date: date
+                    .parse()
+                    .with_context(|| format!("invalid commit timestamp {:?}", date))?,
+                message_subject: message_subject.to_string(),
+                path: lines
+                    .find(|line| !line.is_empty())
+                    .map_or_else(|| path.to_path_buf(), PathBuf::from),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file_history() {
+        let output = "\
+            \x1e8d5b0b1c6f3f6a8b2a1e0e3c9d7f1a2b3c4d5e6f\x00Jane Doe\x001718000000\x00Fix the parser\n\
+            \n\
+            src/parser.rs\n\
+            \x1e1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b\x00John Doe\x001717000000\x00Add a parser\n\
+            \n\
+            src/parse.rs\n";
+        assert_eq!(
+            parse_file_history(output, Path::new("src/parser.rs")).unwrap(),
+            [
+                CommitSummary {
+                    hash: "8d5b0b1c6f3f6a8b2a1e0e3c9d7f1a2b3c4d5e6f".parse().unwrap(),
+                    author: "Jane Doe".into(),
+                    date: 1718000000,
+                    message_subject: "Fix the parser".into(),
+                    path: "src/parser.rs".into(),
+                },
+                CommitSummary {
+                    hash: "1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b".parse().unwrap(),
+                    author: "John Doe".into(),
+                    date: 1717000000,
+                    message_subject: "Add a parser".into(),
+                    path: "src/parse.rs".into(),
+                },
+            ]
+        );
+        assert!(parse_file_history("", Path::new("a.rs"))
+            .unwrap()
+            .is_empty());
+        assert!(parse_file_history("\x1enot a hash\x00a\x000\x00b\n", Path::new("a.rs")).is_err());
+    }
+}
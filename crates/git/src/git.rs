@@ -12,9 +12,15 @@ pub use lazy_static::lazy_static;
 pub use crate::hosting_provider::*;
 
 pub mod blame;
+pub mod cherry_pick;
+mod command;
 pub mod commit;
 pub mod diff;
+pub mod file_history;
+pub mod merge_conflict;
 pub mod repository;
+pub mod staging;
+pub mod stash;
 pub mod status;
 
 lazy_static! {
@@ -0,0 +1,258 @@
+use std::ops::Range;
+use text::{Anchor, BufferSnapshot, Point, ToOffset};
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+const MARKER_LEN: usize = 7;
+
+/// A conflict left in a file by a merge, between the `<<<<<<<` and `>>>>>>>` markers.
+#[derive(Clone, Debug)]
+pub struct MergeConflictRegion {
+    /// The whole conflict, from the start of the `<<<<<<<` line to the end of the `>>>>>>>` line,
+    /// including its newline.
+    pub range: Range<Anchor>,
+    /// The lines of the current branch's side of the conflict.
+    pub ours: Range<Anchor>,
+    /// The lines of the merged branch's side of the conflict.
+    pub theirs: Range<Anchor>,
+    /// The start of the `=======` line.
+    pub separator: Anchor,
+}
+
+/// Which sides of a conflict to keep when resolving it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeConflictResolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+impl MergeConflictRegion {
+    /// Returns the edit that replaces the conflict and its markers with the chosen sides.
+    pub fn resolve(
+        &self,
+        resolution: MergeConflictResolution,
+        buffer: &BufferSnapshot,
+    ) -> (Range<usize>, String) {
+        let text_for = |range: &Range<Anchor>| buffer.text_for_range(range.clone());
+        let new_text = match resolution {
+            MergeConflictResolution::Ours => text_for(&self.ours).collect(),
+            MergeConflictResolution::Theirs => text_for(&self.theirs).collect(),
+            MergeConflictResolution::Both => {
+                text_for(&self.ours).chain(text_for(&self.theirs)).collect()
+            }
+        };
+        (
+            self.range.start.to_offset(buffer)..self.range.end.to_offset(buffer),
+            new_text,
+        )
+    }
+}
+
+/// Finds the merge conflicts in a buffer, rescanning it only when it changed.
+#[derive(Default)]
+pub struct MergeConflictDetector {
+    regions: Vec<MergeConflictRegion>,
+    scanned_version: Option<clock::Global>,
+}
+
+impl MergeConflictDetector {
+    pub fn regions(&self) -> &[MergeConflictRegion] {
+        &self.regions
+    }
+
+    /// Rescans the buffer if it changed since the last scan, returning whether it did.
+    pub fn refresh(&mut self, buffer: &BufferSnapshot) -> bool {
+        if self.scanned_version.as_ref() == Some(buffer.version()) {
+            return false;
+        }
+        self.scanned_version = Some(buffer.version().clone());
+        self.regions = merge_conflicts(buffer);
+        true
+    }
+
+    /// Returns the conflict containing the given position.
+    pub fn region_at(
+        &self,
+        offset: usize,
+        buffer: &BufferSnapshot,
+    ) -> Option<&MergeConflictRegion> {
+        self.regions.iter().find(|region| {
+            region.range.start.to_offset(buffer) <= offset
+                && offset < region.range.end.to_offset(buffer)
+        })
+    }
+}
+
+/// Scans a buffer for conflict markers, ignoring any that aren't part of a complete conflict.
+/// The common ancestor's lines in conflicts written with the `diff3` style are left out of both
+/// sides.
+pub fn merge_conflicts(buffer: &BufferSnapshot) -> Vec<MergeConflictRegion> {
+    struct PendingConflict {
+        start: usize,
+        ours_start: usize,
+        ours_end: Option<usize>,
+        separator: Option<usize>,
+        theirs_start: usize,
+    }
+
+    let mut regions = Vec::new();
+    let mut pending: Option<PendingConflict> = None;
+    // Only the start of each line is needed to tell whether it's a marker, so the lines are
+    // read one prefix at a time instead of copying the whole buffer.
+    let mut content = String::with_capacity(MARKER_LEN + 1);
+    let max_row = buffer.max_point().row;
+    let mut line_start = 0;
+    for row in 0..=max_row {
+        let line_end = if row < max_row {
+            buffer.point_to_offset(Point::new(row + 1, 0))
+        } else {
+            buffer.len()
+        };
+        content.clear();
+        content.extend(
+            buffer
+                .chars_at(line_start)
+                .take_while(|c| *c != '\n')
+                .take(MARKER_LEN + 1),
+        );
+        let content = content.as_str();
+        if is_marker(content, OURS_MARKER) {
+            // A conflict that is never closed is ignored, so start over at the next one.
+            pending = Some(PendingConflict {
+                start: line_start,
+                ours_start: line_end,
+                ours_end: None,
+                separator: None,
+                theirs_start: line_end,
+            });
+        } else if let Some(conflict) = pending.as_mut() {
+            if is_marker(content, BASE_MARKER) && conflict.separator.is_none() {
+                conflict.ours_end.get_or_insert(line_start);
+            } else if content == SEPARATOR_MARKER && conflict.separator.is_none() {
+                conflict.ours_end.get_or_insert(line_start);
+                conflict.separator = Some(line_start);
+                conflict.theirs_start = line_end;
+            } else if let Some(separator) = conflict.separator {
+                if is_marker(content, THEIRS_MARKER) {
+                    let ours_end = conflict.ours_end.unwrap_or(separator);
+                    regions.push(MergeConflictRegion {
+                        range: buffer.anchor_before(conflict.start)..buffer.anchor_after(line_end),
+                        ours: buffer.anchor_after(conflict.ours_start)
+                            ..buffer.anchor_before(ours_end),
+                        theirs: buffer.anchor_after(conflict.theirs_start)
+                            ..buffer.anchor_before(line_start),
+                        separator: buffer.anchor_before(separator),
+                    });
+                    pending = None;
+                }
+            }
+        }
+        line_start = line_end;
+    }
+    regions
+}
+
+fn is_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .map_or(false, |rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{Buffer, BufferId};
+    use unindent::Unindent as _;
+
+    fn buffer(text: &str) -> BufferSnapshot {
+        Buffer::new(0, BufferId::new(1).unwrap(), text.into()).snapshot()
+    }
+
+    fn sides(region: &MergeConflictRegion, buffer: &BufferSnapshot) -> (String, String, String) {
+        (
+            buffer.text_for_range(region.range.clone()).collect(),
+            buffer.text_for_range(region.ours.clone()).collect(),
+            buffer.text_for_range(region.theirs.clone()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let text = "
+            fn main() {
+            <<<<<<< HEAD
+                println!(\"ours\");
+            =======
+                println!(\"theirs\");
+                println!(\"theirs again\");
+            >>>>>>> feature
+            }
+            <<<<<<< HEAD
+            ours
+            ||||||| base
+            base
+            =======
+            >>>>>>> feature
+            <<<<<<< HEAD
+            unterminated
+            =======
+        "
+        .unindent();
+        let buffer = buffer(&text);
+        let regions = merge_conflicts(&buffer);
+        assert_eq!(regions.len(), 2);
+
+        let (range, ours, theirs) = sides(&regions[0], &buffer);
+        assert!(range.starts_with("<<<<<<< HEAD\n"));
+        assert!(range.ends_with(">>>>>>> feature\n"));
+        assert_eq!(ours, "    println!(\"ours\");\n");
+        assert_eq!(
+            theirs,
+            "    println!(\"theirs\");\n    println!(\"theirs again\");\n"
+        );
+        assert_eq!(
+            regions[0].separator.to_offset(&buffer),
+            text.find("=======").unwrap()
+        );
+
+        // The common ancestor's lines are left out, and either side can be empty.
+        let (_, ours, theirs) = sides(&regions[1], &buffer);
+        assert_eq!(ours, "ours\n");
+        assert_eq!(theirs, "");
+
+        assert_eq!(
+            regions[0].resolve(MergeConflictResolution::Both, &buffer).1,
+            "    println!(\"ours\");\n    println!(\"theirs\");\n    println!(\"theirs again\");\n"
+        );
+        assert_eq!(
+            regions[1].resolve(MergeConflictResolution::Theirs, &buffer),
+            (
+                text.find("<<<<<<< HEAD\nours").unwrap()..text.rfind("<<<<<<<").unwrap(),
+                String::new()
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_conflict_detector() {
+        let mut buffer = Buffer::new(
+            0,
+            BufferId::new(1).unwrap(),
+            "<<<<<<< HEAD\na\n=======\nb\n>>>>>>> other\n".into(),
+        );
+        let mut detector = MergeConflictDetector::default();
+        assert!(detector.refresh(&buffer.snapshot()));
+        assert_eq!(detector.regions().len(), 1);
+        assert!(!detector.refresh(&buffer.snapshot()));
+        assert!(detector.region_at(3, &buffer.snapshot()).is_some());
+
+        let (range, new_text) =
+            detector.regions()[0].resolve(MergeConflictResolution::Ours, &buffer.snapshot());
+        buffer.edit([(range, new_text)]);
+        assert_eq!(buffer.text(), "a\n");
+        assert!(detector.refresh(&buffer.snapshot()));
+        assert!(detector.regions().is_empty());
+    }
+}
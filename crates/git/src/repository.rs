@@ -1,5 +1,13 @@
 use crate::GitHostingProviderRegistry;
-use crate::{blame::Blame, status::GitStatus};
+use crate::{
+    blame::Blame,
+    cherry_pick::CherryPickOutcome,
+    file_history::CommitSummary,
+    staging::DiffHunk,
+    stash::{StashEntry, StashId},
+    status::GitStatus,
+    Oid,
+};
 use anyhow::{Context, Result};
 use collections::HashMap;
 use git2::BranchType;
@@ -29,6 +37,9 @@ pub trait GitRepository: Send + Sync {
     /// Note that for symlink entries, this will return the contents of the symlink, not the target.
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Loads a git repository entry's contents as of the HEAD commit.
+    fn load_committed_text(&self, relative_file_path: &Path) -> Option<String>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -47,6 +58,44 @@ pub trait GitRepository: Send + Sync {
     fn create_branch(&self, _: &str) -> Result<()>;
 
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame>;
+
+    /// Applies a hunk of the diff between the index and the working copy to the index.
+    fn stage_hunk(&self, hunk: &DiffHunk) -> Result<()>;
+
+    /// Removes a hunk of the diff between HEAD and the index from the index.
+    fn unstage_hunk(&self, hunk: &DiffHunk) -> Result<()> {
+        self.stage_hunk(&hunk.reversed())
+    }
+
+    /// Returns the repository's stashes, most recent first.
+    fn stashes(&self) -> Result<Vec<StashEntry>>;
+
+    /// Stashes the changes in the working directory and the index.
+    fn create_stash(&self, message: Option<&str>) -> Result<Oid>;
+
+    fn apply_stash(&self, sha: Oid, pop: bool) -> Result<()>;
+    fn drop_stash(&self, sha: Oid) -> Result<()>;
+
+    /// Returns up to `max_commits` of the commits that changed a file, most recent first,
+    /// following it across renames.
+    fn file_history(&self, path: &Path, max_commits: usize) -> Result<Vec<CommitSummary>>;
+
+    /// Returns the contents of a file at the given revision, or `None` if it doesn't exist there.
+    fn file_at_revision(&self, path: &Path, revision: &str) -> Result<Option<String>>;
+
+    /// Applies the changes introduced by a commit, committing them unless `no_commit` is true.
+    fn cherry_pick(&self, hash: Oid, no_commit: bool) -> Result<CherryPickOutcome>;
+
+    /// Whether a cherry-pick stopped on conflicts, leaving a `CHERRY_PICK_HEAD` behind.
+    fn cherry_pick_in_progress(&self) -> bool;
+
+    fn abort_cherry_pick(&self) -> Result<()>;
+
+    /// Returns the diff between HEAD and the index.
+    fn staged_diff(&self) -> Result<String>;
+
+    /// Commits the index with the message and author of the given commit.
+    fn commit_reusing_message(&self, hash: Oid) -> Result<()>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -73,6 +122,15 @@ impl RealGitRepository {
             hosting_provider_registry,
         }
     }
+
+    fn working_directory(&self) -> Result<PathBuf> {
+        Ok(self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf())
+    }
 }
 
 impl GitRepository for RealGitRepository {
@@ -106,6 +164,28 @@ impl GitRepository for RealGitRepository {
         None
     }
 
+    fn load_committed_text(&self, relative_file_path: &Path) -> Option<String> {
+        fn logic(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<String>> {
+            check_path_to_repo_path_errors(relative_file_path)?;
+
+            let tree = repo.head()?.peel_to_tree()?;
+            let oid = match tree.get_path(relative_file_path) {
+                Ok(entry) => entry.id(),
+                Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+
+            let content = repo.find_blob(oid)?.content().to_owned();
+            Ok(Some(String::from_utf8(content)?))
+        }
+
+        match logic(&self.repository.lock(), relative_file_path) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error loading committed text: {:?}", err),
+        }
+        None
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let repo = self.repository.lock();
         let remote = repo.find_remote(name).ok()?;
@@ -200,6 +280,78 @@ impl GitRepository for RealGitRepository {
             self.hosting_provider_registry.clone(),
         )
     }
+
+    fn stage_hunk(&self, hunk: &DiffHunk) -> Result<()> {
+        let working_directory = self.working_directory()?;
+        crate::staging::apply_to_index(&self.git_binary_path, &working_directory, hunk)
+    }
+
+    fn stashes(&self) -> Result<Vec<StashEntry>> {
+        let working_directory = self.working_directory()?;
+        crate::stash::list_stashes(&self.git_binary_path, &working_directory)
+    }
+
+    fn create_stash(&self, message: Option<&str>) -> Result<Oid> {
+        let working_directory = self.working_directory()?;
+        crate::stash::create_stash(&self.git_binary_path, &working_directory, message)
+    }
+
+    fn apply_stash(&self, sha: Oid, pop: bool) -> Result<()> {
+        let working_directory = self.working_directory()?;
+        crate::stash::apply_stash(&self.git_binary_path, &working_directory, sha, pop)
+    }
+
+    fn drop_stash(&self, sha: Oid) -> Result<()> {
+        let working_directory = self.working_directory()?;
+        crate::stash::drop_stash(&self.git_binary_path, &working_directory, sha)
+    }
+
+    fn file_history(&self, path: &Path, max_commits: usize) -> Result<Vec<CommitSummary>> {
+        let working_directory = self.working_directory()?;
+        crate::file_history::file_history(
+            &self.git_binary_path,
+            &working_directory,
+            path,
+            max_commits,
+        )
+    }
+
+    fn file_at_revision(&self, path: &Path, revision: &str) -> Result<Option<String>> {
+        let working_directory = self.working_directory()?;
+        crate::file_history::file_at_revision(
+            &self.git_binary_path,
+            &working_directory,
+            path,
+            revision,
+        )
+    }
+
+    fn cherry_pick(&self, hash: Oid, no_commit: bool) -> Result<CherryPickOutcome> {
+        let working_directory = self.working_directory()?;
+        crate::cherry_pick::cherry_pick(&self.git_binary_path, &working_directory, hash, no_commit)
+    }
+
+    fn cherry_pick_in_progress(&self) -> bool {
+        matches!(
+            self.repository.lock().state(),
+            git2::RepositoryState::CherryPick | git2::RepositoryState::CherryPickSequence
+        )
+    }
+
+    fn abort_cherry_pick(&self) -> Result<()> {
+        let working_directory = self.working_directory()?;
+        crate::cherry_pick::abort_cherry_pick(&self.git_binary_path, &working_directory)
+    }
+
+    fn staged_diff(&self) -> Result<String> {
+        let working_directory = self.working_directory()?;
+        crate::cherry_pick::staged_diff(&self.git_binary_path, &working_directory)
+    }
+
+    fn commit_reusing_message(&self, hash: Oid) -> Result<()> {
+        let working_directory = self.working_directory()?;
+        crate::cherry_pick::commit_reusing_message(&self.git_binary_path, &working_directory, hash)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -210,9 +362,22 @@ pub struct FakeGitRepository {
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
+    pub head_contents: HashMap<PathBuf, String>,
     pub blames: HashMap<PathBuf, Blame>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
+    /// Stashes, most recent first. Their ids are reassigned by position.
+    pub stashes: Vec<StashEntry>,
+    /// The number of stashes made, used to give each new stash a different commit.
+    pub stash_count: usize,
+    /// The commits that changed each file, most recent first, with the file's contents in them.
+    pub file_histories: HashMap<PathBuf, Vec<(CommitSummary, String)>>,
+    /// The absolute paths of the files that conflict when cherry-picking each commit.
+    pub cherry_pick_conflicts: HashMap<Oid, Vec<PathBuf>>,
+    /// The commit being cherry-picked, while its conflicts are unresolved.
+    pub cherry_pick_head: Option<Oid>,
+    /// Commits made by cherry-picking, oldest first.
+    pub cherry_picked_commits: Vec<Oid>,
 }
 
 impl FakeGitRepository {
@@ -229,6 +394,11 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_committed_text(&self, path: &Path) -> Option<String> {
+        let state = self.state.lock();
+        state.head_contents.get(path).cloned()
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -285,6 +455,172 @@ impl GitRepository for FakeGitRepository {
             .with_context(|| format!("failed to get blame for {:?}", path))
             .cloned()
     }
+
+    fn stage_hunk(&self, hunk: &DiffHunk) -> Result<()> {
+        let mut state = self.state.lock();
+        let contents = state
+            .index_contents
+            .get_mut(&hunk.path)
+            .with_context(|| format!("{:?} is not in the index", hunk.path))?;
+        *contents = hunk.apply(contents)?;
+        Ok(())
+    }
+
+    fn stashes(&self) -> Result<Vec<StashEntry>> {
+        let state = self.state.lock();
+        Ok(state.stashes.clone())
+    }
+
+    fn create_stash(&self, message: Option<&str>) -> Result<Oid> {
+        let mut state = self.state.lock();
+        if state.worktree_statuses.is_empty() {
+            anyhow::bail!("there are no local changes to stash");
+        }
+        let branch = state.branch_name.clone().unwrap_or_default();
+        let message = match message {
+            Some(message) => format!("On {branch}: {message}"),
+            None => format!("WIP on {branch}"),
+        };
+        let paths = state
+            .worktree_statuses
+            .drain()
+            .map(|(path, _)| path.0)
+            .collect();
+        // Stashes made by the fake are told apart by how many stashes were made before them.
+        let mut sha = [0; 20];
+        sha[..8].copy_from_slice(&(state.stash_count as u64).to_be_bytes());
+        let sha = Oid::from_bytes(&sha)?;
+        state.stash_count += 1;
+        state.stashes.insert(
+            0,
+            StashEntry {
+                id: StashId(0),
+                sha,
+                message,
+                timestamp: 0,
+                paths,
+            },
+        );
+        state.renumber_stashes();
+        Ok(sha)
+    }
+
+    fn apply_stash(&self, sha: Oid, pop: bool) -> Result<()> {
+        let mut state = self.state.lock();
+        let ix = state.stash_ix(sha)?;
+        for path in state.stashes[ix].paths.clone() {
+            state
+                .worktree_statuses
+                .insert(RepoPath(path), GitFileStatus::Modified);
+        }
+        if pop {
+            state.stashes.remove(ix);
+            state.renumber_stashes();
+        }
+        Ok(())
+    }
+
+    fn drop_stash(&self, sha: Oid) -> Result<()> {
+        let mut state = self.state.lock();
+        let ix = state.stash_ix(sha)?;
+        state.stashes.remove(ix);
+        state.renumber_stashes();
+        Ok(())
+    }
+
+    fn file_history(&self, path: &Path, max_commits: usize) -> Result<Vec<CommitSummary>> {
+        let state = self.state.lock();
+        Ok(state
+            .file_histories
+            .get(path)
+            .into_iter()
+            .flatten()
+            .take(max_commits)
+            .map(|(commit, _)| commit.clone())
+            .collect())
+    }
+
+    fn file_at_revision(&self, path: &Path, revision: &str) -> Result<Option<String>> {
+        let state = self.state.lock();
+        let (hash, parent) = match revision.strip_suffix('^') {
+            Some(hash) => (hash, true),
+            None => (revision, false),
+        };
+        let commits = state
+            .file_histories
+            .values()
+            .find(|commits| {
+                commits
+                    .iter()
+                    .any(|(commit, _)| commit.hash.to_string() == hash)
+            })
+            .with_context(|| format!("invalid object name {revision:?}"))?;
+        let ix = commits
+            .iter()
+            .position(|(commit, _)| commit.hash.to_string() == hash)
+            .unwrap();
+        let ix = if parent { ix + 1 } else { ix };
+        Ok(commits
+            .get(ix)
+            .filter(|(commit, _)| commit.path == path)
+            .map(|(_, contents)| contents.clone()))
+    }
+
+    fn cherry_pick(&self, hash: Oid, no_commit: bool) -> Result<CherryPickOutcome> {
+        let mut state = self.state.lock();
+        if state.cherry_pick_head.is_some() {
+            anyhow::bail!("cherry-pick is already in progress");
+        }
+        if let Some(paths) = state.cherry_pick_conflicts.get(&hash).cloned() {
+            state.cherry_pick_head = Some(hash);
+            return Ok(CherryPickOutcome::Conflicted { paths });
+        }
+        if no_commit {
+            Ok(CherryPickOutcome::Staged)
+        } else {
+            state.cherry_picked_commits.push(hash);
+            Ok(CherryPickOutcome::Committed)
+        }
+    }
+
+    fn cherry_pick_in_progress(&self) -> bool {
+        self.state.lock().cherry_pick_head.is_some()
+    }
+
+    fn abort_cherry_pick(&self) -> Result<()> {
+        let mut state = self.state.lock();
+        state
+            .cherry_pick_head
+            .take()
+            .context("no cherry-pick or revert in progress")?;
+        Ok(())
+    }
+
+    fn staged_diff(&self) -> Result<String> {
+        Ok(String::new())
+    }
+
+    fn commit_reusing_message(&self, hash: Oid) -> Result<()> {
+        let mut state = self.state.lock();
+        state.cherry_pick_head = None;
+        state.cherry_picked_commits.push(hash);
+        Ok(())
+    }
+}
+
+impl FakeGitRepositoryState {
+    fn renumber_stashes(&mut self) {
+        for (ix, stash) in self.stashes.iter_mut().enumerate() {
+            stash.id = StashId(ix);
+        }
+    }
+
+    fn stash_ix(&self, sha: Oid) -> Result<usize> {
+        self.stashes
+            .iter()
+            .position(|stash| stash.sha == sha)
+            .with_context(|| format!("stash {} no longer exists", sha.display_short()))
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
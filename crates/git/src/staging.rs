@@ -0,0 +1,421 @@
+use crate::command::git_command;
+use anyhow::{anyhow, Context, Result};
+use git2::{DiffOptions as GitOptions, Patch as GitPatch};
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+const NO_NEWLINE_AT_EOF: &str = "\\ No newline at end of file\n";
+
+/// A single hunk of a diff between two versions of a file, without context lines, that can be
+/// applied on its own to the old version of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// The path of the file, relative to the repository's working directory.
+    pub path: PathBuf,
+    /// The hunk header, like `@@ -3,2 +3,4 @@`.
+    pub header: String,
+    /// The removed and added lines of the hunk, each prefixed with `-` or `+`.
+    pub diff_text: String,
+}
+
+/// Whether a change between HEAD and the working copy is in the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StagedStatus {
+    /// All of the change has been staged.
+    Staged,
+    /// Some of the change has been staged, but the working copy has further unstaged changes.
+    PartiallyStaged,
+}
+
+impl DiffHunk {
+    /// Returns a patch for this hunk, in the format understood by `git apply`.
+    pub fn patch(&self) -> String {
+        let path = self.path.to_string_lossy();
+        format!(
+            "diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n{}{}",
+            self.header, self.diff_text
+        )
+    }
+
+    /// Returns the hunk that undoes this one.
+    pub fn reversed(&self) -> Self {
+        let (old, new) = self.ranges();
+        let lines = self.lines();
+        let removed = lines.iter().filter(|(origin, _)| *origin == '+');
+        let added = lines.iter().filter(|(origin, _)| *origin == '-');
+        Self {
+            path: self.path.clone(),
+            header: format!("@@ -{},{} +{},{} @@\n", new.0, new.1, old.0, old.1),
+            diff_text: render_lines(
+                removed
+                    .map(|(_, content)| ('-', *content))
+                    .chain(added.map(|(_, content)| ('+', *content))),
+            ),
+        }
+    }
+
+    /// The zero-based rows replaced by this hunk in the old version of the file. Hunks that only
+    /// add lines return an empty range at the row the lines are inserted before.
+    pub fn old_rows(&self) -> Range<u32> {
+        rows(self.ranges().0)
+    }
+
+    /// The zero-based rows this hunk adds to the new version of the file. Hunks that only remove
+    /// lines return an empty range at the row following the removed lines.
+    pub fn new_rows(&self) -> Range<u32> {
+        rows(self.ranges().1)
+    }
+
+    /// Applies this hunk to the old version of the file, returning the new version.
+    pub fn apply(&self, text: &str) -> Result<String> {
+        let mut lines = text.split_inclusive('\n').collect::<Vec<_>>();
+        let old_rows = self.old_rows();
+        let old_rows = old_rows.start as usize..old_rows.end as usize;
+        let hunk_lines = self.lines();
+        let removed = hunk_lines
+            .iter()
+            .filter(|(origin, _)| *origin == '-')
+            .map(|(_, content)| *content);
+        let added = hunk_lines
+            .iter()
+            .filter(|(origin, _)| *origin == '+')
+            .map(|(_, content)| *content);
+
+        let existing = lines
+            .get(old_rows.clone())
+            .with_context(|| format!("hunk {} is out of range", self.header.trim()))?;
+        if !existing.iter().copied().eq(removed) {
+            return Err(anyhow!(
+                "hunk {} does not apply to {:?}",
+                self.header.trim(),
+                self.path
+            ));
+        }
+        lines.splice(old_rows, added);
+        Ok(lines.concat())
+    }
+
+    fn ranges(&self) -> ((u32, u32), (u32, u32)) {
+        let mut ranges = self
+            .header
+            .trim_start_matches("@@ ")
+            .split(' ')
+            .take(2)
+            .map(|range| {
+                let range = range.trim_start_matches(['-', '+']);
+                let (start, len) = range.split_once(',').unwrap_or((range, "1"));
+                (start.parse().unwrap_or(0), len.parse().unwrap_or(0))
+            });
+        let old = ranges.next().unwrap_or_default();
+        let new = ranges.next().unwrap_or_default();
+        (old, new)
+    }
+
+    /// The lines of the hunk with their origins, where the last line of the file has no
+    /// trailing newline if the diff says so.
+    fn lines(&self) -> Vec<(char, &str)> {
+        let mut lines: Vec<(char, &str)> = Vec::new();
+        for line in self.diff_text.split_inclusive('\n') {
+            if line.starts_with('\\') {
+                if let Some((_, content)) = lines.last_mut() {
+                    *content = content.strip_suffix('\n').unwrap_or(content);
+                }
+            } else if let Some(origin) = line.chars().next() {
+                lines.push((origin, &line[origin.len_utf8()..]));
+            }
+        }
+        lines
+    }
+}
+
+fn rows((start, len): (u32, u32)) -> Range<u32> {
+    if len == 0 {
+        start..start
+    } else {
+        let start = start.saturating_sub(1);
+        start..start + len
+    }
+}
+
+fn render_lines<'a>(lines: impl Iterator<Item = (char, &'a str)>) -> String {
+    let mut text = String::new();
+    for (origin, content) in lines {
+        text.push(origin);
+        text.push_str(content);
+        if !content.ends_with('\n') {
+            text.push('\n');
+            text.push_str(NO_NEWLINE_AT_EOF);
+        }
+    }
+    text
+}
+
+/// Returns the hunks of the diff between two versions of a file, without context lines.
+pub fn diff_hunks(path: &Path, old_text: &str, new_text: &str) -> Result<Vec<DiffHunk>> {
+    let mut options = GitOptions::default();
+    options.context_lines(0);
+    let patch = GitPatch::from_buffers(
+        old_text.as_bytes(),
+        Some(path),
+        new_text.as_bytes(),
+        Some(path),
+        Some(&mut options),
+    )?;
+
+    let mut hunks = Vec::with_capacity(patch.num_hunks());
+    for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_index)?;
+        let mut lines = Vec::with_capacity(line_count);
+        for line_index in 0..line_count {
+            let line = patch.line_in_hunk(hunk_index, line_index)?;
+            // The end-of-file markers are emitted as lines of their own, and are added back
+            // when rendering lines without a trailing newline.
+            if matches!(line.origin(), '+' | '-') {
+                lines.push((line.origin(), String::from_utf8_lossy(line.content())));
+            }
+        }
+        hunks.push(DiffHunk {
+            path: path.to_path_buf(),
+            header: String::from_utf8_lossy(hunk.header()).into_owned(),
+            diff_text: render_lines(
+                lines
+                    .iter()
+                    .map(|(origin, content)| (*origin, content.as_ref())),
+            ),
+        });
+    }
+    Ok(hunks)
+}
+
+/// Returns the hunks of the unstaged changes in a file that touch the given rows of its working
+/// copy.
+pub fn hunks_to_stage(
+    path: &Path,
+    index_text: &str,
+    buffer_text: &str,
+    rows: Range<u32>,
+) -> Result<Vec<DiffHunk>> {
+    let mut hunks = diff_hunks(path, index_text, buffer_text)?;
+    hunks.retain(|hunk| rows_overlap(&hunk.new_rows(), &rows));
+    Ok(hunks)
+}
+
+/// Returns the hunks of the staged changes in a file that touch the given rows of its working
+/// copy, as hunks that remove those changes from the index.
+pub fn hunks_to_unstage(
+    path: &Path,
+    head_text: &str,
+    index_text: &str,
+    buffer_text: &str,
+    rows: Range<u32>,
+) -> Result<Vec<DiffHunk>> {
+    let unstaged = diff_hunks(path, index_text, buffer_text)?;
+    let index_rows = index_row(&unstaged, rows.start)..index_row(&unstaged, rows.end);
+    let mut hunks = diff_hunks(path, head_text, index_text)?;
+    hunks.retain(|hunk| rows_overlap(&hunk.new_rows(), &index_rows));
+    Ok(hunks.iter().map(DiffHunk::reversed).collect())
+}
+
+/// Returns the rows of the working copy of a file that have been changed since HEAD, and
+/// whether those changes have been staged. Changes that haven't been staged at all are left out.
+pub fn staged_hunks(
+    path: &Path,
+    head_text: &str,
+    index_text: &str,
+    buffer_text: &str,
+) -> Result<Vec<(Range<u32>, StagedStatus)>> {
+    let staged = diff_hunks(path, head_text, index_text)?;
+    let unstaged = diff_hunks(path, index_text, buffer_text)?;
+    let changes = diff_hunks(path, head_text, buffer_text)?;
+    Ok(changes
+        .into_iter()
+        .filter_map(|change| {
+            let old_rows = change.old_rows();
+            let new_rows = change.new_rows();
+            staged
+                .iter()
+                .any(|hunk| rows_overlap(&hunk.old_rows(), &old_rows))
+                .then(|| {
+                    let status = if unstaged
+                        .iter()
+                        .any(|hunk| rows_overlap(&hunk.new_rows(), &new_rows))
+                    {
+                        StagedStatus::PartiallyStaged
+                    } else {
+                        StagedStatus::Staged
+                    };
+                    (new_rows, status)
+                })
+        })
+        .collect())
+}
+
+/// Maps a row of the working copy to the corresponding row of the index.
+fn index_row(unstaged: &[DiffHunk], row: u32) -> u32 {
+    let mut divergence = 0i64;
+    for hunk in unstaged {
+        let new_rows = hunk.new_rows();
+        let old_rows = hunk.old_rows();
+        if new_rows.end <= row {
+            divergence += new_rows.len() as i64 - old_rows.len() as i64;
+        } else if new_rows.start <= row {
+            return old_rows.start;
+        } else {
+            break;
+        }
+    }
+    (row as i64 - divergence).max(0) as u32
+}
+
+/// Whether two row ranges touch, treating empty ranges as the row they point at.
+fn rows_overlap(a: &Range<u32>, b: &Range<u32>) -> bool {
+    a.start < b.end.max(b.start + 1) && b.start < a.end.max(a.start + 1)
+}
+
+/// Applies a hunk to the index with `git apply --cached`, leaving the working copy untouched.
+pub(crate) fn apply_to_index(
+    git_binary: &Path,
+    working_directory: &Path,
+    hunk: &DiffHunk,
+) -> Result<()> {
+    let mut child = git_command(git_binary, working_directory);
+    child
+        .args([
+            "apply",
+            "--cached",
+            "--unidiff-zero",
+            "--whitespace=nowarn",
+            "-",
+        ])
+        .stdin(Stdio::piped());
+
+    let mut child = child
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start git apply process: {}", e))?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("failed to get pipe to stdin of git apply command")?;
+    stdin.write_all(hunk.patch().as_bytes())?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| anyhow!("Failed to read git apply output: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git apply process failed: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use unindent::Unindent as _;
+
+    #[test]
+    fn test_diff_hunks_apply_and_reverse() {
+        let path = Path::new("src/main.rs");
+        let old_text = "
+            one
+            two
+            three
+            four
+        "
+        .unindent();
+        let new_text = "
+            zero
+            one
+            TWO
+            three
+        "
+        .unindent();
+
+        let hunks = diff_hunks(path, &old_text, &new_text).unwrap();
+        assert_eq!(
+            hunks
+                .iter()
+                .map(|hunk| (hunk.old_rows(), hunk.new_rows()))
+                .collect::<Vec<_>>(),
+            [(0..0, 0..1), (1..2, 2..3), (3..4, 4..4)]
+        );
+        assert_eq!(hunks[1].header, "@@ -2 +3 @@\n");
+        assert_eq!(hunks[1].diff_text, "-two\n+TWO\n");
+
+        // Applying the hunks bottom-up keeps the rows of the earlier hunks valid.
+        let mut text = old_text.clone();
+        for hunk in hunks.iter().rev() {
+            text = hunk.apply(&text).unwrap();
+        }
+        assert_eq!(text, new_text);
+
+        let mut text = new_text.clone();
+        for hunk in hunks.iter().map(DiffHunk::reversed).rev() {
+            text = hunk.apply(&text).unwrap();
+        }
+        assert_eq!(text, old_text);
+
+        // Only the selected hunk is applied.
+        assert_eq!(
+            hunks[1].apply(&old_text).unwrap(),
+            "one\nTWO\nthree\nfour\n"
+        );
+        assert!(hunks[1].apply(&new_text).is_err());
+        assert_eq!(
+            hunks[1].patch(),
+            "diff --git a/src/main.rs b/src/main.rs\n\
+             --- a/src/main.rs\n\
+             +++ b/src/main.rs\n\
+             @@ -2 +3 @@\n\
+             -two\n\
+             +TWO\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_hunks_without_trailing_newline() {
+        let path = Path::new("a.txt");
+        let hunks = diff_hunks(path, "one\ntwo", "one\ntwo\nthree").unwrap();
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(
+            hunks[0].diff_text,
+            format!("-two\n{NO_NEWLINE_AT_EOF}+two\n+three\n{NO_NEWLINE_AT_EOF}")
+        );
+        assert_eq!(hunks[0].apply("one\ntwo").unwrap(), "one\ntwo\nthree");
+        assert_eq!(
+            hunks[0].reversed().apply("one\ntwo\nthree").unwrap(),
+            "one\ntwo"
+        );
+    }
+
+    #[test]
+    fn test_staged_hunks() {
+        let path = Path::new("a.txt");
+        let head_text = "a\nb\nc\nd\ne\nf\n";
+        let index_text = "a\nB\nc\nD\ne\nf\n";
+        let buffer_text = "a\nB\nc\nDD\ne\nF\n";
+
+        assert_eq!(
+            staged_hunks(path, head_text, index_text, buffer_text).unwrap(),
+            [
+                (1..2, StagedStatus::Staged),
+                (3..4, StagedStatus::PartiallyStaged)
+            ]
+        );
+
+        let to_stage = hunks_to_stage(path, index_text, buffer_text, 5..6).unwrap();
+        assert_eq!(to_stage.len(), 1);
+        assert_eq!(to_stage[0].apply(index_text).unwrap(), "a\nB\nc\nD\ne\nF\n");
+
+        let to_unstage = hunks_to_unstage(path, head_text, index_text, buffer_text, 0..2).unwrap();
+        assert_eq!(to_unstage.len(), 1);
+        assert_eq!(
+            to_unstage[0].apply(index_text).unwrap(),
+            "a\nb\nc\nD\ne\nf\n"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use crate::command::run_git_checked;
+use crate::repository::GitRepository;
+use crate::Oid;
+use anyhow::{anyhow, Context, Result};
+use gpui::{BackgroundExecutor, Task};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The position of a stash in the stash list, where the most recent stash is `0`.
+///
+/// Positions shift whenever a stash is created or removed, so stashes are changed by their
+/// [`StashEntry::sha`] instead, which doesn't change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StashId(pub usize);
+
+impl fmt::Display for StashId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "stash@{{{}}}", self.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StashEntry {
+    pub id: StashId,
+    /// The stash's commit.
+    pub sha: Oid,
+    pub message: String,
+    /// Time the stash was created, normalized to Unix Epoch format.
+    pub timestamp: i64,
+    /// Paths changed by the stash, relative to the repository's working directory.
+    pub paths: Vec<PathBuf>,
+}
+
+/// Creates, lists and applies the stashes of a repository, running the git commands on the
+/// background executor.
+#[derive(Clone)]
+pub struct StashManager {
+    repository: Arc<dyn GitRepository>,
+    executor: BackgroundExecutor,
+}
+
+impl StashManager {
+    pub fn new(repository: Arc<dyn GitRepository>, executor: BackgroundExecutor) -> Self {
+        Self {
+            repository,
+            executor,
+        }
+    }
+
+    /// Stashes the changes in the working directory and the index, returning the new stash's
+    /// commit.
+    pub fn create_stash(&self, message: Option<&str>) -> Task<Result<Oid>> {
+        let repository = self.repository.clone();
+        let message = message.map(str::to_string);
+        self.executor
+            .spawn(async move { repository.create_stash(message.as_deref()) })
+    }
+
+    /// Returns the repository's stashes, most recent first.
+    pub fn list_stashes(&self) -> Task<Result<Vec<StashEntry>>> {
+        let repository = self.repository.clone();
+        self.executor.spawn(async move { repository.stashes() })
+    }
+
+    /// Applies a stash to the working directory, removing it from the stash list if `pop` is
+    /// true and it applied without conflicts.
+    pub fn apply_stash(&self, sha: Oid, pop: bool) -> Task<Result<()>> {
+        let repository = self.repository.clone();
+        self.executor
+            .spawn(async move { repository.apply_stash(sha, pop) })
+    }
+
+    pub fn drop_stash(&self, sha: Oid) -> Task<Result<()>> {
+        let repository = self.repository.clone();
+        self.executor
+            .spawn(async move { repository.drop_stash(sha) })
+    }
+}
+
+/// Separates the stashes in the output of `git stash list`.
+const STASH_LIST_RECORD_SEPARATOR: char = '\x1e';
+
+pub(crate) fn list_stashes(git_binary: &Path, working_directory: &Path) -> Result<Vec<StashEntry>> {
+    let output = run_git_checked(git_binary, working_directory, |command| {
+        command.args([
+            "stash",
+            "list",
+            "--name-only",
+            "--format=%x1e%gd%x00%H%x00%ct%x00%gs",
+        ]);
+    })?;
+    parse_stash_list(&output)
+}
+
+pub(crate) fn create_stash(
+    git_binary: &Path,
+    working_directory: &Path,
+    message: Option<&str>,
+) -> Result<Oid> {
+    // `git stash push` succeeds without creating a stash when there's nothing to stash, and
+    // only says so in a localized message, so compare the latest stash before and after.
+    let previous_stash = latest_stash(git_binary, working_directory)?;
+    run_git_checked(git_binary, working_directory, |command| {
+        command.args(["stash", "push"]);
+        if let Some(message) = message {
+            command.arg("--message").arg(message);
+        }
+    })?;
+    match latest_stash(git_binary, working_directory)? {
+        Some(stash) if Some(stash) != previous_stash => Ok(stash),
+        _ => Err(anyhow!("there are no local changes to stash")),
+    }
+}
+
+pub(crate) fn apply_stash(
+    git_binary: &Path,
+    working_directory: &Path,
+    sha: Oid,
+    pop: bool,
+) -> Result<()> {
+    let id = stash_id(git_binary, working_directory, sha)?;
+    let subcommand = if pop { "pop" } else { "apply" };
+    run_git_checked(git_binary, working_directory, |command| {
+        command.args(["stash", subcommand]).arg(id.to_string());
+    })?;
+    Ok(())
+}
+
+pub(crate) fn drop_stash(git_binary: &Path, working_directory: &Path, sha: Oid) -> Result<()> {
+    let id = stash_id(git_binary, working_directory, sha)?;
+    run_git_checked(git_binary, working_directory, |command| {
+        command.args(["stash", "drop"]).arg(id.to_string());
+    })?;
+    Ok(())
+}
+
+fn latest_stash(git_binary: &Path, working_directory: &Path) -> Result<Option<Oid>> {
+    let output = run_git_checked(git_binary, working_directory, |command| {
+        command.args(["stash", "list", "-n", "1", "--format=%H"]);
+    })?;
+    output.lines().next().map(str::parse).transpose()
+}
+
+/// Finds the current position of a stash, as the stash subcommands that remove stashes only
+/// accept `stash@{n}` references.
+fn stash_id(git_binary: &Path, working_directory: &Path, sha: Oid) -> Result<StashId> {
+    let output = run_git_checked(git_binary, working_directory, |command| {
+        command.args(["stash", "list", "--format=%H"]);
+    })?;
+    output
+        .lines()
+        .position(|line| line.parse::<Oid>().ok() == Some(sha))
+        .map(StashId)
+        .with_context(|| format!("stash {} no longer exists", sha.display_short()))
+}
+
+fn parse_stash_list(output: &str) -> Result<Vec<StashEntry>> {
+    output
+        .split(STASH_LIST_RECORD_SEPARATOR)
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| {
+            let mut lines = record.lines();
+            let header = lines.next().unwrap_or_default();
+            let mut fields = header.splitn(4, '\0');
+            let (Some(reflog_selector), Some(sha), Some(timestamp), Some(message)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(anyhow!("invalid stash list entry {:?}", header));
+            };
+            let id = reflog_selector
+                .strip_prefix("stash@{")
+                .and_then(|selector| selector.strip_suffix('}'))
+                .and_then(|index| index.parse().ok())
+                .map(StashId)
+                .with_context(|| format!("invalid stash reference {:?}", reflog_selector))?;
+            let sha = sha.parse()?;
+            let timestamp = timestamp
+                .parse()
+                .with_context(|| format!("invalid stash timestamp {:?}", timestamp))?;
+            Ok(StashEntry {
+                id,
+                sha,
+                message: message.to_string(),
+                timestamp,
+                paths: lines
+                    .filter(|line| !line.is_empty())
+                    .map(PathBuf::from)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stash_list() {
+        let first_sha = "1b2c3d4e5f60718293a4b5c6d7e8f90112233445";
+        let second_sha = "a1b2c3d4e5f60718293a4b5c6d7e8f9011223344";
+        let output = format!(
+            "\
+            \x1estash@{{0}}\x00{first_sha}\x001718000000\x00On main: wip parser\n\
+            \n\
+            src/parser.rs\n\
+            src/lexer.rs\n\
+            \x1estash@{{1}}\x00{second_sha}\x001717000000\x00WIP on main: 1a2b3c4 Fix typo\n\
+            \n\
+            README.md\n"
+        );
+        assert_eq!(
+            parse_stash_list(&output).unwrap(),
+            [
+                StashEntry {
+                    id: StashId(0),
+                    sha: first_sha.parse().unwrap(),
+                    message: "On main: wip parser".into(),
+                    timestamp: 1718000000,
+                    paths: vec!["src/parser.rs".into(), "src/lexer.rs".into()],
+                },
+                StashEntry {
+                    id: StashId(1),
+                    sha: second_sha.parse().unwrap(),
+                    message: "WIP on main: 1a2b3c4 Fix typo".into(),
+                    timestamp: 1717000000,
+                    paths: vec!["README.md".into()],
+                },
+            ]
+        );
+        assert!(parse_stash_list("").unwrap().is_empty());
+        assert!(parse_stash_list(&format!(
+            "\x1estash@{{0}}\x00{first_sha}\x00not a timestamp\x00message\n"
+        ))
+        .is_err());
+        assert_eq!(StashId(3).to_string(), "stash@{3}");
+    }
+}
@@ -97,9 +97,11 @@ mod macos {
             "AtlasTile".into(),
             "PathRasterizationInputIndex".into(),
             "PathVertex_ScaledPixels".into(),
+            "PathFillRule".into(),
             "ShadowInputIndex".into(),
             "Shadow".into(),
             "QuadInputIndex".into(),
+            "Gradient".into(),
             "Underline".into(),
             "UnderlineInputIndex".into(),
             "Quad".into(),
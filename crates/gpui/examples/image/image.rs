@@ -22,16 +22,30 @@ impl ImageContainer {
 impl RenderOnce for ImageContainer {
     fn render(self, _: &mut WindowContext) -> impl IntoElement {
         div().child(
-            div()
-                .flex_row()
-                .size_full()
-                .gap_4()
-                .child(self.text)
-                .child(img(self.src).w(px(512.0)).h(px(512.0))),
+            div().flex_row().size_full().gap_4().child(self.text).child(
+                img(self.src)
+                    .w(px(512.0))
+                    .h(px(512.0))
+                    .with_loading(|| placeholder("Loading…", rgb(0xe0e0e0)).into_any_element())
+                    .with_fallback(|| {
+                        placeholder("Failed to load image", rgb(0xf8d7da)).into_any_element()
+                    }),
+            ),
         )
     }
 }
 
+fn placeholder(label: &'static str, color: Rgba) -> Div {
+    div()
+        .flex()
+        .size_full()
+        .justify_center()
+        .items_center()
+        .bg(color)
+        .text_color(rgb(0x555555))
+        .child(label)
+}
+
 struct ImageShowcase {
     local_resource: Arc<PathBuf>,
     remote_resource: SharedUri,
@@ -55,6 +69,10 @@ impl Render for ImageShowcase {
                 "Image loaded from a remote resource",
                 self.remote_resource.clone(),
             ))
+            .child(ImageContainer::new(
+                "Image that fails to load",
+                PathBuf::from("examples/image/missing.png"),
+            ))
     }
 }
 
@@ -80,7 +98,7 @@ fn main() {
             }),
 
             window_bounds: Some(WindowBounds::Windowed(Bounds {
-                size: size(px(1100.), px(600.)),
+                size: size(px(1650.), px(600.)),
                 origin: Point::new(px(200.), px(200.)),
             })),
 
@@ -0,0 +1,84 @@
+use gpui::*;
+use smallvec::smallvec;
+
+struct Shadow;
+
+fn example(label: &'static str, element: Div) -> Div {
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_3()
+        .child(element.size_16().rounded_md().bg(rgb(0xffffff)))
+        .child(label)
+}
+
+impl Render for Shadow {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_wrap()
+            .justify_center()
+            .items_center()
+            .gap_12()
+            .p_12()
+            .size_full()
+            .bg(rgb(0xf0f0f0))
+            .text_sm()
+            .text_color(rgb(0x333333))
+            .child(example("shadow_none", div().shadow_none()))
+            .child(example("shadow_sm", div().shadow_sm()))
+            .child(example("shadow_md", div().shadow_md()))
+            .child(example("shadow_lg", div().shadow_lg()))
+            .child(example("shadow_xl", div().shadow_xl()))
+            .child(example(
+                "offset",
+                div().shadow(smallvec![BoxShadow {
+                    color: hsla(0., 0., 0., 0.3),
+                    offset: point(px(8.), px(8.)),
+                    blur_radius: px(0.),
+                    spread_radius: px(0.),
+                }]),
+            ))
+            .child(example(
+                "blur and spread",
+                div().shadow(smallvec![BoxShadow {
+                    color: hsla(0.6, 0.8, 0.5, 0.5),
+                    offset: point(px(0.), px(0.)),
+                    blur_radius: px(16.),
+                    spread_radius: px(4.),
+                }]),
+            ))
+            .child(example(
+                "layered",
+                div().shadow(smallvec![
+                    BoxShadow {
+                        color: hsla(0., 0.8, 0.5, 0.4),
+                        offset: point(px(-6.), px(-6.)),
+                        blur_radius: px(8.),
+                        spread_radius: px(0.),
+                    },
+                    BoxShadow {
+                        color: hsla(0.6, 0.8, 0.5, 0.4),
+                        offset: point(px(6.), px(6.)),
+                        blur_radius: px(8.),
+                        spread_radius: px(0.),
+                    },
+                ]),
+            ))
+    }
+}
+
+fn main() {
+    App::new().run(|cx: &mut AppContext| {
+        let bounds = Bounds::centered(None, size(px(800.0), px(500.0)), cx);
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                ..Default::default()
+            },
+            |cx| cx.new_view(|_cx| Shadow),
+        )
+        .unwrap();
+    });
+}
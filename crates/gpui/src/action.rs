@@ -158,6 +158,8 @@ impl ActionRegistry {
     }
 
     /// Construct an action based on its name and optional JSON parameters sourced from the keymap.
+    /// Fails with the reason the parameters couldn't be deserialized into the action's type if
+    /// they don't match it.
     pub fn build_action(
         &self,
         name: &str,
@@ -167,8 +169,9 @@ impl ActionRegistry {
             .builders_by_name
             .get(name)
             .ok_or_else(|| anyhow!("no action type registered for {}", name))?;
-        (build_action)(params.unwrap_or_else(|| json!({})))
-            .with_context(|| format!("Attempting to build action {}", name))
+        let params = params.unwrap_or_else(|| json!({}));
+        (build_action)(params.clone())
+            .with_context(|| format!("invalid arguments {} for action {}", params, name))
     }
 
     pub fn all_action_names(&self) -> &[SharedString] {
@@ -324,3 +327,139 @@ mod no_action {
 
     actions!(zed, [NoAction]);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as gpui;
+    use gpui::{actions, impl_actions};
+    use serde_derive::{Deserialize, Serialize};
+
+    actions!(action_test, [Unit]);
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Fields {
+        index: usize,
+        #[serde(default)]
+        center: bool,
+        label: Option<String>,
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Index(usize);
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    enum Direction {
+        #[default]
+        Up,
+        Down,
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Nested {
+        direction: Direction,
+        #[serde(default)]
+        counts: Vec<u32>,
+    }
+
+    impl_actions!(action_test, [Fields, Index, Direction, Nested]);
+
+    fn assert_round_trip<A: Action + serde::Serialize>(registry: &ActionRegistry, action: A) {
+        let params = serde_json::to_value(&action).unwrap();
+        let built = registry
+            .build_action(action.name(), Some(params.clone()))
+            .unwrap();
+        assert!(built.partial_eq(&action), "{} {params}", action.name());
+        assert_eq!(built.name(), action.name());
+    }
+
+    #[test]
+    fn test_action_arguments_round_trip() {
+        let registry = ActionRegistry::default();
+
+        assert_round_trip(
+            &registry,
+            Fields {
+                index: 2,
+                center: true,
+                label: Some("two".into()),
+            },
+        );
+        assert_round_trip(&registry, Fields::default());
+        assert_round_trip(&registry, Index(7));
+        assert_round_trip(&registry, Direction::Down);
+        assert_round_trip(
+            &registry,
+            Nested {
+                direction: Direction::Up,
+                counts: vec![1, 2, 3],
+            },
+        );
+
+        // Arguments written by hand in a keymap, with optional fields left out.
+        let build = |name: &str, params| registry.build_action(name, params).unwrap();
+        assert!(build("action_test::Unit", None).partial_eq(&Unit));
+        assert!(
+            build("action_test::Fields", Some(json!({ "index": 1 }))).partial_eq(&Fields {
+                index: 1,
+                center: false,
+                label: None,
+            })
+        );
+        assert!(build("action_test::Index", Some(json!(3))).partial_eq(&Index(3)));
+        assert!(build("action_test::Direction", Some(json!("Down"))).partial_eq(&Direction::Down));
+        assert!(
+            build("action_test::Nested", Some(json!({ "direction": "Up" }))).partial_eq(&Nested {
+                direction: Direction::Up,
+                counts: Vec::new(),
+            })
+        );
+
+        // Actions with different arguments aren't equal, so they can be bound separately.
+        assert!(!build("action_test::Index", Some(json!(3))).partial_eq(&Index(4)));
+        assert!(!build("action_test::Index", Some(json!(3))).partial_eq(&Fields::default()));
+    }
+
+    #[test]
+    fn test_action_names_and_types() {
+        let registry = ActionRegistry::default();
+        assert_eq!(
+            registry.names_by_type_id.get(&TypeId::of::<Index>()),
+            Some(&SharedString::from("action_test::Index"))
+        );
+        assert!(registry
+            .all_action_names()
+            .contains(&SharedString::from("action_test::Nested")));
+        assert!(registry
+            .build_action_type(&TypeId::of::<Unit>())
+            .unwrap()
+            .partial_eq(&Unit));
+    }
+
+    #[test]
+    fn test_invalid_action_arguments() {
+        let registry = ActionRegistry::default();
+        let error = |name: &str, params| {
+            let error = registry.build_action(name, Some(params)).unwrap_err();
+            format!("{error:#}")
+        };
+
+        assert_eq!(
+            error("action_test::Fields", json!({ "index": "one" })),
+            "invalid arguments {\"index\":\"one\"} for action action_test::Fields: \
+            invalid type: string \"one\", expected usize"
+        );
+        assert_eq!(
+            error("action_test::Fields", json!({})),
+            "invalid arguments {} for action action_test::Fields: missing field `index`"
+        );
+        assert!(error("action_test::Direction", json!("Left"))
+            .ends_with("unknown variant `Left`, expected `Up` or `Down`"));
+        assert!(error("action_test::Index", json!({ "index": 1 }))
+            .starts_with("invalid arguments {\"index\":1} for action action_test::Index"));
+        assert_eq!(
+            error("action_test::Missing", json!({})),
+            "no action type registered for action_test::Missing"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     any::{type_name, TypeId},
-    cell::{Ref, RefCell, RefMut},
+    cell::{Cell, Ref, RefCell, RefMut},
     marker::PhantomData,
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
@@ -13,37 +13,54 @@ use anyhow::{anyhow, Result};
 use derive_more::{Deref, DerefMut};
 use futures::{channel::oneshot, future::LocalBoxFuture, Future};
 use slotmap::SlotMap;
+use smallvec::SmallVec;
 use smol::future::FutureExt;
 use time::UtcOffset;
 
 pub use async_context::*;
+use clipboard_history::ClipboardHistory;
+pub(crate) use clipboard_history::CLIPBOARD_POLL_INTERVAL;
+pub use clipboard_history::{
+    looks_like_secret, ClipboardHistoryEntry, ClipboardItemSource,
+    DEFAULT_CLIPBOARD_HISTORY_CAPACITY,
+};
 use collections::{FxHashMap, FxHashSet, VecDeque};
+pub use debounce::*;
 pub use entity_map::*;
 use http::{self, HttpClient};
 pub use model_context::*;
 #[cfg(any(test, feature = "test-support"))]
 pub use test_context::*;
+pub use throttle::*;
 use util::ResultExt;
 
 use crate::{
     current_platform, init_app_menus, Action, ActionRegistry, Any, AnyView, AnyWindowHandle,
     AssetCache, AssetSource, BackgroundExecutor, ClipboardItem, Context, DispatchPhase, DisplayId,
-    Entity, EventEmitter, ForegroundExecutor, Global, KeyBinding, Keymap, Keystroke, LayoutId,
-    Menu, MenuItem, OwnedMenu, PathPromptOptions, Pixels, Platform, PlatformDisplay, Point,
-    PromptBuilder, PromptHandle, PromptLevel, Render, RenderablePromptHandle, Reservation,
-    SharedString, SubscriberSet, Subscription, SvgRenderer, Task, TextSystem, View, ViewContext,
-    Window, WindowAppearance, WindowContext, WindowHandle, WindowId,
+    Entity, EventEmitter, ForegroundExecutor, Global, KeyBinding, KeyBindingContextPredicate,
+    KeyContext, Keymap, Keystroke, LayoutId, Menu, MenuItem, OwnedMenu, PathPromptOptions, Pixels,
+    Platform, PlatformDisplay, Point, PromptBuilder, PromptHandle, PromptLevel, Render,
+    RenderablePromptHandle, Reservation, SharedString, SubscriberSet, Subscription, SvgRenderer,
+    Task, TextSystem, View, ViewContext, Window, WindowAppearance, WindowContext, WindowHandle,
+    WindowId,
 };
 
 mod async_context;
+mod clipboard_history;
+mod debounce;
 mod entity_map;
 mod model_context;
 #[cfg(any(test, feature = "test-support"))]
 mod test_context;
+mod throttle;
 
 /// The duration for which futures returned from [AppContext::on_app_context] or [ModelContext::on_app_quit] can run before the application fully quits.
 pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(100);
 
+/// How long a window waits for the next keystroke of a multi-stroke binding, unless set with
+/// [AppContext::set_pending_input_timeout].
+pub const DEFAULT_PENDING_INPUT_TIMEOUT: Duration = Duration::from_secs(1);
+
 /// Temporary(?) wrapper around [`RefCell<AppContext>`] to help us debug any double borrows.
 /// Strongly consider removing after stabilization.
 #[doc(hidden)]
@@ -71,6 +88,12 @@ impl AppCell {
         }
         AppRefMut(self.app.borrow_mut())
     }
+
+    /// Mutably borrows the app, unless it is already borrowed.
+    #[cfg(any(test, feature = "test-support"))]
+    pub(crate) fn try_borrow_mut(&self) -> Option<AppRefMut> {
+        self.app.try_borrow_mut().ok().map(AppRefMut)
+    }
 }
 
 #[doc(hidden)]
@@ -220,6 +243,7 @@ pub struct AppContext {
     pub(crate) windows: SlotMap<WindowId, Option<Window>>,
     pub(crate) window_handles: FxHashMap<WindowId, AnyWindowHandle>,
     pub(crate) keymap: Rc<RefCell<Keymap>>,
+    pub(crate) pending_input_timeout: Duration,
     pub(crate) global_action_listeners:
         FxHashMap<TypeId, Vec<Rc<dyn Fn(&dyn Any, DispatchPhase, &mut Self)>>>,
     pending_effects: VecDeque<Effect>,
@@ -232,6 +256,11 @@ pub struct AppContext {
     pub(crate) release_listeners: SubscriberSet<EntityId, ReleaseListener>,
     pub(crate) global_observers: SubscriberSet<TypeId, Handler>,
     pub(crate) quit_observers: SubscriberSet<(), QuitHandler>,
+    pub(crate) clipboard_observers: SubscriberSet<(), Handler>,
+    clipboard_history: RefCell<ClipboardHistory>,
+    /// Whether the platform reports clipboard changes, so the clipboard doesn't need polling.
+    observes_clipboard: bool,
+    polling_clipboard: Cell<bool>,
     pub(crate) layout_id_buffer: Vec<LayoutId>, // We recycle this memory across layout requests.
     pub(crate) propagate_event: bool,
     pub(crate) prompt_builder: Option<PromptBuilder>,
@@ -276,6 +305,7 @@ impl AppContext {
                 window_handles: FxHashMap::default(),
                 windows: SlotMap::with_key(),
                 keymap: Rc::new(RefCell::new(Keymap::default())),
+                pending_input_timeout: DEFAULT_PENDING_INPUT_TIMEOUT,
                 global_action_listeners: FxHashMap::default(),
                 pending_effects: VecDeque::new(),
                 pending_notifications: FxHashSet::default(),
@@ -286,6 +316,10 @@ impl AppContext {
                 keystroke_observers: SubscriberSet::new(),
                 global_observers: SubscriberSet::new(),
                 quit_observers: SubscriberSet::new(),
+                clipboard_observers: SubscriberSet::new(),
+                clipboard_history: RefCell::new(ClipboardHistory::new()),
+                observes_clipboard: false,
+                polling_clipboard: Cell::new(false),
                 layout_id_buffer: Default::default(),
                 propagate_event: true,
                 prompt_builder: Some(PromptBuilder::Default),
@@ -301,6 +335,26 @@ impl AppContext {
             }
         }));
 
+        let observes_clipboard = platform.on_clipboard_change(Box::new({
+            let app = Rc::downgrade(&app);
+            let executor = platform.foreground_executor();
+            move || {
+                // Handle the change once the platform is done reporting it, as it may do so
+                // while the app is being updated.
+                let app = app.clone();
+                executor
+                    .spawn(async move {
+                        if let Some(app) = app.upgrade() {
+                            let mut cx = app.borrow_mut();
+                            let item = cx.platform.read_from_clipboard();
+                            cx.update(|cx| cx.clipboard_changed(item));
+                        }
+                    })
+                    .detach();
+            }
+        }));
+        app.borrow_mut().observes_clipboard = observes_clipboard;
+
         app
     }
 
@@ -542,8 +596,11 @@ impl AppContext {
         self.platform.write_to_primary(item)
     }
 
-    /// Writes data to the platform clipboard.
+    /// Writes data to the platform clipboard, recording it in the clipboard history.
     pub fn write_to_clipboard(&self, item: ClipboardItem) {
+        self.clipboard_history
+            .borrow_mut()
+            .push(&item, ClipboardItemSource::App);
         self.platform.write_to_clipboard(item)
     }
 
@@ -559,6 +616,103 @@ impl AppContext {
         self.platform.read_from_clipboard()
     }
 
+    /// Register a callback to be invoked when the clipboard's contents change, whether they
+    /// were written by this app or copied in another application. Platforms that can't report
+    /// changes are polled for them while there are callbacks or the clipboard history is
+    /// enabled.
+    pub fn on_clipboard_change(
+        &self,
+        mut f: impl FnMut(&mut AppContext) + 'static,
+    ) -> Subscription {
+        let (subscription, activate) = self.clipboard_observers.insert(
+            (),
+            Box::new(move |cx| {
+                f(cx);
+                true
+            }),
+        );
+        activate();
+        self.poll_clipboard_if_needed();
+        subscription
+    }
+
+    /// Starts or stops recording the items that are put on the clipboard, which is disabled by
+    /// default. Disabling the history forgets the items it recorded.
+    pub fn set_clipboard_history_enabled(&mut self, enabled: bool) {
+        self.clipboard_history.borrow_mut().set_enabled(enabled);
+        self.poll_clipboard_if_needed();
+    }
+
+    /// Returns the most recent text items that were on the clipboard, newest first. The history
+    /// is kept in memory for as long as the app runs.
+    pub fn clipboard_history(&self) -> Vec<ClipboardHistoryEntry> {
+        self.clipboard_history.borrow().entries().cloned().collect()
+    }
+
+    /// Sets how many items the clipboard history keeps, dropping the oldest ones beyond it.
+    pub fn set_clipboard_history_capacity(&mut self, capacity: usize) {
+        self.clipboard_history.borrow_mut().set_capacity(capacity);
+    }
+
+    /// Sets which items are left out of the clipboard history, removing any it already has.
+    /// By default, items that [look like secrets](looks_like_secret) are left out.
+    pub fn set_clipboard_history_filter(
+        &mut self,
+        exclude: impl Fn(&ClipboardItem) -> bool + 'static,
+    ) {
+        self.clipboard_history
+            .borrow_mut()
+            .set_exclude(Box::new(exclude));
+    }
+
+    fn needs_clipboard_polling(&self) -> bool {
+        !self.observes_clipboard
+            && (!self.clipboard_observers.is_empty()
+                || self.clipboard_history.borrow().is_enabled())
+    }
+
+    /// Starts reading the clipboard periodically to look for changes, on platforms that can't
+    /// report them, until nothing is interested in them anymore.
+    fn poll_clipboard_if_needed(&self) {
+        if self.polling_clipboard.get() || !self.needs_clipboard_polling() {
+            return;
+        }
+        self.polling_clipboard.set(true);
+
+        let app = self.this.clone();
+        let background_executor = self.background_executor.clone();
+        self.foreground_executor
+            .spawn(async move {
+                let mut last_item = app.upgrade()?.borrow().read_from_clipboard();
+                loop {
+                    background_executor.timer(CLIPBOARD_POLL_INTERVAL).await;
+                    let app = app.upgrade()?;
+                    let mut cx = app.borrow_mut();
+                    if !cx.needs_clipboard_polling() {
+                        cx.polling_clipboard.set(false);
+                        return Some(());
+                    }
+                    let item = cx.read_from_clipboard();
+                    if item != last_item {
+                        last_item = item.clone();
+                        cx.update(|cx| cx.clipboard_changed(item));
+                    }
+                }
+            })
+            .detach();
+    }
+
+    fn clipboard_changed(&mut self, item: Option<ClipboardItem>) {
+        if let Some(item) = item {
+            self.clipboard_history
+                .borrow_mut()
+                .push(&item, ClipboardItemSource::External);
+        }
+        self.clipboard_observers
+            .clone()
+            .retain(&(), |observer| observer(self));
+    }
+
     /// Writes credentials to the platform keychain.
     pub fn write_credentials(
         &self,
@@ -848,6 +1002,26 @@ impl AppContext {
         self.foreground_executor.spawn(f(self.to_async()))
     }
 
+    /// Returns a [`DebounceHandle`] that invokes the given callback once `duration` has elapsed
+    /// since it was last triggered, coalescing bursts of triggers into a single call.
+    pub fn debounce(
+        &self,
+        duration: Duration,
+        callback: impl FnMut(&mut AppContext) + 'static,
+    ) -> DebounceHandle {
+        DebounceHandle::new(duration, callback, self.to_async())
+    }
+
+    /// Returns a [`ThrottleHandle`] that invokes the given callback as soon as it is triggered,
+    /// then ignores any triggers until `duration` has elapsed.
+    pub fn throttle(
+        &self,
+        duration: Duration,
+        callback: impl FnMut(&mut AppContext) + 'static,
+    ) -> ThrottleHandle {
+        ThrottleHandle::new(duration, callback)
+    }
+
     /// Schedules the given function to be run at the end of the current effect cycle, allowing entities
     /// that are currently on the stack to be returned to the app.
     pub fn defer(&mut self, f: impl FnOnce(&mut AppContext) + 'static) {
@@ -1050,6 +1224,40 @@ impl AppContext {
         self.pending_effects.push_back(Effect::Refresh);
     }
 
+    /// Returns the keystrokes and context predicates of every binding of the given action, with
+    /// overridden and unbound bindings left out. See [`Keymap::all_bindings_for_action`].
+    pub fn all_bindings_for_action(
+        &self,
+        action: &dyn Action,
+    ) -> Vec<(SmallVec<[Keystroke; 2]>, Option<KeyBindingContextPredicate>)> {
+        self.keymap.borrow().all_bindings_for_action(action)
+    }
+
+    /// Returns the action that typing the given keystrokes would invoke, given the key contexts
+    /// from the root of a window to its focused element.
+    pub fn action_for_keystrokes(
+        &self,
+        keystrokes: &[Keystroke],
+        context_stack: &[KeyContext],
+    ) -> Option<Box<dyn Action>> {
+        self.keymap
+            .borrow()
+            .action_for_keystrokes(keystrokes, context_stack)
+            .map(Action::boxed_clone)
+    }
+
+    /// How long a window waits for the next keystroke of a multi-stroke binding, such as
+    /// `ctrl-k ctrl-s`, before handling the keystrokes typed so far as normal input.
+    pub fn pending_input_timeout(&self) -> Duration {
+        self.pending_input_timeout
+    }
+
+    /// Set how long a window waits for the next keystroke of a multi-stroke binding.
+    /// Defaults to [`DEFAULT_PENDING_INPUT_TIMEOUT`].
+    pub fn set_pending_input_timeout(&mut self, timeout: Duration) {
+        self.pending_input_timeout = timeout;
+    }
+
     /// Register a global listener for actions invoked via the keyboard.
     pub fn on_action<A: Action>(&mut self, listener: impl Fn(&A, &mut Self) + 'static) {
         self.global_action_listeners
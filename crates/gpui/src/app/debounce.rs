@@ -0,0 +1,113 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+use util::ResultExt;
+
+use crate::{AppContext, AsyncAppContext, Task};
+
+/// A handle to a debounced callback, created with [`AppContext::debounce`].
+///
+/// Each call to [`DebounceHandle::trigger`] restarts the debounce window, so a
+/// burst of triggers results in a single invocation of the callback once the
+/// window elapses without further triggers. Clones of a handle share the same
+/// callback and window. Dropping every clone cancels any pending invocation.
+#[derive(Clone)]
+pub struct DebounceHandle(Rc<DebounceState>);
+
+struct DebounceState {
+    duration: Duration,
+    callback: RefCell<Box<dyn FnMut(&mut AppContext)>>,
+    pending: RefCell<Option<Task<()>>>,
+    cx: AsyncAppContext,
+}
+
+impl DebounceHandle {
+    pub(crate) fn new(
+        duration: Duration,
+        callback: impl FnMut(&mut AppContext) + 'static,
+        cx: AsyncAppContext,
+    ) -> Self {
+        Self(Rc::new(DebounceState {
+            duration,
+            callback: RefCell::new(Box::new(callback)),
+            pending: RefCell::new(None),
+            cx,
+        }))
+    }
+
+    /// Schedules the callback to run once the debounce duration has elapsed,
+    /// replacing any invocation that is already pending.
+    pub fn trigger(&self) {
+        let state = Rc::downgrade(&self.0);
+        let timer = self.0.cx.background_executor().timer(self.0.duration);
+        let task = self.0.cx.foreground_executor().spawn(async move {
+            timer.await;
+            Self::invoke(state);
+        });
+        // Dropping the previously pending task cancels it.
+        *self.0.pending.borrow_mut() = Some(task);
+    }
+
+    /// Returns whether an invocation of the callback is waiting to run.
+    pub fn is_pending(&self) -> bool {
+        self.0.pending.borrow().is_some()
+    }
+
+    fn invoke(state: Weak<DebounceState>) {
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        // The pending task is the one currently running, so detach it rather
+        // than cancelling it. This also allows the callback to trigger again.
+        if let Some(task) = state.pending.borrow_mut().take() {
+            task.detach();
+        }
+        state
+            .cx
+            .update(|cx| (state.callback.borrow_mut())(cx))
+            .log_err();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    use crate::{self as gpui, TestAppContext};
+
+    #[gpui::test]
+    fn test_debounce(cx: &mut TestAppContext) {
+        let call_count = Rc::new(Cell::new(0));
+        let handle = cx.update(|cx| {
+            let call_count = call_count.clone();
+            cx.debounce(Duration::from_millis(50), move |_| {
+                call_count.set(call_count.get() + 1)
+            })
+        });
+
+        // 100 triggers spread over 50ms coalesce into a single call.
+        let trigger_interval = Duration::from_micros(500);
+        for _ in 0..100 {
+            handle.clone().trigger();
+            cx.executor().advance_clock(trigger_interval);
+        }
+        assert_eq!(call_count.get(), 0);
+        assert!(handle.is_pending());
+
+        cx.executor().advance_clock(Duration::from_millis(50));
+        assert_eq!(call_count.get(), 1);
+        assert!(!handle.is_pending());
+
+        cx.executor().advance_clock(Duration::from_millis(500));
+        assert_eq!(call_count.get(), 1);
+
+        // Dropping the handle cancels the pending call.
+        handle.trigger();
+        drop(handle);
+        cx.executor().advance_clock(Duration::from_millis(500));
+        assert_eq!(call_count.get(), 1);
+    }
+}
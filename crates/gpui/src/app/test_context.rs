@@ -196,6 +196,27 @@ impl TestAppContext {
         .unwrap()
     }
 
+    /// Opens a new window with the given options and root view, which is closed when the returned
+    /// handle is dropped.
+    ///
+    /// Unlike [`Self::add_window`], the window uses the given options as they are rather than
+    /// filling the test display.
+    pub fn open_window_with<V>(
+        &mut self,
+        options: WindowOptions,
+        build_root_view: impl FnOnce(&mut ViewContext<V>) -> V,
+    ) -> crate::test::WindowHandle<V>
+    where
+        V: 'static + Render,
+    {
+        let handle = self
+            .app
+            .borrow_mut()
+            .open_window(options, |cx| cx.new_view(build_root_view))
+            .unwrap();
+        crate::test::WindowHandle::new(handle, Rc::downgrade(&self.app))
+    }
+
     /// Adds a new window with no content.
     pub fn add_empty_window(&mut self) -> &mut VisualTestContext {
         let mut cx = self.app.borrow_mut();
@@ -248,7 +269,7 @@ impl TestAppContext {
         &self.text_system
     }
 
-    /// Simulates writing to the platform clipboard
+    /// Simulates another application writing to the platform clipboard
     pub fn write_to_clipboard(&self, item: ClipboardItem) {
         self.test_platform.write_to_clipboard(item)
     }
@@ -287,6 +308,11 @@ impl TestAppContext {
         self.test_window(window_handle).simulate_resize(size);
     }
 
+    /// Simulates the platform requesting a new frame for the given window, e.g. on vsync.
+    pub fn simulate_window_frame(&self, window_handle: AnyWindowHandle) {
+        self.test_window(window_handle).simulate_frame();
+    }
+
     /// Returns all windows open in the test.
     pub fn windows(&self) -> Vec<AnyWindowHandle> {
         self.app.borrow().windows().clone()
@@ -762,6 +788,29 @@ impl VisualTestContext {
         self.simulate_window_resize(self.window, size)
     }
 
+    /// Simulates the platform requesting a new frame for this window, e.g. on vsync.
+    pub fn simulate_frame(&self) {
+        self.simulate_window_frame(self.window)
+    }
+
+    /// Simulates frames until no animations in the window are running, advancing the clock by
+    /// the duration of a frame before each of them.
+    pub fn finish_animations(&mut self) {
+        const FRAME_DURATION: Duration = Duration::from_millis(16);
+        const MAX_FRAMES: usize = 10_000;
+
+        for _ in 0..MAX_FRAMES {
+            self.run_until_parked();
+            let animating = self.update(|cx| !cx.window.next_frame_callbacks.borrow().is_empty());
+            if !animating {
+                return;
+            }
+            self.executor().advance_clock(FRAME_DURATION);
+            self.simulate_frame();
+        }
+        panic!("animations did not finish after {MAX_FRAMES} frames");
+    }
+
     /// debug_bounds returns the bounds of the element with the given selector.
     pub fn debug_bounds(&mut self, selector: &'static str) -> Option<Bounds<Pixels>> {
         self.update(|cx| cx.window.rendered_frame.debug_bounds.get(selector).copied())
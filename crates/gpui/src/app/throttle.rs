@@ -0,0 +1,119 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crate::AppContext;
+
+/// A handle to a throttled callback, created with [`AppContext::throttle`].
+///
+/// The first call to [`ThrottleHandle::trigger`] invokes the callback
+/// immediately, after which any triggers that occur within the throttle
+/// duration are dropped. Clones of a handle share the same callback and
+/// duration.
+#[derive(Clone)]
+pub struct ThrottleHandle(Rc<ThrottleState>);
+
+struct ThrottleState {
+    duration: Duration,
+    callback: RefCell<Box<dyn FnMut(&mut AppContext)>>,
+    last_invoked_at: Cell<Option<Instant>>,
+}
+
+impl ThrottleHandle {
+    pub(crate) fn new(duration: Duration, callback: impl FnMut(&mut AppContext) + 'static) -> Self {
+        Self(Rc::new(ThrottleState {
+            duration,
+            callback: RefCell::new(Box::new(callback)),
+            last_invoked_at: Cell::new(None),
+        }))
+    }
+
+    /// Invokes the callback, unless it was already invoked within the throttle
+    /// duration.
+    pub fn trigger(&self, cx: &mut AppContext) {
+        let now = cx.background_executor().now();
+        if let Some(last_invoked_at) = self.0.last_invoked_at.get() {
+            if now < last_invoked_at + self.0.duration {
+                return;
+            }
+        }
+
+        self.0.last_invoked_at.set(Some(now));
+        (self.0.callback.borrow_mut())(cx);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    use crate::{self as gpui, TestAppContext};
+
+    #[gpui::test]
+    fn test_throttle(cx: &mut TestAppContext) {
+        let call_count = Rc::new(Cell::new(0));
+        let handle = cx.update(|cx| {
+            let call_count = call_count.clone();
+            cx.throttle(Duration::from_millis(50), move |_| {
+                call_count.set(call_count.get() + 1)
+            })
+        });
+
+        // The first trigger fires immediately, and the rest of the burst is dropped.
+        for _ in 0..100 {
+            cx.update(|cx| handle.trigger(cx));
+            assert_eq!(call_count.get(), 1);
+            cx.executor().advance_clock(Duration::from_micros(400));
+        }
+
+        // Dropped triggers are not replayed once the duration has elapsed.
+        cx.executor().advance_clock(Duration::from_millis(50));
+        assert_eq!(call_count.get(), 1);
+
+        cx.update(|cx| handle.clone().trigger(cx));
+        assert_eq!(call_count.get(), 2);
+    }
+
+    #[gpui::test]
+    fn test_throttle_fires_on_leading_edge_and_debounce_on_trailing_edge(cx: &mut TestAppContext) {
+        let throttle_calls = Rc::new(Cell::new(0));
+        let debounce_calls = Rc::new(Cell::new(0));
+        let (throttle, debounce) = cx.update(|cx| {
+            let throttle_calls = throttle_calls.clone();
+            let debounce_calls = debounce_calls.clone();
+            (
+                cx.throttle(Duration::from_millis(50), move |_| {
+                    throttle_calls.set(throttle_calls.get() + 1)
+                }),
+                cx.debounce(Duration::from_millis(50), move |_| {
+                    debounce_calls.set(debounce_calls.get() + 1)
+                }),
+            )
+        });
+
+        cx.update(|cx| {
+            throttle.trigger(cx);
+            debounce.trigger();
+        });
+        assert_eq!(throttle_calls.get(), 1);
+        assert_eq!(debounce_calls.get(), 0);
+
+        // Triggering every 10ms for 100ms lets the throttle fire every 50ms,
+        // but keeps pushing back the debounce.
+        for _ in 0..10 {
+            cx.executor().advance_clock(Duration::from_millis(10));
+            cx.update(|cx| {
+                throttle.trigger(cx);
+                debounce.trigger();
+            });
+        }
+        assert_eq!(throttle_calls.get(), 3);
+        assert_eq!(debounce_calls.get(), 0);
+
+        cx.executor().advance_clock(Duration::from_millis(50));
+        assert_eq!(throttle_calls.get(), 3);
+        assert_eq!(debounce_calls.get(), 1);
+    }
+}
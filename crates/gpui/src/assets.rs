@@ -5,6 +5,7 @@ use std::{
     borrow::Cow,
     fmt,
     hash::Hash,
+    io::Cursor,
     sync::atomic::{AtomicUsize, Ordering::SeqCst},
 };
 
@@ -64,8 +65,44 @@ impl ImageData {
         let (width, height) = self.data.dimensions();
         size(width.into(), height.into())
     }
+
+    /// Decode an image from PNG data, like the images found on the clipboard.
+    pub fn from_png(bytes: &[u8]) -> Result<Self> {
+        let mut data =
+            image::load_from_memory_with_format(bytes, image::ImageFormat::Png)?.into_rgba8();
+
+        // Convert from RGBA to BGRA.
+        for pixel in data.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+
+        Ok(Self::new(data))
+    }
+
+    /// Encode this image as PNG data.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let mut data = self.data.clone();
+
+        // Convert from BGRA to RGBA.
+        for pixel in data.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+
+        let mut bytes = Vec::new();
+        data.write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+        Ok(bytes)
+    }
 }
 
+/// Images are equal if they have the same pixels, regardless of their IDs.
+impl PartialEq for ImageData {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl Eq for ImageData {}
+
 impl fmt::Debug for ImageData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ImageData")
@@ -19,6 +19,8 @@ pub struct Anchored {
     fit_mode: AnchoredFitMode,
     anchor_position: Option<Point<Pixels>>,
     position_mode: AnchoredPositionMode,
+    beside: Option<(Bounds<Pixels>, AnchoredSide)>,
+    offset: Pixels,
 }
 
 /// anchored gives you an element that will avoid overflowing the window bounds.
@@ -30,6 +32,8 @@ pub fn anchored() -> Anchored {
         fit_mode: AnchoredFitMode::SwitchAnchor,
         anchor_position: None,
         position_mode: AnchoredPositionMode::Window,
+        beside: None,
+        offset: Pixels::ZERO,
     }
 }
 
@@ -55,6 +59,21 @@ impl Anchored {
         self
     }
 
+    /// Places the anchored element on a side of the given bounds, in window coordinates, instead
+    /// of anchoring one of its corners to a position. It flips to the opposite side of the bounds
+    /// when it only fits there, and is snapped inside the window otherwise.
+    pub fn beside(mut self, bounds: Bounds<Pixels>, side: AnchoredSide) -> Self {
+        self.beside = Some((bounds, side));
+        self
+    }
+
+    /// Sets the gap between the anchored element and the bounds it is placed
+    /// [beside](Self::beside).
+    pub fn offset(mut self, offset: impl Into<Pixels>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+
     /// Snap to window edge instead of switching anchor corner when an overflow would occur.
     pub fn snap_to_window(mut self) -> Self {
         self.fit_mode = AnchoredFitMode::SnapToWindow;
@@ -118,58 +137,46 @@ impl Element for Anchored {
         }
         let size: Size<Pixels> = (child_max - child_min).into();
 
-        let (origin, mut desired) = self.position_mode.get_position_and_bounds(
-            self.anchor_position,
-            self.anchor_corner,
-            size,
-            bounds,
-        );
-
         let limits = Bounds {
             origin: Point::default(),
             size: cx.viewport_size(),
         };
 
-        if self.fit_mode == AnchoredFitMode::SwitchAnchor {
-            let mut anchor_corner = self.anchor_corner;
-
-            if desired.left() < limits.left() || desired.right() > limits.right() {
-                let switched = anchor_corner
-                    .switch_axis(Axis::Horizontal)
-                    .get_bounds(origin, size);
-                if !(switched.left() < limits.left() || switched.right() > limits.right()) {
-                    anchor_corner = anchor_corner.switch_axis(Axis::Horizontal);
-                    desired = switched
+        let desired = if let Some((anchor_bounds, side)) = self.beside {
+            side.get_bounds(anchor_bounds, size, self.offset, limits)
+        } else {
+            let (origin, mut desired) = self.position_mode.get_position_and_bounds(
+                self.anchor_position,
+                self.anchor_corner,
+                size,
+                bounds,
+            );
+
+            if self.fit_mode == AnchoredFitMode::SwitchAnchor {
+                let mut anchor_corner = self.anchor_corner;
+
+                if desired.left() < limits.left() || desired.right() > limits.right() {
+                    let switched = anchor_corner
+                        .switch_axis(Axis::Horizontal)
+                        .get_bounds(origin, size);
+                    if !(switched.left() < limits.left() || switched.right() > limits.right()) {
+                        anchor_corner = anchor_corner.switch_axis(Axis::Horizontal);
+                        desired = switched
+                    }
                 }
-            }
 
-            if desired.top() < limits.top() || desired.bottom() > limits.bottom() {
-                let switched = anchor_corner
-                    .switch_axis(Axis::Vertical)
-                    .get_bounds(origin, size);
-                if !(switched.top() < limits.top() || switched.bottom() > limits.bottom()) {
-                    desired = switched;
+                if desired.top() < limits.top() || desired.bottom() > limits.bottom() {
+                    let switched = anchor_corner
+                        .switch_axis(Axis::Vertical)
+                        .get_bounds(origin, size);
+                    if !(switched.top() < limits.top() || switched.bottom() > limits.bottom()) {
+                        desired = switched;
+                    }
                 }
             }
-        }
-
-        // Snap the horizontal edges of the anchored element to the horizontal edges of the window if
-        // its horizontal bounds overflow, aligning to the left if it is wider than the limits.
-        if desired.right() > limits.right() {
-            desired.origin.x -= desired.right() - limits.right();
-        }
-        if desired.left() < limits.left() {
-            desired.origin.x = limits.origin.x;
-        }
-
-        // Snap the vertical edges of the anchored element to the vertical edges of the window if
-        // its vertical bounds overflow, aligning to the top if it is taller than the limits.
-        if desired.bottom() > limits.bottom() {
-            desired.origin.y -= desired.bottom() - limits.bottom();
-        }
-        if desired.top() < limits.top() {
-            desired.origin.y = limits.origin.y;
-        }
+            desired
+        };
+        let desired = snap_to_limits(desired, limits);
 
         let offset = desired.origin - bounds.origin;
         let offset = point(offset.x.round(), offset.y.round());
@@ -203,6 +210,29 @@ impl IntoElement for Anchored {
     }
 }
 
+/// Moves bounds that overflow the limits back inside them, aligning them to the top left of the
+/// limits if they are larger.
+fn snap_to_limits(mut bounds: Bounds<Pixels>, limits: Bounds<Pixels>) -> Bounds<Pixels> {
+    // Snap the horizontal edges of the anchored element to the horizontal edges of the window if
+    // its horizontal bounds overflow, aligning to the left if it is wider than the limits.
+    if bounds.right() > limits.right() {
+        bounds.origin.x -= bounds.right() - limits.right();
+    }
+    if bounds.left() < limits.left() {
+        bounds.origin.x = limits.origin.x;
+    }
+
+    // Snap the vertical edges of the anchored element to the vertical edges of the window if
+    // its vertical bounds overflow, aligning to the top if it is taller than the limits.
+    if bounds.bottom() > limits.bottom() {
+        bounds.origin.y -= bounds.bottom() - limits.bottom();
+    }
+    if bounds.top() < limits.top() {
+        bounds.origin.y = limits.origin.y;
+    }
+    bounds
+}
+
 enum Axis {
     Horizontal,
     Vertical,
@@ -249,6 +279,80 @@ impl AnchoredPositionMode {
     }
 }
 
+/// Which side of the bounds it is placed [beside](Anchored::beside) the anchored element is on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnchoredSide {
+    /// Above the bounds, aligned with their left edge
+    Top,
+    /// Below the bounds, aligned with their left edge
+    Bottom,
+    /// Left of the bounds, aligned with their top edge
+    Left,
+    /// Right of the bounds, aligned with their top edge
+    Right,
+}
+
+impl AnchoredSide {
+    fn get_bounds(
+        self,
+        anchor_bounds: Bounds<Pixels>,
+        size: Size<Pixels>,
+        offset: Pixels,
+        limits: Bounds<Pixels>,
+    ) -> Bounds<Pixels> {
+        let desired = self.place(anchor_bounds, size, offset);
+        if !self.fits(desired, limits) {
+            let switched = self.opposite().place(anchor_bounds, size, offset);
+            if self.fits(switched, limits) {
+                return switched;
+            }
+        }
+        desired
+    }
+
+    fn place(
+        self,
+        anchor_bounds: Bounds<Pixels>,
+        size: Size<Pixels>,
+        offset: Pixels,
+    ) -> Bounds<Pixels> {
+        let origin = match self {
+            Self::Top => point(
+                anchor_bounds.left(),
+                anchor_bounds.top() - offset - size.height,
+            ),
+            Self::Bottom => point(anchor_bounds.left(), anchor_bounds.bottom() + offset),
+            Self::Left => point(
+                anchor_bounds.left() - offset - size.width,
+                anchor_bounds.top(),
+            ),
+            Self::Right => point(anchor_bounds.right() + offset, anchor_bounds.top()),
+        };
+        Bounds { origin, size }
+    }
+
+    /// Whether the bounds fit inside the limits along the axis the side is on.
+    fn fits(self, bounds: Bounds<Pixels>, limits: Bounds<Pixels>) -> bool {
+        match self {
+            Self::Top | Self::Bottom => {
+                bounds.top() >= limits.top() && bounds.bottom() <= limits.bottom()
+            }
+            Self::Left | Self::Right => {
+                bounds.left() >= limits.left() && bounds.right() <= limits.right()
+            }
+        }
+    }
+
+    fn opposite(self) -> Self {
+        match self {
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
+
 /// Which corner of the anchored element should be considered the anchor.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum AnchorCorner {
@@ -310,3 +414,66 @@ impl AnchorCorner {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{px, size};
+
+    fn bounds(x: f32, y: f32, width: f32, height: f32) -> Bounds<Pixels> {
+        Bounds::new(point(px(x), px(y)), size(px(width), px(height)))
+    }
+
+    fn beside_bounds(
+        anchor_bounds: Bounds<Pixels>,
+        size: Size<Pixels>,
+        side: AnchoredSide,
+        offset: f32,
+    ) -> Bounds<Pixels> {
+        let limits = bounds(0., 0., 400., 300.);
+        let desired = side.get_bounds(anchor_bounds, size, px(offset), limits);
+        snap_to_limits(desired, limits)
+    }
+
+    #[test]
+    fn test_beside_bounds() {
+        let menu = size(px(100.), px(80.));
+        let anchor_bounds = bounds(20., 20., 60., 20.);
+
+        assert_eq!(
+            beside_bounds(anchor_bounds, menu, AnchoredSide::Bottom, 4.),
+            bounds(20., 44., 100., 80.)
+        );
+        assert_eq!(
+            beside_bounds(anchor_bounds, menu, AnchoredSide::Right, 4.),
+            bounds(84., 20., 100., 80.)
+        );
+
+        // There's no room above the bounds, so the element flips below them.
+        assert_eq!(
+            beside_bounds(anchor_bounds, menu, AnchoredSide::Top, 4.),
+            bounds(20., 44., 100., 80.)
+        );
+
+        // Near the bottom right corner, the element flips above the bounds and is shifted left
+        // to stay inside the window.
+        let anchor_bounds = bounds(340., 250., 50., 20.);
+        assert_eq!(
+            beside_bounds(anchor_bounds, menu, AnchoredSide::Bottom, 4.),
+            bounds(300., 166., 100., 80.)
+        );
+        assert_eq!(
+            beside_bounds(anchor_bounds, menu, AnchoredSide::Right, 4.),
+            bounds(236., 220., 100., 80.)
+        );
+
+        // When neither side has room, the element stays on its side and is snapped inside the
+        // window.
+        let tall_menu = size(px(100.), px(200.));
+        let anchor_bounds = bounds(20., 140., 60., 20.);
+        assert_eq!(
+            beside_bounds(anchor_bounds, tall_menu, AnchoredSide::Bottom, 0.),
+            bounds(20., 100., 100., 200.)
+        );
+    }
+}
@@ -1,6 +1,13 @@
-use std::time::{Duration, Instant};
+use std::{
+    fmt::Debug,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use crate::{AnyElement, Element, ElementId, GlobalElementId, IntoElement};
+use crate::{
+    AnyElement, AppContext, Element, ElementId, GlobalElementId, Hsla, IntoElement, Pixels, Point,
+    Size, ViewContext,
+};
 
 pub use easing::*;
 
@@ -89,8 +96,8 @@ impl<E: IntoElement + 'static> IntoElement for AnimationElement<E> {
     }
 }
 
-struct AnimationState {
-    start: Instant,
+struct AnimationElementState {
+    clock: AnimationClock,
 }
 
 impl<E: IntoElement + 'static> Element for AnimationElement<E> {
@@ -107,11 +114,12 @@ impl<E: IntoElement + 'static> Element for AnimationElement<E> {
         cx: &mut crate::WindowContext,
     ) -> (crate::LayoutId, Self::RequestLayoutState) {
         cx.with_element_state(global_id.unwrap(), |state, cx| {
-            let state = state.unwrap_or_else(|| AnimationState {
-                start: Instant::now(),
+            let now = cx.background_executor().now();
+            let mut state = state.unwrap_or_else(|| AnimationElementState {
+                clock: AnimationClock::new(now),
             });
             let mut delta =
-                state.start.elapsed().as_secs_f32() / self.animation.duration.as_secs_f32();
+                state.clock.tick(now).as_secs_f32() / self.animation.duration.as_secs_f32();
 
             let mut done = false;
             if delta > 1.0 {
@@ -169,6 +177,162 @@ impl<E: IntoElement + 'static> Element for AnimationElement<E> {
     }
 }
 
+/// The longest time an animation can advance by between two consecutive frames.
+const MAX_FRAME_DELTA: Duration = Duration::from_millis(100);
+
+/// Measures how long an animation has been running, based on the frames in which it was
+/// ticked. Gaps between frames are clamped to [`MAX_FRAME_DELTA`], so animations pause while
+/// the window isn't producing frames, e.g. because it's occluded.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct AnimationClock {
+    elapsed: Duration,
+    last_tick: Instant,
+}
+
+impl AnimationClock {
+    pub(crate) fn new(now: Instant) -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+            last_tick: now,
+        }
+    }
+
+    /// Advances the clock to the given time, returning the total time elapsed.
+    pub(crate) fn tick(&mut self, now: Instant) -> Duration {
+        let delta = now.saturating_duration_since(self.last_tick);
+        self.elapsed += delta.min(MAX_FRAME_DELTA);
+        self.last_tick = now;
+        self.elapsed
+    }
+}
+
+/// A value that can be animated, by interpolating between a start and an end value.
+pub trait Interpolate {
+    /// Returns the value at the given delta between `self` (0) and `other` (1).
+    fn interpolate(&self, other: &Self, delta: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, delta: f32) -> Self {
+        self + (other - self) * delta
+    }
+}
+
+impl Interpolate for Pixels {
+    fn interpolate(&self, other: &Self, delta: f32) -> Self {
+        Pixels(self.0.interpolate(&other.0, delta))
+    }
+}
+
+impl Interpolate for Hsla {
+    fn interpolate(&self, other: &Self, delta: f32) -> Self {
+        Hsla {
+            h: self.h.interpolate(&other.h, delta),
+            s: self.s.interpolate(&other.s, delta),
+            l: self.l.interpolate(&other.l, delta),
+            a: self.a.interpolate(&other.a, delta),
+        }
+    }
+}
+
+impl<T: Interpolate + Clone + Default + Debug> Interpolate for Point<T> {
+    fn interpolate(&self, other: &Self, delta: f32) -> Self {
+        Point {
+            x: self.x.interpolate(&other.x, delta),
+            y: self.y.interpolate(&other.y, delta),
+        }
+    }
+}
+
+impl<T: Interpolate + Clone + Default + Debug> Interpolate for Size<T> {
+    fn interpolate(&self, other: &Self, delta: f32) -> Self {
+        Size {
+            width: self.width.interpolate(&other.width, delta),
+            height: self.height.interpolate(&other.height, delta),
+        }
+    }
+}
+
+/// A value stored in a view that animates towards a new target whenever it changes, e.g. the
+/// width of a panel. Read it with [`AnimationState::value`] when rendering, which re-renders the
+/// view on every frame until the value settles.
+pub struct AnimationState<T> {
+    from: T,
+    to: T,
+    duration: Duration,
+    easing: Rc<dyn Fn(f32) -> f32>,
+    clock: Option<AnimationClock>,
+}
+
+impl<T: Interpolate + Clone + PartialEq> AnimationState<T> {
+    /// Create a new animation state that is settled at the given value.
+    /// By default, changes are animated with a linear easing function.
+    pub fn new(value: T, duration: Duration) -> Self {
+        Self {
+            from: value.clone(),
+            to: value,
+            duration,
+            easing: Rc::new(linear),
+            clock: None,
+        }
+    }
+
+    /// Set the easing function used to animate changes of the value.
+    pub fn with_easing(mut self, easing: impl Fn(f32) -> f32 + 'static) -> Self {
+        self.easing = Rc::new(easing);
+        self
+    }
+
+    /// The value this state is animating towards, or is settled at.
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+
+    /// Whether the value has reached its target.
+    pub fn is_settled(&self) -> bool {
+        self.clock.is_none()
+    }
+
+    /// Start animating towards the given target, from the current value.
+    pub fn set_target(&mut self, target: T, cx: &AppContext) {
+        if target == self.to {
+            return;
+        }
+        let now = cx.background_executor().now();
+        self.from = self.value_at(now);
+        self.to = target;
+        self.clock = Some(AnimationClock::new(now));
+    }
+
+    /// Jump to the target, skipping the rest of the animation.
+    pub fn finish(&mut self) {
+        self.from = self.to.clone();
+        self.clock = None;
+    }
+
+    /// Returns the current value, and schedules the view to be rendered again on the next frame
+    /// if the value hasn't settled yet.
+    pub fn value<V: 'static>(&mut self, cx: &mut ViewContext<V>) -> T {
+        let value = self.value_at(cx.background_executor().now());
+        if !self.is_settled() {
+            cx.on_next_frame(|_, cx| cx.notify());
+        }
+        value
+    }
+
+    fn value_at(&mut self, now: Instant) -> T {
+        let Some(clock) = self.clock.as_mut() else {
+            return self.to.clone();
+        };
+        let delta = clock.tick(now).as_secs_f32() / self.duration.as_secs_f32();
+        if delta >= 1.0 || self.duration.is_zero() {
+            self.finish();
+            return self.to.clone();
+        }
+        self.from.interpolate(&self.to, (self.easing)(delta))
+    }
+}
+
 mod easing {
     /// The linear easing function, or delta itself
     pub fn linear(delta: f32) -> f32 {
@@ -190,6 +354,13 @@ mod easing {
         }
     }
 
+    /// A critically damped spring, which quickly approaches its target and then slowly settles
+    pub fn spring(delta: f32) -> f32 {
+        const STIFFNESS: f32 = 8.0;
+        let position = |t: f32| 1.0 - (1.0 + STIFFNESS * t) * (-STIFFNESS * t).exp();
+        position(delta) / position(1.0)
+    }
+
     /// Apply the given easing function, first in the forward direction and then in the reverse direction
     pub fn bounce(easing: impl Fn(f32) -> f32) -> impl Fn(f32) -> f32 {
         move |delta| {
@@ -201,3 +372,127 @@ mod easing {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as gpui, div, px, Render, Styled, TestAppContext};
+    use futures::FutureExt as _;
+
+    struct AnimatedView {
+        width: AnimationState<Pixels>,
+        rendered_widths: Vec<Pixels>,
+        animated_values: Vec<f32>,
+    }
+
+    impl AnimatedView {
+        fn new() -> Self {
+            Self {
+                width: AnimationState::new(px(100.), Duration::from_millis(200)),
+                rendered_widths: Vec::new(),
+                animated_values: Vec::new(),
+            }
+        }
+
+        fn last_width(&self) -> f32 {
+            self.rendered_widths.last().unwrap().0
+        }
+    }
+
+    impl Render for AnimatedView {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            let width = self.width.value(cx);
+            self.rendered_widths.push(width);
+            div().w(width)
+        }
+    }
+
+    fn assert_approx_eq(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_easing() {
+        for easing in [linear, quadratic, ease_in_out, spring] {
+            assert_approx_eq(easing(0.), 0.);
+            assert_approx_eq(easing(1.), 1.);
+            let values = (0..=10).map(|i| easing(i as f32 / 10.)).collect::<Vec<_>>();
+            assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+        assert!(spring(0.25) > ease_in_out(0.25));
+    }
+
+    #[gpui::test]
+    fn test_animation_state(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|_| AnimatedView::new());
+        cx.run_until_parked();
+        view.update(cx, |view, cx| {
+            assert_eq!(view.last_width(), 100.);
+            view.width.set_target(px(200.), cx);
+            cx.notify();
+        });
+
+        cx.executor().advance_clock(Duration::from_millis(50));
+        cx.simulate_frame();
+        view.update(cx, |view, _| assert_approx_eq(view.last_width(), 125.));
+
+        // No frames are produced while the window is occluded, so the animation pauses.
+        cx.executor().advance_clock(Duration::from_secs(10));
+        cx.simulate_frame();
+        view.update(cx, |view, _| {
+            assert_approx_eq(view.last_width(), 175.);
+            assert!(!view.width.is_settled());
+        });
+
+        cx.finish_animations();
+        let render_count = view.update(cx, |view, _| {
+            assert_eq!(view.last_width(), 200.);
+            assert!(view.width.is_settled());
+            view.rendered_widths.len()
+        });
+
+        // Once settled, the view stops scheduling frames and isn't rendered anymore.
+        for _ in 0..3 {
+            cx.executor().advance_clock(Duration::from_millis(16));
+            cx.simulate_frame();
+        }
+        assert!(cx.update(|cx| cx.window.next_frame_callbacks.borrow().is_empty()));
+        view.update(cx, |view, _| {
+            assert_eq!(view.rendered_widths.len(), render_count)
+        });
+    }
+
+    #[gpui::test]
+    fn test_animate(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|_| AnimatedView::new());
+        cx.run_until_parked();
+
+        let task = view.update(cx, |_, cx| {
+            cx.animate(Duration::from_millis(100), ease_in_out, |view, delta, _| {
+                view.animated_values.push(delta)
+            })
+        });
+        cx.finish_animations();
+        assert_eq!(task.now_or_never(), Some(()));
+        view.update(cx, |view, _| {
+            let values = &view.animated_values;
+            assert!(values.len() > 2);
+            assert_eq!(values.last(), Some(&1.));
+            assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+            view.animated_values.clear();
+        });
+
+        // Dropping the task stops the animation.
+        let task = view.update(cx, |_, cx| {
+            cx.animate(Duration::from_millis(100), linear, |view, delta, _| {
+                view.animated_values.push(delta)
+            })
+        });
+        drop(task);
+        cx.finish_animations();
+        view.update(cx, |view, _| assert!(view.animated_values.is_empty()));
+    }
+}
@@ -1084,6 +1084,17 @@ pub fn div() -> Div {
     }
 }
 
+/// Construct a container that scrolls its children in both axes when they overflow it.
+///
+/// Scroll wheel events go to the innermost scrollable container under the mouse that can still
+/// scroll in their direction, so that scrolling past the end of a nested container scrolls its
+/// parent instead. Use [`StatefulInteractiveElement::track_scroll`] to control the scroll offset
+/// from code.
+#[track_caller]
+pub fn scrollable(id: impl Into<ElementId>) -> Stateful<Div> {
+    div().id(id).overflow_scroll()
+}
+
 /// A [`Div`] element, the all-in-one element for building complex UIs in GPUI
 pub struct Div {
     interactivity: Interactivity,
@@ -1427,37 +1438,40 @@ impl Interactivity {
                 scroll_handle.0.borrow_mut().overflow = style.overflow;
             }
 
-            let rem_size = cx.rem_size();
-            let padding_size = size(
-                style
-                    .padding
-                    .left
-                    .to_pixels(bounds.size.width.into(), rem_size)
-                    + style
-                        .padding
-                        .right
-                        .to_pixels(bounds.size.width.into(), rem_size),
-                style
-                    .padding
-                    .top
-                    .to_pixels(bounds.size.height.into(), rem_size)
-                    + style
-                        .padding
-                        .bottom
-                        .to_pixels(bounds.size.height.into(), rem_size),
-            );
-            let scroll_max = (self.content_size + padding_size - bounds.size).max(&Size::default());
+            let scroll_max = self.scroll_max(bounds, style, cx.rem_size());
             // Clamp scroll offset in case scroll max is smaller now (e.g., if children
             // were removed or the bounds became larger).
             let mut scroll_offset = scroll_offset.borrow_mut();
-            scroll_offset.x = scroll_offset.x.clamp(-scroll_max.width, px(0.));
-            scroll_offset.y = scroll_offset.y.clamp(-scroll_max.height, px(0.));
+            *scroll_offset = clamp_scroll_offset(*scroll_offset, scroll_max);
             *scroll_offset
         } else {
             Point::default()
         }
     }
 
+    /// The furthest this element's content can be scrolled in each axis.
+    fn scroll_max(&self, bounds: Bounds<Pixels>, style: &Style, rem_size: Pixels) -> Size<Pixels> {
+        let padding_size = size(
+            style
+                .padding
+                .left
+                .to_pixels(bounds.size.width.into(), rem_size)
+                + style
+                    .padding
+                    .right
+                    .to_pixels(bounds.size.width.into(), rem_size),
+            style
+                .padding
+                .top
+                .to_pixels(bounds.size.height.into(), rem_size)
+                + style
+                    .padding
+                    .bottom
+                    .to_pixels(bounds.size.height.into(), rem_size),
+        );
+        (self.content_size + padding_size - bounds.size).max(&Size::default())
+    }
+
     /// Paint this element according to this interactivity state's configured styles
     /// and bind the element's mouse and keyboard events.
     ///
@@ -1497,35 +1511,44 @@ impl Interactivity {
                     return ((), element_state);
                 }
 
-                style.paint(bounds, cx, |cx: &mut WindowContext| {
-                    cx.with_text_style(style.text_style().cloned(), |cx| {
-                        cx.with_content_mask(style.overflow_mask(bounds, cx.rem_size()), |cx| {
-                            if let Some(hitbox) = hitbox {
-                                #[cfg(debug_assertions)]
-                                self.paint_debug_info(global_id, hitbox, &style, cx);
-
-                                if !cx.has_active_drag() {
-                                    if let Some(mouse_cursor) = style.mouse_cursor {
-                                        cx.set_cursor_style(mouse_cursor, hitbox);
+                cx.with_element_opacity(style.opacity, |cx| {
+                    style.paint(bounds, cx, |cx: &mut WindowContext| {
+                        cx.with_text_style(style.text_style().cloned(), |cx| {
+                            cx.with_content_mask(
+                                style.overflow_mask(bounds, cx.rem_size()),
+                                |cx| {
+                                    if let Some(hitbox) = hitbox {
+                                        #[cfg(debug_assertions)]
+                                        self.paint_debug_info(global_id, hitbox, &style, cx);
+
+                                        if !cx.has_active_drag() {
+                                            if let Some(mouse_cursor) = style.mouse_cursor {
+                                                cx.set_cursor_style(mouse_cursor, hitbox);
+                                            }
+                                        }
+
+                                        if let Some(group) = self.group.clone() {
+                                            GroupHitboxes::push(group, hitbox.id, cx);
+                                        }
+
+                                        self.paint_mouse_listeners(
+                                            hitbox,
+                                            element_state.as_mut(),
+                                            cx,
+                                        );
+                                        self.paint_scroll_listener(hitbox, &style, cx);
                                     }
-                                }
-
-                                if let Some(group) = self.group.clone() {
-                                    GroupHitboxes::push(group, hitbox.id, cx);
-                                }
-
-                                self.paint_mouse_listeners(hitbox, element_state.as_mut(), cx);
-                                self.paint_scroll_listener(hitbox, &style, cx);
-                            }
 
-                            self.paint_keyboard_listeners(cx);
-                            f(&style, cx);
+                                    self.paint_keyboard_listeners(cx);
+                                    f(&style, cx);
 
-                            if hitbox.is_some() {
-                                if let Some(group) = self.group.as_ref() {
-                                    GroupHitboxes::pop(group, cx);
-                                }
-                            }
+                                    if hitbox.is_some() {
+                                        if let Some(group) = self.group.as_ref() {
+                                            GroupHitboxes::pop(group, cx);
+                                        }
+                                    }
+                                },
+                            );
                         });
                     });
                 });
@@ -2046,37 +2069,22 @@ impl Interactivity {
         if let Some(scroll_offset) = self.scroll_offset.clone() {
             let overflow = style.overflow;
             let line_height = cx.line_height();
+            let scroll_max = self.scroll_max(hitbox.bounds, style, cx.rem_size());
             let hitbox = hitbox.clone();
             cx.on_mouse_event(move |event: &ScrollWheelEvent, phase, cx| {
                 if phase == DispatchPhase::Bubble && hitbox.is_hovered(cx) {
-                    let mut scroll_offset = scroll_offset.borrow_mut();
-                    let old_scroll_offset = *scroll_offset;
+                    // Precise deltas from trackpads, including the platform's momentum events,
+                    // are applied as they are, while wheel deltas are measured in lines.
                     let delta = event.delta.pixel_delta(line_height);
-
-                    if overflow.x == Overflow::Scroll {
-                        let mut delta_x = Pixels::ZERO;
-                        if !delta.x.is_zero() {
-                            delta_x = delta.x;
-                        } else if overflow.y != Overflow::Scroll {
-                            delta_x = delta.y;
-                        }
-
-                        scroll_offset.x += delta_x;
-                    }
-
-                    if overflow.y == Overflow::Scroll {
-                        let mut delta_y = Pixels::ZERO;
-                        if !delta.y.is_zero() {
-                            delta_y = delta.y;
-                        } else if overflow.x != Overflow::Scroll {
-                            delta_y = delta.x;
-                        }
-
-                        scroll_offset.y += delta_y;
-                    }
-
-                    cx.stop_propagation();
-                    if *scroll_offset != old_scroll_offset {
+                    let mut scroll_offset = scroll_offset.borrow_mut();
+                    let new_scroll_offset =
+                        apply_scroll_delta(*scroll_offset, delta, overflow, scroll_max);
+
+                    // Leave events this element can't scroll any further in their direction
+                    // to the scrollable elements containing it.
+                    if new_scroll_offset != *scroll_offset {
+                        *scroll_offset = new_scroll_offset;
+                        cx.stop_propagation();
                         cx.refresh();
                     }
                 }
@@ -2437,6 +2445,69 @@ where
     }
 }
 
+/// Keeps a scroll offset between the start of the content and `scroll_max`. Offsets are
+/// negative as the content moves up and left.
+fn clamp_scroll_offset(offset: Point<Pixels>, scroll_max: Size<Pixels>) -> Point<Pixels> {
+    point(
+        offset.x.clamp(-scroll_max.width, px(0.)),
+        offset.y.clamp(-scroll_max.height, px(0.)),
+    )
+}
+
+/// Applies a scroll wheel delta to the axes that scroll. When only one axis scrolls, deltas
+/// along the other axis scroll it too, so that a vertical wheel can scroll horizontal content.
+fn apply_scroll_delta(
+    offset: Point<Pixels>,
+    delta: Point<Pixels>,
+    overflow: Point<Overflow>,
+    scroll_max: Size<Pixels>,
+) -> Point<Pixels> {
+    let mut new_offset = offset;
+    if overflow.x == Overflow::Scroll {
+        if !delta.x.is_zero() {
+            new_offset.x += delta.x;
+        } else if overflow.y != Overflow::Scroll {
+            new_offset.x += delta.y;
+        }
+    }
+    if overflow.y == Overflow::Scroll {
+        if !delta.y.is_zero() {
+            new_offset.y += delta.y;
+        } else if overflow.x != Overflow::Scroll {
+            new_offset.y += delta.x;
+        }
+    }
+    clamp_scroll_offset(new_offset, scroll_max)
+}
+
+/// The scroll offset that reveals `target`, painted at the current `offset`, in the given
+/// viewport. When `target` is larger than the viewport, its top left corner is revealed.
+fn reveal_scroll_offset(
+    offset: Point<Pixels>,
+    viewport: Bounds<Pixels>,
+    target: Bounds<Pixels>,
+    overflow: Point<Overflow>,
+) -> Point<Pixels> {
+    let mut new_offset = offset;
+    if overflow.y == Overflow::Scroll {
+        if target.top() < viewport.top() {
+            new_offset.y += viewport.top() - target.top();
+        } else if target.bottom() > viewport.bottom() {
+            new_offset.y -=
+                (target.bottom() - viewport.bottom()).min(target.top() - viewport.top());
+        }
+    }
+    if overflow.x == Overflow::Scroll {
+        if target.left() < viewport.left() {
+            new_offset.x += viewport.left() - target.left();
+        } else if target.right() > viewport.right() {
+            new_offset.x -=
+                (target.right() - viewport.right()).min(target.left() - viewport.left());
+        }
+    }
+    new_offset
+}
+
 #[derive(Default, Debug)]
 struct ScrollHandleState {
     offset: Rc<RefCell<Point<Pixels>>>,
@@ -2506,29 +2577,19 @@ impl ScrollHandle {
     /// scroll_to_item scrolls the minimal amount to ensure that the child is
     /// fully visible
     pub fn scroll_to_item(&self, ix: usize) {
-        let state = self.0.borrow();
-
-        let Some(bounds) = state.child_bounds.get(ix) else {
+        let Some(bounds) = self.bounds_for_item(ix) else {
             return;
         };
+        let offset = self.offset();
+        self.scroll_to_reveal(Bounds::new(bounds.origin + offset, bounds.size));
+    }
 
+    /// Scrolls the minimal amount to make the given bounds, in window coordinates as last
+    /// painted, visible. If they don't fit, their top left corner is revealed.
+    pub fn scroll_to_reveal(&self, bounds: Bounds<Pixels>) {
+        let state = self.0.borrow();
         let mut scroll_offset = state.offset.borrow_mut();
-
-        if state.overflow.y == Overflow::Scroll {
-            if bounds.top() + scroll_offset.y < state.bounds.top() {
-                scroll_offset.y = state.bounds.top() - bounds.top();
-            } else if bounds.bottom() + scroll_offset.y > state.bounds.bottom() {
-                scroll_offset.y = state.bounds.bottom() - bounds.bottom();
-            }
-        }
-
-        if state.overflow.x == Overflow::Scroll {
-            if bounds.left() + scroll_offset.x < state.bounds.left() {
-                scroll_offset.x = state.bounds.left() - bounds.left();
-            } else if bounds.right() + scroll_offset.x > state.bounds.right() {
-                scroll_offset.x = state.bounds.right() - bounds.right();
-            }
-        }
+        *scroll_offset = reveal_scroll_offset(*scroll_offset, state.bounds, bounds, state.overflow);
     }
 
     /// Set the offset explicitly. The offset is the distance from the top left of the
@@ -2559,3 +2620,312 @@ impl ScrollHandle {
         self.0.borrow_mut().requested_scroll_top = Some((ix, px));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        self as gpui, ExternalPaths, FileDropEvent, Modifiers, ScrollDelta, TestAppContext,
+        ViewContext, VisualContext,
+    };
+    use smallvec::smallvec;
+    use std::path::PathBuf;
+
+    fn bounds(x: f32, y: f32, width: f32, height: f32) -> Bounds<Pixels> {
+        Bounds::new(point(px(x), px(y)), size(px(width), px(height)))
+    }
+
+    #[test]
+    fn test_apply_scroll_delta() {
+        let both = point(Overflow::Scroll, Overflow::Scroll);
+        let vertical = point(Overflow::Visible, Overflow::Scroll);
+        let horizontal = point(Overflow::Scroll, Overflow::Visible);
+        let scroll_max = size(px(50.), px(100.));
+
+        assert_eq!(
+            apply_scroll_delta(
+                point(px(0.), px(0.)),
+                point(px(-10.), px(-20.)),
+                both,
+                scroll_max
+            ),
+            point(px(-10.), px(-20.))
+        );
+
+        // Offsets stop at the start and end of the content.
+        assert_eq!(
+            apply_scroll_delta(
+                point(px(-5.), px(-5.)),
+                point(px(10.), px(10.)),
+                both,
+                scroll_max
+            ),
+            point(px(0.), px(0.))
+        );
+        assert_eq!(
+            apply_scroll_delta(
+                point(px(-40.), px(-90.)),
+                point(px(-20.), px(-20.)),
+                both,
+                scroll_max
+            ),
+            point(px(-50.), px(-100.))
+        );
+
+        // Deltas along an axis that doesn't scroll are ignored, unless the other axis has none.
+        assert_eq!(
+            apply_scroll_delta(
+                point(px(0.), px(0.)),
+                point(px(-10.), px(-20.)),
+                vertical,
+                scroll_max
+            ),
+            point(px(0.), px(-20.))
+        );
+        assert_eq!(
+            apply_scroll_delta(
+                point(px(0.), px(0.)),
+                point(px(0.), px(-20.)),
+                horizontal,
+                scroll_max
+            ),
+            point(px(-20.), px(0.))
+        );
+
+        // Content that fits doesn't scroll.
+        assert_eq!(
+            apply_scroll_delta(
+                point(px(0.), px(0.)),
+                point(px(-10.), px(-20.)),
+                both,
+                Size::default()
+            ),
+            point(px(0.), px(0.))
+        );
+    }
+
+    #[test]
+    fn test_reveal_scroll_offset() {
+        let both = point(Overflow::Scroll, Overflow::Scroll);
+        let viewport = bounds(0., 100., 200., 100.);
+        let offset = point(px(0.), px(-50.));
+
+        // Visible bounds don't scroll.
+        assert_eq!(
+            reveal_scroll_offset(offset, viewport, bounds(10., 120., 20., 20.), both),
+            offset
+        );
+        // Bounds above or below the viewport are scrolled to its nearest edge.
+        assert_eq!(
+            reveal_scroll_offset(offset, viewport, bounds(10., 80., 20., 20.), both),
+            point(px(0.), px(-30.))
+        );
+        assert_eq!(
+            reveal_scroll_offset(offset, viewport, bounds(10., 210., 20., 20.), both),
+            point(px(0.), px(-80.))
+        );
+        assert_eq!(
+            reveal_scroll_offset(offset, viewport, bounds(250., 120., 20., 20.), both),
+            point(px(-70.), px(-50.))
+        );
+        // Bounds taller than the viewport have their top revealed.
+        assert_eq!(
+            reveal_scroll_offset(offset, viewport, bounds(10., 150., 20., 300.), both),
+            point(px(0.), px(-100.))
+        );
+        // Axes that don't scroll are left alone.
+        assert_eq!(
+            reveal_scroll_offset(
+                offset,
+                viewport,
+                bounds(250., 210., 20., 20.),
+                point(Overflow::Visible, Overflow::Scroll)
+            ),
+            point(px(0.), px(-80.))
+        );
+    }
+
+    struct NestedScrollView {
+        outer: ScrollHandle,
+        inner: ScrollHandle,
+    }
+
+    impl Render for NestedScrollView {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div().size_full().child(
+                div()
+                    .id("outer")
+                    .size(px(100.))
+                    .overflow_y_scroll()
+                    .track_scroll(&self.outer)
+                    .child(
+                        div()
+                            .id("inner")
+                            .w(px(100.))
+                            .h(px(50.))
+                            .overflow_y_scroll()
+                            .track_scroll(&self.inner)
+                            .child(div().w(px(100.)).h(px(200.))),
+                    )
+                    .child(div().w(px(100.)).h(px(300.))),
+            )
+        }
+    }
+
+    #[gpui::test]
+    fn test_nested_scrolling(cx: &mut TestAppContext) {
+        let outer = ScrollHandle::new();
+        let inner = ScrollHandle::new();
+        let (_, cx) = cx.add_window_view(|_| NestedScrollView {
+            outer: outer.clone(),
+            inner: inner.clone(),
+        });
+        cx.run_until_parked();
+
+        let mut scroll = |delta_y: f32| {
+            cx.simulate_event(ScrollWheelEvent {
+                position: point(px(10.), px(10.)),
+                delta: ScrollDelta::Pixels(point(px(0.), px(delta_y))),
+                ..Default::default()
+            });
+            cx.run_until_parked();
+            (outer.offset().y, inner.offset().y)
+        };
+
+        // The inner container scrolls until it reaches the end of its content.
+        assert_eq!(scroll(-100.), (px(0.), px(-100.)));
+        assert_eq!(scroll(-100.), (px(0.), px(-150.)));
+        // Then the outer one takes over, moving the inner one out from under the mouse.
+        assert_eq!(scroll(-100.), (px(-100.), px(-150.)));
+        assert_eq!(scroll(-500.), (px(-250.), px(-150.)));
+        assert_eq!(scroll(250.), (px(0.), px(-150.)));
+        // Back over the inner container, which can scroll up again.
+        assert_eq!(scroll(100.), (px(0.), px(-50.)));
+    }
+
+    #[derive(Clone)]
+    struct DraggedItem(&'static str);
+
+    impl Render for DraggedItem {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div().size(px(10.)).child(self.0)
+        }
+    }
+
+    #[derive(Default)]
+    struct DragAndDropView {
+        dropped_items: Vec<&'static str>,
+        dropped_paths: Vec<(Vec<PathBuf>, Point<Pixels>)>,
+    }
+
+    impl Render for DragAndDropView {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .flex()
+                .size_full()
+                .child(
+                    div()
+                        .id("source")
+                        .size(px(100.))
+                        .on_drag(DraggedItem("item"), |item, cx| {
+                            cx.new_view(|_| item.clone())
+                        }),
+                )
+                .child(
+                    div()
+                        .id("target")
+                        .size(px(100.))
+                        .on_drop(cx.listener(|this, item: &DraggedItem, _| {
+                            this.dropped_items.push(item.0)
+                        }))
+                        .on_drop(cx.listener(|this, paths: &ExternalPaths, cx| {
+                            this.dropped_paths
+                                .push((paths.paths().to_vec(), cx.mouse_position()))
+                        })),
+                )
+        }
+    }
+
+    #[gpui::test]
+    fn test_drag_and_drop(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|_| DragAndDropView::default());
+        cx.run_until_parked();
+
+        let source = point(px(50.), px(50.));
+        let target = point(px(150.), px(50.));
+        cx.simulate_mouse_down(source, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(source, MouseButton::Left, Modifiers::none());
+        assert!(!cx.update(|cx| cx.has_active_drag()));
+
+        // The drag starts once the mouse moved past the threshold.
+        cx.simulate_mouse_move(
+            point(px(60.), px(50.)),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        assert!(cx.update(|cx| cx.has_active_drag()));
+        cx.simulate_mouse_move(target, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(target, MouseButton::Left, Modifiers::none());
+        assert!(!cx.update(|cx| cx.has_active_drag()));
+        view.update(cx, |view, _| assert_eq!(view.dropped_items, ["item"]));
+
+        // Escape cancels the drag, so nothing is dropped.
+        cx.simulate_mouse_down(source, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(target, MouseButton::Left, Modifiers::none());
+        assert!(cx.update(|cx| cx.has_active_drag()));
+        cx.simulate_keystrokes("escape");
+        assert!(!cx.update(|cx| cx.has_active_drag()));
+        cx.simulate_mouse_up(target, MouseButton::Left, Modifiers::none());
+        view.update(cx, |view, _| assert_eq!(view.dropped_items, ["item"]));
+
+        // Dropping the item outside of the target doesn't drop it either.
+        cx.simulate_mouse_down(source, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(target, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(
+            point(px(250.), px(50.)),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        assert!(!cx.update(|cx| cx.has_active_drag()));
+        view.update(cx, |view, _| assert_eq!(view.dropped_items, ["item"]));
+    }
+
+    #[gpui::test]
+    fn test_external_file_drop(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|_| DragAndDropView::default());
+        cx.run_until_parked();
+
+        let paths = ExternalPaths(smallvec![PathBuf::from("/a.txt"), PathBuf::from("/b.txt")]);
+        cx.simulate_event(FileDropEvent::Entered {
+            position: point(px(50.), px(50.)),
+            paths: paths.clone(),
+        });
+        assert!(cx.update(|cx| cx.has_active_drag()));
+        cx.simulate_event(FileDropEvent::Pending {
+            position: point(px(150.), px(60.)),
+        });
+        cx.simulate_event(FileDropEvent::Submit {
+            position: point(px(150.), px(60.)),
+        });
+        assert!(!cx.update(|cx| cx.has_active_drag()));
+        view.update(cx, |view, _| {
+            assert_eq!(
+                view.dropped_paths,
+                [(
+                    vec![PathBuf::from("/a.txt"), PathBuf::from("/b.txt")],
+                    point(px(150.), px(60.))
+                )]
+            );
+            assert!(view.dropped_items.is_empty());
+        });
+
+        // Files that leave the window are not dropped.
+        cx.simulate_event(FileDropEvent::Entered {
+            position: point(px(150.), px(60.)),
+            paths,
+        });
+        cx.simulate_event(FileDropEvent::Exited);
+        assert!(!cx.update(|cx| cx.has_active_drag()));
+        view.update(cx, |view, _| assert_eq!(view.dropped_paths.len(), 1));
+    }
+}
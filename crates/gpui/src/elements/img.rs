@@ -3,10 +3,10 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::{
-    point, px, size, AbsoluteLength, Asset, Bounds, DefiniteLength, DevicePixels, Element,
-    ElementId, GlobalElementId, Hitbox, ImageData, InteractiveElement, Interactivity, IntoElement,
-    LayoutId, Length, Pixels, SharedUri, Size, StyleRefinement, Styled, SvgSize, UriOrPath,
-    WindowContext,
+    point, px, size, AbsoluteLength, AnyElement, Asset, Bounds, DefiniteLength, DevicePixels,
+    Element, ElementId, GlobalElementId, Hitbox, ImageData, InteractiveElement, Interactivity,
+    IntoElement, LayoutId, Length, Pixels, SharedUri, Size, StyleRefinement, Styled, SvgSize,
+    UriOrPath, WindowContext,
 };
 use futures::{AsyncReadExt, Future};
 use image::{ImageBuffer, ImageError};
@@ -81,6 +81,8 @@ pub struct Img {
     source: ImageSource,
     grayscale: bool,
     object_fit: ObjectFit,
+    loading: Option<Box<dyn Fn() -> AnyElement>>,
+    fallback: Option<Box<dyn Fn() -> AnyElement>>,
 }
 
 /// Create a new image element.
@@ -90,6 +92,8 @@ pub fn img(source: impl Into<ImageSource>) -> Img {
         source: source.into(),
         grayscale: false,
         object_fit: ObjectFit::Contain,
+        loading: None,
+        fallback: None,
     }
 }
 
@@ -228,10 +232,23 @@ impl Img {
         self.object_fit = object_fit;
         self
     }
+
+    /// Set the element to display in place of the image while it is loading.
+    pub fn with_loading(mut self, loading: impl Fn() -> AnyElement + 'static) -> Self {
+        self.loading = Some(Box::new(loading));
+        self
+    }
+
+    /// Set the element to display in place of the image if it fails to load or decode.
+    pub fn with_fallback(mut self, fallback: impl Fn() -> AnyElement + 'static) -> Self {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
 }
 
 impl Element for Img {
-    type RequestLayoutState = ();
+    /// The element displayed in place of the image, if it is still loading or failed to load.
+    type RequestLayoutState = Option<AnyElement>;
     type PrepaintState = Option<Hitbox>;
 
     fn id(&self) -> Option<ElementId> {
@@ -243,56 +260,77 @@ impl Element for Img {
         global_id: Option<&GlobalElementId>,
         cx: &mut WindowContext,
     ) -> (LayoutId, Self::RequestLayoutState) {
+        let mut replacement = None;
         let layout_id = self
             .interactivity
             .request_layout(global_id, cx, |mut style, cx| {
-                if let Some(data) = self.source.data(cx) {
-                    let image_size = data.size();
-                    match (style.size.width, style.size.height) {
-                        (Length::Auto, Length::Auto) => {
-                            style.size = Size {
-                                width: Length::Definite(DefiniteLength::Absolute(
-                                    AbsoluteLength::Pixels(px(image_size.width.0 as f32)),
-                                )),
-                                height: Length::Definite(DefiniteLength::Absolute(
-                                    AbsoluteLength::Pixels(px(image_size.height.0 as f32)),
-                                )),
+                match self.source.use_data(cx) {
+                    Some(Ok(data)) => {
+                        let image_size = data.size();
+                        match (style.size.width, style.size.height) {
+                            (Length::Auto, Length::Auto) => {
+                                style.size = Size {
+                                    width: Length::Definite(DefiniteLength::Absolute(
+                                        AbsoluteLength::Pixels(px(image_size.width.0 as f32)),
+                                    )),
+                                    height: Length::Definite(DefiniteLength::Absolute(
+                                        AbsoluteLength::Pixels(px(image_size.height.0 as f32)),
+                                    )),
+                                }
                             }
+                            _ => {}
                         }
-                        _ => {}
                     }
+                    Some(Err(_)) => replacement = self.fallback.as_ref().map(|fallback| fallback()),
+                    None if !self.source.is_surface() => {
+                        replacement = self.loading.as_ref().map(|loading| loading())
+                    }
+                    None => {}
                 }
 
-                cx.request_layout(style, [])
+                let child_layout_id = replacement
+                    .as_mut()
+                    .map(|replacement| replacement.request_layout(cx));
+                cx.request_layout(style, child_layout_id)
             });
-        (layout_id, ())
+        (layout_id, replacement)
     }
 
     fn prepaint(
         &mut self,
         global_id: Option<&GlobalElementId>,
         bounds: Bounds<Pixels>,
-        _request_layout: &mut Self::RequestLayoutState,
+        replacement: &mut Self::RequestLayoutState,
         cx: &mut WindowContext,
     ) -> Option<Hitbox> {
         self.interactivity
-            .prepaint(global_id, bounds, bounds.size, cx, |_, _, hitbox, _| hitbox)
+            .prepaint(global_id, bounds, bounds.size, cx, |_, _, hitbox, cx| {
+                if let Some(replacement) = replacement {
+                    replacement.prepaint(cx);
+                }
+                hitbox
+            })
     }
 
     fn paint(
         &mut self,
         global_id: Option<&GlobalElementId>,
         bounds: Bounds<Pixels>,
-        _: &mut Self::RequestLayoutState,
+        replacement: &mut Self::RequestLayoutState,
         hitbox: &mut Self::PrepaintState,
         cx: &mut WindowContext,
     ) {
         let source = self.source.clone();
         self.interactivity
             .paint(global_id, bounds, hitbox.as_ref(), cx, |style, cx| {
+                if let Some(replacement) = replacement {
+                    replacement.paint(cx);
+                    return;
+                }
+
                 let corner_radii = style.corner_radii.to_pixels(bounds.size, cx.rem_size());
 
-                if let Some(data) = source.data(cx) {
+                if let Some(data) = source.use_data(cx).and_then(|result| result.log_err()) {
                     let new_bounds = self.object_fit.get_bounds(bounds, data.size());
                     cx.paint_image(new_bounds, corner_radii, data.clone(), self.grayscale)
                         .log_err();
@@ -333,7 +371,14 @@ impl InteractiveElement for Img {
 }
 
 impl ImageSource {
-    fn data(&self, cx: &mut WindowContext) -> Option<Arc<ImageData>> {
+    /// Returns the decoded image, or `None` if it is still loading or isn't backed by image data.
+    ///
+    /// Images loaded from a URI or file are decoded once and cached for the lifetime of the
+    /// app, so calling this for a source that is shown many times is cheap.
+    pub fn use_data(
+        &self,
+        cx: &mut WindowContext,
+    ) -> Option<Result<Arc<ImageData>, ImageCacheError>> {
         match self {
             ImageSource::Uri(_) | ImageSource::File(_) => {
                 let uri_or_path: UriOrPath = match self {
@@ -342,14 +387,22 @@ impl ImageSource {
                     _ => unreachable!(),
                 };
 
-                cx.use_cached_asset::<Image>(&uri_or_path)?.log_err()
+                cx.use_cached_asset::<Image>(&uri_or_path)
             }
 
-            ImageSource::Data(data) => Some(data.to_owned()),
+            ImageSource::Data(data) => Some(Ok(data.to_owned())),
             #[cfg(target_os = "macos")]
             ImageSource::Surface(_) => None,
         }
     }
+
+    fn is_surface(&self) -> bool {
+        #[cfg(target_os = "macos")]
+        if let ImageSource::Surface(_) = self {
+            return true;
+        }
+        false
+    }
 }
 
 #[derive(Clone)]
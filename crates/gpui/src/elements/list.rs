@@ -623,7 +623,7 @@ impl StateInner {
                 let mut item_origin = bounds.origin + Point::new(px(0.), padding.top);
                 item_origin.y -= layout_response.scroll_top.offset_in_item;
                 for item in &mut layout_response.item_layouts {
-                    cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                    cx.with_content_mask(Some(ContentMask::new(bounds)), |cx| {
                         item.element.prepaint_at(item_origin, cx);
                     });
 
@@ -824,7 +824,7 @@ impl Element for List {
         prepaint: &mut Self::PrepaintState,
         cx: &mut crate::WindowContext,
     ) {
-        cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+        cx.with_content_mask(Some(ContentMask::new(bounds)), |cx| {
             for item in &mut prepaint.layout.item_layouts {
                 item.element.paint(cx);
             }
@@ -5,6 +5,8 @@ mod deferred;
 mod div;
 mod img;
 mod list;
+mod overlay;
+mod scrollbar;
 mod svg;
 mod text;
 mod uniform_list;
@@ -16,6 +18,8 @@ pub use deferred::*;
 pub use div::*;
 pub use img::*;
 pub use list::*;
+pub use overlay::*;
+pub use scrollbar::*;
 pub use svg::*;
 pub use text::*;
 pub use uniform_list::*;
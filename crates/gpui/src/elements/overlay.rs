@@ -0,0 +1,186 @@
+use crate::{
+    anchored, deferred, Anchored, AnchoredSide, AnyElement, Bounds, Deferred, IntoElement,
+    ParentElement, Pixels,
+};
+
+/// Builds an `Overlay` element, which draws its children on top of the rest of the window, on a
+/// side of the given bounds in window coordinates. Its children should occlude the mouse if the
+/// content beneath them shouldn't be hovered or clicked.
+pub fn overlay(anchor_bounds: Bounds<Pixels>, side: AnchoredSide) -> Overlay {
+    Overlay {
+        anchored: anchored().beside(anchor_bounds, side),
+        priority: 0,
+    }
+}
+
+/// An element which is [deferred](crate::deferred) and [anchored](crate::anchored) beside the
+/// bounds of another element, flipping to the opposite side of them when it only fits there.
+pub struct Overlay {
+    anchored: Anchored,
+    priority: usize,
+}
+
+impl Overlay {
+    /// Sets the gap between the overlay and the bounds it is placed beside.
+    pub fn offset(mut self, offset: impl Into<Pixels>) -> Self {
+        self.anchored = self.anchored.offset(offset);
+        self
+    }
+
+    /// Sets the priority of the overlay relative to other deferred elements in the same layer,
+    /// with higher values being drawn on top.
+    pub fn priority(mut self, priority: usize) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl ParentElement for Overlay {
+    fn extend(&mut self, elements: impl IntoIterator<Item = AnyElement>) {
+        self.anchored.extend(elements)
+    }
+}
+
+impl IntoElement for Overlay {
+    type Element = Deferred;
+
+    fn into_element(self) -> Self::Element {
+        deferred(self.anchored.snap_to_window()).with_priority(self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        self as gpui, div, point, px, size, Element, GlobalElementId, InteractiveElement, LayoutId,
+        Modifiers, Render, StatefulInteractiveElement, Styled, TestAppContext, ViewContext,
+        WindowContext,
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    /// Defers the elements within its child in a layer with the given z-index.
+    struct Layer {
+        z_index: usize,
+        child: AnyElement,
+    }
+
+    impl Element for Layer {
+        type RequestLayoutState = ();
+        type PrepaintState = ();
+
+        fn id(&self) -> Option<crate::ElementId> {
+            None
+        }
+
+        fn request_layout(
+            &mut self,
+            _id: Option<&GlobalElementId>,
+            cx: &mut WindowContext,
+        ) -> (LayoutId, ()) {
+            (self.child.request_layout(cx), ())
+        }
+
+        fn prepaint(
+            &mut self,
+            _id: Option<&GlobalElementId>,
+            _bounds: Bounds<Pixels>,
+            _request_layout: &mut (),
+            cx: &mut WindowContext,
+        ) {
+            cx.push_layer(self.z_index);
+            self.child.prepaint(cx);
+            cx.pop_layer();
+        }
+
+        fn paint(
+            &mut self,
+            _id: Option<&GlobalElementId>,
+            _bounds: Bounds<Pixels>,
+            _request_layout: &mut (),
+            _prepaint: &mut (),
+            cx: &mut WindowContext,
+        ) {
+            self.child.paint(cx);
+        }
+    }
+
+    impl IntoElement for Layer {
+        type Element = Self;
+
+        fn into_element(self) -> Self::Element {
+            self
+        }
+    }
+
+    struct OverlayView {
+        clicks: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl Render for OverlayView {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            let button = |id: &'static str, size: f32| {
+                let clicks = self.clicks.clone();
+                div()
+                    .id(id)
+                    .size(px(size))
+                    .occlude()
+                    .on_click(move |_, _| clicks.borrow_mut().push(id))
+            };
+            let trigger_bounds = Bounds::new(point(px(0.), px(0.)), size(px(20.), px(20.)));
+            div()
+                .size_full()
+                .flex()
+                .flex_col()
+                .child(
+                    div()
+                        .size(px(20.))
+                        .child(
+                            overlay(trigger_bounds, AnchoredSide::Bottom)
+                                .priority(2)
+                                .child(button("top", 50.)),
+                        )
+                        .child(
+                            overlay(trigger_bounds, AnchoredSide::Bottom)
+                                .offset(px(20.))
+                                .priority(1)
+                                .child(button("middle", 50.)),
+                        )
+                        // Has the lowest priority, but is in a layer above the others.
+                        .child(Layer {
+                            z_index: 1,
+                            child: overlay(trigger_bounds, AnchoredSide::Right)
+                                .child(button("layer", 30.))
+                                .into_any_element(),
+                        }),
+                )
+                // Painted after the trigger, but still beneath the overlays.
+                .child(button("content", 100.))
+        }
+    }
+
+    #[gpui::test]
+    fn test_overlays_intercept_clicks_in_paint_order(cx: &mut TestAppContext) {
+        let clicks = Rc::new(RefCell::new(Vec::new()));
+        let (_, cx) = cx.add_window_view(|_| OverlayView {
+            clicks: clicks.clone(),
+        });
+        cx.run_until_parked();
+
+        let mut click = |x: f32, y: f32| {
+            cx.simulate_click(point(px(x), px(y)), Modifiers::none());
+            clicks.borrow_mut().drain(..).collect::<Vec<_>>()
+        };
+        // The content spans 20..120 below the 20px trigger. The overlays below the trigger are
+        // drawn over it at 20..70 and 40..90, with the higher priority one on top. The one in the
+        // upper layer is right of the trigger, spanning 0..30, and is drawn over all of them.
+        assert_eq!(click(10., 30.), ["top"]);
+        assert_eq!(click(10., 60.), ["top"]);
+        assert_eq!(click(10., 80.), ["middle"]);
+        assert_eq!(click(10., 100.), ["content"]);
+        assert_eq!(click(30., 25.), ["layer"]);
+        assert_eq!(click(30., 60.), ["top"]);
+        assert_eq!(click(75., 60.), ["content"]);
+        assert_eq!(click(10., 10.), Vec::<&str>::new());
+    }
+}
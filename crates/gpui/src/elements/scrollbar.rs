@@ -0,0 +1,548 @@
+use std::{
+    cell::RefCell,
+    ops::Range,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use refineable::Refineable as _;
+
+use crate::{
+    fill, hsla, point, px, size, Axis, Bounds, DispatchPhase, Element, ElementId, EntityId,
+    GlobalElementId, Hitbox, Hsla, IntoElement, LayoutId, MouseButton, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, Pixels, Point, Style, StyleRefinement, Styled, Task,
+    WindowContext,
+};
+
+/// How long a scrollbar takes to fade out once it hides.
+const FADE_DURATION: Duration = Duration::from_millis(200);
+
+/// The shortest a marker is drawn, so that markers for short ranges in long content are still
+/// visible.
+const MIN_MARKER_LENGTH: Pixels = px(2.);
+
+/// What a [`ScrollbarMarker`] marks. Markers of later kinds are painted over earlier ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScrollbarMarkerKind {
+    /// A changed range of a file under version control.
+    GitHunk,
+    /// A selection.
+    Selection,
+    /// A match of the current search.
+    SearchResult,
+    /// A diagnostic, such as an error or a warning.
+    Diagnostic,
+}
+
+/// A range of the content highlighted in the track of a [`Scrollbar`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollbarMarker {
+    /// The marked range, as fractions of the content's size from `0.` to `1.`.
+    pub range: Range<f32>,
+    /// The color the marker is painted with.
+    pub color: Hsla,
+    /// What the marker marks, which decides what it is painted over.
+    pub kind: ScrollbarMarkerKind,
+}
+
+/// Construct a scrollbar for content of the given size, shown in a viewport of the given size
+/// and scrolled by `scroll_offset` from its start.
+pub fn scrollbar(
+    id: impl Into<ElementId>,
+    content_size: Pixels,
+    viewport_size: Pixels,
+    scroll_offset: Pixels,
+) -> Scrollbar {
+    Scrollbar {
+        id: id.into(),
+        axis: Axis::Vertical,
+        content_size,
+        viewport_size,
+        scroll_offset,
+        markers: Vec::new(),
+        min_thumb_size: px(24.),
+        thumb_color: hsla(0., 0., 0.5, 0.4),
+        active_thumb_color: hsla(0., 0., 0.5, 0.7),
+        track_color: hsla(0., 0., 0.5, 0.08),
+        auto_hide: Some(Duration::from_secs(1)),
+        on_scroll: None,
+        style: StyleRefinement::default(),
+    }
+}
+
+/// An overlay scrollbar, with a thumb sized by the share of the content that is visible and
+/// markers for ranges of the content, such as diagnostics and search results.
+///
+/// The scrollbar doesn't scroll anything itself: dragging the thumb or clicking the track
+/// reports the new scroll offset to [`Scrollbar::on_scroll`], and the caller is expected to
+/// apply it and render the scrollbar again with the new offset. Offsets are positive distances
+/// from the start of the content.
+///
+/// Unless auto-hide is turned off, the track and thumb fade out once the content hasn't been
+/// scrolled or the scrollbar hovered for a while. Markers stay visible.
+pub struct Scrollbar {
+    id: ElementId,
+    axis: Axis,
+    content_size: Pixels,
+    viewport_size: Pixels,
+    scroll_offset: Pixels,
+    markers: Vec<ScrollbarMarker>,
+    min_thumb_size: Pixels,
+    thumb_color: Hsla,
+    active_thumb_color: Hsla,
+    track_color: Hsla,
+    auto_hide: Option<Duration>,
+    on_scroll: Option<Rc<dyn Fn(Pixels, &mut WindowContext)>>,
+    style: StyleRefinement,
+}
+
+impl Scrollbar {
+    /// Sets the axis the content scrolls along. Scrollbars are vertical by default.
+    pub fn axis(mut self, axis: Axis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Adds markers to the track. They are painted in the order of their
+    /// [kinds](ScrollbarMarkerKind), whatever order they're added in.
+    pub fn markers(mut self, markers: impl IntoIterator<Item = ScrollbarMarker>) -> Self {
+        self.markers.extend(markers);
+        self
+    }
+
+    /// Sets the shortest the thumb can be, so that it can still be grabbed when the content is
+    /// much larger than the viewport.
+    pub fn min_thumb_size(mut self, min_thumb_size: Pixels) -> Self {
+        self.min_thumb_size = min_thumb_size;
+        self
+    }
+
+    /// Sets the colors of the thumb, when idle and when hovered or dragged.
+    pub fn thumb_color(mut self, color: impl Into<Hsla>, active_color: impl Into<Hsla>) -> Self {
+        self.thumb_color = color.into();
+        self.active_thumb_color = active_color.into();
+        self
+    }
+
+    /// Sets the color of the track.
+    pub fn track_color(mut self, color: impl Into<Hsla>) -> Self {
+        self.track_color = color.into();
+        self
+    }
+
+    /// Sets how long the scrollbar stays visible after the content was last scrolled or the
+    /// scrollbar hovered, or `None` to always show it.
+    pub fn auto_hide(mut self, delay: Option<Duration>) -> Self {
+        self.auto_hide = delay;
+        self
+    }
+
+    /// Called with the new scroll offset when the thumb is dragged or the track is clicked.
+    pub fn on_scroll(mut self, handler: impl Fn(Pixels, &mut WindowContext) + 'static) -> Self {
+        self.on_scroll = Some(Rc::new(handler));
+        self
+    }
+
+    fn metrics(&self, track_size: Pixels) -> ScrollbarMetrics {
+        ScrollbarMetrics {
+            content_size: self.content_size,
+            viewport_size: self.viewport_size,
+            track_size,
+            min_thumb_size: self.min_thumb_size,
+        }
+    }
+
+    /// Returns the part of the track between the given offsets along it.
+    fn track_slice(&self, track: Bounds<Pixels>, start: Pixels, length: Pixels) -> Bounds<Pixels> {
+        match self.axis {
+            Axis::Vertical => Bounds::new(
+                point(track.origin.x, track.origin.y + start),
+                size(track.size.width, length),
+            ),
+            Axis::Horizontal => Bounds::new(
+                point(track.origin.x + start, track.origin.y),
+                size(length, track.size.height),
+            ),
+        }
+    }
+
+    fn track_size(&self, track: Bounds<Pixels>) -> Pixels {
+        match self.axis {
+            Axis::Vertical => track.size.height,
+            Axis::Horizontal => track.size.width,
+        }
+    }
+}
+
+/// The sizes that place a scrollbar's thumb, all along the axis the content scrolls along.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScrollbarMetrics {
+    content_size: Pixels,
+    viewport_size: Pixels,
+    track_size: Pixels,
+    min_thumb_size: Pixels,
+}
+
+impl ScrollbarMetrics {
+    fn max_scroll_offset(&self) -> Pixels {
+        (self.content_size - self.viewport_size).max(px(0.))
+    }
+
+    /// The thumb's share of the track is the share of the content that is visible, unless that
+    /// would make it shorter than the minimum.
+    fn thumb_size(&self) -> Pixels {
+        if self.content_size <= self.viewport_size {
+            return self.track_size;
+        }
+        let size = self.track_size * (self.viewport_size / self.content_size);
+        size.max(self.min_thumb_size).min(self.track_size)
+    }
+
+    /// How far the thumb can move along the track.
+    fn thumb_travel(&self) -> Pixels {
+        (self.track_size - self.thumb_size()).max(px(0.))
+    }
+
+    /// Where the thumb starts along the track when the content is scrolled by `scroll_offset`.
+    fn thumb_offset(&self, scroll_offset: Pixels) -> Pixels {
+        let max_scroll_offset = self.max_scroll_offset();
+        if max_scroll_offset <= px(0.) {
+            return px(0.);
+        }
+        let progress = (scroll_offset / max_scroll_offset).clamp(0., 1.);
+        self.thumb_travel() * progress
+    }
+
+    /// The scroll offset at which the thumb starts at `thumb_offset` along the track.
+    fn scroll_offset(&self, thumb_offset: Pixels) -> Pixels {
+        let thumb_travel = self.thumb_travel();
+        if thumb_travel <= px(0.) {
+            return px(0.);
+        }
+        let progress = (thumb_offset / thumb_travel).clamp(0., 1.);
+        self.max_scroll_offset() * progress
+    }
+}
+
+/// How visible the track and thumb are, `elapsed` after the content was last scrolled or the
+/// scrollbar hovered.
+fn visibility(auto_hide: Option<Duration>, elapsed: Duration) -> f32 {
+    let Some(delay) = auto_hide else {
+        return 1.;
+    };
+    let Some(fading_for) = elapsed.checked_sub(delay) else {
+        return 1.;
+    };
+    1. - (fading_for.as_secs_f32() / FADE_DURATION.as_secs_f32()).min(1.)
+}
+
+struct ScrollbarState {
+    last_activity: Instant,
+    last_scroll_offset: Pixels,
+    hovered: bool,
+    /// Where the thumb was grabbed, relative to its start, while it is being dragged.
+    drag_offset: Option<Pixels>,
+    _hide_task: Option<Task<()>>,
+}
+
+impl ScrollbarState {
+    fn is_active(&self) -> bool {
+        self.hovered || self.drag_offset.is_some()
+    }
+}
+
+#[doc(hidden)]
+pub struct ScrollbarPrepaintState {
+    hitbox: Hitbox,
+    state: Rc<RefCell<ScrollbarState>>,
+}
+
+impl IntoElement for Scrollbar {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for Scrollbar {
+    type RequestLayoutState = Style;
+    type PrepaintState = ScrollbarPrepaintState;
+
+    fn id(&self) -> Option<ElementId> {
+        Some(self.id.clone())
+    }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        cx: &mut WindowContext,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let mut style = Style::default();
+        style.refine(&self.style);
+        let layout_id = cx.request_layout(style.clone(), []);
+        (layout_id, style)
+    }
+
+    fn prepaint(
+        &mut self,
+        id: Option<&GlobalElementId>,
+        bounds: Bounds<Pixels>,
+        _style: &mut Style,
+        cx: &mut WindowContext,
+    ) -> ScrollbarPrepaintState {
+        let hitbox = cx.insert_hitbox(bounds, false);
+        let state = cx.with_element_state(id.unwrap(), |state, _| {
+            let state = state.unwrap_or_else(|| {
+                Rc::new(RefCell::new(ScrollbarState {
+                    last_activity: Instant::now(),
+                    last_scroll_offset: self.scroll_offset,
+                    hovered: false,
+                    drag_offset: None,
+                    _hide_task: None,
+                }))
+            });
+            (state.clone(), state)
+        });
+        ScrollbarPrepaintState { hitbox, state }
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        bounds: Bounds<Pixels>,
+        _style: &mut Style,
+        prepaint: &mut ScrollbarPrepaintState,
+        cx: &mut WindowContext,
+    ) {
+        let parent_view_id = cx.parent_view_id();
+        let metrics = self.metrics(self.track_size(bounds));
+        let hitbox = prepaint.hitbox.clone();
+
+        let opacity = {
+            let mut state = prepaint.state.borrow_mut();
+            let hovered = hitbox.is_hovered(cx);
+            if hovered != state.hovered || self.scroll_offset != state.last_scroll_offset {
+                state.hovered = hovered;
+                state.last_scroll_offset = self.scroll_offset;
+                state.last_activity = Instant::now();
+                state._hide_task = self
+                    .auto_hide
+                    .map(|delay| schedule_refresh(delay, parent_view_id, cx));
+            }
+            if state.is_active() {
+                state.last_activity = Instant::now();
+            }
+
+            let opacity = visibility(self.auto_hide, state.last_activity.elapsed());
+            if opacity > 0. && opacity < 1. {
+                cx.on_next_frame(move |cx| refresh(parent_view_id, cx));
+            }
+            opacity
+        };
+
+        let thumb_bounds = self.track_slice(
+            bounds,
+            metrics.thumb_offset(self.scroll_offset),
+            metrics.thumb_size(),
+        );
+        let can_scroll = metrics.max_scroll_offset() > px(0.);
+        if opacity > 0. {
+            let thumb_color = if prepaint.state.borrow().is_active() {
+                self.active_thumb_color
+            } else {
+                self.thumb_color
+            };
+            let corner_radius = match self.axis {
+                Axis::Vertical => thumb_bounds.size.width,
+                Axis::Horizontal => thumb_bounds.size.height,
+            } * 0.5;
+            cx.with_element_opacity(Some(opacity), |cx| {
+                cx.paint_quad(fill(bounds, self.track_color));
+                if can_scroll {
+                    cx.paint_quad(fill(thumb_bounds, thumb_color).corner_radii(corner_radius));
+                }
+            });
+        }
+
+        let track_size = metrics.track_size;
+        let mut markers = std::mem::take(&mut self.markers);
+        markers.sort_by_key(|marker| marker.kind);
+        for marker in markers {
+            let start = track_size * marker.range.start.clamp(0., 1.);
+            let end = track_size * marker.range.end.clamp(0., 1.);
+            let length = (end - start).max(MIN_MARKER_LENGTH);
+            let start = start.min(track_size - length).max(px(0.));
+            cx.paint_quad(fill(self.track_slice(bounds, start, length), marker.color));
+        }
+
+        if !can_scroll {
+            return;
+        }
+
+        let axis = self.axis;
+        let along = move |position: Point<Pixels>| match axis {
+            Axis::Vertical => position.y,
+            Axis::Horizontal => position.x,
+        };
+        let track_start = along(bounds.origin);
+        let scroll_offset = self.scroll_offset;
+        let viewport_size = self.viewport_size;
+
+        cx.on_mouse_event({
+            let state = prepaint.state.clone();
+            let hitbox = hitbox.clone();
+            let on_scroll = self.on_scroll.clone();
+            move |event: &MouseDownEvent, phase, cx| {
+                if phase != DispatchPhase::Bubble
+                    || event.button != MouseButton::Left
+                    || !hitbox.is_hovered(cx)
+                {
+                    return;
+                }
+                cx.stop_propagation();
+
+                let position = along(event.position);
+                if thumb_bounds.contains(&event.position) {
+                    state.borrow_mut().drag_offset = Some(position - along(thumb_bounds.origin));
+                    refresh(parent_view_id, cx);
+                } else if let Some(on_scroll) = on_scroll.as_ref() {
+                    // Clicking the track pages towards the click.
+                    let new_offset = if position < along(thumb_bounds.origin) {
+                        scroll_offset - viewport_size
+                    } else {
+                        scroll_offset + viewport_size
+                    };
+                    let new_offset = new_offset.clamp(px(0.), metrics.max_scroll_offset());
+                    on_scroll(new_offset, cx);
+                }
+            }
+        });
+
+        cx.on_mouse_event({
+            let state = prepaint.state.clone();
+            let on_scroll = self.on_scroll.clone();
+            move |event: &MouseMoveEvent, phase, cx| {
+                if phase != DispatchPhase::Bubble {
+                    return;
+                }
+                let Some(drag_offset) = state.borrow().drag_offset else {
+                    return;
+                };
+                if !event.dragging() {
+                    state.borrow_mut().drag_offset = None;
+                    refresh(parent_view_id, cx);
+                    return;
+                }
+                cx.stop_propagation();
+
+                let thumb_offset = along(event.position) - track_start - drag_offset;
+                let new_offset = metrics.scroll_offset(thumb_offset);
+                if new_offset != scroll_offset {
+                    if let Some(on_scroll) = on_scroll.as_ref() {
+                        on_scroll(new_offset, cx);
+                    }
+                }
+            }
+        });
+
+        cx.on_mouse_event({
+            let state = prepaint.state.clone();
+            move |_: &MouseUpEvent, phase, cx| {
+                if phase == DispatchPhase::Bubble && state.borrow_mut().drag_offset.take().is_some()
+                {
+                    refresh(parent_view_id, cx);
+                }
+            }
+        });
+    }
+}
+
+impl Styled for Scrollbar {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+/// Redraws the view containing the scrollbar, or the whole window if it isn't in a view.
+fn refresh(view_id: Option<EntityId>, cx: &mut WindowContext) {
+    match view_id {
+        Some(view_id) => cx.notify(view_id),
+        None => cx.refresh(),
+    }
+}
+
+/// Redraws the scrollbar once it should start fading out.
+fn schedule_refresh(
+    delay: Duration,
+    view_id: Option<EntityId>,
+    cx: &mut WindowContext,
+) -> Task<()> {
+    cx.spawn(|mut cx| async move {
+        cx.background_executor().timer(delay).await;
+        cx.update(|cx| refresh(view_id, cx)).ok();
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(content_size: f32, viewport_size: f32) -> ScrollbarMetrics {
+        ScrollbarMetrics {
+            content_size: px(content_size),
+            viewport_size: px(viewport_size),
+            track_size: px(100.),
+            min_thumb_size: px(20.),
+        }
+    }
+
+    #[test]
+    fn test_thumb_position() {
+        // A quarter of the content is visible, so the thumb is a quarter of the track.
+        let metrics = metrics(400., 100.);
+        assert_eq!(metrics.thumb_size(), px(25.));
+        assert_eq!(metrics.thumb_offset(px(0.)), px(0.));
+        assert_eq!(metrics.thumb_offset(px(150.)), px(37.5));
+        assert_eq!(metrics.thumb_offset(px(300.)), px(75.));
+        // Offsets past either end pin the thumb to that end.
+        assert_eq!(metrics.thumb_offset(px(-10.)), px(0.));
+        assert_eq!(metrics.thumb_offset(px(1000.)), px(75.));
+
+        for scroll_offset in [0., 60., 150., 300.] {
+            let thumb_offset = metrics.thumb_offset(px(scroll_offset));
+            assert_eq!(metrics.scroll_offset(thumb_offset), px(scroll_offset));
+        }
+        assert_eq!(metrics.scroll_offset(px(-5.)), px(0.));
+        assert_eq!(metrics.scroll_offset(px(90.)), px(300.));
+    }
+
+    #[test]
+    fn test_min_thumb_size() {
+        // A hundredth of the content is visible, but the thumb can't be shorter than 20px.
+        let metrics = metrics(10_000., 100.);
+        assert_eq!(metrics.thumb_size(), px(20.));
+        assert_eq!(metrics.thumb_travel(), px(80.));
+        assert_eq!(metrics.thumb_offset(px(4950.)), px(40.));
+        assert_eq!(metrics.thumb_offset(px(9900.)), px(80.));
+        assert_eq!(metrics.scroll_offset(px(40.)), px(4950.));
+        assert_eq!(metrics.scroll_offset(px(80.)), px(9900.));
+    }
+
+    #[test]
+    fn test_content_fits_viewport() {
+        let metrics = metrics(80., 100.);
+        assert_eq!(metrics.thumb_size(), px(100.));
+        assert_eq!(metrics.thumb_offset(px(10.)), px(0.));
+        assert_eq!(metrics.scroll_offset(px(10.)), px(0.));
+    }
+
+    #[test]
+    fn test_visibility() {
+        let delay = Some(Duration::from_secs(1));
+        assert_eq!(visibility(None, Duration::from_secs(10)), 1.);
+        assert_eq!(visibility(delay, Duration::from_millis(500)), 1.);
+        assert_eq!(visibility(delay, Duration::from_millis(1100)), 0.5);
+        assert_eq!(visibility(delay, Duration::from_secs(2)), 0.);
+    }
+}
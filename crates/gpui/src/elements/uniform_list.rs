@@ -57,6 +57,8 @@ where
         },
         scroll_handle: None,
         sizing_behavior: ListSizingBehavior::default(),
+        overscan: 0,
+        on_scroll: None,
     }
 }
 
@@ -69,6 +71,8 @@ pub struct UniformList {
     interactivity: Interactivity,
     scroll_handle: Option<UniformListScrollHandle>,
     sizing_behavior: ListSizingBehavior,
+    overscan: usize,
+    on_scroll: Option<Rc<dyn Fn(&UniformListScrollEvent, &mut WindowContext)>>,
 }
 
 /// Frame state used by the [UniformList].
@@ -86,10 +90,33 @@ pub struct UniformListScrollHandle(pub Rc<RefCell<UniformListScrollState>>);
 #[allow(missing_docs)]
 pub struct UniformListScrollState {
     pub base_handle: ScrollHandle,
-    pub deferred_scroll_to_item: Option<usize>,
+    pub deferred_scroll_to_item: Option<(usize, ScrollAlignment)>,
     pub last_item_height: Option<Pixels>,
 }
 
+/// Where an item is placed in the viewport when scrolling a uniform list to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScrollAlignment {
+    /// Scroll as little as possible to make the item fully visible.
+    #[default]
+    Nearest,
+    /// Scroll the item to the top of the viewport.
+    Top,
+    /// Scroll the item to the middle of the viewport.
+    Center,
+    /// Scroll the item to the bottom of the viewport.
+    Bottom,
+}
+
+/// Reported to [`UniformList::on_scroll`] when the scroll position of the list changes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniformListScrollEvent {
+    /// The range of items that are at least partially visible, without overscan.
+    pub visible_range: Range<usize>,
+    /// How far the list is scrolled from its top.
+    pub scroll_top: Pixels,
+}
+
 impl UniformListScrollHandle {
     /// Create a new scroll handle to bind to a uniform list.
     pub fn new() -> Self {
@@ -100,15 +127,34 @@ impl UniformListScrollHandle {
         })))
     }
 
-    /// Scroll the list to the given item index.
+    /// Scroll the list as little as possible to make the given item fully visible.
     pub fn scroll_to_item(&mut self, ix: usize) {
-        self.0.borrow_mut().deferred_scroll_to_item = Some(ix);
+        self.scroll_to(ix, ScrollAlignment::Nearest);
+    }
+
+    /// Scroll the list to place the given item as specified by the alignment. The scroll
+    /// position is updated the next time the list is laid out.
+    pub fn scroll_to(&mut self, ix: usize, alignment: ScrollAlignment) {
+        self.0.borrow_mut().deferred_scroll_to_item = Some((ix, alignment));
+    }
+
+    /// Get how far the list is scrolled from its top.
+    pub fn scroll_top(&self) -> Pixels {
+        -self.0.borrow().base_handle.offset().y
+    }
+
+    /// Scroll the list to the given distance from its top, as when dragging a scrollbar.
+    pub fn set_scroll_top(&self, scroll_top: Pixels) {
+        let this = self.0.borrow();
+        let offset = this.base_handle.offset();
+        this.base_handle.set_offset(point(offset.x, -scroll_top));
     }
 
     /// Get the index of the topmost visible child.
     pub fn logical_scroll_top_index(&self) -> usize {
         let this = self.0.borrow();
         this.deferred_scroll_to_item
+            .map(|(ix, _)| ix)
             .unwrap_or_else(|| this.base_handle.logical_scroll_top().0)
     }
 }
@@ -226,40 +272,40 @@ impl Element for UniformList {
                 }
 
                 if self.item_count > 0 {
-                    let content_height =
-                        item_height * self.item_count + padding.top + padding.bottom;
-                    let min_scroll_offset = padded_bounds.size.height - content_height;
-                    let is_scrolled = scroll_offset.y != px(0.);
-
-                    if is_scrolled && scroll_offset.y < min_scroll_offset {
-                        shared_scroll_offset.borrow_mut().y = min_scroll_offset;
-                        scroll_offset.y = min_scroll_offset;
+                    let metrics = UniformListMetrics {
+                        item_count: self.item_count,
+                        item_height,
+                        padding_top: padding.top,
+                        padding_bottom: padding.bottom,
+                        viewport_height: padded_bounds.size.height,
+                    };
+
+                    // Keep the list scrolled to its end when items are removed from it.
+                    let max_scroll_top = metrics.max_scroll_top();
+                    if -scroll_offset.y > max_scroll_top {
+                        shared_scroll_offset.borrow_mut().y = -max_scroll_top;
+                        scroll_offset.y = -max_scroll_top;
                     }
 
-                    if let Some(ix) = shared_scroll_to_item {
-                        let list_height = padded_bounds.size.height;
-                        let mut updated_scroll_offset = shared_scroll_offset.borrow_mut();
-                        let item_top = item_height * ix + padding.top;
-                        let item_bottom = item_top + item_height;
-                        let scroll_top = -updated_scroll_offset.y;
-                        if item_top < scroll_top + padding.top {
-                            updated_scroll_offset.y = -(item_top) + padding.top;
-                        } else if item_bottom > scroll_top + list_height - padding.bottom {
-                            updated_scroll_offset.y = -(item_bottom - list_height) - padding.bottom;
-                        }
-                        scroll_offset = *updated_scroll_offset;
+                    if let Some((ix, alignment)) = shared_scroll_to_item {
+                        let scroll_top =
+                            metrics.scroll_top_for_item(ix, alignment, -scroll_offset.y);
+                        shared_scroll_offset.borrow_mut().y = -scroll_top;
+                        scroll_offset.y = -scroll_top;
                     }
 
-                    let first_visible_element_ix =
-                        (-(scroll_offset.y + padding.top) / item_height).floor() as usize;
-                    let last_visible_element_ix = ((-scroll_offset.y + padded_bounds.size.height)
-                        / item_height)
-                        .ceil() as usize;
-                    let visible_range = first_visible_element_ix
-                        ..cmp::min(last_visible_element_ix, self.item_count);
+                    let scroll_top = -scroll_offset.y;
+                    if let Some(on_scroll) = self.on_scroll.clone() {
+                        let event = UniformListScrollEvent {
+                            visible_range: metrics.visible_range(scroll_top, 0),
+                            scroll_top,
+                        };
+                        report_scroll(global_id, event, on_scroll, cx);
+                    }
 
-                    let mut items = (self.render_items)(visible_range.clone(), cx);
-                    let content_mask = ContentMask { bounds };
+                    let visible_range = metrics.visible_range(scroll_top, self.overscan);
+                    let items = (self.render_items)(visible_range.clone(), cx);
+                    let content_mask = ContentMask::new(bounds);
                     cx.with_content_mask(Some(content_mask), |cx| {
                         for (mut item, ix) in items.into_iter().zip(visible_range) {
                             let item_origin = padded_bounds.origin
@@ -318,6 +364,23 @@ impl UniformList {
         self
     }
 
+    /// Renders this many items beyond each edge of the viewport, so that they're already laid
+    /// out when they're scrolled into view.
+    pub fn with_overscan(mut self, items: usize) -> Self {
+        self.overscan = items;
+        self
+    }
+
+    /// Called after layout whenever the scroll position or the range of visible items changed,
+    /// whether by scrolling, resizing the list or changing its items.
+    pub fn on_scroll(
+        mut self,
+        handler: impl Fn(&UniformListScrollEvent, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_scroll = Some(Rc::new(handler));
+        self
+    }
+
     fn measure_item(&self, list_width: Option<Pixels>, cx: &mut WindowContext) -> Size<Pixels> {
         if self.item_count == 0 {
             return Size::default();
@@ -348,3 +411,295 @@ impl InteractiveElement for UniformList {
         &mut self.interactivity
     }
 }
+
+/// Calls the scroll handler, after the current frame, if the event differs from the one
+/// reported for the previous frame.
+fn report_scroll(
+    global_id: Option<&GlobalElementId>,
+    event: UniformListScrollEvent,
+    on_scroll: Rc<dyn Fn(&UniformListScrollEvent, &mut WindowContext)>,
+    cx: &mut WindowContext,
+) {
+    let Some(global_id) = global_id else {
+        return;
+    };
+    cx.with_element_state::<UniformListScrollEvent, _>(global_id, |last_event, cx| {
+        if last_event.as_ref() != Some(&event) {
+            let event = event.clone();
+            cx.defer(move |cx| on_scroll(&event, cx));
+        }
+        ((), event)
+    });
+}
+
+/// The dimensions of a uniform list, used to compute which items are visible and where to
+/// scroll to. Scroll positions are distances from the top of the list.
+#[derive(Clone, Copy, Debug)]
+struct UniformListMetrics {
+    item_count: usize,
+    item_height: Pixels,
+    padding_top: Pixels,
+    padding_bottom: Pixels,
+    viewport_height: Pixels,
+}
+
+impl UniformListMetrics {
+    fn content_height(&self) -> Pixels {
+        self.item_height * self.item_count + self.padding_top + self.padding_bottom
+    }
+
+    fn max_scroll_top(&self) -> Pixels {
+        (self.content_height() - self.viewport_height).max(px(0.))
+    }
+
+    /// The items that are at least partially visible at the given scroll position, extended by
+    /// `overscan` items on each side.
+    fn visible_range(&self, scroll_top: Pixels, overscan: usize) -> Range<usize> {
+        if self.item_count == 0 || self.item_height <= px(0.) {
+            return 0..0;
+        }
+
+        let top = scroll_top - self.padding_top;
+        let first_visible = (top / self.item_height).floor().max(0.) as usize;
+        let end_visible = ((top + self.viewport_height) / self.item_height)
+            .ceil()
+            .max(0.) as usize;
+        let start = cmp::min(first_visible.saturating_sub(overscan), self.item_count);
+        let end = cmp::min(end_visible.saturating_add(overscan), self.item_count);
+        start..end
+    }
+
+    /// The scroll position that places the given item as specified by the alignment, when the
+    /// list is currently scrolled to `scroll_top`.
+    fn scroll_top_for_item(
+        &self,
+        ix: usize,
+        alignment: ScrollAlignment,
+        scroll_top: Pixels,
+    ) -> Pixels {
+        let ix = cmp::min(ix, self.item_count.saturating_sub(1));
+        let item_top = self.padding_top + self.item_height * ix;
+        let item_bottom = item_top + self.item_height;
+        let top_aligned = item_top - self.padding_top;
+        let bottom_aligned = item_bottom + self.padding_bottom - self.viewport_height;
+
+        let scroll_top = match alignment {
+            ScrollAlignment::Top => top_aligned,
+            ScrollAlignment::Bottom => bottom_aligned,
+            ScrollAlignment::Center => item_top + (self.item_height - self.viewport_height) / 2.,
+            ScrollAlignment::Nearest => {
+                if top_aligned < scroll_top {
+                    top_aligned
+                } else if bottom_aligned > scroll_top {
+                    bottom_aligned
+                } else {
+                    scroll_top
+                }
+            }
+        };
+        scroll_top.clamp(px(0.), self.max_scroll_top())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as gpui, div, ScrollDelta, ScrollWheelEvent, TestAppContext};
+
+    fn metrics(item_count: usize) -> UniformListMetrics {
+        UniformListMetrics {
+            item_count,
+            item_height: px(10.),
+            padding_top: px(0.),
+            padding_bottom: px(0.),
+            viewport_height: px(100.),
+        }
+    }
+
+    #[test]
+    fn test_visible_range() {
+        let list = metrics(500_000);
+
+        // Top edge
+        assert_eq!(list.visible_range(px(0.), 0), 0..10);
+        assert_eq!(list.visible_range(px(0.), 3), 0..13);
+        assert_eq!(list.visible_range(px(5.), 0), 0..11);
+
+        // Middle
+        assert_eq!(list.visible_range(px(2_500_000.), 0), 250_000..250_010);
+        assert_eq!(list.visible_range(px(2_500_005.), 3), 249_997..250_014);
+
+        // Bottom edge
+        let max_scroll_top = list.max_scroll_top();
+        assert_eq!(max_scroll_top, px(4_999_900.));
+        assert_eq!(list.visible_range(max_scroll_top, 0), 499_990..500_000);
+        assert_eq!(list.visible_range(max_scroll_top, 3), 499_987..500_000);
+
+        // Fewer items than fit in the viewport
+        assert_eq!(metrics(4).visible_range(px(0.), 3), 0..4);
+        assert_eq!(metrics(4).max_scroll_top(), px(0.));
+        assert_eq!(metrics(0).visible_range(px(0.), 3), 0..0);
+
+        // Padding before the first item
+        let padded = UniformListMetrics {
+            padding_top: px(20.),
+            ..list
+        };
+        assert_eq!(padded.visible_range(px(0.), 0), 0..8);
+        assert_eq!(padded.visible_range(px(25.), 0), 0..11);
+    }
+
+    #[test]
+    fn test_scroll_top_for_item() {
+        let list = metrics(1_000);
+
+        assert_eq!(
+            list.scroll_top_for_item(500, ScrollAlignment::Top, px(0.)),
+            px(5_000.)
+        );
+        assert_eq!(
+            list.scroll_top_for_item(500, ScrollAlignment::Bottom, px(0.)),
+            px(4_910.)
+        );
+        assert_eq!(
+            list.scroll_top_for_item(500, ScrollAlignment::Center, px(0.)),
+            px(4_955.)
+        );
+
+        // Items that are already fully visible don't scroll the list.
+        assert_eq!(
+            list.scroll_top_for_item(505, ScrollAlignment::Nearest, px(5_000.)),
+            px(5_000.)
+        );
+        assert_eq!(
+            list.scroll_top_for_item(495, ScrollAlignment::Nearest, px(5_000.)),
+            px(4_950.)
+        );
+        assert_eq!(
+            list.scroll_top_for_item(515, ScrollAlignment::Nearest, px(5_000.)),
+            px(5_060.)
+        );
+
+        // The list can't be scrolled past its edges.
+        assert_eq!(
+            list.scroll_top_for_item(2, ScrollAlignment::Center, px(100.)),
+            px(0.)
+        );
+        assert_eq!(
+            list.scroll_top_for_item(999, ScrollAlignment::Top, px(0.)),
+            px(9_900.)
+        );
+        assert_eq!(
+            list.scroll_top_for_item(5_000, ScrollAlignment::Nearest, px(0.)),
+            px(9_900.)
+        );
+    }
+
+    struct ListView {
+        height: Pixels,
+        scroll_handle: UniformListScrollHandle,
+        rendered_ranges: Rc<RefCell<Vec<Range<usize>>>>,
+        scroll_events: Rc<RefCell<Vec<UniformListScrollEvent>>>,
+    }
+
+    impl Render for ListView {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            let rendered_ranges = self.rendered_ranges.clone();
+            let scroll_events = self.scroll_events.clone();
+            uniform_list(cx.view().clone(), "list", 500_000, move |_, range, _| {
+                rendered_ranges.borrow_mut().push(range.clone());
+                range.map(|_| div().h(px(10.))).collect()
+            })
+            .with_overscan(2)
+            .on_scroll(move |event, _| scroll_events.borrow_mut().push(event.clone()))
+            .track_scroll(self.scroll_handle.clone())
+            .w(px(100.))
+            .h(self.height)
+        }
+    }
+
+    #[gpui::test]
+    fn test_uniform_list_renders_visible_items(cx: &mut TestAppContext) {
+        let rendered_ranges = Rc::new(RefCell::new(Vec::new()));
+        let scroll_events = Rc::new(RefCell::new(Vec::new()));
+        let scroll_handle = UniformListScrollHandle::new();
+        let (view, cx) = cx.add_window_view(|_| ListView {
+            height: px(100.),
+            scroll_handle: scroll_handle.clone(),
+            rendered_ranges: rendered_ranges.clone(),
+            scroll_events: scroll_events.clone(),
+        });
+        cx.run_until_parked();
+
+        // Only the visible items and the overscan are rendered, besides the item measured for
+        // the item height.
+        let take_rendered = || {
+            let ranges = rendered_ranges.borrow_mut().drain(..).collect::<Vec<_>>();
+            assert!(ranges.iter().map(|range| range.len()).sum::<usize>() < 100);
+            ranges.last().cloned().unwrap()
+        };
+        let last_event = || scroll_events.borrow().last().cloned().unwrap();
+        assert_eq!(take_rendered(), 0..12);
+        assert_eq!(
+            last_event(),
+            UniformListScrollEvent {
+                visible_range: 0..10,
+                scroll_top: px(0.),
+            }
+        );
+
+        // Resizing the list updates the visible range.
+        view.update(cx, |view, cx| {
+            view.height = px(200.);
+            cx.notify();
+        });
+        cx.run_until_parked();
+        assert_eq!(take_rendered(), 0..22);
+        assert_eq!(last_event().visible_range, 0..20);
+        view.update(cx, |view, cx| {
+            view.height = px(100.);
+            cx.notify();
+        });
+        cx.run_until_parked();
+        assert_eq!(take_rendered(), 0..12);
+
+        // Scrolling to the middle and to the end.
+        let mut scroll_to = |ix, alignment| {
+            scroll_handle.clone().scroll_to(ix, alignment);
+            view.update(cx, |_, cx| cx.notify());
+            cx.run_until_parked();
+        };
+        scroll_to(250_000, ScrollAlignment::Center);
+        assert_eq!(take_rendered(), 249_993..250_008);
+        assert_eq!(
+            last_event(),
+            UniformListScrollEvent {
+                visible_range: 249_995..250_006,
+                scroll_top: px(2_499_955.),
+            }
+        );
+        scroll_to(499_999, ScrollAlignment::Bottom);
+        assert_eq!(take_rendered(), 499_988..500_000);
+        assert_eq!(scroll_handle.scroll_top(), px(4_999_900.));
+        scroll_to(0, ScrollAlignment::Top);
+        assert_eq!(take_rendered(), 0..12);
+
+        // Scrolling with the mouse wheel.
+        let event_count = scroll_events.borrow().len();
+        cx.simulate_event(ScrollWheelEvent {
+            position: point(px(10.), px(10.)),
+            delta: ScrollDelta::Pixels(point(px(0.), px(-25.))),
+            ..Default::default()
+        });
+        assert_eq!(take_rendered(), 0..15);
+        assert_eq!(scroll_events.borrow().len(), event_count + 1);
+        assert_eq!(last_event().visible_range, 2..13);
+
+        // Dragging a scrollbar sets the scroll position directly.
+        scroll_handle.set_scroll_top(px(1_000.));
+        view.update(cx, |_, cx| cx.notify());
+        cx.run_until_parked();
+        assert_eq!(take_rendered(), 98..112);
+        assert_eq!(last_event().visible_range, 100..110);
+    }
+}
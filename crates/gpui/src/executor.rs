@@ -1,8 +1,8 @@
 use crate::{AppContext, PlatformDispatcher};
-use futures::channel::mpsc;
+use futures::{channel::mpsc, future::abortable};
 use smol::prelude::*;
 use std::{
-    fmt::Debug,
+    fmt::{self, Debug},
     marker::PhantomData,
     mem,
     num::NonZeroUsize,
@@ -69,6 +69,40 @@ impl<T> Task<T> {
     }
 }
 
+impl<T: Send + 'static> Task<T> {
+    /// Returns a task that resolves with this task's output if it completes
+    /// within the given duration, and with [`Elapsed`] otherwise. If the
+    /// deadline passes first, this task is dropped, cancelling it.
+    pub fn timeout(
+        self,
+        duration: Duration,
+        executor: &BackgroundExecutor,
+    ) -> Task<Result<T, Elapsed>> {
+        let timer = executor.timer(duration);
+        executor.spawn(async move {
+            let output = async move { Ok(self.await) };
+            let deadline = async move {
+                timer.await;
+                Err(Elapsed(duration))
+            };
+            output.or(deadline).await
+        })
+    }
+}
+
+/// The error returned by [`Task::timeout`] when a task doesn't complete
+/// before its deadline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elapsed(pub Duration);
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task did not complete within {:?}", self.0)
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
 impl<E, T> Task<Result<T, E>>
 where
     T: 'static,
@@ -96,6 +130,8 @@ impl<T> Future for Task<T> {
     }
 }
 
+pub use futures::future::{AbortHandle, Aborted};
+
 /// A task label is an opaque identifier that you can use to
 /// refer to a task in tests.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -137,6 +173,21 @@ impl BackgroundExecutor {
         self.spawn_internal::<R>(Box::pin(future), None)
     }
 
+    /// Enqueues the given future to be run on a background thread, returning
+    /// a handle that can stop it from outside of the task. Once aborted, the
+    /// future is dropped the next time it would be polled and the task
+    /// resolves with [`Aborted`].
+    pub fn spawn_abortable<R>(
+        &self,
+        future: impl Future<Output = R> + Send + 'static,
+    ) -> (Task<Result<R, Aborted>>, AbortHandle)
+    where
+        R: Send + 'static,
+    {
+        let (future, abort_handle) = abortable(future);
+        (self.spawn(future), abort_handle)
+    }
+
     /// Enqueues the given future to be run to completion on a background thread.
     /// The given label can be used to control the priority of the task in tests.
     pub fn spawn_labeled<R>(
@@ -509,3 +560,56 @@ impl<'a> Drop for Scope<'a> {
         self.executor.block(self.rx.next());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{self as gpui, Aborted, Elapsed, TestAppContext};
+
+    #[gpui::test]
+    async fn test_task_timeout(cx: &mut TestAppContext) {
+        let executor = cx.executor();
+
+        let task = executor.spawn({
+            let executor = executor.clone();
+            async move {
+                executor.timer(Duration::from_millis(10)).await;
+                1
+            }
+        });
+        let task = task.timeout(Duration::from_millis(100), &executor);
+        executor.advance_clock(Duration::from_millis(10));
+        assert_eq!(task.await, Ok(1));
+
+        let task = executor.spawn({
+            let executor = executor.clone();
+            async move {
+                executor.timer(Duration::from_millis(200)).await;
+                2
+            }
+        });
+        let task = task.timeout(Duration::from_millis(100), &executor);
+        executor.advance_clock(Duration::from_millis(100));
+        assert_eq!(task.await, Err(Elapsed(Duration::from_millis(100))));
+    }
+
+    #[gpui::test]
+    async fn test_spawn_abortable(cx: &mut TestAppContext) {
+        let executor = cx.executor();
+
+        let (task, abort_handle) = executor.spawn_abortable({
+            let executor = executor.clone();
+            async move {
+                executor.timer(Duration::from_secs(1)).await;
+                1
+            }
+        });
+        executor.run_until_parked();
+        abort_handle.abort();
+        assert_eq!(task.await, Err(Aborted));
+
+        let (task, _abort_handle) = executor.spawn_abortable(async { 2 });
+        assert_eq!(task.await, Ok(2));
+    }
+}
@@ -16,7 +16,7 @@ use std::{
 use crate::{AppContext, DisplayId};
 
 /// An axis along which a measurement can be made.
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum Axis {
     /// The y axis, or up and down
     Vertical,
@@ -1390,6 +1390,72 @@ impl Bounds<Pixels> {
             size: self.size.to_device_pixels(factor),
         }
     }
+
+    /// Splits the bounds into columns, laid out from left to right, whose widths are
+    /// proportional to the given fractions.
+    ///
+    /// The fractions don't need to add up to 1.0, as they are normalized by their sum.
+    /// Negative fractions are treated as zero, and if none of the fractions is positive,
+    /// the bounds are split into equal columns. The returned bounds cover these bounds
+    /// without gaps or overlaps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use gpui::{point, px, size, Bounds};
+    /// let bounds = Bounds::new(point(px(0.), px(0.)), size(px(100.), px(20.)));
+    /// let columns = bounds.split_horizontally(&[1., 3.]);
+    /// assert_eq!(columns[0], Bounds::new(point(px(0.), px(0.)), size(px(25.), px(20.))));
+    /// assert_eq!(columns[1], Bounds::new(point(px(25.), px(0.)), size(px(75.), px(20.))));
+    /// ```
+    pub fn split_horizontally(&self, fractions: &[f32]) -> Vec<Bounds<Pixels>> {
+        self.split_along(Axis::Horizontal, fractions)
+    }
+
+    /// Splits the bounds into rows, laid out from top to bottom, whose heights are
+    /// proportional to the given fractions.
+    ///
+    /// See [`Bounds::split_horizontally`] for how the fractions are interpreted.
+    pub fn split_vertically(&self, fractions: &[f32]) -> Vec<Bounds<Pixels>> {
+        self.split_along(Axis::Vertical, fractions)
+    }
+
+    fn split_along(&self, axis: Axis, fractions: &[f32]) -> Vec<Bounds<Pixels>> {
+        let fractions = fractions
+            .iter()
+            .map(|fraction| fraction.max(0.))
+            .collect::<Vec<_>>();
+        let mut total = fractions.iter().sum::<f32>();
+        let even = total <= 0.;
+        if even {
+            total = fractions.len() as f32;
+        }
+
+        let start = self.origin.along(axis);
+        let length = self.size.along(axis);
+        let mut cumulative = 0.;
+        let mut part_start = start;
+        fractions
+            .iter()
+            .enumerate()
+            .map(|(ix, fraction)| {
+                cumulative += if even { 1. } else { *fraction };
+                // Compute each edge from the running total, and pin the last one to the end of
+                // the bounds, so that rounding errors don't open gaps between the parts.
+                let part_end = if ix == fractions.len() - 1 {
+                    start + length
+                } else {
+                    start + length * (cumulative / total)
+                };
+                let part = Bounds {
+                    origin: self.origin.apply_along(axis, |_| part_start),
+                    size: self.size.apply_along(axis, |_| part_end - part_start),
+                };
+                part_start = part_end;
+                part
+            })
+            .collect()
+    }
 }
 
 impl Bounds<DevicePixels> {
@@ -1977,6 +2043,26 @@ impl Corners<Pixels> {
         }
     }
 
+    /// Clamps each corner's radius to half the smaller side of a quad with the given size, so
+    /// that the rounded corners of opposite sides never overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use zed::{px, size, Corners};
+    /// let corners = Corners::all(px(40.)).clamp_radii_for_quad_size(size(px(100.), px(30.)));
+    /// assert_eq!(corners, Corners::all(px(15.)));
+    /// ```
+    pub fn clamp_radii_for_quad_size(&self, size: Size<Pixels>) -> Corners<Pixels> {
+        let max = size.width.min(size.height).max(Pixels::ZERO) / 2.;
+        Corners {
+            top_left: self.top_left.min(max),
+            top_right: self.top_right.min(max),
+            bottom_right: self.bottom_right.min(max),
+            bottom_left: self.bottom_left.min(max),
+        }
+    }
+
     /// Returns the maximum value of any corner.
     ///
     /// # Returns
@@ -3085,6 +3171,35 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_corners_clamp_radii_for_quad_size() {
+        let corners = Corners {
+            top_left: px(4.),
+            top_right: px(12.),
+            bottom_right: px(0.),
+            bottom_left: px(30.),
+        };
+        assert_eq!(
+            corners.clamp_radii_for_quad_size(size(px(20.), px(50.))),
+            Corners {
+                top_left: px(4.),
+                top_right: px(10.),
+                bottom_right: px(0.),
+                bottom_left: px(10.),
+            }
+        );
+
+        // Zero-size and inverted quads can't be rounded at all.
+        assert_eq!(
+            corners.clamp_radii_for_quad_size(size(px(0.), px(50.))),
+            Corners::all(px(0.))
+        );
+        assert_eq!(
+            corners.clamp_radii_for_quad_size(size(px(-5.), px(50.))),
+            Corners::all(px(0.))
+        );
+    }
+
     #[test]
     fn test_bounds_intersects() {
         let bounds1 = Bounds {
@@ -3118,4 +3233,63 @@ mod tests {
         // Test Case 3: Bounds intersecting with themselves
         assert_eq!(bounds1.intersects(&bounds1), true);
     }
+
+    #[test]
+    fn test_bounds_split_horizontally() {
+        let bounds = Bounds::new(point(px(10.), px(20.)), size(px(100.), px(30.)));
+
+        let columns = bounds.split_horizontally(&[1., 1., 2.]);
+        assert_eq!(
+            columns,
+            vec![
+                Bounds::new(point(px(10.), px(20.)), size(px(25.), px(30.))),
+                Bounds::new(point(px(35.), px(20.)), size(px(25.), px(30.))),
+                Bounds::new(point(px(60.), px(20.)), size(px(50.), px(30.))),
+            ]
+        );
+
+        // Fractions are normalized, so they don't need to add up to 1.
+        let columns = bounds.split_horizontally(&[0.5, 0.25, 0.25]);
+        assert_eq!(
+            columns
+                .iter()
+                .map(|column| column.size.width)
+                .collect::<Vec<_>>(),
+            [px(50.), px(25.), px(25.)]
+        );
+
+        // Without positive fractions, the bounds are split evenly.
+        let columns = bounds.split_horizontally(&[0., 0.]);
+        assert_eq!(
+            columns,
+            vec![
+                Bounds::new(point(px(10.), px(20.)), size(px(50.), px(30.))),
+                Bounds::new(point(px(60.), px(20.)), size(px(50.), px(30.))),
+            ]
+        );
+        assert!(bounds.split_horizontally(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_bounds_split_vertically() {
+        let bounds = Bounds::new(point(px(0.), px(5.)), size(px(40.), px(10.)));
+
+        // Parts that can't be represented exactly still cover the bounds without gaps.
+        let rows = bounds.split_vertically(&[1., 1., 1.]);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].top(), bounds.top());
+        assert_eq!(rows[0].bottom(), rows[1].top());
+        assert_eq!(rows[1].bottom(), rows[2].top());
+        assert_eq!(rows[2].bottom(), bounds.bottom());
+        for row in &rows {
+            assert_eq!(row.origin.x, px(0.));
+            assert_eq!(row.size.width, px(40.));
+            assert!((row.size.height.0 - 10. / 3.).abs() < 0.001);
+        }
+
+        let rows = bounds.split_vertically(&[1., 4.]);
+        assert_eq!(rows[0].size.height, px(2.));
+        assert_eq!(rows[1].top(), px(7.));
+        assert_eq!(rows[1].bottom(), px(15.));
+    }
 }
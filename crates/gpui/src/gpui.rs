@@ -78,10 +78,13 @@ mod elements;
 mod executor;
 mod geometry;
 mod global;
+mod gradient;
 mod input;
 mod interactive;
+pub mod json;
 mod key_dispatch;
 mod keymap;
+mod path_builder;
 mod platform;
 pub mod prelude;
 mod scene;
@@ -97,6 +100,7 @@ pub mod test;
 mod text_system;
 mod util;
 mod view;
+pub mod views;
 mod window;
 
 /// Do not touch, here be dragons for use by gpui_macros and such.
@@ -128,10 +132,12 @@ pub use executor::*;
 pub use geometry::*;
 pub use global::*;
 pub use gpui_macros::{register_action, test, IntoElement, Render};
+pub use gradient::*;
 pub use input::*;
 pub use interactive::*;
 use key_dispatch::*;
 pub use keymap::*;
+pub use path_builder::*;
 pub use platform::*;
 pub use refineable::*;
 pub use scene::*;
@@ -0,0 +1,326 @@
+use crate::{point, transparent_black, Bounds, Hsla, Pixels, Point, Rgba};
+use smallvec::SmallVec;
+
+/// The most color stops that the quad shaders draw a gradient with. Gradients with more stops
+/// are resampled at evenly spaced positions.
+pub(crate) const MAX_GRADIENT_STOPS: usize = 4;
+
+/// A color at a position along a gradient.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorStop {
+    /// The color of the gradient at this stop.
+    pub color: Hsla,
+    /// The position of this stop along the gradient, from 0 at its start to 1 at its end.
+    pub position: f32,
+}
+
+/// Creates a color stop at the given position along a gradient, from 0 to 1.
+pub fn color_stop(color: impl Into<Hsla>, position: f32) -> ColorStop {
+    ColorStop {
+        color: color.into(),
+        position: position.clamp(0., 1.),
+    }
+}
+
+/// A gradient along a line through the center of the filled bounds, which starts and ends on
+/// lines through their corners, like CSS linear gradients.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearGradient {
+    /// The direction of the gradient, in degrees clockwise from the top of the bounds.
+    pub angle: f32,
+    /// The colors of the gradient, ordered by position.
+    pub stops: SmallVec<[ColorStop; MAX_GRADIENT_STOPS]>,
+}
+
+/// A gradient spreading out in circles from a point in the filled bounds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadialGradient {
+    /// The center of the gradient, relative to the filled bounds, from (0, 0) at their top left
+    /// corner to (1, 1) at their bottom right corner.
+    pub center: Point<f32>,
+    /// The radius of the circle where the gradient ends, relative to half the smaller side of
+    /// the filled bounds.
+    pub radius: f32,
+    /// The colors of the gradient, ordered by position.
+    pub stops: SmallVec<[ColorStop; MAX_GRADIENT_STOPS]>,
+}
+
+/// Creates a linear gradient in the direction of the given angle, in degrees clockwise from the
+/// top. The gradient is a solid color when given a single stop.
+pub fn linear_gradient(angle: f32, stops: impl IntoIterator<Item = ColorStop>) -> LinearGradient {
+    LinearGradient {
+        angle,
+        stops: sorted_stops(stops),
+    }
+}
+
+/// Creates a radial gradient around the given center, relative to the filled bounds, which ends
+/// at the given radius, relative to half their smaller side. The gradient is a solid color when
+/// given a single stop.
+pub fn radial_gradient(
+    center: Point<f32>,
+    radius: f32,
+    stops: impl IntoIterator<Item = ColorStop>,
+) -> RadialGradient {
+    RadialGradient {
+        center,
+        radius,
+        stops: sorted_stops(stops),
+    }
+}
+
+fn sorted_stops(
+    stops: impl IntoIterator<Item = ColorStop>,
+) -> SmallVec<[ColorStop; MAX_GRADIENT_STOPS]> {
+    let mut stops = stops.into_iter().collect::<SmallVec<_>>();
+    stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+    stops
+}
+
+impl LinearGradient {
+    /// Returns the points where the gradient starts and ends in the given bounds.
+    pub(crate) fn axis(&self, bounds: Bounds<Pixels>) -> (Point<Pixels>, Point<Pixels>) {
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+        let half_length = (bounds.size.width * sin.abs() + bounds.size.height * cos.abs()) / 2.;
+        let offset = point(half_length * sin, half_length * -cos);
+        let center = bounds.center();
+        (center - offset, center + offset)
+    }
+}
+
+impl RadialGradient {
+    /// Returns the center of the gradient in the given bounds, and a point on the circle where
+    /// it ends.
+    pub(crate) fn axis(&self, bounds: Bounds<Pixels>) -> (Point<Pixels>, Point<Pixels>) {
+        let center = bounds.origin
+            + point(
+                bounds.size.width * self.center.x,
+                bounds.size.height * self.center.y,
+            );
+        let radius = bounds.size.width.min(bounds.size.height) / 2. * self.radius;
+        (center, center + point(radius, Pixels::ZERO))
+    }
+}
+
+/// Returns the color of a gradient with the given stops at a position along it, interpolating
+/// between stops in the Oklab color space, with premultiplied alpha, as the quad shaders do.
+pub(crate) fn gradient_color_at(stops: &[ColorStop], position: f32) -> Hsla {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return transparent_black();
+    };
+    if position <= first.position {
+        return first.color;
+    }
+    if position >= last.position {
+        return last.color;
+    }
+    for pair in stops.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        if position <= end.position {
+            let span = end.position - start.position;
+            let t = if span > 0. {
+                (position - start.position) / span
+            } else {
+                1.
+            };
+            return mix_oklab(start.color, end.color, t);
+        }
+    }
+    last.color
+}
+
+/// Resamples the stops of a gradient to at most `MAX_GRADIENT_STOPS` stops, evenly spaced
+/// between its first and last stops when there are more.
+pub(crate) fn resample_stops(stops: &[ColorStop]) -> SmallVec<[ColorStop; MAX_GRADIENT_STOPS]> {
+    if stops.len() <= MAX_GRADIENT_STOPS {
+        return stops.iter().copied().collect();
+    }
+    let start = stops[0].position;
+    let end = stops[stops.len() - 1].position;
+    (0..MAX_GRADIENT_STOPS)
+        .map(|ix| {
+            let position = start + (end - start) * ix as f32 / (MAX_GRADIENT_STOPS - 1) as f32;
+            ColorStop {
+                color: gradient_color_at(stops, position),
+                position,
+            }
+        })
+        .collect()
+}
+
+fn mix_oklab(start: Hsla, end: Hsla, t: f32) -> Hsla {
+    let start = to_premultiplied_oklab(start.into());
+    let end = to_premultiplied_oklab(end.into());
+    let mixed: [f32; 4] = std::array::from_fn(|ix| start[ix] + (end[ix] - start[ix]) * t);
+    from_premultiplied_oklab(mixed).into()
+}
+
+fn to_premultiplied_oklab(color: Rgba) -> [f32; 4] {
+    let [r, g, b] = [color.r, color.g, color.b].map(srgb_to_linear);
+    let l = (0.4122215 * r + 0.5363325 * g + 0.0514460 * b).cbrt();
+    let m = (0.2119035 * r + 0.6806995 * g + 0.1073970 * b).cbrt();
+    let s = (0.0883025 * r + 0.2817188 * g + 0.6299787 * b).cbrt();
+    [
+        (0.2104543 * l + 0.7936178 * m - 0.0040720 * s) * color.a,
+        (1.9779985 * l - 2.4285922 * m + 0.4505937 * s) * color.a,
+        (0.0259040 * l + 0.7827718 * m - 0.8086758 * s) * color.a,
+        color.a,
+    ]
+}
+
+fn from_premultiplied_oklab([lightness, a, b, alpha]: [f32; 4]) -> Rgba {
+    if alpha <= 0. {
+        return Rgba::default();
+    }
+    let [lightness, a, b] = [lightness, a, b].map(|component| component / alpha);
+    let l = (lightness + 0.3963378 * a + 0.2158038 * b).powi(3);
+    let m = (lightness - 0.1055613 * a - 0.0638542 * b).powi(3);
+    let s = (lightness - 0.0894842 * a - 1.2914855 * b).powi(3);
+    let [r, g, b] = [
+        4.0767417 * l - 3.3077116 * m + 0.2309699 * s,
+        -1.2684380 * l + 2.6097574 * m - 0.3413194 * s,
+        -0.0041961 * l - 0.7034186 * m + 1.7076147 * s,
+    ]
+    .map(|channel| linear_to_srgb(channel.clamp(0., 1.)));
+    Rgba { r, g, b, a: alpha }
+}
+
+fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(channel: f32) -> f32 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1. / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{black, px, red, size, white, Background};
+
+    fn rgba(color: Hsla) -> [f32; 4] {
+        let color = Rgba::from(color);
+        [color.r, color.g, color.b, color.a].map(|channel| (channel * 1000.).round() / 1000.)
+    }
+
+    #[test]
+    fn test_gradient_color_at() {
+        let stops = [color_stop(black(), 0.), color_stop(white(), 1.)];
+        assert_eq!(rgba(gradient_color_at(&stops, -1.)), [0., 0., 0., 1.]);
+        assert_eq!(rgba(gradient_color_at(&stops, 0.)), [0., 0., 0., 1.]);
+        assert_eq!(rgba(gradient_color_at(&stops, 1.)), [1., 1., 1., 1.]);
+        assert_eq!(rgba(gradient_color_at(&stops, 2.)), [1., 1., 1., 1.]);
+
+        // The middle of the gradient is perceptually halfway between black and white, which is
+        // darker than the average of their sRGB channels.
+        let middle = rgba(gradient_color_at(&stops, 0.5));
+        assert_eq!(middle, [0.389, 0.389, 0.389, 1.]);
+
+        // Fading to transparency keeps the color instead of fading through black.
+        let stops = [color_stop(red(), 0.), color_stop(transparent_black(), 1.)];
+        let faded = rgba(gradient_color_at(&stops, 0.5));
+        assert_eq!(faded, [1., 0., 0., 0.5]);
+
+        // Stops are sorted, and a color changes abruptly at stops sharing a position.
+        let gradient = linear_gradient(
+            0.,
+            [
+                color_stop(white(), 1.),
+                color_stop(black(), 0.5),
+                color_stop(white(), 0.5),
+                color_stop(black(), 0.),
+            ],
+        );
+        assert_eq!(
+            rgba(gradient_color_at(&gradient.stops, 0.49)),
+            [0., 0., 0., 1.]
+        );
+        assert_eq!(
+            rgba(gradient_color_at(&gradient.stops, 0.51)),
+            [1., 1., 1., 1.]
+        );
+    }
+
+    #[test]
+    fn test_resample_stops() {
+        let stops = (0..=4)
+            .map(|ix| color_stop(hsla_grey(ix as f32 / 4.), ix as f32 / 4.))
+            .collect::<Vec<_>>();
+        assert_eq!(resample_stops(&stops[..3]).as_slice(), &stops[..3]);
+
+        let resampled = resample_stops(&stops);
+        assert_eq!(resampled.len(), MAX_GRADIENT_STOPS);
+        let positions = resampled
+            .iter()
+            .map(|stop| stop.position)
+            .collect::<Vec<_>>();
+        assert_eq!(positions, [0., 1. / 3., 2. / 3., 1.]);
+        assert_eq!(resampled[0].color, stops[0].color);
+        assert_eq!(resampled[3].color, stops[4].color);
+    }
+
+    fn hsla_grey(lightness: f32) -> Hsla {
+        Hsla {
+            h: 0.,
+            s: 0.,
+            l: lightness,
+            a: 1.,
+        }
+    }
+
+    #[test]
+    fn test_gradient_axes() {
+        let bounds = Bounds::new(point(px(10.), px(20.)), size(px(100.), px(50.)));
+
+        // Gradients to the bottom and to the right span the bounds.
+        let (start, end) = linear_gradient(180., []).axis(bounds);
+        assert_eq!(round(start), point(px(60.), px(20.)));
+        assert_eq!(round(end), point(px(60.), px(70.)));
+        let (start, end) = linear_gradient(90., []).axis(bounds);
+        assert_eq!(round(start), point(px(10.), px(45.)));
+        assert_eq!(round(end), point(px(110.), px(45.)));
+
+        // Diagonal gradients end on the lines through the corners, perpendicular to them.
+        let (start, end) = linear_gradient(45., []).axis(bounds);
+        let length = (end.x - start.x).0.hypot((end.y - start.y).0);
+        assert!((length - 150. / 2f32.sqrt()).abs() < 0.01);
+
+        let (center, edge) = radial_gradient(point(0.5, 0.), 2., []).axis(bounds);
+        assert_eq!(center, point(px(60.), px(20.)));
+        assert_eq!(edge, point(px(110.), px(20.)));
+    }
+
+    fn round(point: Point<Pixels>) -> Point<Pixels> {
+        point.map(|coordinate| px(coordinate.0.round()))
+    }
+
+    #[test]
+    fn test_degenerate_gradients() {
+        let bounds = Bounds::new(point(px(0.), px(0.)), size(px(100.), px(50.)));
+
+        // A gradient with a single stop is drawn as a solid color.
+        let fill = linear_gradient(90., [color_stop(red(), 0.3)]).into();
+        let background = Background::new(&fill, bounds, 2., 1.);
+        assert_eq!(background, Background::Solid(red()));
+
+        // A gradient without stops is transparent.
+        let fill = radial_gradient(point(0.5, 0.5), 1., []).into();
+        let background = Background::new(&fill, bounds, 2., 0.5);
+        assert_eq!(background, Background::Solid(transparent_black()));
+
+        let fill = linear_gradient(90., [color_stop(red(), 0.), color_stop(black(), 1.)]).into();
+        let Background::Gradient(gradient) = Background::new(&fill, bounds, 2., 0.5) else {
+            panic!("expected a gradient");
+        };
+        assert_eq!(gradient.stop_count, 2);
+        assert_eq!(gradient.colors[0].a, 0.5);
+    }
+}
@@ -0,0 +1,125 @@
+//! Conversion of GPUI state into JSON, used to dump element trees and model state when
+//! debugging.
+
+use serde_json::{Map, Value};
+
+use crate::{Bounds, Pixels, Point, SharedString, Size};
+
+pub use gpui_macros::ToJson;
+
+/// A type that can be converted into JSON for debugging.
+///
+/// This can be derived with `#[derive(ToJson)]` for structs whose fields implement
+/// `serde::Serialize`. Fields whose type is one of the struct's type parameters are converted
+/// with that parameter's `ToJson` impl instead, and fields marked with `#[json(skip)]` are
+/// left out.
+///
+/// ```
+/// use gpui::json::ToJson;
+/// use gpui::private::serde_json::json;
+///
+/// #[derive(ToJson)]
+/// struct Selection<T> {
+///     start: T,
+///     end: T,
+///     reversed: bool,
+///     #[json(skip)]
+///     cached_len: Option<usize>,
+/// }
+///
+/// let selection = Selection { start: 2u32, end: 5u32, reversed: false, cached_len: None };
+/// assert_eq!(selection.to_json(), json!({ "start": 2, "end": 5, "reversed": false }));
+/// ```
+pub trait ToJson {
+    /// Converts this value into JSON.
+    fn to_json(&self) -> Value;
+}
+
+macro_rules! impl_to_json_via_from {
+    ($($ty:ty),*) => {
+        $(
+            impl ToJson for $ty {
+                fn to_json(&self) -> Value {
+                    Value::from(self.clone())
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json_via_from!(bool, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64, String);
+
+impl ToJson for str {
+    fn to_json(&self) -> Value {
+        Value::from(self)
+    }
+}
+
+impl ToJson for SharedString {
+    fn to_json(&self) -> Value {
+        Value::from(self.as_ref())
+    }
+}
+
+impl ToJson for Pixels {
+    fn to_json(&self) -> Value {
+        Value::from(self.0)
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Value {
+        (*self).to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, ToJson::to_json)
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: ToJson + Clone + Default + std::fmt::Debug> ToJson for Point<T> {
+    fn to_json(&self) -> Value {
+        object([("x", self.x.to_json()), ("y", self.y.to_json())])
+    }
+}
+
+impl<T: ToJson + Clone + Default + std::fmt::Debug> ToJson for Size<T> {
+    fn to_json(&self) -> Value {
+        object([
+            ("width", self.width.to_json()),
+            ("height", self.height.to_json()),
+        ])
+    }
+}
+
+impl<T: ToJson + Clone + Default + std::fmt::Debug> ToJson for Bounds<T> {
+    fn to_json(&self) -> Value {
+        object([
+            ("origin", self.origin.to_json()),
+            ("size", self.size.to_json()),
+        ])
+    }
+}
+
+fn object<const N: usize>(entries: [(&str, Value); N]) -> Value {
+    Value::Object(
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect::<Map<_, _>>(),
+    )
+}
@@ -89,6 +89,64 @@ impl Keymap {
             .filter(move |binding| binding.action().partial_eq(action))
     }
 
+    /// Returns the keystrokes and context predicates of every binding of the given action, in the
+    /// order they were added. Bindings that were disabled, or replaced by a later binding of the
+    /// same keystrokes in the same context, are left out, and bindings that were added more than
+    /// once are only returned once.
+    pub fn all_bindings_for_action(
+        &self,
+        action: &dyn Action,
+    ) -> Vec<(SmallVec<[Keystroke; 2]>, Option<KeyBindingContextPredicate>)> {
+        let action_id = action.type_id();
+        self.binding_indices_by_action_id
+            .get(&action_id)
+            .map_or(&[] as _, SmallVec::as_slice)
+            .iter()
+            .filter(|ix| {
+                let binding = &self.bindings[**ix];
+                binding.action().partial_eq(action)
+                    && !self.bindings[**ix + 1..].iter().any(|later| {
+                        later.keystrokes == binding.keystrokes
+                            && later.context_predicate == binding.context_predicate
+                    })
+                    && self
+                        .disabled_keystrokes
+                        .get(&binding.keystrokes)
+                        .map_or(true, |disabled| {
+                            !disabled.contains(&None)
+                                && !disabled.contains(&binding.context_predicate)
+                        })
+            })
+            .map(|ix| {
+                let binding = &self.bindings[*ix];
+                (
+                    binding.keystrokes.clone(),
+                    binding.context_predicate.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the action that typing the given keystrokes invokes, given the contexts from the
+    /// root of the window to the focused element. Like when dispatching keystrokes, bindings in
+    /// deeper contexts take precedence, then bindings that were added later.
+    pub fn action_for_keystrokes(
+        &self,
+        keystrokes: &[Keystroke],
+        context_stack: &[KeyContext],
+    ) -> Option<&dyn Action> {
+        let shallowest = context_stack.len().min(1);
+        (shallowest..=context_stack.len())
+            .rev()
+            .find_map(|depth| {
+                self.bindings.iter().rev().find(|binding| {
+                    binding.match_keystrokes(keystrokes) == KeyMatch::Matched
+                        && self.binding_enabled(binding, &context_stack[..depth])
+                })
+            })
+            .map(KeyBinding::action)
+    }
+
     /// Check if the given binding is enabled, given a certain key context.
     pub fn binding_enabled(&self, binding: &KeyBinding, context: &[KeyContext]) -> bool {
         // If binding has a context predicate, it must match the current context,
@@ -122,7 +180,11 @@ impl Keymap {
 mod tests {
     use super::*;
     use crate as gpui;
-    use gpui::actions;
+    use gpui::{
+        actions, div, FocusHandle, FocusableView, InteractiveElement, IntoElement, KeyDownEvent,
+        Render, TestAppContext, ViewContext, VisualContext, DEFAULT_PENDING_INPUT_TIMEOUT,
+    };
+    use std::{cell::RefCell, rc::Rc, time::Duration};
 
     actions!(
         keymap_test,
@@ -180,4 +242,358 @@ mod tests {
         // binding is globally disabled
         assert!(!keymap.binding_enabled(&bindings[1], &[KeyContext::parse("barf").unwrap()]));
     }
+
+    #[test]
+    fn test_all_bindings_for_action() {
+        let keymap = Keymap::new(vec![
+            // Default bindings
+            KeyBinding::new("ctrl-a", ActionAlpha {}, None),
+            KeyBinding::new("ctrl-b", ActionAlpha {}, Some("editor")),
+            KeyBinding::new("ctrl-c", ActionAlpha {}, Some("pane")),
+            KeyBinding::new("ctrl-d", ActionAlpha {}, Some("editor")),
+            KeyBinding::new("ctrl-e", ActionAlpha {}, Some("pane")),
+            KeyBinding::new("ctrl-f", ActionBeta {}, None),
+            // User bindings
+            KeyBinding::new("ctrl-a", ActionAlpha {}, None),
+            KeyBinding::new("ctrl-b", ActionGamma {}, Some("editor")),
+            KeyBinding::new("ctrl-c", ActionGamma {}, Some("editor")),
+            KeyBinding::new("ctrl-d", NoAction {}, Some("editor")),
+            KeyBinding::new("ctrl-e", NoAction {}, None),
+            KeyBinding::new("ctrl-g", ActionAlpha {}, Some("editor")),
+        ]);
+
+        let describe = |action: &dyn Action| {
+            keymap
+                .all_bindings_for_action(action)
+                .into_iter()
+                .map(|(keystrokes, predicate)| {
+                    let keystrokes = keystrokes
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    (keystrokes, predicate)
+                })
+                .collect::<Vec<_>>()
+        };
+        let predicate = |source| KeyBindingContextPredicate::parse(source).ok();
+
+        // The duplicate binding is listed once, overridden and unbound ones aren't listed, and a
+        // binding overridden in another context is still listed.
+        assert_eq!(
+            describe(&ActionAlpha {}),
+            [
+                ("ctrl-c".to_string(), predicate("pane")),
+                ("ctrl-a".to_string(), None),
+                ("ctrl-g".to_string(), predicate("editor")),
+            ]
+        );
+        assert_eq!(describe(&ActionBeta {}), [("ctrl-f".to_string(), None)]);
+        assert_eq!(describe(&ActionGamma {}).len(), 2);
+        assert!(describe(&ActionDelta {}).is_empty());
+    }
+
+    #[test]
+    fn test_action_for_keystrokes() {
+        let keymap = Keymap::new(vec![
+            KeyBinding::new("ctrl-a", ActionAlpha {}, None),
+            KeyBinding::new("ctrl-a", ActionBeta {}, Some("pane")),
+            KeyBinding::new("ctrl-k ctrl-a", ActionGamma {}, Some("editor")),
+            KeyBinding::new("ctrl-b", ActionAlpha {}, Some("pane")),
+            KeyBinding::new("ctrl-b", ActionBeta {}, Some("pane")),
+            KeyBinding::new("ctrl-c", ActionAlpha {}, None),
+            KeyBinding::new("ctrl-c", NoAction {}, Some("editor")),
+            KeyBinding::new("ctrl-d", ActionAlpha {}, Some("editor")),
+            KeyBinding::new("ctrl-d", ActionBeta {}, Some("pane")),
+        ]);
+        let contexts = [
+            KeyContext::parse("workspace").unwrap(),
+            KeyContext::parse("pane").unwrap(),
+            KeyContext::parse("editor").unwrap(),
+        ];
+        let action_name = |keystrokes: &str, depth: usize| {
+            let keystrokes = keystrokes
+                .split(' ')
+                .map(|keystroke| Keystroke::parse(keystroke).unwrap())
+                .collect::<Vec<_>>();
+            keymap
+                .action_for_keystrokes(&keystrokes, &contexts[..depth])
+                .map(|action| action.name())
+        };
+
+        assert_eq!(action_name("ctrl-a", 0), Some("keymap_test::ActionAlpha"));
+        assert_eq!(action_name("ctrl-a", 1), Some("keymap_test::ActionAlpha"));
+        assert_eq!(action_name("ctrl-a", 2), Some("keymap_test::ActionBeta"));
+        // Bindings in deeper contexts take precedence...
+        assert_eq!(action_name("ctrl-a", 3), Some("keymap_test::ActionAlpha"));
+        assert_eq!(action_name("ctrl-d", 3), Some("keymap_test::ActionAlpha"));
+        // ...then bindings that were added later.
+        assert_eq!(action_name("ctrl-b", 2), Some("keymap_test::ActionBeta"));
+        assert_eq!(
+            action_name("ctrl-k ctrl-a", 3),
+            Some("keymap_test::ActionGamma")
+        );
+        assert_eq!(action_name("ctrl-k", 3), None);
+        assert_eq!(action_name("ctrl-k ctrl-a", 2), None);
+        // A binding disabled in the focused context is still invoked from its ancestors.
+        assert_eq!(action_name("ctrl-c", 3), Some("keymap_test::ActionAlpha"));
+    }
+
+    struct ChordHost {
+        focus_handle: FocusHandle,
+        events: Vec<String>,
+    }
+
+    impl FocusableView for ChordHost {
+        fn focus_handle(&self, _: &gpui::AppContext) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for ChordHost {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .key_context("ChordHost")
+                .track_focus(&self.focus_handle)
+                .on_action(cx.listener(|this, _: &ActionAlpha, _| this.events.push("alpha".into())))
+                .on_action(cx.listener(|this, _: &ActionBeta, _| this.events.push("beta".into())))
+                .on_action(cx.listener(|this, _: &ActionGamma, _| this.events.push("gamma".into())))
+                .on_key_down(cx.listener(|this, event: &KeyDownEvent, _| {
+                    this.events.push(event.keystroke.to_string())
+                }))
+        }
+    }
+
+    struct NestedContexts {
+        focus_handle: FocusHandle,
+        dispatched: usize,
+    }
+
+    impl FocusableView for NestedContexts {
+        fn focus_handle(&self, _: &gpui::AppContext) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for NestedContexts {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div().key_context("Workspace").child(
+                div().key_context("Pane").child(
+                    div()
+                        .key_context("Editor mode=full")
+                        .track_focus(&self.focus_handle)
+                        .on_action(cx.listener(|this, _: &ActionDelta, _| this.dispatched += 1)),
+                ),
+            )
+        }
+    }
+
+    #[gpui::test]
+    fn test_context_predicates_match_ancestors(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|cx| NestedContexts {
+            focus_handle: cx.focus_handle(),
+            dispatched: 0,
+        });
+        cx.focus_view(&view);
+
+        for (predicate, matches) in [
+            ("Editor", true),
+            ("Pane", true),
+            ("Workspace", true),
+            ("Terminal", false),
+            ("Editor && mode == full", true),
+            ("Editor && mode == auto_height", false),
+            ("Editor && mode != auto_height", true),
+            // Each predicate is checked against a single context at a time.
+            ("Pane && mode == full", false),
+            ("Workspace && Pane", false),
+            ("Workspace || Terminal", true),
+            ("!Terminal && (Editor || Terminal)", true),
+            // The innermost context doesn't match, but its parent does.
+            ("!Editor", true),
+            ("Pane > Editor", true),
+            ("Workspace > Editor", false),
+            ("Workspace > Pane > Editor", true),
+            ("Editor > Pane", false),
+            ("Pane > Editor && mode == full", true),
+            ("Pane > Editor && !mode", false),
+        ] {
+            cx.update(|cx| {
+                cx.clear_key_bindings();
+                cx.bind_keys([KeyBinding::new("ctrl-x", ActionDelta {}, Some(predicate))]);
+            });
+            cx.simulate_keystrokes("ctrl-x");
+            let dispatched = view.update(cx, |view, _| std::mem::take(&mut view.dispatched));
+            assert_eq!(dispatched == 1, matches, "{predicate:?}");
+        }
+    }
+
+    #[gpui::test]
+    fn test_multi_stroke_bindings(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.bind_keys([
+                KeyBinding::new("ctrl-k", ActionAlpha {}, None),
+                KeyBinding::new("ctrl-k ctrl-s", ActionBeta {}, None),
+                KeyBinding::new("g g", ActionGamma {}, Some("ChordHost")),
+            ])
+        });
+        let (host, cx) = cx.add_window_view(|cx| ChordHost {
+            focus_handle: cx.focus_handle(),
+            events: Vec::new(),
+        });
+        cx.focus_view(&host);
+        let take_events = |cx: &mut gpui::VisualTestContext| {
+            host.update(cx, |host, _| std::mem::take(&mut host.events))
+        };
+
+        // The longer binding wins over its prefix once it's complete.
+        cx.simulate_keystrokes("ctrl-k");
+        assert_eq!(
+            cx.update(|cx| {
+                cx.pending_input_keystrokes().map(|keystrokes| {
+                    keystrokes
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                })
+            }),
+            Some(vec!["ctrl-k".to_string()])
+        );
+        assert_eq!(take_events(cx), Vec::<String>::new());
+        cx.simulate_keystrokes("ctrl-s");
+        assert!(cx.update(|cx| cx.pending_input_keystrokes().is_none()));
+        assert_eq!(take_events(cx), ["beta"]);
+
+        cx.simulate_keystrokes("g g");
+        assert_eq!(take_events(cx), ["gamma"]);
+
+        // A key that can't continue the binding runs the prefix, then is handled on its own.
+        cx.simulate_keystrokes("ctrl-k x");
+        assert_eq!(take_events(cx), ["alpha", "x"]);
+        cx.simulate_keystrokes("g x g g");
+        assert_eq!(take_events(cx), ["g", "x", "gamma"]);
+    }
+
+    #[gpui::test]
+    fn test_pending_input_timeout(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.bind_keys([
+                KeyBinding::new("ctrl-k", ActionAlpha {}, None),
+                KeyBinding::new("ctrl-k ctrl-s", ActionBeta {}, None),
+                KeyBinding::new("g g", ActionGamma {}, None),
+            ])
+        });
+        let (host, cx) = cx.add_window_view(|cx| ChordHost {
+            focus_handle: cx.focus_handle(),
+            events: Vec::new(),
+        });
+        cx.focus_view(&host);
+        let take_events = |cx: &mut gpui::VisualTestContext| {
+            host.update(cx, |host, _| std::mem::take(&mut host.events))
+        };
+
+        // Once the timeout expires, the prefix runs its own binding...
+        cx.simulate_keystrokes("ctrl-k");
+        cx.executor()
+            .advance_clock(DEFAULT_PENDING_INPUT_TIMEOUT - Duration::from_millis(1));
+        cx.run_until_parked();
+        assert_eq!(take_events(cx), Vec::<String>::new());
+        cx.executor().advance_clock(Duration::from_millis(1));
+        cx.run_until_parked();
+        assert!(cx.update(|cx| cx.pending_input_keystrokes().is_none()));
+        assert_eq!(take_events(cx), ["alpha"]);
+
+        // ...or is handled as normal input if it has none.
+        cx.update(|cx| cx.set_pending_input_timeout(Duration::from_millis(200)));
+        cx.simulate_keystrokes("g");
+        cx.executor().advance_clock(Duration::from_millis(200));
+        cx.run_until_parked();
+        assert_eq!(take_events(cx), ["g"]);
+
+        // Keys typed after the timeout start a new binding.
+        cx.simulate_keystrokes("g");
+        cx.executor().advance_clock(Duration::from_millis(300));
+        cx.run_until_parked();
+        cx.simulate_keystrokes("g");
+        assert_eq!(take_events(cx), ["g"]);
+        cx.simulate_keystrokes("g");
+        assert_eq!(take_events(cx), ["gamma"]);
+    }
+
+    #[gpui::test]
+    fn test_keystroke_capture(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.bind_keys([
+                KeyBinding::new("ctrl-k", ActionAlpha {}, None),
+                KeyBinding::new("ctrl-k ctrl-s", ActionBeta {}, None),
+                KeyBinding::new("escape", ActionGamma {}, None),
+            ])
+        });
+        let (host, cx) = cx.add_window_view(|cx| ChordHost {
+            focus_handle: cx.focus_handle(),
+            events: Vec::new(),
+        });
+        cx.focus_view(&host);
+        let take_events = |cx: &mut gpui::VisualTestContext| {
+            host.update(cx, |host, _| std::mem::take(&mut host.events))
+        };
+        let captured = Rc::new(RefCell::new(Vec::new()));
+        let capture = |cx: &mut gpui::VisualTestContext| {
+            let captured = captured.clone();
+            cx.update(|cx| {
+                cx.capture_keystrokes(move |keystrokes, _| {
+                    captured.borrow_mut().push(keystrokes.map(|keystrokes| {
+                        keystrokes
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                    }))
+                })
+            });
+        };
+
+        // Captured keystrokes aren't dispatched, and enter ends the capture.
+        capture(cx);
+        cx.simulate_keystrokes("ctrl-k ctrl-s alt-enter enter");
+        assert_eq!(
+            captured.take(),
+            [Some(vec![
+                "ctrl-k".to_string(),
+                "ctrl-s".to_string(),
+                "alt-enter".to_string()
+            ])]
+        );
+        assert_eq!(take_events(cx), Vec::<String>::new());
+        assert!(!cx.update(|cx| cx.is_capturing_keystrokes()));
+
+        // Escape cancels the capture, rather than being captured or dispatched.
+        capture(cx);
+        cx.simulate_keystrokes("ctrl-k escape");
+        assert_eq!(captured.take(), [None]);
+        assert_eq!(take_events(cx), Vec::<String>::new());
+        cx.simulate_keystrokes("ctrl-k ctrl-s escape");
+        assert_eq!(take_events(cx), ["beta", "gamma"]);
+
+        // The capture ends once no keystroke is typed for the pending input timeout.
+        capture(cx);
+        cx.simulate_keystrokes("g");
+        cx.executor()
+            .advance_clock(DEFAULT_PENDING_INPUT_TIMEOUT - Duration::from_millis(1));
+        cx.run_until_parked();
+        cx.simulate_keystrokes("g");
+        cx.executor().advance_clock(DEFAULT_PENDING_INPUT_TIMEOUT);
+        cx.run_until_parked();
+        assert_eq!(
+            captured.take(),
+            [Some(vec!["g".to_string(), "g".to_string()])]
+        );
+        assert_eq!(take_events(cx), Vec::<String>::new());
+
+        // Starting a new capture cancels the current one.
+        capture(cx);
+        cx.simulate_keystrokes("x");
+        capture(cx);
+        cx.simulate_keystrokes("y enter");
+        assert_eq!(captured.take(), [None, Some(vec!["y".to_string()])]);
+    }
 }
@@ -1,5 +1,5 @@
 use crate::SharedString;
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use smallvec::SmallVec;
 use std::fmt;
 
@@ -207,11 +207,20 @@ impl KeyBindingContextPredicate {
     ///
     /// This syntax supports `!=`, `||` and `&&` as logical operators.
     /// You can also preface an operation or check with a `!` to negate it.
+    ///
+    /// Invalid predicates fail with a [`ContextPredicateParseError`] giving the position
+    /// of the problem in `source`.
     pub fn parse(source: &str) -> Result<Self> {
-        let source = skip_whitespace(source);
-        let (predicate, rest) = Self::parse_expr(source, 0)?;
+        let position = |error: SyntaxError| ContextPredicateParseError {
+            position: source.len() - error.remaining,
+            message: error.message,
+        };
+        let (predicate, rest) = Self::parse_expr(skip_whitespace(source), 0).map_err(position)?;
         if let Some(next) = rest.chars().next() {
-            Err(anyhow!("unexpected character {next:?}"))
+            Err(position(SyntaxError::new(
+                rest,
+                format!("unexpected character {next:?}"),
+            )))?
         } else {
             Ok(predicate)
         }
@@ -241,11 +250,11 @@ impl KeyBindingContextPredicate {
         }
     }
 
-    fn parse_expr(mut source: &str, min_precedence: u32) -> anyhow::Result<(Self, &str)> {
+    fn parse_expr(mut source: &str, min_precedence: u32) -> Result<(Self, &str), SyntaxError> {
         type Op = fn(
             KeyBindingContextPredicate,
             KeyBindingContextPredicate,
-        ) -> Result<KeyBindingContextPredicate>;
+        ) -> Option<KeyBindingContextPredicate>;
 
         let (mut predicate, rest) = Self::parse_primary(source)?;
         source = rest;
@@ -259,9 +268,15 @@ impl KeyBindingContextPredicate {
                 ("!=", PRECEDENCE_EQ, Self::new_neq as Op),
             ] {
                 if source.starts_with(operator) && precedence >= min_precedence {
+                    let operator_source = source;
                     source = skip_whitespace(&source[operator.len()..]);
                     let (right, rest) = Self::parse_expr(source, precedence + 1)?;
-                    predicate = constructor(predicate, right)?;
+                    predicate = constructor(predicate, right).ok_or_else(|| {
+                        SyntaxError::new(
+                            operator_source,
+                            format!("operands of `{operator}` must be identifiers"),
+                        )
+                    })?;
                     source = rest;
                     continue 'parse;
                 }
@@ -272,11 +287,11 @@ impl KeyBindingContextPredicate {
         Ok((predicate, source))
     }
 
-    fn parse_primary(mut source: &str) -> anyhow::Result<(Self, &str)> {
+    fn parse_primary(mut source: &str) -> Result<(Self, &str), SyntaxError> {
         let next = source
             .chars()
             .next()
-            .ok_or_else(|| anyhow!("unexpected eof"))?;
+            .ok_or_else(|| SyntaxError::new(source, "unexpected end of input"))?;
         match next {
             '(' => {
                 source = skip_whitespace(&source[1..]);
@@ -285,7 +300,7 @@ impl KeyBindingContextPredicate {
                     source = skip_whitespace(stripped);
                     Ok((predicate, source))
                 } else {
-                    Err(anyhow!("expected a ')'"))
+                    Err(SyntaxError::new(rest, "expected a ')'"))
                 }
             }
             '!' => {
@@ -312,35 +327,72 @@ impl KeyBindingContextPredicate {
                     source,
                 ))
             }
-            _ => Err(anyhow!("unexpected character {next:?}")),
+            _ => Err(SyntaxError::new(
+                source,
+                format!("unexpected character {next:?}"),
+            )),
         }
     }
 
-    fn new_or(self, other: Self) -> Result<Self> {
-        Ok(Self::Or(Box::new(self), Box::new(other)))
+    fn new_or(self, other: Self) -> Option<Self> {
+        Some(Self::Or(Box::new(self), Box::new(other)))
     }
 
-    fn new_and(self, other: Self) -> Result<Self> {
-        Ok(Self::And(Box::new(self), Box::new(other)))
+    fn new_and(self, other: Self) -> Option<Self> {
+        Some(Self::And(Box::new(self), Box::new(other)))
     }
 
-    fn new_child(self, other: Self) -> Result<Self> {
-        Ok(Self::Child(Box::new(self), Box::new(other)))
+    fn new_child(self, other: Self) -> Option<Self> {
+        Some(Self::Child(Box::new(self), Box::new(other)))
     }
 
-    fn new_eq(self, other: Self) -> Result<Self> {
+    fn new_eq(self, other: Self) -> Option<Self> {
         if let (Self::Identifier(left), Self::Identifier(right)) = (self, other) {
-            Ok(Self::Equal(left, right))
+            Some(Self::Equal(left, right))
         } else {
-            Err(anyhow!("operands must be identifiers"))
+            None
         }
     }
 
-    fn new_neq(self, other: Self) -> Result<Self> {
+    fn new_neq(self, other: Self) -> Option<Self> {
         if let (Self::Identifier(left), Self::Identifier(right)) = (self, other) {
-            Ok(Self::NotEqual(left, right))
+            Some(Self::NotEqual(left, right))
         } else {
-            Err(anyhow!("operands must be identifiers"))
+            None
+        }
+    }
+}
+
+/// An error in the syntax of a [`KeyBindingContextPredicate`], returned by
+/// [`KeyBindingContextPredicate::parse`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContextPredicateParseError {
+    /// The byte offset in the source at which the error was found.
+    pub position: usize,
+    /// What is wrong with the source at that position.
+    pub message: String,
+}
+
+impl fmt::Display for ContextPredicateParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ContextPredicateParseError {}
+
+/// A syntax error found while parsing, located by the length of the source left to parse
+/// until [`KeyBindingContextPredicate::parse`] turns that into a position.
+struct SyntaxError {
+    remaining: usize,
+    message: String,
+}
+
+impl SyntaxError {
+    fn new(rest: &str, message: impl Into<String>) -> Self {
+        Self {
+            remaining: rest.len(),
+            message: message.into(),
         }
     }
 }
@@ -447,7 +499,7 @@ mod tests {
             KeyBindingContextPredicate::parse("c == !d")
                 .unwrap_err()
                 .to_string(),
-            "operands must be identifiers"
+            "operands of `==` must be identifiers at position 2"
         );
     }
 
@@ -531,4 +583,50 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_parse_precedence() {
+        for (source, equivalent) in [
+            ("a || b && c", "a || (b && c)"),
+            ("a && b || c", "(a && b) || c"),
+            ("!a && b", "(!a) && b"),
+            ("!a || b", "(!a) || b"),
+            ("a == b && c != d", "(a == b) && (c != d)"),
+            ("a > b && c", "a > (b && c)"),
+            ("a > b || c", "a > (b || c)"),
+            ("a > b > c", "(a > b) > c"),
+            ("a && b && c", "(a && b) && c"),
+        ] {
+            assert_eq!(
+                KeyBindingContextPredicate::parse(source).unwrap(),
+                KeyBindingContextPredicate::parse(equivalent).unwrap(),
+                "{source:?} should parse as {equivalent:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (source, position, message) in [
+            ("", 0, "unexpected end of input"),
+            ("a &&", 4, "unexpected end of input"),
+            ("a && #b", 5, "unexpected character '#'"),
+            ("a b", 2, "unexpected character 'b'"),
+            ("(a || b", 7, "expected a ')'"),
+            ("a || (b && c))", 13, "unexpected character ')'"),
+            ("a == (b || c)", 2, "operands of `==` must be identifiers"),
+            ("!a == b", 3, "operands of `==` must be identifiers"),
+            ("a && b != !c", 7, "operands of `!=` must be identifiers"),
+        ] {
+            let error = KeyBindingContextPredicate::parse(source)
+                .unwrap_err()
+                .downcast::<ContextPredicateParseError>()
+                .unwrap();
+            assert_eq!(
+                (error.position, error.message.as_str()),
+                (position, message),
+                "parsing {source:?}"
+            );
+        }
+    }
 }
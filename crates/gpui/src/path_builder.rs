@@ -0,0 +1,939 @@
+use crate::{point, px, Path, PathFillRule, Pixels, Point};
+use std::{
+    f32::consts::PI,
+    hash::{Hash, Hasher},
+};
+
+/// The largest distance, in pixels, between a curve and the lines or simpler curves that
+/// approximate it.
+const TOLERANCE: f32 = 0.1;
+/// The most pieces that a single curve or arc is split into.
+const MAX_CURVE_SEGMENTS: usize = 64;
+/// Miter joins that stick out further than this many half stroke widths are beveled instead.
+const MITER_LIMIT: f32 = 4.;
+/// How far past the edges of a stroke, in pixels, it fades out to smooth them.
+const FRINGE_WIDTH: f32 = 1.;
+/// Points closer than this many pixels apart are merged when stroking.
+const EPSILON: f32 = 1e-3;
+
+/// How the ends of an open stroke are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineCap {
+    /// The stroke ends right at the end of the path.
+    #[default]
+    Butt,
+    /// The stroke ends with a half circle around the end of the path.
+    Round,
+    /// The stroke ends with a half square past the end of the path.
+    Square,
+}
+
+/// How a stroke turns the corners between the lines and curves of a path.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+    /// The outer edges of the stroke are extended until they meet, unless they'd meet too far
+    /// from the corner, in which case the corner is beveled.
+    #[default]
+    Miter,
+    /// The corner is rounded off with a circle around it.
+    Round,
+    /// The corner is cut off with a straight line.
+    Bevel,
+}
+
+/// The options of a stroked path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrokeOptions {
+    /// The width of the stroke.
+    pub width: Pixels,
+    /// How the ends of open subpaths are drawn.
+    pub cap: LineCap,
+    /// How corners are drawn.
+    pub join: LineJoin,
+}
+
+impl StrokeOptions {
+    /// Creates the options of a stroke with the given width, butt caps and miter joins.
+    pub fn new(width: Pixels) -> Self {
+        Self {
+            width,
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+        }
+    }
+
+    /// Sets how the ends of open subpaths are drawn.
+    pub fn cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Sets how corners are drawn.
+    pub fn join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+}
+
+/// Whether a path is filled or stroked.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PathStyle {
+    /// The inside of the path is filled. Where subpaths overlap or a path crosses itself, the
+    /// regions enclosed an odd number of times are inside.
+    #[default]
+    Fill,
+    /// The path is drawn as a line.
+    Stroke(StrokeOptions),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PathCommand {
+    MoveTo(Point<Pixels>),
+    LineTo(Point<Pixels>),
+    QuadraticTo {
+        ctrl: Point<Pixels>,
+        to: Point<Pixels>,
+    },
+    CubicTo {
+        ctrl_a: Point<Pixels>,
+        ctrl_b: Point<Pixels>,
+        to: Point<Pixels>,
+    },
+    Close,
+}
+
+/// Builds a vector path out of lines and curves, which is tessellated into a [`Path`] to be
+/// filled or stroked. Paint it with
+/// [`WindowContext::paint_path_builder`](crate::WindowContext::paint_path_builder), from the
+/// paint callback of a [`canvas`](crate::canvas) for instance.
+///
+/// ```
+/// # use zed::{point, px, LineCap, PathBuilder, StrokeOptions};
+/// let mut builder = PathBuilder::stroke(StrokeOptions::new(px(2.)).cap(LineCap::Round));
+/// builder.move_to(point(px(0.), px(10.)));
+/// builder.cubic_to(
+///     point(px(5.), px(0.)),
+///     point(px(15.), px(20.)),
+///     point(px(20.), px(10.)),
+/// );
+/// let path = builder.build();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathBuilder {
+    commands: Vec<PathCommand>,
+    style: PathStyle,
+}
+
+impl PathBuilder {
+    /// Creates a builder for a path whose inside is filled.
+    pub fn fill() -> Self {
+        Self {
+            commands: Vec::new(),
+            style: PathStyle::Fill,
+        }
+    }
+
+    /// Creates a builder for a path that is drawn as a line with the given options.
+    pub fn stroke(options: StrokeOptions) -> Self {
+        Self {
+            commands: Vec::new(),
+            style: PathStyle::Stroke(options),
+        }
+    }
+
+    /// Starts a new subpath at the given point.
+    pub fn move_to(&mut self, to: Point<Pixels>) {
+        self.commands.push(PathCommand::MoveTo(to));
+    }
+
+    /// Draws a straight line to the given point. Without a current point, this starts a new
+    /// subpath at the given point instead.
+    pub fn line_to(&mut self, to: Point<Pixels>) {
+        self.commands.push(PathCommand::LineTo(to));
+    }
+
+    /// Draws a quadratic Bézier curve to the given point, bending towards the control point.
+    pub fn quadratic_to(&mut self, ctrl: Point<Pixels>, to: Point<Pixels>) {
+        self.commands.push(PathCommand::QuadraticTo { ctrl, to });
+    }
+
+    /// Draws a cubic Bézier curve to the given point, leaving the current point towards the
+    /// first control point and arriving from the second one.
+    pub fn cubic_to(&mut self, ctrl_a: Point<Pixels>, ctrl_b: Point<Pixels>, to: Point<Pixels>) {
+        self.commands
+            .push(PathCommand::CubicTo { ctrl_a, ctrl_b, to });
+    }
+
+    /// Closes the current subpath with a straight line back to its start. Drawing on starts a
+    /// new subpath from there.
+    pub fn close(&mut self) {
+        self.commands.push(PathCommand::Close);
+    }
+
+    /// Tessellates the path into triangles.
+    pub fn build(&self) -> Path<Pixels> {
+        let subpaths = self.subpaths();
+        match self.style {
+            PathStyle::Fill => fill(&subpaths),
+            PathStyle::Stroke(options) => {
+                let mut stroker = Stroker::new(options);
+                for subpath in &subpaths {
+                    stroker.stroke(&subpath.flatten(), subpath.closed);
+                }
+                stroker.finish()
+            }
+        }
+    }
+
+    /// A hash of everything the tessellated path depends on.
+    pub(crate) fn content_hash(&self) -> u64 {
+        fn hash_point(point: Point<Pixels>, hasher: &mut impl Hasher) {
+            point.x.0.to_bits().hash(hasher);
+            point.y.0.to_bits().hash(hasher);
+        }
+
+        let mut hasher = collections::FxHasher::default();
+        match self.style {
+            PathStyle::Fill => 0u8.hash(&mut hasher),
+            PathStyle::Stroke(options) => {
+                1u8.hash(&mut hasher);
+                options.width.0.to_bits().hash(&mut hasher);
+                options.cap.hash(&mut hasher);
+                options.join.hash(&mut hasher);
+            }
+        }
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(to) => {
+                    0u8.hash(&mut hasher);
+                    hash_point(to, &mut hasher);
+                }
+                PathCommand::LineTo(to) => {
+                    1u8.hash(&mut hasher);
+                    hash_point(to, &mut hasher);
+                }
+                PathCommand::QuadraticTo { ctrl, to } => {
+                    2u8.hash(&mut hasher);
+                    hash_point(ctrl, &mut hasher);
+                    hash_point(to, &mut hasher);
+                }
+                PathCommand::CubicTo { ctrl_a, ctrl_b, to } => {
+                    3u8.hash(&mut hasher);
+                    hash_point(ctrl_a, &mut hasher);
+                    hash_point(ctrl_b, &mut hasher);
+                    hash_point(to, &mut hasher);
+                }
+                PathCommand::Close => 4u8.hash(&mut hasher),
+            }
+        }
+        hasher.finish()
+    }
+
+    fn subpaths(&self) -> Vec<Subpath> {
+        let mut subpaths = Vec::new();
+        let mut current: Option<Subpath> = None;
+        for command in &self.commands {
+            let (first_point, segment) = match *command {
+                PathCommand::MoveTo(to) => {
+                    subpaths.extend(current.replace(Subpath::new(to_f32(to))));
+                    continue;
+                }
+                PathCommand::Close => {
+                    if let Some(mut subpath) = current.take() {
+                        subpath.closed = true;
+                        current = Some(Subpath::new(subpath.start));
+                        subpaths.push(subpath);
+                    }
+                    continue;
+                }
+                PathCommand::LineTo(to) => (to, Segment::Line(to_f32(to))),
+                PathCommand::QuadraticTo { ctrl, to } => {
+                    (ctrl, Segment::Quadratic(to_f32(ctrl), to_f32(to)))
+                }
+                PathCommand::CubicTo { ctrl_a, ctrl_b, to } => (
+                    ctrl_a,
+                    Segment::Cubic(to_f32(ctrl_a), to_f32(ctrl_b), to_f32(to)),
+                ),
+            };
+            current
+                .get_or_insert_with(|| Subpath::new(to_f32(first_point)))
+                .segments
+                .push(segment);
+        }
+        subpaths.extend(current);
+        // Subpaths that were only moved to draw nothing.
+        subpaths.retain(|subpath| subpath.closed || !subpath.segments.is_empty());
+        subpaths
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Segment {
+    Line(Point<f32>),
+    Quadratic(Point<f32>, Point<f32>),
+    Cubic(Point<f32>, Point<f32>, Point<f32>),
+}
+
+impl Segment {
+    fn end(&self) -> Point<f32> {
+        match *self {
+            Segment::Line(to) | Segment::Quadratic(_, to) | Segment::Cubic(_, _, to) => to,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Subpath {
+    start: Point<f32>,
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+impl Subpath {
+    fn new(start: Point<f32>) -> Self {
+        Self {
+            start,
+            segments: Vec::new(),
+            closed: false,
+        }
+    }
+
+    /// Approximates the subpath with straight lines through the returned points.
+    fn flatten(&self) -> Vec<Point<f32>> {
+        let mut points = vec![self.start];
+        let mut current = self.start;
+        for segment in &self.segments {
+            match *segment {
+                Segment::Line(to) => points.push(to),
+                Segment::Quadratic(ctrl, to) => {
+                    // Each line strays from the curve by at most a quarter of the curve's second
+                    // derivative times the square of the line's share of the curve.
+                    let second_difference = length(current - ctrl * 2. + to);
+                    let count = segment_count((second_difference / (4. * TOLERANCE)).sqrt());
+                    points
+                        .extend((1..=count).map(|ix| {
+                            quadratic_point(current, ctrl, to, ix as f32 / count as f32)
+                        }));
+                }
+                Segment::Cubic(ctrl_a, ctrl_b, to) => {
+                    let second_difference = length(current - ctrl_a * 2. + ctrl_b)
+                        .max(length(ctrl_a - ctrl_b * 2. + to));
+                    let count = segment_count((3. * second_difference / (4. * TOLERANCE)).sqrt());
+                    points.extend((1..=count).map(|ix| {
+                        cubic_point(current, ctrl_a, ctrl_b, to, ix as f32 / count as f32)
+                    }));
+                }
+            }
+            current = segment.end();
+        }
+
+        points.dedup_by(|point, previous| length(*point - *previous) < EPSILON);
+        if self.closed && points.len() > 1 && length(points[points.len() - 1] - points[0]) < EPSILON
+        {
+            points.pop();
+        }
+        points
+    }
+}
+
+/// Fills subpaths with a fan of triangles from the start of each one, which cover the points
+/// inside an odd number of times, and with a curved triangle under each curve. Curved edges are
+/// anti-aliased by their triangles, and straight edges by fringes on both of their sides.
+fn fill(subpaths: &[Subpath]) -> Path<Pixels> {
+    let start = subpaths
+        .first()
+        .map_or_else(Point::default, |subpath| to_pixels(subpath.start));
+    let mut path = Path::new(start);
+    for (ix, subpath) in subpaths.iter().enumerate() {
+        if ix > 0 {
+            path.move_to(to_pixels(subpath.start));
+        }
+        let mut edges = Vec::new();
+        let mut current = subpath.start;
+        for segment in &subpath.segments {
+            match *segment {
+                Segment::Line(to) => {
+                    path.line_to(to_pixels(to));
+                    edges.push((current, to));
+                }
+                Segment::Quadratic(ctrl, to) => path.curve_to(to_pixels(to), to_pixels(ctrl)),
+                Segment::Cubic(ctrl_a, ctrl_b, to) => {
+                    for (ctrl, to) in cubic_to_quadratics(current, ctrl_a, ctrl_b, to) {
+                        path.curve_to(to_pixels(to), to_pixels(ctrl));
+                    }
+                }
+            }
+            current = segment.end();
+        }
+        // Fills close their subpaths with a straight edge.
+        edges.push((current, subpath.start));
+
+        // The even-odd rule turns the number of triangles covering a point into coverage that
+        // rises and falls linearly between whole numbers, so fading out on both sides of an edge
+        // smooths the step at it, whichever side is inside. Subpaths whose fans cover nothing
+        // have no edges to smooth, and their fringes would draw a line.
+        let points = subpath.flatten();
+        let covers_area = points
+            .windows(2)
+            .any(|pair| cross(pair[0] - points[0], pair[1] - points[0]).abs() >= EPSILON);
+        if !covers_area {
+            continue;
+        }
+        for (a, b) in edges {
+            if length(b - a) < EPSILON {
+                continue;
+            }
+            let normal = perpendicular(normalize(b - a));
+            push_fringe(&mut path, a, b, normal);
+            push_fringe(&mut path, a, b, normal * -1.);
+        }
+    }
+    path
+}
+
+/// Strokes flattened subpaths with convex polygons, which are allowed to overlap.
+struct Stroker {
+    path: Option<Path<Pixels>>,
+    half_width: f32,
+    cap: LineCap,
+    join: LineJoin,
+}
+
+impl Stroker {
+    fn new(options: StrokeOptions) -> Self {
+        Self {
+            path: None,
+            half_width: options.width.0.max(0.) / 2.,
+            cap: options.cap,
+            join: options.join,
+        }
+    }
+
+    fn stroke(&mut self, points: &[Point<f32>], closed: bool) {
+        let radius = self.half_width;
+        if radius <= 0. {
+            return;
+        }
+
+        let count = points.len();
+        match points {
+            [] => return,
+            // A subpath without length is drawn as a dot, if its caps stick out.
+            [center] => {
+                match self.cap {
+                    LineCap::Butt => {}
+                    LineCap::Round => {
+                        let steps = arc_segment_count(radius, 2. * PI).max(3);
+                        let circle = (0..steps)
+                            .map(|ix| *center + polar(radius, 2. * PI * ix as f32 / steps as f32))
+                            .collect::<Vec<_>>();
+                        self.polygon(&circle);
+                    }
+                    LineCap::Square => self.polygon(&[
+                        *center + point(-radius, -radius),
+                        *center + point(radius, -radius),
+                        *center + point(radius, radius),
+                        *center + point(-radius, radius),
+                    ]),
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let segment_count = if closed { count } else { count - 1 };
+        for ix in 0..segment_count {
+            let from = points[ix];
+            let to = points[(ix + 1) % count];
+            let normal = perpendicular(normalize(to - from)) * radius;
+            self.polygon(&[from + normal, to + normal, to - normal, from - normal]);
+        }
+
+        let corners = if closed { 0..count } else { 1..count - 1 };
+        for ix in corners {
+            let previous = points[(ix + count - 1) % count];
+            let next = points[(ix + 1) % count];
+            self.join(previous, points[ix], next);
+        }
+
+        if !closed {
+            self.cap(points[0], points[1]);
+            self.cap(points[count - 1], points[count - 2]);
+        }
+    }
+
+    /// Fills the gap that the outlines of two lines leave on the outside of the corner between
+    /// them.
+    fn join(&mut self, previous: Point<f32>, corner: Point<f32>, next: Point<f32>) {
+        let radius = self.half_width;
+        let incoming = normalize(corner - previous);
+        let outgoing = normalize(next - corner);
+        let turn = cross(incoming, outgoing);
+        if turn.abs() < EPSILON && dot(incoming, outgoing) > 0. {
+            return;
+        }
+
+        // The outer side of the corner is the one the path turns away from.
+        let side = if turn > 0. { -radius } else { radius };
+        let incoming_normal = perpendicular(incoming) * side;
+        let outgoing_normal = perpendicular(outgoing) * side;
+        let bevel = [corner, corner + incoming_normal, corner + outgoing_normal];
+
+        match self.join {
+            LineJoin::Bevel => self.polygon(&bevel),
+            LineJoin::Miter => {
+                let direction = normalize(incoming_normal + outgoing_normal);
+                let cos_half_angle = dot(direction, incoming_normal) / radius;
+                if cos_half_angle * MITER_LIMIT <= 1. {
+                    self.polygon(&bevel);
+                } else {
+                    let tip = corner + direction * (radius / cos_half_angle);
+                    self.polygon(&[
+                        corner,
+                        corner + incoming_normal,
+                        tip,
+                        corner + outgoing_normal,
+                    ]);
+                }
+            }
+            LineJoin::Round => {
+                let start_angle = incoming_normal.y.atan2(incoming_normal.x);
+                let mut sweep = outgoing_normal.y.atan2(outgoing_normal.x) - start_angle;
+                if sweep > PI {
+                    sweep -= 2. * PI;
+                } else if sweep < -PI {
+                    sweep += 2. * PI;
+                }
+                self.arc(corner, start_angle, sweep);
+            }
+        }
+    }
+
+    /// Draws the cap at the end of an open subpath, given the point before it.
+    fn cap(&mut self, end: Point<f32>, previous: Point<f32>) {
+        let radius = self.half_width;
+        let direction = normalize(end - previous);
+        let normal = perpendicular(direction) * radius;
+        match self.cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                let offset = direction * radius;
+                self.polygon(&[
+                    end + normal,
+                    end + normal + offset,
+                    end - normal + offset,
+                    end - normal,
+                ]);
+            }
+            // Half a turn from one side of the stroke to the other, through its end.
+            LineCap::Round => self.arc(end, normal.y.atan2(normal.x), -PI),
+        }
+    }
+
+    /// Draws the slice of a circle around the given center with the stroke's radius.
+    fn arc(&mut self, center: Point<f32>, start_angle: f32, sweep: f32) {
+        let radius = self.half_width;
+        let steps = arc_segment_count(radius, sweep.abs());
+        let mut points = Vec::with_capacity(steps + 2);
+        points.push(center);
+        points.extend(
+            (0..=steps)
+                .map(|ix| center + polar(radius, start_angle + sweep * ix as f32 / steps as f32)),
+        );
+        self.polygon(&points);
+    }
+
+    /// Fills a convex polygon, and fades out past its edges so that they're anti-aliased.
+    fn polygon(&mut self, points: &[Point<f32>]) {
+        if area(points).abs() < EPSILON {
+            return;
+        }
+
+        let path = self
+            .path
+            .get_or_insert_with(|| Path::new(to_pixels(points[0])));
+        let inside = (point(0., 1.), point(0., 1.), point(0., 1.));
+        for ix in 1..points.len() - 1 {
+            path.push_triangle(
+                (
+                    to_pixels(points[0]),
+                    to_pixels(points[ix]),
+                    to_pixels(points[ix + 1]),
+                ),
+                inside,
+            );
+        }
+
+        let center = points
+            .iter()
+            .fold(Point::default(), |sum: Point<f32>, point| sum + *point)
+            / points.len() as f32;
+        for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+            let (a, b) = (*a, *b);
+            if length(b - a) < EPSILON {
+                continue;
+            }
+            let mut outward = perpendicular(normalize(b - a));
+            if dot(outward, (a + b) / 2. - center) < 0. {
+                outward = outward * -1.;
+            }
+            push_fringe(path, a, b, outward);
+        }
+    }
+
+    fn finish(self) -> Path<Pixels> {
+        let mut path = self.path.unwrap_or_else(|| Path::new(Point::default()));
+        path.fill_rule = PathFillRule::Union;
+        path
+    }
+}
+
+/// Pushes the triangles of a fringe past the edge from `a` to `b`, on the side that `outward`
+/// points to.
+fn push_fringe(path: &mut Path<Pixels>, a: Point<f32>, b: Point<f32>, outward: Point<f32>) {
+    // The rasterizer fades the fringe out with the distance from the edge, as the curve s² = t
+    // would be.
+    let fringe = outward * FRINGE_WIDTH;
+    let (edge, faded) = (point(0., 0.), point(0., -1.));
+    path.push_triangle(
+        (to_pixels(a), to_pixels(b), to_pixels(b + fringe)),
+        (edge, edge, faded),
+    );
+    path.push_triangle(
+        (to_pixels(a), to_pixels(b + fringe), to_pixels(a + fringe)),
+        (edge, faded, faded),
+    );
+}
+
+/// The signed area of a polygon.
+fn area(points: &[Point<f32>]) -> f32 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| cross(*a, *b))
+        .sum::<f32>()
+        / 2.
+}
+
+/// Splits a cubic Bézier curve into quadratic ones that stray from it by at most the tolerance,
+/// returning the control point and end of each.
+fn cubic_to_quadratics(
+    from: Point<f32>,
+    ctrl_a: Point<f32>,
+    ctrl_b: Point<f32>,
+    to: Point<f32>,
+) -> impl Iterator<Item = (Point<f32>, Point<f32>)> {
+    // The error of the approximation shrinks with the cube of the number of pieces.
+    let error = length(to - ctrl_b * 3. + ctrl_a * 3. - from) * 3f32.sqrt() / 36.;
+    let count = segment_count((error / TOLERANCE).cbrt());
+    let step = 1. / count as f32;
+    (0..count).map(move |ix| {
+        let (start_t, end_t) = (ix as f32 * step, (ix + 1) as f32 * step);
+        let start = cubic_point(from, ctrl_a, ctrl_b, to, start_t);
+        let end = cubic_point(from, ctrl_a, ctrl_b, to, end_t);
+        let piece_ctrl_a =
+            start + cubic_derivative(from, ctrl_a, ctrl_b, to, start_t) * (step / 3.);
+        let piece_ctrl_b = end - cubic_derivative(from, ctrl_a, ctrl_b, to, end_t) * (step / 3.);
+        let ctrl = (piece_ctrl_a + piece_ctrl_b) * 0.75 - (start + end) * 0.25;
+        (ctrl, end)
+    })
+}
+
+fn quadratic_point(from: Point<f32>, ctrl: Point<f32>, to: Point<f32>, t: f32) -> Point<f32> {
+    let mt = 1. - t;
+    from * (mt * mt) + ctrl * (2. * mt * t) + to * (t * t)
+}
+
+fn cubic_point(
+    from: Point<f32>,
+    ctrl_a: Point<f32>,
+    ctrl_b: Point<f32>,
+    to: Point<f32>,
+    t: f32,
+) -> Point<f32> {
+    let mt = 1. - t;
+    from * (mt * mt * mt)
+        + ctrl_a * (3. * mt * mt * t)
+        + ctrl_b * (3. * mt * t * t)
+        + to * (t * t * t)
+}
+
+fn cubic_derivative(
+    from: Point<f32>,
+    ctrl_a: Point<f32>,
+    ctrl_b: Point<f32>,
+    to: Point<f32>,
+    t: f32,
+) -> Point<f32> {
+    let mt = 1. - t;
+    ((ctrl_a - from) * (mt * mt) + (ctrl_b - ctrl_a) * (2. * mt * t) + (to - ctrl_b) * (t * t)) * 3.
+}
+
+fn segment_count(count: f32) -> usize {
+    if count.is_finite() {
+        (count.ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS)
+    } else {
+        1
+    }
+}
+
+/// The number of lines that approximate an arc of the given angle within the tolerance.
+fn arc_segment_count(radius: f32, angle: f32) -> usize {
+    let step = 2. * (1. - TOLERANCE / radius).clamp(-1., 1.).acos();
+    segment_count(angle / step)
+}
+
+fn polar(radius: f32, angle: f32) -> Point<f32> {
+    point(angle.cos(), angle.sin()) * radius
+}
+
+fn to_f32(point: Point<Pixels>) -> Point<f32> {
+    point.map(|coordinate| coordinate.0)
+}
+
+fn to_pixels(point: Point<f32>) -> Point<Pixels> {
+    point.map(px)
+}
+
+fn length(vector: Point<f32>) -> f32 {
+    vector.x.hypot(vector.y)
+}
+
+fn normalize(vector: Point<f32>) -> Point<f32> {
+    let length = length(vector);
+    if length > 0. {
+        vector / length
+    } else {
+        Point::default()
+    }
+}
+
+fn perpendicular(vector: Point<f32>) -> Point<f32> {
+    point(-vector.y, vector.x)
+}
+
+fn dot(a: Point<f32>, b: Point<f32>) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+fn cross(a: Point<f32>, b: Point<f32>) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pt(x: f32, y: f32) -> Point<Pixels> {
+        point(px(x), px(y))
+    }
+
+    fn triangle_count(path: &Path<Pixels>) -> usize {
+        assert_eq!(path.vertices.len() % 3, 0);
+        path.vertices.len() / 3
+    }
+
+    fn fringe_triangle_count(path: &Path<Pixels>) -> usize {
+        path.vertices
+            .chunks(3)
+            .filter(|triangle| {
+                triangle
+                    .iter()
+                    .any(|vertex| vertex.st_position == point(0., -1.))
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_fill_self_intersecting_path() {
+        // A bow tie, whose lines cross in the middle.
+        let mut builder = PathBuilder::fill();
+        builder.move_to(pt(0., 0.));
+        builder.line_to(pt(10., 10.));
+        builder.line_to(pt(10., 0.));
+        builder.line_to(pt(0., 10.));
+        builder.close();
+        let path = builder.build();
+
+        assert_eq!(path.fill_rule, PathFillRule::EvenOdd);
+        // A fan of two triangles from the start, which cover each half of the bow tie once and
+        // the gap above its middle twice, and a fringe of two triangles on each side of its four
+        // edges.
+        assert_eq!(fringe_triangle_count(&path), 4 * 2 * 2);
+        assert_eq!(triangle_count(&path), 2 + 4 * 2 * 2);
+        // The fringes stick out past the vertical edges by their width.
+        assert_eq!(path.bounds.origin.x, px(-FRINGE_WIDTH));
+        assert_eq!(path.bounds.lower_right().x, px(10. + FRINGE_WIDTH));
+    }
+
+    #[test]
+    fn test_fill_subpaths_and_curves() {
+        let mut builder = PathBuilder::fill();
+        builder.move_to(pt(0., 0.));
+        builder.line_to(pt(20., 0.));
+        builder.line_to(pt(20., 20.));
+        builder.line_to(pt(0., 20.));
+        builder.close();
+        // A hole, which starts its own fan.
+        builder.move_to(pt(5., 5.));
+        builder.line_to(pt(15., 5.));
+        builder.quadratic_to(pt(20., 10.), pt(15., 15.));
+        builder.cubic_to(pt(10., 20.), pt(5., 20.), pt(5., 15.));
+        let path = builder.build();
+
+        let curve_triangles = path
+            .vertices
+            .chunks(3)
+            .filter(|triangle| triangle[1].st_position == point(0.5, 0.))
+            .count();
+        // The four edges of the square, and the line and closing edge of the hole, are
+        // straight and have fringes. The curves are anti-aliased by their own triangles.
+        let fringe_triangles = fringe_triangle_count(&path);
+        assert_eq!(fringe_triangles, 6 * 2 * 2);
+        // Besides the two triangles of the square, each curve of the hole adds a triangle to
+        // its fan.
+        assert_eq!(
+            curve_triangles,
+            (triangle_count(&path) - fringe_triangles - 2) / 2
+        );
+        // The cubic curve is split into at least one quadratic one.
+        assert!(curve_triangles >= 2);
+        assert_eq!(path.bounds.origin, pt(-FRINGE_WIDTH, -FRINGE_WIDTH));
+        assert_eq!(
+            path.bounds.size,
+            crate::size(px(20. + 2. * FRINGE_WIDTH), px(20. + 2. * FRINGE_WIDTH))
+        );
+    }
+
+    #[test]
+    fn test_fill_without_area() {
+        // A line that doubles back encloses nothing, so it isn't drawn as a hairline either.
+        let mut builder = PathBuilder::fill();
+        builder.move_to(pt(0., 0.));
+        builder.line_to(pt(10., 0.));
+        builder.line_to(pt(0., 0.));
+        let path = builder.build();
+        assert_eq!(fringe_triangle_count(&path), 0);
+    }
+
+    #[test]
+    fn test_cubic_to_quadratics() {
+        let (from, ctrl_a, ctrl_b, to) = (
+            point(0., 0.),
+            point(0., 100.),
+            point(100., -100.),
+            point(100., 0.),
+        );
+        let quadratics = cubic_to_quadratics(from, ctrl_a, ctrl_b, to).collect::<Vec<_>>();
+        assert!(quadratics.len() > 1);
+        assert_eq!(quadratics.last().unwrap().1, to);
+
+        // The quadratic curves stay close to the cubic one.
+        let mut start = from;
+        for (ix, (ctrl, end)) in quadratics.iter().enumerate() {
+            let midpoint = quadratic_point(start, *ctrl, *end, 0.5);
+            let t = (ix as f32 + 0.5) / quadratics.len() as f32;
+            let expected = cubic_point(from, ctrl_a, ctrl_b, to, t);
+            assert!(length(midpoint - expected) < 2. * TOLERANCE);
+            start = *end;
+        }
+
+        // A straight line stays a single piece.
+        let line = cubic_to_quadratics(from, point(1., 0.), point(2., 0.), point(3., 0.));
+        assert_eq!(line.count(), 1);
+    }
+
+    #[test]
+    fn test_stroke_bounds() {
+        let mut builder = PathBuilder::stroke(StrokeOptions::new(px(4.)).cap(LineCap::Square));
+        builder.move_to(pt(10., 10.));
+        builder.line_to(pt(30., 10.));
+        let path = builder.build();
+
+        assert_eq!(path.fill_rule, PathFillRule::Union);
+        // The line and its square caps, each with fringes past their edges.
+        let fringe = FRINGE_WIDTH;
+        assert_eq!(path.bounds.origin, pt(8. - fringe, 8. - fringe));
+        assert_eq!(
+            path.bounds.size,
+            crate::size(px(24. + 2. * fringe), px(4. + 2. * fringe))
+        );
+    }
+
+    #[test]
+    fn test_stroke_self_intersecting_path() {
+        let mut builder = PathBuilder::stroke(StrokeOptions::new(px(2.)).join(LineJoin::Round));
+        builder.move_to(pt(0., 0.));
+        builder.line_to(pt(10., 10.));
+        builder.line_to(pt(10., 0.));
+        builder.line_to(pt(0., 10.));
+        builder.close();
+        let path = builder.build();
+
+        // Each line is a quad of two triangles, with two more for each of its fringes.
+        let lines = 4;
+        assert!(triangle_count(&path) > lines * 2 * 5);
+        let min = path.bounds.origin;
+        let max = path.bounds.lower_right();
+        assert!(min.x < px(0.) && min.y < px(0.));
+        assert!(max.x > px(10.) && max.y > px(10.));
+    }
+
+    #[test]
+    fn test_stroke_degenerate_paths() {
+        let stroke = |cap, build: &dyn Fn(&mut PathBuilder)| {
+            let mut builder = PathBuilder::stroke(StrokeOptions::new(px(2.)).cap(cap));
+            build(&mut builder);
+            builder.build()
+        };
+
+        // Nothing is drawn for empty paths, or paths that only move.
+        let empty = stroke(LineCap::Round, &|_| {});
+        assert!(empty.vertices.is_empty());
+        let moved = stroke(LineCap::Round, &|builder| builder.move_to(pt(5., 5.)));
+        assert!(moved.vertices.is_empty());
+
+        // Lines without length are drawn as dots, if their caps stick out.
+        let zero_length = |builder: &mut PathBuilder| {
+            builder.move_to(pt(5., 5.));
+            builder.line_to(pt(5., 5.));
+            builder.line_to(pt(5.0001, 5.));
+        };
+        assert!(stroke(LineCap::Butt, &zero_length).vertices.is_empty());
+        let dot = stroke(LineCap::Round, &zero_length);
+        assert!(!dot.vertices.is_empty());
+        assert!(dot.bounds.contains(&pt(4.5, 4.5)) && dot.bounds.contains(&pt(5.5, 5.5)));
+
+        // Lines that double back on themselves are drawn, with nothing at the turn.
+        let doubled = stroke(LineCap::Butt, &|builder| {
+            builder.move_to(pt(0., 0.));
+            builder.line_to(pt(10., 0.));
+            builder.line_to(pt(0., 0.));
+        });
+        assert_eq!(triangle_count(&doubled), 2 * (2 + 4 * 2));
+
+        // Strokes without width draw nothing.
+        let mut builder = PathBuilder::stroke(StrokeOptions::new(px(0.)));
+        builder.move_to(pt(0., 0.));
+        builder.line_to(pt(10., 10.));
+        assert!(builder.build().vertices.is_empty());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let build = |style: PathStyle, to: Point<Pixels>| {
+            let mut builder = PathBuilder {
+                commands: Vec::new(),
+                style,
+            };
+            builder.move_to(pt(0., 0.));
+            builder.line_to(to);
+            builder
+        };
+        let stroke = PathStyle::Stroke(StrokeOptions::new(px(1.)));
+        let hash = build(PathStyle::Fill, pt(1., 1.)).content_hash();
+        assert_eq!(hash, build(PathStyle::Fill, pt(1., 1.)).content_hash());
+        assert_ne!(hash, build(PathStyle::Fill, pt(1., 2.)).content_hash());
+        assert_ne!(hash, build(stroke, pt(1., 1.)).content_hash());
+    }
+}
@@ -24,9 +24,9 @@ mod windows;
 
 use crate::{
     point, Action, AnyWindowHandle, AsyncWindowContext, BackgroundExecutor, Bounds, DevicePixels,
-    DispatchEventResult, Font, FontId, FontMetrics, FontRun, ForegroundExecutor, GlyphId, Keymap,
-    LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams, RenderImageParams,
-    RenderSvgParams, Scene, SharedString, Size, Task, TaskLabel, WindowContext,
+    DispatchEventResult, Font, FontId, FontMetrics, FontRun, ForegroundExecutor, GlyphId,
+    ImageData, Keymap, LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams,
+    RenderImageParams, RenderSvgParams, Scene, SharedString, Size, Task, TaskLabel, WindowContext,
     DEFAULT_WINDOW_SIZE,
 };
 use anyhow::Result;
@@ -172,6 +172,13 @@ pub(crate) trait Platform: 'static {
     fn read_from_primary(&self) -> Option<ClipboardItem>;
     fn read_from_clipboard(&self) -> Option<ClipboardItem>;
 
+    /// Registers a callback to be invoked when the clipboard's contents change, including when
+    /// another application copies something. Returns false if the platform can't report
+    /// changes, in which case the clipboard has to be polled for them.
+    fn on_clipboard_change(&self, _callback: Box<dyn FnMut()>) -> bool {
+        false
+    }
+
     fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>>;
     fn read_credentials(&self, url: &str) -> Task<Result<Option<(String, Vec<u8>)>>>;
     fn delete_credentials(&self, url: &str) -> Task<Result<()>>;
@@ -455,6 +462,9 @@ pub(crate) trait PlatformAtlas: Send + Sync {
         key: &AtlasKey,
         build: &mut dyn FnMut() -> Result<Option<(Size<DevicePixels>, Cow<'a, [u8]>)>>,
     ) -> Result<Option<AtlasTile>>;
+
+    /// Frees the tile stored for the given key, if any. The tile must not be drawn afterwards.
+    fn remove(&self, key: &AtlasKey);
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -956,11 +966,15 @@ impl Default for CursorStyle {
     }
 }
 
-/// A clipboard item that should be copied to the clipboard
+/// A clipboard item that should be copied to the clipboard. It can hold several representations
+/// of the same content at once, such as plain text along with the HTML it was rendered from, and
+/// readers pick the richest one they support.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ClipboardItem {
     pub(crate) text: String,
     pub(crate) metadata: Option<String>,
+    pub(crate) html: Option<String>,
+    pub(crate) image: Option<Arc<ImageData>>,
 }
 
 impl ClipboardItem {
@@ -969,9 +983,28 @@ impl ClipboardItem {
         Self {
             text,
             metadata: None,
+            html: None,
+            image: None,
         }
     }
 
+    /// Create a new clipboard item with the given image and no text
+    pub fn new_image(image: Arc<ImageData>) -> Self {
+        Self::new(String::new()).with_image(image)
+    }
+
+    /// Add an HTML representation of the clipboard item's text, for pasting with formatting
+    pub fn with_html(mut self, html: String) -> Self {
+        self.html = Some(html);
+        self
+    }
+
+    /// Add an image representation of the clipboard item, written to the clipboard as a PNG
+    pub fn with_image(mut self, image: Arc<ImageData>) -> Self {
+        self.image = Some(image);
+        self
+    }
+
     /// Create a new clipboard item with the given text and metadata
     pub fn with_metadata<T: Serialize>(mut self, metadata: T) -> Self {
         self.metadata = Some(serde_json::to_string(&metadata).unwrap());
@@ -983,6 +1016,16 @@ impl ClipboardItem {
         &self.text
     }
 
+    /// Get the HTML representation of the clipboard item, if it has one
+    pub fn html(&self) -> Option<&str> {
+        self.html.as_deref()
+    }
+
+    /// Get the image representation of the clipboard item, if it has one
+    pub fn image(&self) -> Option<&Arc<ImageData>> {
+        self.image.as_ref()
+    }
+
     /// Get the metadata of the clipboard item
     pub fn metadata<T>(&self) -> Option<T>
     where
@@ -130,6 +130,15 @@ impl PlatformAtlas for BladeAtlas {
             Ok(Some(tile))
         }
     }
+
+    fn remove(&self, key: &AtlasKey) {
+        let mut lock = self.0.lock();
+        if let Some(tile) = lock.tiles_by_key.remove(key) {
+            lock.storage[tile.texture_id]
+                .allocator
+                .deallocate(tile.tile_id.into());
+        }
+    }
 }
 
 impl BladeAtlasState {
@@ -294,6 +303,17 @@ impl ops::Index<AtlasTextureId> for BladeAtlasStorage {
     }
 }
 
+impl ops::IndexMut<AtlasTextureId> for BladeAtlasStorage {
+    fn index_mut(&mut self, id: AtlasTextureId) -> &mut Self::Output {
+        let textures = match id.kind {
+            crate::AtlasTextureKind::Monochrome => &mut self.monochrome_textures,
+            crate::AtlasTextureKind::Polychrome => &mut self.polychrome_textures,
+            crate::AtlasTextureKind::Path => &mut self.path_textures,
+        };
+        &mut textures[id.index as usize]
+    }
+}
+
 impl BladeAtlasStorage {
     fn destroy(&mut self, gpu: &gpu::Context) {
         for mut texture in self.monochrome_textures.drain(..) {
@@ -3,9 +3,9 @@
 
 use super::{BladeAtlas, PATH_TEXTURE_FORMAT};
 use crate::{
-    AtlasTextureKind, AtlasTile, Bounds, ContentMask, DevicePixels, Hsla, MonochromeSprite, Path,
-    PathId, PathVertex, PolychromeSprite, PrimitiveBatch, Quad, ScaledPixels, Scene, Shadow, Size,
-    Underline,
+    AtlasTextureKind, AtlasTile, Bounds, ContentMask, DevicePixels, Gradient, Hsla,
+    MonochromeSprite, Path, PathId, PathVertex, PolychromeSprite, PrimitiveBatch, Quad,
+    ScaledPixels, Scene, Shadow, Size, Underline,
 };
 use bytemuck::{Pod, Zeroable};
 use collections::HashMap;
@@ -105,17 +105,40 @@ impl From<Bounds<ScaledPixels>> for PodBounds {
     }
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct PodContentMask {
+    bounds: PodBounds,
+    corner_radii: [f32; 4],
+}
+
+impl From<ContentMask<ScaledPixels>> for PodContentMask {
+    fn from(content_mask: ContentMask<ScaledPixels>) -> Self {
+        let radii = content_mask.corner_radii;
+        Self {
+            bounds: content_mask.bounds.into(),
+            corner_radii: [
+                radii.top_left.0,
+                radii.top_right.0,
+                radii.bottom_right.0,
+                radii.bottom_left.0,
+            ],
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 struct SurfaceParams {
     bounds: PodBounds,
-    content_mask: PodBounds,
+    content_mask: PodContentMask,
 }
 
 #[derive(blade_macros::ShaderData)]
 struct ShaderQuadsData {
     globals: GlobalParams,
     b_quads: gpu::BufferPiece,
+    b_gradients: gpu::BufferPiece,
 }
 
 #[derive(blade_macros::ShaderData)]
@@ -175,6 +198,9 @@ struct PathSprite {
     bounds: Bounds<ScaledPixels>,
     color: Hsla,
     tile: AtlasTile,
+    fill_rule: u32,
+    pad: u32,
+    content_mask: ContentMask<ScaledPixels>,
 }
 
 struct BladePipelines {
@@ -202,6 +228,7 @@ impl BladePipelines {
         shader.check_struct_size::<GlobalParams>();
         shader.check_struct_size::<SurfaceParams>();
         shader.check_struct_size::<Quad>();
+        shader.check_struct_size::<Gradient>();
         shader.check_struct_size::<Shadow>();
         assert_eq!(
             mem::size_of::<PathVertex<ScaledPixels>>(),
@@ -480,9 +507,7 @@ impl BladeRenderer {
                     xy_position: vertex.xy_position - clipped_bounds.origin
                         + tile.bounds.origin.map(Into::into),
                     st_position: vertex.st_position,
-                    content_mask: ContentMask {
-                        bounds: tile.bounds.map(Into::into),
-                    },
+                    content_mask: ContentMask::new(tile.bounds.map(Into::into)),
                 }));
             self.path_tiles.insert(path.id, tile);
         }
@@ -556,6 +581,15 @@ impl BladeRenderer {
             depth_stencil: None,
         }) {
             profiling::scope!("render pass");
+            // Quads refer to the scene's gradients by their index, so they are all uploaded
+            // together. Bindings can't be empty.
+            let no_gradients = [Gradient::default()];
+            let gradients = if scene.gradients.is_empty() {
+                &no_gradients[..]
+            } else {
+                scene.gradients.as_slice()
+            };
+            let gradients_buf = unsafe { self.instance_belt.alloc_typed(gradients, &self.gpu) };
             for batch in scene.batches() {
                 match batch {
                     PrimitiveBatch::Quads(quads) => {
@@ -567,6 +601,7 @@ impl BladeRenderer {
                             &ShaderQuadsData {
                                 globals,
                                 b_quads: instance_buf,
+                                b_gradients: gradients_buf,
                             },
                         );
                         encoder.draw(0, 4, 0, quads.len() as u32);
@@ -598,6 +633,9 @@ impl BladeRenderer {
                                 },
                                 color: path.color,
                                 tile: (*tile).clone(),
+                                fill_rule: path.fill_rule as u32,
+                                pad: 0,
+                                content_mask: path.content_mask.clone(),
                             }];
 
                             let instance_buf =
@@ -726,7 +764,7 @@ impl BladeRenderer {
                                         globals,
                                         surface_locals: SurfaceParams {
                                             bounds: surface.bounds.into(),
-                                            content_mask: surface.content_mask.bounds.into(),
+                                            content_mask: surface.content_mask.clone().into(),
                                         },
                                         t_y,
                                         t_cb_cr,
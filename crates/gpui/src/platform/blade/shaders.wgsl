@@ -21,6 +21,10 @@ struct Corners {
     bottom_right: f32,
     bottom_left: f32,
 }
+struct ContentMask {
+    bounds: Bounds,
+    corner_radii: Corners,
+}
 struct Edges {
     top: f32,
     right: f32,
@@ -189,6 +193,142 @@ fn quad_sdf(point: vec2<f32>, bounds: Bounds, corner_radii: Corners) -> f32 {
         corner_radius;
 }
 
+// Returns how far along the outline of a rounded quad `point` is, going clockwise from the start
+// of the top edge. Points in a corner are projected onto its arc, and other points onto their
+// nearest edge.
+fn quad_outline_position(point: vec2<f32>, bounds: Bounds, radii: Corners) -> f32 {
+    let size = bounds.size;
+    let p = point - bounds.origin;
+    let quarter_turn = M_PI_F / 2.0;
+
+    let top_end = size.x - radii.top_left - radii.top_right;
+    let right_start = top_end + quarter_turn * radii.top_right;
+    let right_end = right_start + size.y - radii.top_right - radii.bottom_right;
+    let bottom_start = right_end + quarter_turn * radii.bottom_right;
+    let bottom_end = bottom_start + size.x - radii.bottom_right - radii.bottom_left;
+    let left_start = bottom_end + quarter_turn * radii.bottom_left;
+    let left_end = left_start + size.y - radii.bottom_left - radii.top_left;
+
+    if (p.x < radii.top_left && p.y < radii.top_left) {
+        let d = p - vec2<f32>(radii.top_left);
+        return left_end + radii.top_left * atan2(-d.y, -d.x);
+    }
+    if (p.x > size.x - radii.top_right && p.y < radii.top_right) {
+        let d = p - vec2<f32>(size.x - radii.top_right, radii.top_right);
+        return top_end + radii.top_right * atan2(d.x, -d.y);
+    }
+    if (p.x > size.x - radii.bottom_right && p.y > size.y - radii.bottom_right) {
+        let d = p - (size - vec2<f32>(radii.bottom_right));
+        return right_end + radii.bottom_right * atan2(d.y, d.x);
+    }
+    if (p.x < radii.bottom_left && p.y > size.y - radii.bottom_left) {
+        let d = p - vec2<f32>(radii.bottom_left, size.y - radii.bottom_left);
+        return bottom_end + radii.bottom_left * atan2(-d.x, d.y);
+    }
+
+    let edge_distances = vec4<f32>(p.y, size.x - p.x, size.y - p.y, p.x);
+    let nearest = min(min(edge_distances.x, edge_distances.y), min(edge_distances.z, edge_distances.w));
+    if (nearest == edge_distances.x) {
+        return p.x - radii.top_left;
+    } else if (nearest == edge_distances.y) {
+        return right_start + p.y - radii.top_right;
+    } else if (nearest == edge_distances.z) {
+        return bottom_start + size.x - radii.bottom_right - p.x;
+    } else {
+        return left_start + size.y - radii.bottom_left - p.y;
+    }
+}
+
+// Returns the color of a gradient background at `position`, interpolating between its stops in
+// the Oklab color space with premultiplied alpha.
+fn gradient_color(background: Gradient, position: vec2<f32>) -> vec4<f32> {
+    // Arrays can only be indexed dynamically through variables.
+    var gradient = background;
+    let axis = gradient.end - gradient.start;
+    var t = 0.0;
+    if (gradient.kind == 1u) {
+        t = dot(position - gradient.start, axis) / max(dot(axis, axis), 0.0001);
+    } else {
+        t = length(position - gradient.start) / max(length(axis), 0.0001);
+    }
+
+    var end_ix = 0u;
+    while (end_ix < gradient.stop_count && gradient.positions[end_ix] < t) {
+        end_ix += 1u;
+    }
+    if (end_ix == 0u) {
+        return hsla_to_rgba(gradient.colors[0]);
+    }
+    if (end_ix == gradient.stop_count) {
+        return hsla_to_rgba(gradient.colors[gradient.stop_count - 1u]);
+    }
+
+    let start_ix = end_ix - 1u;
+    let span = gradient.positions[end_ix] - gradient.positions[start_ix];
+    let progress = select(1.0, (t - gradient.positions[start_ix]) / span, span > 0.0);
+    let start_color = hsla_to_rgba(gradient.colors[start_ix]);
+    let end_color = hsla_to_rgba(gradient.colors[end_ix]);
+    let mixed = mix(
+        vec4<f32>(srgb_to_oklab(start_color.rgb) * start_color.a, start_color.a),
+        vec4<f32>(srgb_to_oklab(end_color.rgb) * end_color.a, end_color.a),
+        progress,
+    );
+    if (mixed.a <= 0.0) {
+        return vec4<f32>(0.0);
+    }
+    return vec4<f32>(oklab_to_srgb(mixed.rgb / mixed.a), mixed.a);
+}
+
+fn srgb_to_oklab(color: vec3<f32>) -> vec3<f32> {
+    let linear_color = select(pow((color + 0.055) / 1.055, vec3<f32>(2.4)), color / 12.92,
+                              color <= vec3<f32>(0.04045));
+    let lms = pow(vec3<f32>(
+        dot(vec3<f32>(0.4122215, 0.5363325, 0.0514460), linear_color),
+        dot(vec3<f32>(0.2119035, 0.6806995, 0.1073970), linear_color),
+        dot(vec3<f32>(0.0883025, 0.2817188, 0.6299787), linear_color),
+    ), vec3<f32>(1.0 / 3.0));
+    return vec3<f32>(
+        dot(vec3<f32>(0.2104543, 0.7936178, -0.0040720), lms),
+        dot(vec3<f32>(1.9779985, -2.4285922, 0.4505937), lms),
+        dot(vec3<f32>(0.0259040, 0.7827718, -0.8086758), lms),
+    );
+}
+
+fn oklab_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let lms_root = vec3<f32>(
+        dot(vec3<f32>(1.0, 0.3963378, 0.2158038), color),
+        dot(vec3<f32>(1.0, -0.1055613, -0.0638542), color),
+        dot(vec3<f32>(1.0, -0.0894842, -1.2914855), color),
+    );
+    let lms = lms_root * lms_root * lms_root;
+    let linear_color = saturate(vec3<f32>(
+        dot(vec3<f32>(4.0767417, -3.3077116, 0.2309699), lms),
+        dot(vec3<f32>(-1.2684380, 2.6097574, -0.3413194), lms),
+        dot(vec3<f32>(-0.0041961, -0.7034186, 1.7076147), lms),
+    ));
+    return select(1.055 * pow(linear_color, vec3<f32>(1.0 / 2.4)) - 0.055, linear_color * 12.92,
+                  linear_color <= vec3<f32>(0.0031308));
+}
+
+// Returns the coverage of a dashed line at `position` along it, antialiasing both ends of each
+// dash.
+fn dash_alpha(position: f32, dash_length: f32, dash_gap: f32) -> f32 {
+    let period = dash_length + dash_gap;
+    let offset = position - floor(position / period) * period;
+    return max(saturate(min(offset, dash_length - offset) + 0.5), saturate(offset - period + 0.5));
+}
+
+// Returns the coverage of `point` by the content mask's rounded corners. The
+// rectangular part of the mask is already handled by the clip distances.
+fn content_mask_alpha(point: vec2<f32>, content_mask: ContentMask) -> f32 {
+    let radii = content_mask.corner_radii;
+    if (radii.top_left == 0.0 && radii.top_right == 0.0 &&
+        radii.bottom_right == 0.0 && radii.bottom_left == 0.0) {
+        return 1.0;
+    }
+    return saturate(0.5 - quad_sdf(point, content_mask.bounds, radii));
+}
+
 // Abstract away the final color transformation based on the
 // target alpha compositing mode.
 fn blend_color(color: vec4<f32>, alpha_factor: f32) -> vec4<f32> {
@@ -199,15 +339,27 @@ fn blend_color(color: vec4<f32>, alpha_factor: f32) -> vec4<f32> {
 
 // --- quads --- //
 
+struct Gradient {
+    kind: u32,
+    stop_count: u32,
+    start: vec2<f32>,
+    end: vec2<f32>,
+    colors: array<Hsla, 4>,
+    positions: array<f32, 4>,
+}
+var<storage, read> b_gradients: array<Gradient>;
+
 struct Quad {
     order: u32,
-    pad: u32,
+    gradient_id: u32,
     bounds: Bounds,
-    content_mask: Bounds,
+    content_mask: ContentMask,
     background: Hsla,
     border_color: Hsla,
     corner_radii: Corners,
     border_widths: Edges,
+    dash_length: f32,
+    dash_gap: f32,
 }
 var<storage, read> b_quads: array<Quad>;
 
@@ -230,7 +382,7 @@ fn vs_quad(@builtin(vertex_index) vertex_id: u32, @builtin(instance_index) insta
     out.background_color = hsla_to_rgba(quad.background);
     out.border_color = hsla_to_rgba(quad.border_color);
     out.quad_id = instance_id;
-    out.clip_distances = distance_from_clip_rect(unit_vertex, quad.bounds, quad.content_mask);
+    out.clip_distances = distance_from_clip_rect(unit_vertex, quad.bounds, quad.content_mask.bounds);
     return out;
 }
 
@@ -242,13 +394,18 @@ fn fs_quad(input: QuadVarying) -> @location(0) vec4<f32> {
     }
 
     let quad = b_quads[input.quad_id];
+    let clip_alpha = content_mask_alpha(input.position.xy, quad.content_mask);
+    var background_color = input.background_color;
+    if (quad.gradient_id != 0u) {
+        background_color = gradient_color(b_gradients[quad.gradient_id - 1u], input.position.xy);
+    }
     // Fast path when the quad is not rounded and doesn't have any border.
     if (quad.corner_radii.top_left == 0.0 && quad.corner_radii.bottom_left == 0.0 &&
         quad.corner_radii.top_right == 0.0 &&
         quad.corner_radii.bottom_right == 0.0 && quad.border_widths.top == 0.0 &&
         quad.border_widths.left == 0.0 && quad.border_widths.right == 0.0 &&
         quad.border_widths.bottom == 0.0) {
-        return blend_color(input.background_color, 1.0);
+        return blend_color(background_color, clip_alpha);
     }
 
     let half_size = quad.bounds.size / 2.0;
@@ -277,17 +434,22 @@ fn fs_quad(input: QuadVarying) -> @location(0) vec4<f32> {
         border_width = vertical_border;
     }
 
-    var color = input.background_color;
+    var color = background_color;
     if (border_width > 0.0) {
         let inset_distance = distance + border_width;
         // Blend the border on top of the background and then linearly interpolate
         // between the two as we slide inside the background.
-        let blended_border = over(input.background_color, input.border_color);
-        color = mix(blended_border, input.background_color,
+        var blended_border = over(background_color, input.border_color);
+        if (quad.dash_length > 0.0) {
+            let position = quad_outline_position(input.position.xy, quad.bounds, quad.corner_radii);
+            blended_border = mix(background_color, blended_border,
+                                 dash_alpha(position, quad.dash_length, quad.dash_gap));
+        }
+        color = mix(blended_border, background_color,
                     saturate(0.5 - inset_distance));
     }
 
-    return blend_color(color, saturate(0.5 - distance));
+    return blend_color(color, saturate(0.5 - distance) * clip_alpha);
 }
 
 // --- shadows --- //
@@ -297,7 +459,7 @@ struct Shadow {
     blur_radius: f32,
     bounds: Bounds,
     corner_radii: Corners,
-    content_mask: Bounds,
+    content_mask: ContentMask,
     color: Hsla,
 }
 var<storage, read> b_shadows: array<Shadow>;
@@ -325,7 +487,7 @@ fn vs_shadow(@builtin(vertex_index) vertex_id: u32, @builtin(instance_index) ins
     out.position = to_device_position(unit_vertex, shadow.bounds);
     out.color = hsla_to_rgba(shadow.color);
     out.shadow_id = instance_id;
-    out.clip_distances = distance_from_clip_rect(unit_vertex, shadow.bounds, shadow.content_mask);
+    out.clip_distances = distance_from_clip_rect(unit_vertex, shadow.bounds, shadow.content_mask.bounds);
     return out;
 }
 
@@ -360,6 +522,7 @@ fn fs_shadow(input: ShadowVarying) -> @location(0) vec4<f32> {
         y += step;
     }
 
+    alpha *= content_mask_alpha(input.position.xy, shadow.content_mask);
     return blend_color(input.color, alpha);
 }
 
@@ -368,7 +531,7 @@ fn fs_shadow(input: ShadowVarying) -> @location(0) vec4<f32> {
 struct PathVertex {
     xy_position: vec2<f32>,
     st_position: vec2<f32>,
-    content_mask: Bounds,
+    content_mask: ContentMask,
 }
 var<storage, read> b_path_vertices: array<PathVertex>;
 
@@ -386,7 +549,7 @@ fn vs_path_rasterization(@builtin(vertex_index) vertex_id: u32) -> PathRasteriza
     var out = PathRasterizationVarying();
     out.position = to_device_position_impl(v.xy_position);
     out.st_position = v.st_position;
-    out.clip_distances = distance_from_clip_rect_impl(v.xy_position, v.content_mask);
+    out.clip_distances = distance_from_clip_rect_impl(v.xy_position, v.content_mask.bounds);
     return out;
 }
 
@@ -410,6 +573,9 @@ struct PathSprite {
     bounds: Bounds,
     color: Hsla,
     tile: AtlasTile,
+    fill_rule: u32,
+    pad: u32,
+    content_mask: ContentMask,
 }
 var<storage, read> b_path_sprites: array<PathSprite>;
 
@@ -417,25 +583,36 @@ struct PathVarying {
     @builtin(position) position: vec4<f32>,
     @location(0) tile_position: vec2<f32>,
     @location(1) color: vec4<f32>,
+    @location(2) @interpolate(flat) fill_rule: u32,
+    @location(3) @interpolate(flat) sprite_id: u32,
 }
 
 @vertex
 fn vs_path(@builtin(vertex_index) vertex_id: u32, @builtin(instance_index) instance_id: u32) -> PathVarying {
     let unit_vertex = vec2<f32>(f32(vertex_id & 1u), 0.5 * f32(vertex_id & 2u));
     let sprite = b_path_sprites[instance_id];
-    // Don't apply content mask because it was already accounted for when rasterizing the path.
+    // The rectangular part of the content mask was already accounted for when rasterizing the
+    // path. Its rounded corners are applied to the coverage of the whole path.
 
     var out = PathVarying();
     out.position = to_device_position(unit_vertex, sprite.bounds);
     out.tile_position = to_tile_position(unit_vertex, sprite.tile);
     out.color = hsla_to_rgba(sprite.color);
+    out.fill_rule = sprite.fill_rule;
+    out.sprite_id = instance_id;
     return out;
 }
 
 @fragment
 fn fs_path(input: PathVarying) -> @location(0) vec4<f32> {
     let sample = textureSample(t_sprite, s_sprite, input.tile_position).r;
-    let mask = 1.0 - abs(1.0 - sample % 2.0);
+    var mask = 1.0 - abs(1.0 - sample % 2.0);
+    if (input.fill_rule == 1u) {
+        // Overlapping triangles cover their union.
+        mask = saturate(sample);
+    }
+    let sprite = b_path_sprites[input.sprite_id];
+    mask *= content_mask_alpha(input.position.xy, sprite.content_mask);
     return blend_color(input.color, mask);
 }
 
@@ -445,7 +622,7 @@ struct Underline {
     order: u32,
     pad: u32,
     bounds: Bounds,
-    content_mask: Bounds,
+    content_mask: ContentMask,
     color: Hsla,
     thickness: f32,
     wavy: u32,
@@ -469,7 +646,7 @@ fn vs_underline(@builtin(vertex_index) vertex_id: u32, @builtin(instance_index)
     out.position = to_device_position(unit_vertex, underline.bounds);
     out.color = hsla_to_rgba(underline.color);
     out.underline_id = instance_id;
-    out.clip_distances = distance_from_clip_rect(unit_vertex, underline.bounds, underline.content_mask);
+    out.clip_distances = distance_from_clip_rect(unit_vertex, underline.bounds, underline.content_mask.bounds);
     return out;
 }
 
@@ -481,9 +658,10 @@ fn fs_underline(input: UnderlineVarying) -> @location(0) vec4<f32> {
     }
 
     let underline = b_underlines[input.underline_id];
+    let clip_alpha = content_mask_alpha(input.position.xy, underline.content_mask);
     if ((underline.wavy & 0xFFu) == 0u)
     {
-        return blend_color(input.color, input.color.a);
+        return blend_color(input.color, input.color.a * clip_alpha);
     }
 
     let half_thickness = underline.thickness * 0.5;
@@ -497,7 +675,7 @@ fn fs_underline(input: UnderlineVarying) -> @location(0) vec4<f32> {
     let distance_from_top_border = distance_in_pixels - half_thickness;
     let distance_from_bottom_border = distance_in_pixels + half_thickness;
     let alpha = saturate(0.5 - max(-distance_from_bottom_border, distance_from_top_border));
-    return blend_color(input.color, alpha * input.color.a);
+    return blend_color(input.color, alpha * input.color.a * clip_alpha);
 }
 
 // --- monochrome sprites --- //
@@ -506,7 +684,7 @@ struct MonochromeSprite {
     order: u32,
     pad: u32,
     bounds: Bounds,
-    content_mask: Bounds,
+    content_mask: ContentMask,
     color: Hsla,
     tile: AtlasTile,
     transformation: TransformationMatrix,
@@ -517,6 +695,7 @@ struct MonoSpriteVarying {
     @builtin(position) position: vec4<f32>,
     @location(0) tile_position: vec2<f32>,
     @location(1) @interpolate(flat) color: vec4<f32>,
+    @location(2) @interpolate(flat) sprite_id: u32,
     @location(3) clip_distances: vec4<f32>,
 }
 
@@ -530,7 +709,8 @@ fn vs_mono_sprite(@builtin(vertex_index) vertex_id: u32, @builtin(instance_index
 
     out.tile_position = to_tile_position(unit_vertex, sprite.tile);
     out.color = hsla_to_rgba(sprite.color);
-    out.clip_distances = distance_from_clip_rect(unit_vertex, sprite.bounds, sprite.content_mask);
+    out.sprite_id = instance_id;
+    out.clip_distances = distance_from_clip_rect(unit_vertex, sprite.bounds, sprite.content_mask.bounds);
     return out;
 }
 
@@ -541,7 +721,9 @@ fn fs_mono_sprite(input: MonoSpriteVarying) -> @location(0) vec4<f32> {
     if (any(input.clip_distances < vec4<f32>(0.0))) {
         return vec4<f32>(0.0);
     }
-    return blend_color(input.color, sample);
+    let sprite = b_mono_sprites[input.sprite_id];
+    let clip_alpha = content_mask_alpha(input.position.xy, sprite.content_mask);
+    return blend_color(input.color, sample * clip_alpha);
 }
 
 // --- polychrome sprites --- //
@@ -549,8 +731,10 @@ fn fs_mono_sprite(input: MonoSpriteVarying) -> @location(0) vec4<f32> {
 struct PolychromeSprite {
     order: u32,
     grayscale: u32,
+    opacity: f32,
+    pad: u32,
     bounds: Bounds,
-    content_mask: Bounds,
+    content_mask: ContentMask,
     corner_radii: Corners,
     tile: AtlasTile,
 }
@@ -572,7 +756,7 @@ fn vs_poly_sprite(@builtin(vertex_index) vertex_id: u32, @builtin(instance_index
     out.position = to_device_position(unit_vertex, sprite.bounds);
     out.tile_position = to_tile_position(unit_vertex, sprite.tile);
     out.sprite_id = instance_id;
-    out.clip_distances = distance_from_clip_rect(unit_vertex, sprite.bounds, sprite.content_mask);
+    out.clip_distances = distance_from_clip_rect(unit_vertex, sprite.bounds, sprite.content_mask.bounds);
     return out;
 }
 
@@ -592,14 +776,15 @@ fn fs_poly_sprite(input: PolySpriteVarying) -> @location(0) vec4<f32> {
         let grayscale = dot(color.rgb, GRAYSCALE_FACTORS);
         color = vec4<f32>(vec3<f32>(grayscale), sample.a);
     }
-    return blend_color(color, saturate(0.5 - distance));
+    let clip_alpha = content_mask_alpha(input.position.xy, sprite.content_mask);
+    return blend_color(color, sprite.opacity * saturate(0.5 - distance) * clip_alpha);
 }
 
 // --- surfaces --- //
 
 struct SurfaceParams {
     bounds: Bounds,
-    content_mask: Bounds,
+    content_mask: ContentMask,
 }
 
 var<uniform> surface_locals: SurfaceParams;
@@ -627,7 +812,7 @@ fn vs_surface(@builtin(vertex_index) vertex_id: u32) -> SurfaceVarying {
     var out = SurfaceVarying();
     out.position = to_device_position(unit_vertex, surface_locals.bounds);
     out.texture_position = unit_vertex;
-    out.clip_distances = distance_from_clip_rect(unit_vertex, surface_locals.bounds, surface_locals.content_mask);
+    out.clip_distances = distance_from_clip_rect(unit_vertex, surface_locals.bounds, surface_locals.content_mask.bounds);
     return out;
 }
 
@@ -643,5 +828,6 @@ fn fs_surface(input: SurfaceVarying) -> @location(0) vec4<f32> {
         textureSampleLevel(t_cb_cr, s_surface, input.texture_position, 0.0).rg,
         1.0);
 
-    return ycbcr_to_RGB * y_cb_cr;
+    let clip_alpha = content_mask_alpha(input.position.xy, surface_locals.content_mask);
+    return blend_color(ycbcr_to_RGB * y_cb_cr, clip_alpha);
 }
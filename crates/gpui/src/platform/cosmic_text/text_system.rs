@@ -1,13 +1,18 @@
 use crate::{
-    point, size, Bounds, DevicePixels, Font, FontFeatures, FontId, FontMetrics, FontRun, FontStyle,
-    FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem, Point, RenderGlyphParams,
-    ShapedGlyph, SharedString, Size,
+    has_color_layers, point, size, Bounds, DevicePixels, Font, FontAxes, FontFeatures, FontId,
+    FontMetrics, FontRun, FontStyle, FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem,
+    Point, RenderGlyphParams, ShapedGlyph, SharedString, Size,
 };
 use anyhow::{anyhow, Context, Ok, Result};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use cosmic_text::{
+    rustybuzz,
+    swash::{
+        scale::{Render, ScaleContext, Source, StrikeWith},
+        zeno::Format,
+    },
     Attrs, AttrsList, CacheKey, Family, Font as CosmicTextFont, FontSystem, ShapeBuffer, ShapeLine,
-    SwashCache,
+    SwashCache, SwashImage,
 };
 
 use itertools::Itertools;
@@ -21,17 +26,31 @@ use std::{borrow::Cow, sync::Arc};
 
 pub(crate) struct CosmicTextSystem(RwLock<CosmicTextSystemState>);
 
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct FontKey {
+    family: SharedString,
+    features: FontFeatures,
+    axes: FontAxes,
+}
+
 struct CosmicTextSystemState {
     swash_cache: SwashCache,
+    /// Scales the glyphs of variable fonts, which the swash cache only knows at their defaults.
+    scale_context: ScaleContext,
     font_system: FontSystem,
     scratch: ShapeBuffer,
     /// Contains all already loaded fonts, including all faces. Indexed by `FontId`.
     loaded_fonts_store: Vec<Arc<CosmicTextFont>>,
-    /// Caches the `FontId`s associated with a specific family to avoid iterating the font database
-    /// for every font face in a family.
-    font_ids_by_family_cache: HashMap<SharedString, SmallVec<[FontId; 4]>>,
-    /// The name of each font associated with the given font id
-    postscript_names: HashMap<FontId, String>,
+    /// Caches the `FontId`s associated with a specific family, set of features and variation axes
+    /// to avoid iterating the font database for every font face in a family.
+    font_ids_by_font_key: HashMap<FontKey, SmallVec<[FontId; 4]>>,
+    /// The OpenType features applied when shaping text with each font that has any.
+    features_by_font_id: HashMap<FontId, FontFeatures>,
+    /// The values applied to the axes of each variable font instance, by axis tag.
+    variations_by_font_id: HashMap<FontId, Vec<(u32, f32)>>,
+    /// Fonts with color glyphs, like emoji fonts with CBDT or COLR tables, which may also have
+    /// glyphs drawn as outlines.
+    color_font_ids: HashSet<FontId>,
 }
 
 impl CosmicTextSystem {
@@ -44,10 +63,13 @@ impl CosmicTextSystem {
         Self(RwLock::new(CosmicTextSystemState {
             font_system,
             swash_cache: SwashCache::new(),
+            scale_context: ScaleContext::new(),
             scratch: ShapeBuffer::default(),
             loaded_fonts_store: Vec::new(),
-            font_ids_by_family_cache: HashMap::default(),
-            postscript_names: HashMap::default(),
+            font_ids_by_font_key: HashMap::default(),
+            features_by_font_id: HashMap::default(),
+            variations_by_font_id: HashMap::default(),
+            color_font_ids: HashSet::default(),
         }))
     }
 }
@@ -88,14 +110,17 @@ impl PlatformTextSystem for CosmicTextSystem {
         // todo(linux): Do we need to use CosmicText's Font APIs? Can we consolidate this to use font_kit?
         let mut state = self.0.write();
 
-        let candidates = if let Some(font_ids) = state.font_ids_by_family_cache.get(&font.family) {
+        let key = FontKey {
+            family: font.family.clone(),
+            features: font.features.clone(),
+            axes: font.axes.clone(),
+        };
+        let candidates = if let Some(font_ids) = state.font_ids_by_font_key.get(&key) {
             font_ids.as_slice()
         } else {
-            let font_ids = state.load_family(&font.family, &font.features)?;
-            state
-                .font_ids_by_family_cache
-                .insert(font.family.clone(), font_ids);
-            state.font_ids_by_family_cache[&font.family].as_ref()
+            let font_ids = state.load_family(&font.family, &font.features, &font.axes)?;
+            state.font_ids_by_font_key.insert(key.clone(), font_ids);
+            state.font_ids_by_font_key[&key].as_ref()
         };
 
         // todo(linux) ideally we would make fontdb's `find_best_match` pub instead of using font-kit here
@@ -196,12 +221,14 @@ impl CosmicTextSystemState {
         Ok(())
     }
 
-    // todo(linux) handle `FontFeatures`
+    /// Loads the faces of a family, under `FontId`s of their own for each set of features and
+    /// variation axes.
     #[profiling::function]
     fn load_family(
         &mut self,
         name: &str,
-        _features: &FontFeatures,
+        features: &FontFeatures,
+        axes: &FontAxes,
     ) -> Result<SmallVec<[FontId; 4]>> {
         // TODO: Determine the proper system UI font.
         let name = if name == ".SystemUIFont" {
@@ -240,8 +267,25 @@ impl CosmicTextSystemState {
 
             let font_id = FontId(self.loaded_fonts_store.len());
             font_ids.push(font_id);
+            if has_color_glyphs(&font) {
+                self.color_font_ids.insert(font_id);
+            }
+            if !features.tag_value_list().is_empty() {
+                self.features_by_font_id.insert(font_id, features.clone());
+            }
+            if !axes.is_empty() {
+                // Static fonts are left as they are, to be matched by the axes' weight and style.
+                let swash_font = font.as_swash();
+                let variations = swash_font
+                    .table(u32::from_be_bytes(*b"fvar"))
+                    .and_then(|fvar| {
+                        axes.resolve(fvar, swash_font.table(u32::from_be_bytes(*b"name")))
+                    });
+                if let Some(variations) = variations.filter(|variations| !variations.is_empty()) {
+                    self.variations_by_font_id.insert(font_id, variations);
+                }
+            }
             self.loaded_fonts_store.push(font);
-            self.postscript_names.insert(font_id, postscript_name);
         }
 
         Ok(font_ids)
@@ -271,31 +315,22 @@ impl CosmicTextSystemState {
         }
     }
 
-    fn is_emoji(&self, font_id: FontId) -> bool {
-        // TODO: Include other common emoji fonts
-        self.postscript_names
-            .get(&font_id)
-            .map_or(false, |postscript_name| postscript_name == "NotoColorEmoji")
+    /// Whether the glyph is drawn with color layers or a color bitmap, which swash rasterizes
+    /// as an image rather than a mask.
+    fn is_color_glyph(&self, font_id: FontId, glyph_id: u16) -> bool {
+        if !self.color_font_ids.contains(&font_id) {
+            return false;
+        }
+        let font = self.loaded_fonts_store[font_id.0].as_swash();
+        font.table(u32::from_be_bytes(*b"COLR"))
+            .map_or(false, |colr| has_color_layers(colr, glyph_id))
+            || font
+                .color_strikes()
+                .any(|strike| strike.get(glyph_id).is_some())
     }
 
     fn raster_bounds(&mut self, params: &RenderGlyphParams) -> Result<Bounds<DevicePixels>> {
-        let font = &self.loaded_fonts_store[params.font_id.0];
-        let font_system = &mut self.font_system;
-        let image = self
-            .swash_cache
-            .get_image(
-                font_system,
-                CacheKey::new(
-                    font.id(),
-                    params.glyph_id.0 as u16,
-                    (params.font_size * params.scale_factor).into(),
-                    (0.0, 0.0),
-                    cosmic_text::CacheKeyFlags::empty(),
-                )
-                .0,
-            )
-            .clone()
-            .with_context(|| format!("no image for {params:?} in font {font:?}"))?;
+        let image = self.glyph_image(params)?;
         Ok(Bounds {
             origin: point(image.placement.left.into(), (-image.placement.top).into()),
             size: size(image.placement.width.into(), image.placement.height.into()),
@@ -313,12 +348,44 @@ impl CosmicTextSystemState {
         } else {
             // todo(linux) handle subpixel variants
             let bitmap_size = glyph_bounds.size;
-            let font = &self.loaded_fonts_store[params.font_id.0];
-            let font_system = &mut self.font_system;
-            let mut image = self
-                .swash_cache
+            let mut image = self.glyph_image(params)?;
+
+            if params.is_emoji {
+                // Convert from RGBA to BGRA.
+                for pixel in image.data.chunks_exact_mut(4) {
+                    pixel.swap(0, 2);
+                }
+            }
+
+            Ok((bitmap_size, image.data))
+        }
+    }
+
+    fn glyph_image(&mut self, params: &RenderGlyphParams) -> Result<SwashImage> {
+        let font = &self.loaded_fonts_store[params.font_id.0];
+        let image = if let Some(variations) = self.variations_by_font_id.get(&params.font_id) {
+            let mut scaler = self
+                .scale_context
+                .builder(font.as_swash())
+                .size(params.font_size.0 * params.scale_factor)
+                .hint(true)
+                .variations(
+                    variations
+                        .iter()
+                        .map(|&(tag, value)| cosmic_text::swash::Setting { tag, value }),
+                )
+                .build();
+            Render::new(&[
+                Source::ColorOutline(0),
+                Source::ColorBitmap(StrikeWith::BestFit),
+                Source::Outline,
+            ])
+            .format(Format::Alpha)
+            .render(&mut scaler, params.glyph_id.0 as u16)
+        } else {
+            self.swash_cache
                 .get_image(
-                    font_system,
+                    &mut self.font_system,
                     CacheKey::new(
                         font.id(),
                         params.glyph_id.0 as u16,
@@ -329,17 +396,8 @@ impl CosmicTextSystemState {
                     .0,
                 )
                 .clone()
-                .with_context(|| format!("no image for {params:?} in font {font:?}"))?;
-
-            if params.is_emoji {
-                // Convert from RGBA to BGRA.
-                for pixel in image.data.chunks_exact_mut(4) {
-                    pixel.swap(0, 2);
-                }
-            }
-
-            Ok((bitmap_size, image.data))
-        }
+        };
+        image.with_context(|| format!("no image for {params:?} in font {font:?}"))
     }
 
     fn font_id_for_cosmic_id(&mut self, id: cosmic_text::fontdb::ID) -> FontId {
@@ -352,24 +410,155 @@ impl CosmicTextSystemState {
         } else {
             // This matches the behavior of the mac text system
             let font = self.font_system.get_font(id).unwrap();
-            let face = self
-                .font_system
-                .db()
-                .faces()
-                .find(|info| info.id == id)
-                .unwrap();
-
             let font_id = FontId(self.loaded_fonts_store.len());
+            if has_color_glyphs(&font) {
+                self.color_font_ids.insert(font_id);
+            }
             self.loaded_fonts_store.push(font);
-            self.postscript_names
-                .insert(font_id, face.post_script_name.clone());
 
             font_id
         }
     }
 
+    /// Whether text in the given font is shaped on its own, as cosmic-text doesn't apply font
+    /// features or variation axes.
+    fn shapes_alone(&self, font_id: FontId) -> bool {
+        self.features_by_font_id.contains_key(&font_id)
+            || self.variations_by_font_id.contains_key(&font_id)
+    }
+
+    /// Lays out a line, shaping the runs whose fonts have features or variation axes on their
+    /// own, and the rest of the line with cosmic-text so it can fall back to other fonts.
     #[profiling::function]
     fn layout_line(&mut self, text: &str, font_size: Pixels, font_runs: &[FontRun]) -> LineLayout {
+        if !font_runs.iter().any(|run| self.shapes_alone(run.font_id)) {
+            return self.layout_line_with_fallback(text, font_size, font_runs);
+        }
+
+        let mut layout = LineLayout {
+            font_size,
+            len: text.len(),
+            ..Default::default()
+        };
+        let mut offset = 0;
+        let mut run_ix = 0;
+        while run_ix < font_runs.len() {
+            let shapes_alone = self.shapes_alone(font_runs[run_ix].font_id);
+            let end_ix = if shapes_alone {
+                run_ix + 1
+            } else {
+                run_ix
+                    + font_runs[run_ix..]
+                        .iter()
+                        .take_while(|run| !self.shapes_alone(run.font_id))
+                        .count()
+            };
+            let runs = &font_runs[run_ix..end_ix];
+            let len = runs.iter().map(|run| run.len).sum::<usize>();
+            let segment = &text[offset..offset + len];
+            let segment_layout = shapes_alone
+                .then(|| self.layout_run(segment, font_size, runs[0].font_id))
+                .flatten()
+                .unwrap_or_else(|| self.layout_line_with_fallback(segment, font_size, runs));
+
+            for mut run in segment_layout.runs {
+                for glyph in &mut run.glyphs {
+                    glyph.position.x += layout.width;
+                    glyph.index += offset;
+                }
+                layout.runs.push(run);
+            }
+            layout.width += segment_layout.width;
+            layout.ascent = layout.ascent.max(segment_layout.ascent);
+            layout.descent = layout.descent.max(segment_layout.descent);
+            offset += len;
+            run_ix = end_ix;
+        }
+        layout
+    }
+
+    /// Shapes a run with its font's features and variation axes, or returns `None` if the font
+    /// lacks some of the run's characters, in which case it's left to cosmic-text to fall back to
+    /// other fonts.
+    fn layout_run(&self, text: &str, font_size: Pixels, font_id: FontId) -> Option<LineLayout> {
+        let font = &self.loaded_fonts_store[font_id.0];
+        let features = self
+            .features_by_font_id
+            .get(&font_id)
+            .map_or(&[][..], |features| features.tag_value_list())
+            .iter()
+            .filter_map(|(tag, value)| {
+                let tag = <[u8; 4]>::try_from(tag.as_bytes()).ok()?;
+                Some(rustybuzz::Feature::new(
+                    rustybuzz::ttf_parser::Tag::from_bytes(&tag),
+                    *value,
+                    ..,
+                ))
+            })
+            .collect::<SmallVec<[_; 4]>>();
+        let varied_face;
+        let face = match self.variations_by_font_id.get(&font_id) {
+            Some(variations) => {
+                let index = self.font_system.db().face(font.id())?.index;
+                let mut face = rustybuzz::Face::from_slice(font.data(), index)?;
+                face.set_variations(
+                    &variations
+                        .iter()
+                        .map(|&(tag, value)| rustybuzz::Variation {
+                            tag: rustybuzz::ttf_parser::Tag(tag),
+                            value,
+                        })
+                        .collect::<SmallVec<[_; 4]>>(),
+                );
+                varied_face = face;
+                &varied_face
+            }
+            None => font.rustybuzz(),
+        };
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.guess_segment_properties();
+        let output = rustybuzz::shape(face, &features, buffer);
+        if output.glyph_infos().iter().any(|info| info.glyph_id == 0) {
+            return None;
+        }
+
+        let metrics = font.as_swash().metrics(&[]);
+        let scale = font_size.0 / metrics.units_per_em as f32;
+        let mut x = 0.;
+        let mut glyphs = SmallVec::new();
+        for (info, position) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+            let is_emoji = self.is_color_glyph(font_id, info.glyph_id as u16);
+            glyphs.push(ShapedGlyph {
+                id: GlyphId(info.glyph_id),
+                position: point(
+                    (x + position.x_offset as f32 * scale).into(),
+                    (-position.y_offset as f32 * scale).into(),
+                ),
+                index: info.cluster as usize,
+                is_emoji,
+            });
+            x += position.x_advance as f32 * scale;
+        }
+
+        Some(LineLayout {
+            font_size,
+            width: x.into(),
+            ascent: (metrics.ascent * scale).into(),
+            descent: (metrics.descent * scale).into(),
+            runs: vec![crate::ShapedRun { font_id, glyphs }],
+            len: text.len(),
+            bidi_runs: Vec::new(),
+            visual_cells: Default::default(),
+        })
+    }
+
+    fn layout_line_with_fallback(
+        &mut self,
+        text: &str,
+        font_size: Pixels,
+        font_runs: &[FontRun],
+    ) -> LineLayout {
         let mut attrs_list = AttrsList::new(Attrs::new());
         let mut offs = 0;
         for run in font_runs {
@@ -410,11 +599,11 @@ impl CosmicTextSystemState {
         for glyph in &layout.glyphs {
             let font_id = glyph.font_id;
             let font_id = self.font_id_for_cosmic_id(font_id);
-            let is_emoji = self.is_emoji(font_id);
+            let is_emoji = self.is_color_glyph(font_id, glyph.glyph_id);
             let mut glyphs = SmallVec::new();
 
             // HACK: Prevent crash caused by variation selectors.
-            if glyph.glyph_id == 3 && is_emoji {
+            if glyph.glyph_id == 3 && self.color_font_ids.contains(&font_id) {
                 continue;
             }
 
@@ -436,10 +625,20 @@ impl CosmicTextSystemState {
             descent: layout.max_descent.into(),
             runs,
             len: text.len(),
+            bidi_runs: Vec::new(),
+            visual_cells: Default::default(),
         }
     }
 }
 
+/// Whether the font has any color glyphs that swash rasterizes as images rather than masks.
+fn has_color_glyphs(font: &CosmicTextFont) -> bool {
+    let font = font.as_swash();
+    [*b"CBDT", *b"sbix", *b"COLR"]
+        .into_iter()
+        .any(|tag| font.table(u32::from_be_bytes(tag)).is_some())
+}
+
 impl From<RectF> for Bounds<f32> {
     fn from(rect: RectF) -> Self {
         Bounds {
@@ -503,13 +702,18 @@ impl From<FontStyle> for cosmic_text::Style {
 
 fn font_into_properties(font: &crate::Font) -> font_kit::properties::Properties {
     font_kit::properties::Properties {
-        style: match font.style {
+        style: match font.matching_style() {
             crate::FontStyle::Normal => font_kit::properties::Style::Normal,
             crate::FontStyle::Italic => font_kit::properties::Style::Italic,
             crate::FontStyle::Oblique => font_kit::properties::Style::Oblique,
         },
-        weight: font_kit::properties::Weight(font.weight.0),
-        stretch: Default::default(),
+        weight: font_kit::properties::Weight(font.matching_weight().0),
+        stretch: font
+            .axes
+            .get("wdth")
+            .map_or_else(Default::default, |width| {
+                font_kit::properties::Stretch(width / 100.)
+            }),
     }
 }
 
@@ -7,7 +7,7 @@ use std::ffi::OsString;
 use std::fs::File;
 use std::io::Read;
 use std::ops::{Deref, DerefMut};
-use std::os::fd::{AsFd, AsRawFd, FromRawFd};
+use std::os::fd::{AsFd, AsRawFd, FromRawFd, IntoRawFd};
 use std::panic::Location;
 use std::rc::Weak;
 use std::{
@@ -73,6 +73,10 @@ pub trait LinuxClient {
     fn write_to_clipboard(&self, item: ClipboardItem);
     fn read_from_primary(&self) -> Option<ClipboardItem>;
     fn read_from_clipboard(&self) -> Option<ClipboardItem>;
+    /// Whether the client invokes the clipboard change callback when the clipboard changes.
+    fn observes_clipboard_changes(&self) -> bool {
+        false
+    }
     fn active_window(&self) -> Option<AnyWindowHandle>;
     fn run(&self);
 }
@@ -85,6 +89,7 @@ pub(crate) struct PlatformHandlers {
     pub(crate) app_menu_action: Option<Box<dyn FnMut(&dyn Action)>>,
     pub(crate) will_open_app_menu: Option<Box<dyn FnMut()>>,
     pub(crate) validate_app_menu_command: Option<Box<dyn FnMut(&dyn Action) -> bool>>,
+    pub(crate) clipboard_change: Option<Box<dyn FnMut()>>,
 }
 
 pub(crate) struct LinuxCommon {
@@ -491,6 +496,11 @@ impl<P: LinuxClient + 'static> Platform for P {
         self.read_from_clipboard()
     }
 
+    fn on_clipboard_change(&self, callback: Box<dyn FnMut()>) -> bool {
+        self.with_common(|common| common.callbacks.clipboard_change = Some(callback));
+        self.observes_clipboard_changes()
+    }
+
     fn add_recent_document(&self, _path: &Path) {}
 }
 
@@ -556,6 +566,13 @@ pub(super) fn get_xkb_compose_state(cx: &xkb::Context) -> Option<xkb::compose::S
     state
 }
 
+pub(super) unsafe fn read_fd_bytes(fd: FileDescriptor) -> Result<Vec<u8>> {
+    let mut file = File::from_raw_fd(fd.into_raw_fd());
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
 pub(super) unsafe fn read_fd(mut fd: FileDescriptor) -> Result<String> {
     let mut file = File::from_raw_fd(fd.as_raw_fd());
 
@@ -723,11 +723,14 @@ impl LinuxClient for WaylandClient {
             return;
         };
         if state.mouse_focused_window.is_some() || state.keyboard_focused_window.is_some() {
+            let mime_types = Clipboard::mime_types(&item);
             state.clipboard.set(item);
             let serial = state.serial_tracker.get(SerialKind::KeyPress);
             let data_source = data_device_manager.create_data_source(&state.globals.qh, ());
             data_source.offer(state.clipboard.self_mime());
-            data_source.offer(TEXT_MIME_TYPE.to_string());
+            for mime_type in mime_types {
+                data_source.offer(mime_type.to_string());
+            }
             data_device.set_selection(Some(&data_source), serial);
         }
     }
@@ -740,6 +743,10 @@ impl LinuxClient for WaylandClient {
         self.0.borrow_mut().clipboard.read()
     }
 
+    fn observes_clipboard_changes(&self) -> bool {
+        true
+    }
+
     fn active_window(&self) -> Option<AnyWindowHandle> {
         self.0
             .borrow_mut()
@@ -1746,6 +1753,12 @@ impl Dispatch<wl_data_device::WlDataDevice, ()> for WaylandClientStatePtr {
                 } else {
                     state.clipboard.set_offer(None);
                 }
+
+                if let Some(mut callback) = state.common.callbacks.clipboard_change.take() {
+                    drop(state);
+                    callback();
+                    client.borrow_mut().common.callbacks.clipboard_change = Some(callback);
+                }
             }
 
             // Drag and drop
@@ -2,16 +2,24 @@ use std::{
     fs::File,
     io::{ErrorKind, Write},
     os::fd::{AsRawFd, BorrowedFd, OwnedFd},
+    sync::Arc,
 };
 
 use calloop::{LoopHandle, PostAction};
-use filedescriptor::Pipe;
+use filedescriptor::{FileDescriptor, Pipe};
 use wayland_client::{protocol::wl_data_offer::WlDataOffer, Connection};
 use wayland_protocols::wp::primary_selection::zv1::client::zwp_primary_selection_offer_v1::ZwpPrimarySelectionOfferV1;
 
-use crate::{platform::linux::platform::read_fd, ClipboardItem, WaylandClientStatePtr};
+use util::ResultExt as _;
+
+use crate::{
+    platform::linux::platform::{read_fd, read_fd_bytes},
+    ClipboardItem, ImageData, WaylandClientStatePtr,
+};
 
 pub(crate) const TEXT_MIME_TYPE: &str = "text/plain;charset=utf-8";
+pub(crate) const HTML_MIME_TYPE: &str = "text/html";
+pub(crate) const PNG_MIME_TYPE: &str = "image/png";
 pub(crate) const FILE_LIST_MIME_TYPE: &str = "text/uri-list";
 
 /// Text mime types that we'll accept from other programs.
@@ -111,10 +119,34 @@ impl Clipboard {
         self.self_mime.clone()
     }
 
-    pub fn send(&self, _mime_type: String, fd: OwnedFd) {
-        if let Some(contents) = &self.contents {
-            self.send_internal(fd, contents.text.as_bytes().to_owned());
+    /// The mime types the clipboard contents are offered to other programs as.
+    pub fn mime_types(item: &ClipboardItem) -> Vec<&'static str> {
+        let mut mime_types = Vec::new();
+        if !item.text.is_empty() || item.image.is_none() {
+            mime_types.push(TEXT_MIME_TYPE);
+        }
+        if item.html.is_some() {
+            mime_types.push(HTML_MIME_TYPE);
+        }
+        if item.image.is_some() {
+            mime_types.push(PNG_MIME_TYPE);
         }
+        mime_types
+    }
+
+    pub fn send(&self, mime_type: String, fd: OwnedFd) {
+        let Some(contents) = &self.contents else {
+            return;
+        };
+        let bytes = match mime_type.as_str() {
+            HTML_MIME_TYPE => contents.html.clone().map(String::into_bytes),
+            PNG_MIME_TYPE => contents
+                .image
+                .as_ref()
+                .and_then(|image| image.to_png().log_err()),
+            _ => Some(contents.text.as_bytes().to_owned()),
+        };
+        self.send_internal(fd, bytes.unwrap_or_default());
     }
 
     pub fn send_primary(&self, _mime_type: String, fd: OwnedFd) {
@@ -133,9 +165,35 @@ impl Clipboard {
             return self.contents.clone();
         }
 
-        let mime_type = offer.find_text_mime_type()?;
+        let item = if let Some(mime_type) = offer.find_text_mime_type() {
+            let text = self.receive(&offer.inner, mime_type, |fd| unsafe { read_fd(fd) })?;
+            let mut item = ClipboardItem::new(text);
+            if offer.has_mime_type(HTML_MIME_TYPE) {
+                item.html = self.receive(&offer.inner, HTML_MIME_TYPE.to_string(), |fd| unsafe {
+                    read_fd(fd)
+                });
+            }
+            item
+        } else if offer.has_mime_type(PNG_MIME_TYPE) {
+            let png = self.receive(&offer.inner, PNG_MIME_TYPE.to_string(), |fd| unsafe {
+                read_fd_bytes(fd)
+            })?;
+            ClipboardItem::new_image(Arc::new(ImageData::from_png(&png).log_err()?))
+        } else {
+            return None;
+        };
+        self.cached_read = Some(item);
+        self.cached_read.clone()
+    }
+
+    fn receive<T>(
+        &self,
+        offer: &WlDataOffer,
+        mime_type: String,
+        read: impl FnOnce(FileDescriptor) -> anyhow::Result<T>,
+    ) -> Option<T> {
         let pipe = Pipe::new().unwrap();
-        offer.inner.receive(mime_type, unsafe {
+        offer.receive(mime_type, unsafe {
             BorrowedFd::borrow_raw(pipe.write.as_raw_fd())
         });
         let fd = pipe.read;
@@ -143,11 +201,8 @@ impl Clipboard {
 
         self.connection.flush().unwrap();
 
-        match unsafe { read_fd(fd) } {
-            Ok(v) => {
-                self.cached_read = Some(ClipboardItem::new(v));
-                self.cached_read.clone()
-            }
+        match read(fd) {
+            Ok(value) => Some(value),
             Err(err) => {
                 log::error!("error reading clipboard pipe: {err:?}");
                 None
@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::rc::{Rc, Weak};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use calloop::generic::{FdWrapper, Generic};
@@ -15,10 +16,11 @@ use x11rb::connection::{Connection, RequestConnection};
 use x11rb::cursor;
 use x11rb::errors::ConnectionError;
 use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xfixes::ConnectionExt as _;
 use x11rb::protocol::xinput::ConnectionExt;
 use x11rb::protocol::xkb::ConnectionExt as _;
 use x11rb::protocol::xproto::{ChangeWindowAttributesAux, ConnectionExt as _, KeyPressEvent};
-use x11rb::protocol::{randr, render, xinput, xkb, xproto, Event};
+use x11rb::protocol::{randr, render, xfixes, xinput, xkb, xproto, Event};
 use x11rb::resource_manager::Database;
 use x11rb::xcb_ffi::XCBConnection;
 use xim::{x11rb::X11rbClient, Client};
@@ -106,6 +108,7 @@ pub struct X11ClientState {
 
     pub(crate) xcb_connection: Rc<XCBConnection>,
     client_side_decorations_supported: bool,
+    xfixes_supported: bool,
     pub(crate) x_root_index: usize,
     pub(crate) _resource_database: Database,
     pub(crate) atoms: XcbAtoms,
@@ -199,6 +202,9 @@ impl X11Client {
         xcb_connection
             .prefetch_extension_information(xinput::X11_EXTENSION_NAME)
             .unwrap();
+        xcb_connection
+            .prefetch_extension_information(xfixes::X11_EXTENSION_NAME)
+            .unwrap();
 
         let xinput_version = xcb_connection
             .xinput_xi_query_version(2, 0)
@@ -233,6 +239,23 @@ impl X11Client {
         let gtk_frame_extents_supported =
             check_gtk_frame_extents_supported(&xcb_connection, &atoms, root);
         let client_side_decorations_supported = compositor_present && gtk_frame_extents_supported;
+
+        // Have the server tell us whenever the clipboard gets a new owner, so that clipboard
+        // changes don't have to be polled for.
+        let xfixes_supported = xcb_connection
+            .xfixes_query_version(5, 0)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some()
+            && xcb_connection
+                .xfixes_select_selection_input(
+                    root,
+                    atoms.CLIPBOARD,
+                    xfixes::SelectionEventMask::SET_SELECTION_OWNER
+                        | xfixes::SelectionEventMask::SELECTION_WINDOW_DESTROY
+                        | xfixes::SelectionEventMask::SELECTION_CLIENT_CLOSE,
+                )
+                .is_ok();
         log::info!(
             "x11: compositor present: {}, gtk_frame_extents_supported: {}",
             compositor_present,
@@ -343,6 +366,7 @@ impl X11Client {
 
             xcb_connection,
             client_side_decorations_supported,
+            xfixes_supported,
             x_root_index,
             _resource_database: resource_database,
             atoms,
@@ -591,6 +615,14 @@ impl X11Client {
                 let window = self.get_window(event.window)?;
                 window.property_notify(event);
             }
+            Event::XfixesSelectionNotify(_) => {
+                let mut state = self.0.borrow_mut();
+                if let Some(mut callback) = state.common.callbacks.clipboard_change.take() {
+                    drop(state);
+                    callback();
+                    self.0.borrow_mut().common.callbacks.clipboard_change = Some(callback);
+                }
+            }
             Event::FocusIn(event) => {
                 let window = self.get_window(event.event)?;
                 window.set_active(true);
@@ -1218,13 +1250,26 @@ impl LinuxClient for X11Client {
 
     fn write_to_clipboard(&self, item: crate::ClipboardItem) {
         let mut state = self.0.borrow_mut();
+        // Other programs are only offered a single format, so images are only written when
+        // there's no text to go with them. Reads from this app use the cached item instead.
+        let png = item
+            .image
+            .as_ref()
+            .filter(|_| item.text.is_empty())
+            .and_then(|image| image.to_png().log_err());
+        let png_atom = png
+            .as_ref()
+            .and_then(|_| state.clipboard.setter.get_atom("image/png").log_err());
+        let (target, bytes) = match png.zip(png_atom) {
+            Some((png, png_atom)) => (png_atom, png),
+            None => (
+                state.clipboard.setter.atoms.utf8_string,
+                item.text().as_bytes().to_vec(),
+            ),
+        };
         state
             .clipboard
-            .store(
-                state.clipboard.setter.atoms.clipboard,
-                state.clipboard.setter.atoms.utf8_string,
-                item.text().as_bytes(),
-            )
+            .store(state.clipboard.setter.atoms.clipboard, target, bytes)
             .ok();
         state.clipboard_item.replace(item);
     }
@@ -1239,10 +1284,7 @@ impl LinuxClient for X11Client {
                 state.clipboard.getter.atoms.property,
                 Duration::from_secs(3),
             )
-            .map(|text| crate::ClipboardItem {
-                text: String::from_utf8(text).unwrap(),
-                metadata: None,
-            })
+            .map(|text| crate::ClipboardItem::new(String::from_utf8(text).unwrap()))
             .ok()
     }
 
@@ -1262,19 +1304,31 @@ impl LinuxClient for X11Client {
         {
             return state.clipboard_item.clone();
         }
-        state
-            .clipboard
-            .load(
-                state.clipboard.getter.atoms.clipboard,
-                state.clipboard.getter.atoms.utf8_string,
-                state.clipboard.getter.atoms.property,
-                Duration::from_secs(3),
-            )
-            .map(|text| crate::ClipboardItem {
-                text: String::from_utf8(text).unwrap(),
-                metadata: None,
-            })
-            .ok()
+        let load = |target| {
+            state
+                .clipboard
+                .load(
+                    state.clipboard.getter.atoms.clipboard,
+                    target,
+                    state.clipboard.getter.atoms.property,
+                    Duration::from_secs(3),
+                )
+                .ok()
+        };
+        let getter = &state.clipboard.getter;
+        if let Some(text) = load(getter.atoms.utf8_string) {
+            let mut item = crate::ClipboardItem::new(String::from_utf8(text).unwrap());
+            item.html = getter
+                .get_atom("text/html")
+                .ok()
+                .and_then(load)
+                .and_then(|html| String::from_utf8(html).ok());
+            Some(item)
+        } else {
+            let png = load(getter.get_atom("image/png").ok()?)?;
+            let image = crate::ImageData::from_png(&png).log_err()?;
+            Some(crate::ClipboardItem::new_image(Arc::new(image)))
+        }
     }
 
     fn run(&self) {
@@ -1288,6 +1342,10 @@ impl LinuxClient for X11Client {
         event_loop.run(None, &mut self.clone(), |_| {}).log_err();
     }
 
+    fn observes_clipboard_changes(&self) -> bool {
+        self.0.borrow().xfixes_supported
+    }
+
     fn active_window(&self) -> Option<AnyWindowHandle> {
         let state = self.0.borrow();
         state.keyboard_focused_window.and_then(|focused_window| {
@@ -78,6 +78,15 @@ impl PlatformAtlas for MetalAtlas {
             Ok(Some(tile))
         }
     }
+
+    fn remove(&self, key: &AtlasKey) {
+        let mut lock = self.0.lock();
+        if let Some(tile) = lock.tiles_by_key.remove(key) {
+            lock.texture_mut(tile.texture_id)
+                .allocator
+                .deallocate(tile.tile_id.into());
+        }
+    }
 }
 
 impl MetalAtlasState {
@@ -165,6 +174,15 @@ impl MetalAtlasState {
         };
         &textures[id.index as usize]
     }
+
+    fn texture_mut(&mut self, id: AtlasTextureId) -> &mut MetalAtlasTexture {
+        let textures = match id.kind {
+            crate::AtlasTextureKind::Monochrome => &mut self.monochrome_textures,
+            crate::AtlasTextureKind::Polychrome => &mut self.polychrome_textures,
+            crate::AtlasTextureKind::Path => &mut self.path_textures,
+        };
+        &mut textures[id.index as usize]
+    }
 }
 
 struct MetalAtlasTexture {
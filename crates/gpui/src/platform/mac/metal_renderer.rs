@@ -1,8 +1,8 @@
 use super::metal_atlas::MetalAtlas;
 use crate::{
     point, size, AtlasTextureId, AtlasTextureKind, AtlasTile, Bounds, ContentMask, DevicePixels,
-    Hsla, MonochromeSprite, Path, PathId, PathVertex, PolychromeSprite, PrimitiveBatch, Quad,
-    ScaledPixels, Scene, Shadow, Size, Surface, Underline,
+    Gradient, Hsla, MonochromeSprite, Path, PathId, PathVertex, PolychromeSprite, PrimitiveBatch,
+    Quad, ScaledPixels, Scene, Shadow, Size, Surface, Underline,
 };
 use anyhow::{anyhow, Result};
 use block::ConcreteBlock;
@@ -377,6 +377,14 @@ impl MetalRenderer {
         ) else {
             return Err(anyhow!("failed to rasterize {} paths", scene.paths().len()));
         };
+        let Some(gradients_offset) =
+            Self::write_gradients(&scene.gradients, instance_buffer, &mut instance_offset)
+        else {
+            return Err(anyhow!(
+                "scene too large: {} gradients",
+                scene.gradients.len()
+            ));
+        };
 
         let render_pass_descriptor = metal::RenderPassDescriptor::new();
         let color_attachment = render_pass_descriptor
@@ -411,6 +419,7 @@ impl MetalRenderer {
                 ),
                 PrimitiveBatch::Quads(quads) => self.draw_quads(
                     quads,
+                    gradients_offset,
                     instance_buffer,
                     &mut instance_offset,
                     viewport_size,
@@ -509,9 +518,7 @@ impl MetalRenderer {
                     xy_position: vertex.xy_position - clipped_bounds.origin
                         + tile.bounds.origin.map(Into::into),
                     st_position: vertex.st_position,
-                    content_mask: ContentMask {
-                        bounds: tile.bounds.map(Into::into),
-                    },
+                    content_mask: ContentMask::new(tile.bounds.map(Into::into)),
                 }));
             tiles.insert(path.id, tile);
         }
@@ -638,9 +645,38 @@ impl MetalRenderer {
         true
     }
 
+    /// Writes the scene's gradients to the instance buffer, which quads refer to by their index,
+    /// returning the offset they were written at.
+    fn write_gradients(
+        gradients: &[Gradient],
+        instance_buffer: &mut InstanceBuffer,
+        instance_offset: &mut usize,
+    ) -> Option<usize> {
+        align_offset(instance_offset);
+        let gradients_offset = *instance_offset;
+        let gradient_bytes_len = mem::size_of_val(gradients);
+        let next_offset = gradients_offset + gradient_bytes_len;
+        if next_offset > instance_buffer.size {
+            return None;
+        }
+
+        unsafe {
+            let buffer_contents =
+                (instance_buffer.metal_buffer.contents() as *mut u8).add(gradients_offset);
+            ptr::copy_nonoverlapping(
+                gradients.as_ptr() as *const u8,
+                buffer_contents,
+                gradient_bytes_len,
+            );
+        }
+        *instance_offset = next_offset;
+        Some(gradients_offset)
+    }
+
     fn draw_quads(
         &mut self,
         quads: &[Quad],
+        gradients_offset: usize,
         instance_buffer: &mut InstanceBuffer,
         instance_offset: &mut usize,
         viewport_size: Size<DevicePixels>,
@@ -667,6 +703,11 @@ impl MetalRenderer {
             Some(&instance_buffer.metal_buffer),
             *instance_offset as u64,
         );
+        command_encoder.set_fragment_buffer(
+            QuadInputIndex::Gradients as u64,
+            Some(&instance_buffer.metal_buffer),
+            gradients_offset as u64,
+        );
 
         command_encoder.set_vertex_bytes(
             QuadInputIndex::ViewportSize as u64,
@@ -741,6 +782,8 @@ impl MetalRenderer {
                         },
                         color: path.color,
                         tile: (*tile).clone(),
+                        fill_rule: path.fill_rule as u32,
+                        content_mask: path.content_mask.clone(),
                     });
                     paths_and_tiles.next();
                     continue;
@@ -1085,6 +1128,11 @@ impl MetalRenderer {
                 Some(&instance_buffer.metal_buffer),
                 *instance_offset as u64,
             );
+            command_encoder.set_fragment_buffer(
+                SurfaceInputIndex::Surfaces as u64,
+                Some(&instance_buffer.metal_buffer),
+                *instance_offset as u64,
+            );
             command_encoder.set_vertex_bytes(
                 SurfaceInputIndex::TextureSize as u64,
                 mem::size_of_val(&texture_size) as u64,
@@ -1204,6 +1252,7 @@ enum QuadInputIndex {
     Vertices = 0,
     Quads = 1,
     ViewportSize = 2,
+    Gradients = 3,
 }
 
 #[repr(C)]
@@ -1244,6 +1293,8 @@ pub struct PathSprite {
     pub bounds: Bounds<ScaledPixels>,
     pub color: Hsla,
     pub tile: AtlasTile,
+    pub fill_rule: u32,
+    pub content_mask: ContentMask<ScaledPixels>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
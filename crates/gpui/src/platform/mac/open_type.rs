@@ -60,21 +60,45 @@ pub fn apply_features(font: &mut Font, features: &FontFeatures) {
         let new_descriptor = CTFontDescriptorCreateWithAttributes(attrs);
         CFRelease(attrs as _);
         let new_descriptor = CTFontDescriptor::wrap_under_create_rule(new_descriptor);
-        let new_font = CTFontCreateCopyWithAttributes(
-            font.native_font().as_concrete_TypeRef(),
-            0.0,
-            ptr::null(),
-            new_descriptor.as_concrete_TypeRef(),
+        copy_with_descriptor(font, &new_descriptor);
+    }
+}
+
+/// Sets the variation axes of a variable font, given as OpenType tags and their values.
+pub fn apply_variations(font: &mut Font, variations: &[(u32, f32)]) {
+    unsafe {
+        let variation = CFDictionary::from_CFType_pairs(
+            &variations
+                .iter()
+                .map(|&(tag, value)| (CFNumber::from(tag as i64), CFNumber::from(value as f64)))
+                .collect::<Vec<_>>(),
         );
-        let new_font = CTFont::wrap_under_create_rule(new_font);
-        *font = Font::from_native_font(&new_font);
+        let attrs = CFDictionary::from_CFType_pairs(&[(
+            CFString::wrap_under_get_rule(kCTFontVariationAttribute),
+            variation.as_CFType(),
+        )]);
+        let new_descriptor = CTFontDescriptorCreateWithAttributes(attrs.as_concrete_TypeRef());
+        let new_descriptor = CTFontDescriptor::wrap_under_create_rule(new_descriptor);
+        copy_with_descriptor(font, &new_descriptor);
     }
 }
 
+unsafe fn copy_with_descriptor(font: &mut Font, descriptor: &CTFontDescriptor) {
+    let new_font = CTFontCreateCopyWithAttributes(
+        font.native_font().as_concrete_TypeRef(),
+        0.0,
+        ptr::null(),
+        descriptor.as_concrete_TypeRef(),
+    );
+    let new_font = CTFont::wrap_under_create_rule(new_font);
+    *font = Font::from_native_font(&new_font);
+}
+
 #[link(name = "CoreText", kind = "framework")]
 extern "C" {
     static kCTFontOpenTypeFeatureTag: CFStringRef;
     static kCTFontOpenTypeFeatureValue: CFStringRef;
+    static kCTFontVariationAttribute: CFStringRef;
 
     fn CTFontCreateCopyWithAttributes(
         font: CTFontRef,
@@ -1,9 +1,9 @@
 use super::{events::key_to_native, BoolExt};
 use crate::{
     Action, AnyWindowHandle, BackgroundExecutor, ClipboardItem, CursorStyle, ForegroundExecutor,
-    Keymap, MacDispatcher, MacDisplay, MacTextSystem, MacWindow, Menu, MenuItem, PathPromptOptions,
-    Platform, PlatformDisplay, PlatformTextSystem, PlatformWindow, Result, SemanticVersion, Task,
-    WindowAppearance, WindowParams,
+    ImageData, Keymap, MacDispatcher, MacDisplay, MacTextSystem, MacWindow, Menu, MenuItem,
+    PathPromptOptions, Platform, PlatformDisplay, PlatformTextSystem, PlatformWindow, Result,
+    SemanticVersion, Task, WindowAppearance, WindowParams,
 };
 use anyhow::anyhow;
 use block::ConcreteBlock;
@@ -11,7 +11,7 @@ use cocoa::{
     appkit::{
         NSApplication, NSApplicationActivationPolicy::NSApplicationActivationPolicyRegular,
         NSEventModifierFlags, NSMenu, NSMenuItem, NSModalResponse, NSOpenPanel, NSPasteboard,
-        NSPasteboardTypeString, NSSavePanel, NSWindow,
+        NSPasteboardTypeHTML, NSPasteboardTypePNG, NSPasteboardTypeString, NSSavePanel, NSWindow,
     },
     base::{id, nil, selector, BOOL, YES},
     foundation::{
@@ -50,6 +50,7 @@ use std::{
     sync::Arc,
 };
 use time::UtcOffset;
+use util::ResultExt;
 
 use super::renderer;
 
@@ -847,34 +848,38 @@ impl Platform for MacPlatform {
         unsafe {
             state.pasteboard.clearContents();
 
-            let text_bytes = NSData::dataWithBytes_length_(
-                nil,
-                item.text.as_ptr() as *const c_void,
-                item.text.len() as u64,
-            );
-            state
-                .pasteboard
-                .setData_forType(text_bytes, NSPasteboardTypeString);
+            if !item.text.is_empty() || item.image.is_none() {
+                write_to_pasteboard(
+                    state.pasteboard,
+                    item.text.as_bytes(),
+                    NSPasteboardTypeString,
+                );
+            }
+
+            if let Some(html) = item.html.as_ref() {
+                write_to_pasteboard(state.pasteboard, html.as_bytes(), NSPasteboardTypeHTML);
+            }
+
+            if let Some(png) = item
+                .image
+                .as_ref()
+                .and_then(|image| image.to_png().log_err())
+            {
+                write_to_pasteboard(state.pasteboard, &png, NSPasteboardTypePNG);
+            }
 
             if let Some(metadata) = item.metadata.as_ref() {
                 let hash_bytes = ClipboardItem::text_hash(&item.text).to_be_bytes();
-                let hash_bytes = NSData::dataWithBytes_length_(
-                    nil,
-                    hash_bytes.as_ptr() as *const c_void,
-                    hash_bytes.len() as u64,
+                write_to_pasteboard(
+                    state.pasteboard,
+                    &hash_bytes,
+                    state.text_hash_pasteboard_type,
                 );
-                state
-                    .pasteboard
-                    .setData_forType(hash_bytes, state.text_hash_pasteboard_type);
-
-                let metadata_bytes = NSData::dataWithBytes_length_(
-                    nil,
-                    metadata.as_ptr() as *const c_void,
-                    metadata.len() as u64,
+                write_to_pasteboard(
+                    state.pasteboard,
+                    metadata.as_bytes(),
+                    state.metadata_pasteboard_type,
                 );
-                state
-                    .pasteboard
-                    .setData_forType(metadata_bytes, state.metadata_pasteboard_type);
             }
         }
     }
@@ -882,42 +887,66 @@ impl Platform for MacPlatform {
     fn read_from_clipboard(&self) -> Option<ClipboardItem> {
         let state = self.0.lock();
         unsafe {
-            if let Some(text_bytes) =
-                self.read_from_pasteboard(state.pasteboard, NSPasteboardTypeString)
-            {
-                let text = String::from_utf8_lossy(text_bytes).to_string();
-                let hash_bytes = self
-                    .read_from_pasteboard(state.pasteboard, state.text_hash_pasteboard_type)
-                    .and_then(|bytes| bytes.try_into().ok())
-                    .map(u64::from_be_bytes);
-                let metadata_bytes = self
-                    .read_from_pasteboard(state.pasteboard, state.metadata_pasteboard_type)
-                    .and_then(|bytes| String::from_utf8(bytes.to_vec()).ok());
-
-                if let Some((hash, metadata)) = hash_bytes.zip(metadata_bytes) {
-                    if hash == ClipboardItem::text_hash(&text) {
-                        Some(ClipboardItem {
-                            text,
-                            metadata: Some(metadata),
-                        })
-                    } else {
-                        Some(ClipboardItem {
-                            text,
-                            metadata: None,
-                        })
-                    }
-                } else {
-                    Some(ClipboardItem {
-                        text,
-                        metadata: None,
-                    })
-                }
-            } else {
-                None
+            let text = self
+                .read_from_pasteboard(state.pasteboard, NSPasteboardTypeString)
+                .map(|bytes| String::from_utf8_lossy(bytes).to_string());
+            let html = self
+                .read_from_pasteboard(state.pasteboard, NSPasteboardTypeHTML)
+                .map(|bytes| String::from_utf8_lossy(bytes).to_string());
+            let image = self
+                .read_from_pasteboard(state.pasteboard, NSPasteboardTypePNG)
+                .and_then(|bytes| ImageData::from_png(bytes).log_err())
+                .map(Arc::new);
+            if text.is_none() && image.is_none() {
+                return None;
             }
+
+            let text = text.unwrap_or_default();
+            let hash_bytes = self
+                .read_from_pasteboard(state.pasteboard, state.text_hash_pasteboard_type)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u64::from_be_bytes);
+            let metadata_bytes = self
+                .read_from_pasteboard(state.pasteboard, state.metadata_pasteboard_type)
+                .and_then(|bytes| String::from_utf8(bytes.to_vec()).ok());
+            let metadata = hash_bytes
+                .zip(metadata_bytes)
+                .filter(|(hash, _)| *hash == ClipboardItem::text_hash(&text))
+                .map(|(_, metadata)| metadata);
+
+            Some(ClipboardItem {
+                text,
+                metadata,
+                html,
+                image,
+            })
         }
     }
 
+    fn on_clipboard_change(&self, mut callback: Box<dyn FnMut()>) -> bool {
+        // The pasteboard doesn't post notifications, but its change count is cheap to check.
+        let state = self.0.lock();
+        let pasteboard = state.pasteboard;
+        let background_executor = state.background_executor.clone();
+        state
+            .foreground_executor
+            .spawn(async move {
+                let mut change_count: NSInteger = unsafe { msg_send![pasteboard, changeCount] };
+                loop {
+                    background_executor
+                        .timer(crate::CLIPBOARD_POLL_INTERVAL)
+                        .await;
+                    let new_change_count: NSInteger = unsafe { msg_send![pasteboard, changeCount] };
+                    if new_change_count != change_count {
+                        change_count = new_change_count;
+                        callback();
+                    }
+                }
+            })
+            .detach();
+        true
+    }
+
     fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>> {
         let url = url.to_string();
         let username = username.to_string();
@@ -1169,6 +1198,12 @@ unsafe fn ns_string(string: &str) -> id {
     NSString::alloc(nil).init_str(string).autorelease()
 }
 
+unsafe fn write_to_pasteboard(pasteboard: id, bytes: &[u8], kind: id) {
+    let data =
+        NSData::dataWithBytes_length_(nil, bytes.as_ptr() as *const c_void, bytes.len() as u64);
+    pasteboard.setData_forType(data, kind);
+}
+
 unsafe fn ns_url_to_path(url: id) -> Result<PathBuf> {
     let path: *mut c_char = msg_send![url, fileSystemRepresentation];
     if path.is_null() {
@@ -1211,6 +1246,7 @@ mod security {
 #[cfg(test)]
 mod tests {
     use crate::ClipboardItem;
+    use image::{Rgba, RgbaImage};
 
     use super::*;
 
@@ -1244,6 +1280,22 @@ mod tests {
             platform.read_from_clipboard(),
             Some(ClipboardItem::new(text_from_other_app.to_string()))
         );
+
+        let image = Arc::new(ImageData::new(RgbaImage::from_pixel(
+            2,
+            3,
+            Rgba([10, 20, 30, 255]),
+        )));
+        let item = ClipboardItem::new("text".to_string())
+            .with_html("<b>text</b>".to_string())
+            .with_image(image.clone())
+            .with_metadata(5);
+        platform.write_to_clipboard(item.clone());
+        assert_eq!(platform.read_from_clipboard(), Some(item));
+
+        let item = ClipboardItem::new_image(image);
+        platform.write_to_clipboard(item.clone());
+        assert_eq!(platform.read_from_clipboard(), Some(item));
     }
 
     fn build_platform() -> MacPlatform {
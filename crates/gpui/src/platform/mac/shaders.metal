@@ -16,6 +16,13 @@ float4 distance_from_clip_rect(float2 unit_vertex, Bounds_ScaledPixels bounds,
                                Bounds_ScaledPixels clip_bounds);
 float quad_sdf(float2 point, Bounds_ScaledPixels bounds,
                Corners_ScaledPixels corner_radii);
+float content_mask_alpha(float2 point, ContentMask_ScaledPixels content_mask);
+float quad_outline_position(float2 point, Bounds_ScaledPixels bounds,
+                            Corners_ScaledPixels corner_radii);
+float dash_alpha(float position, float dash_length, float dash_gap);
+float4 gradient_color(Gradient background, float2 position);
+float3 srgb_to_oklab(float3 color);
+float3 oklab_to_srgb(float3 color);
 float gaussian(float x, float sigma);
 float2 erf(float2 x);
 float blur_along_x(float x, float y, float sigma, float corner,
@@ -63,8 +70,16 @@ vertex QuadVertexOutput quad_vertex(uint unit_vertex_id [[vertex_id]],
 
 fragment float4 quad_fragment(QuadFragmentInput input [[stage_in]],
                               constant Quad *quads
-                              [[buffer(QuadInputIndex_Quads)]]) {
+                              [[buffer(QuadInputIndex_Quads)]],
+                              constant Gradient *gradients
+                              [[buffer(QuadInputIndex_Gradients)]]) {
   Quad quad = quads[input.quad_id];
+  float clip_alpha = content_mask_alpha(input.position.xy, quad.content_mask);
+  float4 background_color = input.background_color;
+  if (quad.gradient_id != 0) {
+    background_color =
+        gradient_color(gradients[quad.gradient_id - 1], input.position.xy);
+  }
 
   // Fast path when the quad is not rounded and doesn't have any border.
   if (quad.corner_radii.top_left == 0. && quad.corner_radii.bottom_left == 0. &&
@@ -72,7 +87,7 @@ fragment float4 quad_fragment(QuadFragmentInput input [[stage_in]],
       quad.corner_radii.bottom_right == 0. && quad.border_widths.top == 0. &&
       quad.border_widths.left == 0. && quad.border_widths.right == 0. &&
       quad.border_widths.bottom == 0.) {
-    return input.background_color;
+    return background_color * float4(1., 1., 1., clip_alpha);
   }
 
   float2 half_size =
@@ -120,17 +135,24 @@ fragment float4 quad_fragment(QuadFragmentInput input [[stage_in]],
 
   float4 color;
   if (border_width == 0.) {
-    color = input.background_color;
+    color = background_color;
   } else {
     float inset_distance = distance + border_width;
     // Blend the border on top of the background and then linearly interpolate
     // between the two as we slide inside the background.
-    float4 blended_border = over(input.background_color, input.border_color);
-    color = mix(blended_border, input.background_color,
+    float4 blended_border = over(background_color, input.border_color);
+    if (quad.dash_length > 0.) {
+      float position = quad_outline_position(input.position.xy, quad.bounds,
+                                             quad.corner_radii);
+      blended_border =
+          mix(background_color, blended_border,
+              dash_alpha(position, quad.dash_length, quad.dash_gap));
+    }
+    color = mix(blended_border, background_color,
                 saturate(0.5 - inset_distance));
   }
 
-  return color * float4(1., 1., 1., saturate(0.5 - distance));
+  return color * float4(1., 1., 1., saturate(0.5 - distance) * clip_alpha);
 }
 
 struct ShadowVertexOutput {
@@ -219,6 +241,7 @@ fragment float4 shadow_fragment(ShadowFragmentInput input [[stage_in]],
     y += step;
   }
 
+  alpha *= content_mask_alpha(input.position.xy, shadow.content_mask);
   return input.color * float4(1., 1., 1., alpha);
 }
 
@@ -259,6 +282,8 @@ fragment float4 underline_fragment(UnderlineFragmentInput input [[stage_in]],
                                    constant Underline *underlines
                                    [[buffer(UnderlineInputIndex_Underlines)]]) {
   Underline underline = underlines[input.underline_id];
+  float clip_alpha =
+      content_mask_alpha(input.position.xy, underline.content_mask);
   if (underline.wavy) {
     float half_thickness = underline.thickness * 0.5;
     float2 origin =
@@ -275,9 +300,9 @@ fragment float4 underline_fragment(UnderlineFragmentInput input [[stage_in]],
     float distance_from_bottom_border = distance_in_pixels + half_thickness;
     float alpha = saturate(
         0.5 - max(-distance_from_bottom_border, distance_from_top_border));
-    return input.color * float4(1., 1., 1., alpha);
+    return input.color * float4(1., 1., 1., alpha * clip_alpha);
   } else {
-    return input.color;
+    return input.color * float4(1., 1., 1., clip_alpha);
   }
 }
 
@@ -285,6 +310,7 @@ struct MonochromeSpriteVertexOutput {
   float4 position [[position]];
   float2 tile_position;
   float4 color [[flat]];
+  uint sprite_id [[flat]];
   float clip_distance [[clip_distance]][4];
 };
 
@@ -292,6 +318,7 @@ struct MonochromeSpriteFragmentInput {
   float4 position [[position]];
   float2 tile_position;
   float4 color [[flat]];
+  uint sprite_id [[flat]];
 };
 
 vertex MonochromeSpriteVertexOutput monochrome_sprite_vertex(
@@ -314,6 +341,7 @@ vertex MonochromeSpriteVertexOutput monochrome_sprite_vertex(
       device_position,
       tile_position,
       color,
+      sprite_id,
       {clip_distance.x, clip_distance.y, clip_distance.z, clip_distance.w}};
 }
 
@@ -321,12 +349,14 @@ fragment float4 monochrome_sprite_fragment(
     MonochromeSpriteFragmentInput input [[stage_in]],
     constant MonochromeSprite *sprites [[buffer(SpriteInputIndex_Sprites)]],
     texture2d<float> atlas_texture [[texture(SpriteInputIndex_AtlasTexture)]]) {
+  MonochromeSprite sprite = sprites[input.sprite_id];
   constexpr sampler atlas_texture_sampler(mag_filter::linear,
                                           min_filter::linear);
   float4 sample =
       atlas_texture.sample(atlas_texture_sampler, input.tile_position);
   float4 color = input.color;
   color.a *= sample.a;
+  color.a *= content_mask_alpha(input.position.xy, sprite.content_mask);
   return color;
 }
 
@@ -385,7 +415,8 @@ fragment float4 polychrome_sprite_fragment(
     color.g = grayscale;
     color.b = grayscale;
   }
-  color.a *= saturate(0.5 - distance);
+  color.a *= sprite.opacity * saturate(0.5 - distance);
+  color.a *= content_mask_alpha(input.position.xy, sprite.content_mask);
   return color;
 }
 
@@ -438,6 +469,8 @@ struct PathSpriteVertexOutput {
   float4 position [[position]];
   float2 tile_position;
   float4 color [[flat]];
+  uint fill_rule [[flat]];
+  uint sprite_id [[flat]];
 };
 
 vertex PathSpriteVertexOutput path_sprite_vertex(
@@ -451,13 +484,15 @@ vertex PathSpriteVertexOutput path_sprite_vertex(
 
   float2 unit_vertex = unit_vertices[unit_vertex_id];
   PathSprite sprite = sprites[sprite_id];
-  // Don't apply content mask because it was already accounted for when
-  // rasterizing the path.
+  // The rectangular part of the content mask was already accounted for when
+  // rasterizing the path. Its rounded corners are applied to the coverage of
+  // the whole path.
   float4 device_position =
       to_device_position(unit_vertex, sprite.bounds, viewport_size);
   float2 tile_position = to_tile_position(unit_vertex, sprite.tile, atlas_size);
   float4 color = hsla_to_rgba(sprite.color);
-  return PathSpriteVertexOutput{device_position, tile_position, color};
+  return PathSpriteVertexOutput{device_position, tile_position, color,
+                                sprite.fill_rule, sprite_id};
 }
 
 fragment float4 path_sprite_fragment(
@@ -469,20 +504,27 @@ fragment float4 path_sprite_fragment(
   float4 sample =
       atlas_texture.sample(atlas_texture_sampler, input.tile_position);
   float mask = 1. - abs(1. - fmod(sample.r, 2.));
+  if (input.fill_rule == PathFillRule_Union) {
+    // Overlapping triangles cover their union.
+    mask = saturate(sample.r);
+  }
+  PathSprite sprite = sprites[input.sprite_id];
   float4 color = input.color;
-  color.a *= mask;
+  color.a *= mask * content_mask_alpha(input.position.xy, sprite.content_mask);
   return color;
 }
 
 struct SurfaceVertexOutput {
   float4 position [[position]];
   float2 texture_position;
+  uint surface_id [[flat]];
   float clip_distance [[clip_distance]][4];
 };
 
 struct SurfaceFragmentInput {
   float4 position [[position]];
   float2 texture_position;
+  uint surface_id [[flat]];
 };
 
 vertex SurfaceVertexOutput surface_vertex(
@@ -505,10 +547,13 @@ vertex SurfaceVertexOutput surface_vertex(
   return SurfaceVertexOutput{
       device_position,
       texture_position,
+      surface_id,
       {clip_distance.x, clip_distance.y, clip_distance.z, clip_distance.w}};
 }
 
 fragment float4 surface_fragment(SurfaceFragmentInput input [[stage_in]],
+                                 constant SurfaceBounds *surfaces
+                                 [[buffer(SurfaceInputIndex_Surfaces)]],
                                  texture2d<float> y_texture
                                  [[texture(SurfaceInputIndex_YTexture)]],
                                  texture2d<float> cb_cr_texture
@@ -523,7 +568,10 @@ fragment float4 surface_fragment(SurfaceFragmentInput input [[stage_in]],
       y_texture.sample(texture_sampler, input.texture_position).r,
       cb_cr_texture.sample(texture_sampler, input.texture_position).rg, 1.0);
 
-  return ycbcrToRGBTransform * ycbcr;
+  SurfaceBounds surface = surfaces[input.surface_id];
+  float4 color = ycbcrToRGBTransform * ycbcr;
+  color.a *= content_mask_alpha(input.position.xy, surface.content_mask);
+  return color;
 }
 
 float4 hsla_to_rgba(Hsla hsla) {
@@ -648,6 +696,146 @@ float quad_sdf(float2 point, Bounds_ScaledPixels bounds,
   return distance;
 }
 
+// Returns the coverage of `point` by the content mask's rounded corners. The
+// rectangular part of the mask is already handled by the vertex clip distances.
+float content_mask_alpha(float2 point, ContentMask_ScaledPixels content_mask) {
+  if (content_mask.corner_radii.top_left == 0. &&
+      content_mask.corner_radii.top_right == 0. &&
+      content_mask.corner_radii.bottom_right == 0. &&
+      content_mask.corner_radii.bottom_left == 0.) {
+    return 1.;
+  }
+
+  float distance =
+      quad_sdf(point, content_mask.bounds, content_mask.corner_radii);
+  return saturate(0.5 - distance);
+}
+
+// Returns how far along the outline of a rounded quad `point` is, going
+// clockwise from the start of the top edge. Points in a corner are projected
+// onto its arc, and other points onto their nearest edge.
+float quad_outline_position(float2 point, Bounds_ScaledPixels bounds,
+                            Corners_ScaledPixels corner_radii) {
+  float2 size = float2(bounds.size.width, bounds.size.height);
+  float2 p = point - float2(bounds.origin.x, bounds.origin.y);
+  float top_left = corner_radii.top_left;
+  float top_right = corner_radii.top_right;
+  float bottom_right = corner_radii.bottom_right;
+  float bottom_left = corner_radii.bottom_left;
+
+  float top_end = size.x - top_left - top_right;
+  float right_start = top_end + M_PI_2_F * top_right;
+  float right_end = right_start + size.y - top_right - bottom_right;
+  float bottom_start = right_end + M_PI_2_F * bottom_right;
+  float bottom_end = bottom_start + size.x - bottom_right - bottom_left;
+  float left_start = bottom_end + M_PI_2_F * bottom_left;
+  float left_end = left_start + size.y - bottom_left - top_left;
+
+  if (p.x < top_left && p.y < top_left) {
+    float2 d = p - float2(top_left, top_left);
+    return left_end + top_left * atan2(-d.y, -d.x);
+  }
+  if (p.x > size.x - top_right && p.y < top_right) {
+    float2 d = p - float2(size.x - top_right, top_right);
+    return top_end + top_right * atan2(d.x, -d.y);
+  }
+  if (p.x > size.x - bottom_right && p.y > size.y - bottom_right) {
+    float2 d = p - (size - bottom_right);
+    return right_end + bottom_right * atan2(d.y, d.x);
+  }
+  if (p.x < bottom_left && p.y > size.y - bottom_left) {
+    float2 d = p - float2(bottom_left, size.y - bottom_left);
+    return bottom_end + bottom_left * atan2(-d.x, d.y);
+  }
+
+  float4 edge_distances = float4(p.y, size.x - p.x, size.y - p.y, p.x);
+  float nearest = min(min(edge_distances.x, edge_distances.y),
+                      min(edge_distances.z, edge_distances.w));
+  if (nearest == edge_distances.x) {
+    return p.x - top_left;
+  } else if (nearest == edge_distances.y) {
+    return right_start + p.y - top_right;
+  } else if (nearest == edge_distances.z) {
+    return bottom_start + size.x - bottom_right - p.x;
+  } else {
+    return left_start + size.y - bottom_left - p.y;
+  }
+}
+
+// Returns the color of a gradient background at `position`, interpolating
+// between its stops in the Oklab color space with premultiplied alpha.
+float4 gradient_color(Gradient background, float2 position) {
+  float2 start = float2(background.start.x, background.start.y);
+  float2 end = float2(background.end.x, background.end.y);
+  float2 axis = end - start;
+  float t;
+  if (background.kind == 1) {
+    t = dot(position - start, axis) / max(dot(axis, axis), 0.0001);
+  } else {
+    t = length(position - start) / max(length(axis), 0.0001);
+  }
+
+  uint end_ix = 0;
+  while (end_ix < background.stop_count && background.positions[end_ix] < t) {
+    end_ix += 1;
+  }
+  if (end_ix == 0) {
+    return hsla_to_rgba(background.colors[0]);
+  }
+  if (end_ix == background.stop_count) {
+    return hsla_to_rgba(background.colors[background.stop_count - 1]);
+  }
+
+  uint start_ix = end_ix - 1;
+  float span = background.positions[end_ix] - background.positions[start_ix];
+  float progress =
+      span > 0. ? (t - background.positions[start_ix]) / span : 1.;
+  float4 start_color = hsla_to_rgba(background.colors[start_ix]);
+  float4 end_color = hsla_to_rgba(background.colors[end_ix]);
+  float4 mixed =
+      mix(float4(srgb_to_oklab(start_color.rgb) * start_color.a, start_color.a),
+          float4(srgb_to_oklab(end_color.rgb) * end_color.a, end_color.a),
+          progress);
+  if (mixed.a <= 0.) {
+    return float4(0.);
+  }
+  return float4(oklab_to_srgb(mixed.rgb / mixed.a), mixed.a);
+}
+
+float3 srgb_to_oklab(float3 color) {
+  float3 linear_color = select(pow((color + 0.055) / 1.055, 2.4), color / 12.92,
+                               color <= 0.04045);
+  float3 lms = float3(dot(float3(0.4122215, 0.5363325, 0.0514460), linear_color),
+                      dot(float3(0.2119035, 0.6806995, 0.1073970), linear_color),
+                      dot(float3(0.0883025, 0.2817188, 0.6299787), linear_color));
+  lms = pow(lms, 1. / 3.);
+  return float3(dot(float3(0.2104543, 0.7936178, -0.0040720), lms),
+                dot(float3(1.9779985, -2.4285922, 0.4505937), lms),
+                dot(float3(0.0259040, 0.7827718, -0.8086758), lms));
+}
+
+float3 oklab_to_srgb(float3 color) {
+  float3 lms = float3(dot(float3(1., 0.3963378, 0.2158038), color),
+                      dot(float3(1., -0.1055613, -0.0638542), color),
+                      dot(float3(1., -0.0894842, -1.2914855), color));
+  lms = lms * lms * lms;
+  float3 linear_color =
+      saturate(float3(dot(float3(4.0767417, -3.3077116, 0.2309699), lms),
+                      dot(float3(-1.2684380, 2.6097574, -0.3413194), lms),
+                      dot(float3(-0.0041961, -0.7034186, 1.7076147), lms)));
+  return select(1.055 * pow(linear_color, 1. / 2.4) - 0.055,
+                linear_color * 12.92, linear_color <= 0.0031308);
+}
+
+// Returns the coverage of a dashed line at `position` along it, antialiasing
+// both ends of each dash.
+float dash_alpha(float position, float dash_length, float dash_gap) {
+  float period = dash_length + dash_gap;
+  float offset = position - floor(position / period) * period;
+  return max(saturate(min(offset, dash_length - offset) + 0.5),
+             saturate(offset - period + 0.5));
+}
+
 // A standard gaussian function, used for weighting samples
 float gaussian(float x, float sigma) {
   return exp(-(x * x) / (2. * sigma * sigma)) / (sqrt(2. * M_PI_F) * sigma);
@@ -1,11 +1,12 @@
 use crate::{
-    point, px, size, Bounds, DevicePixels, Font, FontFeatures, FontId, FontMetrics, FontRun,
-    FontStyle, FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem, Point,
-    RenderGlyphParams, Result, ShapedGlyph, ShapedRun, SharedString, Size, SUBPIXEL_VARIANTS,
+    has_color_layers, point, px, size, Bounds, DevicePixels, Font, FontAxes, FontFeatures, FontId,
+    FontMetrics, FontRun, FontStyle, FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem,
+    Point, RenderGlyphParams, Result, ShapedGlyph, ShapedRun, SharedString, Size,
+    SUBPIXEL_VARIANTS,
 };
 use anyhow::anyhow;
 use cocoa::appkit::{CGFloat, CGPoint};
-use collections::{BTreeSet, HashMap};
+use collections::{BTreeSet, HashMap, HashSet};
 use core_foundation::{
     attributed_string::CFMutableAttributedString,
     base::{CFRange, TCFType},
@@ -16,11 +17,13 @@ use core_graphics::{
     base::{kCGImageAlphaPremultipliedLast, CGGlyph},
     color_space::CGColorSpace,
     context::CGContext,
+    geometry::CG_AFFINE_TRANSFORM_IDENTITY,
 };
 use core_text::{
     font::CTFont,
     font_descriptor::{
-        kCTFontSlantTrait, kCTFontSymbolicTrait, kCTFontWeightTrait, kCTFontWidthTrait,
+        kCTFontColorGlyphsTrait, kCTFontSlantTrait, kCTFontSymbolicTrait, kCTFontWeightTrait,
+        kCTFontWidthTrait,
     },
     line::CTLine,
     string_attributes::kCTFontAttributeName,
@@ -30,7 +33,7 @@ use font_kit::{
     handle::Handle,
     hinting::HintingOptions,
     metrics::Metrics,
-    properties::{Style as FontkitStyle, Weight as FontkitWeight},
+    properties::{Stretch as FontkitStretch, Style as FontkitStyle, Weight as FontkitWeight},
     source::SystemSource,
     sources::mem::MemSource,
 };
@@ -54,6 +57,7 @@ pub(crate) struct MacTextSystem(RwLock<MacTextSystemState>);
 struct FontKey {
     font_family: SharedString,
     font_features: FontFeatures,
+    font_axes: FontAxes,
 }
 
 struct MacTextSystemState {
@@ -64,6 +68,11 @@ struct MacTextSystemState {
     font_ids_by_postscript_name: HashMap<String, FontId>,
     font_ids_by_font_key: HashMap<FontKey, SmallVec<[FontId; 4]>>,
     postscript_names_by_font_id: HashMap<FontId, String>,
+    /// Fonts with color glyphs, like emoji fonts with sbix or COLR tables, which may also have
+    /// glyphs drawn as outlines.
+    color_font_ids: HashSet<FontId>,
+    /// The `COLR` tables of color fonts, which list their glyphs with color layers.
+    colr_tables: HashMap<FontId, Box<[u8]>>,
 }
 
 impl MacTextSystem {
@@ -76,6 +85,8 @@ impl MacTextSystem {
             font_ids_by_postscript_name: HashMap::default(),
             font_ids_by_font_key: HashMap::default(),
             postscript_names_by_font_id: HashMap::default(),
+            color_font_ids: HashSet::default(),
+            colr_tables: HashMap::default(),
         }))
     }
 }
@@ -123,11 +134,12 @@ impl PlatformTextSystem for MacTextSystem {
             let font_key = FontKey {
                 font_family: font.family.clone(),
                 font_features: font.features.clone(),
+                font_axes: font.axes.clone(),
             };
             let candidates = if let Some(font_ids) = lock.font_ids_by_font_key.get(&font_key) {
                 font_ids.as_slice()
             } else {
-                let font_ids = lock.load_family(&font.family, &font.features)?;
+                let font_ids = lock.load_family(&font.family, &font.features, &font.axes)?;
                 lock.font_ids_by_font_key.insert(font_key.clone(), font_ids);
                 lock.font_ids_by_font_key[&font_key].as_ref()
             };
@@ -140,9 +152,12 @@ impl PlatformTextSystem for MacTextSystem {
             let ix = font_kit::matching::find_best_match(
                 &candidate_properties,
                 &font_kit::properties::Properties {
-                    style: font.style.into(),
-                    weight: font.weight.into(),
-                    stretch: Default::default(),
+                    style: font.matching_style().into(),
+                    weight: font.matching_weight().into(),
+                    stretch: font
+                        .axes
+                        .get("wdth")
+                        .map_or_else(Default::default, |width| FontkitStretch(width / 100.)),
                 },
             )?;
 
@@ -212,6 +227,7 @@ impl MacTextSystemState {
         &mut self,
         name: &str,
         features: &FontFeatures,
+        axes: &FontAxes,
     ) -> Result<SmallVec<[FontId; 4]>> {
         let name = if name == ".SystemUIFont" {
             ".AppleSystemUIFont"
@@ -228,6 +244,18 @@ impl MacTextSystemState {
             let mut font = font.load()?;
 
             open_type::apply_features(&mut font, features);
+            if !axes.is_empty() {
+                // Static fonts are left as they are, to be matched by the axes' weight and style.
+                let variations =
+                    font.load_font_table(u32::from_be_bytes(*b"fvar"))
+                        .and_then(|fvar| {
+                            let name = font.load_font_table(u32::from_be_bytes(*b"name"));
+                            axes.resolve(&fvar, name.as_deref())
+                        });
+                if let Some(variations) = variations.filter(|variations| !variations.is_empty()) {
+                    open_type::apply_variations(&mut font, &variations);
+                }
+            }
 
             // This block contains a precautionary fix to guard against loading fonts
             // that might cause panics due to `.unwrap()`s up the chain.
@@ -288,10 +316,14 @@ impl MacTextSystemState {
             let font_id = FontId(self.fonts.len());
             font_ids.push(font_id);
             let postscript_name = font.postscript_name().unwrap();
+            // Fonts with other features or axes share their postscript name, which keeps
+            // referring to the first of them.
             self.font_ids_by_postscript_name
-                .insert(postscript_name.clone(), font_id);
+                .entry(postscript_name.clone())
+                .or_insert(font_id);
             self.postscript_names_by_font_id
                 .insert(font_id, postscript_name);
+            self.record_color_glyphs(font_id, &font);
             self.fonts.push(font);
         }
         Ok(font_ids)
@@ -315,20 +347,37 @@ impl MacTextSystemState {
                 .insert(postscript_name.clone(), font_id);
             self.postscript_names_by_font_id
                 .insert(font_id, postscript_name);
-            self.fonts
-                .push(font_kit::font::Font::from_core_graphics_font(
-                    requested_font.copy_to_CGFont(),
-                ));
+            let font =
+                font_kit::font::Font::from_core_graphics_font(requested_font.copy_to_CGFont());
+            self.record_color_glyphs(font_id, &font);
+            self.fonts.push(font);
             font_id
         }
     }
 
-    fn is_emoji(&self, font_id: FontId) -> bool {
-        self.postscript_names_by_font_id
-            .get(&font_id)
-            .map_or(false, |postscript_name| {
-                postscript_name == "AppleColorEmoji" || postscript_name == ".AppleColorEmojiUI"
-            })
+    fn record_color_glyphs(&mut self, font_id: FontId, font: &FontKitFont) {
+        if has_color_glyphs(font) {
+            self.color_font_ids.insert(font_id);
+            if let Some(colr) = font.load_font_table(u32::from_be_bytes(*b"COLR")) {
+                self.colr_tables.insert(font_id, colr);
+            }
+        }
+    }
+
+    /// Whether the glyph is drawn with color layers or a color bitmap, which is rasterized as an
+    /// image rather than a mask.
+    fn is_color_glyph(&self, font_id: FontId, glyph_id: CGGlyph) -> bool {
+        if !self.color_font_ids.contains(&font_id) {
+            return false;
+        }
+        if let Some(colr) = self.colr_tables.get(&font_id) {
+            return has_color_layers(colr, glyph_id);
+        }
+        // Glyphs drawn from sbix bitmaps have no outline.
+        self.fonts[font_id.0]
+            .native_font()
+            .create_path_for_glyph(glyph_id, &CG_AFFINE_TRANSFORM_IDENTITY)
+            .is_err()
     }
 
     fn raster_bounds(&self, params: &RenderGlyphParams) -> Result<Bounds<DevicePixels>> {
@@ -483,7 +532,22 @@ impl MacTextSystemState {
                     .downcast::<CTFont>()
                     .unwrap()
             };
-            let font_id = self.id_for_native_font(font);
+            // Glyph runs only report the postscript name of their font, which is shared by all
+            // the features and axes it was loaded with, so the requested font is preferred.
+            let requested_font_id = run.string_indices().first().and_then(|&utf16_ix| {
+                let mut ix_converter = StringIndexConverter::new(text);
+                ix_converter.advance_to_utf16_ix(usize::try_from(utf16_ix).ok()?);
+                font_id_at(font_runs, ix_converter.utf8_ix)
+            });
+            let font_id = match requested_font_id {
+                Some(font_id)
+                    if self.postscript_names_by_font_id.get(&font_id)
+                        == Some(&font.postscript_name()) =>
+                {
+                    font_id
+                }
+                _ => self.id_for_native_font(font),
+            };
 
             let mut ix_converter = StringIndexConverter::new(text);
             let mut glyphs = SmallVec::new();
@@ -499,7 +563,7 @@ impl MacTextSystemState {
                     id: GlyphId(*glyph_id as u32),
                     position: point(position.x as f32, position.y as f32).map(px),
                     index: ix_converter.utf8_ix,
-                    is_emoji: self.is_emoji(font_id),
+                    is_emoji: self.is_color_glyph(font_id, *glyph_id),
                 });
             }
 
@@ -514,10 +578,30 @@ impl MacTextSystemState {
             ascent: typographic_bounds.ascent.into(),
             descent: typographic_bounds.descent.into(),
             len: text.len(),
+            bidi_runs: Vec::new(),
+            visual_cells: Default::default(),
         }
     }
 }
 
+/// Whether the font draws any of its glyphs in color, which are rasterized as images rather than
+/// masks.
+fn has_color_glyphs(font: &FontKitFont) -> bool {
+    font.native_font().symbolic_traits() & kCTFontColorGlyphsTrait != 0
+}
+
+/// Returns the font of the run containing the given UTF-8 offset.
+fn font_id_at(font_runs: &[FontRun], utf8_ix: usize) -> Option<FontId> {
+    let mut run_end = 0;
+    font_runs
+        .iter()
+        .find(|run| {
+            run_end += run.len;
+            utf8_ix < run_end
+        })
+        .map(|run| run.font_id)
+}
+
 #[derive(Clone)]
 struct StringIndexConverter<'a> {
     text: &'a str,
@@ -675,7 +759,7 @@ mod lenient_font_attributes {
 
 #[cfg(test)]
 mod tests {
-    use crate::{font, px, FontRun, GlyphId, MacTextSystem, PlatformTextSystem};
+    use crate::{font, px, FontAxes, FontRun, GlyphId, MacTextSystem, PlatformTextSystem};
 
     #[test]
     fn test_layout_line_bom_char() {
@@ -701,4 +785,31 @@ mod tests {
                                                                  // There's no glyph for \u{feff}
         assert_eq!(layout.runs[0].glyphs[1].id, GlyphId(69u32)); // b
     }
+
+    #[test]
+    fn test_layout_line_with_axes() {
+        let fonts = MacTextSystem::new();
+        let line = "mmmm";
+        let layout_with_weight = |weight: f32| {
+            let mut font = font(".SystemUIFont");
+            font.axes = FontAxes::new([("wght", weight)]);
+            let font_id = fonts.font_id(&font).unwrap();
+            let layout = fonts.layout_line(
+                line,
+                px(16.),
+                &[FontRun {
+                    font_id,
+                    len: line.len(),
+                }],
+            );
+            assert_eq!(layout.runs[0].font_id, font_id);
+            layout
+        };
+
+        // The system font is variable, so each weight gets its own instance.
+        let light = layout_with_weight(300.);
+        let heavy = layout_with_weight(800.);
+        assert_ne!(light.runs[0].font_id, heavy.runs[0].font_id);
+        assert!(heavy.width > light.width);
+    }
 }
@@ -23,6 +23,7 @@ pub(crate) struct TestPlatform {
     active_display: Rc<dyn PlatformDisplay>,
     active_cursor: Mutex<CursorStyle>,
     current_clipboard_item: Mutex<Option<ClipboardItem>>,
+    clipboard_change_callback: RefCell<Option<Box<dyn FnMut()>>>,
     #[cfg(target_os = "linux")]
     current_primary_item: Mutex<Option<ClipboardItem>>,
     pub(crate) prompts: RefCell<TestPrompts>,
@@ -62,6 +63,7 @@ impl TestPlatform {
             active_display: Rc::new(TestDisplay::new()),
             active_window: Default::default(),
             current_clipboard_item: Mutex::new(None),
+            clipboard_change_callback: RefCell::new(None),
             #[cfg(target_os = "linux")]
             current_primary_item: Mutex::new(None),
             weak: weak.clone(),
@@ -280,6 +282,9 @@ impl Platform for TestPlatform {
 
     fn write_to_clipboard(&self, item: ClipboardItem) {
         *self.current_clipboard_item.lock() = Some(item);
+        if let Some(callback) = self.clipboard_change_callback.borrow_mut().as_mut() {
+            callback();
+        }
     }
 
     #[cfg(target_os = "linux")]
@@ -291,6 +296,11 @@ impl Platform for TestPlatform {
         self.current_clipboard_item.lock().clone()
     }
 
+    fn on_clipboard_change(&self, callback: Box<dyn FnMut()>) -> bool {
+        *self.clipboard_change_callback.borrow_mut() = Some(callback);
+        true
+    }
+
     fn write_credentials(&self, _url: &str, _username: &str, _password: &[u8]) -> Task<Result<()>> {
         Task::ready(Ok(()))
     }
@@ -307,3 +317,45 @@ impl Platform for TestPlatform {
         unimplemented!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{ClipboardItem, ImageData, TestAppContext};
+    use image::{Rgba, RgbaImage};
+    use std::sync::Arc;
+
+    // For compatibility with the test macro
+    use crate as gpui;
+
+    #[gpui::test]
+    fn test_clipboard_formats(cx: &mut TestAppContext) {
+        let image = Arc::new(ImageData::new(RgbaImage::from_pixel(
+            2,
+            3,
+            Rgba([10, 20, 30, 255]),
+        )));
+        let item = ClipboardItem::new("**bold**".to_string())
+            .with_html("<b>bold</b>".to_string())
+            .with_image(image.clone())
+            .with_metadata(7);
+        cx.write_to_clipboard(item.clone());
+        let read = cx.read_from_clipboard().unwrap();
+        assert_eq!(read, item);
+        assert_eq!(read.text(), "**bold**");
+        assert_eq!(read.html(), Some("<b>bold</b>"));
+        assert_eq!(read.image(), Some(&image));
+        assert_eq!(read.metadata::<i32>(), Some(7));
+
+        let item = ClipboardItem::new_image(image.clone());
+        cx.write_to_clipboard(item.clone());
+        let read = cx.read_from_clipboard().unwrap();
+        assert_eq!(read.text(), "");
+        assert_eq!(read.html(), None);
+        assert_eq!(read.image(), Some(&image));
+
+        // Images are written to platform clipboards as PNGs, and compare equal once read back.
+        let decoded = ImageData::from_png(&image.to_png().unwrap()).unwrap();
+        assert_eq!(decoded, *image);
+        assert_ne!(decoded.id, image.id);
+    }
+}
@@ -30,6 +30,8 @@ struct FontInfo {
     font_family: String,
     font_face: IDWriteFontFace3,
     features: IDWriteTypography,
+    /// The values applied to the axes of a variable font, empty for static fonts.
+    axis_values: Vec<DWRITE_FONT_AXIS_VALUE>,
     is_system_font: bool,
     is_emoji: bool,
 }
@@ -71,6 +73,8 @@ struct FontIdentifier {
     postscript_name: String,
     weight: i32,
     style: i32,
+    /// The tags and value bits of a variable font instance's axes, to tell its instances apart.
+    axis_values: Vec<(u32, u32)>,
 }
 
 impl DirectWriteComponent {
@@ -296,6 +300,7 @@ impl DirectWriteState {
         font_weight: FontWeight,
         font_style: FontStyle,
         font_features: &FontFeatures,
+        font_axes: &FontAxes,
         is_system_font: bool,
     ) -> Option<FontId> {
         let collection = if is_system_font {
@@ -320,6 +325,16 @@ impl DirectWriteState {
             let Some(font_face) = font_face_ref.CreateFontFace().log_err() else {
                 continue;
             };
+            let (font_face, axis_values) = if font_axes.is_empty() {
+                (font_face, Vec::new())
+            } else {
+                // Static fonts are left as they are, to be matched by the axes' weight and style.
+                match create_font_instance(&font_face, font_axes).log_err() {
+                    Some(Some(instance)) => instance,
+                    Some(None) => (font_face, Vec::new()),
+                    None => continue,
+                }
+            };
             let Some(identifier) = get_font_identifier(&font_face, &self.components.locale) else {
                 continue;
             };
@@ -333,6 +348,7 @@ impl DirectWriteState {
                 font_face,
                 is_system_font,
                 features: direct_write_features,
+                axis_values,
                 is_emoji,
             };
             let font_id = FontId(self.fonts.len());
@@ -362,26 +378,29 @@ impl DirectWriteState {
                 let family = self.system_ui_font_name.clone();
                 self.find_font_id(
                     family.as_ref(),
-                    target_font.weight,
-                    target_font.style,
+                    target_font.matching_weight(),
+                    target_font.matching_style(),
                     &target_font.features,
+                    &target_font.axes,
                 )
                 .unwrap()
             } else {
                 self.find_font_id(
                     target_font.family.as_ref(),
-                    target_font.weight,
-                    target_font.style,
+                    target_font.matching_weight(),
+                    target_font.matching_style(),
                     &target_font.features,
+                    &target_font.axes,
                 )
                 .unwrap_or_else(|| {
                     let family = self.system_ui_font_name.clone();
                     log::error!("{} not found, use {} instead.", target_font.family, family);
                     self.get_font_id_from_font_collection(
                         family.as_ref(),
-                        target_font.weight,
-                        target_font.style,
+                        target_font.matching_weight(),
+                        target_font.matching_style(),
                         &target_font.features,
+                        &target_font.axes,
                         true,
                     )
                     .unwrap()
@@ -396,15 +415,30 @@ impl DirectWriteState {
         weight: FontWeight,
         style: FontStyle,
         features: &FontFeatures,
+        axes: &FontAxes,
     ) -> Option<FontId> {
         // try to find target font in custom font collection first
-        self.get_font_id_from_font_collection(family_name, weight, style, features, false)
+        self.get_font_id_from_font_collection(family_name, weight, style, features, axes, false)
             .or_else(|| {
-                self.get_font_id_from_font_collection(family_name, weight, style, features, true)
+                self.get_font_id_from_font_collection(
+                    family_name,
+                    weight,
+                    style,
+                    features,
+                    axes,
+                    true,
+                )
             })
             .or_else(|| {
                 self.update_system_font_collection();
-                self.get_font_id_from_font_collection(family_name, weight, style, features, true)
+                self.get_font_id_from_font_collection(
+                    family_name,
+                    weight,
+                    style,
+                    features,
+                    axes,
+                    true,
+                )
             })
     }
 
@@ -458,6 +492,7 @@ impl DirectWriteState {
                     length: current_text_utf16_length,
                 };
                 layout.SetTypography(&font_info.features, text_range)?;
+                apply_font_axis_values(&layout, &font_info.axis_values, text_range)?;
                 utf16_offset += current_text_utf16_length;
 
                 layout
@@ -498,6 +533,7 @@ impl DirectWriteState {
                 text_layout.SetFontStyle(font_info.font_face.GetStyle(), text_range)?;
                 text_layout.SetFontWeight(font_info.font_face.GetWeight(), text_range)?;
                 text_layout.SetTypography(&font_info.features, text_range)?;
+                apply_font_axis_values(&text_layout, &font_info.axis_values, text_range)?;
             }
 
             let mut runs = Vec::new();
@@ -523,6 +559,8 @@ impl DirectWriteState {
                 descent,
                 runs,
                 len: text.len(),
+                bidi_runs: Vec::new(),
+                visual_cells: Default::default(),
             })
         }
     }
@@ -983,7 +1021,7 @@ impl IDWriteTextRenderer_Impl for TextRenderer {
             // This `cast()` action here should never fail since we are running on Win10+, and
             // `IDWriteFontFace3` requires Win10
             let font_face = &font_face.cast::<IDWriteFontFace3>().unwrap();
-            let Some((font_identifier, font_struct, is_emoji)) =
+            let Some((font_identifier, font_struct, is_color_font)) =
                 get_font_identifier_and_font_struct(font_face, &self.locale)
             else {
                 return Ok(());
@@ -998,21 +1036,39 @@ impl IDWriteTextRenderer_Impl for TextRenderer {
             } else {
                 context.text_system.select_font(&font_struct)
             };
+            // The glyphs of right-to-left runs are in logical order, and are laid out leftwards
+            // from the end of the run.
+            let is_rtl = glyphrun.bidiLevel % 2 == 1;
+            let run_width = (0..glyph_count)
+                .map(|index| *glyphrun.glyphAdvances.add(index))
+                .sum::<f32>();
             let mut glyphs = SmallVec::new();
+            let mut offset = 0.;
             for index in 0..glyph_count {
-                let id = GlyphId(*glyphrun.glyphIndices.add(index) as u32);
+                let glyph_index = *glyphrun.glyphIndices.add(index);
+                let id = GlyphId(glyph_index as u32);
+                // A color font can still contain plain outline glyphs, which must be tinted
+                // with the text color like any other glyph.
+                let is_emoji = is_color_font && is_color_glyph(font_face, glyph_index);
+                let advance = *glyphrun.glyphAdvances.add(index);
                 context
                     .index_converter
                     .advance_to_utf16_ix(context.utf16_index);
+                let glyph_x = if is_rtl {
+                    context.width + run_width - offset - advance
+                } else {
+                    context.width + offset
+                };
                 glyphs.push(ShapedGlyph {
                     id,
-                    position: point(px(context.width), px(0.0)),
+                    position: point(px(glyph_x), px(0.0)),
                     index: context.index_converter.utf8_ix,
                     is_emoji,
                 });
                 context.utf16_index += utf16_length_per_glyph;
-                context.width += *glyphrun.glyphAdvances.add(index);
+                offset += advance;
             }
+            context.width += run_width;
             context.runs.push(ShapedRun { font_id, glyphs });
         }
         Ok(())
@@ -1171,10 +1227,12 @@ fn get_font_identifier_and_font_struct(
         postscript_name,
         weight: weight.0,
         style: style.0,
+        axis_values: get_font_axis_values(font_face),
     };
     let font_struct = Font {
         family: family_name.into(),
         features: FontFeatures::default(),
+        axes: FontAxes::default(),
         weight: weight.into(),
         style: style.into(),
     };
@@ -1182,6 +1240,22 @@ fn get_font_identifier_and_font_struct(
     Some((identifier, font_struct, is_emoji))
 }
 
+/// Whether the glyph is drawn from color layers or a bitmap rather than from its outline.
+fn is_color_glyph(font_face: &IDWriteFontFace3, glyph_index: u16) -> bool {
+    let Ok(font_face) = font_face.cast::<IDWriteFontFace4>() else {
+        return false;
+    };
+    let Ok(formats) = (unsafe { font_face.GetGlyphImageFormats(glyph_index, 0, u32::MAX) }) else {
+        return false;
+    };
+    let color_formats = DWRITE_GLYPH_IMAGE_FORMATS_COLR
+        | DWRITE_GLYPH_IMAGE_FORMATS_SVG
+        | DWRITE_GLYPH_IMAGE_FORMATS_PNG
+        | DWRITE_GLYPH_IMAGE_FORMATS_JPEG
+        | DWRITE_GLYPH_IMAGE_FORMATS_PREMULTIPLIED_B8G8R8A8;
+    (formats & color_formats).0 != 0
+}
+
 #[inline]
 fn get_font_identifier(font_face: &IDWriteFontFace3, locale: &str) -> Option<FontIdentifier> {
     let weight = unsafe { font_face.GetWeight().0 };
@@ -1192,9 +1266,102 @@ fn get_font_identifier(font_face: &IDWriteFontFace3, locale: &str) -> Option<Fon
             postscript_name,
             weight,
             style,
+            axis_values: get_font_axis_values(font_face),
         })
 }
 
+/// Returns the axis values of a variable font instance, or nothing for static fonts.
+fn get_font_axis_values(font_face: &IDWriteFontFace3) -> Vec<(u32, u32)> {
+    let Ok(font_face) = font_face.cast::<IDWriteFontFace5>() else {
+        return Vec::new();
+    };
+    unsafe {
+        if !font_face.HasVariations().as_bool() {
+            return Vec::new();
+        }
+        let mut values =
+            vec![DWRITE_FONT_AXIS_VALUE::default(); font_face.GetFontAxisValueCount() as usize];
+        if font_face.GetFontAxisValues(&mut values).log_err().is_none() {
+            return Vec::new();
+        }
+        values
+            .iter()
+            .map(|value| (value.axisTag.0, value.value.to_bits()))
+            .collect()
+    }
+}
+
+/// Creates the instance of a variable font with the given axes applied, along with the values
+/// applied to them, or returns `None` if the font isn't variable.
+fn create_font_instance(
+    font_face: &IDWriteFontFace3,
+    axes: &FontAxes,
+) -> Result<Option<(IDWriteFontFace3, Vec<DWRITE_FONT_AXIS_VALUE>)>> {
+    let Some(fvar) = get_font_table(font_face, *b"fvar")? else {
+        return Ok(None);
+    };
+    let name = get_font_table(font_face, *b"name")?;
+    let Some(variations) = axes.resolve(&fvar, name.as_deref()) else {
+        return Ok(None);
+    };
+    if variations.is_empty() {
+        return Ok(None);
+    }
+    let axis_values = variations
+        .into_iter()
+        .map(|(tag, value)| DWRITE_FONT_AXIS_VALUE {
+            // DirectWrite tags are in little-endian byte order.
+            axisTag: DWRITE_FONT_AXIS_TAG(tag.swap_bytes()),
+            value,
+        })
+        .collect::<Vec<_>>();
+    let instance = unsafe {
+        font_face
+            .cast::<IDWriteFontFace5>()?
+            .GetFontResource()?
+            .CreateFontFace(font_face.GetSimulations(), &axis_values)?
+    };
+    Ok(Some((instance.cast()?, axis_values)))
+}
+
+/// Copies one of the font's OpenType tables, if it has it.
+fn get_font_table(font_face: &IDWriteFontFace3, tag: [u8; 4]) -> Result<Option<Vec<u8>>> {
+    let mut data = std::ptr::null_mut();
+    let mut size = 0;
+    let mut context = std::ptr::null_mut();
+    let mut exists = BOOL(0);
+    unsafe {
+        font_face.TryGetFontTable(
+            u32::from_le_bytes(tag),
+            &mut data,
+            &mut size,
+            &mut context,
+            &mut exists,
+        )?;
+        if !exists.as_bool() {
+            return Ok(None);
+        }
+        let table = std::slice::from_raw_parts(data as *const u8, size as usize).to_vec();
+        font_face.ReleaseFontTable(context);
+        Ok(Some(table))
+    }
+}
+
+/// Applies the axis values of a variable font instance to a range of a text layout, which
+/// otherwise lays the range out with the font's default instance.
+unsafe fn apply_font_axis_values(
+    text_layout: &IDWriteTextLayout,
+    axis_values: &[DWRITE_FONT_AXIS_VALUE],
+    text_range: DWRITE_TEXT_RANGE,
+) -> Result<()> {
+    if !axis_values.is_empty() {
+        text_layout
+            .cast::<IDWriteTextLayout4>()?
+            .SetFontAxisValues(axis_values, text_range)?;
+    }
+    Ok(())
+}
+
 #[inline]
 fn get_postscript_name(font_face: &IDWriteFontFace3, locale: &str) -> Result<String> {
     let mut info = None;
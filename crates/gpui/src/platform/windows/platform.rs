@@ -12,7 +12,7 @@ use std::{
 
 use ::util::ResultExt;
 use anyhow::{anyhow, Context, Result};
-use clipboard_win::{get_clipboard_string, set_clipboard_string};
+use clipboard_win::{get_clipboard_string, Clipboard};
 use futures::channel::oneshot::{self, Receiver};
 use itertools::Itertools;
 use parking_lot::RwLock;
@@ -24,7 +24,10 @@ use windows::{
         Foundation::*,
         Graphics::Gdi::*,
         Security::Credentials::*,
-        System::{Com::*, LibraryLoader::*, Ole::*, SystemInformation::*, Threading::*, Time::*},
+        System::{
+            Com::*, DataExchange::*, LibraryLoader::*, Ole::*, SystemInformation::*, Threading::*,
+            Time::*,
+        },
         UI::{Input::KeyboardAndMouse::*, Shell::*, WindowsAndMessaging::*},
     },
     UI::ViewManagement::UISettings,
@@ -498,17 +501,41 @@ impl Platform for WindowsPlatform {
     }
 
     fn write_to_clipboard(&self, item: ClipboardItem) {
-        if item.text.len() > 0 {
-            set_clipboard_string(item.text()).unwrap();
-        }
+        write_to_clipboard(&item).log_err();
     }
 
     fn read_from_clipboard(&self) -> Option<ClipboardItem> {
-        let text = get_clipboard_string().ok()?;
-        Some(ClipboardItem {
-            text,
-            metadata: None,
-        })
+        if let Ok(text) = get_clipboard_string() {
+            let mut item = ClipboardItem::new(text);
+            item.html = read_clipboard_format(CLIPBOARD_HTML_FORMAT)
+                .and_then(|data| String::from_utf8(data).ok())
+                .and_then(|data| html_from_clipboard_html(&data));
+            Some(item)
+        } else {
+            let png = read_clipboard_format(CLIPBOARD_PNG_FORMAT)?;
+            let image = ImageData::from_png(&png).log_err()?;
+            Some(ClipboardItem::new_image(Arc::new(image)))
+        }
+    }
+
+    fn on_clipboard_change(&self, mut callback: Box<dyn FnMut()>) -> bool {
+        // The sequence number changes whenever the clipboard's contents do, and is cheaper to
+        // check than listening for clipboard updates with a window.
+        let background_executor = self.background_executor.clone();
+        self.foreground_executor
+            .spawn(async move {
+                let mut sequence_number = unsafe { GetClipboardSequenceNumber() };
+                loop {
+                    background_executor.timer(CLIPBOARD_POLL_INTERVAL).await;
+                    let new_sequence_number = unsafe { GetClipboardSequenceNumber() };
+                    if new_sequence_number != sequence_number {
+                        sequence_number = new_sequence_number;
+                        callback();
+                    }
+                }
+            })
+            .detach();
+        true
     }
 
     fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>> {
@@ -680,3 +707,62 @@ fn should_auto_hide_scrollbars() -> Result<bool> {
     let ui_settings = UISettings::new()?;
     Ok(ui_settings.AutoHideScrollBars()?)
 }
+
+/// The registered clipboard format browsers and office suites use for formatted text.
+const CLIPBOARD_HTML_FORMAT: &str = "HTML Format";
+/// The registered clipboard format image editors use for images with transparency.
+const CLIPBOARD_PNG_FORMAT: &str = "PNG";
+
+fn write_to_clipboard(item: &ClipboardItem) -> Result<()> {
+    let clipboard = Clipboard::new()?;
+    clipboard.empty()?;
+    if !item.text.is_empty() {
+        clipboard.set_string(item.text())?;
+    }
+    if let Some(html) = item.html() {
+        let format = Clipboard::register_format(CLIPBOARD_HTML_FORMAT)?;
+        clipboard.set(format, clipboard_html(html).as_bytes())?;
+    }
+    if let Some(image) = item.image() {
+        let format = Clipboard::register_format(CLIPBOARD_PNG_FORMAT)?;
+        clipboard.set(format, &image.to_png()?)?;
+    }
+    Ok(())
+}
+
+fn read_clipboard_format(name: &str) -> Option<Vec<u8>> {
+    let format = Clipboard::register_format(name).ok()?;
+    let size = Clipboard::size(format)?;
+    let clipboard = Clipboard::new().ok()?;
+    let mut data = vec![0; size];
+    let len = clipboard.get(format, &mut data).ok()?;
+    data.truncate(len);
+    Some(data)
+}
+
+/// Wraps an HTML fragment in the header of the "HTML Format" clipboard format, which gives the
+/// byte offsets of the document and of the fragment within it.
+fn clipboard_html(html: &str) -> String {
+    const HEADER_LEN: usize = 105;
+    const PREFIX: &str = "<html><body><!--StartFragment-->";
+    const SUFFIX: &str = "<!--EndFragment--></body></html>";
+    let start_fragment = HEADER_LEN + PREFIX.len();
+    let end_fragment = start_fragment + html.len();
+    let end_html = end_fragment + SUFFIX.len();
+    format!(
+        "Version:0.9\r\nStartHTML:{HEADER_LEN:010}\r\nEndHTML:{end_html:010}\r\n\
+         StartFragment:{start_fragment:010}\r\nEndFragment:{end_fragment:010}\r\n\
+         {PREFIX}{html}{SUFFIX}"
+    )
+}
+
+/// Returns the fragment of a document in the "HTML Format" clipboard format.
+fn html_from_clipboard_html(data: &str) -> Option<String> {
+    let offset = |key: &str| -> Option<usize> {
+        let line = data.lines().find_map(|line| line.strip_prefix(key))?;
+        line.trim().parse().ok()
+    };
+    let start = offset("StartFragment:")?;
+    let end = offset("EndFragment:")?;
+    data.get(start..end).map(ToString::to_string)
+}
@@ -2,8 +2,8 @@
 #![cfg_attr(windows, allow(dead_code))]
 
 use crate::{
-    bounds_tree::BoundsTree, point, AtlasTextureId, AtlasTile, Bounds, ContentMask, Corners, Edges,
-    Hsla, Pixels, Point, Radians, ScaledPixels, Size,
+    bounds_tree::BoundsTree, point, resample_stops, transparent_black, AtlasTextureId, AtlasTile,
+    Bounds, ContentMask, Corners, Edges, Fill, Hsla, Pixels, Point, Radians, ScaledPixels, Size,
 };
 use std::{fmt::Debug, iter::Peekable, ops::Range, slice};
 
@@ -19,6 +19,7 @@ pub(crate) struct Scene {
     layer_stack: Vec<DrawOrder>,
     pub(crate) shadows: Vec<Shadow>,
     pub(crate) quads: Vec<Quad>,
+    pub(crate) gradients: Vec<Gradient>,
     pub(crate) paths: Vec<Path<ScaledPixels>>,
     pub(crate) underlines: Vec<Underline>,
     pub(crate) monochrome_sprites: Vec<MonochromeSprite>,
@@ -34,6 +35,7 @@ impl Scene {
         self.paths.clear();
         self.shadows.clear();
         self.quads.clear();
+        self.gradients.clear();
         self.underlines.clear();
         self.monochrome_sprites.clear();
         self.polychrome_sprites.clear();
@@ -60,6 +62,13 @@ impl Scene {
         self.paint_operations.push(PaintOperation::EndLayer);
     }
 
+    /// Stores a gradient for quads to be filled with, returning the `gradient_id` that refers to
+    /// it.
+    pub fn push_gradient(&mut self, gradient: Gradient) -> u32 {
+        self.gradients.push(gradient);
+        self.gradients.len() as u32
+    }
+
     pub fn insert_primitive(&mut self, primitive: impl Into<Primitive>) {
         let mut primitive = primitive.into();
         let clipped_bounds = primitive
@@ -113,6 +122,15 @@ impl Scene {
     pub fn replay(&mut self, range: Range<usize>, prev_scene: &Scene) {
         for operation in &prev_scene.paint_operations[range] {
             match operation {
+                // Quads refer to their gradients in the scene they were painted in.
+                PaintOperation::Primitive(Primitive::Quad(quad)) if quad.gradient_id != 0 => {
+                    let gradient = prev_scene.gradients[quad.gradient_id as usize - 1];
+                    let gradient_id = self.push_gradient(gradient);
+                    self.insert_primitive(Quad {
+                        gradient_id,
+                        ..quad.clone()
+                    })
+                }
                 PaintOperation::Primitive(primitive) => self.insert_primitive(primitive.clone()),
                 PaintOperation::StartLayer(bounds) => self.push_layer(*bounds),
                 PaintOperation::EndLayer => self.pop_layer(),
@@ -126,7 +144,8 @@ impl Scene {
         self.paths.sort();
         self.underlines.sort();
         self.monochrome_sprites.sort();
-        self.polychrome_sprites.sort();
+        self.polychrome_sprites
+            .sort_by_key(|sprite| (sprite.order, sprite.tile.tile_id));
         self.surfaces.sort();
     }
 
@@ -175,7 +194,7 @@ pub(crate) enum PaintOperation {
     EndLayer,
 }
 
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub(crate) enum Primitive {
     Shadow(Shadow),
     Quad(Quad),
@@ -418,13 +437,18 @@ pub(crate) enum PrimitiveBatch<'a> {
 #[repr(C)]
 pub(crate) struct Quad {
     pub order: DrawOrder,
-    pub pad: u32, // align to 8 bytes
+    /// One more than the index of the gradient that fills the quad in the scene's gradients, or
+    /// 0 when the quad is filled with its background color.
+    pub gradient_id: u32,
     pub bounds: Bounds<ScaledPixels>,
     pub content_mask: ContentMask<ScaledPixels>,
     pub background: Hsla,
     pub border_color: Hsla,
     pub corner_radii: Corners<ScaledPixels>,
     pub border_widths: Edges<ScaledPixels>,
+    /// The length of the dashes drawn along the border, which is continuous when zero.
+    pub dash_length: ScaledPixels,
+    pub dash_gap: ScaledPixels,
 }
 
 impl Ord for Quad {
@@ -439,6 +463,75 @@ impl PartialOrd for Quad {
     }
 }
 
+/// A gradient filling the background of a quad, as drawn by the quad shaders. Gradients are
+/// stored apart from the quads they fill, so that quads with solid backgrounds stay small.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub(crate) struct Gradient {
+    /// 1 for a linear gradient and 2 for a radial gradient.
+    pub kind: u32,
+    pub stop_count: u32,
+    /// The start and end of the gradient's axis. Radial gradients start at their center, and end
+    /// on the circle of their last stop.
+    pub start: Point<ScaledPixels>,
+    pub end: Point<ScaledPixels>,
+    /// The colors and positions of the gradient's stops, up to `MAX_GRADIENT_STOPS`.
+    pub colors: [Hsla; 4],
+    pub positions: [f32; 4],
+}
+
+/// The background of a quad, laid out in its bounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Background {
+    Solid(Hsla),
+    Gradient(Gradient),
+}
+
+impl Background {
+    /// Lays out the given fill in the given bounds, multiplying the alpha of its colors by the
+    /// given opacity.
+    pub fn new(fill: &Fill, bounds: Bounds<Pixels>, scale_factor: f32, opacity: f32) -> Self {
+        let (kind, stops, (start, end)) = match fill {
+            Fill::Color(color) => {
+                return Self::Solid(Hsla {
+                    a: color.a * opacity,
+                    ..*color
+                })
+            }
+            Fill::LinearGradient(gradient) => (1, &gradient.stops, gradient.axis(bounds)),
+            Fill::RadialGradient(gradient) => (2, &gradient.stops, gradient.axis(bounds)),
+        };
+
+        let stops = resample_stops(stops);
+        match stops.as_slice() {
+            [] => Self::Solid(transparent_black()),
+            [stop] => Self::Solid(Hsla {
+                a: stop.color.a * opacity,
+                ..stop.color
+            }),
+            stops => {
+                let mut colors = [Hsla::default(); 4];
+                let mut positions = [0.; 4];
+                for (ix, stop) in stops.iter().enumerate() {
+                    colors[ix] = Hsla {
+                        a: stop.color.a * opacity,
+                        ..stop.color
+                    };
+                    positions[ix] = stop.position;
+                }
+                Self::Gradient(Gradient {
+                    kind,
+                    stop_count: stops.len() as u32,
+                    start: start.scale(scale_factor),
+                    end: end.scale(scale_factor),
+                    colors,
+                    positions,
+                })
+            }
+        }
+    }
+}
+
 impl From<Quad> for Primitive {
     fn from(quad: Quad) -> Self {
         Primitive::Quad(quad)
@@ -640,32 +733,19 @@ impl From<MonochromeSprite> for Primitive {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 #[repr(C)]
 pub(crate) struct PolychromeSprite {
     pub order: DrawOrder,
     pub grayscale: bool,
+    pub opacity: f32,
+    pub pad: u32, // align to 8 bytes
     pub bounds: Bounds<ScaledPixels>,
     pub content_mask: ContentMask<ScaledPixels>,
     pub corner_radii: Corners<ScaledPixels>,
     pub tile: AtlasTile,
 }
 
-impl Ord for PolychromeSprite {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.order.cmp(&other.order) {
-            std::cmp::Ordering::Equal => self.tile.tile_id.cmp(&other.tile.tile_id),
-            order => order,
-        }
-    }
-}
-
-impl PartialOrd for PolychromeSprite {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
 impl From<PolychromeSprite> for Primitive {
     fn from(sprite: PolychromeSprite) -> Self {
         Primitive::PolychromeSprite(sprite)
@@ -702,6 +782,18 @@ impl From<Surface> for Primitive {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) struct PathId(pub(crate) usize);
 
+/// Which points are inside a path, given how many of its triangles cover them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(u32)]
+pub(crate) enum PathFillRule {
+    /// Points covered by an odd number of triangles are inside, which fills the regions of
+    /// self-intersecting paths the way the even-odd rule does.
+    #[default]
+    EvenOdd = 0,
+    /// Points covered by any triangle are inside, so that triangles can overlap.
+    Union = 1,
+}
+
 /// A line made up of a series of vertices and control points.
 #[derive(Clone, Debug)]
 pub struct Path<P: Clone + Default + Debug> {
@@ -711,6 +803,7 @@ pub struct Path<P: Clone + Default + Debug> {
     pub(crate) content_mask: ContentMask<P>,
     pub(crate) vertices: Vec<PathVertex<P>>,
     pub(crate) color: Hsla,
+    pub(crate) fill_rule: PathFillRule,
     start: Point<P>,
     current: Point<P>,
     contour_count: usize,
@@ -731,10 +824,18 @@ impl Path<Pixels> {
             },
             content_mask: Default::default(),
             color: Default::default(),
+            fill_rule: PathFillRule::EvenOdd,
             contour_count: 0,
         }
     }
 
+    /// Start a new contour at the given point, without drawing a line to it.
+    pub fn move_to(&mut self, to: Point<Pixels>) {
+        self.start = to;
+        self.current = to;
+        self.contour_count = 0;
+    }
+
     /// Scale this path by the given factor.
     pub fn scale(&self, factor: f32) -> Path<ScaledPixels> {
         Path {
@@ -751,6 +852,7 @@ impl Path<Pixels> {
             current: self.current.scale(factor),
             contour_count: self.contour_count,
             color: self.color,
+            fill_rule: self.fill_rule,
         }
     }
 
@@ -783,7 +885,7 @@ impl Path<Pixels> {
         self.current = to;
     }
 
-    fn push_triangle(
+    pub(crate) fn push_triangle(
         &mut self,
         xy: (Point<Pixels>, Point<Pixels>, Point<Pixels>),
         st: (Point<f32>, Point<f32>, Point<f32>),
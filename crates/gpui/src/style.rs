@@ -6,9 +6,9 @@ use std::{
 
 use crate::{
     black, phi, point, quad, rems, AbsoluteLength, Bounds, ContentMask, Corners, CornersRefinement,
-    CursorStyle, DefiniteLength, Edges, EdgesRefinement, Font, FontFeatures, FontStyle, FontWeight,
-    Hsla, Length, Pixels, Point, PointRefinement, Rgba, SharedString, Size, SizeRefinement, Styled,
-    TextRun, WindowContext,
+    CursorStyle, DefiniteLength, Edges, EdgesRefinement, Font, FontAxes, FontFeatures, FontStyle,
+    FontWeight, Hsla, Length, LinearGradient, Pixels, Point, PointRefinement, RadialGradient, Rgba,
+    SharedString, Size, SizeRefinement, Styled, TextRun, WindowContext,
 };
 use collections::HashSet;
 use refineable::Refineable;
@@ -106,6 +106,9 @@ pub struct Style {
     /// The border color of this element
     pub border_color: Option<Hsla>,
 
+    /// The style of the line drawn for this element's border
+    pub border_style: BorderStyle,
+
     /// The radius of the corners of this element
     #[refineable]
     pub corner_radii: Corners<AbsoluteLength>,
@@ -113,6 +116,9 @@ pub struct Style {
     /// Box Shadow of the element
     pub box_shadow: SmallVec<[BoxShadow; 2]>,
 
+    /// The opacity of this element and everything it contains, from 0.0 to 1.0
+    pub opacity: Option<f32>,
+
     /// The text style of this element
     pub text: TextStyleRefinement,
 
@@ -180,6 +186,9 @@ pub struct TextStyle {
     /// The font features to use
     pub font_features: FontFeatures,
 
+    /// The variation axes to use, for variable fonts
+    pub font_axes: FontAxes,
+
     /// The font size to use, in pixels or rems.
     pub font_size: AbsoluteLength,
 
@@ -218,6 +227,7 @@ impl Default for TextStyle {
                 "Helvetica".into()
             },
             font_features: FontFeatures::default(),
+            font_axes: FontAxes::default(),
             font_size: rems(1.).into(),
             line_height: phi(),
             font_weight: FontWeight::default(),
@@ -269,6 +279,7 @@ impl TextStyle {
         Font {
             family: self.font_family.clone(),
             features: self.font_features.clone(),
+            axes: self.font_axes.clone(),
             weight: self.font_weight,
             style: self.font_style,
         }
@@ -286,6 +297,7 @@ impl TextStyle {
             font: Font {
                 family: self.font_family.clone(),
                 features: Default::default(),
+                axes: self.font_axes.clone(),
                 weight: self.font_weight,
                 style: self.font_style,
             },
@@ -371,17 +383,20 @@ impl Style {
             _ => {
                 let mut min = bounds.origin;
                 let mut max = bounds.lower_right();
+                let mut border_widths = Edges::<Pixels>::default();
 
                 if self
                     .border_color
                     .map_or(false, |color| !color.is_transparent())
                 {
-                    min.x += self.border_widths.left.to_pixels(rem_size);
-                    max.x -= self.border_widths.right.to_pixels(rem_size);
-                    min.y += self.border_widths.top.to_pixels(rem_size);
-                    max.y -= self.border_widths.bottom.to_pixels(rem_size);
+                    border_widths = self.border_widths.to_pixels(rem_size);
+                    min.x += border_widths.left;
+                    max.x -= border_widths.right;
+                    min.y += border_widths.top;
+                    max.y -= border_widths.bottom;
                 }
 
+                let mut corner_radii = Corners::default();
                 let bounds = match (
                     self.overflow.x == Overflow::Visible,
                     self.overflow.y == Overflow::Visible,
@@ -399,10 +414,32 @@ impl Style {
                         point(bounds.lower_right().x, max.y),
                     ),
                     // both hidden
-                    (false, false) => Bounds::from_corners(min, max),
+                    (false, false) => {
+                        // Clip children to the inner edge of the border, so content never bleeds
+                        // past the element's rounded corners.
+                        let outer_radii = self.corner_radii.to_pixels(bounds.size, rem_size);
+                        corner_radii = Corners {
+                            top_left: (outer_radii.top_left
+                                - border_widths.top.max(border_widths.left))
+                            .max(Pixels::ZERO),
+                            top_right: (outer_radii.top_right
+                                - border_widths.top.max(border_widths.right))
+                            .max(Pixels::ZERO),
+                            bottom_right: (outer_radii.bottom_right
+                                - border_widths.bottom.max(border_widths.right))
+                            .max(Pixels::ZERO),
+                            bottom_left: (outer_radii.bottom_left
+                                - border_widths.bottom.max(border_widths.left))
+                            .max(Pixels::ZERO),
+                        };
+                        Bounds::from_corners(min, max)
+                    }
                 };
 
-                Some(ContentMask { bounds })
+                Some(ContentMask {
+                    bounds,
+                    corner_radii,
+                })
             }
         }
     }
@@ -432,14 +469,17 @@ impl Style {
             &self.box_shadow,
         );
 
-        let background_color = self.background.as_ref().and_then(Fill::color);
-        if background_color.map_or(false, |color| !color.is_transparent()) {
-            let mut border_color = background_color.unwrap_or_default();
+        if let Some(background) = self
+            .background
+            .as_ref()
+            .filter(|background| !background.is_transparent())
+        {
+            let mut border_color = background.color().unwrap_or_default();
             border_color.a = 0.;
             cx.paint_quad(quad(
                 bounds,
                 self.corner_radii.to_pixels(bounds.size, rem_size),
-                background_color.unwrap_or_default(),
+                background.clone(),
                 Edges::default(),
                 border_color,
             ));
@@ -478,35 +518,21 @@ impl Style {
                 background,
                 border_widths,
                 self.border_color.unwrap_or_default(),
-            );
+            )
+            .border_style(self.border_style);
 
-            cx.with_content_mask(Some(ContentMask { bounds: top_bounds }), |cx| {
+            cx.with_content_mask(Some(ContentMask::new(top_bounds)), |cx| {
                 cx.paint_quad(quad.clone());
             });
-            cx.with_content_mask(
-                Some(ContentMask {
-                    bounds: right_bounds,
-                }),
-                |cx| {
-                    cx.paint_quad(quad.clone());
-                },
-            );
-            cx.with_content_mask(
-                Some(ContentMask {
-                    bounds: bottom_bounds,
-                }),
-                |cx| {
-                    cx.paint_quad(quad.clone());
-                },
-            );
-            cx.with_content_mask(
-                Some(ContentMask {
-                    bounds: left_bounds,
-                }),
-                |cx| {
-                    cx.paint_quad(quad);
-                },
-            );
+            cx.with_content_mask(Some(ContentMask::new(right_bounds)), |cx| {
+                cx.paint_quad(quad.clone());
+            });
+            cx.with_content_mask(Some(ContentMask::new(bottom_bounds)), |cx| {
+                cx.paint_quad(quad.clone());
+            });
+            cx.with_content_mask(Some(ContentMask::new(left_bounds)), |cx| {
+                cx.paint_quad(quad);
+            });
         }
 
         #[cfg(debug_assertions)]
@@ -555,8 +581,10 @@ impl Default for Style {
             flex_basis: Length::Auto,
             background: None,
             border_color: None,
+            border_style: BorderStyle::default(),
             corner_radii: Corners::default(),
             box_shadow: Default::default(),
+            opacity: None,
             text: TextStyleRefinement::default(),
             mouse_cursor: None,
 
@@ -568,6 +596,61 @@ impl Default for Style {
     }
 }
 
+/// The style of the line drawn for a border.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub enum BorderStyle {
+    /// A continuous line.
+    #[default]
+    Solid,
+    /// A line of dashes, separated by gaps.
+    Dashed {
+        /// The length of each dash.
+        dash_length: Pixels,
+        /// The length of the gaps between dashes.
+        gap: Pixels,
+    },
+    /// A line of square dots as wide as the border.
+    Dotted,
+}
+
+impl BorderStyle {
+    /// Returns the length of the dashes and gaps drawn for a border of the given width, around a
+    /// quad with the given size and corner radii, or `None` if the border is continuous.
+    ///
+    /// The dashes are stretched so that a whole number of them fit around the quad, which
+    /// keeps the pattern seamless where the outline loops back to its start.
+    pub(crate) fn dash_pattern(
+        &self,
+        border_width: Pixels,
+        size: Size<Pixels>,
+        corner_radii: &Corners<Pixels>,
+    ) -> Option<(Pixels, Pixels)> {
+        let (dash_length, gap) = match *self {
+            BorderStyle::Solid => return None,
+            BorderStyle::Dashed { dash_length, gap } => (dash_length, gap),
+            BorderStyle::Dotted => (border_width, border_width),
+        };
+        if dash_length <= Pixels::ZERO || gap <= Pixels::ZERO {
+            return None;
+        }
+
+        // Each rounded corner replaces two straight segments of its radius with a quarter circle.
+        let radii_sum = corner_radii.top_left
+            + corner_radii.top_right
+            + corner_radii.bottom_right
+            + corner_radii.bottom_left;
+        let perimeter =
+            (size.width + size.height) * 2. - radii_sum * (2. - std::f32::consts::FRAC_PI_2);
+        if perimeter <= Pixels::ZERO {
+            return None;
+        }
+        let period = dash_length + gap;
+        let dash_count = (perimeter / period).round().max(1.);
+        let scale = perimeter / (period * dash_count);
+        Some((dash_length * scale, gap * scale))
+    }
+}
+
 /// The properties that can be applied to an underline.
 #[derive(Refineable, Copy, Clone, Default, Debug, PartialEq, Eq, Hash)]
 #[refineable(Debug)]
@@ -598,6 +681,10 @@ pub struct StrikethroughStyle {
 pub enum Fill {
     /// A solid color fill.
     Color(Hsla),
+    /// A linear gradient fill.
+    LinearGradient(LinearGradient),
+    /// A radial gradient fill.
+    RadialGradient(RadialGradient),
 }
 
 impl Fill {
@@ -605,8 +692,19 @@ impl Fill {
     pub fn color(&self) -> Option<Hsla> {
         match self {
             Fill::Color(color) => Some(*color),
+            Fill::LinearGradient(_) | Fill::RadialGradient(_) => None,
         }
     }
+
+    /// Returns whether the fill is fully transparent everywhere.
+    pub fn is_transparent(&self) -> bool {
+        let stops = match self {
+            Fill::Color(color) => return color.is_transparent(),
+            Fill::LinearGradient(gradient) => &gradient.stops,
+            Fill::RadialGradient(gradient) => &gradient.stops,
+        };
+        stops.iter().all(|stop| stop.color.is_transparent())
+    }
 }
 
 impl Default for Fill {
@@ -627,6 +725,18 @@ impl From<Rgba> for Fill {
     }
 }
 
+impl From<LinearGradient> for Fill {
+    fn from(gradient: LinearGradient) -> Self {
+        Self::LinearGradient(gradient)
+    }
+}
+
+impl From<RadialGradient> for Fill {
+    fn from(gradient: RadialGradient) -> Self {
+        Self::RadialGradient(gradient)
+    }
+}
+
 impl From<TextStyle> for HighlightStyle {
     fn from(other: TextStyle) -> Self {
         Self::from(&other)
@@ -778,7 +888,7 @@ pub fn combine_highlights(
 
 #[cfg(test)]
 mod tests {
-    use crate::{blue, green, red, yellow};
+    use crate::{blue, green, px, red, size, yellow};
 
     use super::*;
 
@@ -870,4 +980,94 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_rounded_overflow_mask() {
+        let mut style = Style::default();
+        style.overflow = Point {
+            x: Overflow::Hidden,
+            y: Overflow::Hidden,
+        };
+        style.corner_radii = Corners::all(px(8.).into());
+        style.border_widths = Edges::all(px(2.).into());
+        style.border_color = Some(red());
+
+        let bounds = Bounds::new(point(px(0.), px(0.)), size(px(100.), px(50.)));
+        let mask = style.overflow_mask(bounds, px(16.)).unwrap();
+        assert_eq!(
+            mask.bounds,
+            Bounds::new(point(px(2.), px(2.)), size(px(96.), px(46.)))
+        );
+        assert_eq!(mask.corner_radii, Corners::all(px(6.)));
+
+        // Only corners within the rounded mask's corners stay rounded.
+        let child = ContentMask::new(Bounds::new(point(px(50.), px(2.)), size(px(100.), px(10.))));
+        let intersection = mask.intersect(&child);
+        assert_eq!(
+            intersection.bounds,
+            Bounds::new(point(px(50.), px(2.)), size(px(48.), px(10.)))
+        );
+        assert_eq!(
+            intersection.corner_radii,
+            Corners {
+                top_left: px(0.),
+                top_right: px(6.),
+                bottom_right: px(0.),
+                bottom_left: px(0.),
+            }
+        );
+
+        // Corners inset from the rounded mask are rounded concentrically with it.
+        let child = ContentMask::new(Bounds::new(point(px(4.), px(4.)), size(px(92.), px(20.))));
+        assert_eq!(
+            mask.intersect(&child).corner_radii,
+            Corners {
+                top_left: px(4.),
+                top_right: px(4.),
+                bottom_right: px(0.),
+                bottom_left: px(0.),
+            }
+        );
+    }
+
+    #[test]
+    fn test_border_dash_pattern() {
+        let size = size(px(100.), px(50.));
+        let square_corners = Corners::all(px(0.));
+        let dashed = BorderStyle::Dashed {
+            dash_length: px(4.),
+            gap: px(2.),
+        };
+        assert_eq!(
+            BorderStyle::Solid.dash_pattern(px(1.), size, &square_corners),
+            None
+        );
+        // 50 dashes fit exactly around the quad, and dots are as long as the border is wide.
+        assert_eq!(
+            dashed.dash_pattern(px(1.), size, &square_corners),
+            Some((px(4.), px(2.)))
+        );
+        assert_eq!(
+            BorderStyle::Dotted.dash_pattern(px(3.), size, &square_corners),
+            Some((px(3.), px(3.)))
+        );
+
+        // Rounded corners shorten the outline, so the dashes are stretched to fill it evenly.
+        let rounded_corners = Corners::all(px(10.));
+        let perimeter = 300. - 40. * (2. - std::f32::consts::FRAC_PI_2);
+        let (dash_length, gap) = dashed.dash_pattern(px(1.), size, &rounded_corners).unwrap();
+        assert!(dash_length > px(4.) && gap > px(2.));
+        assert!(((dash_length + gap).0 * 47. - perimeter).abs() < 0.01);
+        assert!((dash_length / gap - 2.).abs() < 0.001);
+
+        // Degenerate patterns and quads are drawn as solid borders.
+        assert_eq!(
+            BorderStyle::Dotted.dash_pattern(px(0.), size, &square_corners),
+            None
+        );
+        assert_eq!(
+            dashed.dash_pattern(px(1.), crate::size(px(0.), px(0.)), &square_corners),
+            None
+        );
+    }
 }
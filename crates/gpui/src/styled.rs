@@ -1,8 +1,9 @@
 use crate::TextStyleRefinement;
 use crate::{
-    self as gpui, px, relative, rems, AbsoluteLength, AlignItems, CursorStyle, DefiniteLength,
-    Fill, FlexDirection, FlexWrap, Font, FontStyle, FontWeight, Hsla, JustifyContent, Length,
-    SharedString, StyleRefinement, WhiteSpace,
+    self as gpui, linear_gradient, px, radial_gradient, relative, rems, AbsoluteLength, AlignItems,
+    BorderStyle, ColorStop, CursorStyle, DefiniteLength, Fill, FlexDirection, FlexWrap, Font,
+    FontStyle, FontWeight, Hsla, JustifyContent, Length, Point, SharedString, StyleRefinement,
+    WhiteSpace,
 };
 pub use gpui_macros::{
     box_shadow_style_methods, cursor_style_methods, margin_style_methods, overflow_style_methods,
@@ -303,6 +304,39 @@ pub trait Styled: Sized {
         self
     }
 
+    /// Sets the background of the element to a linear gradient in the direction of the given
+    /// angle, in degrees clockwise from the top.
+    fn fill_gradient(self, angle: f32, stops: impl IntoIterator<Item = ColorStop>) -> Self
+    where
+        Self: Sized,
+    {
+        self.bg(linear_gradient(angle, stops))
+    }
+
+    /// Sets the background of the element to a radial gradient around the given center, relative
+    /// to the element's bounds, ending at the given radius, relative to half their smaller side.
+    fn fill_radial_gradient(
+        self,
+        center: Point<f32>,
+        radius: f32,
+        stops: impl IntoIterator<Item = ColorStop>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        self.bg(radial_gradient(center, radius, stops))
+    }
+
+    /// Sets the opacity of the element and all of its children, from 0.0 (transparent) to 1.0 (opaque).
+    /// [Docs](https://tailwindcss.com/docs/opacity)
+    fn opacity(mut self, opacity: f32) -> Self
+    where
+        Self: Sized,
+    {
+        self.style().opacity = Some(opacity);
+        self
+    }
+
     /// Sets the border color of the element.
     fn border_color<C>(mut self, border_color: C) -> Self
     where
@@ -313,6 +347,34 @@ pub trait Styled: Sized {
         self
     }
 
+    /// Sets the style of the line drawn for the element's border.
+    fn border_style(mut self, border_style: BorderStyle) -> Self
+    where
+        Self: Sized,
+    {
+        self.style().border_style = Some(border_style);
+        self
+    }
+
+    /// Draws the element's border as a line of dashes.
+    fn border_dashed(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.border_style(BorderStyle::Dashed {
+            dash_length: px(4.),
+            gap: px(2.),
+        })
+    }
+
+    /// Draws the element's border as a line of dots.
+    fn border_dotted(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.border_style(BorderStyle::Dotted)
+    }
+
     /// Get the text style that has been configured on this element.
     fn text_style(&mut self) -> &mut Option<TextStyleRefinement> {
         let style: &mut StyleRefinement = self.style();
@@ -514,6 +576,7 @@ pub trait Styled: Sized {
         let Font {
             family,
             features,
+            axes,
             weight,
             style,
         } = font;
@@ -521,6 +584,7 @@ pub trait Styled: Sized {
         let text_style = self.text_style().get_or_insert_with(Default::default);
         text_style.font_family = Some(family);
         text_style.font_features = Some(features);
+        text_style.font_axes = Some(axes);
         text_style.font_weight = Some(weight);
         text_style.font_style = Some(style);
 
@@ -88,6 +88,11 @@ where
         (subscription, move || active.set(true))
     }
 
+    /// Returns whether there are no subscriptions, including inert ones.
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().subscribers.is_empty()
+    }
+
     pub fn remove(&self, emitter: &EmitterKey) -> impl IntoIterator<Item = Callback> {
         let subscribers = self.0.lock().subscribers.remove(emitter);
         subscribers
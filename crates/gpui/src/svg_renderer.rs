@@ -1,17 +1,93 @@
-use crate::{AssetSource, DevicePixels, IsZero, Result, SharedString, Size};
+use crate::{AssetSource, DevicePixels, IsZero, Result, ScaledPixels, SharedString, Size};
 use anyhow::anyhow;
+use collections::{HashMap, HashSet};
+use parking_lot::Mutex;
 use resvg::tiny_skia::Pixmap;
 use std::{hash::Hash, sync::Arc};
 
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, Debug, PartialEq, Hash, Eq)]
 pub(crate) struct RenderSvgParams {
     pub(crate) path: SharedString,
     pub(crate) size: Size<DevicePixels>,
 }
 
+impl RenderSvgParams {
+    /// Returns the parameters for rasterizing an SVG drawn at the given size in device pixels.
+    ///
+    /// SVGs are rasterized at twice their size to get a higher quality result, rounded up to
+    /// whole pixels so that sizes only differing by a fraction of a pixel share a rasterization.
+    /// The color isn't part of the parameters, since SVGs are rasterized into an alpha mask that
+    /// is tinted when drawn.
+    pub(crate) fn new(path: SharedString, size: Size<ScaledPixels>) -> Self {
+        Self {
+            path,
+            size: size.map(|pixels| DevicePixels::from((pixels.0 * 2.).ceil() as i32)),
+        }
+    }
+}
+
+/// Keeps track of the SVG rasterizations stored in a window's sprite atlas, so that the least
+/// recently drawn ones can be evicted.
+pub(crate) struct SvgRasterCache {
+    capacity: usize,
+    frame: u64,
+    last_drawn: HashMap<RenderSvgParams, u64>,
+}
+
+impl SvgRasterCache {
+    pub(crate) const DEFAULT_CAPACITY: usize = 1024;
+
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            frame: 0,
+            last_drawn: HashMap::default(),
+        }
+    }
+
+    /// Records that the rasterization with the given parameters is drawn in the current frame.
+    pub(crate) fn touch(&mut self, params: &RenderSvgParams) {
+        if let Some(frame) = self.last_drawn.get_mut(params) {
+            *frame = self.frame;
+        } else {
+            self.last_drawn.insert(params.clone(), self.frame);
+        }
+    }
+
+    /// Ends the current frame, returning the rasterizations to evict when there are more than
+    /// the cache's capacity. Rasterizations drawn in this frame or the previous one are kept,
+    /// since the GPU may still be using them.
+    pub(crate) fn finish_frame(&mut self) -> Vec<RenderSvgParams> {
+        let mut evicted = Vec::new();
+        if self.last_drawn.len() > self.capacity {
+            let mut entries = self
+                .last_drawn
+                .iter()
+                .filter(|(_, frame)| **frame + 1 < self.frame)
+                .map(|(params, frame)| (*frame, params.clone()))
+                .collect::<Vec<_>>();
+            entries.sort_unstable_by_key(|(frame, _)| *frame);
+            let excess = self.last_drawn.len() - self.capacity;
+            for (_, params) in entries.into_iter().take(excess) {
+                self.last_drawn.remove(&params);
+                evicted.push(params);
+            }
+        }
+        self.frame += 1;
+        evicted
+    }
+
+    /// Forgets every rasterization, returning them so that they can be evicted. This is used
+    /// when the window's scale factor changes, since none of them will be drawn anymore.
+    pub(crate) fn clear(&mut self) -> Vec<RenderSvgParams> {
+        self.last_drawn.drain().map(|(params, _)| params).collect()
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct SvgRenderer {
     asset_source: Arc<dyn AssetSource>,
+    failed_paths: Arc<Mutex<HashSet<SharedString>>>,
 }
 
 pub enum SvgSize {
@@ -21,7 +97,22 @@ pub enum SvgSize {
 
 impl SvgRenderer {
     pub fn new(asset_source: Arc<dyn AssetSource>) -> Self {
-        Self { asset_source }
+        Self {
+            asset_source,
+            failed_paths: Default::default(),
+        }
+    }
+
+    /// Whether the SVG at the given path failed to render before.
+    pub(crate) fn has_failed(&self, path: &SharedString) -> bool {
+        self.failed_paths.lock().contains(path)
+    }
+
+    /// Records that the SVG at the given path failed to render, logging the error the first time.
+    pub(crate) fn report_failure(&self, path: &SharedString, error: anyhow::Error) {
+        if self.failed_paths.lock().insert(path.clone()) {
+            log::error!("failed to render svg {path:?}: {error:?}");
+        }
     }
 
     pub fn render(&self, params: &RenderSvgParams) -> Result<Option<Vec<u8>>> {
@@ -70,3 +161,60 @@ impl SvgRenderer {
         Ok(pixmap)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::size;
+
+    fn params(path: &'static str, width: f32, height: f32) -> RenderSvgParams {
+        RenderSvgParams::new(path.into(), size(ScaledPixels(width), ScaledPixels(height)))
+    }
+
+    #[test]
+    fn test_svg_params_size_rounding() {
+        let icon = params("icon.svg", 16., 16.);
+        assert_eq!(icon.size, size(DevicePixels(32), DevicePixels(32)));
+
+        // Fractional sizes are rounded up, so slightly different sizes share a rasterization.
+        assert_eq!(params("icon.svg", 15.6, 15.9), icon);
+        assert_eq!(
+            params("icon.svg", 16.2, 15.5).size,
+            size(DevicePixels(33), DevicePixels(31))
+        );
+
+        // The same icon drawn on a display with twice the scale factor is rasterized separately.
+        assert_ne!(params("icon.svg", 32., 32.), icon);
+        assert_ne!(params("other.svg", 16., 16.), icon);
+    }
+
+    #[test]
+    fn test_svg_raster_cache_eviction() {
+        let a = params("a.svg", 16., 16.);
+        let b = params("b.svg", 16., 16.);
+        let c = params("c.svg", 16., 16.);
+        let d = params("d.svg", 16., 16.);
+        let mut cache = SvgRasterCache::new(2);
+
+        // Drawing the same rasterization several times only stores it once, and rasterizations
+        // drawn in the current or the previous frame are never evicted.
+        for params in [&a, &b, &a, &c] {
+            cache.touch(params);
+        }
+        assert_eq!(cache.finish_frame(), []);
+        cache.touch(&c);
+        assert_eq!(cache.finish_frame(), []);
+
+        // Once they're older than that, the least recently drawn ones are evicted.
+        cache.touch(&b);
+        cache.touch(&d);
+        assert_eq!(cache.finish_frame(), [a.clone()]);
+        cache.touch(&d);
+        assert_eq!(cache.finish_frame(), [c.clone()]);
+
+        let mut cleared = cache.clear();
+        cleared.sort_by_key(|params| params.path.to_string());
+        assert_eq!(cleared, [b, d]);
+        assert_eq!(cache.finish_frame(), []);
+    }
+}
@@ -25,13 +25,43 @@
 //!   assert!(true)
 //! }
 //! ```
-use crate::{Entity, Subscription, TestAppContext, TestDispatcher};
+//!
+//! ## Closing windows
+//!
+//! Windows opened with [`TestAppContext::open_window_with`] are closed when the returned
+//! [`WindowHandle`] is dropped, so a test can't leak them into the rest of the app:
+//!
+//! ```
+//! use gpui::{div, IntoElement, Render, TestAppContext, ViewContext, WindowOptions};
+//!
+//! struct Counter(usize);
+//!
+//! impl Render for Counter {
+//!     fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+//!         div()
+//!     }
+//! }
+//!
+//! #[gpui::test]
+//! fn test_counter(cx: &mut TestAppContext) {
+//!     {
+//!         let window = cx.open_window_with(WindowOptions::default(), |_| Counter(0));
+//!         window.update(cx, |counter, _| counter.0 += 1).unwrap();
+//!         assert_eq!(cx.windows().len(), 1);
+//!     }
+//!     // The window was closed when `window` went out of scope.
+//!     assert!(cx.windows().is_empty());
+//! }
+//! ```
+use crate::{AppCell, Context, Entity, Subscription, TestAppContext, TestDispatcher};
 use futures::StreamExt as _;
 use rand::prelude::*;
 use smol::channel;
 use std::{
     env,
+    ops::Deref,
     panic::{self, RefUnwindSafe},
+    rc::Weak,
 };
 
 /// Run the given test function with the configured parameters.
@@ -111,3 +141,71 @@ pub fn observe<T: 'static>(entity: &impl Entity<T>, cx: &mut TestAppContext) ->
 
     Observation { rx, _subscription }
 }
+
+/// A window opened by a test, which is closed when this handle is dropped.
+///
+/// This dereferences to the window's [`crate::WindowHandle`], so it can be used anywhere the
+/// plain handle can. See [`TestAppContext::open_window_with`].
+pub struct WindowHandle<V> {
+    handle: crate::WindowHandle<V>,
+    app: Weak<AppCell>,
+}
+
+impl<V> WindowHandle<V> {
+    pub(crate) fn new(handle: crate::WindowHandle<V>, app: Weak<AppCell>) -> Self {
+        Self { handle, app }
+    }
+}
+
+impl<V> Deref for WindowHandle<V> {
+    type Target = crate::WindowHandle<V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+impl<V> Drop for WindowHandle<V> {
+    fn drop(&mut self) {
+        let Some(app) = self.app.upgrade() else {
+            return;
+        };
+        // If the app is borrowed, this handle is being dropped while the app is being updated
+        // (or unwound after a panic in an update). Leave the window to the test's teardown
+        // rather than panicking on a second borrow.
+        if let Some(mut cx) = app.try_borrow_mut() {
+            cx.update_window(*self.handle, |_, cx| cx.remove_window())
+                .ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as gpui, div, IntoElement, Render, TestAppContext, ViewContext, WindowOptions,
+    };
+
+    struct Counter(usize);
+
+    impl Render for Counter {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    #[gpui::test]
+    fn test_window_handle_closes_window_on_drop(cx: &mut TestAppContext) {
+        let first = cx.open_window_with(WindowOptions::default(), |_| Counter(0));
+        let second = cx.open_window_with(WindowOptions::default(), |_| Counter(10));
+        first.update(cx, |counter, _| counter.0 += 1).unwrap();
+        assert_eq!(cx.windows().len(), 2);
+
+        drop(first);
+        assert!(cx.windows() == vec![**second]);
+        assert_eq!(second.update(cx, |counter, _| counter.0).unwrap(), 10);
+
+        drop(second);
+        assert!(cx.windows().is_empty());
+    }
+}
@@ -1,8 +1,15 @@
+mod bidi;
+mod color_glyphs;
+mod font_axes;
+mod font_fallbacks;
 mod font_features;
 mod line;
 mod line_layout;
 mod line_wrapper;
 
+pub use bidi::*;
+pub(crate) use color_glyphs::*;
+pub use font_axes::*;
 pub use font_features::*;
 pub use line::*;
 pub use line_layout::*;
@@ -26,6 +33,7 @@ use std::{
     cmp,
     fmt::{Debug, Display, Formatter},
     hash::{Hash, Hasher},
+    num::NonZeroU32,
     ops::{Deref, DerefMut, Range},
     sync::Arc,
 };
@@ -50,6 +58,9 @@ pub struct TextSystem {
     wrapper_pool: Mutex<FxHashMap<FontIdWithSize, Vec<LineWrapper>>>,
     font_runs_pool: Mutex<Vec<Vec<FontRun>>>,
     fallback_font_stack: SmallVec<[Font; 2]>,
+    font_fallbacks: RwLock<FxHashMap<SharedString, Arc<[SharedString]>>>,
+    default_font_fallbacks: RwLock<Arc<[SharedString]>>,
+    font_ids_by_char: RwLock<FxHashMap<(FontId, char), FontId>>,
 }
 
 impl TextSystem {
@@ -72,6 +83,9 @@ impl TextSystem {
                 font("Noto Sans"), // KDE
                 font("DejaVu Sans")
             ],
+            font_fallbacks: RwLock::default(),
+            default_font_fallbacks: RwLock::new(font_fallbacks::default_font_fallbacks().into()),
+            font_ids_by_char: RwLock::default(),
         }
     }
 
@@ -96,6 +110,24 @@ impl TextSystem {
         self.platform_text_system.add_fonts(fonts)
     }
 
+    /// Adds Zed Plex Mono and Zed Plex Sans, along with the fonts made for tests, so that text
+    /// is laid out the same way regardless of which fonts are installed.
+    #[cfg(test)]
+    pub(crate) fn add_test_fonts(&self) {
+        self.add_fonts(
+            [
+                "../../assets/fonts/plex-mono/ZedPlexMono-Regular.ttf",
+                "../../assets/fonts/plex-sans/ZedPlexSans-Regular.ttf",
+                "test_data/ZedTestCJK.ttf",
+                "test_data/ZedTestVariable.ttf",
+            ]
+            .into_iter()
+            .map(|path| std::fs::read(path).unwrap().into())
+            .collect(),
+        )
+        .unwrap();
+    }
+
     /// Get the FontId for the configure font family and style.
     pub fn font_id(&self, font: &Font) -> Result<FontId> {
         fn clone_font_id_result(font_id: &Result<FontId>) -> Result<FontId> {
@@ -158,6 +190,87 @@ impl TextSystem {
         );
     }
 
+    /// Set the font families used, in order, for characters that the given font family has no
+    /// glyphs for.
+    pub fn set_font_fallbacks(
+        &self,
+        family: impl Into<SharedString>,
+        fallbacks: Vec<SharedString>,
+    ) {
+        self.font_fallbacks
+            .write()
+            .insert(family.into(), fallbacks.into());
+        self.font_ids_by_char.write().clear();
+    }
+
+    /// Set the font families used, in order, for characters missing from font families that
+    /// have no fallbacks of their own. Defaults to fonts covering CJK scripts, emoji and symbols
+    /// on the current platform.
+    pub fn set_default_font_fallbacks(&self, fallbacks: Vec<SharedString>) {
+        *self.default_font_fallbacks.write() = fallbacks.into();
+        self.font_ids_by_char.write().clear();
+    }
+
+    /// Get the font families used, in order, for characters that the given font family has no
+    /// glyphs for.
+    pub fn font_fallbacks(&self, family: &SharedString) -> Arc<[SharedString]> {
+        self.font_fallbacks
+            .read()
+            .get(family)
+            .cloned()
+            .unwrap_or_else(|| self.default_font_fallbacks.read().clone())
+    }
+
+    /// Resolves the font to render a character with, which is the given font if it has a glyph
+    /// for it, or otherwise the first of its fallbacks that does. Falls back to the given font
+    /// if none of them do, leaving it to the platform to render the character.
+    fn font_id_for_char(&self, font: &Font, font_id: FontId, ch: char) -> FontId {
+        if let Some(char_font_id) = self.font_ids_by_char.read().get(&(font_id, ch)) {
+            return *char_font_id;
+        }
+
+        let char_font_id = if self
+            .platform_text_system
+            .glyph_for_char(font_id, ch)
+            .is_some()
+        {
+            font_id
+        } else {
+            self.font_fallbacks(&font.family)
+                .iter()
+                .find_map(|family| {
+                    let fallback = Font {
+                        family: family.clone(),
+                        ..font.clone()
+                    };
+                    let fallback_id = self.font_id(&fallback).ok()?;
+                    self.platform_text_system
+                        .glyph_for_char(fallback_id, ch)
+                        .is_some()
+                        .then_some(fallback_id)
+                })
+                .unwrap_or(font_id)
+        };
+        self.font_ids_by_char
+            .write()
+            .insert((font_id, ch), char_font_id);
+        char_font_id
+    }
+
+    /// Appends the runs to lay out a piece of text in the given font to `font_runs`, splitting
+    /// it wherever it contains characters that have to be rendered with one of the font's
+    /// fallbacks.
+    fn push_font_runs(&self, text: &str, font: &Font, font_runs: &mut Vec<FontRun>) -> FontId {
+        let font_id = self.resolve_font(font);
+        font_fallbacks::push_font_runs(
+            text,
+            font_id,
+            |ch| self.font_id_for_char(font, font_id, ch),
+            font_runs,
+        );
+        font_id
+    }
+
     /// Get the bounding box for the given font and font size.
     /// A font's bounding box is the smallest rectangle that could enclose all glyphs
     /// in the font. superimposed over one another.
@@ -375,6 +488,22 @@ impl WindowTextSystem {
         font_size: Pixels,
         runs: &[TextRun],
         wrap_width: Option<Pixels>,
+    ) -> Result<SmallVec<[WrappedLine; 1]>> {
+        self.shape_text_with_tab_size(text, font_size, runs, wrap_width, None)
+    }
+
+    /// Shape a multi line string of text like [`Self::shape_text`], additionally moving the text
+    /// after each tab to the next tab stop, every `tab_size` spaces, if `tab_size` is provided.
+    ///
+    /// Lines are wrapped at whitespace and between CJK characters, breaking words that don't fit
+    /// on a line of their own. Whitespace after a line's last word never starts a new line.
+    pub fn shape_text_with_tab_size(
+        &self,
+        text: SharedString,
+        font_size: Pixels,
+        runs: &[TextRun],
+        wrap_width: Option<Pixels>,
+        tab_size: Option<NonZeroU32>,
     ) -> Result<SmallVec<[WrappedLine; 1]>> {
         let mut runs = runs.iter().cloned().peekable();
         let mut font_runs = self.font_runs_pool.lock().pop().unwrap_or_default();
@@ -385,7 +514,7 @@ impl WindowTextSystem {
         let mut process_line = |line_text: SharedString| {
             let line_end = line_start + line_text.len();
 
-            let mut last_font: Option<Font> = None;
+            let mut metrics_font_id = None;
             let mut decoration_runs = SmallVec::<[DecorationRun; 32]>::new();
             let mut run_start = line_start;
             while run_start < line_end {
@@ -395,15 +524,9 @@ impl WindowTextSystem {
 
                 let run_len_within_line = cmp::min(line_end, run_start + run.len) - run_start;
 
-                if last_font == Some(run.font.clone()) {
-                    font_runs.last_mut().unwrap().len += run_len_within_line;
-                } else {
-                    last_font = Some(run.font.clone());
-                    font_runs.push(FontRun {
-                        len: run_len_within_line,
-                        font_id: self.resolve_font(&run.font),
-                    });
-                }
+                let run_text = &line_text[run_start - line_start..][..run_len_within_line];
+                let font_id = self.push_font_runs(run_text, &run.font, &mut font_runs);
+                metrics_font_id.get_or_insert(font_id);
 
                 if decoration_runs.last().map_or(false, |last_run| {
                     last_run.color == run.color
@@ -431,9 +554,14 @@ impl WindowTextSystem {
                 run_start += run_len_within_line;
             }
 
-            let layout = self
-                .line_layout_cache
-                .layout_wrapped_line(&line_text, font_size, &font_runs, wrap_width);
+            let layout = self.line_layout_cache.layout_wrapped_line(
+                &line_text,
+                font_size,
+                &font_runs,
+                metrics_font_id,
+                wrap_width,
+                tab_size,
+            );
 
             lines.push(WrappedLine {
                 layout,
@@ -491,23 +619,18 @@ impl WindowTextSystem {
         runs: &[TextRun],
     ) -> Result<Arc<LineLayout>> {
         let mut font_runs = self.font_runs_pool.lock().pop().unwrap_or_default();
+        let mut metrics_font_id = None;
+        let mut run_start = 0;
         for run in runs.iter() {
-            let font_id = self.resolve_font(&run.font);
-            if let Some(last_run) = font_runs.last_mut() {
-                if last_run.font_id == font_id {
-                    last_run.len += run.len;
-                    continue;
-                }
-            }
-            font_runs.push(FontRun {
-                len: run.len,
-                font_id,
-            });
+            let run_text = &text[run_start..run_start + run.len];
+            let font_id = self.push_font_runs(run_text, &run.font, &mut font_runs);
+            metrics_font_id.get_or_insert(font_id);
+            run_start += run.len;
         }
 
-        let layout = self
-            .line_layout_cache
-            .layout_line(text, font_size, &font_runs);
+        let layout =
+            self.line_layout_cache
+                .layout_line(text, font_size, &font_runs, metrics_font_id);
 
         font_runs.clear();
         self.font_runs_pool.lock().push(font_runs);
@@ -632,6 +755,21 @@ pub struct TextRun {
     pub strikethrough: Option<StrikethroughStyle>,
 }
 
+#[cfg(test)]
+impl TextRun {
+    /// A run of unstyled text in the given font.
+    pub(crate) fn plain(len: usize, font: Font) -> Self {
+        Self {
+            len,
+            font,
+            color: Hsla::default(),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        }
+    }
+}
+
 /// An identifier for a specific glyph, as returned by [`TextSystem::layout_line`].
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 #[repr(C)]
@@ -670,6 +808,9 @@ pub struct Font {
     /// The font features to use.
     pub features: FontFeatures,
 
+    /// The variation axes to use, for variable fonts.
+    pub axes: FontAxes,
+
     /// The font weight.
     pub weight: FontWeight,
 
@@ -682,6 +823,7 @@ pub fn font(family: impl Into<SharedString>) -> Font {
     Font {
         family: family.into(),
         features: FontFeatures::default(),
+        axes: FontAxes::default(),
         weight: FontWeight::default(),
         style: FontStyle::default(),
     }
@@ -699,6 +841,21 @@ impl Font {
         self.style = FontStyle::Italic;
         self
     }
+
+    /// The weight to select a face of the font's family by, which is the `wght` axis when it
+    /// is set, so that families without a variable face use the closest static weight.
+    pub(crate) fn matching_weight(&self) -> FontWeight {
+        self.axes.get("wght").map_or(self.weight, FontWeight)
+    }
+
+    /// The style to select a face of the font's family by, which is oblique when the `slnt` axis
+    /// is set to a non-zero angle.
+    pub(crate) fn matching_style(&self) -> FontStyle {
+        match self.axes.get("slnt") {
+            Some(slant) if slant != 0. && self.style == FontStyle::Normal => FontStyle::Oblique,
+            _ => self.style,
+        }
+    }
 }
 
 /// A struct for storing font metrics.
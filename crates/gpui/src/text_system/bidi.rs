@@ -0,0 +1,83 @@
+use std::ops::Range;
+use unicode_bidi::BidiInfo;
+
+/// A run of text that flows in a single direction.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BidiRun {
+    /// The range of the run in its line, in UTF-8 bytes.
+    pub range: Range<usize>,
+    /// Whether the run flows from right to left.
+    pub is_rtl: bool,
+}
+
+/// Applies the Unicode bidirectional algorithm to a line of text, returning its directional runs
+/// in visual order, from left to right. Returns `None` when the whole line flows from left to
+/// right, which is the case of most lines.
+pub fn visual_runs(text: &str) -> Option<Vec<BidiRun>> {
+    if text.is_ascii() {
+        return None;
+    }
+    let bidi_info = BidiInfo::new(text, None);
+    if !bidi_info.has_rtl() {
+        return None;
+    }
+
+    // Each paragraph has its own base direction, but the paragraphs of a line are still laid out
+    // after each other.
+    let mut runs = Vec::new();
+    for paragraph in &bidi_info.paragraphs {
+        let (levels, level_runs) = bidi_info.visual_runs(paragraph, paragraph.range.clone());
+        runs.extend(level_runs.into_iter().map(|range| BidiRun {
+            is_rtl: levels[range.start].is_rtl(),
+            range,
+        }));
+    }
+    Some(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(range: Range<usize>, is_rtl: bool) -> BidiRun {
+        BidiRun { range, is_rtl }
+    }
+
+    #[test]
+    fn test_left_to_right_lines() {
+        assert_eq!(visual_runs(""), None);
+        assert_eq!(visual_runs("fn main() {}"), None);
+        assert_eq!(visual_runs("naïve café 你好"), None);
+    }
+
+    #[test]
+    fn test_right_to_left_lines() {
+        // "שלום", in a single run that is reordered by the shaper.
+        assert_eq!(visual_runs("שלום"), Some(vec![run(0..8, true)]));
+        // "مرحبا"
+        assert_eq!(visual_runs("مرحبا"), Some(vec![run(0..10, true)]));
+    }
+
+    #[test]
+    fn test_mixed_direction_lines() {
+        // Hebrew within a left-to-right paragraph keeps its position, and the spaces around it
+        // take the paragraph's direction.
+        assert_eq!(
+            visual_runs("abc אבג def"),
+            Some(vec![run(0..4, false), run(4..10, true), run(10..14, false)])
+        );
+
+        // Numbers within a right-to-left paragraph are laid out from left to right, and the
+        // runs are reversed.
+        assert_eq!(
+            visual_runs("אב 12 גד"),
+            Some(vec![run(7..12, true), run(5..7, false), run(0..5, true)])
+        );
+
+        // The base direction comes from the first strong character.
+        assert_eq!(
+            visual_runs("אב cd"),
+            Some(vec![run(5..7, false), run(0..5, true)])
+        );
+    }
+}
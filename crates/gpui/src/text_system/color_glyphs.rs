@@ -0,0 +1,84 @@
+use super::font_axes::{read_u16, read_u32};
+
+/// Whether a glyph is drawn with color layers from a font's `COLR` table, either as a list of
+/// layers or as a paint graph.
+///
+/// https://learn.microsoft.com/en-us/typography/opentype/spec/colr
+pub(crate) fn has_color_layers(colr: &[u8], glyph_id: u16) -> bool {
+    let Some(version) = read_u16(colr, 0) else {
+        return false;
+    };
+
+    // Version 0 records are sorted by glyph ID.
+    let record_count = read_u16(colr, 2).unwrap_or(0) as usize;
+    let records_offset = read_u32(colr, 4).unwrap_or(0) as usize;
+    if find_glyph_record(colr, records_offset, record_count, 6, glyph_id) {
+        return true;
+    }
+
+    // Version 1 adds a list of glyphs with paint graphs, which are also sorted by glyph ID.
+    if version >= 1 {
+        let list_offset = read_u32(colr, 14).unwrap_or(0) as usize;
+        if list_offset != 0 {
+            let record_count = read_u32(colr, list_offset).unwrap_or(0) as usize;
+            return find_glyph_record(colr, list_offset + 4, record_count, 6, glyph_id);
+        }
+    }
+    false
+}
+
+/// Binary searches for a glyph in an array of records that start with a glyph ID.
+fn find_glyph_record(
+    data: &[u8],
+    offset: usize,
+    record_count: usize,
+    record_size: usize,
+    glyph_id: u16,
+) -> bool {
+    let (mut start, mut end) = (0, record_count);
+    while start < end {
+        let mid = (start + end) / 2;
+        let Some(record_glyph_id) = read_u16(data, offset + mid * record_size) else {
+            return false;
+        };
+        match record_glyph_id.cmp(&glyph_id) {
+            std::cmp::Ordering::Less => start = mid + 1,
+            std::cmp::Ordering::Greater => end = mid,
+            std::cmp::Ordering::Equal => return true,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_color_layers() {
+        // A version 1 table with layers for glyphs 3 and 8, and a paint graph for glyph 5.
+        let mut colr = Vec::new();
+        colr.extend(0x0001u16.to_be_bytes());
+        colr.extend(2u16.to_be_bytes());
+        colr.extend(34u32.to_be_bytes());
+        colr.extend(0u32.to_be_bytes());
+        colr.extend(0u16.to_be_bytes());
+        colr.extend(46u32.to_be_bytes());
+        colr.extend([0; 16]);
+        for glyph_id in [3u16, 8] {
+            colr.extend(glyph_id.to_be_bytes());
+            colr.extend([0; 4]);
+        }
+        colr.extend(1u32.to_be_bytes());
+        colr.extend(5u16.to_be_bytes());
+        colr.extend([0; 4]);
+
+        for glyph_id in [3, 5, 8] {
+            assert!(has_color_layers(&colr, glyph_id), "glyph {glyph_id}");
+        }
+        for glyph_id in [0, 4, 6, 9] {
+            assert!(!has_color_layers(&colr, glyph_id), "glyph {glyph_id}");
+        }
+        assert!(!has_color_layers(&[], 3));
+    }
+}
@@ -0,0 +1,476 @@
+use std::{
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use schemars::schema::{InstanceType, SchemaObject};
+
+use crate::SharedString;
+
+/// The variation axes to use for a variable font, like its weight (`wght`), width (`wdth`) or
+/// slant (`slnt`), either set explicitly or taken from one of the font's named instances.
+///
+/// Fonts that aren't variable are matched by the weight, width and slant given here instead.
+#[derive(Default, Clone)]
+pub struct FontAxes {
+    /// The name of one of the font's named instances, like "Condensed Bold", providing the
+    /// values of the axes that aren't set explicitly.
+    pub named_instance: Option<SharedString>,
+    /// The values of the axes, by their four-character tag, sorted by tag.
+    pub axes: Arc<Vec<(String, f32)>>,
+}
+
+impl FontAxes {
+    /// Create a set of variation axes from their tags and values. A tag that is given more than
+    /// once takes its last value.
+    pub fn new(axes: impl IntoIterator<Item = (impl Into<String>, f32)>) -> Self {
+        let axes = axes
+            .into_iter()
+            .map(|(tag, value)| (tag.into(), value))
+            .collect::<BTreeMap<_, _>>();
+        Self {
+            named_instance: None,
+            axes: Arc::new(axes.into_iter().collect()),
+        }
+    }
+
+    /// Use the values of a named instance for the axes that aren't set explicitly.
+    pub fn named_instance(mut self, name: impl Into<SharedString>) -> Self {
+        self.named_instance = Some(name.into());
+        self
+    }
+
+    /// Returns the explicitly set value of an axis.
+    pub fn get(&self, tag: &str) -> Option<f32> {
+        self.axes
+            .iter()
+            .find_map(|(axis_tag, value)| (axis_tag == tag).then_some(*value))
+    }
+
+    /// Whether no axes or named instance are set, leaving the font at its default instance.
+    pub fn is_empty(&self) -> bool {
+        self.named_instance.is_none() && self.axes.is_empty()
+    }
+
+    /// Returns the values to apply to a variable font's axes, given its `fvar` and `name` tables,
+    /// or `None` if the font isn't variable. Axes the font doesn't have are left out, and values
+    /// are clamped to the range the font supports.
+    pub(crate) fn resolve(&self, fvar: &[u8], name: Option<&[u8]>) -> Option<Vec<(u32, f32)>> {
+        let fvar = FvarTable::parse(fvar)?;
+        let instance = self.named_instance.as_ref().and_then(|instance_name| {
+            fvar.instances.iter().find(|instance| {
+                name.and_then(|name| name_table_string(name, instance.subfamily_name_id))
+                    .map_or(false, |name| {
+                        name.eq_ignore_ascii_case(instance_name.as_ref())
+                    })
+            })
+        });
+
+        Some(
+            fvar.axes
+                .iter()
+                .enumerate()
+                .filter_map(|(ix, axis)| {
+                    let value = self
+                        .axes
+                        .iter()
+                        .find(|(tag, _)| tag_from_str(tag) == Some(axis.tag))
+                        .map(|(_, value)| *value)
+                        .or_else(|| {
+                            instance.and_then(|instance| instance.coordinates.get(ix).copied())
+                        })?;
+                    Some((axis.tag, value.clamp(axis.min_value, axis.max_value)))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl PartialEq for FontAxes {
+    fn eq(&self, other: &Self) -> bool {
+        self.named_instance == other.named_instance
+            && self.axes.len() == other.axes.len()
+            && self.axes.iter().zip(other.axes.iter()).all(
+                |((a_tag, a_value), (b_tag, b_value))| {
+                    a_tag == b_tag && a_value.to_bits() == b_value.to_bits()
+                },
+            )
+    }
+}
+
+impl Eq for FontAxes {}
+
+impl Hash for FontAxes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.named_instance.hash(state);
+        for (tag, value) in self.axes.iter() {
+            tag.hash(state);
+            state.write_u32(value.to_bits());
+        }
+    }
+}
+
+impl std::fmt::Debug for FontAxes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("FontAxes");
+        if let Some(named_instance) = &self.named_instance {
+            debug.field("named_instance", named_instance);
+        }
+        for (tag, value) in self.axes.iter() {
+            debug.field(tag, value);
+        }
+
+        debug.finish()
+    }
+}
+
+/// The key of the named instance in the JSON representation, which can't be confused with an
+/// axis tag since those are four characters long.
+const NAMED_INSTANCE_KEY: &str = "instance";
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum AxisValue {
+    Number(f32),
+    Name(String),
+}
+
+impl<'de> serde::Deserialize<'de> for FontAxes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{MapAccess, Visitor};
+        use std::fmt;
+
+        struct FontAxesVisitor;
+
+        impl<'de> Visitor<'de> for FontAxesVisitor {
+            type Value = FontAxes;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of font variation axes")
+            }
+
+            fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                let mut named_instance = None;
+                let mut axes = Vec::new();
+                while let Some((key, value)) = access.next_entry::<String, AxisValue>()? {
+                    match value {
+                        AxisValue::Name(name) if key == NAMED_INSTANCE_KEY => {
+                            named_instance = Some(SharedString::from(name));
+                        }
+                        AxisValue::Number(value) if tag_from_str(&key).is_some() => {
+                            axes.push((key, value));
+                        }
+                        value => {
+                            log::error!("Incorrect font axis {} with value {:?}", key, value);
+                        }
+                    }
+                }
+
+                let mut font_axes = FontAxes::new(axes);
+                font_axes.named_instance = named_instance;
+                Ok(font_axes)
+            }
+        }
+
+        deserializer.deserialize_map(FontAxesVisitor)
+    }
+}
+
+impl serde::Serialize for FontAxes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(named_instance) = &self.named_instance {
+            map.serialize_entry(NAMED_INSTANCE_KEY, named_instance)?;
+        }
+        for (tag, value) in self.axes.iter() {
+            map.serialize_entry(tag, value)?;
+        }
+
+        map.end()
+    }
+}
+
+impl schemars::JsonSchema for FontAxes {
+    fn schema_name() -> String {
+        "FontAxes".into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = SchemaObject::default();
+        schema.instance_type = Some(schemars::schema::SingleOrVec::Single(Box::new(
+            InstanceType::Object,
+        )));
+        {
+            let mut property = SchemaObject::default();
+            property.instance_type = Some(schemars::schema::SingleOrVec::Single(Box::new(
+                InstanceType::Number,
+            )));
+            schema
+                .object()
+                .pattern_properties
+                .insert("^[0-9a-zA-Z ]{4}$".into(), property.into());
+        }
+        {
+            let mut property = SchemaObject::default();
+            property.instance_type = Some(schemars::schema::SingleOrVec::Single(Box::new(
+                InstanceType::String,
+            )));
+            schema
+                .object()
+                .properties
+                .insert(NAMED_INSTANCE_KEY.into(), property.into());
+        }
+        schema.into()
+    }
+}
+
+/// Converts a four-character axis tag, like `wght`, into its numeric form.
+fn tag_from_str(tag: &str) -> Option<u32> {
+    let bytes: [u8; 4] = tag.as_bytes().try_into().ok()?;
+    bytes
+        .iter()
+        .all(|byte| byte.is_ascii_alphanumeric() || *byte == b' ')
+        .then(|| u32::from_be_bytes(bytes))
+}
+
+struct FvarAxis {
+    tag: u32,
+    min_value: f32,
+    max_value: f32,
+}
+
+struct FvarInstance {
+    subfamily_name_id: u16,
+    coordinates: Vec<f32>,
+}
+
+/// The axes and named instances of a variable font.
+///
+/// https://learn.microsoft.com/en-us/typography/opentype/spec/fvar
+struct FvarTable {
+    axes: Vec<FvarAxis>,
+    instances: Vec<FvarInstance>,
+}
+
+impl FvarTable {
+    fn parse(data: &[u8]) -> Option<Self> {
+        let axes_offset = read_u16(data, 4)? as usize;
+        let axis_count = read_u16(data, 8)? as usize;
+        let axis_size = read_u16(data, 10)? as usize;
+        let instance_count = read_u16(data, 12)? as usize;
+        let instance_size = read_u16(data, 14)? as usize;
+
+        let axes = (0..axis_count)
+            .map(|ix| {
+                let offset = axes_offset + ix * axis_size;
+                Some(FvarAxis {
+                    tag: read_u32(data, offset)?,
+                    min_value: read_fixed(data, offset + 4)?,
+                    max_value: read_fixed(data, offset + 12)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let instances_offset = axes_offset + axis_count * axis_size;
+        let instances = (0..instance_count)
+            .map(|ix| {
+                let offset = instances_offset + ix * instance_size;
+                Some(FvarInstance {
+                    subfamily_name_id: read_u16(data, offset)?,
+                    coordinates: (0..axis_count)
+                        .map(|axis_ix| read_fixed(data, offset + 4 + axis_ix * 4))
+                        .collect::<Option<_>>()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { axes, instances })
+    }
+}
+
+/// Returns a string from the font's `name` table, preferring Unicode records.
+///
+/// https://learn.microsoft.com/en-us/typography/opentype/spec/name
+fn name_table_string(data: &[u8], name_id: u16) -> Option<String> {
+    let count = read_u16(data, 2)? as usize;
+    let storage_offset = read_u16(data, 4)? as usize;
+    let mut mac_roman_name = None;
+    for ix in 0..count {
+        let record = 6 + ix * 12;
+        if read_u16(data, record + 6)? != name_id {
+            continue;
+        }
+        let platform_id = read_u16(data, record)?;
+        let encoding_id = read_u16(data, record + 2)?;
+        let length = read_u16(data, record + 8)? as usize;
+        let start = storage_offset + read_u16(data, record + 10)? as usize;
+        let bytes = data.get(start..start + length)?;
+        match (platform_id, encoding_id) {
+            // Unicode and Windows records are encoded in UTF-16BE.
+            (0, _) | (3, 0) | (3, 1) | (3, 10) => {
+                let units = bytes
+                    .chunks_exact(2)
+                    .map(|unit| u16::from_be_bytes([unit[0], unit[1]]));
+                return char::decode_utf16(units)
+                    .collect::<Result<String, _>>()
+                    .ok();
+            }
+            // Macintosh records use Mac Roman, which matches ASCII for the names of instances.
+            (1, 0) if bytes.is_ascii() => {
+                mac_roman_name.get_or_insert_with(|| String::from_utf8_lossy(bytes).into_owned());
+            }
+            _ => {}
+        }
+    }
+    mac_roman_name
+}
+
+pub(super) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+pub(super) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+/// Reads a 16.16 fixed-point number.
+fn read_fixed(data: &[u8], offset: usize) -> Option<f32> {
+    Some(read_u32(data, offset)? as i32 as f32 / 65536.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as gpui, font, px, Font, TestAppContext, TextRun, WindowTextSystem};
+
+    fn fixed(value: f32) -> [u8; 4] {
+        ((value * 65536.) as i32).to_be_bytes()
+    }
+
+    /// Builds an `fvar` table with `wght` and `wdth` axes and a "Condensed Bold" instance, and a
+    /// `name` table with the instance's name.
+    fn test_tables() -> (Vec<u8>, Vec<u8>) {
+        let mut fvar = Vec::new();
+        for value in [1, 0, 16, 2, 2, 20, 1, 12] {
+            fvar.extend(u16::to_be_bytes(value));
+        }
+        for (tag, min, default, max) in [(b"wght", 100., 400., 900.), (b"wdth", 75., 100., 100.)] {
+            fvar.extend(tag);
+            fvar.extend(fixed(min));
+            fvar.extend(fixed(default));
+            fvar.extend(fixed(max));
+            fvar.extend([0, 0, 1, 0]);
+        }
+        fvar.extend(u16::to_be_bytes(257));
+        fvar.extend([0, 0]);
+        fvar.extend(fixed(700.));
+        fvar.extend(fixed(75.));
+
+        let name_utf16 = "Condensed Bold"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect::<Vec<_>>();
+        let mut name = Vec::new();
+        for value in [0, 1, 18, 3, 1, 0x409, 257, name_utf16.len() as u16, 0] {
+            name.extend(u16::to_be_bytes(value));
+        }
+        name.extend(name_utf16);
+        (fvar, name)
+    }
+
+    #[test]
+    fn test_resolve_font_axes() {
+        let (fvar, name) = test_tables();
+        let wght = u32::from_be_bytes(*b"wght");
+        let wdth = u32::from_be_bytes(*b"wdth");
+
+        // Axes the font doesn't have are ignored, and values are clamped to the font's range.
+        let axes = FontAxes::new([("wght", 550.), ("wdth", 50.), ("opsz", 12.)]);
+        assert_eq!(
+            axes.resolve(&fvar, Some(&name)),
+            Some(vec![(wght, 550.), (wdth, 75.)])
+        );
+
+        // Explicit values take precedence over the named instance's.
+        let axes = FontAxes::new([("wght", 300.)]).named_instance("condensed bold");
+        assert_eq!(
+            axes.resolve(&fvar, Some(&name)),
+            Some(vec![(wght, 300.), (wdth, 75.)])
+        );
+        let axes = FontAxes::default().named_instance("Condensed Bold");
+        assert_eq!(
+            axes.resolve(&fvar, Some(&name)),
+            Some(vec![(wght, 700.), (wdth, 75.)])
+        );
+        let axes = FontAxes::default().named_instance("Ultra Expanded");
+        assert_eq!(axes.resolve(&fvar, Some(&name)), Some(vec![]));
+
+        assert_eq!(FontAxes::new([("wght", 700.)]).resolve(&[], None), None);
+    }
+
+    #[test]
+    fn test_deserialize_font_axes() {
+        let axes: FontAxes = serde_json::from_str(
+            r#"{"wght": 450, "wdth": 87.5, "instance": "Condensed", "bad!": 1, "wght": 460}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            axes,
+            FontAxes::new([("wdth", 87.5), ("wght", 460.)]).named_instance("Condensed")
+        );
+        assert_eq!(axes.get("wght"), Some(460.));
+        assert_ne!(axes, FontAxes::new([("wdth", 87.5), ("wght", 460.)]));
+        assert_eq!(
+            serde_json::from_str::<FontAxes>(&serde_json::to_string(&axes).unwrap()).unwrap(),
+            axes
+        );
+    }
+
+    #[gpui::test]
+    fn test_layout_line_with_font_axes(cx: &mut TestAppContext) {
+        // A variable font whose `m` widens from 400 to 1000 units as its weight goes from 100 to
+        // 900, with "Thin" and "Black" instances at either end.
+        cx.text_system().add_test_fonts();
+
+        cx.update(|cx| {
+            let text_system = WindowTextSystem::new(cx.text_system().clone());
+            let width = |axes: FontAxes| {
+                let run = TextRun::plain(
+                    2,
+                    Font {
+                        axes,
+                        ..font("Zed Test Variable")
+                    },
+                );
+                let layout = text_system.layout_line("mm", px(10.), &[run]).unwrap();
+                layout.width.0.round()
+            };
+
+            assert_eq!(width(FontAxes::default()), 12.);
+            assert_eq!(width(FontAxes::new([("wght", 100.)])), 8.);
+            assert_eq!(width(FontAxes::new([("wght", 900.)])), 20.);
+            assert_eq!(
+                width(FontAxes::default().named_instance("Black")),
+                width(FontAxes::new([("wght", 900.)]))
+            );
+            assert_eq!(
+                width(FontAxes::new([("wght", 2000.)])),
+                width(FontAxes::new([("wght", 900.)]))
+            );
+        });
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{FontId, FontRun, SharedString};
+
+/// The font families searched, in order, for characters missing from a font family that has no
+/// fallbacks of its own, covering CJK scripts, emoji and symbols on each platform.
+pub(crate) fn default_font_fallbacks() -> Vec<SharedString> {
+    let families: &[&str] = if cfg!(target_os = "macos") {
+        &[
+            "Menlo",
+            "PingFang SC",
+            "Hiragino Sans",
+            "Apple SD Gothic Neo",
+            "Apple Color Emoji",
+            "Apple Symbols",
+        ]
+    } else if cfg!(target_os = "windows") {
+        &[
+            "Consolas",
+            "Microsoft YaHei",
+            "Yu Gothic",
+            "Malgun Gothic",
+            "Segoe UI Emoji",
+            "Segoe UI Symbol",
+        ]
+    } else {
+        &[
+            "DejaVu Sans Mono",
+            "Noto Sans CJK SC",
+            "Noto Sans CJK JP",
+            "Noto Sans CJK KR",
+            "Noto Color Emoji",
+            "Symbola",
+        ]
+    };
+    families
+        .iter()
+        .map(|family| SharedString::from(*family))
+        .collect()
+}
+
+/// Appends runs covering `text` to `font_runs`, splitting it wherever the font resolved for its
+/// characters changes, and merging the first run into the last one if they share a font.
+///
+/// Characters that only modify the one before them, like combining marks, variation selectors
+/// and the parts of emoji sequences, stay in the same run so that they are shaped together.
+/// Control characters like tabs aren't looked up either, and use the run's font.
+pub(crate) fn push_font_runs(
+    text: &str,
+    font_id: FontId,
+    mut font_id_for_char: impl FnMut(char) -> FontId,
+    font_runs: &mut Vec<FontRun>,
+) {
+    let mut previous_char = None;
+    for ch in text.chars() {
+        let joins_previous_char = continues_cluster(ch) || ch.is_control();
+        let char_font_id = match (previous_char, font_runs.last()) {
+            (Some(previous_char), Some(last_run))
+                if joins_previous_char || previous_char == ZERO_WIDTH_JOINER =>
+            {
+                last_run.font_id
+            }
+            _ if joins_previous_char => font_id,
+            _ => font_id_for_char(ch),
+        };
+        match font_runs.last_mut() {
+            Some(last_run) if last_run.font_id == char_font_id => last_run.len += ch.len_utf8(),
+            _ => font_runs.push(FontRun {
+                len: ch.len_utf8(),
+                font_id: char_font_id,
+            }),
+        }
+        previous_char = Some(ch);
+    }
+}
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+fn continues_cluster(ch: char) -> bool {
+    matches!(
+        ch,
+        ZERO_WIDTH_JOINER
+            // Combining diacritical marks
+            | '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE20}'..='\u{FE2F}'
+            // Variation selectors
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{E0100}'..='\u{E01EF}'
+            // Emoji skin tone modifiers
+            | '\u{1F3FB}'..='\u{1F3FF}'
+            // Tags, used by subdivision flags
+            | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(target_os = "macos")]
+    use crate::{self as gpui, font, px, TestAppContext, TextRun, WindowTextSystem};
+
+    const PRIMARY: FontId = FontId(0);
+    const CJK: FontId = FontId(1);
+    const EMOJI: FontId = FontId(2);
+
+    /// Resolves ASCII to the primary font, emoji to the emoji font and anything else to the CJK
+    /// font, counting how often it is called.
+    fn font_runs(text: &str) -> (Vec<(usize, FontId)>, usize) {
+        let mut lookups = 0;
+        let mut font_runs = Vec::new();
+        push_font_runs(
+            text,
+            PRIMARY,
+            |ch| {
+                lookups += 1;
+                if ch.is_ascii() {
+                    PRIMARY
+                } else if ('\u{1F300}'..='\u{1FAFF}').contains(&ch) {
+                    EMOJI
+                } else {
+                    CJK
+                }
+            },
+            &mut font_runs,
+        );
+        let runs = font_runs
+            .into_iter()
+            .map(|run| (run.len, run.font_id))
+            .collect();
+        (runs, lookups)
+    }
+
+    #[test]
+    fn test_push_font_runs() {
+        // Latin, CJK and emoji in a single line.
+        let (runs, _) = font_runs("let 名前 = \"😀\";");
+        assert_eq!(
+            runs,
+            [
+                (4, PRIMARY),
+                ("名前".len(), CJK),
+                (4, PRIMARY),
+                ("😀".len(), EMOJI),
+                (2, PRIMARY),
+            ]
+        );
+
+        // Emoji sequences and combining marks stay with the character they modify, and control
+        // characters with the text before them.
+        let (runs, lookups) = font_runs("a👩🏽\u{200D}💻e\u{301}❤\u{FE0F}\t");
+        assert_eq!(
+            runs,
+            [
+                (1, PRIMARY),
+                ("👩🏽\u{200D}💻".len(), EMOJI),
+                ("e\u{301}".len(), PRIMARY),
+                ("❤\u{FE0F}\t".len(), CJK),
+            ]
+        );
+        assert_eq!(lookups, 4);
+
+        // Runs are merged with the previous run when they share a font.
+        let mut font_runs = vec![FontRun {
+            len: 3,
+            font_id: CJK,
+        }];
+        push_font_runs(
+            "中a",
+            PRIMARY,
+            |ch| if ch.is_ascii() { PRIMARY } else { CJK },
+            &mut font_runs,
+        );
+        assert_eq!(
+            font_runs,
+            [
+                FontRun {
+                    len: 6,
+                    font_id: CJK
+                },
+                FontRun {
+                    len: 1,
+                    font_id: PRIMARY
+                }
+            ]
+        );
+    }
+
+    // Which fonts are installed varies between systems, so this relies on the macOS defaults.
+    #[cfg(target_os = "macos")]
+    #[gpui::test]
+    fn test_layout_line_with_fallbacks(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let text_system = WindowTextSystem::new(cx.text_system().clone());
+            let font = font("Helvetica");
+            let font_id = text_system.resolve_font(&font);
+            let font_size = px(16.);
+            let layout_line = |text: &str| {
+                let run = TextRun::plain(text.len(), font.clone());
+                text_system.layout_line(text, font_size, &[run]).unwrap()
+            };
+
+            let latin = layout_line("let name = \"ok\";");
+            let mixed = layout_line("let 名前 = \"😀\";");
+            let glyph_fonts = mixed
+                .runs
+                .iter()
+                .map(|run| (run.font_id, run.glyphs.len()))
+                .collect::<Vec<_>>();
+            assert_eq!(glyph_fonts.len(), 5, "{glyph_fonts:?}");
+            assert_eq!(glyph_fonts[0], (font_id, 4));
+            assert_ne!(glyph_fonts[1].0, font_id);
+            assert_eq!(glyph_fonts[2], (font_id, 4));
+            assert_ne!(glyph_fonts[3].0, font_id);
+            assert_ne!(glyph_fonts[3].0, glyph_fonts[1].0);
+            assert!(mixed.runs[3].glyphs[0].is_emoji);
+            assert_eq!(glyph_fonts[4], (font_id, 2));
+
+            // The line keeps the primary font's metrics, so it lines up with other lines.
+            assert_eq!(mixed.ascent, latin.ascent);
+            assert_eq!(mixed.descent, latin.descent);
+            assert_eq!(mixed.ascent, text_system.ascent(font_id, font_size));
+
+            // Resolving the fallbacks is cached per character.
+            let cached_fonts = text_system.font_ids_by_char.read().len();
+            layout_line("名前 😀");
+            assert_eq!(text_system.font_ids_by_char.read().len(), cached_fonts);
+        });
+    }
+}
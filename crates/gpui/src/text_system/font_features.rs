@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use schemars::schema::{InstanceType, SchemaObject};
 
@@ -7,6 +7,19 @@ use schemars::schema::{InstanceType, SchemaObject};
 pub struct FontFeatures(pub Arc<Vec<(String, u32)>>);
 
 impl FontFeatures {
+    /// Create a set of OpenType features from their tags and values, where 0 disables a feature,
+    /// 1 enables it and larger values select one of its alternates.
+    ///
+    /// The features are sorted by tag, and a tag that is given more than once takes its last
+    /// value, so that equal sets of features resolve to the same font and share cached layouts.
+    pub fn new(features: impl IntoIterator<Item = (impl Into<String>, u32)>) -> Self {
+        let features = features
+            .into_iter()
+            .map(|(tag, value)| (tag.into(), value))
+            .collect::<BTreeMap<_, _>>();
+        Self(Arc::new(features.into_iter().collect()))
+    }
+
     /// Get the tag name list of the font OpenType features
     /// only enabled or disabled features are returned
     pub fn tag_value_list(&self) -> &[(String, u32)] {
@@ -87,7 +100,7 @@ impl<'de> serde::Deserialize<'de> for FontFeatures {
                     }
                 }
 
-                Ok(FontFeatures(Arc::new(feature_list)))
+                Ok(FontFeatures::new(feature_list))
             }
         }
 
@@ -146,3 +159,77 @@ impl schemars::JsonSchema for FontFeatures {
 fn is_valid_feature_tag(tag: &str) -> bool {
     tag.len() == 4 && tag.chars().all(|c| c.is_ascii_alphanumeric())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    use crate::{self as gpui, font, px, Font, TestAppContext, TextRun, WindowTextSystem};
+
+    #[test]
+    fn test_deserialize_font_features() {
+        let features: FontFeatures = serde_json::from_str(
+            r#"{"ss01": true, "calt": false, "cv02": 3, "zero": null, "bad!": 1, "calt": true}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            features.tag_value_list(),
+            [
+                ("calt".to_string(), 1),
+                ("cv02".to_string(), 3),
+                ("ss01".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            FontFeatures::new([("liga", 0), ("calt", 0)]),
+            FontFeatures::new([("calt", 0), ("liga", 0)])
+        );
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    #[gpui::test]
+    fn test_layout_line_with_features(cx: &mut TestAppContext) {
+        cx.text_system().add_test_fonts();
+
+        cx.update(|cx| {
+            let text_system = WindowTextSystem::new(cx.text_system().clone());
+            let glyph_ids = |text: &str, font: Font| {
+                let run = TextRun::plain(text.len(), font);
+                let layout = text_system.layout_line(text, px(16.), &[run]).unwrap();
+                layout
+                    .runs
+                    .iter()
+                    .flat_map(|run| run.glyphs.iter().map(|glyph| glyph.id))
+                    .collect::<Vec<_>>()
+            };
+            let with_features = |family: &str, features: &[(&str, u32)]| Font {
+                features: FontFeatures::new(features.iter().copied()),
+                ..font(family.to_string())
+            };
+
+            // Ligatures replace several characters with a single glyph.
+            let ligature = glyph_ids("fi", with_features("Zed Plex Sans", &[]));
+            let no_ligature = glyph_ids("fi", with_features("Zed Plex Sans", &[("liga", 0)]));
+            assert_eq!(ligature.len(), 1);
+            assert_eq!(no_ligature.len(), 2);
+
+            // Contextual alternates substitute the glyphs of operators like `=>`. Toggling them
+            // doesn't reuse the layout cached for the other setting.
+            let alternates = glyph_ids("=>", with_features("Zed Plex Mono", &[]));
+            let no_alternates = glyph_ids("=>", with_features("Zed Plex Mono", &[("calt", 0)]));
+            assert_eq!(alternates.len(), 2);
+            assert_eq!(no_alternates.len(), 2);
+            assert_ne!(alternates, no_alternates);
+            assert_eq!(
+                glyph_ids("=>", with_features("Zed Plex Mono", &[("calt", 1)])),
+                alternates
+            );
+
+            // Stylistic alternates, like a slashed zero.
+            assert_ne!(
+                glyph_ids("0", with_features("Zed Plex Mono", &[("zero", 1)])),
+                glyph_ids("0", with_features("Zed Plex Mono", &[]))
+            );
+        });
+    }
+}
@@ -127,53 +127,58 @@ fn paint_line(
         let mut current_strikethrough: Option<(Point<Pixels>, StrikethroughStyle)> = None;
         let mut current_background: Option<(Point<Pixels>, Hsla)> = None;
         let text_system = cx.text_system().clone();
+        // Bidirectional lines aren't painted from left to right in the order of their glyphs,
+        // so where each glyph goes is worked out upfront.
+        let painted_glyphs =
+            (!layout.bidi_runs.is_empty()).then(|| layout.painted_glyphs(wrap_boundaries));
+        let mut painted_glyph_ix = 0;
         let mut glyph_origin = origin;
         let mut prev_glyph_position = Point::default();
         for (run_ix, run) in layout.runs.iter().enumerate() {
             let max_glyph_size = text_system.bounding_box(run.font_id, layout.font_size).size;
 
             for (glyph_ix, glyph) in run.glyphs.iter().enumerate() {
-                glyph_origin.x += glyph.position.x - prev_glyph_position.x;
-
-                if wraps.peek() == Some(&&WrapBoundary { run_ix, glyph_ix }) {
-                    wraps.next();
-                    if let Some((background_origin, background_color)) = current_background.as_mut()
-                    {
-                        cx.paint_quad(fill(
-                            Bounds {
-                                origin: *background_origin,
-                                size: size(glyph_origin.x - background_origin.x, line_height),
-                            },
-                            *background_color,
-                        ));
-                        background_origin.x = origin.x;
-                        background_origin.y += line_height;
-                    }
-                    if let Some((underline_origin, underline_style)) = current_underline.as_mut() {
-                        cx.paint_underline(
-                            *underline_origin,
-                            glyph_origin.x - underline_origin.x,
-                            underline_style,
+                if let Some(painted_glyphs) = &painted_glyphs {
+                    let painted = painted_glyphs[painted_glyph_ix];
+                    let painted_origin = point(
+                        origin.x + painted.x,
+                        origin.y + line_height * painted.line_ix as f32,
+                    );
+                    if painted_glyph_ix > 0 && !painted.follows_previous {
+                        let prev = painted_glyphs[painted_glyph_ix - 1];
+                        split_decorations(
+                            origin.x + prev.x + prev.advance,
+                            painted_origin.x,
+                            painted_origin.y - glyph_origin.y,
+                            line_height,
+                            &mut current_background,
+                            &mut current_underline,
+                            &mut current_strikethrough,
+                            cx,
                         );
-                        underline_origin.x = origin.x;
-                        underline_origin.y += line_height;
                     }
-                    if let Some((strikethrough_origin, strikethrough_style)) =
-                        current_strikethrough.as_mut()
-                    {
-                        cx.paint_strikethrough(
-                            *strikethrough_origin,
-                            glyph_origin.x - strikethrough_origin.x,
-                            strikethrough_style,
+                    glyph_origin = painted_origin;
+                    painted_glyph_ix += 1;
+                } else {
+                    glyph_origin.x += glyph.position.x - prev_glyph_position.x;
+
+                    if wraps.peek() == Some(&&WrapBoundary { run_ix, glyph_ix }) {
+                        wraps.next();
+                        split_decorations(
+                            glyph_origin.x,
+                            origin.x,
+                            line_height,
+                            line_height,
+                            &mut current_background,
+                            &mut current_underline,
+                            &mut current_strikethrough,
+                            cx,
                         );
-                        strikethrough_origin.x = origin.x;
-                        strikethrough_origin.y += line_height;
+                        glyph_origin.x = origin.x;
+                        glyph_origin.y += line_height;
                     }
-
-                    glyph_origin.x = origin.x;
-                    glyph_origin.y += line_height;
+                    prev_glyph_position = glyph.position;
                 }
-                prev_glyph_position = glyph.position;
 
                 let mut finished_background: Option<(Point<Pixels>, Hsla)> = None;
                 let mut finished_underline: Option<(Point<Pixels>, UnderlineStyle)> = None;
@@ -293,7 +298,9 @@ fn paint_line(
         }
 
         let mut last_line_end_x = origin.x + layout.width;
-        if let Some(boundary) = wrap_boundaries.last() {
+        if let Some(last) = painted_glyphs.as_ref().and_then(|glyphs| glyphs.last()) {
+            last_line_end_x = origin.x + last.x + last.advance;
+        } else if let Some(boundary) = wrap_boundaries.last() {
             let run = &layout.runs[boundary.run_ix];
             let glyph = &run.glyphs[boundary.glyph_ix];
             last_line_end_x -= glyph.position.x;
@@ -328,3 +335,122 @@ fn paint_line(
         Ok(())
     })
 }
+
+/// Ends the decorations being painted at `end_x`, and continues them from `restart_x`, `y_offset`
+/// further down.
+#[allow(clippy::too_many_arguments)]
+fn split_decorations(
+    end_x: Pixels,
+    restart_x: Pixels,
+    y_offset: Pixels,
+    line_height: Pixels,
+    current_background: &mut Option<(Point<Pixels>, Hsla)>,
+    current_underline: &mut Option<(Point<Pixels>, UnderlineStyle)>,
+    current_strikethrough: &mut Option<(Point<Pixels>, StrikethroughStyle)>,
+    cx: &mut WindowContext,
+) {
+    if let Some((background_origin, background_color)) = current_background.as_mut() {
+        cx.paint_quad(fill(
+            Bounds {
+                origin: *background_origin,
+                size: size(end_x - background_origin.x, line_height),
+            },
+            *background_color,
+        ));
+        background_origin.x = restart_x;
+        background_origin.y += y_offset;
+    }
+    if let Some((underline_origin, underline_style)) = current_underline.as_mut() {
+        cx.paint_underline(
+            *underline_origin,
+            end_x - underline_origin.x,
+            underline_style,
+        );
+        underline_origin.x = restart_x;
+        underline_origin.y += y_offset;
+    }
+    if let Some((strikethrough_origin, strikethrough_style)) = current_strikethrough.as_mut() {
+        cx.paint_strikethrough(
+            *strikethrough_origin,
+            end_x - strikethrough_origin.x,
+            strikethrough_style,
+        );
+        strikethrough_origin.x = restart_x;
+        strikethrough_origin.y += y_offset;
+    }
+}
+
+// Color emoji come from Apple Color Emoji, as a fallback of the system font.
+#[cfg(all(test, target_os = "macos"))]
+mod tests {
+    use super::*;
+    use crate::{self as gpui, canvas, font, TestAppContext, TextRun};
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    fn test_paint_color_emoji(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = "🙂 a 👍🏽";
+        let run = TextRun {
+            len: text.len(),
+            font: font(".SystemUIFont"),
+            color: black(),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+
+        let line = cx.update(|cx| {
+            cx.text_system()
+                .shape_line(text.into(), px(16.), &[run])
+                .unwrap()
+        });
+        // The skin tone modifier is shaped with its emoji, and the emoji are hit as a whole.
+        let glyphs = line
+            .runs
+            .iter()
+            .flat_map(|run| &run.glyphs)
+            .collect::<Vec<_>>();
+        assert_eq!(glyphs.len(), 5);
+        let emoji_ixs = glyphs
+            .iter()
+            .filter(|glyph| glyph.is_emoji)
+            .map(|glyph| glyph.index)
+            .collect::<Vec<_>>();
+        assert_eq!(emoji_ixs, [0, text.find('👍').unwrap()]);
+        let thumbs_up = glyphs[4];
+        let thumbs_up_x = thumbs_up.position.x + px(1.);
+        assert_eq!(line.index_for_x(thumbs_up_x), Some(thumbs_up.index));
+
+        let sprites = Rc::new(RefCell::new(None));
+        cx.draw(point(px(0.), px(0.)), size(px(200.), px(50.)), |_| {
+            let sprites = sprites.clone();
+            canvas(
+                |_, _| {},
+                move |bounds, _, cx| {
+                    line.paint(bounds.origin, px(20.), cx).unwrap();
+                    let scene = &cx.window.next_frame.scene;
+                    *sprites.borrow_mut() = Some((
+                        scene.polychrome_sprites.clone(),
+                        scene.monochrome_sprites.clone(),
+                    ));
+                },
+            )
+            .size_full()
+        });
+
+        let scale_factor = cx.update(|cx| cx.scale_factor());
+        let (emoji, glyphs) = sprites.take().unwrap();
+        assert_eq!(emoji.len(), 2);
+        assert_eq!(glyphs.len(), 1);
+        let a = glyphs[0].bounds;
+        for emoji in &emoji {
+            let bounds = emoji.bounds;
+            assert!(bounds.size.width.0 > 0. && bounds.size.height.0 > 0.);
+            // Emoji sit on the baseline of the text, and are about as tall as the font size.
+            assert!(bounds.origin.y < a.origin.y + a.size.height);
+            assert!(bounds.origin.y + bounds.size.height > a.origin.y);
+            assert!(bounds.size.height.0 < 16. * 1.5 * scale_factor);
+        }
+    }
+}
@@ -1,16 +1,20 @@
-use crate::{point, px, FontId, GlyphId, Pixels, PlatformTextSystem, Point, Size};
+use crate::{
+    point, px, visual_runs, BidiRun, FontId, GlyphId, LineWrapper, Pixels, PlatformTextSystem,
+    Point, Size,
+};
 use collections::FxHashMap;
 use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
 use smallvec::SmallVec;
 use std::{
     borrow::Borrow,
     hash::{Hash, Hasher},
+    num::NonZeroU32,
     ops::Range,
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 
 /// A laid out and styled line of text
-#[derive(Default, Debug)]
+#[derive(Clone, Default, Debug)]
 pub struct LineLayout {
     /// The font size for this line
     pub font_size: Pixels,
@@ -24,10 +28,15 @@ pub struct LineLayout {
     pub runs: Vec<ShapedRun>,
     /// The length of the line in utf-8 bytes
     pub len: usize,
+    /// The directional runs of the line in visual order, which is empty when the whole line
+    /// flows from left to right
+    pub bidi_runs: Vec<BidiRun>,
+    /// The characters of the line from left to right, computed when carets are first placed.
+    pub(crate) visual_cells: OnceLock<Vec<VisualCell>>,
 }
 
 /// A run of text that has been shaped .
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ShapedRun {
     /// The font id for this run
     pub font_id: FontId,
@@ -35,6 +44,70 @@ pub struct ShapedRun {
     pub glyphs: SmallVec<[ShapedGlyph; 8]>,
 }
 
+/// A position of the caret in a line of text.
+///
+/// Where the direction of the text changes, the same index can be displayed at two places: after
+/// the character preceding it, and before the character following it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CaretPosition {
+    /// The index of the caret in the line, in utf-8 bytes.
+    pub index: usize,
+    /// The x coordinate of the caret.
+    pub x: Pixels,
+}
+
+/// A character, or cluster of characters shaped together, and the horizontal extent of its glyphs.
+#[derive(Clone, Debug)]
+pub(crate) struct VisualCell {
+    range: Range<usize>,
+    x: Range<Pixels>,
+    is_rtl: bool,
+}
+
+impl VisualCell {
+    /// The caret at the left edge of the cell.
+    fn left_caret(&self) -> CaretPosition {
+        CaretPosition {
+            index: if self.is_rtl {
+                self.range.end
+            } else {
+                self.range.start
+            },
+            x: self.x.start,
+        }
+    }
+
+    /// The caret at the right edge of the cell.
+    fn right_caret(&self) -> CaretPosition {
+        CaretPosition {
+            index: if self.is_rtl {
+                self.range.start
+            } else {
+                self.range.end
+            },
+            x: self.x.end,
+        }
+    }
+
+    fn center(&self) -> Pixels {
+        (self.x.start + self.x.end) / 2.
+    }
+}
+
+/// Where a glyph is painted in a line that may have been wrapped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PaintedGlyph {
+    /// The wrapped line the glyph is painted on.
+    pub line_ix: usize,
+    /// The x coordinate of the glyph in its wrapped line.
+    pub x: Pixels,
+    /// The horizontal space taken by the glyph.
+    pub advance: Pixels,
+    /// Whether the glyph is painted right after the glyph preceding it in the text, so that
+    /// decorations can run across both.
+    pub follows_previous: bool,
+}
+
 /// A single glyph, ready to paint.
 #[derive(Clone, Debug)]
 pub struct ShapedGlyph {
@@ -56,6 +129,14 @@ impl LineLayout {
     pub fn index_for_x(&self, x: Pixels) -> Option<usize> {
         if x >= self.width {
             None
+        } else if !self.bidi_runs.is_empty() {
+            let cells = self.visual_cells();
+            let cell = cells
+                .iter()
+                .rev()
+                .find(|cell| cell.x.start <= x)
+                .or(cells.first());
+            Some(cell.map_or(0, |cell| cell.range.start))
         } else {
             for run in self.runs.iter().rev() {
                 for glyph in run.glyphs.iter().rev() {
@@ -71,6 +152,18 @@ impl LineLayout {
     /// closest_index_for_x returns the character boundary closest to the given x coordinate
     /// (e.g. to handle aligning up/down arrow keys)
     pub fn closest_index_for_x(&self, x: Pixels) -> usize {
+        if !self.bidi_runs.is_empty() {
+            let cells = self.visual_cells();
+            let Some(cell) = cells.iter().find(|cell| x < cell.x.end).or(cells.last()) else {
+                return 0;
+            };
+            return if x < cell.center() {
+                cell.left_caret().index
+            } else {
+                cell.right_caret().index
+            };
+        }
+
         let mut prev_index = 0;
         let mut prev_x = px(0.);
 
@@ -93,6 +186,10 @@ impl LineLayout {
 
     /// The x position of the character at the given index
     pub fn x_for_index(&self, index: usize) -> Pixels {
+        if !self.bidi_runs.is_empty() {
+            return self.caret_positions_for_index(index)[0];
+        }
+
         for run in &self.runs {
             for glyph in &run.glyphs {
                 if glyph.index >= index {
@@ -115,6 +212,205 @@ impl LineLayout {
         None
     }
 
+    /// The x positions where the caret at the given index can be displayed. The first one is
+    /// before the character following the caret, and there is a second one where the direction of
+    /// the text changes, after the character preceding the caret.
+    pub fn caret_positions_for_index(&self, index: usize) -> SmallVec<[Pixels; 2]> {
+        let cells = self.visual_cells();
+        let mut positions = SmallVec::new();
+        if let Some(cell) = cells.iter().find(|cell| cell.range.contains(&index)) {
+            positions.push(if cell.is_rtl {
+                cell.x.end
+            } else {
+                cell.x.start
+            });
+        }
+        if let Some(cell) = cells.iter().find(|cell| cell.range.end == index) {
+            let x = if cell.is_rtl {
+                cell.x.start
+            } else {
+                cell.x.end
+            };
+            if !positions.contains(&x) {
+                positions.push(x);
+            }
+        }
+        if positions.is_empty() {
+            positions.push(self.width);
+        }
+        positions
+    }
+
+    /// The caret at the given index, displayed before the character following it.
+    pub fn caret_for_index(&self, index: usize) -> CaretPosition {
+        CaretPosition {
+            index,
+            x: self.caret_positions_for_index(index)[0],
+        }
+    }
+
+    /// Moves the caret one character to the left on screen, which goes back in the text where it
+    /// flows from left to right and forward where it flows from right to left. Returns `None` at
+    /// the left end of the line.
+    pub fn visual_left(&self, caret: CaretPosition) -> Option<CaretPosition> {
+        self.visual_cells()
+            .iter()
+            .rev()
+            .find(|cell| cell.center() < caret.x)
+            .map(VisualCell::left_caret)
+    }
+
+    /// Moves the caret one character to the right on screen, which goes forward in the text where
+    /// it flows from left to right and back where it flows from right to left. Returns `None` at
+    /// the right end of the line.
+    pub fn visual_right(&self, caret: CaretPosition) -> Option<CaretPosition> {
+        self.visual_cells()
+            .iter()
+            .find(|cell| cell.center() > caret.x)
+            .map(VisualCell::right_caret)
+    }
+
+    fn is_rtl_at(&self, index: usize) -> bool {
+        self.bidi_runs
+            .iter()
+            .any(|run| run.is_rtl && run.range.contains(&index))
+    }
+
+    /// Returns the characters of the line from left to right, merging the glyphs of clusters.
+    fn visual_cells(&self) -> &[VisualCell] {
+        self.visual_cells
+            .get_or_init(|| self.compute_visual_cells())
+    }
+
+    fn compute_visual_cells(&self) -> Vec<VisualCell> {
+        let mut glyphs = self
+            .runs
+            .iter()
+            .flat_map(|run| {
+                run.glyphs
+                    .iter()
+                    .map(|glyph| (glyph.index, glyph.position.x))
+            })
+            .collect::<Vec<_>>();
+        glyphs.sort_by_key(|(_, x)| *x);
+        let mut indices = glyphs.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut cells = Vec::<VisualCell>::with_capacity(glyphs.len());
+        for (glyph_ix, &(index, x)) in glyphs.iter().enumerate() {
+            let right = glyphs
+                .get(glyph_ix + 1)
+                .map_or(self.width, |(_, next_x)| *next_x);
+            if let Some(cell) = cells.last_mut().filter(|cell| cell.range.start == index) {
+                cell.x.end = right;
+                continue;
+            }
+            let end = indices
+                .get(indices.partition_point(|&other_index| other_index <= index))
+                .copied()
+                .unwrap_or(self.len);
+            cells.push(VisualCell {
+                range: index..end,
+                x: x..right,
+                is_rtl: self.is_rtl_at(index),
+            });
+        }
+        cells
+    }
+
+    /// Moves the glyphs following each tab to the next tab stop, which are `tab_width` apart
+    /// for the font of the tab.
+    fn expand_tabs(&mut self, text: &str, mut tab_width: impl FnMut(FontId) -> Pixels) {
+        let glyph_xs = self
+            .runs
+            .iter()
+            .flat_map(|run| run.glyphs.iter().map(|glyph| glyph.position.x))
+            .chain([self.width])
+            .collect::<Vec<_>>();
+        self.visual_cells = OnceLock::new();
+        let mut shift = px(0.);
+        let mut glyph_ix = 0;
+        for run in &mut self.runs {
+            for glyph in &mut run.glyphs {
+                let x = glyph_xs[glyph_ix] + shift;
+                glyph.position.x = x;
+                if text[glyph.index..].starts_with('\t') {
+                    let tab_width = tab_width(run.font_id);
+                    let next_tab_stop = tab_width * ((x / tab_width).floor() + 1.);
+                    shift = next_tab_stop - glyph_xs[glyph_ix + 1];
+                }
+                glyph_ix += 1;
+            }
+        }
+        self.width += shift;
+    }
+
+    /// Returns the horizontal space taken by each glyph, in the order of `runs`, up to the glyph
+    /// that follows it on screen.
+    fn glyph_advances(&self) -> Vec<Pixels> {
+        let xs = self
+            .runs
+            .iter()
+            .flat_map(|run| run.glyphs.iter().map(|glyph| glyph.position.x))
+            .collect::<Vec<_>>();
+        let mut visual_order = (0..xs.len()).collect::<Vec<_>>();
+        if !self.bidi_runs.is_empty() {
+            visual_order.sort_by_key(|&glyph_ix| xs[glyph_ix]);
+        }
+        let mut advances = vec![px(0.); xs.len()];
+        for (rank, &glyph_ix) in visual_order.iter().enumerate() {
+            let next_x = visual_order
+                .get(rank + 1)
+                .map_or(self.width, |&next_ix| xs[next_ix]);
+            advances[glyph_ix] = next_x - xs[glyph_ix];
+        }
+        advances
+    }
+
+    /// Returns where each glyph is painted when the line is wrapped at the given boundaries, in
+    /// the order of `runs`. The glyphs of each wrapped line keep the order they have on screen in
+    /// the unwrapped line, so text that flows from right to left still does once wrapped.
+    pub(crate) fn painted_glyphs(&self, wrap_boundaries: &[WrapBoundary]) -> Vec<PaintedGlyph> {
+        let advances = self.glyph_advances();
+        let mut glyphs = Vec::with_capacity(advances.len());
+        let mut wraps = wrap_boundaries.iter().peekable();
+        let mut line_ix = 0;
+        for (run_ix, run) in self.runs.iter().enumerate() {
+            for (glyph_ix, glyph) in run.glyphs.iter().enumerate() {
+                if wraps.peek() == Some(&&WrapBoundary { run_ix, glyph_ix }) {
+                    wraps.next();
+                    line_ix += 1;
+                }
+                glyphs.push(PaintedGlyph {
+                    line_ix,
+                    x: glyph.position.x,
+                    advance: advances[glyphs.len()],
+                    follows_previous: false,
+                });
+            }
+        }
+
+        let mut visual_order = (0..glyphs.len()).collect::<Vec<_>>();
+        visual_order.sort_by_key(|&glyph_ix| (glyphs[glyph_ix].line_ix, glyphs[glyph_ix].x));
+        let mut ranks = vec![0; glyphs.len()];
+        let mut x = px(0.);
+        for (rank, &glyph_ix) in visual_order.iter().enumerate() {
+            if rank > 0 && glyphs[visual_order[rank - 1]].line_ix != glyphs[glyph_ix].line_ix {
+                x = px(0.);
+            }
+            glyphs[glyph_ix].x = x;
+            x += glyphs[glyph_ix].advance;
+            ranks[glyph_ix] = rank;
+        }
+        for glyph_ix in 1..glyphs.len() {
+            glyphs[glyph_ix].follows_previous = glyphs[glyph_ix].line_ix
+                == glyphs[glyph_ix - 1].line_ix
+                && ranks[glyph_ix] == ranks[glyph_ix - 1] + 1;
+        }
+        glyphs
+    }
+
     fn compute_wrap_boundaries(
         &self,
         text: &str,
@@ -131,6 +427,18 @@ impl LineLayout {
         };
         let mut last_boundary_x = px(0.);
         let mut prev_ch = '\0';
+        // The glyphs of bidirectional lines aren't ordered by their x coordinates, so the widths
+        // of their wrapped lines are measured by adding up the advances of their glyphs instead.
+        let logical_xs = (!self.bidi_runs.is_empty()).then(|| {
+            self.glyph_advances()
+                .into_iter()
+                .scan(px(0.), |x, advance| {
+                    let glyph_x = *x;
+                    *x += advance;
+                    Some(glyph_x)
+                })
+                .collect::<Vec<_>>()
+        });
         let mut glyphs = self
             .runs
             .iter()
@@ -138,13 +446,16 @@ impl LineLayout {
             .flat_map(move |(run_ix, run)| {
                 run.glyphs.iter().enumerate().map(move |(glyph_ix, glyph)| {
                     let character = text[glyph.index..].chars().next().unwrap();
-                    (
-                        WrapBoundary { run_ix, glyph_ix },
-                        character,
-                        glyph.position.x,
-                    )
+                    (WrapBoundary { run_ix, glyph_ix }, character, glyph)
                 })
             })
+            .enumerate()
+            .map(|(ix, (boundary, character, glyph))| {
+                let x = logical_xs
+                    .as_ref()
+                    .map_or(glyph.position.x, |logical_xs| logical_xs[ix]);
+                (boundary, character, x)
+            })
             .peekable();
 
         while let Some((boundary, ch, x)) = glyphs.next() {
@@ -152,18 +463,29 @@ impl LineLayout {
                 continue;
             }
 
-            if prev_ch == ' ' && ch != ' ' && first_non_whitespace_ix.is_some() {
-                last_candidate_ix = Some(boundary);
-                last_candidate_x = x;
+            if LineWrapper::is_word_char(ch) {
+                if prev_ch == ' ' && ch != ' ' && first_non_whitespace_ix.is_some() {
+                    last_candidate_ix = Some(boundary);
+                    last_candidate_x = x;
+                }
+            } else {
+                // CJK may not be space separated, e.g.: `Hello world你好世界`
+                if ch != ' ' && first_non_whitespace_ix.is_some() {
+                    last_candidate_ix = Some(boundary);
+                    last_candidate_x = x;
+                }
             }
 
             if ch != ' ' && first_non_whitespace_ix.is_none() {
                 first_non_whitespace_ix = Some(boundary);
             }
 
+            // Whitespace after the indentation hangs past the wrap width, so that a line is never
+            // wrapped just to start with the spaces that followed its last word.
+            let is_hanging_whitespace = ch == ' ' && first_non_whitespace_ix.is_some();
             let next_x = glyphs.peek().map_or(self.width, |(_, _, x)| *x);
             let width = next_x - last_boundary_x;
-            if width > wrap_width && boundary > last_boundary {
+            if width > wrap_width && boundary > last_boundary && !is_hanging_whitespace {
                 if let Some(last_candidate_ix) = last_candidate_ix.take() {
                     last_boundary = last_candidate_ix;
                     last_boundary_x = last_candidate_x;
@@ -258,6 +580,9 @@ impl WrappedLineLayout {
         line_height: Pixels,
     ) -> Result<usize, usize> {
         let wrapped_line_ix = (position.y / line_height) as usize;
+        if !self.unwrapped_layout.bidi_runs.is_empty() && !self.wrap_boundaries.is_empty() {
+            return self.bidi_index_for_position(position.x, wrapped_line_ix);
+        }
 
         let wrapped_line_start_index;
         let wrapped_line_start_x;
@@ -304,6 +629,10 @@ impl WrappedLineLayout {
 
     /// Returns the pixel position for the given byte index.
     pub fn position_for_index(&self, index: usize, line_height: Pixels) -> Option<Point<Pixels>> {
+        if !self.unwrapped_layout.bidi_runs.is_empty() && !self.wrap_boundaries.is_empty() {
+            return self.bidi_position_for_index(index, line_height);
+        }
+
         let mut line_start_ix = 0;
         let mut line_end_indices = self
             .wrap_boundaries
@@ -331,6 +660,54 @@ impl WrappedLineLayout {
 
         None
     }
+
+    /// Each wrapped line of a bidirectional line places its glyphs in the order they have in the
+    /// unwrapped line, so positions in it are found by moving between the glyphs of both.
+    fn bidi_index_for_position(&self, x: Pixels, wrapped_line_ix: usize) -> Result<usize, usize> {
+        let layout = &self.unwrapped_layout;
+        let painted_glyphs = layout.painted_glyphs(&self.wrap_boundaries);
+        let glyphs = layout.runs.iter().flat_map(|run| run.glyphs.iter());
+        let mut line_glyphs = glyphs
+            .zip(&painted_glyphs)
+            .filter(|(_, painted)| painted.line_ix == wrapped_line_ix)
+            .peekable();
+        let Some(line_start_ix) = line_glyphs.peek().map(|(glyph, _)| glyph.index) else {
+            return Err(0);
+        };
+
+        for (glyph, painted) in line_glyphs {
+            if painted.x <= x && x < painted.x + painted.advance {
+                let unwrapped_x = glyph.position.x + (x - painted.x);
+                return Ok(layout.index_for_x(unwrapped_x).unwrap_or(glyph.index));
+            }
+        }
+        if x < px(0.) {
+            Err(line_start_ix)
+        } else {
+            Err(self
+                .wrap_boundaries
+                .get(wrapped_line_ix)
+                .map_or(layout.len, |boundary| {
+                    layout.runs[boundary.run_ix].glyphs[boundary.glyph_ix].index
+                }))
+        }
+    }
+
+    fn bidi_position_for_index(&self, index: usize, line_height: Pixels) -> Option<Point<Pixels>> {
+        let layout = &self.unwrapped_layout;
+        let painted_glyphs = layout.painted_glyphs(&self.wrap_boundaries);
+        // The caret is displayed next to the character following it, which moves along with its
+        // glyph when the line is wrapped.
+        let (glyph, painted) = layout
+            .runs
+            .iter()
+            .flat_map(|run| run.glyphs.iter())
+            .zip(&painted_glyphs)
+            .take_while(|(glyph, _)| glyph.index <= index)
+            .last()?;
+        let x = layout.x_for_index(index) - glyph.position.x + painted.x;
+        Some(point(x, line_height * painted.line_ix as f32))
+    }
 }
 
 pub(crate) struct LineLayoutCache {
@@ -414,13 +791,17 @@ impl LineLayoutCache {
         text: &str,
         font_size: Pixels,
         runs: &[FontRun],
+        metrics_font_id: Option<FontId>,
         wrap_width: Option<Pixels>,
+        tab_size: Option<NonZeroU32>,
     ) -> Arc<WrappedLineLayout> {
         let key = &CacheKeyRef {
             text,
             font_size,
             runs,
+            metrics_font_id,
             wrap_width,
+            tab_size,
         } as &dyn AsCacheKeyRef;
 
         let current_frame = self.current_frame.upgradable_read();
@@ -439,7 +820,18 @@ impl LineLayoutCache {
         } else {
             drop(current_frame);
 
-            let unwrapped_layout = self.layout_line(text, font_size, runs);
+            let mut unwrapped_layout = self.layout_line(text, font_size, runs, metrics_font_id);
+            // Tabs are only expanded in lines flowing from left to right, where the glyphs
+            // following them are further to the right.
+            if let Some(tab_size) =
+                tab_size.filter(|_| text.contains('\t') && unwrapped_layout.bidi_runs.is_empty())
+            {
+                let mut layout = LineLayout::clone(&unwrapped_layout);
+                layout.expand_tabs(text, |font_id| {
+                    self.space_width(font_id, font_size) * tab_size.get() as f32
+                });
+                unwrapped_layout = Arc::new(layout);
+            }
             let wrap_boundaries = if let Some(wrap_width) = wrap_width {
                 unwrapped_layout.compute_wrap_boundaries(text.as_ref(), wrap_width)
             } else {
@@ -454,7 +846,9 @@ impl LineLayoutCache {
                 text: text.into(),
                 font_size,
                 runs: SmallVec::from(runs),
+                metrics_font_id,
                 wrap_width,
+                tab_size,
             });
 
             let mut current_frame = self.current_frame.write();
@@ -467,12 +861,22 @@ impl LineLayoutCache {
         }
     }
 
-    pub fn layout_line(&self, text: &str, font_size: Pixels, runs: &[FontRun]) -> Arc<LineLayout> {
+    /// Lays out a line, with the ascent and descent of the font with the given id if there is one,
+    /// so that lines don't shift when some of their characters are rendered with a fallback font.
+    pub fn layout_line(
+        &self,
+        text: &str,
+        font_size: Pixels,
+        runs: &[FontRun],
+        metrics_font_id: Option<FontId>,
+    ) -> Arc<LineLayout> {
         let key = &CacheKeyRef {
             text,
             font_size,
             runs,
+            metrics_font_id,
             wrap_width: None,
+            tab_size: None,
         } as &dyn AsCacheKeyRef;
 
         let current_frame = self.current_frame.upgradable_read();
@@ -486,18 +890,98 @@ impl LineLayoutCache {
             current_frame.used_lines.push(key);
             layout
         } else {
-            let layout = Arc::new(self.platform_text_system.layout_line(text, font_size, runs));
+            let mut layout = self.shape_line(text, font_size, runs);
+            if let Some(font_id) = metrics_font_id {
+                let metrics = self.platform_text_system.font_metrics(font_id);
+                layout.ascent = metrics.ascent(font_size);
+                // Font metrics measure the descent downwards from the baseline.
+                layout.descent = -metrics.descent(font_size);
+            }
+            let layout = Arc::new(layout);
             let key = Arc::new(CacheKey {
                 text: text.into(),
                 font_size,
                 runs: SmallVec::from(runs),
+                metrics_font_id,
                 wrap_width: None,
+                tab_size: None,
             });
             current_frame.lines.insert(key.clone(), layout.clone());
             current_frame.used_lines.push(key);
             layout
         }
     }
+
+    /// Shapes each directional run of a line separately, and places them in visual order.
+    fn shape_line(&self, text: &str, font_size: Pixels, runs: &[FontRun]) -> LineLayout {
+        let Some(bidi_runs) = visual_runs(text) else {
+            return self.platform_text_system.layout_line(text, font_size, runs);
+        };
+        if bidi_runs.len() == 1 {
+            let mut layout = self.platform_text_system.layout_line(text, font_size, runs);
+            layout.bidi_runs = bidi_runs;
+            return layout;
+        }
+
+        let mut layout = LineLayout {
+            font_size,
+            len: text.len(),
+            ..Default::default()
+        };
+        for bidi_run in &bidi_runs {
+            let range = bidi_run.range.clone();
+            let run_layout = self.platform_text_system.layout_line(
+                &text[range.clone()],
+                font_size,
+                &font_runs_in_range(runs, range.clone()),
+            );
+            for mut shaped_run in run_layout.runs {
+                for glyph in &mut shaped_run.glyphs {
+                    glyph.position.x += layout.width;
+                    glyph.index += range.start;
+                }
+                layout.runs.push(shaped_run);
+            }
+            layout.width += run_layout.width;
+            layout.ascent = layout.ascent.max(run_layout.ascent);
+            layout.descent = layout.descent.max(run_layout.descent);
+        }
+
+        // Lines are painted in logical order, so that the styles of their text apply to the right
+        // glyphs.
+        for shaped_run in &mut layout.runs {
+            shaped_run.glyphs.sort_by_key(|glyph| glyph.index);
+        }
+        layout
+            .runs
+            .sort_by_key(|shaped_run| shaped_run.glyphs.first().map_or(0, |glyph| glyph.index));
+        layout.bidi_runs = bidi_runs;
+        layout
+    }
+
+    fn space_width(&self, font_id: FontId, font_size: Pixels) -> Pixels {
+        self.layout_line(" ", font_size, &[FontRun { len: 1, font_id }], None)
+            .width
+    }
+}
+
+/// Returns the font runs covering the given range of their text, relative to its start.
+fn font_runs_in_range(runs: &[FontRun], range: Range<usize>) -> SmallVec<[FontRun; 1]> {
+    let mut run_start = 0;
+    let mut runs_in_range = SmallVec::new();
+    for run in runs {
+        let run_end = run_start + run.len;
+        let start = run_start.max(range.start);
+        let end = run_end.min(range.end);
+        if start < end {
+            runs_in_range.push(FontRun {
+                len: end - start,
+                font_id: run.font_id,
+            });
+        }
+        run_start = run_end;
+    }
+    runs_in_range
 }
 
 /// A run of text with a single font.
@@ -516,7 +1000,9 @@ struct CacheKey {
     text: String,
     font_size: Pixels,
     runs: SmallVec<[FontRun; 1]>,
+    metrics_font_id: Option<FontId>,
     wrap_width: Option<Pixels>,
+    tab_size: Option<NonZeroU32>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
@@ -524,7 +1010,9 @@ struct CacheKeyRef<'a> {
     text: &'a str,
     font_size: Pixels,
     runs: &'a [FontRun],
+    metrics_font_id: Option<FontId>,
     wrap_width: Option<Pixels>,
+    tab_size: Option<NonZeroU32>,
 }
 
 impl<'a> PartialEq for (dyn AsCacheKeyRef + 'a) {
@@ -547,7 +1035,9 @@ impl AsCacheKeyRef for CacheKey {
             text: &self.text,
             font_size: self.font_size,
             runs: self.runs.as_slice(),
+            metrics_font_id: self.metrics_font_id,
             wrap_width: self.wrap_width,
+            tab_size: self.tab_size,
         }
     }
 }
@@ -575,3 +1065,384 @@ impl<'a> AsCacheKeyRef for CacheKeyRef<'a> {
         *self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{font, Font, TestAppContext, TestDispatcher, TextRun, WindowTextSystem};
+    use rand::prelude::*;
+
+    fn window_text_system() -> WindowTextSystem {
+        let dispatcher = TestDispatcher::new(StdRng::seed_from_u64(0));
+        let cx = TestAppContext::new(dispatcher, None);
+        cx.text_system().add_test_fonts();
+        WindowTextSystem::new(cx.text_system().clone())
+    }
+
+    fn shape(
+        text_system: &WindowTextSystem,
+        text: &str,
+        font: Font,
+        wrap_width: Pixels,
+        tab_size: Option<u32>,
+    ) -> Arc<WrappedLineLayout> {
+        let run = TextRun::plain(text.len(), font);
+        let lines = text_system
+            .shape_text_with_tab_size(
+                text.to_string().into(),
+                px(16.),
+                &[run],
+                Some(wrap_width),
+                tab_size.and_then(NonZeroU32::new),
+            )
+            .unwrap();
+        assert_eq!(lines.len(), 1);
+        lines[0].layout.clone()
+    }
+
+    /// Returns the byte offsets at which each wrapped line after the first starts.
+    fn wrap_offsets(layout: &WrappedLineLayout) -> Vec<usize> {
+        layout
+            .wrap_boundaries()
+            .iter()
+            .map(|boundary| layout.runs()[boundary.run_ix].glyphs[boundary.glyph_ix].index)
+            .collect()
+    }
+
+    #[track_caller]
+    fn assert_close(actual: Pixels, expected: Pixels) {
+        assert!(
+            (actual.0 - expected.0).abs() < 0.01,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_wrap_long_unbroken_text() {
+        let text_system = window_text_system();
+        let mono = font("Zed Plex Mono");
+
+        // Each character of Zed Plex Mono at 16px is 9.6px wide, so 7 of them fit in 72px.
+        let layout = shape(
+            &text_system,
+            "aaaaaaaaaaaaaaaaaaaa",
+            mono.clone(),
+            px(72.),
+            None,
+        );
+        assert_eq!(wrap_offsets(&layout), [7, 14]);
+
+        let layout = shape(&text_system, "aa aaaaaaaaaaaaaaaa", mono, px(72.), None);
+        assert_eq!(wrap_offsets(&layout), [3, 10, 17]);
+
+        let position = layout.position_for_index(12, px(20.)).unwrap();
+        assert_eq!(position.y, px(40.));
+        assert_close(
+            position.x,
+            layout.unwrapped_layout.x_for_index(12) - layout.unwrapped_layout.x_for_index(10),
+        );
+        assert_eq!(
+            layout.index_for_position(point(px(1.), px(41.)), px(20.)),
+            Ok(10)
+        );
+    }
+
+    #[test]
+    fn test_wrap_trailing_whitespace() {
+        let text_system = window_text_system();
+        let mono = font("Zed Plex Mono");
+
+        // The spaces after the last word don't get a line of their own.
+        let layout = shape(
+            &text_system,
+            "aaaa bbb          ",
+            mono.clone(),
+            px(72.),
+            None,
+        );
+        assert_eq!(wrap_offsets(&layout), [5]);
+
+        let layout = shape(&text_system, "aaaa bbbbb   cc", mono, px(72.), None);
+        assert_eq!(wrap_offsets(&layout), [5, 13]);
+    }
+
+    #[test]
+    fn test_wrap_mixed_width_glyphs() {
+        let text_system = window_text_system();
+        let text = "Willow iii WWW illicit MMMM iiiii wow";
+        let wrap_width = px(60.);
+        let layout = shape(&text_system, text, font("Zed Plex Sans"), wrap_width, None);
+        let offsets = wrap_offsets(&layout);
+        assert!(!offsets.is_empty());
+
+        let mut line_start = 0;
+        for line_end in offsets.iter().copied().chain([text.len()]) {
+            let line = &text[line_start..line_end];
+            assert!(
+                line_start == 0 || text[..line_start].ends_with(' '),
+                "line {line:?} doesn't start a word"
+            );
+            let trimmed_end = line_start + line.trim_end().len();
+            let width = layout.unwrapped_layout.x_for_index(trimmed_end)
+                - layout.unwrapped_layout.x_for_index(line_start);
+            assert!(width <= wrap_width, "line {line:?} is {width:?} wide");
+            line_start = line_end;
+        }
+    }
+
+    #[test]
+    fn test_wrap_cjk() {
+        let text_system = window_text_system();
+        // Zed Test CJK has glyphs for ASCII and for these ideographs, so no fallback font is
+        // needed. Its ideographs are 16px wide at 16px, and everything else 8px.
+        let cjk = font("Zed Test CJK");
+        let text = "Hello 你好世界你好世界";
+        let unwrapped = shape(&text_system, text, cjk.clone(), px(10000.), None);
+        assert!(unwrapped.wrap_boundaries().is_empty());
+        assert_close(unwrapped.unwrapped_layout.width, px(8. * 6. + 16. * 8.));
+
+        // Without breaks between CJK characters, the line would be wrapped after the space.
+        // Instead, "Hello 你好" fills the first 80px and "世界你好世" the next.
+        let layout = shape(&text_system, text, cjk, px(90.), None);
+        assert_eq!(wrap_offsets(&layout), [12, 27]);
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        let text_system = window_text_system();
+        let mono = font("Zed Plex Mono");
+        let space = shape(&text_system, " ", mono.clone(), px(1000.), None);
+        let tab_width = space.unwrapped_layout.width * 4.;
+
+        let text = "\ta\tbb";
+        let layout = shape(&text_system, text, mono.clone(), px(1000.), Some(4));
+        assert_close(layout.position_for_index(1, px(20.)).unwrap().x, tab_width);
+        assert_close(
+            layout.position_for_index(3, px(20.)).unwrap().x,
+            tab_width * 2.,
+        );
+        assert_eq!(
+            layout.index_for_position(point(tab_width * 2. + px(1.), px(0.)), px(20.)),
+            Ok(3)
+        );
+
+        // Without a tab size, tabs keep their width in the font.
+        let unexpanded = shape(&text_system, text, mono, px(1000.), None);
+        assert!(unexpanded.unwrapped_layout.width < layout.unwrapped_layout.width);
+    }
+
+    /// Lays out glyphs 10px wide at the given indices, from left to right. Like the layouts of
+    /// bidirectional lines, the glyphs are kept in logical order.
+    fn bidi_layout(text: &str, glyph_indices: &[usize]) -> LineLayout {
+        let mut glyphs = glyph_indices
+            .iter()
+            .enumerate()
+            .map(|(glyph_ix, &index)| ShapedGlyph {
+                id: GlyphId(0),
+                position: point(px(10. * glyph_ix as f32), px(0.)),
+                index,
+                is_emoji: false,
+            })
+            .collect::<SmallVec<[_; 8]>>();
+        glyphs.sort_by_key(|glyph| glyph.index);
+        LineLayout {
+            font_size: px(16.),
+            width: px(10. * glyph_indices.len() as f32),
+            runs: vec![ShapedRun {
+                font_id: FontId(0),
+                glyphs,
+            }],
+            len: text.len(),
+            bidi_runs: visual_runs(text).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bidi_caret_positions() {
+        // Displayed as "ab בא", with "א" at 3..5 and "ב" at 5..7.
+        let layout = bidi_layout("ab אב", &[0, 1, 2, 5, 3]);
+
+        assert_eq!(layout.caret_positions_for_index(0).as_slice(), [px(0.)]);
+        assert_eq!(layout.caret_positions_for_index(2).as_slice(), [px(20.)]);
+        // Before "א" on the right end of the line, or after the space.
+        assert_eq!(
+            layout.caret_positions_for_index(3).as_slice(),
+            [px(50.), px(30.)]
+        );
+        assert_eq!(layout.caret_positions_for_index(5).as_slice(), [px(40.)]);
+        // After "ב", on the left of it.
+        assert_eq!(layout.caret_positions_for_index(7).as_slice(), [px(30.)]);
+        assert_eq!(layout.x_for_index(3), px(50.));
+
+        assert_eq!(layout.index_for_x(px(35.)), Some(5));
+        assert_eq!(layout.index_for_x(px(45.)), Some(3));
+        assert_eq!(layout.index_for_x(px(50.)), None);
+        assert_eq!(layout.closest_index_for_x(px(12.)), 1);
+        assert_eq!(layout.closest_index_for_x(px(32.)), 7);
+        assert_eq!(layout.closest_index_for_x(px(38.)), 5);
+        assert_eq!(layout.closest_index_for_x(px(48.)), 3);
+        assert_eq!(layout.closest_index_for_x(px(100.)), 3);
+    }
+
+    #[test]
+    fn test_bidi_visual_movement() {
+        // Displayed as "ab בא cd", with "א" at 3..5 and "ב" at 5..7.
+        let layout = bidi_layout("ab אב cd", &[0, 1, 2, 5, 3, 7, 8, 9]);
+        let caret = |index, x: f32| CaretPosition { index, x: px(x) };
+
+        let mut carets = vec![layout.caret_for_index(0)];
+        while let Some(next) = layout.visual_right(*carets.last().unwrap()) {
+            carets.push(next);
+        }
+        assert_eq!(
+            carets,
+            [
+                caret(0, 0.),
+                caret(1, 10.),
+                caret(2, 20.),
+                caret(3, 30.),
+                caret(5, 40.),
+                caret(3, 50.),
+                caret(8, 60.),
+                caret(9, 70.),
+                caret(10, 80.),
+            ]
+        );
+
+        let mut carets = vec![layout.caret_for_index(layout.len)];
+        while let Some(next) = layout.visual_left(*carets.last().unwrap()) {
+            carets.push(next);
+        }
+        assert_eq!(
+            carets,
+            [
+                caret(10, 80.),
+                caret(9, 70.),
+                caret(8, 60.),
+                caret(7, 50.),
+                caret(5, 40.),
+                caret(7, 30.),
+                caret(2, 20.),
+                caret(1, 10.),
+                caret(0, 0.),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bidi_wrapping() {
+        // Displayed as "ab גבא", with "א" at 3..5, "ב" at 5..7 and "ג" at 7..9.
+        let layout = bidi_layout("ab אבג", &[0, 1, 2, 7, 5, 3]);
+
+        // "ab א" is as wide as the first 40px of the line, although "א" is painted at 50px.
+        let wrap_boundaries = layout.compute_wrap_boundaries("ab אבג", px(45.));
+        assert_eq!(
+            wrap_boundaries.as_slice(),
+            [WrapBoundary {
+                run_ix: 0,
+                glyph_ix: 4
+            }]
+        );
+
+        // Each wrapped line keeps the visual order of its glyphs, with "גב" on the second one.
+        let painted = |line_ix, x: f32, follows_previous| PaintedGlyph {
+            line_ix,
+            x: px(x),
+            advance: px(10.),
+            follows_previous,
+        };
+        assert_eq!(
+            layout.painted_glyphs(&wrap_boundaries),
+            [
+                painted(0, 0., false),
+                painted(0, 10., true),
+                painted(0, 20., true),
+                painted(0, 30., true),
+                painted(1, 10., false),
+                painted(1, 0., false),
+            ]
+        );
+
+        let wrapped = WrappedLineLayout {
+            unwrapped_layout: Arc::new(layout),
+            wrap_boundaries,
+            wrap_width: Some(px(45.)),
+        };
+        // Before "ב", on the right of it.
+        assert_eq!(
+            wrapped.position_for_index(5, px(20.)),
+            Some(point(px(20.), px(20.)))
+        );
+        // After "ג", on the left of it.
+        assert_eq!(
+            wrapped.position_for_index(9, px(20.)),
+            Some(point(px(0.), px(20.)))
+        );
+        assert_eq!(
+            wrapped.index_for_position(point(px(15.), px(25.)), px(20.)),
+            Ok(5)
+        );
+        assert_eq!(
+            wrapped.index_for_position(point(px(5.), px(25.)), px(20.)),
+            Ok(7)
+        );
+        assert_eq!(
+            wrapped.index_for_position(point(px(35.), px(5.)), px(20.)),
+            Ok(3)
+        );
+        assert_eq!(
+            wrapped.index_for_position(point(px(45.), px(5.)), px(20.)),
+            Err(5)
+        );
+    }
+
+    #[test]
+    fn test_font_runs_in_range() {
+        let runs = [
+            FontRun {
+                len: 3,
+                font_id: FontId(0),
+            },
+            FontRun {
+                len: 4,
+                font_id: FontId(1),
+            },
+        ];
+        assert_eq!(
+            font_runs_in_range(&runs, 2..5).as_slice(),
+            [
+                FontRun {
+                    len: 1,
+                    font_id: FontId(0),
+                },
+                FontRun {
+                    len: 2,
+                    font_id: FontId(1),
+                },
+            ]
+        );
+        assert_eq!(
+            font_runs_in_range(&runs, 3..7).as_slice(),
+            [FontRun {
+                len: 4,
+                font_id: FontId(1),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_wrapped_layouts_are_cached() {
+        let text_system = window_text_system();
+        let mono = font("Zed Plex Mono");
+        let text = "aaaa\tbbbb cccc";
+        let layout = shape(&text_system, text, mono.clone(), px(72.), Some(4));
+        let cached = shape(&text_system, text, mono.clone(), px(72.), Some(4));
+        assert!(Arc::ptr_eq(&layout, &cached));
+
+        let narrower = shape(&text_system, text, mono.clone(), px(50.), Some(4));
+        assert!(!Arc::ptr_eq(&layout, &narrower));
+        let other_tab_size = shape(&text_system, text, mono, px(72.), Some(8));
+        assert!(!Arc::ptr_eq(&layout, &other_tab_size));
+    }
+}
@@ -185,13 +185,7 @@ mod tests {
     fn test_wrap_line() {
         let dispatcher = TestDispatcher::new(StdRng::seed_from_u64(0));
         let cx = TestAppContext::new(dispatcher, None);
-        cx.text_system()
-            .add_fonts(vec![std::fs::read(
-                "../../assets/fonts/plex-mono/ZedPlexMono-Regular.ttf",
-            )
-            .unwrap()
-            .into()])
-            .unwrap();
+        cx.text_system().add_test_fonts();
         let id = cx.text_system().font_id(&font("Zed Plex Mono")).unwrap();
 
         cx.update(|cx| {
@@ -316,22 +310,8 @@ mod tests {
         cx.update(|cx| {
             let text_system = WindowTextSystem::new(cx.text_system().clone());
 
-            let normal = TextRun {
-                len: 0,
-                font: font("Helvetica"),
-                color: Default::default(),
-                underline: Default::default(),
-                strikethrough: None,
-                background_color: None,
-            };
-            let bold = TextRun {
-                len: 0,
-                font: font("Helvetica").bold(),
-                color: Default::default(),
-                underline: Default::default(),
-                strikethrough: None,
-                background_color: None,
-            };
+            let normal = TextRun::plain(0, font("Helvetica"));
+            let bold = TextRun::plain(0, font("Helvetica").bold());
 
             impl TextRun {
                 fn with_len(&self, len: usize) -> Self {
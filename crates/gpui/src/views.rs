@@ -0,0 +1,11 @@
+//! Ready-made elements for common window-level UI, built from GPUI's primitives.
+
+mod command_palette;
+mod context_menu;
+mod modal;
+mod split_view;
+
+pub use command_palette::*;
+pub use context_menu::*;
+pub use modal::*;
+pub use split_view::*;
@@ -0,0 +1,443 @@
+use std::cmp::Reverse;
+
+use crate::{
+    self as gpui, actions, div, hsla, prelude::FluentBuilder, px, uniform_list, Action, AppContext,
+    DismissEvent, EventEmitter, FocusHandle, FocusableView, InteractiveElement, IntoElement,
+    KeyBinding, KeyDownEvent, ParentElement, Render, SharedString, StatefulInteractiveElement,
+    Styled, UniformListScrollHandle, ViewContext,
+};
+
+actions!(gpui, [ToggleCommandPalette]);
+
+/// An action that can be run from the [`CommandPalette`].
+struct Command {
+    name: SharedString,
+    action: Box<dyn Action>,
+    key_binding: Option<SharedString>,
+}
+
+/// A list of every registered action that can be built without arguments, filtered by what
+/// the user types. Pressing enter runs the selected action on the element that was focused
+/// when the palette opened, and escape closes the palette.
+///
+/// The palette emits a [`DismissEvent`] when it closes, after which the view showing it
+/// should stop rendering it. [`CommandPalette::init`] binds [`ToggleCommandPalette`], which
+/// the view showing the palette should handle by opening it.
+///
+/// ```
+/// use gpui::{
+///     div, views::{CommandPalette, ToggleCommandPalette}, DismissEvent, FocusHandle,
+///     InteractiveElement, IntoElement, ParentElement, Render, View, ViewContext, VisualContext,
+/// };
+///
+/// struct Workspace {
+///     focus_handle: FocusHandle,
+///     command_palette: Option<View<CommandPalette>>,
+/// }
+///
+/// impl Render for Workspace {
+///     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+///         div()
+///             .track_focus(&self.focus_handle)
+///             .on_action(cx.listener(|this, _: &ToggleCommandPalette, cx| {
+///                 let palette = cx.new_view(CommandPalette::new);
+///                 cx.subscribe(&palette, |this, _, _: &DismissEvent, cx| {
+///                     this.command_palette = None;
+///                     cx.notify();
+///                 })
+///                 .detach();
+///                 this.command_palette = Some(palette);
+///                 cx.notify();
+///             }))
+///             .children(self.command_palette.clone())
+///     }
+/// }
+/// ```
+pub struct CommandPalette {
+    focus_handle: FocusHandle,
+    previous_focus: Option<FocusHandle>,
+    commands: Vec<Command>,
+    /// Indices into `commands` of the commands matching the query, best match first.
+    matches: Vec<usize>,
+    query: String,
+    selected_ix: usize,
+    scroll_handle: UniformListScrollHandle,
+}
+
+impl CommandPalette {
+    /// The keystrokes that [`CommandPalette::init`] binds to [`ToggleCommandPalette`].
+    pub const DEFAULT_KEYSTROKES: &'static str = if cfg!(target_os = "macos") {
+        "cmd-shift-p"
+    } else {
+        "ctrl-shift-p"
+    };
+
+    /// Binds [`CommandPalette::DEFAULT_KEYSTROKES`] to [`ToggleCommandPalette`].
+    pub fn init(cx: &mut AppContext) {
+        Self::bind_toggle(Self::DEFAULT_KEYSTROKES, cx);
+    }
+
+    /// Binds the given keystrokes to [`ToggleCommandPalette`].
+    pub fn bind_toggle(keystrokes: &str, cx: &mut AppContext) {
+        cx.bind_keys([KeyBinding::new(keystrokes, ToggleCommandPalette, None)]);
+    }
+
+    /// Creates a palette of the registered actions and focuses it. Actions run from the
+    /// palette are dispatched on the element that is focused when this is called.
+    pub fn new(cx: &mut ViewContext<Self>) -> Self {
+        let previous_focus = cx.focused();
+        let mut commands = cx
+            .all_action_names()
+            .to_vec()
+            .into_iter()
+            .filter_map(|name| {
+                let action = cx.build_action(&name, None).ok()?;
+                let key_binding = match previous_focus.as_ref() {
+                    Some(previous_focus) => {
+                        cx.bindings_for_action_in(action.as_ref(), previous_focus)
+                    }
+                    None => cx.bindings_for_action(action.as_ref()),
+                }
+                .pop()
+                .map(|binding| {
+                    binding
+                        .keystrokes()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .into()
+                });
+                Some(Command {
+                    name: humanize_action_name(&name).into(),
+                    action,
+                    key_binding,
+                })
+            })
+            .collect::<Vec<_>>();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let focus_handle = cx.focus_handle();
+        cx.focus(&focus_handle);
+        let mut this = Self {
+            focus_handle,
+            previous_focus,
+            commands,
+            matches: Vec::new(),
+            query: String::new(),
+            selected_ix: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+        };
+        this.update_matches();
+        this
+    }
+
+    fn update_matches(&mut self) {
+        let mut matches = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, command)| Some((match_score(&self.query, &command.name)?, ix)))
+            .collect::<Vec<_>>();
+        // Commands are sorted by name, so a stable sort keeps equal scores alphabetical.
+        matches.sort_by_key(|(score, _)| Reverse(*score));
+        self.matches = matches.into_iter().map(|(_, ix)| ix).collect();
+        self.selected_ix = 0;
+        self.scroll_handle.scroll_to_item(0);
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_ix = ix;
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn dismiss(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(previous_focus) = self.previous_focus.as_ref() {
+            cx.focus(previous_focus);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(&command_ix) = self.matches.get(self.selected_ix) else {
+            return;
+        };
+        let action = self.commands[command_ix].action.boxed_clone();
+        // Restore focus first, so that the action is dispatched where the palette was opened.
+        self.dismiss(cx);
+        cx.dispatch_action(action);
+    }
+
+    fn handle_key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        let keystroke = &event.keystroke;
+        match keystroke.key.as_str() {
+            "escape" => self.dismiss(cx),
+            "enter" => self.confirm(cx),
+            "up" if self.selected_ix > 0 => self.select(self.selected_ix - 1, cx),
+            "down" if self.selected_ix + 1 < self.matches.len() => {
+                self.select(self.selected_ix + 1, cx)
+            }
+            "up" | "down" => {}
+            "backspace" => {
+                self.query.pop();
+                self.update_matches();
+                cx.notify();
+            }
+            _ => {
+                let modifiers = keystroke.modifiers;
+                let Some(input) = keystroke.ime_key.as_ref().filter(|input| {
+                    !modifiers.control
+                        && !modifiers.platform
+                        && !modifiers.function
+                        && !input.chars().any(char::is_control)
+                }) else {
+                    return;
+                };
+                self.query.push_str(input);
+                self.update_matches();
+                cx.notify();
+            }
+        }
+        cx.stop_propagation();
+    }
+
+    fn render_match(&self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let command = &self.commands[self.matches[ix]];
+        div()
+            .id(ix)
+            .flex()
+            .flex_row()
+            .justify_between()
+            .gap_4()
+            .px_3()
+            .py_1()
+            .cursor_pointer()
+            .when(ix == self.selected_ix, |this| {
+                this.bg(hsla(0., 0., 1., 0.08))
+            })
+            .child(command.name.clone())
+            .children(
+                command
+                    .key_binding
+                    .clone()
+                    .map(|key_binding| div().text_color(hsla(0., 0., 0.6, 1.)).child(key_binding)),
+            )
+            .on_click(cx.listener(move |this, _, cx| {
+                this.selected_ix = ix;
+                this.confirm(cx);
+            }))
+    }
+}
+
+/// Turns an action name like `editor::ToggleLineNumbers` into `editor: toggle line numbers`.
+fn humanize_action_name(name: &str) -> String {
+    let (namespace, action) = name.rsplit_once("::").unwrap_or(("", name));
+    let mut result = namespace.replace("::", ": ").replace('_', " ");
+    if !result.is_empty() {
+        result.push_str(": ");
+    }
+    for (ix, char) in action.char_indices() {
+        if ix > 0 && char.is_uppercase() {
+            result.push(' ');
+        }
+        result.extend(char.to_lowercase());
+    }
+    result
+}
+
+/// Scores how well `candidate` matches `query`, or returns `None` if the query's characters
+/// don't all appear in the candidate in order. Matches of consecutive characters and of the
+/// starts of words score higher. Case and whitespace in the query are ignored.
+fn match_score(query: &str, candidate: &str) -> Option<u32> {
+    let candidate = candidate.chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut next_ix = 0;
+    let mut previous_match_ix = None;
+
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let ix = next_ix
+            + candidate[next_ix..]
+                .iter()
+                .position(|c| c.to_lowercase().eq(query_char.to_lowercase()))?;
+
+        score += 1;
+        if previous_match_ix.is_some_and(|previous_ix| previous_ix + 1 == ix) {
+            score += 2;
+        }
+        if ix == 0 || !candidate[ix - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous_match_ix = Some(ix);
+        next_ix = ix + 1;
+    }
+    Some(score)
+}
+
+impl EventEmitter<DismissEvent> for CommandPalette {}
+
+impl FocusableView for CommandPalette {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let border_color = hsla(0., 0., 0.3, 1.);
+        let query = if self.query.is_empty() {
+            div()
+                .text_color(hsla(0., 0., 0.5, 1.))
+                .child("Execute a command…")
+        } else {
+            div().child(self.query.clone())
+        };
+
+        div()
+            .key_context("CommandPalette")
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .flex()
+            .flex_col()
+            .w(px(480.))
+            .rounded_md()
+            .border_1()
+            .border_color(border_color)
+            .bg(hsla(0., 0., 0.12, 1.))
+            .text_color(hsla(0., 0., 0.9, 1.))
+            .shadow_lg()
+            .overflow_hidden()
+            .child(
+                div()
+                    .px_3()
+                    .py_2()
+                    .border_b_1()
+                    .border_color(border_color)
+                    .child(query),
+            )
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "command-palette-matches",
+                    self.matches.len(),
+                    |this, range, cx| range.map(|ix| this.render_match(ix, cx)).collect(),
+                )
+                .track_scroll(self.scroll_handle.clone())
+                .h(px(320.)),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Keystroke, TestAppContext, View, VisualContext};
+
+    actions!(palette_test, [IncrementCounter]);
+
+    #[test]
+    fn test_humanize_action_name() {
+        assert_eq!(
+            humanize_action_name("editor::ToggleLineNumbers"),
+            "editor: toggle line numbers"
+        );
+        assert_eq!(
+            humanize_action_name("project_panel::NewFile"),
+            "project panel: new file"
+        );
+        assert_eq!(humanize_action_name("Quit"), "quit");
+    }
+
+    #[test]
+    fn test_match_score() {
+        assert_eq!(match_score("", "editor: copy"), Some(0));
+        assert_eq!(match_score("xyz", "editor: copy"), None);
+        assert_eq!(
+            match_score("Copy", "editor: copy"),
+            match_score("copy", "editor: copy")
+        );
+        assert!(match_score("ec", "editor: copy") > match_score("ec", "dock: select"));
+        assert!(match_score("tog", "toggle") > match_score("tog", "the outline guide"));
+    }
+
+    struct PaletteHost {
+        focus_handle: FocusHandle,
+        palette: Option<View<CommandPalette>>,
+        count: usize,
+    }
+
+    impl Render for PaletteHost {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .track_focus(&self.focus_handle)
+                .size_full()
+                .on_action(cx.listener(|this, _: &IncrementCounter, _| this.count += 1))
+                .on_action(cx.listener(|this, _: &ToggleCommandPalette, cx| {
+                    let palette = cx.new_view(CommandPalette::new);
+                    cx.subscribe(&palette, |this, _, _: &DismissEvent, cx| {
+                        this.palette = None;
+                        cx.notify();
+                    })
+                    .detach();
+                    this.palette = Some(palette);
+                    cx.notify();
+                }))
+                .children(self.palette.clone())
+        }
+    }
+
+    #[gpui::test]
+    fn test_run_action_from_palette(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            CommandPalette::bind_toggle("ctrl-shift-p", cx);
+            cx.bind_keys([KeyBinding::new("ctrl-i", IncrementCounter, None)]);
+        });
+        let (host, cx) = cx.add_window_view(|cx| PaletteHost {
+            focus_handle: cx.focus_handle(),
+            palette: None,
+            count: 0,
+        });
+        cx.focus_view(&host);
+
+        cx.simulate_keystrokes("ctrl-shift-p");
+        let palette = cx.update(|cx| host.read(cx).palette.clone().unwrap());
+        cx.update(|cx| {
+            let palette = palette.read(cx);
+            assert!(palette.focus_handle.is_focused(cx));
+            let command = palette
+                .commands
+                .iter()
+                .find(|command| command.name == "palette test: increment counter")
+                .unwrap();
+            assert_eq!(
+                command.key_binding,
+                Some(Keystroke::parse("ctrl-i").unwrap().to_string().into())
+            );
+        });
+
+        cx.simulate_input("incrementcounter");
+        cx.update(|cx| {
+            let palette = palette.read(cx);
+            let selected = &palette.commands[palette.matches[palette.selected_ix]];
+            assert_eq!(selected.name, "palette test: increment counter");
+        });
+
+        cx.simulate_keystrokes("enter");
+        cx.update(|cx| {
+            let host = host.read(cx);
+            assert_eq!(host.count, 1);
+            assert!(host.palette.is_none());
+            assert!(host.focus_handle.is_focused(cx));
+        });
+
+        // Escape closes the palette without running anything.
+        cx.simulate_keystrokes("ctrl-shift-p");
+        cx.simulate_keystrokes("escape");
+        cx.update(|cx| {
+            let host = host.read(cx);
+            assert_eq!(host.count, 1);
+            assert!(host.palette.is_none());
+            assert!(host.focus_handle.is_focused(cx));
+        });
+    }
+}
@@ -0,0 +1,700 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Duration};
+
+use crate::{
+    anchored, canvas, deferred, div, hsla, point, prelude::FluentBuilder, px, Action, AnchorCorner,
+    AnyElement, AppContext, Bounds, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, KeyDownEvent, MouseDownEvent, MouseMoveEvent, ParentElement,
+    Pixels, Point, Render, SharedString, Size, StatefulInteractiveElement, Styled, Task, View,
+    ViewContext, VisualContext, WeakView, WindowContext,
+};
+
+/// A callback run on the view that deployed a [`ContextMenu`] when one of its items is
+/// activated.
+pub type ContextMenuHandler<V> = Rc<dyn Fn(&mut V, &mut ViewContext<V>)>;
+
+/// An entry in a [`ContextMenu`].
+pub enum ContextMenuItem<V> {
+    /// An item with the given label that runs the handler when it is activated.
+    Action(SharedString, ContextMenuHandler<V>),
+    /// A line between groups of items, which can't be selected.
+    Separator,
+    /// An item with the given label that opens another menu.
+    Submenu(SharedString, ContextMenu<V>),
+}
+
+impl<V: 'static> ContextMenuItem<V> {
+    /// Creates an item that runs the handler on the view that deployed the menu.
+    pub fn action(
+        label: impl Into<SharedString>,
+        handler: impl Fn(&mut V, &mut ViewContext<V>) + 'static,
+    ) -> Self {
+        Self::Action(label.into(), Rc::new(handler))
+    }
+
+    /// Creates an item that dispatches the action on the element that was focused when the
+    /// menu was deployed.
+    pub fn dispatch(label: impl Into<SharedString>, action: Box<dyn Action>) -> Self {
+        Self::action(label, move |_, cx| cx.dispatch_action(action.boxed_clone()))
+    }
+
+    /// Creates an item that opens the given menu.
+    pub fn submenu(label: impl Into<SharedString>, menu: ContextMenu<V>) -> Self {
+        Self::Submenu(label.into(), menu)
+    }
+
+    fn is_selectable(&self) -> bool {
+        !matches!(self, Self::Separator)
+    }
+}
+
+/// The items of a menu opened by right-clicking on a view of type `V`.
+///
+/// Deploying the menu with [`ContextMenu::deploy`] shows it at the cursor, moved or flipped
+/// to stay inside the window, and focuses it. The up and down arrows select items, enter
+/// activates the selected item, right and left open and close submenus, and escape or a
+/// click outside the menu dismisses it. Hovering an item with a submenu opens it after
+/// [`ContextMenu::submenu_delay`].
+///
+/// ```
+/// use gpui::{
+///     div, views::{ContextMenu, ContextMenuItem, ContextMenuView}, DismissEvent,
+///     InteractiveElement, IntoElement, MouseButton, MouseDownEvent, ParentElement, Render,
+///     View, ViewContext,
+/// };
+///
+/// struct Document {
+///     context_menu: Option<View<ContextMenuView<Self>>>,
+/// }
+///
+/// impl Document {
+///     fn copy(&mut self, _: &mut ViewContext<Self>) {}
+///     fn paste(&mut self, _: &mut ViewContext<Self>) {}
+/// }
+///
+/// impl Render for Document {
+///     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+///         div()
+///             .on_mouse_down(
+///                 MouseButton::Right,
+///                 cx.listener(|this, event: &MouseDownEvent, cx| {
+///                     let menu = ContextMenu::new(vec![
+///                         ContextMenuItem::action("Copy", Self::copy),
+///                         ContextMenuItem::action("Paste", Self::paste),
+///                     ])
+///                     .deploy(event.position, cx);
+///                     cx.subscribe(&menu, |this, _, _: &DismissEvent, cx| {
+///                         this.context_menu = None;
+///                         cx.notify();
+///                     })
+///                     .detach();
+///                     this.context_menu = Some(menu);
+///                     cx.notify();
+///                 }),
+///             )
+///             .children(self.context_menu.clone())
+///     }
+/// }
+/// ```
+pub struct ContextMenu<V> {
+    items: Vec<ContextMenuItem<V>>,
+    submenu_delay: Duration,
+}
+
+impl<V: 'static> ContextMenu<V> {
+    /// How long the cursor has to rest on an item before its submenu opens, unless set with
+    /// [`ContextMenu::submenu_delay`].
+    pub const DEFAULT_SUBMENU_DELAY: Duration = Duration::from_millis(200);
+
+    /// Creates a menu with the given items.
+    pub fn new(items: Vec<ContextMenuItem<V>>) -> Self {
+        Self {
+            items,
+            submenu_delay: Self::DEFAULT_SUBMENU_DELAY,
+        }
+    }
+
+    /// Sets how long the cursor has to rest on an item before its submenu opens.
+    ///
+    /// When the menu is deployed, its delay is used for all of its submenus.
+    pub fn submenu_delay(mut self, delay: Duration) -> Self {
+        self.submenu_delay = delay;
+        self
+    }
+
+    /// Returns the items of the menu.
+    pub fn items(&self) -> &[ContextMenuItem<V>] {
+        &self.items
+    }
+
+    /// Shows the menu at the given position in the window and focuses it. Activated items
+    /// run on the view whose context is given.
+    ///
+    /// The returned view has to be rendered by the caller, until it emits a [`DismissEvent`].
+    pub fn deploy(
+        self,
+        position: Point<Pixels>,
+        cx: &mut ViewContext<V>,
+    ) -> View<ContextMenuView<V>> {
+        let owner = cx.view().downgrade();
+        let previous_focus = cx.focused();
+        cx.new_view(|cx| {
+            let focus_handle = cx.focus_handle();
+            cx.focus(&focus_handle);
+            ContextMenuView {
+                menu: self,
+                owner,
+                focus_handle,
+                previous_focus,
+                position,
+                open_path: Vec::new(),
+                selected_ix: None,
+                bounds: Rc::default(),
+                _open_submenu: None,
+            }
+        })
+    }
+}
+
+/// Where the menus of a [`ContextMenuView`] were drawn in the last frame.
+#[derive(Default)]
+struct MenuBounds {
+    /// The bounds of each open menu, by depth.
+    menus: HashMap<usize, Bounds<Pixels>>,
+    /// The bounds of each item with a submenu, by depth and index.
+    items: HashMap<(usize, usize), Bounds<Pixels>>,
+}
+
+/// A deployed [`ContextMenu`], created with [`ContextMenu::deploy`].
+pub struct ContextMenuView<V> {
+    menu: ContextMenu<V>,
+    owner: WeakView<V>,
+    focus_handle: FocusHandle,
+    previous_focus: Option<FocusHandle>,
+    position: Point<Pixels>,
+    /// The indices of the items whose submenus are open, starting with the root menu.
+    open_path: Vec<usize>,
+    /// The selected item in the innermost open menu.
+    selected_ix: Option<usize>,
+    bounds: Rc<RefCell<MenuBounds>>,
+    _open_submenu: Option<Task<()>>,
+}
+
+impl<V: 'static> ContextMenuView<V> {
+    /// Returns the open menu at the given depth, where the root menu is at depth 0.
+    fn menu(&self, depth: usize) -> &ContextMenu<V> {
+        let mut menu = &self.menu;
+        for &ix in &self.open_path[..depth] {
+            if let ContextMenuItem::Submenu(_, submenu) = &menu.items[ix] {
+                menu = submenu;
+            }
+        }
+        menu
+    }
+
+    fn innermost_menu(&self) -> &ContextMenu<V> {
+        self.menu(self.open_path.len())
+    }
+
+    fn select_adjacent(&mut self, forward: bool, cx: &mut ViewContext<Self>) {
+        let items = &self.innermost_menu().items;
+        if let Some(ix) = adjacent_selectable(items, self.selected_ix, forward) {
+            self.selected_ix = Some(ix);
+            self._open_submenu = None;
+            cx.notify();
+        }
+    }
+
+    /// Opens the submenu of the item at `ix` in the innermost menu, if it has one.
+    fn open_submenu(&mut self, ix: usize, select_first: bool, cx: &mut ViewContext<Self>) {
+        let ContextMenuItem::Submenu(_, submenu) = &self.innermost_menu().items[ix] else {
+            return;
+        };
+        let selected_ix = if select_first {
+            adjacent_selectable(&submenu.items, None, true)
+        } else {
+            None
+        };
+
+        // Forget the size of whichever submenu was open at this depth before.
+        let depth = self.open_path.len() + 1;
+        self.bounds
+            .borrow_mut()
+            .menus
+            .retain(|menu_depth, _| *menu_depth < depth);
+        self.open_path.push(ix);
+        self.selected_ix = selected_ix;
+        self._open_submenu = None;
+        cx.notify();
+    }
+
+    fn close_submenu(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.open_path.pop() {
+            self.selected_ix = Some(ix);
+            cx.notify();
+        }
+    }
+
+    fn hover_item(&mut self, depth: usize, ix: usize, cx: &mut ViewContext<Self>) {
+        let is_selected = self.open_path.len() == depth && self.selected_ix == Some(ix);
+        if is_selected || self.open_path.get(depth) == Some(&ix) || self.open_path.len() < depth {
+            return;
+        }
+
+        self.open_path.truncate(depth);
+        self.selected_ix = Some(ix);
+        self._open_submenu = None;
+        if let ContextMenuItem::Submenu(..) = self.innermost_menu().items[ix] {
+            let delay = self.menu.submenu_delay;
+            self._open_submenu = Some(cx.spawn(|this, mut cx| async move {
+                cx.background_executor().timer(delay).await;
+                this.update(&mut cx, |this, cx| {
+                    if this.open_path.len() == depth && this.selected_ix == Some(ix) {
+                        this.open_submenu(ix, false, cx);
+                    }
+                })
+                .ok();
+            }));
+        }
+        cx.notify();
+    }
+
+    fn click_item(&mut self, depth: usize, ix: usize, cx: &mut ViewContext<Self>) {
+        self.open_path.truncate(depth);
+        self.selected_ix = Some(ix);
+        self.confirm(cx);
+    }
+
+    fn confirm(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(ix) = self.selected_ix else {
+            return;
+        };
+        match &self.innermost_menu().items[ix] {
+            ContextMenuItem::Action(_, handler) => {
+                let handler = handler.clone();
+                // Restore focus first, so that actions dispatched by the handler reach the
+                // element the menu was deployed from.
+                self.dismiss(cx);
+                self.owner.update(cx, |owner, cx| handler(owner, cx)).ok();
+            }
+            ContextMenuItem::Submenu(..) => self.open_submenu(ix, true, cx),
+            ContextMenuItem::Separator => {}
+        }
+    }
+
+    fn dismiss(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(previous_focus) = self.previous_focus.as_ref() {
+            cx.focus(previous_focus);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn handle_key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        match event.keystroke.key.as_str() {
+            "escape" => self.dismiss(cx),
+            "enter" => self.confirm(cx),
+            "up" => self.select_adjacent(false, cx),
+            "down" => self.select_adjacent(true, cx),
+            "right" => {
+                if let Some(ix) = self.selected_ix {
+                    self.open_submenu(ix, true, cx);
+                }
+            }
+            "left" => self.close_submenu(cx),
+            _ => return,
+        }
+        cx.stop_propagation();
+    }
+
+    fn handle_mouse_down_out(&mut self, event: &MouseDownEvent, cx: &mut ViewContext<Self>) {
+        let clicked_menu = self
+            .bounds
+            .borrow()
+            .menus
+            .values()
+            .any(|bounds| bounds.contains(&event.position));
+        if !clicked_menu {
+            self.dismiss(cx);
+        }
+    }
+
+    fn render_menu(&self, depth: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        let highlighted_ix = match self.open_path.get(depth) {
+            Some(ix) => Some(*ix),
+            None => self.selected_ix,
+        };
+        let border_color = hsla(0., 0., 0.3, 1.);
+        let muted_color = hsla(0., 0., 0.6, 1.);
+
+        let items = self
+            .menu(depth)
+            .items
+            .iter()
+            .enumerate()
+            .map(|(ix, item)| {
+                let (label, has_submenu) = match item {
+                    ContextMenuItem::Separator => {
+                        return div().my_1().h(px(1.)).bg(border_color).into_any_element()
+                    }
+                    ContextMenuItem::Action(label, _) => (label.clone(), false),
+                    ContextMenuItem::Submenu(label, _) => (label.clone(), true),
+                };
+                div()
+                    .id(ix)
+                    .debug_selector({
+                        let label = label.clone();
+                        move || format!("CONTEXT-MENU-ITEM-{label}")
+                    })
+                    .relative()
+                    .flex()
+                    .flex_row()
+                    .justify_between()
+                    .gap_4()
+                    .px_3()
+                    .py_1()
+                    .cursor_pointer()
+                    .when(highlighted_ix == Some(ix), |this| {
+                        this.bg(hsla(0., 0., 1., 0.08))
+                    })
+                    .on_mouse_move(cx.listener(move |this, _: &MouseMoveEvent, cx| {
+                        this.hover_item(depth, ix, cx)
+                    }))
+                    .on_click(cx.listener(move |this, _, cx| this.click_item(depth, ix, cx)))
+                    .child(label)
+                    .when(has_submenu, |this| {
+                        let bounds = self.bounds.clone();
+                        this.child(div().text_color(muted_color).child("›"))
+                            .child(track_bounds(move |item_bounds, _| {
+                                bounds.borrow_mut().items.insert((depth, ix), item_bounds);
+                            }))
+                    })
+                    .into_any_element()
+            })
+            .collect::<Vec<_>>();
+
+        let bounds = self.bounds.clone();
+        div()
+            .id(depth)
+            .debug_selector(move || format!("CONTEXT-MENU-{depth}"))
+            .relative()
+            .flex()
+            .flex_col()
+            .min_w(px(160.))
+            .py_1()
+            .rounded_md()
+            .border_1()
+            .border_color(border_color)
+            .bg(hsla(0., 0., 0.12, 1.))
+            .text_color(hsla(0., 0., 0.9, 1.))
+            .shadow_lg()
+            .occlude()
+            .child(track_bounds(move |menu_bounds, cx| {
+                let previous = bounds.borrow_mut().menus.insert(depth, menu_bounds);
+                // Submenus are placed using the size of the menu they open from, and their
+                // own, so draw again once a menu's size is known.
+                if previous.map(|previous| previous.size) != Some(menu_bounds.size) {
+                    cx.on_next_frame(|cx| cx.refresh());
+                }
+            }))
+            .children(items)
+            .into_any_element()
+    }
+}
+
+/// Returns the index of the next selectable item after `current`, or before it if `forward`
+/// is false, wrapping around at the ends of the menu. Without a current item, this returns
+/// the first or last selectable item.
+fn adjacent_selectable<V: 'static>(
+    items: &[ContextMenuItem<V>],
+    current: Option<usize>,
+    forward: bool,
+) -> Option<usize> {
+    let count = items.len();
+    (1..=count)
+        .map(|offset| match (current, forward) {
+            (Some(ix), true) => (ix + offset) % count,
+            (Some(ix), false) => (ix + count - offset % count) % count,
+            (None, true) => offset - 1,
+            (None, false) => count - offset,
+        })
+        .find(|ix| items[*ix].is_selectable())
+}
+
+/// Returns where to anchor a submenu of the given size, opened from `item` in the menu with
+/// the given bounds.
+///
+/// The submenu opens to the right of its parent menu, unless it would go past the right
+/// edge of the window and fits on the left. Until the submenu's size is known, it opens to
+/// the right.
+fn submenu_position(
+    parent: Bounds<Pixels>,
+    item: Bounds<Pixels>,
+    size: Option<Size<Pixels>>,
+    viewport: Size<Pixels>,
+) -> (Point<Pixels>, AnchorCorner) {
+    let flip = size.map_or(false, |size| {
+        parent.right() + size.width > viewport.width && parent.left() - size.width >= px(0.)
+    });
+    if flip {
+        (point(parent.left(), item.top()), AnchorCorner::TopRight)
+    } else {
+        (point(parent.right(), item.top()), AnchorCorner::TopLeft)
+    }
+}
+
+/// An element covering its parent that reports the parent's bounds whenever it is drawn.
+fn track_bounds(
+    callback: impl FnOnce(Bounds<Pixels>, &mut WindowContext) + 'static,
+) -> impl IntoElement {
+    canvas(callback, |_, _, _| {})
+        .absolute()
+        .top_0()
+        .left_0()
+        .size_full()
+}
+
+impl<V: 'static> EventEmitter<DismissEvent> for ContextMenuView<V> {}
+
+impl<V: 'static> FocusableView for ContextMenuView<V> {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl<V: 'static> Render for ContextMenuView<V> {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let viewport = cx.viewport_size();
+        let mut menus = vec![anchored()
+            .position(self.position)
+            .child(self.render_menu(0, cx))
+            .into_any_element()];
+        for (depth, parent_ix) in self.open_path.iter().copied().enumerate() {
+            let (position, anchor) = {
+                let bounds = self.bounds.borrow();
+                let (Some(parent), Some(item)) = (
+                    bounds.menus.get(&depth),
+                    bounds.items.get(&(depth, parent_ix)),
+                ) else {
+                    break;
+                };
+                submenu_position(
+                    *parent,
+                    *item,
+                    bounds.menus.get(&(depth + 1)).map(|bounds| bounds.size),
+                    viewport,
+                )
+            };
+            menus.push(
+                anchored()
+                    .position(position)
+                    .anchor(anchor)
+                    .child(self.render_menu(depth + 1, cx))
+                    .into_any_element(),
+            );
+        }
+
+        deferred(
+            div()
+                .key_context("ContextMenu")
+                .track_focus(&self.focus_handle)
+                .on_key_down(cx.listener(Self::handle_key_down))
+                .on_mouse_down_out(cx.listener(Self::handle_mouse_down_out))
+                .children(menus),
+        )
+        .with_priority(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as gpui, size, Modifiers, MouseButton, TestAppContext};
+
+    fn items(labels: &[&str]) -> Vec<ContextMenuItem<()>> {
+        labels
+            .iter()
+            .map(|label| match *label {
+                "-" => ContextMenuItem::Separator,
+                label => ContextMenuItem::action(label.to_string(), |_, _| {}),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_adjacent_selectable() {
+        let items = items(&["-", "Cut", "Copy", "-", "Paste", "-"]);
+        assert_eq!(adjacent_selectable(&items, None, true), Some(1));
+        assert_eq!(adjacent_selectable(&items, None, false), Some(4));
+        assert_eq!(adjacent_selectable(&items, Some(1), true), Some(2));
+        assert_eq!(adjacent_selectable(&items, Some(2), true), Some(4));
+        assert_eq!(adjacent_selectable(&items, Some(4), true), Some(1));
+        assert_eq!(adjacent_selectable(&items, Some(1), false), Some(4));
+        assert_eq!(adjacent_selectable(&items, Some(4), false), Some(2));
+
+        assert_eq!(
+            adjacent_selectable(&self::items(&["-", "-"]), None, true),
+            None
+        );
+        assert_eq!(adjacent_selectable(&self::items(&[]), None, true), None);
+    }
+
+    #[test]
+    fn test_submenu_position() {
+        let viewport = size(px(1000.), px(800.));
+        let item = Bounds::new(point(px(600.), px(140.)), size(px(200.), px(20.)));
+        let parent = Bounds::new(point(px(600.), px(100.)), size(px(200.), px(300.)));
+
+        assert_eq!(
+            submenu_position(parent, item, None, viewport),
+            (point(px(800.), px(140.)), AnchorCorner::TopLeft)
+        );
+        assert_eq!(
+            submenu_position(parent, item, Some(size(px(200.), px(100.))), viewport),
+            (point(px(800.), px(140.)), AnchorCorner::TopLeft)
+        );
+        // There isn't room on the right, so the submenu flips to the left of its parent.
+        assert_eq!(
+            submenu_position(parent, item, Some(size(px(250.), px(100.))), viewport),
+            (point(px(600.), px(140.)), AnchorCorner::TopRight)
+        );
+        // There isn't room on either side, so the submenu stays on the right.
+        assert_eq!(
+            submenu_position(parent, item, Some(size(px(700.), px(100.))), viewport),
+            (point(px(800.), px(140.)), AnchorCorner::TopLeft)
+        );
+    }
+
+    struct MenuHost {
+        focus_handle: FocusHandle,
+        menu: Option<View<ContextMenuView<Self>>>,
+        activated: Vec<&'static str>,
+    }
+
+    impl MenuHost {
+        fn deploy_menu(&mut self, cx: &mut ViewContext<Self>) {
+            let menu = ContextMenu::new(vec![
+                ContextMenuItem::action("Copy", |this: &mut Self, _| this.activated.push("copy")),
+                ContextMenuItem::Separator,
+                ContextMenuItem::submenu(
+                    "Share",
+                    ContextMenu::new(vec![
+                        ContextMenuItem::action("Link", |this: &mut Self, _| {
+                            this.activated.push("link")
+                        }),
+                        ContextMenuItem::action("Email", |this: &mut Self, _| {
+                            this.activated.push("email")
+                        }),
+                    ]),
+                ),
+            ])
+            .deploy(point(px(10.), px(10.)), cx);
+            cx.subscribe(&menu, |this, _, _: &DismissEvent, cx| {
+                this.menu = None;
+                cx.notify();
+            })
+            .detach();
+            self.menu = Some(menu);
+            cx.notify();
+        }
+    }
+
+    impl Render for MenuHost {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .track_focus(&self.focus_handle)
+                .size_full()
+                .children(self.menu.clone())
+        }
+    }
+
+    #[gpui::test]
+    fn test_keyboard_navigation(cx: &mut TestAppContext) {
+        let (host, cx) = cx.add_window_view(|cx| MenuHost {
+            focus_handle: cx.focus_handle(),
+            menu: None,
+            activated: Vec::new(),
+        });
+        cx.focus_view(&host);
+
+        host.update(cx, |host, cx| host.deploy_menu(cx));
+        let menu = cx.update(|cx| host.read(cx).menu.clone().unwrap());
+        cx.update(|cx| assert!(menu.read(cx).focus_handle.is_focused(cx)));
+
+        // The separator is skipped, and enter on an item with a submenu opens it.
+        cx.simulate_keystrokes("down down enter");
+        cx.update(|cx| {
+            let menu = menu.read(cx);
+            assert_eq!(menu.open_path, [2]);
+            assert_eq!(menu.selected_ix, Some(0));
+        });
+        cx.simulate_keystrokes("left");
+        cx.update(|cx| {
+            let menu = menu.read(cx);
+            assert!(menu.open_path.is_empty());
+            assert_eq!(menu.selected_ix, Some(2));
+        });
+
+        cx.simulate_keystrokes("right down enter");
+        cx.update(|cx| {
+            let host = host.read(cx);
+            assert_eq!(host.activated, ["email"]);
+            assert!(host.menu.is_none());
+            assert!(host.focus_handle.is_focused(cx));
+        });
+
+        // Escape dismisses the menu without activating anything.
+        host.update(cx, |host, cx| host.deploy_menu(cx));
+        cx.simulate_keystrokes("down escape");
+        cx.update(|cx| {
+            let host = host.read(cx);
+            assert_eq!(host.activated, ["email"]);
+            assert!(host.menu.is_none());
+            assert!(host.focus_handle.is_focused(cx));
+        });
+    }
+
+    #[gpui::test]
+    fn test_mouse_interaction(cx: &mut TestAppContext) {
+        let (host, cx) = cx.add_window_view(|cx| MenuHost {
+            focus_handle: cx.focus_handle(),
+            menu: None,
+            activated: Vec::new(),
+        });
+        cx.simulate_resize(size(px(1000.), px(800.)));
+        cx.focus_view(&host);
+        host.update(cx, |host, cx| host.deploy_menu(cx));
+        cx.run_until_parked();
+
+        // Hovering an item with a submenu opens it after a delay.
+        let share = cx.debug_bounds("CONTEXT-MENU-ITEM-Share").unwrap();
+        cx.simulate_mouse_move(share.center(), None, Modifiers::none());
+        assert!(cx.debug_bounds("CONTEXT-MENU-1").is_none());
+        cx.executor()
+            .advance_clock(ContextMenu::<MenuHost>::DEFAULT_SUBMENU_DELAY);
+        cx.run_until_parked();
+        let submenu = cx.debug_bounds("CONTEXT-MENU-1").unwrap();
+        let menu = cx.debug_bounds("CONTEXT-MENU-0").unwrap();
+        assert_eq!(submenu.left(), menu.right());
+
+        let link = cx.debug_bounds("CONTEXT-MENU-ITEM-Link").unwrap();
+        cx.simulate_click(link.center(), Modifiers::none());
+        cx.update(|cx| {
+            let host = host.read(cx);
+            assert_eq!(host.activated, ["link"]);
+            assert!(host.menu.is_none());
+        });
+
+        // Clicking outside the menu dismisses it.
+        host.update(cx, |host, cx| host.deploy_menu(cx));
+        cx.run_until_parked();
+        cx.simulate_mouse_down(
+            point(px(900.), px(700.)),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        cx.update(|cx| {
+            let host = host.read(cx);
+            assert_eq!(host.activated, ["link"]);
+            assert!(host.menu.is_none());
+        });
+    }
+}
@@ -0,0 +1,345 @@
+use std::rc::Rc;
+
+use crate::{
+    anchored, deferred, div, hsla, point, AnchoredPositionMode, AnyElement, Bounds, DismissEvent,
+    Element, ElementId, FocusHandle, GlobalElementId, Hsla, InteractiveElement, IntoElement,
+    KeyDownEvent, LayoutId, MouseButton, ParentElement, Pixels, Styled, WindowContext,
+};
+
+/// A modal dialog, which shows its content centered over a backdrop that covers the window.
+///
+/// When the modal is first drawn, it records which element had focus and moves focus into the
+/// modal. Pressing escape or clicking the backdrop calls the [`Modal::on_dismiss`] handler and
+/// returns focus to the element that had it before. While the modal is focused, tab and
+/// shift-tab cycle through its [`Modal::tab_stops`] rather than leaving the modal.
+///
+/// The modal isn't dismissed on its own: the dismiss handler should stop rendering it.
+///
+/// ```
+/// use gpui::{div, views::Modal, DismissEvent, IntoElement, ParentElement, Render, ViewContext};
+///
+/// struct Editor {
+///     confirming: bool,
+/// }
+///
+/// impl Render for Editor {
+///     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+///         div().size_full().children(self.confirming.then(|| {
+///             Modal::new(div().child("Discard changes?")).on_dismiss(cx.listener(
+///                 |this, _: &DismissEvent, cx| {
+///                     this.confirming = false;
+///                     cx.notify();
+///                 },
+///             ))
+///         }))
+///     }
+/// }
+/// ```
+pub struct Modal {
+    id: ElementId,
+    content: Option<AnyElement>,
+    backdrop: Hsla,
+    focus_handle: Option<FocusHandle>,
+    tab_stops: Vec<FocusHandle>,
+    on_dismiss: Option<Rc<dyn Fn(&DismissEvent, &mut WindowContext)>>,
+}
+
+impl Modal {
+    /// Creates a modal that shows the given content.
+    pub fn new(content: impl IntoElement) -> Self {
+        Self {
+            id: "modal".into(),
+            content: Some(content.into_any_element()),
+            backdrop: hsla(0., 0., 0., 0.4),
+            focus_handle: None,
+            tab_stops: Vec::new(),
+            on_dismiss: None,
+        }
+    }
+
+    /// Sets the id of the modal, which is needed to show more than one modal from the same
+    /// parent element.
+    pub fn id(mut self, id: impl Into<ElementId>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Sets the color of the backdrop drawn over the rest of the window.
+    pub fn backdrop(mut self, color: impl Into<Hsla>) -> Self {
+        self.backdrop = color.into();
+        self
+    }
+
+    /// Sets the focus handle of the modal's content, which is focused when the modal opens.
+    /// By default, the modal creates its own.
+    pub fn track_focus(mut self, focus_handle: &FocusHandle) -> Self {
+        self.focus_handle = Some(focus_handle.clone());
+        self
+    }
+
+    /// Sets the focus handles that tab and shift-tab move between, in order.
+    pub fn tab_stops(mut self, tab_stops: impl IntoIterator<Item = FocusHandle>) -> Self {
+        self.tab_stops = tab_stops.into_iter().collect();
+        self
+    }
+
+    /// Sets the handler called when the user presses escape or clicks the backdrop.
+    pub fn on_dismiss(
+        mut self,
+        handler: impl Fn(&DismissEvent, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_dismiss = Some(Rc::new(handler));
+        self
+    }
+}
+
+/// The focus handles of a [`Modal`], which outlive each frame it is drawn in.
+struct ModalState {
+    focus_handle: FocusHandle,
+    previous_focus: Option<FocusHandle>,
+}
+
+/// Returns the index of the tab stop that tab (or shift-tab, if `reverse` is set) moves focus
+/// to, wrapping around at either end.
+pub fn next_tab_stop(
+    focused_ix: Option<usize>,
+    tab_stop_count: usize,
+    reverse: bool,
+) -> Option<usize> {
+    if tab_stop_count == 0 {
+        return None;
+    }
+    Some(match (focused_ix, reverse) {
+        (None, false) => 0,
+        (None, true) => tab_stop_count - 1,
+        (Some(ix), false) => (ix + 1) % tab_stop_count,
+        (Some(ix), true) => (ix + tab_stop_count - 1) % tab_stop_count,
+    })
+}
+
+impl Modal {
+    fn render(&mut self, state: &ModalState, cx: &mut WindowContext) -> AnyElement {
+        let dismiss = {
+            let on_dismiss = self.on_dismiss.clone();
+            let previous_focus = state.previous_focus.clone();
+            move |cx: &mut WindowContext| {
+                if let Some(previous_focus) = previous_focus.as_ref() {
+                    cx.focus(previous_focus);
+                }
+                if let Some(on_dismiss) = on_dismiss.as_ref() {
+                    on_dismiss(&DismissEvent, cx);
+                }
+            }
+        };
+        let tab_stops = std::mem::take(&mut self.tab_stops);
+        let viewport_size = cx.viewport_size();
+
+        let content = div()
+            .track_focus(&state.focus_handle)
+            .occlude()
+            .on_key_down({
+                let dismiss = dismiss.clone();
+                move |event: &KeyDownEvent, cx| match event.keystroke.key.as_str() {
+                    "escape" => {
+                        cx.stop_propagation();
+                        dismiss(cx);
+                    }
+                    "tab" => {
+                        cx.stop_propagation();
+                        let focused_ix = tab_stops
+                            .iter()
+                            .position(|tab_stop| tab_stop.contains_focused(cx));
+                        if let Some(ix) = next_tab_stop(
+                            focused_ix,
+                            tab_stops.len(),
+                            event.keystroke.modifiers.shift,
+                        ) {
+                            cx.focus(&tab_stops[ix]);
+                        }
+                    }
+                    _ => {}
+                }
+            })
+            .children(self.content.take());
+
+        deferred(
+            anchored()
+                .position_mode(AnchoredPositionMode::Window)
+                .position(point(Pixels::ZERO, Pixels::ZERO))
+                .child(
+                    div()
+                        .id(self.id.clone())
+                        .w(viewport_size.width)
+                        .h(viewport_size.height)
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .bg(self.backdrop)
+                        .occlude()
+                        .on_mouse_down(MouseButton::Left, move |_, cx| {
+                            cx.stop_propagation();
+                            dismiss(cx);
+                        })
+                        .child(content),
+                ),
+        )
+        .into_any_element()
+    }
+}
+
+impl Element for Modal {
+    type RequestLayoutState = AnyElement;
+    type PrepaintState = ();
+
+    fn id(&self) -> Option<ElementId> {
+        Some(self.id.clone())
+    }
+
+    fn request_layout(
+        &mut self,
+        global_id: Option<&GlobalElementId>,
+        cx: &mut WindowContext,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        cx.with_element_state(global_id.unwrap(), |state, cx| {
+            let state = state.unwrap_or_else(|| {
+                let focus_handle = self
+                    .focus_handle
+                    .clone()
+                    .unwrap_or_else(|| cx.focus_handle());
+                let previous_focus = cx.focused();
+                let modal_focus = focus_handle.clone();
+                cx.defer(move |cx| cx.focus(&modal_focus));
+                ModalState {
+                    focus_handle,
+                    previous_focus,
+                }
+            });
+
+            let mut element = self.render(&state, cx);
+            ((element.request_layout(cx), element), state)
+        })
+    }
+
+    fn prepaint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Bounds<Pixels>,
+        element: &mut Self::RequestLayoutState,
+        cx: &mut WindowContext,
+    ) {
+        element.prepaint(cx);
+    }
+
+    fn paint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Bounds<Pixels>,
+        element: &mut Self::RequestLayoutState,
+        _: &mut Self::PrepaintState,
+        cx: &mut WindowContext,
+    ) {
+        element.paint(cx);
+    }
+}
+
+impl IntoElement for Modal {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        self as gpui, FocusableView, Render, TestAppContext, View, ViewContext, VisualContext,
+        VisualTestContext,
+    };
+
+    #[test]
+    fn test_next_tab_stop() {
+        assert_eq!(next_tab_stop(None, 0, false), None);
+        assert_eq!(next_tab_stop(None, 3, false), Some(0));
+        assert_eq!(next_tab_stop(None, 3, true), Some(2));
+        assert_eq!(next_tab_stop(Some(1), 3, false), Some(2));
+        assert_eq!(next_tab_stop(Some(2), 3, false), Some(0));
+        assert_eq!(next_tab_stop(Some(0), 3, true), Some(2));
+    }
+
+    struct ModalTestView {
+        focus_handle: FocusHandle,
+        modal_focus_handle: FocusHandle,
+        show_modal: bool,
+        dismiss_count: usize,
+    }
+
+    impl Render for ModalTestView {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .track_focus(&self.focus_handle)
+                .size_full()
+                .children(self.show_modal.then(|| {
+                    Modal::new(div().size_10().debug_selector(|| "MODAL-CONTENT".into()))
+                        .track_focus(&self.modal_focus_handle)
+                        .on_dismiss(cx.listener(|this, _: &DismissEvent, cx| {
+                            this.show_modal = false;
+                            this.dismiss_count += 1;
+                            cx.notify();
+                        }))
+                }))
+        }
+    }
+
+    impl FocusableView for ModalTestView {
+        fn focus_handle(&self, _: &gpui::AppContext) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    fn open_modal(view: &View<ModalTestView>, cx: &mut VisualTestContext) {
+        view.update(cx, |view, cx| {
+            view.show_modal = true;
+            cx.notify();
+        });
+        cx.run_until_parked();
+        cx.update(|cx| assert!(view.read(cx).modal_focus_handle.is_focused(cx)));
+    }
+
+    fn assert_dismissed(
+        view: &View<ModalTestView>,
+        dismiss_count: usize,
+        cx: &mut VisualTestContext,
+    ) {
+        cx.update(|cx| {
+            let view = view.read(cx);
+            assert!(!view.show_modal);
+            assert_eq!(view.dismiss_count, dismiss_count);
+            assert!(view.focus_handle.is_focused(cx));
+        });
+    }
+
+    #[gpui::test]
+    fn test_modal_dismissal_restores_focus(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|cx| ModalTestView {
+            focus_handle: cx.focus_handle(),
+            modal_focus_handle: cx.focus_handle(),
+            show_modal: false,
+            dismiss_count: 0,
+        });
+        cx.focus_view(&view);
+
+        open_modal(&view, cx);
+        cx.simulate_keystrokes("escape");
+        assert_dismissed(&view, 1, cx);
+
+        // Clicking the modal's content doesn't dismiss it, but clicking the backdrop does.
+        open_modal(&view, cx);
+        let content_bounds = cx.debug_bounds("MODAL-CONTENT").unwrap();
+        cx.simulate_click(content_bounds.center(), Default::default());
+        cx.update(|cx| assert!(view.read(cx).show_modal));
+        cx.simulate_click(point(Pixels(1.), Pixels(1.)), Default::default());
+        assert_dismissed(&view, 2, cx);
+    }
+}
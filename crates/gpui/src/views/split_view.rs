@@ -0,0 +1,427 @@
+use std::rc::Rc;
+
+use crate::{
+    canvas, div, hsla, prelude::FluentBuilder, px, AnyElement, AnyView, Axis, CursorStyle, Div,
+    DragMoveEvent, Empty, EntityId, EventEmitter, Hsla, InteractiveElement, IntoElement,
+    MouseButton, ParentElement, Pixels, Render, StatefulInteractiveElement, Styled, ViewContext,
+    VisualContext, WindowContext,
+};
+
+/// An event emitted by a [`SplitView`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitViewEvent {
+    /// The user dragged the divider to the given position, as a fraction of the split's size.
+    DividerMoved(f32),
+}
+
+/// A divider between the members of a split while it is being dragged, such as the one in a
+/// [`SplitView`].
+#[derive(Clone)]
+pub struct DraggedDivider {
+    /// The view the divider belongs to.
+    pub owner: EntityId,
+    /// The axis of the split the divider is in.
+    pub axis: Axis,
+    /// Identifies the split the divider is in, for views that contain several splits.
+    pub split: Rc<[usize]>,
+    /// The index of the member before the divider.
+    pub ix: usize,
+}
+
+impl Render for DraggedDivider {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        Empty
+    }
+}
+
+/// Two views shown side by side or one above the other, with a divider between them that
+/// the user can drag to resize them.
+///
+/// The position of the divider is stored as a fraction of the split's size, so that the
+/// panes keep their proportions when the window is resized. Neither pane can be dragged
+/// smaller than [`SplitView::min_size`]. The split emits [`SplitViewEvent::DividerMoved`]
+/// whenever the divider moves, so that its owner can persist the new position and restore
+/// it later with [`SplitView::set_divider_position`].
+///
+/// ```
+/// use gpui::{views::SplitView, AnyView, View, VisualContext, WindowContext};
+///
+/// fn open_split(sidebar: AnyView, content: AnyView, cx: &mut WindowContext) -> View<SplitView> {
+///     cx.new_view(|_| SplitView::horizontal(sidebar, content).with_divider_position(0.25))
+/// }
+/// ```
+pub struct SplitView {
+    axis: Axis,
+    first: AnyView,
+    second: AnyView,
+    divider_position: f32,
+    min_size: Pixels,
+    divider_size: Pixels,
+    divider_color: Hsla,
+}
+
+impl SplitView {
+    /// The size of the divider between the panes, unless set with [`SplitView::divider_size`].
+    pub const DEFAULT_DIVIDER_SIZE: Pixels = px(4.);
+
+    /// The smallest size of a pane, unless set with [`SplitView::min_size`].
+    pub const DEFAULT_MIN_SIZE: Pixels = px(80.);
+
+    /// Creates a split with `first` on the left and `second` on the right.
+    pub fn horizontal(first: impl Into<AnyView>, second: impl Into<AnyView>) -> Self {
+        Self::new(Axis::Horizontal, first.into(), second.into())
+    }
+
+    /// Creates a split with `first` at the top and `second` at the bottom.
+    pub fn vertical(first: impl Into<AnyView>, second: impl Into<AnyView>) -> Self {
+        Self::new(Axis::Vertical, first.into(), second.into())
+    }
+
+    fn new(axis: Axis, first: AnyView, second: AnyView) -> Self {
+        Self {
+            axis,
+            first,
+            second,
+            divider_position: 0.5,
+            min_size: Self::DEFAULT_MIN_SIZE,
+            divider_size: Self::DEFAULT_DIVIDER_SIZE,
+            divider_color: hsla(0., 0., 0.5, 0.3),
+        }
+    }
+
+    /// Sets the initial position of the divider, as a fraction of the split's size between
+    /// 0 and 1. Defaults to 0.5.
+    pub fn with_divider_position(mut self, position: f32) -> Self {
+        self.divider_position = position.clamp(0., 1.);
+        self
+    }
+
+    /// Sets the smallest size each pane can be dragged to.
+    pub fn min_size(mut self, min_size: impl Into<Pixels>) -> Self {
+        self.min_size = min_size.into();
+        self
+    }
+
+    /// Sets the width of a horizontal split's divider, or the height of a vertical one's.
+    pub fn divider_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.divider_size = size.into();
+        self
+    }
+
+    /// Sets the color of the divider.
+    pub fn divider_color(mut self, color: impl Into<Hsla>) -> Self {
+        self.divider_color = color.into();
+        self
+    }
+
+    /// Returns the axis the panes are laid out along.
+    pub fn axis(&self) -> Axis {
+        self.axis
+    }
+
+    /// Returns the position of the divider, as a fraction of the split's size.
+    pub fn divider_position(&self) -> f32 {
+        self.divider_position
+    }
+
+    /// Moves the divider to the given position, as a fraction of the split's size.
+    pub fn set_divider_position(&mut self, position: f32, cx: &mut ViewContext<Self>) {
+        self.divider_position = position.clamp(0., 1.);
+        cx.notify();
+    }
+
+    fn resize(&mut self, event: &DragMoveEvent<DraggedDivider>, cx: &mut ViewContext<Self>) {
+        if event.drag(cx).owner != cx.entity_id() {
+            return;
+        }
+
+        let mut flexes = [self.divider_position, 1. - self.divider_position];
+        if resize_flexes(
+            &mut flexes,
+            0,
+            self.axis,
+            event,
+            self.divider_size,
+            self.min_size,
+        ) {
+            self.divider_position = flexes[0] / (flexes[0] + flexes[1]);
+            cx.emit(SplitViewEvent::DividerMoved(self.divider_position));
+            cx.notify();
+        }
+    }
+}
+
+/// Moves the divider after member `ix` of a split along `axis` to where it's being dragged,
+/// keeping the
+/// members on either side of it at least `min_size` big. Returns whether the flexes changed.
+///
+/// The members of the split are expected to be laid out with [`split_member`], separated by
+/// dividers that are `divider_size` wide, and the drag to be handled by the split itself so
+/// that the event's bounds are the split's.
+pub fn resize_flexes(
+    flexes: &mut [f32],
+    ix: usize,
+    axis: Axis,
+    event: &DragMoveEvent<DraggedDivider>,
+    divider_size: Pixels,
+    min_size: Pixels,
+) -> bool {
+    let bounds = event.bounds;
+    let (offset, size) = match axis {
+        Axis::Horizontal => (event.event.position.x - bounds.origin.x, bounds.size.width),
+        Axis::Vertical => (event.event.position.y - bounds.origin.y, bounds.size.height),
+    };
+    resize_flexes_to(flexes, ix, offset, size, divider_size, min_size)
+}
+
+/// Moves the divider after member `ix` of a split to `offset` along a split of the given
+/// `size`. See [`resize_flexes`].
+fn resize_flexes_to(
+    flexes: &mut [f32],
+    ix: usize,
+    offset: Pixels,
+    size: Pixels,
+    divider_size: Pixels,
+    min_size: Pixels,
+) -> bool {
+    if ix + 1 >= flexes.len() {
+        return false;
+    }
+    let available = size - divider_size * (flexes.len() - 1) as f32;
+    let total_flex = flexes.iter().sum::<f32>();
+    if available <= Pixels::ZERO || total_flex <= 0. {
+        return false;
+    }
+    let member_size = |flex: f32| available * (flex / total_flex);
+
+    let start = flexes[..ix]
+        .iter()
+        .fold(Pixels::ZERO, |start, flex| start + member_size(*flex))
+        + divider_size * ix as f32;
+    let pair_flex = flexes[ix] + flexes[ix + 1];
+    let pair_size = member_size(pair_flex);
+    if pair_size < min_size * 2. {
+        return false;
+    }
+
+    let new_size = (offset - start - divider_size / 2.).clamp(min_size, pair_size - min_size);
+    let new_flex = new_size / available * total_flex;
+    if new_flex == flexes[ix] {
+        return false;
+    }
+    flexes[ix] = new_flex;
+    flexes[ix + 1] = pair_flex - new_flex;
+    true
+}
+
+/// Returns a container for a member of a split laid out along `axis`. Members start out empty
+/// and grow in proportion to their `flex`, so they share the space left over by the dividers.
+pub fn split_member(axis: Axis, flex: f32) -> Div {
+    let mut member = div()
+        .flex_basis(px(0.))
+        .overflow_hidden()
+        .map(|this| match axis {
+            Axis::Horizontal => this.h_full().min_w_0(),
+            Axis::Vertical => this.w_full().min_h_0(),
+        });
+    member.style().flex_grow = Some(flex);
+    member
+}
+
+/// Returns the cursor shown over a divider between the members of a split along `axis`.
+pub fn resize_cursor(axis: Axis) -> CursorStyle {
+    match axis {
+        Axis::Horizontal => CursorStyle::ResizeLeftRight,
+        Axis::Vertical => CursorStyle::ResizeUpDown,
+    }
+}
+
+/// Returns an element that covers its parent and shows the resize cursor while one of the
+/// given view's dividers is being dragged.
+///
+/// Elements don't set the cursor while something is being dragged, so without it the cursor
+/// would change back as soon as it leaves the divider.
+pub fn dragged_divider_cursor(owner: EntityId, cx: &WindowContext) -> Option<AnyElement> {
+    let axis = cx
+        .active_drag
+        .as_ref()
+        .and_then(|drag| drag.value.downcast_ref::<DraggedDivider>())
+        .filter(|divider| divider.owner == owner)?
+        .axis;
+    Some(
+        canvas(
+            |bounds, cx| cx.insert_hitbox(bounds, false),
+            move |_, hitbox, cx| cx.set_cursor_style(resize_cursor(axis), &hitbox),
+        )
+        .absolute()
+        .size_full()
+        .into_any_element(),
+    )
+}
+
+impl EventEmitter<SplitViewEvent> for SplitView {}
+
+impl Render for SplitView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let axis = self.axis;
+        let divider = div()
+            .id("split-view-divider")
+            .debug_selector(|| "SPLIT-VIEW-DIVIDER".into())
+            .flex_none()
+            .bg(self.divider_color)
+            .map(|this| match axis {
+                Axis::Horizontal => this.w(self.divider_size).h_full(),
+                Axis::Vertical => this.h(self.divider_size).w_full(),
+            })
+            .cursor(resize_cursor(axis))
+            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+            .on_drag(
+                DraggedDivider {
+                    owner: cx.entity_id(),
+                    axis,
+                    split: Rc::from([]),
+                    ix: 0,
+                },
+                |divider, cx| {
+                    cx.stop_propagation();
+                    cx.new_view(|_| divider.clone())
+                },
+            );
+
+        let first = split_member(axis, self.divider_position).child(self.first.clone());
+        let second = split_member(axis, 1. - self.divider_position).child(self.second.clone());
+
+        div()
+            .id("split-view")
+            .relative()
+            .flex()
+            .map(|this| match axis {
+                Axis::Horizontal => this.flex_row(),
+                Axis::Vertical => this.flex_col(),
+            })
+            .size_full()
+            .on_drag_move(cx.listener(Self::resize))
+            .child(first)
+            .child(divider)
+            .child(second)
+            .children(dragged_divider_cursor(cx.entity_id(), cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::{self as gpui, point, size, EmptyView, Modifiers, TestAppContext};
+
+    #[test]
+    fn test_resize_flexes() {
+        let mut flexes = [0.5, 0.5];
+        // Drag the divider to 30% of a 1000px split.
+        assert!(resize_flexes_to(
+            &mut flexes,
+            0,
+            px(300.),
+            px(1000.),
+            px(0.),
+            px(100.)
+        ));
+        assert_eq!(flexes[0], 0.3);
+        assert!(resize_flexes_to(
+            &mut flexes,
+            0,
+            px(-20.),
+            px(1000.),
+            px(0.),
+            px(100.)
+        ));
+        assert_eq!(flexes[0], 0.1);
+
+        // The members next to the divider can't be made smaller than the minimum size, and
+        // the other members keep their size.
+        let mut flexes = [1., 1., 2.];
+        assert!(resize_flexes_to(
+            &mut flexes,
+            1,
+            px(960.),
+            px(1024.),
+            px(0.),
+            px(128.)
+        ));
+        assert_eq!(flexes, [1., 2.5, 0.5]);
+        assert!(!resize_flexes_to(
+            &mut flexes,
+            1,
+            px(1000.),
+            px(1024.),
+            px(0.),
+            px(128.)
+        ));
+
+        // Members that are already too small can't be resized.
+        assert!(!resize_flexes_to(
+            &mut [1., 1.],
+            0,
+            px(50.),
+            px(150.),
+            px(0.),
+            px(100.)
+        ));
+    }
+
+    #[gpui::test]
+    fn test_dragging_divider(cx: &mut TestAppContext) {
+        let (split, cx) = cx.add_window_view(|cx| {
+            let first = cx.new_view(|_| EmptyView);
+            let second = cx.new_view(|_| EmptyView);
+            SplitView::horizontal(first, second).min_size(px(100.))
+        });
+        cx.simulate_resize(size(px(1004.), px(600.)));
+        let events = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&split, move |_, event: &SplitViewEvent, _| {
+                events.borrow_mut().push(*event)
+            })
+            .detach();
+        });
+        cx.run_until_parked();
+
+        let divider = cx.debug_bounds("SPLIT-VIEW-DIVIDER").unwrap();
+        assert_eq!(divider.origin.x, px(500.));
+
+        cx.simulate_mouse_down(divider.center(), MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(
+            point(px(302.), divider.center().y),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        cx.simulate_mouse_move(
+            point(px(252.), divider.center().y),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        cx.update(|cx| assert_eq!(split.read(cx).divider_position(), 0.25));
+
+        // The divider stops where the first pane would get smaller than its minimum size.
+        cx.simulate_mouse_move(
+            point(px(20.), divider.center().y),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        cx.simulate_mouse_up(
+            point(px(20.), divider.center().y),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        cx.update(|cx| assert_eq!(split.read(cx).divider_position(), 0.1));
+        assert_eq!(
+            events.borrow().last(),
+            Some(&SplitViewEvent::DividerMoved(0.1))
+        );
+
+        let divider = cx.debug_bounds("SPLIT-VIEW-DIVIDER").unwrap();
+        assert_eq!(divider.origin.x, px(100.));
+    }
+}
@@ -1,20 +1,20 @@
 use crate::{
-    hash, point, prelude::*, px, size, transparent_black, Action, AnyDrag, AnyElement, AnyTooltip,
-    AnyView, AppContext, Arena, Asset, AsyncWindowContext, AvailableSpace, Bounds, BoxShadow,
-    Context, Corners, CursorStyle, Decorations, DevicePixels, DispatchActionListener,
-    DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter,
-    FileDropEvent, Flatten, FontId, Global, GlobalElementId, GlyphId, Hsla, ImageData,
-    InputHandler, IsZero, KeyBinding, KeyContext, KeyDownEvent, KeyEvent, KeyMatch, KeymatchResult,
-    Keystroke, KeystrokeEvent, LayoutId, LineLayoutIndex, Model, ModelContext, Modifiers,
-    ModifiersChangedEvent, MonochromeSprite, MouseButton, MouseEvent, MouseMoveEvent, MouseUpEvent,
-    Path, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler,
-    PlatformWindow, Point, PolychromeSprite, PromptLevel, Quad, Render, RenderGlyphParams,
-    RenderImageParams, RenderSvgParams, ResizeEdge, ScaledPixels, Scene, Shadow, SharedString,
-    Size, StrikethroughStyle, Style, SubscriberSet, Subscription, TaffyLayoutEngine, Task,
-    TextStyle, TextStyleRefinement, TransformationMatrix, Underline, UnderlineStyle, View,
-    VisualContext, WeakView, WindowAppearance, WindowBackgroundAppearance, WindowBounds,
-    WindowControls, WindowDecorations, WindowOptions, WindowParams, WindowTextSystem,
-    SUBPIXEL_VARIANTS,
+    hash, point, prelude::*, px, size, transparent_black, Action, AnimationClock, AnyDrag,
+    AnyElement, AnyTooltip, AnyView, AppContext, Arena, Asset, AsyncWindowContext, AvailableSpace,
+    Background, BorderStyle, Bounds, BoxShadow, Context, Corners, CursorStyle, Decorations,
+    DispatchActionListener, DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity,
+    EntityId, EventEmitter, FileDropEvent, Fill, Flatten, FontId, Global, GlobalElementId, GlyphId,
+    Hsla, ImageData, InputHandler, IsZero, KeyBinding, KeyContext, KeyDownEvent, KeyEvent,
+    KeyMatch, KeymatchResult, Keystroke, KeystrokeEvent, LayoutId, LineLayoutIndex, Model,
+    ModelContext, Modifiers, ModifiersChangedEvent, MonochromeSprite, MouseButton, MouseEvent,
+    MouseMoveEvent, MouseUpEvent, Path, PathBuilder, Pixels, PlatformAtlas, PlatformDisplay,
+    PlatformInput, PlatformInputHandler, PlatformWindow, Point, PolychromeSprite, PromptLevel,
+    Quad, Render, RenderGlyphParams, RenderImageParams, RenderSvgParams, ResizeEdge, ScaledPixels,
+    Scene, Shadow, SharedString, Size, StrikethroughStyle, Style, SubscriberSet, Subscription,
+    SvgRasterCache, TaffyLayoutEngine, Task, TextStyle, TextStyleRefinement, TransformationMatrix,
+    Underline, UnderlineStyle, View, VisualContext, WeakView, WindowAppearance,
+    WindowBackgroundAppearance, WindowBounds, WindowControls, WindowDecorations, WindowOptions,
+    WindowParams, WindowTextSystem, SUBPIXEL_VARIANTS,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::{FxHashMap, FxHashSet};
@@ -385,6 +385,7 @@ pub(crate) struct TooltipRequest {
 }
 
 pub(crate) struct DeferredDraw {
+    z_index: usize,
     priority: usize,
     parent_node: DispatchNodeId,
     element_id_stack: SmallVec<[ElementId; 32]>,
@@ -408,6 +409,9 @@ pub(crate) struct Frame {
     pub(crate) input_handlers: Vec<Option<PlatformInputHandler>>,
     pub(crate) tooltip_requests: Vec<Option<TooltipRequest>>,
     pub(crate) cursor_styles: Vec<CursorStyleRequest>,
+    /// Paths tessellated by [`WindowContext::paint_path_builder`] with their builders, by the
+    /// hash of their builder.
+    pub(crate) tessellated_paths: FxHashMap<u64, (PathBuilder, Path<Pixels>)>,
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) debug_bounds: FxHashMap<String, Bounds<Pixels>>,
 }
@@ -447,6 +451,7 @@ impl Frame {
             input_handlers: Vec::new(),
             tooltip_requests: Vec::new(),
             cursor_styles: Vec::new(),
+            tessellated_paths: FxHashMap::default(),
 
             #[cfg(any(test, feature = "test-support"))]
             debug_bounds: FxHashMap::default(),
@@ -464,13 +469,13 @@ impl Frame {
         self.cursor_styles.clear();
         self.hitboxes.clear();
         self.deferred_draws.clear();
+        self.tessellated_paths.clear();
     }
 
     pub(crate) fn hit_test(&self, position: Point<Pixels>) -> HitTest {
         let mut hit_test = HitTest::default();
         for hitbox in self.hitboxes.iter().rev() {
-            let bounds = hitbox.bounds.intersect(&hitbox.content_mask.bounds);
-            if bounds.contains(&position) {
+            if hitbox.bounds.contains(&position) && hitbox.content_mask.contains(&position) {
                 hit_test.0.push(hitbox.id);
                 if hitbox.opaque {
                     break;
@@ -507,6 +512,7 @@ pub struct Window {
     pub(crate) platform_window: Box<dyn PlatformWindow>,
     display_id: Option<DisplayId>,
     sprite_atlas: Arc<dyn PlatformAtlas>,
+    svg_cache: SvgRasterCache,
     text_system: Arc<WindowTextSystem>,
     rem_size: Pixels,
     /// The stack of override values for the window's rem size.
@@ -520,14 +526,16 @@ pub struct Window {
     pub(crate) element_id_stack: SmallVec<[ElementId; 32]>,
     pub(crate) text_style_stack: Vec<TextStyleRefinement>,
     pub(crate) element_offset_stack: Vec<Point<Pixels>>,
+    pub(crate) layer_stack: Vec<usize>,
     pub(crate) content_mask_stack: Vec<ContentMask<Pixels>>,
+    pub(crate) element_opacity: Option<f32>,
     pub(crate) requested_autoscroll: Option<Bounds<Pixels>>,
     pub(crate) rendered_frame: Frame,
     pub(crate) next_frame: Frame,
     pub(crate) next_hitbox_id: HitboxId,
     pub(crate) next_tooltip_id: TooltipId,
     pub(crate) tooltip_bounds: Option<TooltipBounds>,
-    next_frame_callbacks: Rc<RefCell<Vec<FrameCallback>>>,
+    pub(crate) next_frame_callbacks: Rc<RefCell<Vec<FrameCallback>>>,
     pub(crate) dirty_views: FxHashSet<EntityId>,
     pub(crate) focus_handles: Arc<RwLock<SlotMap<FocusId, AtomicUsize>>>,
     focus_listeners: SubscriberSet<(), AnyWindowFocusListener>,
@@ -553,6 +561,7 @@ pub struct Window {
     pending_input: Option<PendingInput>,
     pending_modifier: ModifierState,
     pending_input_observers: SubscriberSet<(), AnyObserver>,
+    keystroke_capture: Option<KeystrokeCapture>,
     prompt: Option<RenderablePromptHandle>,
 }
 
@@ -601,6 +610,13 @@ impl PendingInput {
     }
 }
 
+/// Keystrokes being recorded by [`WindowContext::capture_keystrokes`] instead of being dispatched.
+struct KeystrokeCapture {
+    keystrokes: Vec<Keystroke>,
+    on_finish: Box<dyn FnOnce(Option<Vec<Keystroke>>, &mut WindowContext)>,
+    timer: Option<Task<()>>,
+}
+
 pub(crate) struct ElementStateBox {
     pub(crate) inner: Box<dyn Any>,
     #[cfg(debug_assertions)]
@@ -811,6 +827,7 @@ impl Window {
             platform_window,
             display_id,
             sprite_atlas,
+            svg_cache: SvgRasterCache::new(SvgRasterCache::DEFAULT_CAPACITY),
             text_system,
             rem_size: px(16.),
             rem_size_override_stack: SmallVec::new(),
@@ -820,7 +837,9 @@ impl Window {
             element_id_stack: SmallVec::default(),
             text_style_stack: Vec::new(),
             element_offset_stack: Vec::new(),
+            layer_stack: Vec::new(),
             content_mask_stack: Vec::new(),
+            element_opacity: None,
             requested_autoscroll: None,
             rendered_frame: Frame::new(DispatchTree::new(cx.keymap.clone(), cx.actions.clone())),
             next_frame: Frame::new(DispatchTree::new(cx.keymap.clone(), cx.actions.clone())),
@@ -853,6 +872,7 @@ impl Window {
             pending_input: None,
             pending_modifier: ModifierState::default(),
             pending_input_observers: SubscriberSet::new(),
+            keystroke_capture: None,
             prompt: None,
         })
     }
@@ -871,13 +891,25 @@ pub(crate) struct DispatchEventResult {
 }
 
 /// Indicates which region of the window is visible. Content falling outside of this mask will not be
-/// rendered. Currently, only rectangular content masks are supported, but we give the mask its own type
-/// to leave room to support more complex shapes in the future.
+/// rendered. Masks are rectangles whose corners may optionally be rounded, which lets elements with
+/// rounded corners clip their children to the rounded shape.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 #[repr(C)]
 pub struct ContentMask<P: Clone + Default + Debug> {
     /// The bounds
     pub bounds: Bounds<P>,
+    /// The radius of each corner of the mask. Zero radii produce a rectangular mask.
+    pub corner_radii: Corners<P>,
+}
+
+impl<P: Clone + Default + Debug> ContentMask<P> {
+    /// Creates a rectangular content mask with the given bounds.
+    pub fn new(bounds: Bounds<P>) -> Self {
+        Self {
+            bounds,
+            corner_radii: Corners::default(),
+        }
+    }
 }
 
 impl ContentMask<Pixels> {
@@ -885,13 +917,113 @@ impl ContentMask<Pixels> {
     pub fn scale(&self, factor: f32) -> ContentMask<ScaledPixels> {
         ContentMask {
             bounds: self.bounds.scale(factor),
+            corner_radii: self.corner_radii.scale(factor),
         }
     }
 
+    /// The largest radius a corner of the mask is drawn with, half of its shorter side.
+    fn max_corner_radius(&self) -> Pixels {
+        self.bounds.size.width.min(self.bounds.size.height) / 2.
+    }
+
+    /// Whether the given point is visible through the mask, outside of its rounded corners.
+    pub fn contains(&self, point: &Point<Pixels>) -> bool {
+        if !self.bounds.contains(point) {
+            return false;
+        }
+
+        let max_radius = self.max_corner_radius();
+        let (origin, lower_right) = (self.bounds.origin, self.bounds.lower_right());
+        let corners: [(Pixels, Pixels, Pixels, f32, f32); 4] = [
+            (self.corner_radii.top_left, origin.x, origin.y, 1., 1.),
+            (
+                self.corner_radii.top_right,
+                lower_right.x,
+                origin.y,
+                -1.,
+                1.,
+            ),
+            (
+                self.corner_radii.bottom_right,
+                lower_right.x,
+                lower_right.y,
+                -1.,
+                -1.,
+            ),
+            (
+                self.corner_radii.bottom_left,
+                origin.x,
+                lower_right.y,
+                1.,
+                -1.,
+            ),
+        ];
+        corners
+            .into_iter()
+            .all(|(radius, corner_x, corner_y, sign_x, sign_y)| {
+                let radius = radius.min(max_radius);
+                // The distance from the center of the corner's circle, towards the corner.
+                let dx = (corner_x + radius * sign_x - point.x).0 * sign_x;
+                let dy = (corner_y + radius * sign_y - point.y).0 * sign_y;
+                dx <= 0. || dy <= 0. || dx.hypot(dy) <= radius.0
+            })
+    }
+
     /// Intersect the content mask with the given content mask.
+    ///
+    /// A corner of the resulting mask that lies within a rounded corner of one of the two masks
+    /// is rounded concentrically with it, so that masks inset from a rounded mask keep clipping
+    /// to its shape. Other corners are square.
     pub fn intersect(&self, other: &Self) -> Self {
         let bounds = self.bounds.intersect(&other.bounds);
-        ContentMask { bounds }
+        let corner_radius = |corner: fn(&Bounds<Pixels>) -> Point<Pixels>,
+                             radius: fn(&Corners<Pixels>) -> Pixels| {
+            let mut result = Pixels::ZERO;
+            for mask in [self, other] {
+                let radius = radius(&mask.corner_radii).min(mask.max_corner_radius());
+                let inset = corner(&bounds) - corner(&mask.bounds);
+                let (inset_x, inset_y) = (inset.x.abs(), inset.y.abs());
+                if inset_x < radius && inset_y < radius {
+                    result = result.max(radius - inset_x.min(inset_y));
+                }
+            }
+            result
+        };
+        let corner_radii = Corners {
+            top_left: corner_radius(|bounds| bounds.origin, |radii| radii.top_left),
+            top_right: corner_radius(Bounds::upper_right, |radii| radii.top_right),
+            bottom_right: corner_radius(Bounds::lower_right, |radii| radii.bottom_right),
+            bottom_left: corner_radius(Bounds::lower_left, |radii| radii.bottom_left),
+        };
+        ContentMask {
+            bounds,
+            corner_radii,
+        }
+    }
+}
+
+fn animation_frame(
+    mut clock: AnimationClock,
+    duration: Duration,
+    easing: Box<dyn Fn(f32) -> f32>,
+    mut callback: Box<dyn FnMut(f32, &mut WindowContext)>,
+    done: oneshot::Sender<()>,
+    cx: &mut WindowContext,
+) {
+    if done.is_canceled() {
+        return;
+    }
+    let elapsed = clock.tick(cx.background_executor().now());
+    let delta = if duration.is_zero() {
+        1.0
+    } else {
+        (elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0)
+    };
+    callback(easing(delta), cx);
+    if delta < 1.0 {
+        cx.on_next_frame(move |cx| animation_frame(clock, duration, easing, callback, done, cx));
+    } else {
+        done.send(()).ok();
     }
 }
 
@@ -1172,6 +1304,28 @@ impl<'a> WindowContext<'a> {
         RefCell::borrow_mut(&self.window.next_frame_callbacks).push(Box::new(callback));
     }
 
+    /// Run an animation for the given duration, calling the given closure once per frame with
+    /// the eased progress of the animation, between 0 and 1. The closure is always called with
+    /// the final value before the animation finishes. The returned task completes when the
+    /// animation finishes, and dropping it stops the animation.
+    pub fn animate(
+        &mut self,
+        duration: Duration,
+        easing: impl Fn(f32) -> f32 + 'static,
+        callback: impl FnMut(f32, &mut WindowContext) + 'static,
+    ) -> Task<()> {
+        let (done_tx, done_rx) = oneshot::channel();
+        let clock = AnimationClock::new(self.background_executor().now());
+        let easing = Box::new(easing);
+        let callback = Box::new(callback);
+        self.on_next_frame(move |cx| {
+            animation_frame(clock, duration, easing, callback, done_tx, cx)
+        });
+        self.spawn(|_| async move {
+            done_rx.await.ok();
+        })
+    }
+
     /// Spawn the future returned by the given closure on the application thread pool.
     /// The closure is provided a handle to the current window and an `AsyncWindowContext` for
     /// use within your future.
@@ -1185,7 +1339,15 @@ impl<'a> WindowContext<'a> {
     }
 
     fn bounds_changed(&mut self) {
-        self.window.scale_factor = self.window.platform_window.scale_factor();
+        let scale_factor = self.window.platform_window.scale_factor();
+        if scale_factor != self.window.scale_factor {
+            // SVGs are rasterized at their size in device pixels, so the existing rasterizations
+            // won't be drawn again.
+            for params in self.window.svg_cache.clear() {
+                self.window.sprite_atlas.remove(&params.into());
+            }
+        }
+        self.window.scale_factor = scale_factor;
         self.window.viewport_size = self.window.platform_window.content_size();
         self.window.display_id = self
             .window
@@ -1449,6 +1611,9 @@ impl<'a> WindowContext<'a> {
 
         self.window.layout_engine.as_mut().unwrap().clear();
         self.text_system().finish_frame();
+        for params in self.window.svg_cache.finish_frame() {
+            self.window.sprite_atlas.remove(&params.into());
+        }
         self.window
             .next_frame
             .finish(&mut self.window.rendered_frame);
@@ -1518,10 +1683,17 @@ impl<'a> WindowContext<'a> {
         // Layout all root elements.
         let mut root_element = self.window.root_view.as_ref().unwrap().clone().into_any();
         root_element.prepaint_as_root(Point::default(), self.window.viewport_size.into(), self);
+        debug_assert!(
+            self.window.layer_stack.is_empty(),
+            "every push_layer must be matched by a pop_layer"
+        );
 
         let mut sorted_deferred_draws =
             (0..self.window.next_frame.deferred_draws.len()).collect::<SmallVec<[_; 8]>>();
-        sorted_deferred_draws.sort_by_key(|ix| self.window.next_frame.deferred_draws[*ix].priority);
+        sorted_deferred_draws.sort_by_key(|ix| {
+            let deferred_draw = &self.window.next_frame.deferred_draws[*ix];
+            (deferred_draw.z_index, deferred_draw.priority)
+        });
         self.prepaint_deferred_draws(&sorted_deferred_draws);
 
         let mut prompt_element = None;
@@ -1716,6 +1888,7 @@ impl<'a> WindowContext<'a> {
                     parent_node: reused_subtree.refresh_node_id(deferred_draw.parent_node),
                     element_id_stack: deferred_draw.element_id_stack.clone(),
                     text_style_stack: deferred_draw.text_style_stack.clone(),
+                    z_index: deferred_draw.z_index,
                     priority: deferred_draw.priority,
                     element: None,
                     absolute_offset: deferred_draw.absolute_offset,
@@ -1854,6 +2027,44 @@ impl<'a> WindowContext<'a> {
         }
     }
 
+    /// Invoke the given function with the given opacity applied to everything it paints, multiplied
+    /// with the opacity of any enclosing elements. This method should only be called during the paint
+    /// phase of element drawing.
+    pub fn with_element_opacity<R>(
+        &mut self,
+        opacity: Option<f32>,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        debug_assert_eq!(
+            self.window.draw_phase,
+            DrawPhase::Paint,
+            "this method can only be called during paint"
+        );
+        let Some(opacity) = opacity else {
+            return f(self);
+        };
+
+        let parent_opacity = self.window.element_opacity;
+        self.window.element_opacity = Some(parent_opacity.unwrap_or(1.) * opacity.clamp(0., 1.));
+        let result = f(self);
+        self.window.element_opacity = parent_opacity;
+        result
+    }
+
+    /// Obtain the opacity applied to everything painted at this point, which is `1.` outside of any
+    /// element with a reduced opacity. This method should only be called during element drawing.
+    pub fn element_opacity(&self) -> f32 {
+        self.window.element_opacity.unwrap_or(1.)
+    }
+
+    /// Returns the given color with its alpha scaled by the current element opacity.
+    fn apply_opacity(&self, color: Hsla) -> Hsla {
+        Hsla {
+            a: color.a * self.element_opacity(),
+            ..color
+        }
+    }
+
     /// Updates the global element offset relative to the current offset. This is used to implement
     /// scrolling. This method should only be called during the prepaint phase of element drawing.
     pub fn with_element_offset<R>(
@@ -2065,11 +2276,11 @@ impl<'a> WindowContext<'a> {
             .content_mask_stack
             .last()
             .cloned()
-            .unwrap_or_else(|| ContentMask {
-                bounds: Bounds {
+            .unwrap_or_else(|| {
+                ContentMask::new(Bounds {
                     origin: Point::default(),
                     size: self.window().viewport_size,
-                },
+                })
             })
     }
 
@@ -2212,8 +2423,9 @@ impl<'a> WindowContext<'a> {
     }
 
     /// Defers the drawing of the given element, scheduling it to be painted on top of the currently-drawn tree
-    /// at a later time. The `priority` parameter determines the drawing order relative to other deferred elements,
-    /// with higher values being drawn on top.
+    /// at a later time. Deferred elements are drawn in the order of the z-index of the layer they were deferred
+    /// in (see [`Self::push_layer`]), and then of their `priority`, with higher values being drawn on top.
+    /// Since hitboxes are tested in paint order, the topmost element also receives the mouse events over it.
     ///
     /// This method should only be called as part of the prepaint phase of element drawing.
     pub fn defer_draw(
@@ -2233,6 +2445,7 @@ impl<'a> WindowContext<'a> {
             parent_node,
             element_id_stack: window.element_id_stack.clone(),
             text_style_stack: window.text_style_stack.clone(),
+            z_index: window.layer_stack.last().copied().unwrap_or_default(),
             priority,
             element: Some(element),
             absolute_offset,
@@ -2241,6 +2454,25 @@ impl<'a> WindowContext<'a> {
         });
     }
 
+    /// Pushes a stacking layer with the given z-index. Elements deferred until it is popped are drawn above
+    /// the ones deferred in layers with a lower z-index, whatever their priority. Deferred elements outside
+    /// of any layer have a z-index of 0.
+    ///
+    /// This method should only be called as part of the prepaint phase of element drawing.
+    pub fn push_layer(&mut self, z_index: usize) {
+        debug_assert_eq!(
+            self.window.draw_phase,
+            DrawPhase::Prepaint,
+            "this method can only be called during prepaint"
+        );
+        self.window.layer_stack.push(z_index);
+    }
+
+    /// Pops the stacking layer pushed by the last call to [`Self::push_layer`].
+    pub fn pop_layer(&mut self) {
+        self.window.layer_stack.pop();
+    }
+
     /// Creates a new painting layer for the specified bounds. A "layer" is a batch
     /// of geometry that are non-overlapping and have the same draw order. This is typically used
     /// for performance reasons.
@@ -2299,7 +2531,7 @@ impl<'a> WindowContext<'a> {
                 bounds: shadow_bounds.scale(scale_factor),
                 content_mask: content_mask.scale(scale_factor),
                 corner_radii: corner_radii.scale(scale_factor),
-                color: shadow.color,
+                color: self.apply_opacity(shadow.color),
             });
         }
     }
@@ -2316,17 +2548,42 @@ impl<'a> WindowContext<'a> {
             "this method can only be called during paint"
         );
 
+        // Empty quads have nothing to draw, and their outlines can't be dashed.
+        if quad.bounds.size.width <= Pixels::ZERO || quad.bounds.size.height <= Pixels::ZERO {
+            return;
+        }
+
         let scale_factor = self.scale_factor();
         let content_mask = self.content_mask();
-        self.window.next_frame.scene.insert_primitive(Quad {
+        let corner_radii = quad
+            .corner_radii
+            .clamp_radii_for_quad_size(quad.bounds.size);
+        let (dash_length, dash_gap) = quad
+            .border_style
+            .dash_pattern(quad.border_widths.max(), quad.bounds.size, &corner_radii)
+            .unwrap_or_default();
+        let background = Background::new(
+            &quad.background,
+            quad.bounds,
+            scale_factor,
+            self.element_opacity(),
+        );
+        let scene = &mut self.window.next_frame.scene;
+        let (background, gradient_id) = match background {
+            Background::Solid(color) => (color, 0),
+            Background::Gradient(gradient) => (gradient.colors[0], scene.push_gradient(gradient)),
+        };
+        scene.insert_primitive(Quad {
             order: 0,
-            pad: 0,
+            gradient_id,
             bounds: quad.bounds.scale(scale_factor),
             content_mask: content_mask.scale(scale_factor),
-            background: quad.background,
-            border_color: quad.border_color,
-            corner_radii: quad.corner_radii.scale(scale_factor),
+            background,
+            border_color: self.apply_opacity(quad.border_color),
+            corner_radii: corner_radii.scale(scale_factor),
             border_widths: quad.border_widths.scale(scale_factor),
+            dash_length: dash_length.scale(scale_factor),
+            dash_gap: dash_gap.scale(scale_factor),
         });
     }
 
@@ -2343,13 +2600,43 @@ impl<'a> WindowContext<'a> {
         let scale_factor = self.scale_factor();
         let content_mask = self.content_mask();
         path.content_mask = content_mask;
-        path.color = color.into();
+        path.color = self.apply_opacity(color.into());
         self.window
             .next_frame
             .scene
             .insert_primitive(path.scale(scale_factor));
     }
 
+    /// Tessellate the path of the given builder and paint it into the scene for the next frame
+    /// at the current z-index. Paths that were painted in the previous frame aren't tessellated
+    /// again.
+    ///
+    /// This method should only be called as part of the paint phase of element drawing.
+    pub fn paint_path_builder(&mut self, builder: &PathBuilder, color: impl Into<Hsla>) {
+        let key = builder.content_hash();
+        // Builders whose hashes collide are told apart by comparing them.
+        let path = if let Some((_, path)) = self
+            .window
+            .next_frame
+            .tessellated_paths
+            .get(&key)
+            .filter(|(cached_builder, _)| cached_builder == builder)
+        {
+            path.clone()
+        } else {
+            let path = match self.window.rendered_frame.tessellated_paths.remove(&key) {
+                Some((cached_builder, path)) if cached_builder == *builder => path,
+                _ => builder.build(),
+            };
+            self.window
+                .next_frame
+                .tessellated_paths
+                .insert(key, (builder.clone(), path.clone()));
+            path
+        };
+        self.paint_path(path, color);
+    }
+
     /// Paint an underline into the scene for the next frame at the current z-index.
     ///
     /// This method should only be called as part of the paint phase of element drawing.
@@ -2382,7 +2669,7 @@ impl<'a> WindowContext<'a> {
             pad: 0,
             bounds: bounds.scale(scale_factor),
             content_mask: content_mask.scale(scale_factor),
-            color: style.color.unwrap_or_default(),
+            color: self.apply_opacity(style.color.unwrap_or_default()),
             thickness: style.thickness.scale(scale_factor),
             wavy: style.wavy,
         });
@@ -2417,7 +2704,7 @@ impl<'a> WindowContext<'a> {
             bounds: bounds.scale(scale_factor),
             content_mask: content_mask.scale(scale_factor),
             thickness: style.thickness.scale(scale_factor),
-            color: style.color.unwrap_or_default(),
+            color: self.apply_opacity(style.color.unwrap_or_default()),
             wavy: false,
         });
     }
@@ -2482,7 +2769,7 @@ impl<'a> WindowContext<'a> {
                     pad: 0,
                     bounds,
                     content_mask,
-                    color,
+                    color: self.apply_opacity(color),
                     tile,
                     transformation: TransformationMatrix::unit(),
                 });
@@ -2546,6 +2833,8 @@ impl<'a> WindowContext<'a> {
                 .insert_primitive(PolychromeSprite {
                     order: 0,
                     grayscale: false,
+                    opacity: self.element_opacity(),
+                    pad: 0,
                     bounds,
                     corner_radii: Default::default(),
                     content_mask,
@@ -2571,28 +2860,37 @@ impl<'a> WindowContext<'a> {
             "this method can only be called during paint"
         );
 
+        if self.svg_renderer.has_failed(&path) {
+            self.paint_svg_placeholder(bounds, color);
+            return Ok(());
+        }
+
         let scale_factor = self.scale_factor();
+        let unscaled_bounds = bounds;
         let bounds = bounds.scale(scale_factor);
-        // Render the SVG at twice the size to get a higher quality result.
-        let params = RenderSvgParams {
-            path,
-            size: bounds
-                .size
-                .map(|pixels| DevicePixels::from((pixels.0 * 2.).ceil() as i32)),
-        };
-
-        let Some(tile) =
-            self.window
-                .sprite_atlas
-                .get_or_insert_with(&params.clone().into(), &mut || {
-                    let Some(bytes) = self.svg_renderer.render(&params)? else {
-                        return Ok(None);
-                    };
-                    Ok(Some((params.size, Cow::Owned(bytes))))
-                })?
-        else {
+        let params = RenderSvgParams::new(path, bounds.size);
+
+        let mut render_error = None;
+        let tile = self.window.sprite_atlas.get_or_insert_with(
+            &params.clone().into(),
+            &mut || match self.svg_renderer.render(&params) {
+                Ok(Some(bytes)) => Ok(Some((params.size, Cow::Owned(bytes)))),
+                Ok(None) => Ok(None),
+                Err(error) => {
+                    render_error = Some(error);
+                    Ok(None)
+                }
+            },
+        )?;
+        if let Some(error) = render_error {
+            self.svg_renderer.report_failure(&params.path, error);
+            self.paint_svg_placeholder(unscaled_bounds, color);
+            return Ok(());
+        }
+        let Some(tile) = tile else {
             return Ok(());
         };
+        self.window.svg_cache.touch(&params);
         let content_mask = self.content_mask().scale(scale_factor);
 
         self.window
@@ -2603,7 +2901,7 @@ impl<'a> WindowContext<'a> {
                 pad: 0,
                 bounds,
                 content_mask,
-                color,
+                color: self.apply_opacity(color),
                 tile,
                 transformation,
             });
@@ -2611,6 +2909,12 @@ impl<'a> WindowContext<'a> {
         Ok(())
     }
 
+    /// Paints an outline in place of an SVG that couldn't be rendered.
+    fn paint_svg_placeholder(&mut self, bounds: Bounds<Pixels>, mut color: Hsla) {
+        color.fade_out(0.5);
+        self.paint_quad(outline(bounds, color));
+    }
+
     /// Paint an image into the scene for the next frame at the current z-index.
     ///
     /// This method should only be called as part of the paint phase of element drawing.
@@ -2647,6 +2951,8 @@ impl<'a> WindowContext<'a> {
             .insert_primitive(PolychromeSprite {
                 order: 0,
                 grayscale,
+                opacity: self.element_opacity(),
+                pad: 0,
                 bounds,
                 content_mask,
                 corner_radii,
@@ -3138,6 +3444,17 @@ impl<'a> WindowContext<'a> {
                     PlatformInput::FileDrop(FileDropEvent::Exited)
                 }
             },
+            // Escape cancels an active drag, instead of being handled by the focused element.
+            PlatformInput::KeyDown(key_down)
+                if key_down.keystroke.key == "escape" && self.has_active_drag() =>
+            {
+                self.active_drag = None;
+                self.refresh();
+                return DispatchEventResult {
+                    propagate: false,
+                    default_prevented: self.window.default_prevented,
+                };
+            }
             PlatformInput::KeyDown(_) | PlatformInput::KeyUp(_) => event,
         };
 
@@ -3204,6 +3521,10 @@ impl<'a> WindowContext<'a> {
             self.draw();
         }
 
+        if self.capture_key_event(event) {
+            return;
+        }
+
         let node_id = self
             .window
             .focus
@@ -3301,8 +3622,9 @@ impl<'a> WindowContext<'a> {
                 currently_pending.bindings.push(binding);
             }
 
+            let timeout = self.pending_input_timeout;
             currently_pending.timer = Some(self.spawn(|mut cx| async move {
-                cx.background_executor.timer(Duration::from_secs(1)).await;
+                cx.background_executor.timer(timeout).await;
                 cx.update(move |cx| {
                     cx.clear_pending_keystrokes();
                     let Some(currently_pending) = cx.window.pending_input.take() else {
@@ -3419,6 +3741,85 @@ impl<'a> WindowContext<'a> {
         }
     }
 
+    /// Records the keystrokes typed in this window instead of dispatching them, e.g. to let the
+    /// user choose the keystrokes of a key binding. The capture ends when enter is pressed, or
+    /// once no keystroke has been typed for the pending input timeout, which is when a
+    /// multi-stroke binding would be complete, and `on_finish` is called with the keystrokes.
+    ///
+    /// Pressing escape cancels the capture and calls `on_finish` with `None`. Enter and escape
+    /// are recorded like any other keystroke when typed with modifiers.
+    pub fn capture_keystrokes(
+        &mut self,
+        on_finish: impl FnOnce(Option<Vec<Keystroke>>, &mut WindowContext) + 'static,
+    ) {
+        self.cancel_keystroke_capture();
+        if self.window.pending_input.take().is_some() {
+            self.pending_input_changed();
+        }
+        self.clear_pending_keystrokes();
+        self.window.keystroke_capture = Some(KeystrokeCapture {
+            keystrokes: Vec::new(),
+            on_finish: Box::new(on_finish),
+            timer: None,
+        });
+    }
+
+    /// Whether keystrokes typed in this window are being recorded rather than dispatched.
+    pub fn is_capturing_keystrokes(&self) -> bool {
+        self.window.keystroke_capture.is_some()
+    }
+
+    /// Stops recording keystrokes, calling the capture's callback with `None`.
+    pub fn cancel_keystroke_capture(&mut self) {
+        if let Some(capture) = self.window.keystroke_capture.take() {
+            (capture.on_finish)(None, self);
+        }
+    }
+
+    fn finish_keystroke_capture(&mut self) {
+        if let Some(capture) = self.window.keystroke_capture.take() {
+            (capture.on_finish)(Some(capture.keystrokes), self);
+        }
+    }
+
+    /// Records a key event if keystrokes are being captured, returning whether it was.
+    fn capture_key_event(&mut self, event: &dyn Any) -> bool {
+        if self.window.keystroke_capture.is_none() {
+            return false;
+        }
+        self.propagate_event = false;
+        let Some(event) = event.downcast_ref::<KeyDownEvent>() else {
+            return true;
+        };
+        if event.is_held {
+            return true;
+        }
+        if event.keystroke.modifiers == Modifiers::none() {
+            match event.keystroke.key.as_str() {
+                "escape" => {
+                    self.cancel_keystroke_capture();
+                    return true;
+                }
+                "enter" => {
+                    self.finish_keystroke_capture();
+                    return true;
+                }
+                _ => {}
+            }
+        }
+
+        let timeout = self.pending_input_timeout;
+        let timer = self.spawn(|mut cx| async move {
+            cx.background_executor.timer(timeout).await;
+            cx.update(|cx| cx.finish_keystroke_capture()).log_err();
+        });
+        if let Some(capture) = self.window.keystroke_capture.as_mut() {
+            capture.keystrokes.push(event.keystroke.clone());
+            capture.timer = Some(timer);
+        }
+        true
+    }
+
     /// Determine whether a potential multi-stroke key binding is in progress on this window.
     pub fn has_pending_keystrokes(&self) -> bool {
         self.window
@@ -3722,6 +4123,24 @@ impl<'a> WindowContext<'a> {
         dispatch_tree.bindings_for_action(action, &context_stack)
     }
 
+    /// Returns the action that typing the given keystrokes would invoke if the given focus handle
+    /// were focused.
+    pub fn action_for_keystrokes_in(
+        &self,
+        keystrokes: &[Keystroke],
+        focus_handle: &FocusHandle,
+    ) -> Option<Box<dyn Action>> {
+        let dispatch_tree = &self.window.rendered_frame.dispatch_tree;
+
+        let node_id = dispatch_tree.focusable_node_id(focus_handle.id)?;
+        let context_stack: Vec<_> = dispatch_tree
+            .dispatch_path(node_id)
+            .into_iter()
+            .filter_map(|node_id| dispatch_tree.node(node_id).context.clone())
+            .collect();
+        self.action_for_keystrokes(keystrokes, &context_stack)
+    }
+
     /// Returns a generic event listener that invokes the given listener with the view and context associated with the given view handle.
     pub fn listener_for<V: Render, E>(
         &self,
@@ -4068,6 +4487,20 @@ impl<'a, V: 'static> ViewContext<'a, V> {
         self.window_cx.on_next_frame(move |cx| view.update(cx, f));
     }
 
+    /// Run an animation for the given duration, calling the given closure once per frame with
+    /// the eased progress of the animation. See [`WindowContext::animate`].
+    pub fn animate(
+        &mut self,
+        duration: Duration,
+        easing: impl Fn(f32) -> f32 + 'static,
+        mut callback: impl FnMut(&mut V, f32, &mut ViewContext<V>) + 'static,
+    ) -> Task<()> {
+        let view = self.view().downgrade();
+        self.window_cx.animate(duration, easing, move |delta, cx| {
+            view.update(cx, |view, cx| callback(view, delta, cx)).ok();
+        })
+    }
+
     /// Schedules the given function to be run at the end of the current effect cycle, allowing entities
     /// that are currently on the stack to be returned to the app.
     pub fn defer(&mut self, f: impl FnOnce(&mut V, &mut ViewContext<V>) + 'static) {
@@ -4891,12 +5324,14 @@ pub struct PaintQuad {
     pub bounds: Bounds<Pixels>,
     /// The radii of the quad's corners.
     pub corner_radii: Corners<Pixels>,
-    /// The background color of the quad.
-    pub background: Hsla,
+    /// The background fill of the quad.
+    pub background: Fill,
     /// The widths of the quad's borders.
     pub border_widths: Edges<Pixels>,
     /// The color of the quad's borders.
     pub border_color: Hsla,
+    /// The style of the line drawn for the quad's borders.
+    pub border_style: BorderStyle,
 }
 
 impl PaintQuad {
@@ -4924,20 +5359,28 @@ impl PaintQuad {
         }
     }
 
-    /// Sets the background color of the quad.
-    pub fn background(self, background: impl Into<Hsla>) -> Self {
+    /// Sets the background fill of the quad.
+    pub fn background(self, background: impl Into<Fill>) -> Self {
         PaintQuad {
             background: background.into(),
             ..self
         }
     }
+
+    /// Sets the style of the line drawn for the quad's borders.
+    pub fn border_style(self, border_style: BorderStyle) -> Self {
+        PaintQuad {
+            border_style,
+            ..self
+        }
+    }
 }
 
 /// Creates a quad with the given parameters.
 pub fn quad(
     bounds: Bounds<Pixels>,
     corner_radii: impl Into<Corners<Pixels>>,
-    background: impl Into<Hsla>,
+    background: impl Into<Fill>,
     border_widths: impl Into<Edges<Pixels>>,
     border_color: impl Into<Hsla>,
 ) -> PaintQuad {
@@ -4947,17 +5390,19 @@ pub fn quad(
         background: background.into(),
         border_widths: border_widths.into(),
         border_color: border_color.into(),
+        border_style: BorderStyle::default(),
     }
 }
 
 /// Creates a filled quad with the given bounds and background color.
-pub fn fill(bounds: impl Into<Bounds<Pixels>>, background: impl Into<Hsla>) -> PaintQuad {
+pub fn fill(bounds: impl Into<Bounds<Pixels>>, background: impl Into<Fill>) -> PaintQuad {
     PaintQuad {
         bounds: bounds.into(),
         corner_radii: (0.).into(),
         background: background.into(),
         border_widths: (0.).into(),
         border_color: transparent_black(),
+        border_style: BorderStyle::default(),
     }
 }
 
@@ -4966,8 +5411,137 @@ pub fn outline(bounds: impl Into<Bounds<Pixels>>, border_color: impl Into<Hsla>)
     PaintQuad {
         bounds: bounds.into(),
         corner_radii: (0.).into(),
-        background: transparent_black(),
+        background: transparent_black().into(),
         border_widths: (1.).into(),
         border_color: border_color.into(),
+        border_style: BorderStyle::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as gpui, div, Modifiers, TestAppContext};
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn test_nested_content_masks() {
+        let outer = ContentMask {
+            bounds: Bounds::new(point(px(0.), px(0.)), size(px(100.), px(100.))),
+            corner_radii: Corners::all(px(10.)),
+        };
+        let middle = ContentMask {
+            bounds: Bounds::new(point(px(50.), px(-20.)), size(px(100.), px(100.))),
+            corner_radii: Corners::all(px(20.)),
+        };
+        let inner = ContentMask::new(Bounds::new(
+            point(px(60.), px(10.)),
+            size(px(100.), px(50.)),
+        ));
+
+        let mask = outer.intersect(&middle).intersect(&inner);
+        assert_eq!(
+            mask.bounds,
+            Bounds::new(point(px(60.), px(10.)), size(px(40.), px(50.)))
+        );
+        assert_eq!(mask.corner_radii, Corners::default());
+
+        // Corners shared by nested masks keep the larger radius.
+        let mask = outer.intersect(&middle);
+        assert_eq!(
+            mask.bounds,
+            Bounds::new(point(px(50.), px(0.)), size(px(50.), px(80.)))
+        );
+        assert_eq!(
+            mask.corner_radii,
+            Corners {
+                top_left: px(0.),
+                top_right: px(10.),
+                bottom_right: px(0.),
+                bottom_left: px(20.),
+            }
+        );
+        assert_eq!(mask.intersect(&outer), mask);
+
+        // Masks inset from a rounded mask keep its rounding, reduced by the inset.
+        let inset = ContentMask::new(Bounds::new(point(px(2.), px(4.)), size(px(96.), px(94.))));
+        let mask = outer.intersect(&inset);
+        assert_eq!(mask.bounds, inset.bounds);
+        assert_eq!(mask.corner_radii, Corners::all(px(8.)));
+        assert_eq!(inset.intersect(&outer), mask);
+    }
+
+    #[test]
+    fn test_content_mask_contains() {
+        let mask = ContentMask {
+            bounds: Bounds::new(point(px(10.), px(10.)), size(px(100.), px(40.))),
+            corner_radii: Corners {
+                top_left: px(10.),
+                top_right: px(0.),
+                bottom_right: px(100.),
+                bottom_left: px(0.),
+            },
+        };
+
+        assert!(mask.contains(&point(px(60.), px(30.))));
+        assert!(!mask.contains(&point(px(5.), px(30.))));
+        // Cut off by the rounded top left corner, but not by the square ones.
+        assert!(!mask.contains(&point(px(11.), px(11.))));
+        assert!(mask.contains(&point(px(14.), px(14.))));
+        assert!(mask.contains(&point(px(109.), px(11.))));
+        assert!(mask.contains(&point(px(11.), px(49.))));
+        // Radii are limited to half of the mask's shorter side.
+        assert!(!mask.contains(&point(px(108.), px(48.))));
+        assert!(mask.contains(&point(px(100.), px(40.))));
+    }
+
+    struct ClippedButton {
+        clicks: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl Render for ClippedButton {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            let clicks = self.clicks.clone();
+            div()
+                .size(px(100.))
+                .rounded(px(20.))
+                .overflow_hidden()
+                .child(
+                    div().size(px(200.)).child(
+                        div()
+                            .absolute()
+                            .left(px(50.))
+                            .top(px(50.))
+                            .size(px(100.))
+                            .overflow_hidden()
+                            .child(
+                                div()
+                                    .id("button")
+                                    .size_full()
+                                    .on_click(move |_, _| clicks.borrow_mut().push("button")),
+                            ),
+                    ),
+                )
+        }
+    }
+
+    #[gpui::test]
+    fn test_hit_testing_respects_content_masks(cx: &mut TestAppContext) {
+        let clicks = Rc::new(RefCell::new(Vec::new()));
+        let (_, cx) = cx.add_window_view(|_| ClippedButton {
+            clicks: clicks.clone(),
+        });
+        cx.run_until_parked();
+
+        let mut click = |x: f32, y: f32| {
+            cx.simulate_click(point(px(x), px(y)), Modifiers::none());
+            clicks.borrow_mut().pop()
+        };
+        // The button is clipped by both masks: to the part of the inner element that overlaps
+        // the outer one, and to the outer element's rounded corner.
+        assert_eq!(click(75., 75.), Some("button"));
+        assert_eq!(click(96., 96.), None);
+        assert_eq!(click(125., 125.), None);
+        assert_eq!(click(25., 25.), None);
     }
 }
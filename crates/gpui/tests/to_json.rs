@@ -0,0 +1,91 @@
+use gpui::json::ToJson;
+use gpui::private::serde_json::{json, Value};
+use gpui::{point, px, size, Bounds, Pixels};
+use serde_derive::Serialize;
+
+#[derive(Serialize)]
+struct Style {
+    color: String,
+    visible: bool,
+}
+
+#[derive(ToJson)]
+struct Node<T> {
+    id: usize,
+    name: String,
+    style: Style,
+    children: Vec<u32>,
+    bounds: T,
+    #[json(skip)]
+    #[allow(dead_code)]
+    cache: Option<std::rc::Rc<()>>,
+}
+
+#[derive(ToJson)]
+#[allow(dead_code)]
+struct Pair(u32, #[json(skip)] String, Option<bool>);
+
+#[derive(ToJson)]
+struct Empty;
+
+struct HandWrittenNode {
+    id: usize,
+    name: String,
+    style: Style,
+    children: Vec<u32>,
+    bounds: Bounds<Pixels>,
+}
+
+impl ToJson for HandWrittenNode {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "style": { "color": self.style.color, "visible": self.style.visible },
+            "children": self.children,
+            "bounds": self.bounds.to_json(),
+        })
+    }
+}
+
+#[test]
+fn test_derive_to_json() {
+    let bounds = Bounds::new(point(px(1.), px(2.)), size(px(30.), px(40.)));
+    let node = Node {
+        id: 7,
+        name: "editor".into(),
+        style: Style {
+            color: "red".into(),
+            visible: true,
+        },
+        children: vec![1, 2, 3],
+        bounds,
+        cache: None,
+    };
+    let hand_written = HandWrittenNode {
+        id: 7,
+        name: "editor".into(),
+        style: Style {
+            color: "red".into(),
+            visible: true,
+        },
+        children: vec![1, 2, 3],
+        bounds,
+    };
+
+    assert_eq!(node.to_json(), hand_written.to_json());
+    assert_eq!(
+        node.to_json()["bounds"],
+        json!({
+            "origin": { "x": 1.0, "y": 2.0 },
+            "size": { "width": 30.0, "height": 40.0 },
+        })
+    );
+    assert!(node.to_json().get("cache").is_none());
+
+    assert_eq!(
+        Pair(1, "skipped".into(), Some(true)).to_json(),
+        json!([1, true])
+    );
+    assert_eq!(Empty.to_json(), Value::Null);
+}
@@ -0,0 +1,130 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Field, Fields, Index, Meta, NestedMeta, Type,
+};
+
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    let mut ast = parse_macro_input!(input as DeriveInput);
+    let type_name = &ast.ident;
+
+    let fields = match &ast.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return syn::Error::new_spanned(&ast.ident, "ToJson can only be derived for structs")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let type_params = ast
+        .generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    let field_to_json = |field: &Field, accessor: TokenStream2| {
+        // Fields whose type is one of the struct's type parameters are converted with that
+        // parameter's `ToJson` impl, and all other fields with their `Serialize` impl.
+        let is_type_param = match &field.ty {
+            Type::Path(path) if path.qself.is_none() => {
+                type_params.iter().any(|param| path.path.is_ident(param))
+            }
+            _ => false,
+        };
+        if is_type_param {
+            quote! { gpui::json::ToJson::to_json(&self.#accessor) }
+        } else {
+            quote! {
+                gpui::private::serde_json::to_value(&self.#accessor)
+                    .unwrap_or(gpui::private::serde_json::Value::Null)
+            }
+        }
+    };
+
+    let mut included_fields = Vec::new();
+    for (ix, field) in fields.iter().enumerate() {
+        match is_skipped(field) {
+            Ok(true) => {}
+            Ok(false) => included_fields.push((ix, field)),
+            Err(error) => return error.to_compile_error().into(),
+        }
+    }
+
+    let body = match fields {
+        Fields::Named(_) => {
+            let entries = included_fields.iter().map(|(_, field)| {
+                let ident = field.ident.as_ref().unwrap();
+                let name = ident.to_string();
+                let value = field_to_json(field, quote! { #ident });
+                quote! { map.insert(#name.to_string(), #value); }
+            });
+            quote! {
+                #[allow(unused_mut)]
+                let mut map = gpui::private::serde_json::Map::new();
+                #(#entries)*
+                gpui::private::serde_json::Value::Object(map)
+            }
+        }
+        Fields::Unnamed(_) => {
+            let values = included_fields.iter().map(|(ix, field)| {
+                let index = Index::from(*ix);
+                field_to_json(field, quote! { #index })
+            });
+            quote! {
+                gpui::private::serde_json::Value::Array(vec![#(#values),*])
+            }
+        }
+        Fields::Unit => quote! { gpui::private::serde_json::Value::Null },
+    };
+
+    let where_clause = ast.generics.make_where_clause();
+    for param in &type_params {
+        where_clause
+            .predicates
+            .push(parse_quote! { #param: gpui::json::ToJson });
+    }
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let gen = quote! {
+        impl #impl_generics gpui::json::ToJson for #type_name #type_generics
+        #where_clause
+        {
+            fn to_json(&self) -> gpui::private::serde_json::Value {
+                #body
+            }
+        }
+    };
+
+    gen.into()
+}
+
+/// Returns whether the field is marked with `#[json(skip)]`.
+fn is_skipped(field: &Field) -> syn::Result<bool> {
+    let mut skipped = false;
+    for attr in &field.attrs {
+        if !attr.path.is_ident("json") {
+            continue;
+        }
+
+        match attr.parse_meta()? {
+            Meta::List(list) => {
+                for nested in &list.nested {
+                    match nested {
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                            skipped = true;
+                        }
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                nested,
+                                "expected `skip` in `#[json(...)]`",
+                            ))
+                        }
+                    }
+                }
+            }
+            meta => return Err(syn::Error::new_spanned(meta, "expected `#[json(skip)]`")),
+        }
+    }
+    Ok(skipped)
+}
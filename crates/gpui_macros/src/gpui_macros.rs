@@ -1,5 +1,6 @@
 mod derive_into_element;
 mod derive_render;
+mod derive_to_json;
 mod register_action;
 mod styles;
 mod test;
@@ -27,6 +28,16 @@ pub fn derive_render(input: TokenStream) -> TokenStream {
     derive_render::derive_render(input)
 }
 
+/// #[derive(ToJson)] implements `gpui::json::ToJson` for a struct, by converting each of its
+/// fields with their `serde::Serialize` impl. Fields whose type is one of the struct's type
+/// parameters are converted with that parameter's `ToJson` impl instead.
+///
+/// Fields marked with `#[json(skip)]` are left out.
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    derive_to_json::derive_to_json(input)
+}
+
 /// Used by GPUI to generate the style helpers.
 #[proc_macro]
 #[doc(hidden)]
@@ -340,6 +340,25 @@ impl StatusItemView for InlineCompletionButton {
         }
         cx.notify();
     }
+
+    fn context_menu(&mut self, cx: &mut ViewContext<Self>) -> Option<View<ContextMenu>> {
+        if all_language_settings(None, cx).inline_completions.provider
+            != InlineCompletionProvider::Copilot
+        {
+            return None;
+        }
+
+        let copilot = Copilot::global(cx)?;
+        if copilot.read(cx).status().is_authorized() {
+            Some(self.build_copilot_context_menu(cx))
+        } else {
+            None
+        }
+    }
+
+    fn status_item_id(&self, _: &AppContext) -> Option<&'static str> {
+        Some("inline_completion")
+    }
 }
 
 impl SupermavenButtonStatus {
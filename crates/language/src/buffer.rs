@@ -26,6 +26,7 @@ use gpui::{
 use lazy_static::lazy_static;
 use lsp::LanguageServerId;
 use parking_lot::Mutex;
+use postage::{prelude::Stream as _, watch};
 use similar::{ChangeTag, TextDiff};
 use smallvec::SmallVec;
 use smol::future::yield_now;
@@ -103,7 +104,7 @@ pub struct Buffer {
     pending_autoindent: Option<Task<()>>,
     sync_parse_timeout: Duration,
     syntax_map: Mutex<SyntaxMap>,
-    parsing_in_background: bool,
+    parse_status: (watch::Sender<ParseStatus>, watch::Receiver<ParseStatus>),
     non_text_state_update_count: usize,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
@@ -290,6 +291,15 @@ pub enum Operation {
     },
 }
 
+/// Whether a buffer is being parsed in the background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseStatus {
+    /// The buffer's syntax trees are up to date, or it has no language.
+    Idle,
+    /// The buffer is being parsed in the background.
+    Parsing,
+}
+
 /// An event that occurs in a buffer.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Event {
@@ -318,6 +328,11 @@ pub enum Event {
     DiagnosticsUpdated,
     /// The buffer gained or lost editing capabilities.
     CapabilityChanged,
+    /// The selections of a remote replica of the buffer changed.
+    RemoteSelectionsChanged {
+        /// The replica whose selections changed.
+        replica_id: ReplicaId,
+    },
     /// The buffer was explicitly requested to close.
     Closed,
 }
@@ -368,6 +383,12 @@ pub trait File: Send + Sync {
 
     /// Return whether Zed considers this to be a private file.
     fn is_private(&self) -> bool;
+
+    /// Returns the id of the language that the user associated with this
+    /// file, which takes precedence over the language detected from its path.
+    fn language_id<'a>(&'a self, _cx: &'a AppContext) -> Option<&'a str> {
+        None
+    }
 }
 
 /// The file associated with a buffer, in the case where the file is on the local disk.
@@ -707,7 +728,7 @@ impl Buffer {
             file,
             capability,
             syntax_map: Mutex::new(SyntaxMap::new()),
-            parsing_in_background: false,
+            parse_status: watch::channel_with(ParseStatus::Idle),
             non_text_state_update_count: 0,
             sync_parse_timeout: Duration::from_millis(1),
             autoindent_requests: Default::default(),
@@ -994,7 +1015,20 @@ impl Buffer {
     /// Whether the buffer is being parsed in the background.
     #[cfg(any(test, feature = "test-support"))]
     pub fn is_parsing(&self) -> bool {
-        self.parsing_in_background
+        *self.parse_status.1.borrow() == ParseStatus::Parsing
+    }
+
+    /// Returns a future that resolves once the buffer isn't being parsed in the background, so
+    /// that its syntax trees reflect its current text.
+    pub fn parsing_idle(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut parse_status = self.parse_status.1.clone();
+        async move {
+            while *parse_status.borrow() != ParseStatus::Idle {
+                if parse_status.recv().await.is_none() {
+                    break;
+                }
+            }
+        }
     }
 
     /// Indicates whether the buffer contains any regions that may be
@@ -1032,7 +1066,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
-        if self.parsing_in_background {
+        if *self.parse_status.1.borrow() == ParseStatus::Parsing {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -1068,7 +1102,7 @@ impl Buffer {
                 return;
             }
             Err(parse_task) => {
-                self.parsing_in_background = true;
+                *self.parse_status.0.borrow_mut() = ParseStatus::Parsing;
                 cx.spawn(move |this, mut cx| async move {
                     let new_syntax_map = parse_task.await;
                     this.update(&mut cx, move |this, cx| {
@@ -1085,7 +1119,7 @@ impl Buffer {
                             || grammar_changed
                             || this.version.changed_since(&parsed_version);
                         this.did_finish_parsing(new_syntax_map, cx);
-                        this.parsing_in_background = false;
+                        *this.parse_status.0.borrow_mut() = ParseStatus::Idle;
                         if parse_again {
                             this.reparse(cx);
                         }
@@ -1967,6 +2001,9 @@ impl Buffer {
                 );
                 self.text.lamport_clock.observe(lamport_timestamp);
                 self.non_text_state_update_count += 1;
+                cx.emit(Event::RemoteSelectionsChanged {
+                    replica_id: lamport_timestamp.replica_id,
+                });
             }
             Operation::UpdateCompletionTriggers {
                 triggers,
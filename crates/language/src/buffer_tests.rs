@@ -2142,6 +2142,86 @@ async fn test_find_matching_indent(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+fn test_structural_matches(cx: &mut AppContext) {
+    let text = r#"
+        fn main() {
+            foo(1, 2);
+            bar(foo(3), 4, 5);
+            let f = foo;
+        }
+    "#
+    .unindent();
+
+    let language = Arc::new(rust_lang());
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text.clone(), cx).with_language(language.clone(), cx));
+    let snapshot = buffer.read(cx).snapshot();
+    let matched_text = |matches: Vec<StructuralMatch>| {
+        matches
+            .into_iter()
+            .map(|mat| &text[mat.range])
+            .collect::<Vec<_>>()
+    };
+
+    // Without a `@match` capture, matches span the pattern's whole node.
+    let query = StructuralQuery::new(
+        language.clone(),
+        r#"(call_expression function: (identifier) @name (#eq? @name "foo"))"#,
+    )
+    .unwrap();
+    assert_eq!(
+        matched_text(snapshot.structural_matches(&query, 0..text.len())),
+        ["foo(1, 2)", "foo(3)"]
+    );
+
+    // Matches must be contained in the searched range.
+    let line = text.find("bar").unwrap()..text.find("let").unwrap();
+    assert_eq!(
+        matched_text(snapshot.structural_matches(&query, line)),
+        ["foo(3)"]
+    );
+
+    let query = StructuralQuery::new(
+        language.clone(),
+        "(call_expression arguments: (arguments (_) (_) (_)))",
+    )
+    .unwrap();
+    assert_eq!(
+        matched_text(snapshot.structural_matches(&query, 0..text.len())),
+        ["bar(foo(3), 4, 5)"]
+    );
+
+    // Replacements can reference captures by position or by name.
+    let query = StructuralQuery::new(
+        language.clone(),
+        r#"(call_expression
+            function: (identifier) @name
+            arguments: (arguments) @args
+            (#eq? @name "foo"))"#,
+    )
+    .unwrap();
+    let matches = snapshot.structural_matches(&query, 0..text.len());
+    assert_eq!(
+        matches
+            .iter()
+            .map(|mat| query.expand_replacement("$1!@args // $0 $$1 @@args", mat, &snapshot))
+            .collect::<Vec<_>>(),
+        [
+            "foo!(1, 2) // foo(1, 2) $1 @args",
+            "foo!(3) // foo(3) $1 @args"
+        ]
+    );
+
+    // Named references end where the longest capture name does, and unknown ones are kept.
+    assert_eq!(
+        query.expand_replacement("@name.clone()@args.len() @other", &matches[1], &snapshot),
+        "foo.clone()(3).len() @other"
+    );
+
+    assert!(StructuralQuery::new(language, "(call_expression").is_err());
+}
+
 #[gpui::test(iterations = 100)]
 fn test_random_collaboration(cx: &mut AppContext, mut rng: StdRng) {
     let min_peers = env::var("MIN_PEERS")
@@ -13,6 +13,7 @@ mod language_registry;
 pub mod language_settings;
 mod outline;
 pub mod proto;
+mod structural_query;
 mod syntax_map;
 mod task_context;
 
@@ -72,6 +73,7 @@ pub use language_registry::{
 };
 pub use lsp::LanguageServerId;
 pub use outline::{render_item, Outline, OutlineItem};
+pub use structural_query::{StructuralMatch, StructuralQuery};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer};
 pub use text::{AnchorRangeExt, LineEnding};
 pub use tree_sitter::{Node, Parser, Tree, TreeCursor};
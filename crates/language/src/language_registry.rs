@@ -492,6 +492,7 @@ impl LanguageRegistry {
             &file.full_path(cx),
             content,
             Some(&user_file_types.file_types),
+            file.language_id(cx),
         )
     }
 
@@ -499,7 +500,7 @@ impl LanguageRegistry {
         self: &Arc<Self>,
         path: &'a Path,
     ) -> impl Future<Output = Result<Arc<Language>>> + 'a {
-        self.language_for_file_internal(path, None, None)
+        self.language_for_file_internal(path, None, None, None)
             .map_err(|error| error.context(format!("language for file path {}", path.display())))
     }
 
@@ -508,6 +509,7 @@ impl LanguageRegistry {
         path: &Path,
         content: Option<&Rope>,
         user_file_types: Option<&HashMap<Arc<str>, GlobSet>>,
+        associated_language: Option<&str>,
     ) -> impl Future<Output = Result<Arc<Language>>> {
         let filename = path.file_name().and_then(|name| name.to_str());
         let extension = path.extension_or_hidden_file_name();
@@ -515,6 +517,11 @@ impl LanguageRegistry {
         let empty = GlobSet::empty();
 
         let rx = self.get_or_load_language(move |language_name, config| {
+            if associated_language.map_or(false, |associated| {
+                UniCase::new(associated) == UniCase::new(language_name)
+            }) {
+                return 3;
+            }
             let path_matches_default_suffix = config
                 .path_suffixes
                 .iter()
@@ -0,0 +1,204 @@
+use crate::{
+    syntax_map::{QueryCursorHandle, TextProvider},
+    BufferSnapshot, Language,
+};
+use anyhow::{anyhow, Result};
+use std::{fmt, ops::Range, sync::Arc};
+use tree_sitter::Query;
+
+/// The name of the capture that determines the range of a [`StructuralMatch`].
+const MATCH_CAPTURE_NAME: &str = "match";
+
+/// A tree-sitter query pattern, used to search buffers by the shape of their syntax trees
+/// instead of their text.
+///
+/// The range of each match is the node captured as `@match`. When the pattern consists of a
+/// single node without such a capture, it is added around that node. Otherwise, matches span
+/// all of their captures.
+pub struct StructuralQuery {
+    language: Arc<Language>,
+    query: Query,
+    match_capture_ix: Option<u32>,
+}
+
+/// A match of a [`StructuralQuery`] in a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralMatch {
+    pub range: Range<usize>,
+    /// The range of each of the query's captures, by capture index.
+    pub captures: Vec<Option<Range<usize>>>,
+}
+
+impl StructuralQuery {
+    pub fn new(language: Arc<Language>, pattern: &str) -> Result<Self> {
+        let grammar = language
+            .grammar()
+            .ok_or_else(|| anyhow!("{} has no grammar", language.name()))?;
+        let mut query = Query::new(&grammar.ts_language, pattern)?;
+        if query.pattern_count() == 1
+            && !query
+                .capture_names()
+                .iter()
+                .any(|name| *name == MATCH_CAPTURE_NAME)
+        {
+            if let Ok(query_with_match) = Query::new(
+                &grammar.ts_language,
+                &format!("{pattern} @{MATCH_CAPTURE_NAME}"),
+            ) {
+                query = query_with_match;
+            }
+        }
+        let match_capture_ix = query.capture_index_for_name(MATCH_CAPTURE_NAME);
+        Ok(Self {
+            language,
+            query,
+            match_capture_ix,
+        })
+    }
+
+    pub fn language(&self) -> &Arc<Language> {
+        &self.language
+    }
+
+    /// Expands the replacement template for the given match. `$0` is replaced with the matched
+    /// text, `$1`, `$2`, ... with the text of the pattern's captures in the order they appear,
+    /// and `@name` with the text of the capture with that name. As capture names can contain
+    /// dots, `@` refers to the longest capture name that follows it, so `@recv.clone()` is the
+    /// `recv` capture followed by `.clone()` unless there's a `recv.clone` capture. `$$` and `@@`
+    /// insert the character itself.
+    pub fn expand_replacement(
+        &self,
+        template: &str,
+        mat: &StructuralMatch,
+        buffer: &BufferSnapshot,
+    ) -> String {
+        let text_for =
+            |range: &Range<usize>| -> String { buffer.text_for_range(range.clone()).collect() };
+        let capture_text = |ix: u32| {
+            mat.captures
+                .get(ix as usize)
+                .and_then(Option::as_ref)
+                .map(text_for)
+                .unwrap_or_default()
+        };
+
+        let mut result = String::new();
+        let mut chars = template.char_indices().peekable();
+        while let Some((ix, ch)) = chars.next() {
+            if ch != '$' && ch != '@' {
+                result.push(ch);
+                continue;
+            }
+            if chars.peek().map(|(_, next)| *next) == Some(ch) {
+                chars.next();
+                result.push(ch);
+                continue;
+            }
+
+            let rest = &template[ix + 1..];
+            let reference_len = if ch == '$' {
+                rest.find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len())
+            } else {
+                self.query
+                    .capture_names()
+                    .iter()
+                    .filter(|name| rest.starts_with(**name))
+                    .map(|name| name.len())
+                    .max()
+                    .unwrap_or(0)
+            };
+            if reference_len == 0 {
+                result.push(ch);
+                continue;
+            }
+            let reference = &rest[..reference_len];
+            for _ in 0..reference_len {
+                chars.next();
+            }
+
+            if ch == '$' {
+                match reference.parse::<usize>() {
+                    Ok(0) => result.push_str(&text_for(&mat.range)),
+                    Ok(n) => {
+                        if let Some(ix) = self.user_capture_indices().nth(n - 1) {
+                            result.push_str(&capture_text(ix));
+                        }
+                    }
+                    Err(_) => {}
+                }
+            } else if let Some(ix) = self.query.capture_index_for_name(reference) {
+                result.push_str(&capture_text(ix));
+            }
+        }
+        result
+    }
+
+    /// The indices of the captures written in the pattern, excluding the one that determines
+    /// the range of matches.
+    fn user_capture_indices(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.query.capture_names().len() as u32)
+            .filter(move |ix| Some(*ix) != self.match_capture_ix)
+    }
+}
+
+impl fmt::Debug for StructuralQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuralQuery")
+            .field("language", &self.language.name())
+            .field("captures", &self.query.capture_names())
+            .finish()
+    }
+}
+
+impl BufferSnapshot {
+    /// Returns the matches of the given query within the range, in the syntax layers of the
+    /// query's language.
+    pub fn structural_matches(
+        &self,
+        query: &StructuralQuery,
+        range: Range<usize>,
+    ) -> Vec<StructuralMatch> {
+        let mut matches = Vec::new();
+        let mut cursor = QueryCursorHandle::new();
+        for layer in self.syntax.layers_for_range(range.clone(), self) {
+            if layer.language.id != query.language.id {
+                continue;
+            }
+
+            cursor.set_byte_range(range.clone());
+            let text = TextProvider(self.as_rope());
+            for mat in cursor.matches(&query.query, layer.node(), text) {
+                let mut captures = vec![None; query.query.capture_names().len()];
+                let mut match_range: Option<Range<usize>> = None;
+                for capture in mat.captures {
+                    let capture_range = capture.node.byte_range();
+                    if Some(capture.index) == query.match_capture_ix {
+                        match_range = Some(capture_range.clone());
+                    } else if query.match_capture_ix.is_none() {
+                        match_range = Some(match match_range {
+                            Some(range) => {
+                                range.start.min(capture_range.start)
+                                    ..range.end.max(capture_range.end)
+                            }
+                            None => capture_range.clone(),
+                        });
+                    }
+                    captures[capture.index as usize].get_or_insert(capture_range);
+                }
+
+                if let Some(match_range) = match_range {
+                    if match_range.start >= range.start && match_range.end <= range.end {
+                        matches.push(StructuralMatch {
+                            range: match_range,
+                            captures,
+                        });
+                    }
+                }
+            }
+        }
+        matches.sort_by_key(|mat| (mat.range.start, mat.range.end));
+        matches.dedup_by_key(|mat| mat.range.clone());
+        matches
+    }
+}
@@ -1,5 +1,7 @@
 use editor::Editor;
-use gpui::{div, IntoElement, ParentElement, Render, Subscription, View, ViewContext, WeakView};
+use gpui::{
+    div, AppContext, IntoElement, ParentElement, Render, Subscription, View, ViewContext, WeakView,
+};
 use std::sync::Arc;
 use ui::{Button, ButtonCommon, Clickable, FluentBuilder, LabelSize, Tooltip};
 use workspace::{item::ItemHandle, StatusItemView, Workspace};
@@ -76,4 +78,8 @@ impl StatusItemView for ActiveBufferLanguage {
 
         cx.notify();
     }
+
+    fn status_item_id(&self, _: &AppContext) -> Option<&'static str> {
+        Some("active_language")
+    }
 }
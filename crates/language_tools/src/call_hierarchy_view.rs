@@ -0,0 +1,405 @@
+use editor::{scroll::Autoscroll, Bias, Editor};
+use gpui::{
+    actions, div, px,
+    views::{SplitView, SplitViewEvent},
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
+    IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Task,
+    View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use language::{point_from_lsp, point_to_lsp, LocalFile as _, PointUtf16};
+use lsp::{
+    CallHierarchyDirection, CallHierarchyItem, CallHierarchyProvider, CallTree, CallTreeNodeId,
+    TextDocumentIdentifier, TextDocumentPositionParams, Url,
+};
+use ui::{prelude::*, ListItem};
+use util::ResultExt as _;
+use workspace::{
+    item::{Item, TabContentParams},
+    Workspace, WorkspaceId,
+};
+
+use crate::persistence::CALL_HIERARCHY_DB;
+
+actions!(call_hierarchy, [ShowCallHierarchy]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ShowCallHierarchy, cx| {
+            CallHierarchyView::deploy(workspace, cx);
+        });
+    })
+    .detach();
+}
+
+/// The callers of the function under the cursor on the left, walked as many levels up as the
+/// user expands them, and the functions called by the selected caller on the right.
+pub struct CallHierarchyView {
+    title: SharedString,
+    split: View<SplitView>,
+    focus_handle: FocusHandle,
+    workspace_id: Option<WorkspaceId>,
+    _subscriptions: Vec<gpui::Subscription>,
+}
+
+impl CallHierarchyView {
+    /// Opens the call hierarchy of the function under the cursor of the active editor, if one
+    /// of its buffer's language servers supports call hierarchies.
+    pub fn deploy(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some((provider, position)) = call_hierarchy_position(workspace, &editor, cx) else {
+            return;
+        };
+
+        let prepare = provider.prepare(position);
+        cx.spawn(|workspace, mut cx| async move {
+            let items = prepare.await?;
+            if items.is_empty() {
+                return anyhow::Ok(());
+            }
+            workspace.update(&mut cx, |workspace, cx| {
+                let workspace_handle = cx.view().downgrade();
+                let workspace_id = workspace.database_id();
+                let view = cx.new_view(|cx| {
+                    CallHierarchyView::new(provider, items, workspace_handle, workspace_id, cx)
+                });
+                workspace.add_item_to_active_pane(Box::new(view), None, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn new(
+        provider: CallHierarchyProvider,
+        items: Vec<CallHierarchyItem>,
+        workspace: WeakView<Workspace>,
+        workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let title = items
+            .first()
+            .map(|item| format!("Calls of {}", item.name).into())
+            .unwrap_or_else(|| "Call Hierarchy".into());
+        let callers = cx.new_view(|cx| {
+            CallTreePane::new(
+                CallTree::new(CallHierarchyDirection::Incoming, items),
+                provider.clone(),
+                workspace.clone(),
+                cx,
+            )
+        });
+        let callees = cx.new_view(|cx| {
+            CallTreePane::new(
+                CallTree::new(CallHierarchyDirection::Outgoing, Vec::new()),
+                provider.clone(),
+                workspace,
+                cx,
+            )
+        });
+        let mut subscriptions = vec![cx.subscribe(&callers, {
+            let callees = callees.clone();
+            move |_, _, event: &CallTreePaneEvent, cx| {
+                let CallTreePaneEvent::Selected(item) = event;
+                let item = item.clone();
+                callees.update(cx, |callees, cx| {
+                    callees.set_tree(
+                        CallTree::new(CallHierarchyDirection::Outgoing, vec![item]),
+                        cx,
+                    )
+                });
+            }
+        })];
+
+        // Start with the divider where it was last left in the workspace.
+        let divider_position = workspace_id
+            .and_then(|workspace_id| {
+                CALL_HIERARCHY_DB
+                    .divider_position(workspace_id)
+                    .log_err()
+                    .flatten()
+            })
+            .unwrap_or(0.5);
+        let split = cx.new_view(|_| {
+            SplitView::horizontal(callers, callees)
+                .with_divider_position(divider_position)
+                .min_size(px(160.))
+        });
+        subscriptions.push(cx.subscribe(&split, |this, _, event, cx| {
+            let SplitViewEvent::DividerMoved(divider_position) = *event;
+            this.save_divider_position(divider_position, cx);
+        }));
+        Self {
+            title,
+            split,
+            focus_handle: cx.focus_handle(),
+            workspace_id,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Persists the divider position, so that call hierarchies opened later in the workspace,
+    /// including in its next sessions, start with it.
+    fn save_divider_position(&self, divider_position: f32, cx: &mut ViewContext<Self>) {
+        let Some(workspace_id) = self.workspace_id else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(async move {
+                CALL_HIERARCHY_DB
+                    .save_divider_position(workspace_id, divider_position)
+                    .await
+                    .log_err()
+            })
+            .detach();
+    }
+}
+
+/// Returns a provider for the buffer under the active editor's cursor, and the cursor's
+/// position in that buffer.
+fn call_hierarchy_position(
+    workspace: &Workspace,
+    editor: &View<Editor>,
+    cx: &AppContext,
+) -> Option<(CallHierarchyProvider, TextDocumentPositionParams)> {
+    let editor = editor.read(cx);
+    let cursor = editor.selections.newest_anchor().head();
+    let (buffer, anchor) = editor
+        .buffer()
+        .read(cx)
+        .text_anchor_for_position(cursor, cx)?;
+    let buffer = buffer.read(cx);
+    let path = project::File::from_dyn(buffer.file())?.abs_path(cx);
+    let uri = Url::from_file_path(path).ok()?;
+    let provider = workspace
+        .project()
+        .read(cx)
+        .language_servers_for_buffer(buffer, cx)
+        .find_map(|(_, server)| CallHierarchyProvider::new(server.clone()))?;
+    let position = point_to_lsp(buffer.summary_for_anchor::<PointUtf16>(&anchor));
+    Some((
+        provider,
+        TextDocumentPositionParams::new(TextDocumentIdentifier::new(uri), position),
+    ))
+}
+
+/// Opens the file of a call hierarchy item and selects the item's name.
+fn open_item(workspace: &WeakView<Workspace>, item: &CallHierarchyItem, cx: &mut WindowContext) {
+    let Ok(path) = item.uri.to_file_path() else {
+        return;
+    };
+    let Some(open) = workspace
+        .update(cx, |workspace, cx| workspace.open_abs_path(path, true, cx))
+        .log_err()
+    else {
+        return;
+    };
+    let position = item.selection_range.start;
+    cx.spawn(|mut cx| async move {
+        let editor = open.await?.downcast::<Editor>();
+        if let Some(editor) = editor {
+            editor.update(&mut cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let point = snapshot.clip_point_utf16(point_from_lsp(position), Bias::Left);
+                editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                    selections.select_ranges([point..point]);
+                });
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+enum CallTreePaneEvent {
+    Selected(CallHierarchyItem),
+}
+
+/// One side of a [`CallHierarchyView`], fetching the calls of its nodes as they are expanded.
+struct CallTreePane {
+    tree: CallTree,
+    provider: CallHierarchyProvider,
+    workspace: WeakView<Workspace>,
+    selected: Option<CallTreeNodeId>,
+    pending_fetches: Vec<Task<()>>,
+}
+
+impl CallTreePane {
+    fn new(
+        tree: CallTree,
+        provider: CallHierarchyProvider,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            tree,
+            provider,
+            workspace,
+            selected: None,
+            pending_fetches: Vec::new(),
+        };
+        this.expand_roots(cx);
+        this
+    }
+
+    fn set_tree(&mut self, tree: CallTree, cx: &mut ViewContext<Self>) {
+        self.tree = tree;
+        self.selected = None;
+        self.pending_fetches.clear();
+        self.expand_roots(cx);
+        cx.notify();
+    }
+
+    fn expand_roots(&mut self, cx: &mut ViewContext<Self>) {
+        for root in self.tree.roots().to_vec() {
+            self.expand(root, cx);
+        }
+    }
+
+    fn expand(&mut self, id: CallTreeNodeId, cx: &mut ViewContext<Self>) {
+        if !self.tree.expand(id) {
+            cx.notify();
+            return;
+        }
+        let item = self.tree.node(id).call.item.clone();
+        let calls = self.provider.calls(self.tree.direction(), item);
+        self.pending_fetches
+            .push(cx.spawn(|this, mut cx| async move {
+                let calls = calls.await.log_err().unwrap_or_default();
+                this.update(&mut cx, |this, cx| {
+                    this.tree.set_calls(id, calls);
+                    cx.notify();
+                })
+                .ok();
+            }));
+    }
+
+    fn toggle(&mut self, id: CallTreeNodeId, cx: &mut ViewContext<Self>) {
+        if self.tree.node(id).is_expanded() {
+            self.tree.collapse(id);
+            cx.notify();
+        } else {
+            self.expand(id, cx);
+        }
+    }
+
+    fn select(&mut self, id: CallTreeNodeId, cx: &mut ViewContext<Self>) {
+        self.selected = Some(id);
+        let item = self.tree.node(id).call.item.clone();
+        open_item(&self.workspace, &item, cx);
+        cx.emit(CallTreePaneEvent::Selected(item));
+        cx.notify();
+    }
+
+    fn render_node(&self, id: CallTreeNodeId, cx: &mut ViewContext<Self>) -> AnyElement {
+        let node = self.tree.node(id);
+        let item = &node.call.item;
+        // Leaves have no toggle, but nodes whose calls haven't been fetched yet might have
+        // children.
+        let toggle = match node.children() {
+            Some([]) => None,
+            _ => Some(node.is_expanded()),
+        };
+        let call_count = match node.call.ranges.len() {
+            0 | 1 => None,
+            count => Some(Label::new(format!("{count} calls")).color(Color::Muted)),
+        };
+        let file_name = item
+            .uri
+            .path_segments()
+            .and_then(|segments| segments.last())
+            .map(|file_name| format!("{file_name}:{}", item.selection_range.start.line + 1));
+
+        ListItem::new(SharedString::from(format!("call-{id:?}")))
+            .indent_level(node.depth)
+            .indent_step_size(px(12.))
+            .selected(self.selected == Some(id))
+            .toggle(toggle)
+            .on_toggle(cx.listener(move |this, _, cx| this.toggle(id, cx)))
+            .on_click(cx.listener(move |this, _, cx| this.select(id, cx)))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(item.name.clone()))
+                    .children(file_name.map(|file_name| Label::new(file_name).color(Color::Muted))),
+            )
+            .end_slot(call_count)
+            .into_any_element()
+    }
+}
+
+impl EventEmitter<CallTreePaneEvent> for CallTreePane {}
+
+impl Render for CallTreePane {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let heading = match self.tree.direction() {
+            CallHierarchyDirection::Incoming => "Callers",
+            CallHierarchyDirection::Outgoing => "Callees",
+        };
+        let nodes = self.tree.visible_nodes();
+        v_flex()
+            .size_full()
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(heading).color(Color::Muted)),
+            )
+            .child(
+                div()
+                    .id("call-tree")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .when(nodes.is_empty(), |this| {
+                        this.p_2().child(
+                            Label::new(match self.tree.direction() {
+                                CallHierarchyDirection::Incoming => "No callers",
+                                CallHierarchyDirection::Outgoing => "Select a caller",
+                            })
+                            .color(Color::Muted),
+                        )
+                    })
+                    .children(nodes.into_iter().map(|id| self.render_node(id, cx))),
+            )
+    }
+}
+
+impl Render for CallHierarchyView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .child(self.split.clone())
+    }
+}
+
+impl EventEmitter<()> for CallHierarchyView {}
+
+impl FocusableView for CallHierarchyView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for CallHierarchyView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext<'_>) -> AnyElement {
+        Label::new(self.title.clone())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
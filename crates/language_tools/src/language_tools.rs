@@ -1,4 +1,8 @@
+mod call_hierarchy_view;
 mod lsp_log;
+mod lsp_log_buffer;
+mod lsp_traffic;
+mod persistence;
 mod syntax_tree_view;
 
 #[cfg(test)]
@@ -6,10 +10,15 @@ mod lsp_log_tests;
 
 use gpui::AppContext;
 
+pub use call_hierarchy_view::CallHierarchyView;
 pub use lsp_log::{LogStore, LspLogToolbarItemView, LspLogView};
+pub use lsp_log_buffer::{LspLogBuffer, LspMessage, LspMessageDirection};
+pub use lsp_traffic::LspTrafficView;
 pub use syntax_tree_view::{SyntaxTreeToolbarItemView, SyntaxTreeView};
 
 pub fn init(cx: &mut AppContext) {
+    call_hierarchy_view::init(cx);
     lsp_log::init(cx);
+    lsp_traffic::init(cx);
     syntax_tree_view::init(cx);
 }
@@ -0,0 +1,350 @@
+use std::{collections::VecDeque, time::Instant};
+
+use collections::HashMap;
+use lsp::RequestId;
+use serde::Deserialize;
+
+/// Which way an [`LspMessage`] was sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LspMessageDirection {
+    /// Sent by the language server to Zed.
+    In,
+    /// Sent by Zed to the language server.
+    Out,
+    /// Written by Zed about the language server, such as when it crashed and was restarted.
+    Internal,
+}
+
+/// A JSON-RPC message exchanged with a language server.
+#[derive(Debug, Clone)]
+pub struct LspMessage {
+    pub direction: LspMessageDirection,
+    pub timestamp: Instant,
+    pub content: String,
+    /// The method of the request or notification, or of the request a response answers.
+    pub method: Option<String>,
+}
+
+/// The parts of a message needed to tell which method it belongs to.
+#[derive(Deserialize)]
+struct MessageHeader {
+    #[serde(default)]
+    id: Option<RequestId>,
+    #[serde(default)]
+    method: Option<String>,
+}
+
+/// The last messages exchanged with a language server while its traffic was being recorded,
+/// oldest first.
+///
+/// Once the buffer holds [`LspLogBuffer::capacity`] messages, or their contents add up to
+/// more than [`LspLogBuffer::MAX_BYTES`], new messages replace the oldest ones. Messages
+/// longer than [`LspLogBuffer::MAX_MESSAGE_LEN`] are truncated.
+pub struct LspLogBuffer {
+    messages: VecDeque<LspMessage>,
+    capacity: usize,
+    /// The total length of the contents of `messages`.
+    len_bytes: usize,
+    /// The methods of requests that haven't been responded to yet, so that responses can be
+    /// attributed to them, along with the order in which the requests were sent.
+    pending_requests: HashMap<(LspMessageDirection, RequestId), (String, usize)>,
+    next_request_ix: usize,
+}
+
+impl Default for LspLogBuffer {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl LspLogBuffer {
+    /// The number of messages kept, unless set with [`LspLogBuffer::with_capacity`].
+    pub const DEFAULT_CAPACITY: usize = 500;
+
+    /// The most bytes of message contents kept, regardless of the capacity.
+    pub const MAX_BYTES: usize = 8 * 1024 * 1024;
+
+    /// The length messages are truncated to. Longer messages are not parsed, so their
+    /// methods are unknown.
+    pub const MAX_MESSAGE_LEN: usize = 256 * 1024;
+
+    /// The most requests awaiting a response that are tracked. Once exceeded, the oldest
+    /// requests are forgotten, as their responses are unlikely to arrive anymore.
+    pub const MAX_PENDING_REQUESTS: usize = 1000;
+
+    /// Creates a buffer that keeps the last `capacity` messages.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            messages: VecDeque::with_capacity(capacity),
+            capacity,
+            len_bytes: 0,
+            pending_requests: HashMap::default(),
+            next_request_ix: 0,
+        }
+    }
+
+    /// The number of messages the buffer keeps.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the number of messages the buffer keeps, dropping the oldest ones if it holds
+    /// more than that.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.evict(0, 0);
+    }
+
+    /// Records a message sent in the given direction.
+    pub fn push(&mut self, direction: LspMessageDirection, content: impl Into<String>) {
+        let mut content = content.into();
+        let method = if content.len() > Self::MAX_MESSAGE_LEN {
+            let mut end = Self::MAX_MESSAGE_LEN;
+            while !content.is_char_boundary(end) {
+                end -= 1;
+            }
+            content.truncate(end);
+            content.push('…');
+            None
+        } else {
+            self.method_for(direction, &content)
+        };
+
+        self.evict(1, content.len());
+        self.len_bytes += content.len();
+        self.messages.push_back(LspMessage {
+            direction,
+            timestamp: Instant::now(),
+            content,
+            method,
+        });
+    }
+
+    /// Drops the oldest messages until the given number of new messages, of the given total
+    /// length, fit in the buffer.
+    fn evict(&mut self, new_messages: usize, new_len: usize) {
+        while self.messages.len() + new_messages > self.capacity
+            || (!self.messages.is_empty() && self.len_bytes + new_len > Self::MAX_BYTES)
+        {
+            let Some(message) = self.messages.pop_front() else {
+                break;
+            };
+            self.len_bytes -= message.content.len();
+        }
+    }
+
+    fn add_pending_request(&mut self, key: (LspMessageDirection, RequestId), method: String) {
+        self.pending_requests
+            .insert(key, (method, self.next_request_ix));
+        self.next_request_ix += 1;
+        if self.pending_requests.len() > Self::MAX_PENDING_REQUESTS {
+            let oldest = self
+                .pending_requests
+                .iter()
+                .min_by_key(|(_, (_, ix))| *ix)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.pending_requests.remove(&oldest);
+            }
+        }
+    }
+
+    fn method_for(&mut self, direction: LspMessageDirection, content: &str) -> Option<String> {
+        if direction == LspMessageDirection::Internal {
+            return None;
+        }
+        let header = serde_json::from_str::<MessageHeader>(content).ok()?;
+        match (header.id, header.method) {
+            (Some(id), Some(method)) => {
+                self.add_pending_request((direction, id), method.clone());
+                Some(method)
+            }
+            (None, Some(method)) => Some(method),
+            // A response answers a request that was sent the other way.
+            (Some(id), None) => {
+                let request_direction = match direction {
+                    LspMessageDirection::In => LspMessageDirection::Out,
+                    LspMessageDirection::Out => LspMessageDirection::In,
+                    LspMessageDirection::Internal => return None,
+                };
+                self.pending_requests
+                    .remove(&(request_direction, id))
+                    .map(|(method, _)| method)
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Returns the messages in the buffer, oldest first.
+    pub fn messages(&self) -> impl DoubleEndedIterator<Item = &LspMessage> + ExactSizeIterator {
+        self.messages.iter()
+    }
+
+    /// Returns the requests, responses and notifications for the given method, oldest first.
+    pub fn filter_by_method<'a>(
+        &'a self,
+        method: &'a str,
+    ) -> impl Iterator<Item = &'a LspMessage> + 'a {
+        self.messages
+            .iter()
+            .filter(move |message| message.method.as_deref() == Some(method))
+    }
+
+    /// The number of messages in the buffer.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Whether the buffer holds no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Removes all messages from the buffer, and forgets about the requests awaiting a
+    /// response.
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.len_bytes = 0;
+        self.pending_requests.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_buffer_keeps_last_messages() {
+        let mut buffer = LspLogBuffer::with_capacity(3);
+        for i in 0..5 {
+            buffer.push(
+                LspMessageDirection::Out,
+                format!(r#"{{"jsonrpc":"2.0","method":"$/n{i}"}}"#),
+            );
+        }
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(
+            buffer
+                .messages()
+                .map(|message| message.method.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            ["$/n2", "$/n3", "$/n4"]
+        );
+
+        buffer.set_capacity(1);
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(
+            buffer.messages().next().unwrap().method.as_deref(),
+            Some("$/n4")
+        );
+    }
+
+    #[test]
+    fn test_log_buffer_filter_by_method() {
+        let mut buffer = LspLogBuffer::default();
+        assert_eq!(buffer.capacity(), LspLogBuffer::DEFAULT_CAPACITY);
+
+        buffer.push(
+            LspMessageDirection::Out,
+            r#"{"jsonrpc":"2.0","id":1,"method":"textDocument/hover","params":{}}"#,
+        );
+        buffer.push(
+            LspMessageDirection::In,
+            r#"{"jsonrpc":"2.0","method":"window/logMessage","params":{}}"#,
+        );
+        // The server uses the same id for a request of its own.
+        buffer.push(
+            LspMessageDirection::In,
+            r#"{"jsonrpc":"2.0","id":1,"method":"workspace/configuration","params":{}}"#,
+        );
+        buffer.push(
+            LspMessageDirection::In,
+            r#"{"jsonrpc":"2.0","id":1,"result":null}"#,
+        );
+        buffer.push(
+            LspMessageDirection::Out,
+            r#"{"jsonrpc":"2.0","id":1,"result":[]}"#,
+        );
+        buffer.push(LspMessageDirection::In, "not json");
+
+        let hover = buffer
+            .filter_by_method("textDocument/hover")
+            .map(|message| message.direction)
+            .collect::<Vec<_>>();
+        assert_eq!(hover, [LspMessageDirection::Out, LspMessageDirection::In]);
+
+        let configuration = buffer
+            .filter_by_method("workspace/configuration")
+            .map(|message| message.direction)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            configuration,
+            [LspMessageDirection::In, LspMessageDirection::Out]
+        );
+
+        assert_eq!(buffer.filter_by_method("window/logMessage").count(), 1);
+        assert_eq!(buffer.messages().last().unwrap().method, None);
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+
+        // Clearing the buffer forgets about pending requests too.
+        buffer.push(
+            LspMessageDirection::Out,
+            r#"{"jsonrpc":"2.0","id":2,"method":"textDocument/hover","params":{}}"#,
+        );
+        buffer.clear();
+        buffer.push(
+            LspMessageDirection::In,
+            r#"{"jsonrpc":"2.0","id":2,"result":null}"#,
+        );
+        assert_eq!(buffer.messages().next().unwrap().method, None);
+    }
+
+    #[test]
+    fn test_log_buffer_limits() {
+        let mut buffer = LspLogBuffer::with_capacity(10_000);
+        let large_message = "x".repeat(LspLogBuffer::MAX_MESSAGE_LEN * 2);
+        buffer.push(LspMessageDirection::In, large_message);
+        let message = buffer.messages().next().unwrap();
+        assert_eq!(
+            message.content.len(),
+            LspLogBuffer::MAX_MESSAGE_LEN + '…'.len_utf8()
+        );
+
+        let message_count = LspLogBuffer::MAX_BYTES / LspLogBuffer::MAX_MESSAGE_LEN;
+        for _ in 0..message_count * 2 {
+            buffer.push(
+                LspMessageDirection::In,
+                "x".repeat(LspLogBuffer::MAX_MESSAGE_LEN),
+            );
+        }
+        assert_eq!(buffer.len(), message_count);
+
+        // Requests that never get a response are eventually forgotten.
+        for id in 0..LspLogBuffer::MAX_PENDING_REQUESTS as i32 + 1 {
+            buffer.push(
+                LspMessageDirection::Out,
+                format!(r#"{{"jsonrpc":"2.0","id":{id},"method":"$/n{id}"}}"#),
+            );
+        }
+        assert_eq!(
+            buffer.pending_requests.len(),
+            LspLogBuffer::MAX_PENDING_REQUESTS
+        );
+        buffer.push(
+            LspMessageDirection::In,
+            r#"{"jsonrpc":"2.0","id":0,"result":null}"#,
+        );
+        assert_eq!(buffer.messages().last().unwrap().method, None);
+        buffer.push(
+            LspMessageDirection::In,
+            r#"{"jsonrpc":"2.0","id":1,"result":null}"#,
+        );
+        assert_eq!(
+            buffer.messages().last().unwrap().method.as_deref(),
+            Some("$/n1")
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use std::{sync::Arc, time::Duration};
+
+use editor::{Editor, EditorEvent};
+use futures::{channel::mpsc, StreamExt};
+use gpui::{
+    actions, div, uniform_list, AnchorCorner, AnyElement, AppContext, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, IntoElement, Model, ParentElement, Render, Styled, Task,
+    UniformListScrollHandle, View, ViewContext, VisualContext, WindowContext,
+};
+use language::LanguageServerId;
+use lsp::{IoKind, LanguageServer};
+use project::Project;
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::{prelude::*, Button, ContextMenu, Label, PopoverMenu};
+use workspace::{
+    item::{Item, TabContentParams},
+    Workspace,
+};
+
+use crate::lsp_log_buffer::{LspLogBuffer, LspMessage, LspMessageDirection};
+
+/// How long to wait for more messages before refreshing the view.
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The most characters of a message shown on its row.
+const MAX_PREVIEW_LEN: usize = 300;
+
+actions!(debug, [OpenLanguageServerTraffic]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &OpenLanguageServerTraffic, cx| {
+            let project = workspace.project().clone();
+            if project.read(cx).is_local() {
+                let traffic_view = cx.new_view(|cx| LspTrafficView::new(project, cx));
+                workspace.add_item_to_active_pane(Box::new(traffic_view), None, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// Shows the messages most recently exchanged with one of the project's language servers.
+///
+/// The messages are only recorded while the view shows the server, so that servers nobody is
+/// inspecting don't pay for copying and parsing every message.
+pub struct LspTrafficView {
+    project: Model<Project>,
+    server: Option<Arc<LanguageServer>>,
+    log_buffer: LspLogBuffer,
+    filter_editor: View<Editor>,
+    messages: Vec<LspMessage>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    _io_subscription: Option<lsp::Subscription>,
+    _refresh_task: Option<Task<()>>,
+    _subscriptions: Vec<gpui::Subscription>,
+}
+
+impl LspTrafficView {
+    pub fn new(project: Model<Project>, cx: &mut ViewContext<Self>) -> Self {
+        let filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter by method, e.g. textDocument/hover", cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&filter_editor, |this, _, event: &EditorEvent, cx| {
+                if matches!(event, EditorEvent::BufferEdited) {
+                    this.refresh_messages(cx);
+                }
+            }),
+            cx.subscribe(&project, |this, _, event, cx| match event {
+                project::Event::LanguageServerAdded(server_id) if this.server.is_none() => {
+                    this.show_server(*server_id, cx);
+                }
+                project::Event::LanguageServerRemoved(server_id)
+                    if this.server_id() == Some(*server_id) =>
+                {
+                    // Keep showing the server's last messages, but stop listening for more.
+                    this._io_subscription.take();
+                    this._refresh_task.take();
+                }
+                project::Event::LanguageServerLog(server_id, message)
+                    if this.server_id() == Some(*server_id) =>
+                {
+                    this.log_buffer
+                        .push(LspMessageDirection::Internal, message.as_str());
+                    this.refresh_messages(cx);
+                }
+                _ => {}
+            }),
+        ];
+
+        let mut this = Self {
+            project,
+            server: None,
+            log_buffer: LspLogBuffer::default(),
+            filter_editor,
+            messages: Vec::new(),
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _io_subscription: None,
+            _refresh_task: None,
+            _subscriptions: subscriptions,
+        };
+        let first_server = this
+            .project
+            .read(cx)
+            .language_servers()
+            .map(|(server_id, ..)| server_id)
+            .min();
+        if let Some(server_id) = first_server {
+            this.show_server(server_id, cx);
+        }
+        this
+    }
+
+    fn server_id(&self) -> Option<LanguageServerId> {
+        self.server.as_ref().map(|server| server.server_id())
+    }
+
+    /// Starts recording the traffic of the given language server, in place of the server shown
+    /// before, and keeps the view up to date as more messages are exchanged with it.
+    pub fn show_server(&mut self, server_id: LanguageServerId, cx: &mut ViewContext<Self>) {
+        let Some(server) = self.project.read(cx).language_server_for_id(server_id) else {
+            return;
+        };
+
+        let (io_tx, mut io_rx) = mpsc::unbounded();
+        self._io_subscription = Some(server.on_io(move |io_kind, message| {
+            let direction = match io_kind {
+                IoKind::StdIn => LspMessageDirection::Out,
+                IoKind::StdOut => LspMessageDirection::In,
+                IoKind::StdErr => return,
+            };
+            io_tx.unbounded_send((direction, message.to_string())).ok();
+        }));
+        self._refresh_task = Some(cx.spawn(|this, mut cx| async move {
+            while let Some(message) = io_rx.next().await {
+                // Messages tend to come in bursts, so wait for the burst to end and record all
+                // of them at once.
+                cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+                let mut messages = vec![message];
+                while let Ok(Some(message)) = io_rx.try_next() {
+                    messages.push(message);
+                }
+                let recorded = this.update(&mut cx, |this, cx| {
+                    for (direction, content) in messages {
+                        this.log_buffer.push(direction, content);
+                    }
+                    this.refresh_messages(cx);
+                });
+                if recorded.is_err() {
+                    break;
+                }
+            }
+        }));
+        self.server = Some(server);
+        self.log_buffer.clear();
+        self.refresh_messages(cx);
+    }
+
+    fn refresh_messages(&mut self, cx: &mut ViewContext<Self>) {
+        let filter = self.filter_editor.read(cx).text(cx);
+        let filter = filter.trim();
+        self.messages = if filter.is_empty() {
+            self.log_buffer.messages().cloned().collect()
+        } else {
+            self.log_buffer.filter_by_method(filter).cloned().collect()
+        };
+
+        if let Some(last_ix) = self.messages.len().checked_sub(1) {
+            self.scroll_handle.scroll_to_item(last_ix);
+        }
+        cx.notify();
+    }
+
+    fn clear(&mut self, cx: &mut ViewContext<Self>) {
+        self.log_buffer.clear();
+        self.messages.clear();
+        cx.notify();
+    }
+
+    fn render_server_menu(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let project = self.project.read(cx);
+        let mut servers = project
+            .language_servers()
+            .filter_map(|(server_id, server_name, worktree_id)| {
+                let worktree = project.worktree_for_id(worktree_id, cx)?;
+                let label = format!("{} ({})", server_name.0, worktree.read(cx).root_name());
+                Some((server_id, label))
+            })
+            .collect::<Vec<_>>();
+        servers.sort_by_key(|(server_id, _)| *server_id);
+
+        let current_server_id = self.server_id();
+        let current_label = current_server_id
+            .and_then(|current_server_id| {
+                servers
+                    .iter()
+                    .find(|(server_id, _)| *server_id == current_server_id)
+                    .map(|(_, label)| label.clone())
+                    .or_else(|| self.server.as_ref().map(|server| server.name().to_string()))
+            })
+            .unwrap_or_else(|| "No server selected".to_string());
+
+        let view = cx.view().clone();
+        PopoverMenu::new("LspTrafficView")
+            .anchor(AnchorCorner::TopLeft)
+            .trigger(Button::new("language_server_traffic_menu", current_label))
+            .menu(move |cx| {
+                let servers = servers.clone();
+                let view = view.clone();
+                ContextMenu::build(cx, move |mut menu, cx| {
+                    for (server_id, label) in servers {
+                        menu = menu.toggleable_entry(
+                            label,
+                            Some(server_id) == current_server_id,
+                            None,
+                            cx.handler_for(&view, move |view, cx| {
+                                view.show_server(server_id, cx);
+                            }),
+                        );
+                    }
+                    menu
+                })
+                .into()
+            })
+    }
+}
+
+/// Returns the first line of a message, shortened to fit on a row.
+fn message_preview(content: &str) -> SharedString {
+    let line = content.lines().next().unwrap_or_default();
+    match line.char_indices().nth(MAX_PREVIEW_LEN) {
+        Some((end, _)) => format!("{}…", &line[..end]).into(),
+        None => line.to_string().into(),
+    }
+}
+
+fn render_message(
+    ix: usize,
+    message: &LspMessage,
+    previous: Option<&LspMessage>,
+    cx: &WindowContext,
+) -> AnyElement {
+    let (arrow, direction_color) = match message.direction {
+        LspMessageDirection::Out => ("→", Color::Accent),
+        LspMessageDirection::In => ("←", Color::Success),
+        LspMessageDirection::Internal => ("•", Color::Warning),
+    };
+    let elapsed = previous
+        .map(|previous| {
+            let elapsed = message
+                .timestamp
+                .saturating_duration_since(previous.timestamp);
+            format!("+{}ms", elapsed.as_millis())
+        })
+        .unwrap_or_default();
+
+    h_flex()
+        .id(ix)
+        .w_full()
+        .gap_2()
+        .px_2()
+        .whitespace_nowrap()
+        .hover(|style| style.bg(cx.theme().colors().element_hover))
+        .child(Label::new(arrow).color(direction_color))
+        .child(
+            div()
+                .w(rems(5.))
+                .flex_none()
+                .child(Label::new(elapsed).color(Color::Muted)),
+        )
+        .child(
+            div().w(rems(18.)).flex_none().overflow_hidden().child(
+                Label::new(match (message.direction, message.method.clone()) {
+                    (_, Some(method)) => method,
+                    (LspMessageDirection::Internal, None) => "zed".into(),
+                    (_, None) => "response".into(),
+                })
+                .color(Color::Default),
+            ),
+        )
+        .child(
+            div()
+                .flex_1()
+                .overflow_hidden()
+                .child(Label::new(message_preview(&message.content)).color(Color::Muted)),
+        )
+        .into_any_element()
+}
+
+impl Render for LspTrafficView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let header = h_flex()
+            .gap_2()
+            .p_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(self.render_server_menu(cx))
+            .child(
+                div()
+                    .flex_1()
+                    .px_2()
+                    .py_1()
+                    .border_1()
+                    .rounded_md()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.filter_editor.clone()),
+            )
+            .child(Label::new(format!("{} messages", self.messages.len())).color(Color::Muted))
+            .child(
+                Button::new("clear_traffic_button", "Clear").on_click(cx.listener(
+                    |this, _, cx| {
+                        this.clear(cx);
+                    },
+                )),
+            );
+
+        let list = if self.messages.is_empty() {
+            div()
+                .flex_1()
+                .p_2()
+                .child(Label::new("No messages").color(Color::Muted))
+                .into_any_element()
+        } else {
+            uniform_list(
+                cx.view().clone(),
+                "LspTrafficView",
+                self.messages.len(),
+                |this, range, cx| {
+                    range
+                        .map(|ix| {
+                            let previous = ix.checked_sub(1).map(|ix| &this.messages[ix]);
+                            render_message(ix, &this.messages[ix], previous, cx)
+                        })
+                        .collect()
+                },
+            )
+            .flex_1()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .font(ThemeSettings::get_global(cx).buffer_font.clone())
+            .child(header)
+            .child(list)
+    }
+}
+
+impl EventEmitter<()> for LspTrafficView {}
+
+impl FocusableView for LspTrafficView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for LspTrafficView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext<'_>) -> AnyElement {
+        Label::new("LSP Traffic")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_preview() {
+        assert_eq!(message_preview("{\"id\":1}\n{}"), "{\"id\":1}");
+        let long = "é".repeat(MAX_PREVIEW_LEN + 10);
+        let preview = message_preview(&long);
+        assert_eq!(preview.chars().count(), MAX_PREVIEW_LEN + 1);
+        assert!(preview.ends_with('…'));
+    }
+}
@@ -0,0 +1,34 @@
+use db::{define_connection, query, sqlez_macros::sql};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection! {
+    pub static ref CALL_HIERARCHY_DB: CallHierarchyDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE call_hierarchy_views (
+                workspace_id INTEGER PRIMARY KEY,
+                divider_position REAL NOT NULL,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        )];
+}
+
+impl CallHierarchyDb {
+    query! {
+        pub async fn save_divider_position(
+            workspace_id: WorkspaceId,
+            divider_position: f32
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO call_hierarchy_views(workspace_id, divider_position)
+            VALUES (?, ?)
+        }
+    }
+
+    query! {
+        pub fn divider_position(workspace_id: WorkspaceId) -> Result<Option<f32>> {
+            SELECT divider_position
+            FROM call_hierarchy_views
+            WHERE workspace_id = ?
+        }
+    }
+}
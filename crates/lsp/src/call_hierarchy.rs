@@ -0,0 +1,397 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures::{future::BoxFuture, FutureExt as _};
+use lsp_types::{
+    request::{CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare},
+    CallHierarchyIncomingCallsParams, CallHierarchyItem, CallHierarchyOutgoingCallsParams,
+    CallHierarchyPrepareParams, CallHierarchyServerCapability, Range, TextDocumentPositionParams,
+};
+
+use crate::LanguageServer;
+
+/// Which calls of an item a [`CallTree`] shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallHierarchyDirection {
+    /// The functions that call the item.
+    Incoming,
+    /// The functions the item calls.
+    Outgoing,
+}
+
+/// A function that calls or is called by another one.
+#[derive(Debug, Clone)]
+pub struct CallHierarchyCall {
+    pub item: CallHierarchyItem,
+    /// Where the calls are made, in the caller. For incoming calls that is `item`, and for
+    /// outgoing calls it is the item whose calls were requested.
+    pub ranges: Vec<Range>,
+}
+
+/// Fetches call hierarchies from a language server: first the items at a position with
+/// `textDocument/prepareCallHierarchy`, then their callers or callees as they are expanded.
+#[derive(Clone)]
+pub struct CallHierarchyProvider {
+    server: Arc<LanguageServer>,
+}
+
+impl CallHierarchyProvider {
+    /// Returns a provider for the given server, unless it doesn't support call hierarchies.
+    pub fn new(server: Arc<LanguageServer>) -> Option<Self> {
+        let supported = match server.capabilities().call_hierarchy_provider {
+            Some(CallHierarchyServerCapability::Simple(enabled)) => enabled,
+            Some(_) => true,
+            None => false,
+        };
+        supported.then_some(Self { server })
+    }
+
+    pub fn server(&self) -> &Arc<LanguageServer> {
+        &self.server
+    }
+
+    /// Returns the items at the given position whose calls can be requested.
+    pub fn prepare(
+        &self,
+        position: TextDocumentPositionParams,
+    ) -> BoxFuture<'static, Result<Vec<CallHierarchyItem>>> {
+        let request = self
+            .server
+            .request::<CallHierarchyPrepare>(CallHierarchyPrepareParams {
+                text_document_position_params: position,
+                work_done_progress_params: Default::default(),
+            });
+        async move { Ok(request.await?.unwrap_or_default()) }.boxed()
+    }
+
+    /// Returns the functions that call `item`, or that `item` calls.
+    pub fn calls(
+        &self,
+        direction: CallHierarchyDirection,
+        item: CallHierarchyItem,
+    ) -> BoxFuture<'static, Result<Vec<CallHierarchyCall>>> {
+        match direction {
+            CallHierarchyDirection::Incoming => {
+                let request = self.server.request::<CallHierarchyIncomingCalls>(
+                    CallHierarchyIncomingCallsParams {
+                        item,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                );
+                async move {
+                    Ok(request
+                        .await?
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|call| CallHierarchyCall {
+                            item: call.from,
+                            ranges: call.from_ranges,
+                        })
+                        .collect())
+                }
+                .boxed()
+            }
+            CallHierarchyDirection::Outgoing => {
+                let request = self.server.request::<CallHierarchyOutgoingCalls>(
+                    CallHierarchyOutgoingCallsParams {
+                        item,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                );
+                async move {
+                    Ok(request
+                        .await?
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|call| CallHierarchyCall {
+                            item: call.to,
+                            ranges: call.from_ranges,
+                        })
+                        .collect())
+                }
+                .boxed()
+            }
+        }
+    }
+}
+
+/// Identifies a node of a [`CallTree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallTreeNodeId(usize);
+
+#[derive(Debug, Clone)]
+pub struct CallTreeNode {
+    pub call: CallHierarchyCall,
+    /// How many levels below a root the node is.
+    pub depth: usize,
+    children: Option<Vec<CallTreeNodeId>>,
+    expanded: bool,
+}
+
+impl CallTreeNode {
+    /// The node's children, or `None` if they haven't been fetched yet.
+    pub fn children(&self) -> Option<&[CallTreeNodeId]> {
+        self.children.as_deref()
+    }
+
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+}
+
+/// The callers or callees of some items, walked as far as the user has expanded them.
+///
+/// A node's calls are only requested when it is first expanded, so the tree can be walked
+/// through recursive calls without end.
+#[derive(Debug, Clone)]
+pub struct CallTree {
+    direction: CallHierarchyDirection,
+    nodes: Vec<CallTreeNode>,
+    roots: Vec<CallTreeNodeId>,
+}
+
+impl CallTree {
+    /// Creates a tree with the given items, as returned by
+    /// [`CallHierarchyProvider::prepare`], at its root.
+    pub fn new(direction: CallHierarchyDirection, roots: Vec<CallHierarchyItem>) -> Self {
+        let mut this = Self {
+            direction,
+            nodes: Vec::new(),
+            roots: Vec::new(),
+        };
+        this.roots = roots
+            .into_iter()
+            .map(|item| {
+                this.push_node(
+                    CallHierarchyCall {
+                        item,
+                        ranges: Vec::new(),
+                    },
+                    0,
+                )
+            })
+            .collect();
+        this
+    }
+
+    fn push_node(&mut self, call: CallHierarchyCall, depth: usize) -> CallTreeNodeId {
+        let id = CallTreeNodeId(self.nodes.len());
+        self.nodes.push(CallTreeNode {
+            call,
+            depth,
+            children: None,
+            expanded: false,
+        });
+        id
+    }
+
+    pub fn direction(&self) -> CallHierarchyDirection {
+        self.direction
+    }
+
+    pub fn roots(&self) -> &[CallTreeNodeId] {
+        &self.roots
+    }
+
+    pub fn node(&self, id: CallTreeNodeId) -> &CallTreeNode {
+        &self.nodes[id.0]
+    }
+
+    /// Expands a node, returning whether its calls still need to be fetched and passed to
+    /// [`CallTree::set_calls`].
+    pub fn expand(&mut self, id: CallTreeNodeId) -> bool {
+        let node = &mut self.nodes[id.0];
+        node.expanded = true;
+        node.children.is_none()
+    }
+
+    pub fn collapse(&mut self, id: CallTreeNodeId) {
+        self.nodes[id.0].expanded = false;
+    }
+
+    /// Sets the calls of a node, fetched with [`CallHierarchyProvider::calls`].
+    pub fn set_calls(&mut self, id: CallTreeNodeId, calls: Vec<CallHierarchyCall>) {
+        let depth = self.nodes[id.0].depth + 1;
+        let children = calls
+            .into_iter()
+            .map(|call| self.push_node(call, depth))
+            .collect();
+        self.nodes[id.0].children = Some(children);
+    }
+
+    /// Returns the nodes that aren't inside a collapsed node, in the order they are shown.
+    pub fn visible_nodes(&self) -> Vec<CallTreeNodeId> {
+        let mut visible = Vec::new();
+        let mut stack = self.roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(id) = stack.pop() {
+            visible.push(id);
+            let node = self.node(id);
+            if let Some(children) = node.children.as_ref().filter(|_| node.expanded) {
+                stack.extend(children.iter().rev().copied());
+            }
+        }
+        visible
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{SemanticVersion, TestAppContext};
+    use lsp_types::{
+        CallHierarchyIncomingCall, CallHierarchyOutgoingCall, Position, ServerCapabilities,
+        SymbolKind, TextDocumentIdentifier, Url,
+    };
+
+    use super::*;
+    use crate::{FakeLanguageServer, LanguageServerBinary, LanguageServerId};
+
+    fn item(name: &str, line: u32) -> CallHierarchyItem {
+        let range = Range::new(Position::new(line, 0), Position::new(line + 2, 1));
+        CallHierarchyItem {
+            name: name.to_string(),
+            kind: SymbolKind::FUNCTION,
+            tags: None,
+            detail: None,
+            uri: Url::parse("file:///src/main.rs").unwrap(),
+            range,
+            selection_range: Range::new(Position::new(line, 3), Position::new(line, 4)),
+            data: None,
+        }
+    }
+
+    /// Returns the callers of a function in `fn a() { b(); c(); } fn b() { c(); } fn c() {}`.
+    fn callers(name: &str) -> Vec<CallHierarchyIncomingCall> {
+        let call_site = |line| vec![Range::new(Position::new(line, 4), Position::new(line, 5))];
+        match name {
+            "b" => vec![CallHierarchyIncomingCall {
+                from: item("a", 0),
+                from_ranges: call_site(1),
+            }],
+            "c" => vec![
+                CallHierarchyIncomingCall {
+                    from: item("a", 0),
+                    from_ranges: call_site(2),
+                },
+                CallHierarchyIncomingCall {
+                    from: item("b", 4),
+                    from_ranges: call_site(5),
+                },
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    fn names(tree: &CallTree) -> Vec<String> {
+        tree.visible_nodes()
+            .into_iter()
+            .map(|id| {
+                let node = tree.node(id);
+                format!("{}{}", "  ".repeat(node.depth), node.call.item.name)
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_call_tree(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            release_channel::init(SemanticVersion::default(), cx);
+        });
+        let (server, fake) = FakeLanguageServer::new(
+            LanguageServerId(0),
+            LanguageServerBinary {
+                path: "path/to/language-server".into(),
+                arguments: vec![],
+                env: None,
+            },
+            "the-lsp".to_string(),
+            ServerCapabilities {
+                call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx.to_async(),
+        );
+        let server = cx.update(|cx| server.initialize(None, cx)).await.unwrap();
+
+        fake.handle_request::<CallHierarchyPrepare, _, _>(|params, _| async move {
+            let line = params.text_document_position_params.position.line;
+            Ok(Some(if line == 8 {
+                vec![item("c", 8)]
+            } else {
+                Vec::new()
+            }))
+        });
+        fake.handle_request::<CallHierarchyIncomingCalls, _, _>(|params, _| async move {
+            Ok(Some(callers(&params.item.name)))
+        });
+        fake.handle_request::<CallHierarchyOutgoingCalls, _, _>(|params, _| async move {
+            let callees = match params.item.name.as_str() {
+                "a" => vec![item("b", 4), item("c", 8)],
+                "b" => vec![item("c", 8)],
+                _ => Vec::new(),
+            };
+            Ok(Some(
+                callees
+                    .into_iter()
+                    .map(|to| CallHierarchyOutgoingCall {
+                        to,
+                        from_ranges: Vec::new(),
+                    })
+                    .collect(),
+            ))
+        });
+
+        let provider = CallHierarchyProvider::new(server).unwrap();
+        let roots = provider
+            .prepare(TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(Url::parse("file:///src/main.rs").unwrap()),
+                Position::new(8, 3),
+            ))
+            .await
+            .unwrap();
+        let mut tree = CallTree::new(CallHierarchyDirection::Incoming, roots);
+        assert_eq!(names(&tree), ["c"]);
+
+        let expand = |tree: &mut CallTree, name: &str| {
+            let id = *tree
+                .visible_nodes()
+                .iter()
+                .find(|id| tree.node(**id).call.item.name == name)
+                .unwrap();
+            tree.expand(id).then(|| {
+                let calls = provider.calls(tree.direction(), tree.node(id).call.item.clone());
+                (id, calls)
+            })
+        };
+
+        let (c, calls) = expand(&mut tree, "c").unwrap();
+        let calls = calls.await.unwrap();
+        assert_eq!(calls[1].ranges[0].start, Position::new(5, 4));
+        tree.set_calls(c, calls);
+        assert_eq!(names(&tree), ["c", "  a", "  b"]);
+
+        // Walk further up the chain, through `b` to its caller.
+        let (b, calls) = expand(&mut tree, "b").unwrap();
+        tree.set_calls(b, calls.await.unwrap());
+        assert_eq!(names(&tree), ["c", "  a", "  b", "    a"]);
+
+        // Collapsed nodes keep their calls, so expanding them again doesn't fetch them.
+        tree.collapse(c);
+        assert_eq!(names(&tree), ["c"]);
+        assert!(expand(&mut tree, "c").is_none());
+        assert_eq!(names(&tree), ["c", "  a", "  b", "    a"]);
+
+        // Functions without callers are leaves once their calls have been fetched.
+        let (a, calls) = expand(&mut tree, "a").unwrap();
+        tree.set_calls(a, calls.await.unwrap());
+        assert_eq!(tree.node(a).children(), Some(&[][..]));
+
+        let mut tree = CallTree::new(CallHierarchyDirection::Outgoing, vec![item("a", 0)]);
+        let (a, calls) = expand(&mut tree, "a").unwrap();
+        tree.set_calls(a, calls.await.unwrap());
+        let (b, calls) = expand(&mut tree, "b").unwrap();
+        tree.set_calls(b, calls.await.unwrap());
+        assert_eq!(names(&tree), ["a", "  b", "    c", "  c"]);
+    }
+}
@@ -0,0 +1,230 @@
+use std::future::Future;
+
+use anyhow::Result;
+use collections::HashSet;
+use futures::{future::join_all, stream::FuturesUnordered, StreamExt as _};
+use lsp_types::CompletionItem;
+
+use crate::LanguageServerId;
+
+/// The language servers attached to a single buffer, such as ESLint alongside the TypeScript
+/// server, with requests sent to all of them and their responses merged into one.
+///
+/// Only the servers' ids are kept, so each request is given a way to send it to a server by
+/// id. Servers it isn't sent to, like those that don't advertise support for it, are skipped.
+/// Responses are merged in the order the servers were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MultiServerFanout {
+    server_ids: Vec<LanguageServerId>,
+}
+
+impl MultiServerFanout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attaches a server, returning whether it wasn't already attached.
+    pub fn add(&mut self, server_id: LanguageServerId) -> bool {
+        if self.server_ids.contains(&server_id) {
+            return false;
+        }
+        self.server_ids.push(server_id);
+        true
+    }
+
+    /// Detaches a server, returning whether it was attached.
+    pub fn remove(&mut self, server_id: LanguageServerId) -> bool {
+        let len = self.server_ids.len();
+        self.server_ids.retain(|id| *id != server_id);
+        self.server_ids.len() != len
+    }
+
+    /// The attached servers, in the order they were added.
+    pub fn server_ids(&self) -> &[LanguageServerId] {
+        &self.server_ids
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.server_ids.is_empty()
+    }
+
+    /// Sends a request to every server that `request` is sent to, resolving to their responses
+    /// in the order the servers were added, so that they can be concatenated. Servers that fail
+    /// are logged and left out, unless they all fail.
+    pub fn request_all<R, F>(
+        &self,
+        mut request: impl FnMut(LanguageServerId) -> Option<F>,
+    ) -> impl 'static + Future<Output = Result<Vec<R>>>
+    where
+        F: 'static + Future<Output = Result<R>>,
+    {
+        let requests = self
+            .server_ids
+            .iter()
+            .filter_map(|server_id| {
+                let server_id = *server_id;
+                let response = request(server_id)?;
+                Some(async move { (server_id, response.await) })
+            })
+            .collect::<Vec<_>>();
+        async move {
+            let mut responses = Vec::new();
+            let mut first_error = None;
+            for (server_id, response) in join_all(requests).await {
+                match response {
+                    Ok(response) => responses.push(response),
+                    Err(error) => {
+                        log::error!("request to language server {server_id} failed: {error:#}");
+                        first_error.get_or_insert(error);
+                    }
+                }
+            }
+            match first_error {
+                Some(error) if responses.is_empty() => Err(error),
+                _ => Ok(responses),
+            }
+        }
+    }
+
+    /// Sends a request to every server that `request` is sent to, resolving to the first
+    /// response that isn't empty. Servers that fail are logged and skipped, unless they all
+    /// fail.
+    pub fn request_first<R, F>(
+        &self,
+        mut request: impl FnMut(LanguageServerId) -> Option<F>,
+    ) -> impl 'static + Future<Output = Result<Option<R>>>
+    where
+        F: 'static + Future<Output = Result<Option<R>>>,
+    {
+        let mut requests = self
+            .server_ids
+            .iter()
+            .filter_map(|server_id| {
+                let server_id = *server_id;
+                let response = request(server_id)?;
+                Some(async move { (server_id, response.await) })
+            })
+            .collect::<FuturesUnordered<_>>();
+        async move {
+            let mut first_error = None;
+            let mut any_succeeded = false;
+            while let Some((server_id, response)) = requests.next().await {
+                match response {
+                    Ok(Some(response)) => return Ok(Some(response)),
+                    Ok(None) => any_succeeded = true,
+                    Err(error) => {
+                        log::error!("request to language server {server_id} failed: {error:#}");
+                        first_error.get_or_insert(error);
+                    }
+                }
+            }
+            match first_error {
+                Some(error) if !any_succeeded => Err(error),
+                _ => Ok(None),
+            }
+        }
+    }
+}
+
+/// Drops the completions whose text was already offered by an earlier one, such as by another
+/// server.
+pub fn dedup_completions<T>(
+    completions: impl IntoIterator<Item = T>,
+    lsp_completion: impl Fn(&T) -> &CompletionItem,
+) -> Vec<T> {
+    let mut insert_texts = HashSet::default();
+    completions
+        .into_iter()
+        .filter(|completion| {
+            insert_texts.insert(completion_insert_text(lsp_completion(completion)).to_string())
+        })
+        .collect()
+}
+
+/// The text a completion inserts, which is its label unless it says otherwise.
+pub fn completion_insert_text(item: &CompletionItem) -> &str {
+    item.insert_text.as_deref().unwrap_or(&item.label)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use futures::{executor::block_on, future};
+
+    use super::*;
+
+    fn completion(label: &str, insert_text: Option<&str>) -> CompletionItem {
+        CompletionItem {
+            label: label.to_string(),
+            insert_text: insert_text.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_dedup_completions() {
+        let completions = dedup_completions(
+            [
+                completion("foo", None),
+                completion("bar()", Some("bar($0)")),
+                // Inserts the same text as the first `foo`.
+                completion("foo: Foo", Some("foo")),
+                completion("bar", Some("bar")),
+            ],
+            |completion| completion,
+        );
+        assert_eq!(
+            completions
+                .iter()
+                .map(|item| item.label.as_str())
+                .collect::<Vec<_>>(),
+            ["foo", "bar()", "bar"]
+        );
+    }
+
+    #[test]
+    fn test_request_all_and_first() {
+        let mut fanout = MultiServerFanout::new();
+        for id in [2, 0, 1, 3] {
+            fanout.add(LanguageServerId(id));
+        }
+        let respond = |server_id: LanguageServerId| match server_id.0 {
+            0 => Some(future::ready(Ok(None))),
+            1 => Some(future::ready(Err(anyhow!("server 1 failed")))),
+            2 => Some(future::ready(Ok(Some(server_id.0)))),
+            _ => None,
+        };
+
+        // Responses keep the order the servers were added in, and failures are left out.
+        assert_eq!(
+            block_on(fanout.request_all(respond)).unwrap(),
+            [Some(2), None]
+        );
+        assert_eq!(block_on(fanout.request_first(respond)).unwrap(), Some(2));
+
+        // Empty responses are skipped, and the request only fails when every server does.
+        fanout.remove(LanguageServerId(2));
+        assert_eq!(block_on(fanout.request_first(respond)).unwrap(), None);
+        fanout.remove(LanguageServerId(0));
+        assert!(block_on(fanout.request_all(respond)).is_err());
+        assert!(block_on(fanout.request_first(respond)).is_err());
+    }
+
+    #[test]
+    fn test_add_and_remove_servers() {
+        let mut fanout = MultiServerFanout::new();
+        assert!(fanout.add(LanguageServerId(1)));
+        assert!(fanout.add(LanguageServerId(0)));
+        assert!(!fanout.add(LanguageServerId(1)));
+        assert_eq!(
+            fanout.server_ids(),
+            [LanguageServerId(1), LanguageServerId(0)]
+        );
+
+        assert!(fanout.remove(LanguageServerId(1)));
+        assert!(!fanout.remove(LanguageServerId(1)));
+        assert_eq!(fanout.server_ids(), [LanguageServerId(0)]);
+        assert!(fanout.remove(LanguageServerId(0)));
+        assert!(fanout.is_empty());
+    }
+}
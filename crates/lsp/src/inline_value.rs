@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures::{future::BoxFuture, FutureExt as _};
+use lsp_types::{
+    request::InlineValueRequest, InlineValue, InlineValueContext, InlineValueParams, OneOf, Range,
+    TextDocumentIdentifier,
+};
+
+use crate::LanguageServer;
+
+/// Fetches the values a debugger should show inline next to the code around where a debug
+/// session stopped, with `textDocument/inlineValue`.
+#[derive(Clone)]
+pub struct InlineValueProvider {
+    server: Arc<LanguageServer>,
+}
+
+impl InlineValueProvider {
+    /// Returns a provider for the given server, unless it doesn't support inline values.
+    pub fn new(server: Arc<LanguageServer>) -> Option<Self> {
+        let supported = match server.capabilities().inline_value_provider {
+            Some(OneOf::Left(enabled)) => enabled,
+            Some(OneOf::Right(_)) => true,
+            None => false,
+        };
+        supported.then_some(Self { server })
+    }
+
+    pub fn server(&self) -> &Arc<LanguageServer> {
+        &self.server
+    }
+
+    /// Returns the inline values in `range` of a document, for a debug session stopped in the
+    /// frame `frame_id` at `stopped_location`.
+    pub fn inline_values(
+        &self,
+        document: TextDocumentIdentifier,
+        range: Range,
+        frame_id: i32,
+        stopped_location: Range,
+    ) -> BoxFuture<'static, Result<Vec<InlineValue>>> {
+        let request = self
+            .server
+            .request::<InlineValueRequest>(InlineValueParams {
+                work_done_progress_params: Default::default(),
+                text_document: document,
+                range,
+                context: InlineValueContext {
+                    frame_id,
+                    stopped_location,
+                },
+            });
+        async move { Ok(request.await?.unwrap_or_default()) }.boxed()
+    }
+}
+
+/// The range of the document an inline value is shown for.
+pub fn inline_value_range(value: &InlineValue) -> Range {
+    match value {
+        InlineValue::Text(text) => text.range,
+        InlineValue::VariableLookup(lookup) => lookup.range,
+        InlineValue::EvaluatableExpression(expression) => expression.range,
+    }
+}
@@ -1,11 +1,22 @@
+mod call_hierarchy;
+mod fanout;
+mod inline_value;
 mod input_handler;
+mod semantic_tokens;
 
+pub use call_hierarchy::*;
+pub use fanout::*;
+pub use inline_value::*;
 pub use lsp_types::request::*;
 pub use lsp_types::*;
+pub use semantic_tokens::*;
 
 use anyhow::{anyhow, Context, Result};
 use collections::HashMap;
-use futures::{channel::oneshot, io::BufWriter, select, AsyncRead, AsyncWrite, Future, FutureExt};
+use futures::{
+    channel::oneshot, future::Shared, io::BufWriter, select, AsyncRead, AsyncWrite, Future,
+    FutureExt,
+};
 use gpui::{AppContext, AsyncAppContext, BackgroundExecutor, Task};
 use parking_lot::Mutex;
 use postage::{barrier, prelude::Stream};
@@ -26,6 +37,7 @@ use std::{
     io::Write,
     path::PathBuf,
     pin::Pin,
+    process::ExitStatus,
     sync::{
         atomic::{AtomicI32, Ordering::SeqCst},
         Arc, Weak,
@@ -41,6 +53,7 @@ const CONTENT_LEN_HEADER: &str = "Content-Length: ";
 
 const LSP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
 const SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const EXIT_STATUS_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 type NotificationHandler = Box<dyn Send + FnMut(Option<RequestId>, Value, AsyncAppContext)>;
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
@@ -63,6 +76,35 @@ pub struct LanguageServerBinary {
     pub env: Option<HashMap<String, String>>,
 }
 
+/// When to restart a language server that crashed.
+///
+/// A server is restarted after a crash as long as it has crashed fewer than `max_restarts`
+/// times since it was last restarted by hand, and its previous crash was more than `cooldown`
+/// ago. Servers that keep crashing right after starting are left stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LspRestartPolicy {
+    pub max_restarts: u8,
+    pub cooldown: Duration,
+}
+
+impl Default for LspRestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            cooldown: Duration::from_secs(10),
+        }
+    }
+}
+
+impl LspRestartPolicy {
+    /// Whether a server that has already crashed `crash_count` times should be restarted,
+    /// given how long ago its previous crash was.
+    pub fn should_restart(&self, crash_count: u8, since_last_crash: Option<Duration>) -> bool {
+        crash_count < self.max_restarts
+            && since_last_crash.map_or(true, |since_last_crash| since_last_crash > self.cooldown)
+    }
+}
+
 /// A running language server process.
 pub struct LanguageServer {
     server_id: LanguageServerId,
@@ -74,6 +116,7 @@ pub struct LanguageServer {
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     io_handlers: Arc<Mutex<HashMap<i32, IoHandler>>>,
+    semantic_tokens: Arc<Mutex<SemanticTokenStore>>,
     executor: BackgroundExecutor,
     #[allow(clippy::type_complexity)]
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
@@ -81,6 +124,7 @@ pub struct LanguageServer {
     root_path: PathBuf,
     working_dir: PathBuf,
     server: Arc<Mutex<Option<Child>>>,
+    exit_status: Shared<oneshot::Receiver<ExitStatus>>,
 }
 
 /// Identifies a running language server.
@@ -335,6 +379,8 @@ impl LanguageServer {
         let response_handlers =
             Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
         let io_handlers = Arc::new(Mutex::new(HashMap::default()));
+        let server = Arc::new(Mutex::new(server));
+        let (exit_status_tx, exit_status_rx) = oneshot::channel();
 
         let stdout_input_task = cx.spawn({
             let on_unhandled_notification = on_unhandled_notification.clone();
@@ -360,9 +406,17 @@ impl LanguageServer {
                 cx.spawn(|_| Self::handle_stderr(stderr, io_handlers, stderr_captures).log_err())
             })
             .unwrap_or_else(|| Task::Ready(Some(None)));
-        let input_task = cx.spawn(|_| async move {
-            let (stdout, stderr) = futures::join!(stdout_input_task, stderr_input_task);
-            stdout.or(stderr)
+        let input_task = cx.spawn({
+            let server = server.clone();
+            |cx| async move {
+                let (stdout, stderr) = futures::join!(stdout_input_task, stderr_input_task);
+                // The server's output ends when it exits, unless it was shut down first, in
+                // which case this task has been dropped.
+                if let Some(status) = Self::wait_for_exit(&server, cx.background_executor()).await {
+                    exit_status_tx.send(status).ok();
+                }
+                stdout.or(stderr)
+            }
         });
         let output_task = cx.background_executor().spawn({
             Self::handle_output(
@@ -380,6 +434,7 @@ impl LanguageServer {
             notification_handlers,
             response_handlers,
             io_handlers,
+            semantic_tokens: Default::default(),
             name: "".into(),
             capabilities: Default::default(),
             code_action_kinds,
@@ -390,10 +445,26 @@ impl LanguageServer {
             output_done_rx: Mutex::new(Some(output_done_rx)),
             root_path: root_path.to_path_buf(),
             working_dir: working_dir.to_path_buf(),
-            server: Arc::new(Mutex::new(server)),
+            server,
+            exit_status: exit_status_rx.shared(),
         }
     }
 
+    /// Polls the server process until it exits, giving up after [`SERVER_SHUTDOWN_TIMEOUT`].
+    async fn wait_for_exit(
+        server: &Mutex<Option<Child>>,
+        executor: &BackgroundExecutor,
+    ) -> Option<ExitStatus> {
+        let attempts = SERVER_SHUTDOWN_TIMEOUT.as_millis() / EXIT_STATUS_POLL_INTERVAL.as_millis();
+        for _ in 0..attempts {
+            if let Some(status) = server.lock().as_mut()?.try_status().log_err()? {
+                return Some(status);
+            }
+            executor.timer(EXIT_STATUS_POLL_INTERVAL).await;
+        }
+        None
+    }
+
     /// List of code action kinds this language server reports being able to emit.
     pub fn code_action_kinds(&self) -> Option<Vec<CodeActionKind>> {
         self.code_action_kinds.clone()
@@ -658,6 +729,57 @@ impl LanguageServer {
                         }),
                         ..SignatureHelpClientCapabilities::default()
                     }),
+                    semantic_tokens: Some(SemanticTokensClientCapabilities {
+                        requests: SemanticTokensClientCapabilitiesRequests {
+                            range: None,
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: vec![
+                            SemanticTokenType::NAMESPACE,
+                            SemanticTokenType::TYPE,
+                            SemanticTokenType::CLASS,
+                            SemanticTokenType::ENUM,
+                            SemanticTokenType::INTERFACE,
+                            SemanticTokenType::STRUCT,
+                            SemanticTokenType::TYPE_PARAMETER,
+                            SemanticTokenType::PARAMETER,
+                            SemanticTokenType::VARIABLE,
+                            SemanticTokenType::PROPERTY,
+                            SemanticTokenType::ENUM_MEMBER,
+                            SemanticTokenType::EVENT,
+                            SemanticTokenType::FUNCTION,
+                            SemanticTokenType::METHOD,
+                            SemanticTokenType::MACRO,
+                            SemanticTokenType::KEYWORD,
+                            SemanticTokenType::MODIFIER,
+                            SemanticTokenType::COMMENT,
+                            SemanticTokenType::STRING,
+                            SemanticTokenType::NUMBER,
+                            SemanticTokenType::REGEXP,
+                            SemanticTokenType::OPERATOR,
+                            SemanticTokenType::DECORATOR,
+                        ],
+                        token_modifiers: vec![
+                            SemanticTokenModifier::DECLARATION,
+                            SemanticTokenModifier::DEFINITION,
+                            SemanticTokenModifier::READONLY,
+                            SemanticTokenModifier::STATIC,
+                            SemanticTokenModifier::DEPRECATED,
+                            SemanticTokenModifier::ABSTRACT,
+                            SemanticTokenModifier::ASYNC,
+                            SemanticTokenModifier::MODIFICATION,
+                            SemanticTokenModifier::DOCUMENTATION,
+                            SemanticTokenModifier::DEFAULT_LIBRARY,
+                        ],
+                        formats: vec![TokenFormat::RELATIVE],
+                        ..Default::default()
+                    }),
+                    call_hierarchy: Some(CallHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    inline_value: Some(InlineValueClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
                     ..TextDocumentClientCapabilities::default()
                 }),
                 experimental: Some(json!({
@@ -913,6 +1035,113 @@ impl LanguageServer {
         self.server_id
     }
 
+    /// Resolves with the exit status of the language server process if it exits on its own.
+    ///
+    /// Resolves with `None` if the server is shut down instead, or doesn't run as a process.
+    pub fn exit_status(&self) -> impl 'static + Send + Future<Output = Option<ExitStatus>> {
+        let exit_status = self.exit_status.clone();
+        async move { exit_status.await.ok() }
+    }
+
+    /// Get the semantic tokens last received for each open document.
+    pub fn semantic_tokens_cache(&self) -> Arc<Mutex<SemanticTokenStore>> {
+        self.semantic_tokens.clone()
+    }
+
+    /// Requests the semantic tokens of a document at the given version, unless they were
+    /// already received for that version.
+    ///
+    /// If the server supports `semanticTokens/full/delta` and tokens were received for an
+    /// earlier version, only the changes to those are requested.
+    pub fn semantic_tokens(
+        &self,
+        uri: Url,
+        version: i32,
+    ) -> impl 'static + Send + Future<Output = Result<Vec<SemanticToken>>> {
+        let store = self.semantic_tokens.clone();
+        let (cached, previous) = {
+            let store = store.lock();
+            let cached = store.get(&uri, version).map(|cache| cache.tokens.clone());
+            let previous = store
+                .previous(&uri)
+                .filter(|_| self.supports_semantic_tokens_delta())
+                .cloned();
+            (cached, previous)
+        };
+
+        let text_document = TextDocumentIdentifier::new(uri.clone());
+        let request = match (cached, previous) {
+            (Some(tokens), _) => return async move { Ok(tokens) }.boxed(),
+            (None, Some(mut previous)) => {
+                let request = self.request::<request::SemanticTokensFullDeltaRequest>(
+                    SemanticTokensDeltaParams {
+                        text_document,
+                        previous_result_id: previous.result_id.take().unwrap_or_default(),
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                );
+                async move {
+                    anyhow::Ok(match request.await? {
+                        Some(SemanticTokensFullDeltaResult::Tokens(tokens)) => tokens.into(),
+                        Some(SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                            previous.apply_edits(delta.edits);
+                            previous.result_id = delta.result_id;
+                            previous
+                        }
+                        Some(SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+                            previous.apply_edits(edits);
+                            previous
+                        }
+                        None => SemanticTokenCache::default(),
+                    })
+                }
+                .boxed()
+            }
+            (None, None) => {
+                let request =
+                    self.request::<request::SemanticTokensFullRequest>(SemanticTokensParams {
+                        text_document,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    });
+                async move {
+                    anyhow::Ok(match request.await? {
+                        Some(SemanticTokensResult::Tokens(tokens)) => tokens.into(),
+                        Some(SemanticTokensResult::Partial(partial)) => SemanticTokenCache {
+                            tokens: partial.data,
+                            result_id: None,
+                        },
+                        None => SemanticTokenCache::default(),
+                    })
+                }
+                .boxed()
+            }
+        };
+
+        async move {
+            let cache: SemanticTokenCache = request.await?;
+            let tokens = cache.tokens.clone();
+            store.lock().insert(uri, version, cache);
+            Ok(tokens)
+        }
+        .boxed()
+    }
+
+    fn supports_semantic_tokens_delta(&self) -> bool {
+        let options = match self.capabilities.semantic_tokens_provider.as_ref() {
+            Some(SemanticTokensServerCapabilities::SemanticTokensOptions(options)) => options,
+            Some(SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options)) => {
+                &options.semantic_tokens_options
+            }
+            None => return false,
+        };
+        matches!(
+            options.full,
+            Some(SemanticTokensFullOptions::Delta { delta: Some(true) })
+        )
+    }
+
     /// Get the root path of the project the language server is running against.
     pub fn root_path(&self) -> &PathBuf {
         &self.root_path
@@ -1110,6 +1339,7 @@ pub struct FakeLanguageServer {
     pub binary: LanguageServerBinary,
     pub server: Arc<LanguageServer>,
     notifications_rx: channel::Receiver<(String, String)>,
+    exit_status_tx: Arc<Mutex<Option<oneshot::Sender<ExitStatus>>>>,
 }
 
 #[cfg(any(test, feature = "test-support"))]
@@ -1140,6 +1370,9 @@ impl FakeLanguageServer {
             |_| {},
         );
         server.name = name.as_str().into();
+        // There's no process to exit, so the fake decides when the server exits.
+        let (exit_status_tx, exit_status_rx) = oneshot::channel();
+        server.exit_status = exit_status_rx.shared();
         let fake = FakeLanguageServer {
             binary,
             server: Arc::new({
@@ -1167,6 +1400,7 @@ impl FakeLanguageServer {
                 server
             }),
             notifications_rx,
+            exit_status_tx: Arc::new(Mutex::new(Some(exit_status_tx))),
         };
         fake.handle_request::<request::Initialize, _, _>({
             let capabilities = capabilities;
@@ -1222,6 +1456,17 @@ impl FakeLanguageServer {
         self.server.request::<T>(params).await
     }
 
+    /// Makes the server look like its process exited with a non-zero status.
+    pub fn simulate_crash(&self) {
+        #[cfg(unix)]
+        let status = std::os::unix::process::ExitStatusExt::from_raw(1 << 8);
+        #[cfg(windows)]
+        let status = std::os::windows::process::ExitStatusExt::from_raw(1);
+        if let Some(exit_status_tx) = self.exit_status_tx.lock().take() {
+            exit_status_tx.send(status).ok();
+        }
+    }
+
     /// Attempts [`Self::try_receive_notification`], unwrapping if it has not received the specified type yet.
     pub async fn receive_notification<T: notification::Notification>(&mut self) -> T::Params {
         self.server.executor.start_waiting();
@@ -1417,6 +1662,115 @@ mod tests {
         fake.receive_notification::<notification::Exit>().await;
     }
 
+    #[gpui::test]
+    async fn test_semantic_tokens_delta(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            release_channel::init(SemanticVersion::default(), cx);
+        });
+        let (server, fake) = FakeLanguageServer::new(
+            LanguageServerId(0),
+            LanguageServerBinary {
+                path: "path/to/language-server".into(),
+                arguments: vec![],
+                env: None,
+            },
+            "the-lsp".to_string(),
+            ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            cx.to_async(),
+        );
+        let server = cx.update(|cx| server.initialize(None, cx)).await.unwrap();
+
+        let token = |delta_line, length| SemanticToken {
+            delta_line,
+            delta_start: 0,
+            length,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        };
+        let full_requests = Arc::new(AtomicI32::new(0));
+        fake.handle_request::<request::SemanticTokensFullRequest, _, _>({
+            let full_requests = full_requests.clone();
+            move |_, _| {
+                let result_id = full_requests.fetch_add(1, SeqCst) + 1;
+                async move {
+                    Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+                        result_id: Some(result_id.to_string()),
+                        data: vec![token(0, 3), token(1, 4)],
+                    })))
+                }
+            }
+        });
+        let delta_requests = Arc::new(Mutex::new(Vec::new()));
+        fake.handle_request::<request::SemanticTokensFullDeltaRequest, _, _>({
+            let delta_requests = delta_requests.clone();
+            move |params, _| {
+                delta_requests.lock().push(params.previous_result_id);
+                async move {
+                    Ok(Some(SemanticTokensFullDeltaResult::TokensDelta(
+                        SemanticTokensDelta {
+                            result_id: Some("delta".to_string()),
+                            edits: vec![SemanticTokensEdit {
+                                start: 5,
+                                delete_count: 5,
+                                data: Some(vec![token(2, 7)]),
+                            }],
+                        },
+                    )))
+                }
+            }
+        });
+
+        let uri = Url::from_str("file://a/b").unwrap();
+        let tokens = server.semantic_tokens(uri.clone(), 0).await.unwrap();
+        assert_eq!(tokens, [token(0, 3), token(1, 4)]);
+        assert_eq!(full_requests.load(SeqCst), 1);
+
+        // Tokens for the same version are reused.
+        let tokens = server.semantic_tokens(uri.clone(), 0).await.unwrap();
+        assert_eq!(tokens, [token(0, 3), token(1, 4)]);
+        assert_eq!(full_requests.load(SeqCst), 1);
+        assert!(delta_requests.lock().is_empty());
+
+        // After an edit, only the changes are requested.
+        let small_edit = TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(0, 0), Position::new(0, 0))),
+            range_length: None,
+            text: "a".to_string(),
+        };
+        server
+            .semantic_tokens_cache()
+            .lock()
+            .did_change(&uri, &[small_edit]);
+        let tokens = server.semantic_tokens(uri.clone(), 1).await.unwrap();
+        assert_eq!(tokens, [token(0, 3), token(2, 7)]);
+        assert_eq!(delta_requests.lock().as_slice(), ["1"]);
+        assert_eq!(full_requests.load(SeqCst), 1);
+
+        // Once the document has been edited too much, all the tokens are requested again.
+        let large_edit = TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(0, 0), Position::new(0, 0))),
+            range_length: None,
+            text: "a".repeat(SemanticTokenStore::DEFAULT_EDIT_THRESHOLD + 1),
+        };
+        server
+            .semantic_tokens_cache()
+            .lock()
+            .did_change(&uri, &[large_edit]);
+        server.semantic_tokens(uri.clone(), 2).await.unwrap();
+        assert_eq!(full_requests.load(SeqCst), 2);
+        assert_eq!(delta_requests.lock().len(), 1);
+    }
+
     #[gpui::test]
     fn test_deserialize_string_digit_id() {
         let json = r#"{"jsonrpc":"2.0","id":"2","method":"workspace/configuration","params":{"items":[{"scopeUri":"file:///Users/mph/Devel/personal/hello-scala/","section":"metals"}]}}"#;
@@ -1466,4 +1820,24 @@ mod tests {
             "{\"jsonrpc\":\"\",\"id\":0,\"error\":null}"
         );
     }
+
+    #[test]
+    fn test_restart_policy() {
+        let policy = LspRestartPolicy {
+            max_restarts: 2,
+            cooldown: Duration::from_secs(10),
+        };
+        assert!(policy.should_restart(0, None));
+        assert!(policy.should_restart(1, Some(Duration::from_secs(60))));
+        // The server crashed again too soon after its last crash.
+        assert!(!policy.should_restart(1, Some(Duration::from_secs(5))));
+        // The server has been restarted as many times as it can be.
+        assert!(!policy.should_restart(2, Some(Duration::from_secs(60))));
+
+        let never = LspRestartPolicy {
+            max_restarts: 0,
+            ..policy
+        };
+        assert!(!never.should_restart(0, None));
+    }
 }
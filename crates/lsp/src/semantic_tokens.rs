@@ -0,0 +1,283 @@
+use std::cmp::Reverse;
+
+use collections::HashMap;
+use lsp_types::{
+    SemanticToken, SemanticTokens, SemanticTokensEdit, TextDocumentContentChangeEvent, Url,
+};
+
+/// How many integers each token is encoded as in the protocol.
+const TOKEN_LEN: usize = 5;
+
+/// The semantic tokens a language server last sent for a document, along with the id it gave
+/// them so that only the changes need to be requested next time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SemanticTokenCache {
+    pub tokens: Vec<SemanticToken>,
+    pub result_id: Option<String>,
+}
+
+impl From<SemanticTokens> for SemanticTokenCache {
+    fn from(tokens: SemanticTokens) -> Self {
+        Self {
+            tokens: tokens.data,
+            result_id: tokens.result_id,
+        }
+    }
+}
+
+impl SemanticTokenCache {
+    /// Applies the edits of a `semanticTokens/full/delta` response to the tokens.
+    ///
+    /// As in the protocol, edits index into the tokens' encoding as five integers each, and
+    /// are all relative to the tokens before any of them were applied.
+    pub fn apply_edits(&mut self, mut edits: Vec<SemanticTokensEdit>) {
+        let mut data = self
+            .tokens
+            .iter()
+            .flat_map(encode_token)
+            .collect::<Vec<_>>();
+        // Apply the last edit first, so the offsets of the others stay valid.
+        edits.sort_by_key(|edit| Reverse(edit.start));
+        for edit in edits {
+            let start = (edit.start as usize).min(data.len());
+            let end = (start + edit.delete_count as usize).min(data.len());
+            let inserted = edit.data.unwrap_or_default();
+            data.splice(start..end, inserted.iter().flat_map(encode_token));
+        }
+        self.tokens = data.chunks_exact(TOKEN_LEN).map(decode_token).collect();
+    }
+}
+
+fn encode_token(token: &SemanticToken) -> [u32; TOKEN_LEN] {
+    [
+        token.delta_line,
+        token.delta_start,
+        token.length,
+        token.token_type,
+        token.token_modifiers_bitset,
+    ]
+}
+
+fn decode_token(data: &[u32]) -> SemanticToken {
+    SemanticToken {
+        delta_line: data[0],
+        delta_start: data[1],
+        length: data[2],
+        token_type: data[3],
+        token_modifiers_bitset: data[4],
+    }
+}
+
+struct CachedDocument {
+    version: i32,
+    /// Roughly how many characters were edited since the tokens were received.
+    edit_distance: usize,
+    cache: SemanticTokenCache,
+}
+
+/// The [`SemanticTokenCache`] of each open document, keyed by the document's version.
+///
+/// Once a document has been edited by more than the edit threshold since its tokens were
+/// received, they are no longer used as the base of a delta request.
+pub struct SemanticTokenStore {
+    documents: HashMap<Url, CachedDocument>,
+    edit_threshold: usize,
+}
+
+impl Default for SemanticTokenStore {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_EDIT_THRESHOLD)
+    }
+}
+
+impl SemanticTokenStore {
+    /// The number of characters that can be edited before cached tokens are dropped, unless
+    /// set with [`SemanticTokenStore::new`].
+    pub const DEFAULT_EDIT_THRESHOLD: usize = 2048;
+
+    pub fn new(edit_threshold: usize) -> Self {
+        Self {
+            documents: HashMap::default(),
+            edit_threshold,
+        }
+    }
+
+    /// The number of characters that can be edited before cached tokens are dropped.
+    pub fn edit_threshold(&self) -> usize {
+        self.edit_threshold
+    }
+
+    pub fn set_edit_threshold(&mut self, edit_threshold: usize) {
+        self.edit_threshold = edit_threshold;
+        self.documents
+            .retain(|_, document| document.edit_distance <= edit_threshold);
+    }
+
+    /// Returns the tokens received for the given version of a document.
+    pub fn get(&self, uri: &Url, version: i32) -> Option<&SemanticTokenCache> {
+        self.documents
+            .get(uri)
+            .filter(|document| document.version == version)
+            .map(|document| &document.cache)
+    }
+
+    /// Returns the tokens last received for a document, if the server gave them an id that a
+    /// delta can be requested from.
+    pub fn previous(&self, uri: &Url) -> Option<&SemanticTokenCache> {
+        self.documents
+            .get(uri)
+            .map(|document| &document.cache)
+            .filter(|cache| cache.result_id.is_some())
+    }
+
+    /// Stores the tokens received for the given version of a document, unless tokens for a
+    /// later version have already been stored.
+    pub fn insert(&mut self, uri: Url, version: i32, cache: SemanticTokenCache) {
+        if self
+            .documents
+            .get(&uri)
+            .map_or(false, |document| document.version > version)
+        {
+            return;
+        }
+        self.documents.insert(
+            uri,
+            CachedDocument {
+                version,
+                edit_distance: 0,
+                cache,
+            },
+        );
+    }
+
+    /// Records edits sent to the server in a `textDocument/didChange` notification, dropping
+    /// the document's tokens once it has been edited by more than the threshold.
+    pub fn did_change(&mut self, uri: &Url, changes: &[TextDocumentContentChangeEvent]) {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return;
+        };
+        for change in changes {
+            document.edit_distance = document
+                .edit_distance
+                .saturating_add(change_edit_distance(change));
+        }
+        if document.edit_distance > self.edit_threshold {
+            self.documents.remove(uri);
+        }
+    }
+
+    /// Drops the tokens of a document, such as when it is closed.
+    pub fn remove(&mut self, uri: &Url) {
+        self.documents.remove(uri);
+    }
+}
+
+/// Roughly how many characters a change inserts and deletes. Deleted lines count as one
+/// character each, since their length isn't known, and replacing the whole document counts
+/// as an edit of any size.
+fn change_edit_distance(change: &TextDocumentContentChangeEvent) -> usize {
+    let Some(range) = change.range else {
+        return usize::MAX;
+    };
+    let deleted = if range.start.line == range.end.line {
+        range.end.character.saturating_sub(range.start.character)
+    } else {
+        range.end.line - range.start.line + range.end.character
+    };
+    change.text.chars().count() + deleted as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range};
+
+    use super::*;
+
+    fn token(delta_line: u32, delta_start: u32, length: u32) -> SemanticToken {
+        SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let mut cache = SemanticTokenCache {
+            tokens: vec![token(0, 0, 3), token(1, 4, 5), token(2, 0, 1)],
+            result_id: Some("1".into()),
+        };
+        cache.apply_edits(vec![
+            SemanticTokensEdit {
+                start: 5,
+                delete_count: 5,
+                data: Some(vec![token(1, 2, 6)]),
+            },
+            SemanticTokensEdit {
+                start: 15,
+                delete_count: 0,
+                data: Some(vec![token(1, 0, 4)]),
+            },
+            SemanticTokensEdit {
+                start: 0,
+                delete_count: 5,
+                data: None,
+            },
+        ]);
+        assert_eq!(
+            cache.tokens,
+            [token(1, 2, 6), token(2, 0, 1), token(1, 0, 4)]
+        );
+    }
+
+    #[test]
+    fn test_store_drops_heavily_edited_documents() {
+        let uri = Url::parse("file:///a.rs").unwrap();
+        let edit = |text: &str| TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(0, 0), Position::new(0, 2))),
+            range_length: None,
+            text: text.to_string(),
+        };
+
+        let mut store = SemanticTokenStore::new(10);
+        store.insert(
+            uri.clone(),
+            1,
+            SemanticTokenCache {
+                tokens: vec![token(0, 0, 3)],
+                result_id: Some("1".into()),
+            },
+        );
+        assert!(store.get(&uri, 1).is_some());
+        assert!(store.get(&uri, 2).is_none());
+
+        // Tokens for an earlier version don't replace later ones.
+        store.insert(uri.clone(), 0, SemanticTokenCache::default());
+        assert!(store.get(&uri, 1).is_some());
+
+        store.did_change(&uri, &[edit("abc")]);
+        assert_eq!(
+            store.previous(&uri).unwrap().result_id.as_deref(),
+            Some("1")
+        );
+        store.did_change(&uri, &[edit("abcdef")]);
+        assert!(store.previous(&uri).is_none());
+        assert!(store.get(&uri, 1).is_none());
+
+        store.insert(uri.clone(), 3, SemanticTokenCache::default());
+        assert!(store.get(&uri, 3).is_some());
+        // Tokens without an id can't be the base of a delta.
+        assert!(store.previous(&uri).is_none());
+        store.did_change(
+            &uri,
+            &[TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: String::new(),
+            }],
+        );
+        assert!(store.get(&uri, 3).is_none());
+    }
+}
@@ -102,6 +102,9 @@ pub enum Event {
     Closed,
     DirtyChanged,
     DiagnosticsUpdated,
+    RemoteSelectionsChanged {
+        replica_id: ReplicaId,
+    },
 }
 
 pub type MultiBufferPoint = Point;
@@ -1637,6 +1640,11 @@ impl MultiBuffer {
                 self.capability = buffer.read(cx).capability();
                 Event::CapabilityChanged
             }
+            language::Event::RemoteSelectionsChanged { replica_id } => {
+                Event::RemoteSelectionsChanged {
+                    replica_id: *replica_id,
+                }
+            }
 
             //
             language::Event::Operation(_) => return,
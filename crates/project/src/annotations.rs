@@ -0,0 +1,610 @@
+use crate::{Event, File, Project, ProjectPath};
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, TypedEnvelope};
+use gpui::{AppContext, AsyncAppContext, Model, ModelContext, Task};
+use language::{
+    proto::{deserialize_anchor, serialize_anchor},
+    Buffer,
+};
+use std::time::SystemTime;
+use text::{Anchor, Bias, BufferId, Point};
+use util::{post_inc, ResultExt};
+
+/// Identifies an annotation thread within a project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ThreadId(pub u64);
+
+/// A single comment left on an [`AnnotationThread`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comment {
+    pub author: String,
+    pub body: String,
+    pub timestamp: SystemTime,
+}
+
+/// A review discussion attached to a position in a buffer.
+#[derive(Clone, Debug)]
+pub struct AnnotationThread {
+    pub id: ThreadId,
+    pub anchor: Anchor,
+    pub comments: Vec<Comment>,
+    pub resolved: bool,
+}
+
+/// An annotation thread of a buffer that was released, kept at the position it had in the
+/// buffer's file until that file is opened again.
+#[derive(Clone, Debug)]
+pub(crate) struct ReleasedAnnotationThread {
+    id: ThreadId,
+    position: Point,
+    comments: Vec<Comment>,
+    resolved: bool,
+}
+
+/// The annotation threads attached to a single buffer, ordered by creation.
+#[derive(Clone, Debug, Default)]
+pub struct AnnotationThreads {
+    threads: Vec<AnnotationThread>,
+}
+
+impl AnnotationThreads {
+    pub fn get(&self, id: ThreadId) -> Option<&AnnotationThread> {
+        let ix = self
+            .threads
+            .binary_search_by_key(&id, |thread| thread.id)
+            .ok()?;
+        Some(&self.threads[ix])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &AnnotationThread> {
+        self.threads.iter()
+    }
+
+    pub fn unresolved(&self) -> impl Iterator<Item = &AnnotationThread> {
+        self.threads.iter().filter(|thread| !thread.resolved)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.threads.is_empty()
+    }
+
+    fn get_mut(&mut self, id: ThreadId) -> Option<&mut AnnotationThread> {
+        let ix = self
+            .threads
+            .binary_search_by_key(&id, |thread| thread.id)
+            .ok()?;
+        Some(&mut self.threads[ix])
+    }
+
+    fn insert(&mut self, thread: AnnotationThread) {
+        match self
+            .threads
+            .binary_search_by_key(&thread.id, |thread| thread.id)
+        {
+            Ok(ix) => self.threads[ix] = thread,
+            Err(ix) => self.threads.insert(ix, thread),
+        }
+    }
+}
+
+impl Project {
+    pub fn annotation_threads(&self, buffer_id: BufferId) -> Option<&AnnotationThreads> {
+        self.annotation_threads.get(&buffer_id)
+    }
+
+    pub fn create_annotation_thread(
+        &mut self,
+        buffer: &Model<Buffer>,
+        anchor: Anchor,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ThreadId>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.is_local() {
+            let author = self.current_user_login(cx);
+            let thread = self.insert_new_annotation_thread(buffer_id, anchor, author, body, cx);
+            Task::ready(Ok(thread.id))
+        } else {
+            let request = self.client.request(proto::CreateAnnotationThread {
+                project_id: self.remote_id().unwrap(),
+                buffer_id: buffer_id.into(),
+                anchor: Some(serialize_anchor(&anchor)),
+                body,
+            });
+            cx.spawn(|this, mut cx| async move {
+                let response = request.await?;
+                this.update(&mut cx, |this, cx| {
+                    this.apply_annotation_thread_response(response, cx)
+                })?
+            })
+        }
+    }
+
+    pub fn reply_to_annotation_thread(
+        &mut self,
+        buffer: &Model<Buffer>,
+        thread_id: ThreadId,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.is_local() {
+            let author = self.current_user_login(cx);
+            let result = self
+                .update_annotation_thread(buffer_id, thread_id, cx, |thread| {
+                    thread.comments.push(Comment {
+                        author,
+                        body,
+                        timestamp: SystemTime::now(),
+                    });
+                })
+                .map(drop);
+            Task::ready(result)
+        } else {
+            let request = self.client.request(proto::ReplyToAnnotationThread {
+                project_id: self.remote_id().unwrap(),
+                buffer_id: buffer_id.into(),
+                thread_id: thread_id.0,
+                body,
+            });
+            cx.spawn(|this, mut cx| async move {
+                let response = request.await?;
+                this.update(&mut cx, |this, cx| {
+                    this.apply_annotation_thread_response(response, cx)
+                })??;
+                Ok(())
+            })
+        }
+    }
+
+    pub fn resolve_annotation_thread(
+        &mut self,
+        buffer: &Model<Buffer>,
+        thread_id: ThreadId,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.is_local() {
+            let result = self
+                .update_annotation_thread(buffer_id, thread_id, cx, |thread| {
+                    thread.resolved = resolved;
+                })
+                .map(drop);
+            Task::ready(result)
+        } else {
+            let request = self.client.request(proto::ResolveAnnotationThread {
+                project_id: self.remote_id().unwrap(),
+                buffer_id: buffer_id.into(),
+                thread_id: thread_id.0,
+                resolved,
+            });
+            cx.spawn(|this, mut cx| async move {
+                let response = request.await?;
+                this.update(&mut cx, |this, cx| {
+                    this.apply_annotation_thread_response(response, cx)
+                })??;
+                Ok(())
+            })
+        }
+    }
+
+    /// Sends every thread of the project to the server once it is shared, which stores them
+    /// by path for guests to load when they open the files.
+    pub(crate) fn send_annotation_threads(&self, project_id: u64, cx: &AppContext) {
+        let open_threads = self
+            .annotation_threads
+            .iter()
+            .flat_map(|(buffer_id, threads)| {
+                threads
+                    .iter()
+                    .filter_map(|thread| self.serialize_annotation_thread(*buffer_id, thread, cx))
+            });
+        let released_threads =
+            self.released_annotation_threads
+                .iter()
+                .flat_map(|(project_path, threads)| {
+                    threads
+                        .iter()
+                        .map(|thread| serialize_released_annotation_thread(project_path, thread))
+                });
+        for thread in open_threads.chain(released_threads) {
+            self.client
+                .send(proto::UpdateAnnotationThread {
+                    project_id,
+                    thread: Some(thread),
+                })
+                .log_err();
+        }
+    }
+
+    /// Attaches the threads of a newly opened buffer. Hosts restore the threads that were left
+    /// on the buffer's file when it was last released, while guests load them from the server.
+    pub(crate) fn register_buffer_annotation_threads(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path.clone(),
+        };
+
+        if self.is_local() {
+            let Some(threads) = self.released_annotation_threads.remove(&project_path) else {
+                return;
+            };
+            let buffer = buffer.read(cx);
+            let threads = threads
+                .into_iter()
+                .map(|thread| AnnotationThread {
+                    id: thread.id,
+                    anchor: buffer.anchor_before(buffer.clip_point(thread.position, Bias::Left)),
+                    comments: thread.comments,
+                    resolved: thread.resolved,
+                })
+                .collect::<Vec<_>>();
+            for thread in threads {
+                self.insert_annotation_thread(buffer_id, thread, cx);
+            }
+        } else if let Some(project_id) = self.remote_id() {
+            let request = self.client.request(proto::GetAnnotationThreads {
+                project_id,
+                worktree_id: project_path.worktree_id.to_proto(),
+                path: project_path.path.to_string_lossy().into(),
+            });
+            let buffer = buffer.clone();
+            cx.spawn(|this, mut cx| async move {
+                let response = request.await?;
+                let mut threads = Vec::new();
+                for thread in response.threads {
+                    let anchor = thread
+                        .anchor
+                        .clone()
+                        .filter(|_| thread.buffer_id == u64::from(buffer_id))
+                        .and_then(deserialize_anchor);
+                    let anchor = match anchor {
+                        Some(anchor) => {
+                            buffer
+                                .update(&mut cx, |buffer, _| buffer.wait_for_anchors([anchor]))?
+                                .await?;
+                            anchor
+                        }
+                        // The thread was stored for another buffer of the same file, so it's
+                        // anchored by its position in the file instead.
+                        None => buffer.update(&mut cx, |buffer, _| {
+                            let position = Point::new(thread.row, thread.column);
+                            buffer.anchor_before(buffer.clip_point(position, Bias::Left))
+                        })?,
+                    };
+                    threads.push(AnnotationThread {
+                        id: ThreadId(thread.id),
+                        anchor,
+                        comments: deserialize_comments(thread.comments),
+                        resolved: thread.resolved,
+                    });
+                }
+                this.update(&mut cx, |this, cx| {
+                    if this.opened_buffers.contains_key(&buffer_id) {
+                        for thread in threads {
+                            this.insert_annotation_thread(buffer_id, thread, cx);
+                        }
+                    }
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    /// Detaches the threads of a released buffer. Hosts keep them at their positions in the
+    /// buffer's file, while guests drop them and load them again if the file is reopened.
+    pub(crate) fn release_buffer_annotation_threads(&mut self, buffer: &Buffer, cx: &AppContext) {
+        let Some(threads) = self.annotation_threads.remove(&buffer.remote_id()) else {
+            return;
+        };
+        if !self.is_local() {
+            return;
+        }
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path.clone(),
+        };
+        let threads = threads
+            .threads
+            .into_iter()
+            .map(|thread| ReleasedAnnotationThread {
+                id: thread.id,
+                position: thread.anchor.to_point(buffer),
+                comments: thread.comments,
+                resolved: thread.resolved,
+            });
+        self.released_annotation_threads
+            .entry(project_path)
+            .or_default()
+            .extend(threads);
+    }
+
+    pub(crate) async fn handle_create_annotation_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::CreateAnnotationThread>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::AnnotationThreadResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let anchor = envelope
+            .payload
+            .anchor
+            .and_then(deserialize_anchor)
+            .context("invalid anchor")?;
+        let buffer = this.update(&mut cx, |this, _| {
+            this.opened_buffers
+                .get(&buffer_id)
+                .and_then(|buffer| buffer.upgrade())
+                .ok_or_else(|| anyhow!("unknown buffer id {}", buffer_id))
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| buffer.wait_for_anchors([anchor]))?
+            .await?;
+
+        this.update(&mut cx, |this, cx| {
+            let author = this.collaborator_login(sender_id, cx);
+            let thread = this.insert_new_annotation_thread(
+                buffer_id,
+                anchor,
+                author,
+                envelope.payload.body,
+                cx,
+            );
+            proto::AnnotationThreadResponse {
+                thread: this.serialize_annotation_thread(buffer_id, &thread, cx),
+            }
+        })
+    }
+
+    pub(crate) async fn handle_reply_to_annotation_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ReplyToAnnotationThread>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::AnnotationThreadResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let thread_id = ThreadId(envelope.payload.thread_id);
+        this.update(&mut cx, |this, cx| {
+            let author = this.collaborator_login(sender_id, cx);
+            let thread = this.update_annotation_thread(buffer_id, thread_id, cx, |thread| {
+                thread.comments.push(Comment {
+                    author,
+                    body: envelope.payload.body,
+                    timestamp: SystemTime::now(),
+                });
+            })?;
+            Ok(proto::AnnotationThreadResponse {
+                thread: this.serialize_annotation_thread(buffer_id, &thread, cx),
+            })
+        })?
+    }
+
+    pub(crate) async fn handle_resolve_annotation_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ResolveAnnotationThread>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::AnnotationThreadResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let thread_id = ThreadId(envelope.payload.thread_id);
+        this.update(&mut cx, |this, cx| {
+            let thread = this.update_annotation_thread(buffer_id, thread_id, cx, |thread| {
+                thread.resolved = envelope.payload.resolved;
+            })?;
+            Ok(proto::AnnotationThreadResponse {
+                thread: this.serialize_annotation_thread(buffer_id, &thread, cx),
+            })
+        })?
+    }
+
+    pub(crate) async fn handle_update_annotation_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateAnnotationThread>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let thread = envelope.payload.thread.context("missing thread")?;
+        let (buffer_id, thread) = deserialize_annotation_thread(thread)?;
+        this.update(&mut cx, |this, cx| {
+            // Threads of buffers that aren't open are loaded from the server when they are.
+            if this.opened_buffers.contains_key(&buffer_id) {
+                this.insert_annotation_thread(buffer_id, thread, cx);
+            }
+        })
+    }
+
+    fn insert_new_annotation_thread(
+        &mut self,
+        buffer_id: BufferId,
+        anchor: Anchor,
+        author: String,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> AnnotationThread {
+        let thread = AnnotationThread {
+            id: ThreadId(post_inc(&mut self.next_annotation_thread_id)),
+            anchor,
+            comments: vec![Comment {
+                author,
+                body,
+                timestamp: SystemTime::now(),
+            }],
+            resolved: false,
+        };
+        self.insert_annotation_thread(buffer_id, thread.clone(), cx);
+        thread
+    }
+
+    fn update_annotation_thread(
+        &mut self,
+        buffer_id: BufferId,
+        thread_id: ThreadId,
+        cx: &mut ModelContext<Self>,
+        update: impl FnOnce(&mut AnnotationThread),
+    ) -> Result<AnnotationThread> {
+        let thread = self
+            .annotation_threads
+            .get_mut(&buffer_id)
+            .and_then(|threads| threads.get_mut(thread_id))
+            .ok_or_else(|| anyhow!("unknown annotation thread {:?}", thread_id))?;
+        update(thread);
+        let thread = thread.clone();
+        self.insert_annotation_thread(buffer_id, thread.clone(), cx);
+        Ok(thread)
+    }
+
+    fn insert_annotation_thread(
+        &mut self,
+        buffer_id: BufferId,
+        thread: AnnotationThread,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self.is_local() {
+            if let Some(project_id) = self.remote_id() {
+                if let Some(thread) = self.serialize_annotation_thread(buffer_id, &thread, cx) {
+                    self.client
+                        .send(proto::UpdateAnnotationThread {
+                            project_id,
+                            thread: Some(thread),
+                        })
+                        .log_err();
+                }
+            }
+        }
+
+        self.annotation_threads
+            .entry(buffer_id)
+            .or_default()
+            .insert(thread);
+        cx.emit(Event::AnnotationThreadsChanged(buffer_id));
+        cx.notify();
+    }
+
+    fn apply_annotation_thread_response(
+        &mut self,
+        response: proto::AnnotationThreadResponse,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<ThreadId> {
+        let thread = response.thread.context("missing thread")?;
+        let (buffer_id, thread) = deserialize_annotation_thread(thread)?;
+        let thread_id = thread.id;
+        self.insert_annotation_thread(buffer_id, thread, cx);
+        Ok(thread_id)
+    }
+
+    /// Serializes a thread along with the path and position it has in its buffer's file, which
+    /// the server stores it by.
+    fn serialize_annotation_thread(
+        &self,
+        buffer_id: BufferId,
+        thread: &AnnotationThread,
+        cx: &AppContext,
+    ) -> Option<proto::AnnotationThread> {
+        let buffer = self.opened_buffers.get(&buffer_id)?.upgrade()?;
+        let buffer = buffer.read(cx);
+        let file = File::from_dyn(buffer.file())?;
+        let position = thread.anchor.to_point(buffer);
+        Some(proto::AnnotationThread {
+            id: thread.id.0,
+            buffer_id: buffer_id.into(),
+            anchor: Some(serialize_anchor(&thread.anchor)),
+            comments: serialize_comments(&thread.comments),
+            resolved: thread.resolved,
+            worktree_id: file.worktree_id(cx).to_proto(),
+            path: file.path.to_string_lossy().into(),
+            row: position.row,
+            column: position.column,
+        })
+    }
+
+    fn current_user_login(&self, cx: &ModelContext<Self>) -> String {
+        self.user_store
+            .read(cx)
+            .current_user()
+            .map(|user| user.github_login.clone())
+            .unwrap_or_default()
+    }
+
+    fn collaborator_login(&self, peer_id: proto::PeerId, cx: &ModelContext<Self>) -> String {
+        self.collaborators
+            .get(&peer_id)
+            .and_then(|collaborator| {
+                self.user_store
+                    .read(cx)
+                    .get_cached_user(collaborator.user_id)
+            })
+            .map(|user| user.github_login.clone())
+            .unwrap_or_default()
+    }
+}
+
+fn serialize_released_annotation_thread(
+    project_path: &ProjectPath,
+    thread: &ReleasedAnnotationThread,
+) -> proto::AnnotationThread {
+    proto::AnnotationThread {
+        id: thread.id.0,
+        buffer_id: 0,
+        anchor: None,
+        comments: serialize_comments(&thread.comments),
+        resolved: thread.resolved,
+        worktree_id: project_path.worktree_id.to_proto(),
+        path: project_path.path.to_string_lossy().into(),
+        row: thread.position.row,
+        column: thread.position.column,
+    }
+}
+
+fn serialize_comments(comments: &[Comment]) -> Vec<proto::AnnotationComment> {
+    comments
+        .iter()
+        .map(|comment| proto::AnnotationComment {
+            author: comment.author.clone(),
+            body: comment.body.clone(),
+            timestamp: Some(comment.timestamp.into()),
+        })
+        .collect()
+}
+
+fn deserialize_comments(comments: Vec<proto::AnnotationComment>) -> Vec<Comment> {
+    comments
+        .into_iter()
+        .map(|comment| Comment {
+            author: comment.author,
+            body: comment.body,
+            timestamp: comment
+                .timestamp
+                .map(SystemTime::from)
+                .unwrap_or(SystemTime::UNIX_EPOCH),
+        })
+        .collect()
+}
+
+fn deserialize_annotation_thread(
+    thread: proto::AnnotationThread,
+) -> Result<(BufferId, AnnotationThread)> {
+    let buffer_id = BufferId::new(thread.buffer_id)?;
+    let anchor = thread
+        .anchor
+        .and_then(deserialize_anchor)
+        .context("invalid anchor")?;
+    Ok((
+        buffer_id,
+        AnnotationThread {
+            id: ThreadId(thread.id),
+            anchor,
+            comments: deserialize_comments(thread.comments),
+            resolved: thread.resolved,
+        },
+    ))
+}
@@ -109,6 +109,7 @@ pub(crate) struct PerformRename {
     pub push_to_history: bool,
 }
 
+#[derive(Clone)]
 pub struct GetDefinition {
     pub position: PointUtf16,
 }
@@ -0,0 +1,194 @@
+use crate::{Event, Project};
+use collections::HashMap;
+use gpui::{Model, ModelContext};
+use language::{Buffer, Operation, ToOffset as _};
+use std::ops::Range;
+use text::{Anchor, BufferId};
+
+/// Edits made to a buffer of a shared project while the connection to the server was down.
+///
+/// Buffers are CRDTs, so these edits are replayed after reconnecting by sending the operations
+/// the other peers haven't observed, in order. The queue keeps track of which regions were edited
+/// offline, so that concurrent remote edits to the same regions can be flagged as conflicts.
+#[derive(Default)]
+pub struct OfflineEditQueue {
+    buffers: HashMap<BufferId, QueuedEdits>,
+    conflicts: HashMap<BufferId, Vec<Range<Anchor>>>,
+}
+
+struct QueuedEdits {
+    /// The first edit made offline. Remote edits that didn't observe it are concurrent with the
+    /// queued edits.
+    first_edit: clock::Lamport,
+    /// The last edit made offline. Once remote edits observe it, the peers have caught up.
+    last_edit: clock::Lamport,
+    ranges: Vec<Range<Anchor>>,
+    edit_count: usize,
+    replayed: bool,
+}
+
+impl OfflineEditQueue {
+    /// The number of edits that haven't been sent to the other peers yet.
+    pub fn pending_edit_count(&self) -> usize {
+        self.buffers
+            .values()
+            .filter(|edits| !edits.replayed)
+            .map(|edits| edits.edit_count)
+            .sum()
+    }
+
+    /// The regions of the given buffer where remote edits conflicted with edits made offline.
+    pub fn conflicts(&self, buffer_id: BufferId) -> &[Range<Anchor>] {
+        self.conflicts
+            .get(&buffer_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn push(&mut self, buffer_id: BufferId, timestamp: clock::Lamport, ranges: Vec<Range<Anchor>>) {
+        let edits = self
+            .buffers
+            .entry(buffer_id)
+            .or_insert_with(|| QueuedEdits {
+                first_edit: timestamp,
+                last_edit: timestamp,
+                ranges: Vec::new(),
+                edit_count: 0,
+                replayed: false,
+            });
+        // Edits made offline after an earlier replay start a new batch.
+        if edits.replayed {
+            edits.first_edit = timestamp;
+            edits.ranges.clear();
+            edits.edit_count = 0;
+            edits.replayed = false;
+        }
+        edits.last_edit = timestamp;
+        edits.ranges.extend(ranges);
+        edits.edit_count += 1;
+    }
+
+    fn mark_replayed(&mut self) -> bool {
+        let mut changed = false;
+        for edits in self.buffers.values_mut() {
+            changed |= !edits.replayed;
+            edits.replayed = true;
+        }
+        changed
+    }
+}
+
+impl Project {
+    pub fn offline_edits(&self) -> &OfflineEditQueue {
+        &self.offline_edits
+    }
+
+    /// The number of edits made while disconnected that the other peers haven't received yet.
+    pub fn pending_edit_count(&self) -> usize {
+        self.offline_edits.pending_edit_count()
+    }
+
+    pub fn dismiss_offline_edit_conflicts(
+        &mut self,
+        buffer_id: BufferId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self.offline_edits.conflicts.remove(&buffer_id).is_some() {
+            cx.emit(Event::OfflineEditConflicts(buffer_id));
+        }
+    }
+
+    /// Records a local operation if it was made while the connection to the server was down.
+    pub(crate) fn queue_offline_edit(
+        &mut self,
+        buffer: &Model<Buffer>,
+        operation: &Operation,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Operation::Buffer(operation) = operation else {
+            return;
+        };
+        if self.remote_id().is_none() || self.client.status().borrow().is_connected() {
+            return;
+        }
+
+        let (timestamp, version) = match operation {
+            text::Operation::Edit(edit) => (edit.timestamp, &edit.version),
+            text::Operation::Undo(undo) => (undo.timestamp, &undo.version),
+        };
+        let buffer = buffer.read(cx);
+        let ranges = buffer
+            .edits_since::<usize>(version)
+            .map(|edit| buffer.anchor_before(edit.new.start)..buffer.anchor_after(edit.new.end))
+            .collect();
+        self.offline_edits
+            .push(buffer.remote_id(), timestamp, ranges);
+        cx.notify();
+    }
+
+    /// Called once the queued edits have been sent to the other peers after reconnecting.
+    pub(crate) fn offline_edits_replayed(&mut self, cx: &mut ModelContext<Self>) {
+        if self.offline_edits.mark_replayed() {
+            cx.notify();
+        }
+    }
+
+    /// Flags the regions edited offline that the given remote operations, which were just applied
+    /// to the buffer on top of `version_before`, changed concurrently.
+    pub(crate) fn detect_offline_edit_conflicts(
+        &mut self,
+        buffer: &Model<Buffer>,
+        operations: &[Operation],
+        version_before: &clock::Global,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer = buffer.read(cx);
+        let buffer_id = buffer.remote_id();
+        let Some(queued) = self.offline_edits.buffers.get(&buffer_id) else {
+            return;
+        };
+        let versions = operations.iter().filter_map(|operation| match operation {
+            Operation::Buffer(text::Operation::Edit(edit)) => Some(&edit.version),
+            Operation::Buffer(text::Operation::Undo(undo)) => Some(&undo.version),
+            _ => None,
+        });
+        let mut is_concurrent = false;
+        let mut caught_up = false;
+        for version in versions {
+            is_concurrent |= !version.observed(queued.first_edit);
+            caught_up |= version.observed(queued.last_edit);
+        }
+
+        if is_concurrent {
+            let queued_ranges = queued
+                .ranges
+                .iter()
+                .map(|range| range.start.to_offset(buffer)..range.end.to_offset(buffer))
+                .collect::<Vec<_>>();
+            let mut conflicts = Vec::new();
+            for edit in buffer.edits_since::<usize>(version_before) {
+                for range in &queued_ranges {
+                    if range.start <= edit.new.end && edit.new.start <= range.end {
+                        let start = range.start.min(edit.new.start);
+                        let end = range.end.max(edit.new.end);
+                        conflicts.push(buffer.anchor_before(start)..buffer.anchor_after(end));
+                    }
+                }
+            }
+            if !conflicts.is_empty() {
+                self.offline_edits
+                    .conflicts
+                    .entry(buffer_id)
+                    .or_default()
+                    .extend(conflicts);
+                cx.emit(Event::OfflineEditConflicts(buffer_id));
+            }
+        }
+
+        if caught_up {
+            self.offline_edits
+                .buffers
+                .retain(|id, edits| *id != buffer_id || !edits.replayed);
+        }
+    }
+}
@@ -1,7 +1,9 @@
+mod annotations;
 pub mod connection_manager;
 pub mod debounced_delay;
 pub mod lsp_command;
 pub mod lsp_ext_command;
+mod offline_edits;
 mod prettier_support;
 pub mod project_settings;
 pub mod search;
@@ -13,6 +15,7 @@ mod project_tests;
 pub mod search_history;
 mod yarn;
 
+use annotations::ReleasedAnnotationThread;
 use anyhow::{anyhow, bail, Context as _, Result};
 use async_trait::async_trait;
 use client::{
@@ -34,7 +37,11 @@ use futures::{
     AsyncWriteExt, Future, FutureExt, StreamExt, TryFutureExt,
 };
 use fuzzy::CharBag;
-use git::{blame::Blame, repository::GitRepository};
+use git::{
+    blame::Blame,
+    repository::{GitRepository, RepoPath},
+    staging::{self, StagedStatus},
+};
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use gpui::{
     AnyModel, AppContext, AsyncAppContext, BackgroundExecutor, BorrowAppContext, Context, Entity,
@@ -73,13 +80,13 @@ use paths::{
 };
 use postage::watch;
 use prettier_support::{DefaultPrettier, PrettierInstance};
-use project_settings::{DirenvSettings, LspSettings, ProjectSettings};
+use project_settings::{DiagnosticFilter, DirenvSettings, LspSettings, ProjectSettings};
 use rand::prelude::*;
 use rpc::{ErrorCode, ErrorExt as _};
 use search::SearchQuery;
 use search_history::SearchHistory;
 use serde::Serialize;
-use settings::{watch_config_file, Settings, SettingsLocation, SettingsStore};
+use settings::{watch_config_file, LayerKey, Settings, SettingsLocation, SettingsStore};
 use sha2::{Digest, Sha256};
 use similar::{ChangeTag, TextDiff};
 use smol::channel::{Receiver, Sender};
@@ -97,7 +104,7 @@ use std::{
     num::NonZeroU32,
     ops::Range,
     path::{self, Component, Path, PathBuf},
-    process::Stdio,
+    process::{ExitStatus, Stdio},
     str::{self, FromStr},
     sync::{
         atomic::{AtomicUsize, Ordering::SeqCst},
@@ -119,8 +126,10 @@ use util::{
 use worktree::{CreatedEntry, RemoteWorktreeClient, Snapshot, Traversal};
 use yarn::YarnPathStore;
 
+pub use annotations::{AnnotationThread, AnnotationThreads, Comment, ThreadId};
 pub use fs::*;
 pub use language::Location;
+pub use offline_edits::OfflineEditQueue;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
@@ -128,8 +137,8 @@ pub use task_inventory::{
 };
 pub use worktree::{
     Entry, EntryKind, File, LocalWorktree, PathChange, ProjectEntryId, RepositoryEntry,
-    UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId, WorktreeSettings,
-    FS_WATCH_LATENCY,
+    SearchScope, UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId,
+    WorktreeSettings, FS_WATCH_LATENCY,
 };
 
 const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
@@ -174,6 +183,7 @@ pub struct Project {
     language_servers: HashMap<LanguageServerId, LanguageServerState>,
     language_server_ids: HashMap<(WorktreeId, LanguageServerName), LanguageServerId>,
     language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
+    language_server_crashes: HashMap<(WorktreeId, LanguageServerName), LanguageServerCrashes>,
     last_formatting_failure: Option<String>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
     language_server_watched_paths: HashMap<LanguageServerId, HashMap<WorktreeId, GlobSet>>,
@@ -195,6 +205,8 @@ pub struct Project {
             )>,
         >,
     >,
+    /// How many diagnostics the user's diagnostic filters dropped, by path and language server.
+    suppressed_diagnostic_counts: HashMap<ProjectPath, HashMap<LanguageServerId, usize>>,
     user_store: Model<UserStore>,
     fs: Arc<dyn Fs>,
     client_state: ProjectClientState,
@@ -232,6 +244,10 @@ pub struct Project {
     hosted_project_id: Option<ProjectId>,
     dev_server_project_id: Option<client::DevServerProjectId>,
     search_history: SearchHistory,
+    annotation_threads: HashMap<BufferId, AnnotationThreads>,
+    released_annotation_threads: HashMap<ProjectPath, Vec<ReleasedAnnotationThread>>,
+    next_annotation_thread_id: u64,
+    offline_edits: OfflineEditQueue,
     snippets: Model<SnippetProvider>,
     yarn: Model<YarnPathStore>,
 }
@@ -364,6 +380,8 @@ pub enum Event {
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
+    AnnotationThreadsChanged(BufferId),
+    OfflineEditConflicts(BufferId),
 }
 
 pub enum LanguageServerState {
@@ -377,6 +395,14 @@ pub enum LanguageServerState {
     },
 }
 
+/// The crashes of a language server since it was last started by hand, used to decide whether
+/// to restart it after it crashes again.
+#[derive(Default)]
+struct LanguageServerCrashes {
+    count: u8,
+    last_crash: Option<Instant>,
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct LanguageServerStatus {
     pub name: String,
@@ -713,6 +739,10 @@ impl Project {
         client.add_model_request_handler(Self::handle_task_templates);
         client.add_model_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
         client.add_model_request_handler(Self::handle_signature_help);
+        client.add_model_request_handler(Self::handle_create_annotation_thread);
+        client.add_model_request_handler(Self::handle_reply_to_annotation_thread);
+        client.add_model_request_handler(Self::handle_resolve_annotation_thread);
+        client.add_model_message_handler(Self::handle_update_annotation_thread);
     }
 
     pub fn local(
@@ -767,10 +797,12 @@ impl Project {
                 next_diagnostic_group_id: Default::default(),
                 diagnostics: Default::default(),
                 diagnostic_summaries: Default::default(),
+                suppressed_diagnostic_counts: Default::default(),
                 supplementary_language_servers: HashMap::default(),
                 language_servers: Default::default(),
                 language_server_ids: HashMap::default(),
                 language_server_statuses: Default::default(),
+                language_server_crashes: HashMap::default(),
                 last_formatting_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
                 language_server_watched_paths: HashMap::default(),
@@ -791,6 +823,10 @@ impl Project {
                 hosted_project_id: None,
                 dev_server_project_id: None,
                 search_history: Self::new_search_history(),
+                annotation_threads: HashMap::default(),
+                released_annotation_threads: HashMap::default(),
+                next_annotation_thread_id: 0,
+                offline_edits: OfflineEditQueue::default(),
             }
         })
     }
@@ -903,6 +939,7 @@ impl Project {
                 next_diagnostic_group_id: Default::default(),
                 diagnostic_summaries: Default::default(),
                 diagnostics: Default::default(),
+                suppressed_diagnostic_counts: Default::default(),
                 client_subscriptions: Default::default(),
                 _subscriptions: vec![
                     cx.on_release(Self::release),
@@ -935,6 +972,7 @@ impl Project {
                         )
                     })
                     .collect(),
+                language_server_crashes: HashMap::default(),
                 last_formatting_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
                 language_server_watched_paths: HashMap::default(),
@@ -960,6 +998,10 @@ impl Project {
                     .dev_server_project_id
                     .map(|dev_server_project_id| DevServerProjectId(dev_server_project_id)),
                 search_history: Self::new_search_history(),
+                annotation_threads: HashMap::default(),
+                released_annotation_threads: HashMap::default(),
+                next_annotation_thread_id: 0,
+                offline_edits: OfflineEditQueue::default(),
             };
             this.set_role(role, cx);
             for worktree in worktrees {
@@ -1593,6 +1635,7 @@ impl Project {
                     .log_err();
             }
         }
+        self.send_annotation_threads(project_id, cx);
 
         let (updates_tx, mut updates_rx) = mpsc::unbounded();
         let client = self.client.clone();
@@ -1727,6 +1770,9 @@ impl Project {
         self.shared_buffers.clear();
         self.set_collaborators_from_proto(message.collaborators, cx)?;
         self.metadata_changed(cx);
+        // Guests synchronize their buffers with the host after it reshares the project, which
+        // sends them the edits made while it was disconnected.
+        self.offline_edits_replayed(cx);
         cx.emit(Event::Reshared);
         Ok(())
     }
@@ -1867,6 +1913,8 @@ impl Project {
             *sharing_has_stopped = true;
 
             self.collaborators.clear();
+            // The buffers become read-only, so edits made offline can't be sent anymore.
+            self.offline_edits = OfflineEditQueue::default();
 
             for worktree in &self.worktrees {
                 if let Some(worktree) = worktree.upgrade() {
@@ -2500,11 +2548,14 @@ impl Project {
 
         self.detect_language_for_buffer(buffer, cx);
         self.register_buffer_with_language_servers(buffer, cx);
+        self.register_buffer_annotation_threads(buffer, cx);
         cx.observe_release(buffer, |this, buffer, cx| {
+            this.release_buffer_annotation_threads(buffer, cx);
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
                     for server in this.language_servers_for_buffer(buffer, cx) {
+                        server.1.semantic_tokens_cache().lock().remove(&uri);
                         server
                             .1
                             .notify::<lsp::notification::DidCloseTextDocument>(
@@ -2639,6 +2690,10 @@ impl Project {
             self.buffer_snapshots.remove(&buffer.remote_id());
             let file_url = lsp::Url::from_file_path(old_path).unwrap();
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
+                language_server
+                    .semantic_tokens_cache()
+                    .lock()
+                    .remove(&file_url);
                 language_server
                     .notify::<lsp::notification::DidCloseTextDocument>(
                         lsp::DidCloseTextDocumentParams {
@@ -2714,6 +2769,7 @@ impl Project {
                             .is_ok()
                         {
                             needs_resync_with_host = false;
+                            this.update(&mut cx, |this, cx| this.offline_edits_replayed(cx))?;
                         }
                     }
 
@@ -2773,6 +2829,7 @@ impl Project {
 
         match event {
             BufferEvent::Operation(operation) => {
+                self.queue_offline_edit(&buffer, operation, cx);
                 self.enqueue_buffer_ordered_message(BufferOrderedMessage::Operation {
                     buffer_id: buffer.read(cx).remote_id(),
                     operation: language::proto::serialize_operation(operation),
@@ -2878,6 +2935,10 @@ impl Project {
                         snapshot: next_snapshot.clone(),
                     });
 
+                    language_server
+                        .semantic_tokens_cache()
+                        .lock()
+                        .did_change(&uri, &content_changes);
                     language_server
                         .notify::<lsp::notification::DidChangeTextDocument>(
                             lsp::DidChangeTextDocumentParams {
@@ -3895,6 +3956,23 @@ impl Project {
             },
         );
 
+        let edit_threshold = ProjectSettings::get(
+            Some(SettingsLocation {
+                worktree_id: key.0.to_proto() as usize,
+                path: Path::new(""),
+            }),
+            cx,
+        )
+        .lsp
+        .get(&adapter.name.0)
+        .and_then(|settings| settings.semantic_tokens_edit_threshold);
+        if let Some(edit_threshold) = edit_threshold {
+            language_server
+                .semantic_tokens_cache()
+                .lock()
+                .set_edit_threshold(edit_threshold);
+        }
+
         self.language_server_statuses.insert(
             server_id,
             LanguageServerStatus {
@@ -3907,6 +3985,20 @@ impl Project {
 
         cx.emit(Event::LanguageServerAdded(server_id));
 
+        let exit_status = language_server.exit_status();
+        let started_at = cx.background_executor().now();
+        cx.spawn(move |this, mut cx| async move {
+            let status = exit_status.await?;
+            if !status.success() {
+                this.update(&mut cx, |this, cx| {
+                    this.handle_language_server_crash(server_id, status, started_at, cx)
+                })
+                .ok();
+            }
+            Some(())
+        })
+        .detach();
+
         if let Some(project_id) = self.remote_id() {
             self.client.send(proto::StartLanguageServer {
                 project_id,
@@ -4059,6 +4151,11 @@ impl Project {
                 });
             }
 
+            self.suppressed_diagnostic_counts
+                .retain(|_, counts_by_server_id| {
+                    counts_by_server_id.remove(&server_id);
+                    !counts_by_server_id.is_empty()
+                });
             self.language_server_watched_paths.remove(&server_id);
             self.language_server_statuses.remove(&server_id);
             cx.notify();
@@ -4174,15 +4271,35 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) {
         let worktree_id = worktree.read(cx).id();
-
-        let stop_tasks = self
+        let adapter_names = self
             .languages
-            .clone()
             .lsp_adapters(&language)
             .iter()
-            .map(|adapter| {
-                let stop_task = self.stop_language_server(worktree_id, adapter.name.clone(), cx);
-                (stop_task, adapter.name.clone())
+            .map(|adapter| adapter.name.clone())
+            .collect::<Vec<_>>();
+
+        // Servers restarted by hand get a fresh set of automatic restarts.
+        for adapter_name in &adapter_names {
+            self.language_server_crashes
+                .remove(&(worktree_id, adapter_name.clone()));
+        }
+        self.restart_named_language_servers(worktree, language, adapter_names, cx);
+    }
+
+    fn restart_named_language_servers(
+        &mut self,
+        worktree: Model<Worktree>,
+        language: Arc<Language>,
+        adapter_names: Vec<LanguageServerName>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let worktree_id = worktree.read(cx).id();
+
+        let stop_tasks = adapter_names
+            .into_iter()
+            .map(|adapter_name| {
+                let stop_task = self.stop_language_server(worktree_id, adapter_name.clone(), cx);
+                (stop_task, adapter_name)
             })
             .collect::<Vec<_>>();
         if stop_tasks.is_empty() {
@@ -4220,6 +4337,74 @@ impl Project {
         .detach();
     }
 
+    fn handle_language_server_crash(
+        &mut self,
+        server_id: LanguageServerId,
+        status: ExitStatus,
+        started_at: Instant,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running {
+            language, adapter, ..
+        }) = self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let (language, adapter) = (language.clone(), adapter.clone());
+        let Some(key) = self
+            .language_server_ids
+            .iter()
+            .find_map(|(key, id)| (*id == server_id).then(|| key.clone()))
+        else {
+            return;
+        };
+        let Some(worktree) = self.worktree_for_id(key.0, cx) else {
+            return;
+        };
+
+        let policy = ProjectSettings::get(
+            Some(SettingsLocation {
+                worktree_id: key.0.to_proto() as usize,
+                path: Path::new(""),
+            }),
+            cx,
+        )
+        .lsp
+        .get(&adapter.name.0)
+        .map(LspSettings::restart_policy)
+        .unwrap_or_default();
+
+        let now = cx.background_executor().now();
+        let crashes = self.language_server_crashes.entry(key.clone()).or_default();
+        // A server that stayed up for longer than the cooldown was working before it crashed,
+        // so its earlier crashes no longer count against it.
+        if now - started_at > policy.cooldown {
+            crashes.count = 0;
+        }
+        let restart = policy.should_restart(
+            crashes.count,
+            crashes.last_crash.map(|last_crash| now - last_crash),
+        );
+        crashes.count = crashes.count.saturating_add(1);
+        crashes.last_crash = Some(now);
+
+        let server_name = &adapter.name.0;
+        let message = if restart {
+            format!(
+                "language server {server_name:?} crashed ({status}), restarting it (attempt {} of {})",
+                crashes.count, policy.max_restarts
+            )
+        } else {
+            format!("language server {server_name:?} crashed ({status}), not restarting it")
+        };
+        log::error!("{message}");
+        cx.emit(Event::LanguageServerLog(server_id, message));
+
+        if restart {
+            self.restart_named_language_servers(worktree, language, vec![key.1], cx);
+        }
+    }
+
     pub fn cancel_language_server_work_for_buffers(
         &mut self,
         buffers: impl IntoIterator<Item = Model<Buffer>>,
@@ -4704,6 +4889,8 @@ impl Project {
         let mut primary_diagnostic_group_ids = HashMap::default();
         let mut sources_by_group_id = HashMap::default();
         let mut supporting_diagnostics = HashMap::default();
+        let filters = self.diagnostic_filters_for_path(language_server_id, &abs_path, cx);
+        let mut suppressed_count = 0;
 
         // Ensure that primary diagnostics are always the most severe
         params.diagnostics.sort_by_key(|item| item.severity);
@@ -4714,6 +4901,14 @@ impl Project {
                 lsp::NumberOrString::Number(code) => code.to_string(),
                 lsp::NumberOrString::String(code) => code.clone(),
             });
+            let severity = diagnostic.severity.unwrap_or(DiagnosticSeverity::ERROR);
+            if filters
+                .iter()
+                .any(|filter| filter.suppresses(code.as_deref(), severity))
+            {
+                suppressed_count += 1;
+                continue;
+            }
             let range = range_from_lsp(diagnostic.range);
             let is_supporting = diagnostic
                 .related_information
@@ -4751,7 +4946,7 @@ impl Project {
                     diagnostic: Diagnostic {
                         source: diagnostic.source.clone(),
                         code: code.clone(),
-                        severity: diagnostic.severity.unwrap_or(DiagnosticSeverity::ERROR),
+                        severity,
                         message: diagnostic.message.trim().to_string(),
                         group_id,
                         is_primary: true,
@@ -4801,14 +4996,99 @@ impl Project {
 
         self.update_diagnostic_entries(
             language_server_id,
-            abs_path,
+            abs_path.clone(),
             params.version,
             diagnostics,
             cx,
         )?;
+        self.update_suppressed_diagnostic_count(
+            language_server_id,
+            &abs_path,
+            suppressed_count,
+            cx,
+        );
         Ok(())
     }
 
+    /// Returns the user's diagnostic filters for a language server that apply to a file.
+    fn diagnostic_filters_for_path(
+        &self,
+        server_id: LanguageServerId,
+        abs_path: &Path,
+        cx: &AppContext,
+    ) -> Vec<DiagnosticFilter> {
+        let Some(adapter) = self.language_server_adapter_for_id(server_id) else {
+            return Vec::new();
+        };
+        let Some((worktree, path)) = self.find_local_worktree(abs_path, cx) else {
+            return Vec::new();
+        };
+        let location = SettingsLocation {
+            worktree_id: worktree.read(cx).id().to_proto() as usize,
+            path: &path,
+        };
+        ProjectSettings::get(Some(location), cx)
+            .lsp
+            .get(&adapter.name.0)
+            .map_or(Vec::new(), |settings| {
+                settings
+                    .diagnostic_filters
+                    .iter()
+                    .filter(|filter| filter.matches_path(&path))
+                    .cloned()
+                    .collect()
+            })
+    }
+
+    fn update_suppressed_diagnostic_count(
+        &mut self,
+        server_id: LanguageServerId,
+        abs_path: &Path,
+        count: usize,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some((worktree, path)) = self.find_local_worktree(abs_path, cx) else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: path.into(),
+        };
+        let counts_by_server_id = self
+            .suppressed_diagnostic_counts
+            .entry(project_path.clone())
+            .or_default();
+        let old_count = if count == 0 {
+            counts_by_server_id.remove(&server_id)
+        } else {
+            counts_by_server_id.insert(server_id, count)
+        };
+        if counts_by_server_id.is_empty() {
+            self.suppressed_diagnostic_counts.remove(&project_path);
+        }
+        if old_count.unwrap_or(0) != count {
+            cx.emit(Event::DiagnosticsUpdated {
+                language_server_id: server_id,
+                path: project_path,
+            });
+        }
+    }
+
+    /// The number of diagnostics in visible worktrees that were dropped by the user's
+    /// diagnostic filters.
+    pub fn suppressed_diagnostic_count(&self, cx: &AppContext) -> usize {
+        self.visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).id())
+            .map(|worktree_id| {
+                self.suppressed_diagnostic_counts
+                    .iter()
+                    .filter(|(path, _)| path.worktree_id == worktree_id)
+                    .flat_map(|(_, counts_by_server_id)| counts_by_server_id.values())
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
     pub fn update_diagnostic_entries(
         &mut self,
         server_id: LanguageServerId,
@@ -5493,13 +5773,18 @@ impl Project {
         position: PointUtf16,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Vec<LocationLink>>> {
-        self.request_lsp(
-            buffer.clone(),
-            LanguageServerToQuery::Primary,
-            GetDefinition { position },
-            cx,
-        )
+        if self.is_local() {
+            self.request_first_lsp_locally(buffer, Some(position), GetDefinition { position }, cx)
+        } else {
+            self.request_lsp(
+                buffer.clone(),
+                LanguageServerToQuery::Primary,
+                GetDefinition { position },
+                cx,
+            )
+        }
     }
+
     pub fn definition<T: ToPointUtf16>(
         &self,
         buffer: &Model<Buffer>,
@@ -5968,7 +6253,8 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) -> Task<Vec<Hover>> {
         let position = position.to_point_utf16(buffer.read(cx));
-        self.hover_impl(buffer, position, cx)
+        let hovers = self.hover_impl(buffer, position, cx);
+        cx.spawn(|_, _| async move { merge_hovers(hovers.await).into_iter().collect() })
     }
 
     fn linked_edit_impl(
@@ -6040,57 +6326,49 @@ impl Project {
         if self.is_local() {
             let snapshot = buffer.read(cx).snapshot();
             let offset = position.to_offset(&snapshot);
-            let scope = snapshot.language_scope_at(offset);
             let language = snapshot.language().cloned();
 
-            let server_ids: Vec<_> = self
-                .language_servers_for_buffer(buffer.read(cx), cx)
-                .filter(|(_, server)| server.capabilities().completion_provider.is_some())
-                .filter(|(adapter, _)| {
-                    scope
-                        .as_ref()
-                        .map(|scope| scope.language_allowed(&adapter.name))
-                        .unwrap_or(true)
-                })
-                .map(|(_, server)| server.server_id())
-                .collect();
+            let fanout = self.language_server_fanout(
+                buffer,
+                Some(offset),
+                |server_capabilities| server_capabilities.completion_provider.is_some(),
+                cx,
+            );
 
             let buffer = buffer.clone();
             cx.spawn(move |this, mut cx| async move {
-                let mut tasks = Vec::with_capacity(server_ids.len());
-                this.update(&mut cx, |this, cx| {
-                    for server_id in server_ids {
+                let responses = this.update(&mut cx, |this, cx| {
+                    fanout.request_all(|server_id| {
                         let lsp_adapter = this.language_server_adapter_for_id(server_id);
-                        tasks.push((
-                            lsp_adapter,
-                            this.request_lsp(
-                                buffer.clone(),
-                                LanguageServerToQuery::Other(server_id),
-                                GetCompletions {
-                                    position,
-                                    context: context.clone(),
-                                },
-                                cx,
-                            ),
-                        ));
-                    }
+                        let task = this.request_lsp(
+                            buffer.clone(),
+                            LanguageServerToQuery::Other(server_id),
+                            GetCompletions {
+                                position,
+                                context: context.clone(),
+                            },
+                            cx,
+                        );
+                        Some(async move { anyhow::Ok((lsp_adapter, task.await?)) })
+                    })
                 })?;
 
                 let mut completions = Vec::new();
-                for (lsp_adapter, task) in tasks {
-                    if let Ok(new_completions) = task.await {
-                        populate_labels_for_completions(
-                            new_completions,
-                            &language_registry,
-                            language.clone(),
-                            lsp_adapter,
-                            &mut completions,
-                        )
-                        .await;
-                    }
+                for (lsp_adapter, new_completions) in responses.await.log_err().unwrap_or_default()
+                {
+                    populate_labels_for_completions(
+                        new_completions,
+                        &language_registry,
+                        language.clone(),
+                        lsp_adapter,
+                        &mut completions,
+                    )
+                    .await;
                 }
 
-                Ok(completions)
+                Ok(lsp::dedup_completions(completions, |completion| {
+                    &completion.lsp_completion
+                }))
             })
         } else if let Some(project_id) = self.remote_id() {
             let task = self.send_lsp_proto_request(
@@ -6119,7 +6397,9 @@ impl Project {
                     &mut result,
                 )
                 .await;
-                Ok(result)
+                Ok(lsp::dedup_completions(result, |completion| {
+                    &completion.lsp_completion
+                }))
             })
         } else {
             Task::ready(Ok(Default::default()))
@@ -7330,8 +7610,6 @@ impl Project {
                 self.fs.clone(),
                 workers,
                 query.clone(),
-                include_root,
-                path_count,
                 snapshots,
                 matching_paths_tx,
             ))
@@ -7381,6 +7659,13 @@ impl Project {
 
                     chunk_results.push(cx.spawn(|cx| async move {
                         let buffer = buffer.await?;
+                        // Structural queries match the syntax trees, which may still be parsed
+                        // in the background for buffers that were just opened.
+                        if query.is_structural() {
+                            buffer
+                                .read_with(&cx, |buffer, _| buffer.parsing_idle())?
+                                .await;
+                        }
                         let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
                         let ranges = cx
                             .background_executor()
@@ -7435,8 +7720,6 @@ impl Project {
         fs: Arc<dyn Fs>,
         workers: usize,
         query: SearchQuery,
-        include_root: bool,
-        path_count: usize,
         snapshots: Vec<(Snapshot, WorktreeSettings)>,
         matching_paths_tx: Sender<SearchMatchCandidate>,
     ) {
@@ -7463,31 +7746,31 @@ impl Project {
                 .log_err();
         }
 
-        let paths_per_worker = (path_count + workers - 1) / workers;
+        let search_scope = query.scope();
+        let candidates = snapshots
+            .iter()
+            .filter_map(|(snapshot, _)| {
+                let entries = snapshot.entries_in_scope(&search_scope).log_err()?;
+                Some(entries.map(move |entry| (snapshot, entry)))
+            })
+            .flatten()
+            .filter(|(_, entry)| {
+                !opened_buffers.contains_key(&entry.path) && query.language_matches(&entry.path)
+            })
+            .collect::<Vec<_>>();
+        let paths_per_worker = candidates.len().div_ceil(workers).max(1);
 
         executor
             .scoped(|scope| {
                 let max_concurrent_workers = Arc::new(Semaphore::new(workers));
 
-                for worker_ix in 0..workers {
-                    let worker_start_ix = worker_ix * paths_per_worker;
-                    let worker_end_ix = worker_start_ix + paths_per_worker;
-                    let opened_buffers = opened_buffers.clone();
+                for worker_candidates in candidates.chunks(paths_per_worker) {
                     let limiter = Arc::clone(&max_concurrent_workers);
                     scope.spawn({
                         async move {
                             let _guard = limiter.acquire().await;
-                            search_snapshots(
-                                snapshots,
-                                worker_start_ix,
-                                worker_end_ix,
-                                query,
-                                matching_paths_tx,
-                                &opened_buffers,
-                                include_root,
-                                fs,
-                            )
-                            .await;
+                            search_candidates(worker_candidates, query, matching_paths_tx, fs)
+                                .await;
                         }
                     });
                 }
@@ -7634,37 +7917,73 @@ impl Project {
             debug_panic!("Should not request multiple lsp commands in non-local project");
             return Task::ready(Vec::new());
         }
+        let fanout = self.language_server_fanout(buffer, position, server_capabilities_check, cx);
+        let responses = fanout.request_all(|server_id| {
+            Some(self.request_lsp(
+                buffer.clone(),
+                LanguageServerToQuery::Other(server_id),
+                request.clone(),
+                cx,
+            ))
+        });
+        cx.spawn(|_, _| async move { responses.await.log_err().unwrap_or_default() })
+    }
+
+    /// Sends a request to every language server of the buffer that supports it, resolving to
+    /// the first response with any locations.
+    fn request_first_lsp_locally<P, R>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: Option<P>,
+        request: R,
+        cx: &mut ModelContext<'_, Self>,
+    ) -> Task<Result<Vec<LocationLink>>>
+    where
+        P: ToOffset,
+        R: LspCommand<Response = Vec<LocationLink>> + Clone,
+        <R::LspRequest as lsp::request::Request>::Result: Send,
+        <R::LspRequest as lsp::request::Request>::Params: Send,
+    {
+        let fanout = self.language_server_fanout(
+            buffer,
+            position,
+            |server_capabilities| request.check_capabilities(server_capabilities),
+            cx,
+        );
+        let response = fanout.request_first(|server_id| {
+            let links = self.request_lsp(
+                buffer.clone(),
+                LanguageServerToQuery::Other(server_id),
+                request.clone(),
+                cx,
+            );
+            Some(async move { anyhow::Ok(Some(links.await?).filter(|links| !links.is_empty())) })
+        });
+        cx.spawn(|_, _| async move { Ok(response.await?.unwrap_or_default()) })
+    }
+
+    /// The running language servers of the buffer that multi-server requests are sent to: those
+    /// that pass the capabilities check and are allowed in the language scope at the position.
+    fn language_server_fanout<P: ToOffset>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: Option<P>,
+        server_capabilities_check: impl Fn(&ServerCapabilities) -> bool,
+        cx: &AppContext,
+    ) -> lsp::MultiServerFanout {
         let snapshot = buffer.read(cx).snapshot();
         let scope = position.and_then(|position| snapshot.language_scope_at(position));
-        let mut response_results = self
-            .language_servers_for_buffer(buffer.read(cx), cx)
-            .filter(|(_, server)| server_capabilities_check(server.capabilities()))
-            .filter(|(adapter, _)| {
-                scope
-                    .as_ref()
-                    .map(|scope| scope.language_allowed(&adapter.name))
-                    .unwrap_or(true)
-            })
-            .map(|(_, server)| server.server_id())
-            .map(|server_id| {
-                self.request_lsp(
-                    buffer.clone(),
-                    LanguageServerToQuery::Other(server_id),
-                    request.clone(),
-                    cx,
-                )
-            })
-            .collect::<FuturesUnordered<_>>();
-
-        return cx.spawn(|_, _| async move {
-            let mut responses = Vec::with_capacity(response_results.len());
-            while let Some(response_result) = response_results.next().await {
-                if let Some(response) = response_result.log_err() {
-                    responses.push(response);
-                }
+        let mut fanout = lsp::MultiServerFanout::new();
+        for (adapter, server) in self.language_servers_for_buffer(buffer.read(cx), cx) {
+            let language_allowed = scope
+                .as_ref()
+                .map(|scope| scope.language_allowed(&adapter.name))
+                .unwrap_or(true);
+            if language_allowed && server_capabilities_check(server.capabilities()) {
+                fanout.add(server.server_id());
             }
-            responses
-        });
+        }
+        fanout
     }
 
     fn send_lsp_proto_request<R: LspCommand>(
@@ -7848,6 +8167,8 @@ impl Project {
     pub fn remove_worktree(&mut self, id_to_remove: WorktreeId, cx: &mut ModelContext<Self>) {
         self.diagnostics.remove(&id_to_remove);
         self.diagnostic_summaries.remove(&id_to_remove);
+        self.suppressed_diagnostic_counts
+            .retain(|path, _| path.worktree_id != id_to_remove);
 
         let mut servers_to_remove = HashMap::default();
         let mut servers_to_preserve = HashSet::default();
@@ -8413,14 +8734,15 @@ impl Project {
                 cx.update_global::<SettingsStore, _>(|store, cx| {
                     for (directory, file_content) in settings_contents {
                         let file_content = file_content.and_then(|content| content.log_err());
-                        store
-                            .set_local_settings(
-                                worktree_id.as_u64() as usize,
-                                directory.clone(),
-                                file_content.as_deref(),
-                                cx,
-                            )
-                            .log_err();
+                        let layer = LayerKey::new(worktree_id.as_u64() as usize, directory.clone());
+                        match file_content
+                            .as_deref()
+                            .filter(|content| !content.is_empty())
+                        {
+                            Some(content) => store.push_layer(layer, content, cx),
+                            None => store.remove_layer(&layer, cx),
+                        }
+                        .log_err();
                         if let Some(remote_id) = project_id {
                             client
                                 .send(proto::UpdateWorktreeSettings {
@@ -8698,6 +9020,141 @@ impl Project {
         }
     }
 
+    /// Stages the unsaved and unstaged changes of a buffer's file that touch the given rows of
+    /// the buffer, leaving its other changes unstaged.
+    pub fn stage_hunks(
+        &self,
+        buffer: &Model<Buffer>,
+        rows: Range<u32>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.update_index_for_buffer(buffer, cx, move |repo, path, buffer_text| {
+            let index_text = repo
+                .load_index_text(path)
+                .with_context(|| format!("{:?} is not tracked", path.0))?;
+            let hunks = staging::hunks_to_stage(path, &index_text, buffer_text, rows)?;
+            // Later hunks are applied first, so that the rows of the earlier ones stay valid.
+            for hunk in hunks.iter().rev() {
+                repo.stage_hunk(hunk)
+                    .with_context(|| format!("Failed to stage hunk in {:?}", path.0))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Removes the staged changes of a buffer's file that touch the given rows of the buffer
+    /// from the index.
+    pub fn unstage_hunks(
+        &self,
+        buffer: &Model<Buffer>,
+        rows: Range<u32>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.update_index_for_buffer(buffer, cx, move |repo, path, buffer_text| {
+            let index_text = repo
+                .load_index_text(path)
+                .with_context(|| format!("{:?} is not tracked", path.0))?;
+            let head_text = repo.load_committed_text(path).unwrap_or_default();
+            let hunks =
+                staging::hunks_to_unstage(path, &head_text, &index_text, buffer_text, rows)?;
+            for hunk in hunks.iter().rev() {
+                repo.unstage_hunk(hunk)
+                    .with_context(|| format!("Failed to unstage hunk in {:?}", path.0))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs `update` on the index entry of a buffer's file in the background, and then diffs
+    /// the buffer against the updated index without waiting for the repository to be rescanned.
+    fn update_index_for_buffer(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+        update: impl 'static + Send + FnOnce(&dyn GitRepository, &RepoPath, &str) -> Result<()>,
+    ) -> Task<Result<()>> {
+        let params = self.local_repository_for_buffer(buffer, cx);
+        let buffer_text = buffer.read(cx).text();
+        let buffer = buffer.clone();
+        let client = self.client.clone();
+        let remote_id = self.remote_id();
+        cx.spawn(|_, mut cx| async move {
+            let (repo, path) = params?;
+            let diff_base = cx
+                .background_executor()
+                .spawn(async move {
+                    update(repo.as_ref(), &path, &buffer_text)?;
+                    anyhow::Ok(repo.load_index_text(&path))
+                })
+                .await?;
+            let buffer_id = buffer.update(&mut cx, |buffer, cx| {
+                buffer.set_diff_base(diff_base.clone(), cx);
+                buffer.remote_id().into()
+            })?;
+            if let Some(project_id) = remote_id {
+                client
+                    .send(proto::UpdateDiffBase {
+                        project_id,
+                        buffer_id,
+                        diff_base,
+                    })
+                    .log_err();
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the rows of a buffer whose changes since HEAD have been staged, fully or in part.
+    /// Buffers outside of a local repository have no staged rows.
+    pub fn staged_hunks(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<(Range<u32>, StagedStatus)>>> {
+        let params = self.local_repository_for_buffer(buffer, cx);
+        let buffer_text = buffer.read(cx).text();
+        cx.background_executor().spawn(async move {
+            let Ok((repo, path)) = params else {
+                return Ok(Vec::new());
+            };
+            let Some(index_text) = repo.load_index_text(&path) else {
+                return Ok(Vec::new());
+            };
+            let head_text = repo.load_committed_text(&path).unwrap_or_default();
+            staging::staged_hunks(&path, &head_text, &index_text, &buffer_text)
+        })
+    }
+
+    /// Returns the repository containing a buffer's file, and the file's path relative to the
+    /// repository's working directory.
+    pub fn local_repository_for_buffer(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Result<(Arc<dyn GitRepository>, RepoPath)> {
+        if !self.is_local() {
+            anyhow::bail!("git operations are only supported in local projects");
+        }
+        let buffer_project_path = buffer
+            .read(cx)
+            .project_path(cx)
+            .context("failed to get buffer project path")?;
+        let worktree = self
+            .worktree_for_id(buffer_project_path.worktree_id, cx)
+            .context("failed to get worktree")?
+            .read(cx)
+            .as_local()
+            .context("worktree was not local")?
+            .snapshot();
+        let (repo_entry, local_repo_entry) = worktree
+            .repo_for_path(&buffer_project_path.path)
+            .ok_or(NoRepositoryError {})?;
+        let relative_path = repo_entry
+            .relativize(&worktree, &buffer_project_path.path)
+            .context("failed to relativize buffer path")?;
+        Ok((local_repo_entry.repo().clone(), relative_path))
+    }
+
     // RPC message handlers
 
     async fn handle_blame_buffer(
@@ -9225,7 +9682,10 @@ impl Project {
             match this.opened_buffers.entry(buffer_id) {
                 hash_map::Entry::Occupied(mut e) => match e.get_mut() {
                     OpenBuffer::Strong(buffer) => {
-                        buffer.update(cx, |buffer, cx| buffer.apply_ops(ops, cx))?;
+                        let buffer = buffer.clone();
+                        let version_before = buffer.read(cx).version();
+                        buffer.update(cx, |buffer, cx| buffer.apply_ops(ops.clone(), cx))?;
+                        this.detect_offline_edit_conflicts(&buffer, &ops, &version_before, cx);
                     }
                     OpenBuffer::Operations(operations) => operations.extend_from_slice(&ops),
                     OpenBuffer::Weak(_) => {}
@@ -10157,7 +10617,17 @@ impl Project {
         mut cx: AsyncAppContext,
     ) -> Result<proto::SearchProjectResponse> {
         let peer_id = envelope.original_sender_id()?;
-        let query = SearchQuery::from_proto(envelope.payload)?;
+        let query = match envelope.payload.structural_language.clone() {
+            Some(language_name) => {
+                let language = this
+                    .update(&mut cx, |this, _| {
+                        this.languages.language_for_name(&language_name)
+                    })?
+                    .await?;
+                SearchQuery::structural_from_proto(envelope.payload, language)?
+            }
+            None => SearchQuery::from_proto(envelope.payload)?,
+        };
         let mut result = this.update(&mut cx, |this, cx| this.search(query, cx))?;
 
         cx.spawn(move |mut cx| async move {
@@ -11334,82 +11804,38 @@ fn deserialize_code_actions(code_actions: &HashMap<String, bool>) -> Vec<lsp::Co
         .collect()
 }
 
-#[allow(clippy::too_many_arguments)]
-async fn search_snapshots(
-    snapshots: &Vec<(Snapshot, WorktreeSettings)>,
-    worker_start_ix: usize,
-    worker_end_ix: usize,
+/// Sends the files that contain a match of the query, out of files in the query's scope.
+async fn search_candidates(
+    candidates: &[(&Snapshot, &Entry)],
     query: &SearchQuery,
     results_tx: &Sender<SearchMatchCandidate>,
-    opened_buffers: &HashMap<Arc<Path>, (Model<Buffer>, BufferSnapshot)>,
-    include_root: bool,
     fs: &Arc<dyn Fs>,
 ) {
-    let mut snapshot_start_ix = 0;
     let mut abs_path = PathBuf::new();
-
-    for (snapshot, _) in snapshots {
-        let snapshot_end_ix = snapshot_start_ix
-            + if query.include_ignored() {
-                snapshot.file_count()
-            } else {
-                snapshot.visible_file_count()
-            };
-        if worker_end_ix <= snapshot_start_ix {
+    for (snapshot, entry) in candidates {
+        if results_tx.is_closed() {
             break;
-        } else if worker_start_ix > snapshot_end_ix {
-            snapshot_start_ix = snapshot_end_ix;
-            continue;
-        } else {
-            let start_in_snapshot = worker_start_ix.saturating_sub(snapshot_start_ix);
-            let end_in_snapshot = cmp::min(worker_end_ix, snapshot_end_ix) - snapshot_start_ix;
-
-            for entry in snapshot
-                .files(false, start_in_snapshot)
-                .take(end_in_snapshot - start_in_snapshot)
-            {
-                if results_tx.is_closed() {
-                    break;
-                }
-                if opened_buffers.contains_key(&entry.path) {
-                    continue;
-                }
-
-                let matched_path = if include_root {
-                    let mut full_path = PathBuf::from(snapshot.root_name());
-                    full_path.push(&entry.path);
-                    query.file_matches(Some(&full_path))
-                } else {
-                    query.file_matches(Some(&entry.path))
-                };
+        }
 
-                let matches = if matched_path {
-                    abs_path.clear();
-                    abs_path.push(&snapshot.abs_path());
-                    abs_path.push(&entry.path);
-                    if let Some(file) = fs.open_sync(&abs_path).await.log_err() {
-                        query.detect(file).unwrap_or(false)
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                };
+        abs_path.clear();
+        abs_path.push(&snapshot.abs_path());
+        abs_path.push(&entry.path);
+        let matches = if let Some(file) = fs.open_sync(&abs_path).await.log_err() {
+            query.detect(file).unwrap_or(false)
+        } else {
+            false
+        };
 
-                if matches {
-                    let project_path = SearchMatchCandidate::Path {
-                        worktree_id: snapshot.id(),
-                        path: entry.path.clone(),
-                        is_ignored: entry.is_ignored,
-                        is_file: entry.is_file(),
-                    };
-                    if results_tx.send(project_path).await.is_err() {
-                        return;
-                    }
-                }
+        if matches {
+            let project_path = SearchMatchCandidate::Path {
+                worktree_id: snapshot.id(),
+                path: entry.path.clone(),
+                is_ignored: entry.is_ignored,
+                is_file: entry.is_file(),
+            };
+            if results_tx.send(project_path).await.is_err() {
+                return;
             }
-
-            snapshot_start_ix = snapshot_end_ix;
         }
     }
 }
@@ -12084,6 +12510,23 @@ fn deserialize_blame_buffer_response(response: proto::BlameBufferResponse) -> gi
     }
 }
 
+/// Joins the hovers of a buffer's language servers into one, separating them with horizontal
+/// rules and keeping the range of the first hover that has one.
+fn merge_hovers(hovers: Vec<Hover>) -> Option<Hover> {
+    let mut hovers = hovers.into_iter();
+    let mut merged = hovers.next()?;
+    for hover in hovers {
+        merged.range = merged.range.or(hover.range);
+        merged.language = merged.language.or(hover.language);
+        merged.contents.push(HoverBlock {
+            text: "---".to_string(),
+            kind: HoverBlockKind::Markdown,
+        });
+        merged.contents.extend(hover.contents);
+    }
+    Some(merged)
+}
+
 fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     hover
         .contents
@@ -1,9 +1,11 @@
 use collections::HashMap;
 use gpui::AppContext;
+use lsp::LspRestartPolicy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
-use std::{sync::Arc, time::Duration};
+use std::{path::Path, sync::Arc, time::Duration};
+use util::paths::PathMatcher;
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectSettings {
@@ -51,6 +53,12 @@ pub struct GitSettings {
     ///
     /// Default: on
     pub inline_blame: Option<InlineBlameSettings>,
+    /// Whether or not to show the git blame of each line in the gutter of
+    /// editors when they're opened. It can still be toggled with
+    /// `editor::ToggleGitBlame`.
+    ///
+    /// Default: false
+    pub show_blame: Option<bool>,
 }
 
 impl GitSettings {
@@ -62,6 +70,10 @@ impl GitSettings {
         }
     }
 
+    pub fn show_blame(&self) -> bool {
+        self.show_blame.unwrap_or(false)
+    }
+
     pub fn inline_blame_delay(&self) -> Option<Duration> {
         match self.inline_blame {
             Some(InlineBlameSettings {
@@ -120,6 +132,97 @@ pub struct LspSettings {
     pub binary: Option<BinarySettings>,
     pub initialization_options: Option<serde_json::Value>,
     pub settings: Option<serde_json::Value>,
+    /// When to restart the language server after it crashes.
+    pub restart_policy: Option<LspRestartPolicySettings>,
+    /// How many characters of a file can be edited before its cached semantic tokens are
+    /// dropped and requested in full, rather than as changes to the cached ones.
+    ///
+    /// Default: 2048
+    pub semantic_tokens_edit_threshold: Option<usize>,
+    /// Diagnostics from the language server to drop before they are shown.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub diagnostic_filters: Vec<DiagnosticFilter>,
+}
+
+impl LspSettings {
+    pub fn restart_policy(&self) -> LspRestartPolicy {
+        let default = LspRestartPolicy::default();
+        let Some(settings) = self.restart_policy.as_ref() else {
+            return default;
+        };
+        LspRestartPolicy {
+            max_restarts: settings.max_restarts.unwrap_or(default.max_restarts),
+            cooldown: settings
+                .cooldown_ms
+                .map_or(default.cooldown, Duration::from_millis),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct LspRestartPolicySettings {
+    /// How many times to restart the language server after it crashes, until it is
+    /// restarted by hand. Set to 0 to never restart it.
+    ///
+    /// Default: 3
+    pub max_restarts: Option<u8>,
+    /// How long the language server must have been running since its last crash, in
+    /// milliseconds, to be restarted after crashing again.
+    ///
+    /// Default: 10000
+    pub cooldown_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct DiagnosticFilter {
+    /// The files the filter applies to, relative to the worktree root, e.g. `"vendor/**"`.
+    pub glob: String,
+    /// The diagnostic codes to drop in matching files.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub codes: Vec<String>,
+    /// The least severe diagnostics to keep in matching files. Less severe ones are dropped.
+    ///
+    /// Default: hint
+    #[serde(default)]
+    pub min_severity: DiagnosticSeveritySetting,
+}
+
+impl DiagnosticFilter {
+    /// Whether the filter applies to a file, given its path relative to the worktree root.
+    pub fn matches_path(&self, path: &Path) -> bool {
+        PathMatcher::new(&[self.glob.clone()]).map_or(false, |matcher| matcher.is_match(path))
+    }
+
+    /// Whether a diagnostic in a matching file should be dropped.
+    pub fn suppresses(&self, code: Option<&str>, severity: lsp::DiagnosticSeverity) -> bool {
+        severity > self.min_severity.to_lsp()
+            || code.map_or(false, |code| self.codes.iter().any(|c| c == code))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeveritySetting {
+    Error,
+    Warning,
+    Info,
+    #[default]
+    Hint,
+}
+
+impl DiagnosticSeveritySetting {
+    pub fn to_lsp(self) -> lsp::DiagnosticSeverity {
+        match self {
+            Self::Error => lsp::DiagnosticSeverity::ERROR,
+            Self::Warning => lsp::DiagnosticSeverity::WARNING,
+            Self::Info => lsp::DiagnosticSeverity::INFORMATION,
+            Self::Hint => lsp::DiagnosticSeverity::HINT,
+        }
+    }
 }
 
 impl Settings for ProjectSettings {
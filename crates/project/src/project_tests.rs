@@ -1220,6 +1220,46 @@ async fn test_disk_based_diagnostics_progress(cx: &mut gpui::TestAppContext) {
     assert_eq!(futures::poll!(events.next()), Poll::Pending);
 }
 
+#[gpui::test]
+async fn test_restarting_crashed_server(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            name: "the-language-server",
+            ..Default::default()
+        },
+    );
+
+    let _buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let mut fake_server = fake_servers.next().await.unwrap();
+
+    // A server that stays up for longer than the cooldown before each crash is restarted
+    // every time, even after more crashes than the maximum number of restarts.
+    let policy = lsp::LspRestartPolicy::default();
+    for _ in 0..policy.max_restarts + 2 {
+        cx.executor().advance_clock(policy.cooldown * 2);
+        fake_server.simulate_crash();
+        fake_server = fake_servers.next().await.unwrap();
+    }
+
+    // A server that keeps crashing right after starting is left stopped.
+    fake_server.simulate_crash();
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+}
+
 #[gpui::test]
 async fn test_restarting_server_with_diagnostics_running(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1299,6 +1339,135 @@ async fn test_restarting_server_with_diagnostics_running(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_diagnostic_filters(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "src": { "a.rs": "one two three" },
+            "vendor": { "b.rs": "one two three" },
+        }),
+    )
+    .await;
+
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.lsp.insert(
+                    Arc::from("the-fake-language-server"),
+                    LspSettings {
+                        diagnostic_filters: vec![
+                            DiagnosticFilter {
+                                glob: "**/*.rs".into(),
+                                codes: vec!["unused".into()],
+                                min_severity: Default::default(),
+                            },
+                            DiagnosticFilter {
+                                glob: "vendor/**".into(),
+                                codes: Vec::new(),
+                                min_severity: project_settings::DiagnosticSeveritySetting::Error,
+                            },
+                        ],
+                        ..Default::default()
+                    },
+                );
+            });
+        })
+    });
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers =
+        language_registry.register_fake_lsp_adapter("Rust", FakeLspAdapter::default());
+
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/src/a.rs", cx)
+        })
+        .await
+        .unwrap();
+    let buffer_b = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/vendor/b.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let diagnostic = |column, severity, code: &str, message: &str| lsp::Diagnostic {
+        range: lsp::Range::new(
+            lsp::Position::new(0, column),
+            lsp::Position::new(0, column + 3),
+        ),
+        severity: Some(severity),
+        code: Some(NumberOrString::String(code.into())),
+        message: message.into(),
+        ..Default::default()
+    };
+    let diagnostics = vec![
+        diagnostic(0, lsp::DiagnosticSeverity::ERROR, "mismatch", "an error"),
+        diagnostic(
+            4,
+            lsp::DiagnosticSeverity::WARNING,
+            "unused",
+            "an unused value",
+        ),
+        diagnostic(
+            8,
+            lsp::DiagnosticSeverity::WARNING,
+            "style",
+            "a style warning",
+        ),
+    ];
+    let fake_server = fake_servers.next().await.unwrap();
+    for path in ["/dir/src/a.rs", "/dir/vendor/b.rs"] {
+        fake_server.notify::<lsp::notification::PublishDiagnostics>(
+            lsp::PublishDiagnosticsParams {
+                uri: Url::from_file_path(path).unwrap(),
+                version: None,
+                diagnostics: diagnostics.clone(),
+            },
+        );
+    }
+    cx.executor().run_until_parked();
+
+    let messages = |buffer: &Model<Buffer>, cx: &mut gpui::TestAppContext| {
+        buffer.update(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+                .map(|entry| entry.diagnostic.message.clone())
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(messages(&buffer_a, cx), ["an error", "a style warning"]);
+    assert_eq!(messages(&buffer_b, cx), ["an error"]);
+    project.update(cx, |project, cx| {
+        assert_eq!(project.suppressed_diagnostic_count(cx), 3);
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 2,
+                warning_count: 1,
+            }
+        );
+    });
+
+    // Once the server clears the diagnostics, nothing is suppressed anymore.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: Url::from_file_path("/dir/vendor/b.rs").unwrap(),
+        version: None,
+        diagnostics: Vec::new(),
+    });
+    cx.executor().run_until_parked();
+    project.update(cx, |project, cx| {
+        assert_eq!(project.suppressed_diagnostic_count(cx), 1);
+    });
+}
+
 #[gpui::test]
 async fn test_restarting_server_with_diagnostics_published(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2945,6 +3114,57 @@ async fn test_save_in_single_file_worktree(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_file_associations(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".zed": {
+                "settings.json": r#"{
+                    "file_associations": {
+                        "*.tmpl": "json",
+                        "Brewfile": "rust"
+                    }
+                }"#,
+            },
+            "a.tmpl": "",
+            "b.rs": "",
+            "c.txt": "",
+            "Brewfile": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let languages = project.update(cx, |project, _| project.languages().clone());
+    languages.add(json_lang());
+    languages.add(rust_lang());
+    cx.executor().run_until_parked();
+
+    for (path, expected_language) in [
+        ("/dir/a.tmpl", Some("JSON")),
+        ("/dir/b.rs", Some("Rust")),
+        ("/dir/c.txt", None),
+        ("/dir/Brewfile", Some("Rust")),
+    ] {
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        cx.executor().run_until_parked();
+        buffer.update(cx, |buffer, _| {
+            assert_eq!(
+                buffer.language().map(|language| language.name()),
+                expected_language.map(Arc::from),
+                "unexpected language for {path}"
+            );
+        });
+    }
+}
+
 #[gpui::test]
 async fn test_save_as(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3250,6 +3470,58 @@ async fn test_buffer_deduping(cx: &mut gpui::TestAppContext) {
     assert_eq!(buffer_a_3.entity_id(), buffer_a_id);
 }
 
+#[gpui::test]
+async fn test_annotation_threads_of_released_buffers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.txt": "one\ntwo\nthree" }))
+        .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.txt", cx))
+        .await
+        .unwrap();
+    let anchor = buffer.update(cx, |buffer, _| buffer.anchor_before(Point::new(1, 1)));
+    let thread_id = project
+        .update(cx, |project, cx| {
+            project.create_annotation_thread(&buffer, anchor, "Rename this?".into(), cx)
+        })
+        .await
+        .unwrap();
+
+    // The thread is detached from the buffer when it's released.
+    let buffer_id = buffer.update(cx, |buffer, _| buffer.remote_id());
+    drop(buffer);
+    cx.executor().run_until_parked();
+    project.update(cx, |project, _| {
+        assert!(project.annotation_threads(buffer_id).is_none());
+    });
+
+    // Opening the file again restores the thread at the same position.
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.txt", cx))
+        .await
+        .unwrap();
+    let buffer_id = buffer.update(cx, |buffer, _| buffer.remote_id());
+    project.update(cx, |project, cx| {
+        let thread = project
+            .annotation_threads(buffer_id)
+            .and_then(|threads| threads.get(thread_id))
+            .unwrap();
+        assert_eq!(thread.anchor.to_point(buffer.read(cx)), Point::new(1, 1));
+        assert_eq!(
+            thread
+                .comments
+                .iter()
+                .map(|comment| comment.body.as_str())
+                .collect::<Vec<_>>(),
+            ["Rename this?"]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_buffer_is_dirty(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3983,6 +4255,58 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "fn one() { foo(1); bar(2); }",
+            "two.rs": "fn two() { bar(foo(2), 3); }",
+            "three.ts": "foo(3);",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language = rust_lang();
+    project.update(cx, |project, _| project.languages().add(language.clone()));
+
+    let query = SearchQuery::structural(
+        r#"(call_expression
+            function: (identifier) @name
+            arguments: (arguments) @args
+            (#eq? @name "foo"))"#,
+        language,
+        false,
+        Default::default(),
+        Default::default(),
+    )
+    .unwrap()
+    .with_replacement("baz@args".to_string());
+    assert_eq!(
+        search(&project, query.clone(), cx).await.unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![11..17]),
+            ("dir/two.rs".to_string(), vec![15..21]),
+        ])
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/one.rs", cx)
+        })
+        .await
+        .unwrap();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(
+        query.structural_replacement_for(&snapshot, 11..17),
+        Some("baz(1)".to_string())
+    );
+    assert_eq!(query.structural_replacement_for(&snapshot, 19..25), None);
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4402,6 +4726,126 @@ async fn test_search_multiple_worktrees_with_inclusions(cx: &mut gpui::TestAppCo
     );
 }
 
+#[gpui::test]
+async fn test_search_excludes_files_out_of_scope(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "src": {
+                "main.rs": "// NEEDLE",
+                "generated": {
+                    "bindings.rs": "// NEEDLE",
+                },
+            },
+            "tests": {
+                "test.rs": "// NEEDLE",
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    // Excluding a directory excludes the files in it, and globs may start with the name of the
+    // worktree's root.
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::text(
+                "NEEDLE",
+                false,
+                true,
+                false,
+                PathMatcher::new(&["*.rs".to_owned()]).unwrap(),
+                PathMatcher::new(&["src/generated".to_owned(), "dir/tests/**".to_owned()]).unwrap(),
+            )
+            .unwrap(),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("dir/src/main.rs".to_string(), vec![3..9])]),
+    );
+}
+
+#[gpui::test]
+async fn test_stage_and_unstage_hunks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "one\nTWO\nthree\nFOUR\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new("/dir/.git");
+    fs.set_head_for_repo(
+        dot_git,
+        &[(Path::new("a.txt"), "one\ntwo\nthree\nfour\n".into())],
+    );
+    fs.set_index_for_repo(
+        dot_git,
+        &[(Path::new("a.txt"), "one\ntwo\nthree\nfour\n".into())],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let index_text = |fs: &FakeFs| {
+        let mut text = None;
+        fs.with_git_state(dot_git, false, |state| {
+            text = state.index_contents.get(Path::new("a.txt")).cloned();
+        });
+        text.unwrap()
+    };
+    let staged_hunks = |cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| project.staged_hunks(&buffer, cx))
+    };
+
+    // Only the hunk touching the given rows is staged.
+    project
+        .update(cx, |project, cx| project.stage_hunks(&buffer, 3..4, cx))
+        .await
+        .unwrap();
+    assert_eq!(index_text(&fs), "one\ntwo\nthree\nFOUR\n");
+    assert_eq!(
+        staged_hunks(cx).await.unwrap(),
+        [(3..4, StagedStatus::Staged)]
+    );
+
+    // Editing a staged line makes its hunk partially staged.
+    buffer.update(cx, |buffer, cx| buffer.edit([(18..18, "!")], None, cx));
+    assert_eq!(
+        staged_hunks(cx).await.unwrap(),
+        [(3..4, StagedStatus::PartiallyStaged)]
+    );
+
+    project
+        .update(cx, |project, cx| project.unstage_hunks(&buffer, 3..4, cx))
+        .await
+        .unwrap();
+    assert_eq!(index_text(&fs), "one\ntwo\nthree\nfour\n");
+    assert!(staged_hunks(cx).await.unwrap().is_empty());
+
+    // Rows without unstaged changes have nothing to stage.
+    project
+        .update(cx, |project, cx| project.stage_hunks(&buffer, 0..1, cx))
+        .await
+        .unwrap();
+    assert_eq!(index_text(&fs), "one\ntwo\nthree\nfour\n");
+}
+
 #[gpui::test]
 async fn test_search_in_gitignored_dirs(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4737,14 +5181,13 @@ async fn test_multiple_language_server_hovers(cx: &mut gpui::TestAppContext) {
     ))
     .await;
     assert_eq!(
-        vec!["TailwindServer hover", "TypeScriptServer hover"],
+        vec!["TypeScriptServer hover|---|TailwindServer hover"],
         hover_task
             .await
             .into_iter()
             .map(|hover| hover.contents.iter().map(|block| &block.text).join("|"))
-            .sorted()
             .collect::<Vec<_>>(),
-        "Should receive hover responses from all related servers with hover capabilities"
+        "Should merge the hovers of all servers with hover capabilities, in server order"
     );
 }
 
@@ -4973,6 +5416,112 @@ async fn test_multiple_language_server_actions(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_multiple_language_server_completions_and_definitions(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.tsx": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(tsx_lang());
+    let capabilities = lsp::ServerCapabilities {
+        completion_provider: Some(lsp::CompletionOptions::default()),
+        definition_provider: Some(lsp::OneOf::Left(true)),
+        ..lsp::ServerCapabilities::default()
+    };
+    let mut fake_tsx_language_servers = language_registry.register_specific_fake_lsp_adapter(
+        "tsx",
+        true,
+        FakeLspAdapter {
+            name: "TypeScriptServer",
+            capabilities: capabilities.clone(),
+            ..FakeLspAdapter::default()
+        },
+    );
+    let _eslint = language_registry.register_specific_fake_lsp_adapter(
+        "tsx",
+        false,
+        FakeLspAdapter {
+            name: "ESLintServer",
+            capabilities,
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.tsx", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    for _ in 0..2 {
+        let server = fake_tsx_language_servers.next().await.unwrap();
+        let is_typescript = server.server.name() == "TypeScriptServer";
+        server.handle_request::<lsp::request::Completion, _, _>(move |_, _| async move {
+            let items = if is_typescript {
+                vec!["foo", "bar"]
+            } else {
+                vec!["foo", "baz"]
+            };
+            Ok(Some(lsp::CompletionResponse::Array(
+                items
+                    .into_iter()
+                    .map(|label| lsp::CompletionItem {
+                        label: label.to_string(),
+                        ..lsp::CompletionItem::default()
+                    })
+                    .collect(),
+            )))
+        });
+        server.handle_request::<lsp::request::GotoDefinition, _, _>(move |_, _| async move {
+            if is_typescript {
+                return Ok(None);
+            }
+            Ok(Some(lsp::GotoDefinitionResponse::Scalar(
+                lsp::Location::new(
+                    lsp::Url::from_file_path("/dir/a.tsx").unwrap(),
+                    lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 1)),
+                ),
+            )))
+        });
+    }
+
+    // Completions from every server are concatenated, keeping the first one for each text.
+    let completions = project
+        .update(cx, |project, cx| {
+            project.completions(&buffer, 1, DEFAULT_COMPLETION_CONTEXT, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        completions
+            .iter()
+            .map(|completion| completion.new_text.as_str())
+            .collect::<Vec<_>>(),
+        ["foo", "bar", "baz"]
+    );
+
+    // The first server with a definition provides it.
+    let definitions = project
+        .update(cx, |project, cx| project.definition(&buffer, 0, cx))
+        .await
+        .unwrap();
+    assert_eq!(definitions.len(), 1);
+    cx.update(|cx| {
+        let target = &definitions[0].target;
+        assert_eq!(target.range.to_offset(target.buffer.read(cx)), 0..1);
+    });
+}
+
 #[gpui::test]
 async fn test_reordering_worktrees(cx: &mut gpui::TestAppContext) {
     init_test(cx);
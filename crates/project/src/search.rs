@@ -1,7 +1,7 @@
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::Result;
 use client::proto;
-use language::{char_kind, BufferSnapshot};
+use language::{char_kind, BufferSnapshot, Language, StructuralQuery};
 use regex::{Captures, Regex, RegexBuilder};
 use smol::future::yield_now;
 use std::{
@@ -11,7 +11,8 @@ use std::{
     path::Path,
     sync::{Arc, OnceLock},
 };
-use util::paths::PathMatcher;
+use util::paths::{PathExt, PathMatcher};
+use worktree::SearchScope;
 
 static TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX: OnceLock<Regex> = OnceLock::new();
 
@@ -53,6 +54,14 @@ pub enum SearchQuery {
         include_ignored: bool,
         inner: SearchInputs,
     },
+
+    /// Matches syntax nodes of a single language using a tree-sitter query pattern.
+    Structural {
+        query: Arc<StructuralQuery>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 impl SearchQuery {
@@ -122,6 +131,43 @@ impl SearchQuery {
         })
     }
 
+    pub fn structural(
+        pattern: impl ToString,
+        language: Arc<Language>,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+    ) -> Result<Self> {
+        let pattern = pattern.to_string();
+        let query = StructuralQuery::new(language, &pattern)?;
+        let inner = SearchInputs {
+            query: pattern.into(),
+            files_to_exclude,
+            files_to_include,
+        };
+        Ok(Self::Structural {
+            query: Arc::new(query),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
+    /// Deserializes a structural search query, whose language was loaded by name from the
+    /// message's `structural_language`.
+    pub fn structural_from_proto(
+        message: proto::SearchProject,
+        language: Arc<Language>,
+    ) -> Result<Self> {
+        Self::structural(
+            message.query,
+            language,
+            message.include_ignored,
+            deserialize_path_matches(&message.files_to_include)?,
+            deserialize_path_matches(&message.files_to_exclude)?,
+        )
+    }
+
     pub fn from_proto(message: proto::SearchProject) -> Result<Self> {
         if message.regex {
             Self::regex(
@@ -152,6 +198,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -168,6 +218,9 @@ impl SearchQuery {
             include_ignored: self.include_ignored(),
             files_to_include: self.files_to_include().sources().join(","),
             files_to_exclude: self.files_to_exclude().sources().join(","),
+            structural_language: self
+                .structural_query()
+                .map(|query| query.language().name().to_string()),
         }
     }
 
@@ -206,17 +259,22 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            // Files are only filtered by their language, as matching requires parsing them.
+            Self::Structural { .. } => Ok(true),
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
+    ///
+    /// Structural replacements depend on the syntax tree, so they're computed by
+    /// [`SearchQuery::structural_replacement_for`] instead.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
             SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
@@ -239,9 +297,31 @@ impl SearchQuery {
                     None
                 }
             }
+            SearchQuery::Structural { .. } => None,
         }
     }
 
+    /// Expands the replacement template of a structural query for the match at the given range
+    /// of the buffer, substituting the references to the query's captures.
+    pub fn structural_replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        let SearchQuery::Structural {
+            query, replacement, ..
+        } = self
+        else {
+            return None;
+        };
+        let replacement = replacement.as_ref()?;
+        let mat = buffer
+            .structural_matches(query, range.clone())
+            .into_iter()
+            .find(|mat| mat.range == range)?;
+        Some(query.expand_replacement(replacement, &mat, buffer))
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -326,6 +406,16 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { query, .. } => {
+                let range = range_offset..range_offset + rope.len();
+                matches.extend(
+                    buffer
+                        .structural_matches(query, range)
+                        .into_iter()
+                        .map(|mat| mat.range.start - range_offset..mat.range.end - range_offset),
+                );
+            }
         }
 
         matches
@@ -343,6 +433,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -350,6 +441,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -361,6 +453,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -368,6 +463,17 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
+    pub fn structural_query(&self) -> Option<&Arc<StructuralQuery>> {
+        match self {
+            Self::Structural { query, .. } => Some(query),
+            _ => None,
+        }
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -376,9 +482,38 @@ impl SearchQuery {
         self.as_inner().files_to_exclude()
     }
 
+    /// The scope of the files that the query includes and excludes.
+    pub fn scope(&self) -> SearchScope {
+        SearchScope {
+            include_globs: self.files_to_include().sources().to_vec(),
+            exclude_globs: self.files_to_exclude().sources().to_vec(),
+        }
+    }
+
+    /// Whether a file can be searched by the query, which is only false for files that a
+    /// structural query's language doesn't apply to.
+    pub fn language_matches(&self, file_path: &Path) -> bool {
+        let Some(query) = self.structural_query() else {
+            return true;
+        };
+        let path_suffixes = [
+            file_path.extension_or_hidden_file_name(),
+            file_path.file_name().and_then(|name| name.to_str()),
+        ];
+        query
+            .language()
+            .path_suffixes()
+            .iter()
+            .any(|suffix| path_suffixes.contains(&Some(suffix.as_str())))
+    }
+
     pub fn file_matches(&self, file_path: Option<&Path>) -> bool {
         match file_path {
             Some(file_path) => {
+                if !self.language_matches(file_path) {
+                    return false;
+                }
+
                 let mut path = file_path.to_path_buf();
                 loop {
                     if self.files_to_exclude().is_match(&path) {
@@ -397,7 +532,9 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 }
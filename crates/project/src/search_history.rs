@@ -84,6 +84,28 @@ impl SearchHistory {
         Some(&self.history[next_index])
     }
 
+    /// The queries in the history, from the oldest to the most recent one.
+    pub fn queries(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Inserts queries before the ones already in the history, e.g. when restoring the history
+    /// of a previous session. The oldest queries are dropped when the history gets longer than
+    /// its maximum length.
+    ///
+    /// Note: This shifts the queries already in the history, so existing cursors may point to
+    /// the wrong query afterwards.
+    pub fn restore(&mut self, queries: impl IntoIterator<Item = String>) {
+        let mut history = queries.into_iter().collect::<Vec<_>>();
+        history.append(&mut self.history);
+        if let Some(max_history_len) = self.max_history_len {
+            if history.len() > max_history_len {
+                history.drain(..history.len() - max_history_len);
+            }
+        }
+        self.history = history;
+    }
+
     pub fn current(&self, cursor: &SearchHistoryCursor) -> Option<&str> {
         cursor
             .selection
@@ -229,6 +251,31 @@ mod tests {
         assert_eq!(search_history.current(&cursor), None);
     }
 
+    #[test]
+    fn test_restore() {
+        let mut search_history = SearchHistory::new(Some(3), QueryInsertionBehavior::AlwaysInsert);
+        let mut cursor = SearchHistoryCursor::default();
+
+        search_history.add(&mut cursor, "TypeScript".to_string());
+        search_history.restore(["Rust".to_string(), "JavaScript".to_string()]);
+        assert_eq!(
+            search_history.queries(),
+            ["Rust", "JavaScript", "TypeScript"],
+            "Restored queries should come before the ones added in this session"
+        );
+
+        search_history.restore(["Python".to_string()]);
+        assert_eq!(
+            search_history.queries(),
+            ["Rust", "JavaScript", "TypeScript"],
+            "The oldest queries should be dropped when exceeding the maximum length"
+        );
+
+        cursor.reset();
+        assert_eq!(search_history.previous(&mut cursor), Some("TypeScript"));
+        assert_eq!(search_history.previous(&mut cursor), Some("JavaScript"));
+    }
+
     #[test]
     fn test_multiple_cursors() {
         let mut search_history = SearchHistory::new(None, QueryInsertionBehavior::AlwaysInsert);
@@ -15,7 +15,7 @@ use std::{
 };
 use task::{SpawnInTerminal, TerminalWorkDir};
 use terminal::{
-    terminal_settings::{self, Shell, TerminalSettings, VenvSettingsContent},
+    terminal_settings::{self, Shell, TerminalProfile, TerminalSettings, VenvSettingsContent},
     TaskState, TaskStatus, Terminal, TerminalBuilder,
 };
 use util::ResultExt;
@@ -69,6 +69,7 @@ impl Project {
         &mut self,
         working_directory: Option<TerminalWorkDir>,
         spawn_task: Option<SpawnInTerminal>,
+        profile: Option<TerminalProfile>,
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
     ) -> anyhow::Result<Model<Terminal>> {
@@ -98,7 +99,21 @@ impl Project {
         let python_settings = settings.detect_venv.clone();
         let (completion_tx, completion_rx) = bounded(1);
 
+        // Profiles only apply to terminals that run a local shell, not to tasks or remote shells.
+        let (profile, working_directory) = if is_terminal {
+            let default_profile = settings
+                .default_profile
+                .as_deref()
+                .and_then(|name| settings.profile(name));
+            profile_and_working_directory(profile, default_profile, working_directory)
+        } else {
+            (None, working_directory)
+        };
+
         let mut env = settings.env.clone();
+        if let Some(profile) = &profile {
+            env.extend(profile.env.clone());
+        }
         // Alacritty uses parent project's working directory when no working directory is provided
         // https://github.com/alacritty/alacritty/blob/fd1a3cc79192d1d03839f0fd8c72e1f8d0fce42e/extra/man/alacritty.5.scd?plain=1#L47-L52
 
@@ -162,7 +177,10 @@ impl Project {
                         },
                     )
                 } else {
-                    (None, settings.shell.clone())
+                    let shell = profile
+                        .as_ref()
+                        .map_or_else(|| settings.shell.clone(), TerminalProfile::shell);
+                    (None, shell)
                 }
             }
         };
@@ -183,7 +201,12 @@ impl Project {
             cx,
         )
         .map(|builder| {
-            let terminal_handle = cx.new_model(|cx| builder.subscribe(cx));
+            let color_scheme = profile.and_then(|profile| profile.color_scheme);
+            let terminal_handle = cx.new_model(|cx| {
+                let mut terminal = builder.subscribe(cx);
+                terminal.set_color_scheme(color_scheme);
+                terminal
+            });
 
             self.terminals
                 .local_handles
@@ -380,10 +403,84 @@ fn add_environment_path(env: &mut HashMap<String, String>, new_path: &Path) -> a
     Ok(())
 }
 
+/// Picks the profile that a local shell starts with, and the directory it starts in. The
+/// directory of a profile picked for the terminal replaces the one it was opened in, while the
+/// directory of the default profile only applies to terminals opened without one.
+fn profile_and_working_directory(
+    picked_profile: Option<TerminalProfile>,
+    default_profile: Option<&TerminalProfile>,
+    working_directory: Option<TerminalWorkDir>,
+) -> (Option<TerminalProfile>, Option<TerminalWorkDir>) {
+    let profile_cwd = |profile: &TerminalProfile| profile.cwd.clone().map(TerminalWorkDir::Local);
+    match picked_profile {
+        Some(profile) => {
+            let working_directory = profile_cwd(&profile).or(working_directory);
+            (Some(profile), working_directory)
+        }
+        None => {
+            let working_directory =
+                working_directory.or_else(|| default_profile.and_then(profile_cwd));
+            (default_profile.cloned(), working_directory)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
     use collections::HashMap;
 
+    fn profile(name: &str, cwd: Option<&str>) -> TerminalProfile {
+        TerminalProfile {
+            name: name.to_string(),
+            shell: "zsh".to_string(),
+            args: Vec::new(),
+            env: HashMap::default(),
+            cwd: cwd.map(PathBuf::from),
+            color_scheme: None,
+        }
+    }
+
+    fn local(path: &str) -> Option<TerminalWorkDir> {
+        Some(TerminalWorkDir::Local(PathBuf::from(path)))
+    }
+
+    #[test]
+    fn test_profile_working_directory() {
+        let picked = profile("picked", Some("/picked"));
+        let default = profile("default", Some("/default"));
+
+        // A picked profile's directory replaces the one the terminal was opened in.
+        assert_eq!(
+            profile_and_working_directory(Some(picked.clone()), Some(&default), local("/opened")),
+            (Some(picked.clone()), local("/picked"))
+        );
+        let picked_without_cwd = profile("picked", None);
+        assert_eq!(
+            profile_and_working_directory(
+                Some(picked_without_cwd.clone()),
+                Some(&default),
+                local("/opened")
+            ),
+            (Some(picked_without_cwd), local("/opened"))
+        );
+
+        // The default profile's directory only applies when the terminal was opened without one.
+        assert_eq!(
+            profile_and_working_directory(None, Some(&default), local("/opened")),
+            (Some(default.clone()), local("/opened"))
+        );
+        assert_eq!(
+            profile_and_working_directory(None, Some(&default), None),
+            (Some(default.clone()), local("/default"))
+        );
+
+        assert_eq!(
+            profile_and_working_directory(None, None, local("/opened")),
+            (None, local("/opened"))
+        );
+    }
+
     #[test]
     fn test_add_environment_path_with_existing_path() {
         let tmp_path = std::path::PathBuf::from("/tmp/new");
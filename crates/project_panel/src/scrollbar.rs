@@ -62,7 +62,7 @@ impl gpui::Element for ProjectPanelScrollbar {
         _request_layout: &mut Self::RequestLayoutState,
         cx: &mut ui::WindowContext,
     ) -> Self::PrepaintState {
-        cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+        cx.with_content_mask(Some(ContentMask::new(bounds)), |cx| {
             cx.insert_hitbox(bounds, false)
         })
     }
@@ -75,7 +75,7 @@ impl gpui::Element for ProjectPanelScrollbar {
         _prepaint: &mut Self::PrepaintState,
         cx: &mut ui::WindowContext,
     ) {
-        cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+        cx.with_content_mask(Some(ContentMask::new(bounds)), |cx| {
             let colors = cx.theme().colors();
             let scrollbar_background = colors.scrollbar_track_border;
             let thumb_background = colors.scrollbar_thumb_background;
@@ -346,6 +346,13 @@ messages!(
     (UpdateContext, Foreground),
     (SynchronizeContexts, Foreground),
     (SynchronizeContextsResponse, Foreground),
+    (CreateAnnotationThread, Foreground),
+    (ReplyToAnnotationThread, Foreground),
+    (ResolveAnnotationThread, Foreground),
+    (AnnotationThreadResponse, Foreground),
+    (UpdateAnnotationThread, Foreground),
+    (GetAnnotationThreads, Foreground),
+    (GetAnnotationThreadsResponse, Foreground),
 );
 
 request_messages!(
@@ -461,6 +468,10 @@ request_messages!(
     (RestartLanguageServers, Ack),
     (OpenContext, OpenContextResponse),
     (SynchronizeContexts, SynchronizeContextsResponse),
+    (CreateAnnotationThread, AnnotationThreadResponse),
+    (ReplyToAnnotationThread, AnnotationThreadResponse),
+    (ResolveAnnotationThread, AnnotationThreadResponse),
+    (GetAnnotationThreads, GetAnnotationThreadsResponse),
 );
 
 entity_messages!(
@@ -527,6 +538,11 @@ entity_messages!(
     OpenContext,
     UpdateContext,
     SynchronizeContexts,
+    CreateAnnotationThread,
+    ReplyToAnnotationThread,
+    ResolveAnnotationThread,
+    UpdateAnnotationThread,
+    GetAnnotationThreads,
 );
 
 entity_messages!(
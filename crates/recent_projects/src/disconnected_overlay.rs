@@ -27,6 +27,10 @@ impl ModalView for DisconnectedOverlay {
     fn fade_out_background(&self) -> bool {
         true
     }
+
+    fn dismiss_on_backdrop_click(&self) -> bool {
+        false
+    }
 }
 
 impl DisconnectedOverlay {
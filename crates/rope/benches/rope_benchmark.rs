@@ -171,6 +171,19 @@ fn rope_benchmarks(c: &mut Criterion) {
         });
     }
     group.finish();
+
+    let mut group = c.benchmark_group("write_to");
+    for size in sizes.iter() {
+        group.throughput(Throughput::Bytes(*size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let rope = generate_random_rope(rng.clone(), *size);
+
+            b.iter(|| {
+                rope.write_to(&mut std::io::sink()).unwrap();
+            });
+        });
+    }
+    group.finish();
 }
 
 criterion_group!(benches, rope_benchmarks);
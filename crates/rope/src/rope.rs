@@ -4,6 +4,7 @@ mod point_utf16;
 mod unclipped;
 
 use arrayvec::ArrayString;
+use futures::{AsyncWrite, AsyncWriteExt};
 use smallvec::SmallVec;
 use std::{
     cmp, fmt, io, mem,
@@ -241,6 +242,22 @@ impl Rope {
         Chunks::new(self, range, true)
     }
 
+    /// Writes the rope's contents to `writer` one chunk at a time, without
+    /// first collecting them into a `String`.
+    pub fn write_to<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        for chunk in self.chunks() {
+            writer.write_all(chunk.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub async fn async_write_to<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> io::Result<()> {
+        for chunk in self.chunks() {
+            writer.write_all(chunk.as_bytes()).await?;
+        }
+        Ok(())
+    }
+
     pub fn offset_to_offset_utf16(&self, offset: usize) -> OffsetUtf16 {
         if offset >= self.summary().len {
             return self.summary().len_utf16;
@@ -1388,6 +1405,20 @@ mod tests {
         assert_eq!(lines.next(), None);
     }
 
+    #[test]
+    fn test_write_to() {
+        let text = "abc\ndefg\nhi🍐✅\n".repeat(32);
+        let rope = Rope::from(text.as_str());
+
+        let mut written = Vec::new();
+        rope.write_to(&mut written).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), text);
+
+        let mut written = Vec::new();
+        futures::executor::block_on(rope.async_write_to(&mut written)).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), text);
+    }
+
     #[gpui::test(iterations = 100)]
     fn test_random_rope(mut rng: StdRng) {
         let operations = env::var("OPERATIONS")
@@ -3,7 +3,8 @@ mod registrar;
 use crate::{
     search_bar::render_nav_button, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll,
     ReplaceNext, SearchOptions, SelectAllMatches, SelectNextMatch, SelectPrevMatch,
-    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleWholeWord,
+    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleStructural,
+    ToggleWholeWord,
 };
 use any_vec::AnyVec;
 use collections::HashMap;
@@ -18,6 +19,7 @@ use gpui::{
     ScrollHandle, Styled, Subscription, Task, TextStyle, View, ViewContext, VisualContext as _,
     WhiteSpace, WindowContext,
 };
+use language::Language;
 use project::{
     search::SearchQuery,
     search_history::{SearchHistory, SearchHistoryCursor},
@@ -115,6 +117,7 @@ impl BufferSearchBar {
             },
             font_family: settings.buffer_font.family.clone(),
             font_features: settings.buffer_font.features.clone(),
+            font_axes: settings.buffer_font.axes.clone(),
             font_size: rems(0.875).into(),
             font_weight: settings.buffer_font.weight,
             font_style: FontStyle::Normal,
@@ -151,6 +154,10 @@ impl Render for BufferSearchBar {
             > self.editor_scroll_handle.bounds().size.width - cx.rem_size() * 6.;
 
         let supported_options = self.supported_options();
+        let supports_structural_search = self
+            .active_searchable_item
+            .as_ref()
+            .map_or(false, |item| item.structural_search_language(cx).is_some());
 
         if self.query_editor.update(cx, |query_editor, cx| {
             query_editor.placeholder_text(cx).is_none()
@@ -238,6 +245,14 @@ impl Render for BufferSearchBar {
                                 cx.listener(|this, _, cx| this.toggle_regex(&ToggleRegex, cx)),
                             )
                         }))
+                        .children(supports_structural_search.then(|| {
+                            self.render_search_option_button(
+                                SearchOptions::STRUCTURAL,
+                                cx.listener(|this, _, cx| {
+                                    this.toggle_structural(&ToggleStructural, cx)
+                                }),
+                            )
+                        }))
                     }),
             )
             .when(supported_options.replacement, |this| {
@@ -383,6 +398,9 @@ impl Render for BufferSearchBar {
             .when(self.supported_options().regex, |this| {
                 this.on_action(cx.listener(Self::toggle_regex))
             })
+            .when(supports_structural_search, |this| {
+                this.on_action(cx.listener(Self::toggle_structural))
+            })
             .when(self.supported_options().selection, |this| {
                 this.on_action(cx.listener(Self::toggle_selection))
             })
@@ -888,6 +906,21 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, cx)
     }
 
+    fn toggle_structural(&mut self, _: &ToggleStructural, cx: &mut ViewContext<Self>) {
+        self.toggle_search_option(SearchOptions::STRUCTURAL, cx)
+    }
+
+    /// The language to match structural queries against, if structural search is enabled and
+    /// supported by the active item.
+    fn structural_search_language(&self, cx: &AppContext) -> Option<Arc<Language>> {
+        if !self.search_options.contains(SearchOptions::STRUCTURAL) {
+            return None;
+        }
+        self.active_searchable_item
+            .as_ref()?
+            .structural_search_language(cx)
+    }
+
     fn clear_active_searchable_item_matches(&mut self, cx: &mut WindowContext) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -931,7 +964,23 @@ impl BufferSearchBar {
                 let _ = done_tx.send(());
                 cx.notify();
             } else {
-                let query: Arc<_> = if self.search_options.contains(SearchOptions::REGEX) {
+                let query: Arc<_> = if let Some(language) = self.structural_search_language(cx) {
+                    match SearchQuery::structural(
+                        query,
+                        language,
+                        false,
+                        Default::default(),
+                        Default::default(),
+                    ) {
+                        Ok(query) => query.with_replacement(self.replacement(cx)),
+                        Err(_) => {
+                            self.query_contains_error = true;
+                            self.clear_active_searchable_item_matches(cx);
+                            cx.notify();
+                            return done_rx;
+                        }
+                    }
+                } else if self.search_options.contains(SearchOptions::REGEX) {
                     match SearchQuery::regex(
                         query,
                         self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -0,0 +1,60 @@
+use db::{define_connection, query, sqlez_macros::sql};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection! {
+    pub static ref SEARCH_DB: SearchDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE project_search_history (
+                workspace_id INTEGER PRIMARY KEY,
+                queries TEXT NOT NULL, // A JSON array of strings, from the oldest to the most recent
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            CREATE TABLE project_search_scopes (
+                workspace_id INTEGER PRIMARY KEY,
+                scope TEXT NOT NULL, // A JSON object with the included and excluded globs
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        )];
+}
+
+impl SearchDb {
+    query! {
+        pub async fn save_project_search_history(
+            workspace_id: WorkspaceId,
+            queries: String
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO project_search_history(workspace_id, queries)
+            VALUES (?, ?)
+        }
+    }
+
+    query! {
+        pub fn project_search_history(workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT queries
+            FROM project_search_history
+            WHERE workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_project_search_scope(
+            workspace_id: WorkspaceId,
+            scope: String
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO project_search_scopes(workspace_id, scope)
+            VALUES (?, ?)
+        }
+    }
+
+    query! {
+        pub fn project_search_scope(workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT scope
+            FROM project_search_scopes
+            WHERE workspace_id = ?
+        }
+    }
+}
@@ -1,39 +1,47 @@
 use crate::{
-    FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
-    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
-    ToggleReplace, ToggleWholeWord,
+    persistence::SEARCH_DB, FileSearchResults, FocusSearch, HistoryQuerySelected, NextHistoryQuery,
+    PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchHistoryView, SearchOptions,
+    SearchResultGroup, SearchScopeSelector, SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive,
+    ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleSearchHistory, ToggleWholeWord,
 };
 use collections::{HashMap, HashSet};
 use editor::{
     actions::SelectAll,
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock,
+    },
     items::active_match_index,
     scroll::{Autoscroll, Axis},
-    Anchor, Editor, EditorElement, EditorEvent, EditorSettings, EditorStyle, MultiBuffer,
-    MAX_TAB_TITLE_LEN,
+    Anchor, Editor, EditorElement, EditorEvent, EditorSettings, EditorStyle, FoldPlaceholder,
+    MultiBuffer, MAX_TAB_TITLE_LEN,
 };
 use gpui::{
-    actions, div, Action, AnyElement, AnyView, AppContext, Context as _, Element, EntityId,
-    EventEmitter, FocusHandle, FocusableView, FontStyle, Global, Hsla, InteractiveElement,
-    IntoElement, Model, ModelContext, ParentElement, Point, Render, SharedString, Styled,
-    Subscription, Task, TextStyle, UpdateGlobal, View, ViewContext, VisualContext, WeakModel,
-    WeakView, WhiteSpace, WindowContext,
+    actions, div, impl_actions, Action, AnyElement, AnyView, AppContext, Context as _,
+    DismissEvent, Element, EntityId, EventEmitter, FocusHandle, FocusableView, FontStyle, Global,
+    Hsla, InteractiveElement, IntoElement, Model, ModelContext, MouseButton, ParentElement, Point,
+    Render, SharedString, Styled, Subscription, Task, TextStyle, UpdateGlobal, View, ViewContext,
+    VisualContext, WeakModel, WeakView, WhiteSpace, WindowContext,
 };
 use menu::Confirm;
-use project::{search::SearchQuery, search_history::SearchHistoryCursor, Project, ProjectPath};
+use project::{
+    search::SearchQuery, search_history::SearchHistoryCursor, Project, ProjectPath, SearchScope,
+};
+use serde::Deserialize;
 use settings::Settings;
 use smol::stream::StreamExt;
 use std::{
     any::{Any, TypeId},
     mem,
     ops::{Not, Range},
-    path::Path,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 use theme::ThemeSettings;
 use ui::{
     h_flex, prelude::*, v_flex, Icon, IconButton, IconName, Label, LabelCommon, LabelSize,
     Selectable, Tooltip,
 };
-use util::paths::PathMatcher;
+use util::{paths::PathMatcher, ResultExt};
 use workspace::{
     item::{BreadcrumbText, Item, ItemEvent, ItemHandle, TabContentParams},
     searchable::{Direction, SearchableItem, SearchableItemHandle},
@@ -49,6 +57,12 @@ actions!(
     [SearchInNew, ToggleFocus, NextField, ToggleFilters]
 );
 
+/// Enables or disables grouping the results of a project search by directory.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct GroupByDirectory(pub bool);
+
+impl_actions!(project_search, [GroupByDirectory]);
+
 #[derive(Default)]
 struct ActiveSettings(HashMap<WeakModel<Project>, ProjectSearchSettings>);
 
@@ -56,7 +70,11 @@ impl Global for ActiveSettings {}
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(ActiveSettings::default());
-    cx.observe_new_views(|workspace: &mut Workspace, _cx| {
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        if let Some(workspace_id) = workspace.database_id() {
+            restore_search_history(workspace.project(), workspace_id, cx);
+            restore_search_scope(workspace.project(), workspace_id, cx);
+        }
         register_workspace_action(workspace, move |search_bar, _: &FocusSearch, cx| {
             search_bar.focus_search(cx);
         });
@@ -75,6 +93,18 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, action: &ToggleReplace, cx| {
             search_bar.toggle_replace(action, cx)
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, action: &ToggleSearchHistory, cx| {
+                search_bar.toggle_search_history(action, cx)
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, action: &GroupByDirectory, cx| {
+                search_bar.group_by_directory(action, cx)
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &SelectPrevMatch, cx| {
@@ -114,8 +144,51 @@ pub fn init(cx: &mut AppContext) {
     .detach();
 }
 
+/// Adds the queries searched in previous sessions of the workspace to the project's history.
+fn restore_search_history(
+    project: &Model<Project>,
+    workspace_id: WorkspaceId,
+    cx: &mut AppContext,
+) {
+    let queries = SEARCH_DB
+        .project_search_history(workspace_id)
+        .log_err()
+        .flatten()
+        .and_then(|queries| serde_json::from_str::<Vec<String>>(&queries).log_err());
+    if let Some(queries) = queries {
+        project.update(cx, |project, _| {
+            project.search_history_mut().restore(queries)
+        });
+    }
+}
+
+/// Makes new searches in the project start with the scope used in the previous session of the
+/// workspace.
+fn restore_search_scope(project: &Model<Project>, workspace_id: WorkspaceId, cx: &mut AppContext) {
+    let scope = SEARCH_DB
+        .project_search_scope(workspace_id)
+        .log_err()
+        .flatten()
+        .and_then(|scope| serde_json::from_str::<SearchScope>(&scope).log_err());
+    if let Some(scope) = scope {
+        ActiveSettings::update_global(cx, |settings, _| {
+            settings
+                .0
+                .entry(project.downgrade())
+                .or_insert_with(|| ProjectSearchSettings {
+                    search_options: SearchOptions::NONE,
+                    filters_enabled: false,
+                    scope: SearchScope::default(),
+                })
+                .scope = scope;
+        });
+    }
+}
+
 struct ProjectSearch {
     project: Model<Project>,
+    /// The workspace whose history is saved after each search.
+    workspace_id: Option<WorkspaceId>,
     excerpts: Model<MultiBuffer>,
     pending_search: Option<Task<Option<()>>>,
     match_ranges: Vec<Range<Anchor>>,
@@ -125,6 +198,22 @@ struct ProjectSearch {
     no_results: Option<bool>,
     limit_reached: bool,
     search_history_cursor: SearchHistoryCursor,
+    file_results: Vec<FileSearchResults>,
+    group_by_directory: bool,
+    collapsed_directories: HashSet<PathBuf>,
+    directory_headers: Vec<DirectoryHeader>,
+    /// Incremented whenever `directory_headers` changes.
+    directory_headers_id: usize,
+}
+
+/// The header shown above each group of results when they are grouped by directory.
+#[derive(Clone)]
+struct DirectoryHeader {
+    directory: PathBuf,
+    match_count: usize,
+    collapsed: bool,
+    /// The excerpts of the group, which are folded away when it is collapsed.
+    range: Range<Anchor>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -148,6 +237,10 @@ pub struct ProjectSearchView {
     excluded_files_editor: View<Editor>,
     filters_enabled: bool,
     replace_enabled: bool,
+    directory_header_blocks: HashSet<BlockId>,
+    directory_headers_id: usize,
+    search_history: Option<View<SearchHistoryView>>,
+    search_history_subscriptions: Vec<Subscription>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -155,6 +248,7 @@ pub struct ProjectSearchView {
 struct ProjectSearchSettings {
     search_options: SearchOptions,
     filters_enabled: bool,
+    scope: SearchScope,
 }
 
 pub struct ProjectSearchBar {
@@ -169,6 +263,7 @@ impl ProjectSearch {
 
         Self {
             project,
+            workspace_id: None,
             excerpts: cx.new_model(|_| MultiBuffer::new(replica_id, capability)),
             pending_search: Default::default(),
             match_ranges: Default::default(),
@@ -178,12 +273,18 @@ impl ProjectSearch {
             no_results: None,
             limit_reached: false,
             search_history_cursor: Default::default(),
+            file_results: Vec::new(),
+            group_by_directory: false,
+            collapsed_directories: HashSet::default(),
+            directory_headers: Vec::new(),
+            directory_headers_id: 0,
         }
     }
 
     fn clone(&self, cx: &mut ModelContext<Self>) -> Model<Self> {
         cx.new_model(|cx| Self {
             project: self.project.clone(),
+            workspace_id: self.workspace_id,
             excerpts: self
                 .excerpts
                 .update(cx, |excerpts, cx| cx.new_model(|cx| excerpts.clone(cx))),
@@ -195,6 +296,11 @@ impl ProjectSearch {
             no_results: self.no_results,
             limit_reached: self.limit_reached,
             search_history_cursor: self.search_history_cursor.clone(),
+            file_results: self.file_results.clone(),
+            group_by_directory: self.group_by_directory,
+            collapsed_directories: self.collapsed_directories.clone(),
+            directory_headers: self.directory_headers.clone(),
+            directory_headers_id: self.directory_headers_id,
         })
     }
 
@@ -215,6 +321,8 @@ impl ProjectSearch {
             this.update(&mut cx, |this, cx| {
                 this.match_ranges.clear();
                 this.excerpts.update(cx, |this, cx| this.clear(cx));
+                this.file_results.clear();
+                this.set_directory_headers(Vec::new());
                 this.no_results = Some(true);
                 this.limit_reached = false;
             })
@@ -227,6 +335,11 @@ impl ProjectSearch {
                         let mut match_ranges = this
                             .update(&mut cx, |this, cx| {
                                 this.no_results = Some(false);
+                                this.file_results.push(FileSearchResults::new(
+                                    buffer.clone(),
+                                    ranges.clone(),
+                                    cx,
+                                ));
                                 this.excerpts.update(cx, |excerpts, cx| {
                                     excerpts.stream_excerpts_with_context_lines(
                                         buffer,
@@ -253,6 +366,13 @@ impl ProjectSearch {
             this.update(&mut cx, |this, cx| {
                 this.limit_reached = limit_reached;
                 this.pending_search.take();
+                // Results stream in as they are found, so they're only grouped once all of
+                // them are known.
+                if this.group_by_directory {
+                    this.rebuild_excerpts(cx);
+                }
+                this.save_search_history(cx);
+                this.save_search_scope(cx);
                 cx.notify();
             })
             .ok()?;
@@ -261,6 +381,146 @@ impl ProjectSearch {
         }));
         cx.notify();
     }
+
+    /// Persists the project's search history, so that it can be restored in the next session
+    /// of the workspace.
+    fn save_search_history(&self, cx: &mut ModelContext<Self>) {
+        let Some(workspace_id) = self.workspace_id else {
+            return;
+        };
+        let Some(queries) =
+            serde_json::to_string(self.project.read(cx).search_history().queries()).log_err()
+        else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(async move {
+                SEARCH_DB
+                    .save_project_search_history(workspace_id, queries)
+                    .await
+                    .log_err()
+            })
+            .detach();
+    }
+
+    /// Persists the scope of the active query, so that searches start with it in the next
+    /// session of the workspace.
+    fn save_search_scope(&self, cx: &mut ModelContext<Self>) {
+        let (Some(workspace_id), Some(query)) = (self.workspace_id, self.active_query.as_ref())
+        else {
+            return;
+        };
+        let Some(scope) = serde_json::to_string(&query.scope()).log_err() else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(async move {
+                SEARCH_DB
+                    .save_project_search_scope(workspace_id, scope)
+                    .await
+                    .log_err()
+            })
+            .detach();
+    }
+
+    fn set_group_by_directory(&mut self, group_by_directory: bool, cx: &mut ModelContext<Self>) {
+        if self.group_by_directory == group_by_directory {
+            return;
+        }
+        self.group_by_directory = group_by_directory;
+        if self.pending_search.is_none() {
+            self.rebuild_excerpts(cx);
+            cx.notify();
+        }
+    }
+
+    fn toggle_directory_collapsed(&mut self, directory: &Path, cx: &mut ModelContext<Self>) {
+        if !self.collapsed_directories.remove(directory) {
+            self.collapsed_directories.insert(directory.to_path_buf());
+        }
+        let headers = self
+            .directory_headers
+            .iter()
+            .cloned()
+            .map(|header| DirectoryHeader {
+                collapsed: self.collapsed_directories.contains(&header.directory),
+                ..header
+            })
+            .collect();
+        self.set_directory_headers(headers);
+        cx.notify();
+    }
+
+    /// Recreates the excerpts for the current results, ordering them by directory when grouping
+    /// is enabled.
+    fn rebuild_excerpts(&mut self, cx: &mut ModelContext<Self>) {
+        let groups = if self.group_by_directory {
+            SearchResultGroup::group_by_directory(self.file_results.iter().cloned())
+        } else {
+            Vec::new()
+        };
+        let files_to_show = if self.group_by_directory {
+            groups.iter().map(|group| group.files.clone()).collect()
+        } else {
+            vec![self.file_results.clone()]
+        };
+
+        let mut group_match_ranges = Vec::new();
+        let match_ranges = self.excerpts.update(cx, |excerpts, cx| {
+            excerpts.clear(cx);
+            let mut match_ranges = Vec::new();
+            for files in files_to_show {
+                let group_start = match_ranges.len();
+                for file in files {
+                    match_ranges.extend(excerpts.push_excerpts_with_context_lines(
+                        file.buffer,
+                        file.ranges,
+                        editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                        cx,
+                    ));
+                }
+                group_match_ranges.push(group_start..match_ranges.len());
+            }
+            match_ranges
+        });
+        self.match_ranges = match_ranges;
+
+        // Each header spans from the start of its group's first excerpt to the end of its last.
+        let snapshot = self.excerpts.read(cx).snapshot(cx);
+        let excerpt_ranges = snapshot
+            .excerpts()
+            .filter_map(|(excerpt_id, _, range)| {
+                let start = snapshot.anchor_in_excerpt(excerpt_id, range.context.start)?;
+                let end = snapshot.anchor_in_excerpt(excerpt_id, range.context.end)?;
+                Some((excerpt_id, start..end))
+            })
+            .collect::<HashMap<_, _>>();
+        let headers = groups
+            .iter()
+            .zip(group_match_ranges)
+            .filter_map(|(group, match_ranges)| {
+                let first_match = self.match_ranges.get(match_ranges.start)?;
+                let last_match = self.match_ranges.get(match_ranges.end.checked_sub(1)?)?;
+                let first_excerpt = excerpt_ranges.get(&first_match.start.excerpt_id)?;
+                let last_excerpt = excerpt_ranges.get(&last_match.start.excerpt_id)?;
+                Some(DirectoryHeader {
+                    directory: group.directory.clone(),
+                    match_count: group.match_count(),
+                    collapsed: self.collapsed_directories.contains(&group.directory),
+                    range: first_excerpt.start..last_excerpt.end,
+                })
+            })
+            .collect();
+        self.set_directory_headers(headers);
+    }
+
+    fn set_directory_headers(&mut self, headers: Vec<DirectoryHeader>) {
+        if headers.is_empty() && self.directory_headers.is_empty() {
+            return;
+        }
+        self.directory_headers = headers;
+        self.directory_headers_id += 1;
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -275,11 +535,10 @@ impl EventEmitter<ViewEvent> for ProjectSearchView {}
 
 impl Render for ProjectSearchView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        if self.has_matches() {
+        let content = if self.has_matches() {
             div()
                 .flex_1()
                 .size_full()
-                .track_focus(&self.focus_handle)
                 .child(self.results_editor.clone())
         } else {
             let model = self.model.read(cx);
@@ -315,7 +574,6 @@ impl Render for ProjectSearchView {
                 .size_full()
                 .justify_center()
                 .bg(cx.theme().colors().editor_background)
-                .track_focus(&self.focus_handle)
                 .child(
                     h_flex()
                         .size_full()
@@ -324,7 +582,20 @@ impl Render for ProjectSearchView {
                         .child(v_flex().child(major_text).children(minor_text))
                         .child(h_flex().flex_1()),
                 )
-        }
+        };
+
+        // The history is shown as a dropdown below the search bar, on top of the results.
+        div()
+            .relative()
+            .flex_1()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .child(content)
+            .children(
+                self.search_history
+                    .clone()
+                    .map(|search_history| div().absolute().top_0().left_2().child(search_history)),
+            )
     }
 }
 
@@ -466,6 +737,9 @@ impl Item for ProjectSearchView {
     }
 
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        let workspace_id = workspace.database_id();
+        self.model
+            .update(cx, |model, _| model.workspace_id = workspace_id);
         self.results_editor
             .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
     }
@@ -528,24 +802,42 @@ impl ProjectSearchView {
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.model.read(cx).project.downgrade(),
-                self.current_settings(),
+                self.current_settings(cx),
             );
         });
     }
 
-    fn current_settings(&self) -> ProjectSearchSettings {
+    fn current_settings(&self, cx: &AppContext) -> ProjectSearchSettings {
         ProjectSearchSettings {
             search_options: self.search_options,
             filters_enabled: self.filters_enabled,
+            scope: self.scope(cx),
         }
     }
 
+    /// The scope currently entered in the include and exclude inputs.
+    fn scope(&self, cx: &AppContext) -> SearchScope {
+        SearchScope {
+            include_globs: Self::parse_globs(&self.included_files_editor.read(cx).text(cx)),
+            exclude_globs: Self::parse_globs(&self.excluded_files_editor.read(cx).text(cx)),
+        }
+    }
+
+    fn set_scope(&mut self, scope: &SearchScope, cx: &mut ViewContext<Self>) {
+        self.included_files_editor.update(cx, |editor, cx| {
+            editor.set_text(scope.include_globs.join(", "), cx)
+        });
+        self.excluded_files_editor.update(cx, |editor, cx| {
+            editor.set_text(scope.exclude_globs.join(", "), cx)
+        });
+    }
+
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut ViewContext<Self>) {
         self.search_options.toggle(option);
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.model.read(cx).project.downgrade(),
-                self.current_settings(),
+                self.current_settings(cx),
             );
         });
     }
@@ -611,10 +903,14 @@ impl ProjectSearchView {
         let mut subscriptions = Vec::new();
 
         // Read in settings if available
-        let (mut options, filters_enabled) = if let Some(settings) = settings {
-            (settings.search_options, settings.filters_enabled)
+        let (mut options, filters_enabled, scope) = if let Some(settings) = settings {
+            (
+                settings.search_options,
+                settings.filters_enabled,
+                settings.scope,
+            )
         } else {
-            (SearchOptions::NONE, false)
+            (SearchOptions::NONE, false, SearchScope::default())
         };
 
         {
@@ -718,8 +1014,13 @@ impl ProjectSearchView {
             excluded_files_editor,
             filters_enabled,
             replace_enabled: false,
+            directory_header_blocks: HashSet::default(),
+            directory_headers_id: 0,
+            search_history: None,
+            search_history_subscriptions: Vec::new(),
             _subscriptions: subscriptions,
         };
+        this.set_scope(&scope, cx);
         this.model_changed(cx);
         this
     }
@@ -852,6 +1153,13 @@ impl ProjectSearchView {
     fn search(&mut self, cx: &mut ViewContext<Self>) {
         if let Some(query) = self.build_search_query(cx) {
             self.model.update(cx, |model, cx| model.search(query, cx));
+            // New searches in the project start with the scope of the last one.
+            ActiveSettings::update_global(cx, |settings, cx| {
+                settings.0.insert(
+                    self.model.read(cx).project.downgrade(),
+                    self.current_settings(cx),
+                );
+            });
         }
     }
 
@@ -957,13 +1265,15 @@ impl ProjectSearchView {
     }
 
     fn parse_path_matches(text: &str) -> anyhow::Result<PathMatcher> {
-        let queries = text
-            .split(',')
+        Ok(PathMatcher::new(&Self::parse_globs(text))?)
+    }
+
+    fn parse_globs(text: &str) -> Vec<String> {
+        text.split(',')
             .map(str::trim)
             .filter(|maybe_glob_str| !maybe_glob_str.is_empty())
             .map(str::to_owned)
-            .collect::<Vec<_>>();
-        Ok(PathMatcher::new(&queries)?)
+            .collect()
     }
 
     fn select_match(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
@@ -984,6 +1294,7 @@ impl ProjectSearchView {
             });
 
             let range_to_select = match_ranges[new_index].clone();
+            self.expand_directory_containing(&range_to_select, cx);
             self.results_editor.update(cx, |editor, cx| {
                 let range_to_select = editor.range_for_match(&range_to_select);
                 editor.unfold_ranges([range_to_select.clone()], false, true, cx);
@@ -994,6 +1305,28 @@ impl ProjectSearchView {
         }
     }
 
+    /// Expands the collapsed directory that a match is in, so that it can be selected.
+    fn expand_directory_containing(&mut self, range: &Range<Anchor>, cx: &mut ViewContext<Self>) {
+        let model = self.model.read(cx);
+        let snapshot = model.excerpts.read(cx).snapshot(cx);
+        let Some(directory) = model
+            .directory_headers
+            .iter()
+            .find(|header| {
+                header.collapsed
+                    && header.range.start.cmp(&range.start, &snapshot).is_le()
+                    && header.range.end.cmp(&range.end, &snapshot).is_ge()
+            })
+            .map(|header| header.directory.clone())
+        else {
+            return;
+        };
+        self.model.update(cx, |model, cx| {
+            model.toggle_directory_collapsed(&directory, cx)
+        });
+        self.update_directory_headers(cx);
+    }
+
     fn focus_query_editor(&mut self, cx: &mut ViewContext<Self>) {
         self.query_editor.update(cx, |query_editor, cx| {
             query_editor.select_all(&SelectAll, cx);
@@ -1007,6 +1340,49 @@ impl ProjectSearchView {
             .update(cx, |query_editor, cx| query_editor.set_text(query, cx));
     }
 
+    fn toggle_search_history(&mut self, cx: &mut ViewContext<Self>) {
+        if self.search_history.is_some() {
+            self.dismiss_search_history(cx);
+            self.focus_query_editor(cx);
+            return;
+        }
+
+        let queries = self
+            .model
+            .read(cx)
+            .project
+            .read(cx)
+            .search_history()
+            .queries()
+            .to_vec();
+        let search_history = cx.new_view(|cx| SearchHistoryView::new(&queries, cx));
+        let focus_handle = search_history.focus_handle(cx);
+        self.search_history_subscriptions = vec![
+            cx.subscribe(
+                &search_history,
+                |this, _, event: &HistoryQuerySelected, cx| {
+                    this.set_query(&event.0, cx);
+                    this.search(cx);
+                },
+            ),
+            cx.subscribe(&search_history, |this, _, _: &DismissEvent, cx| {
+                this.dismiss_search_history(cx);
+                this.focus_query_editor(cx);
+            }),
+            cx.on_blur(&focus_handle, |this, cx| this.dismiss_search_history(cx)),
+        ];
+        cx.focus(&focus_handle);
+        self.search_history = Some(search_history);
+        cx.notify();
+    }
+
+    fn dismiss_search_history(&mut self, cx: &mut ViewContext<Self>) {
+        if self.search_history.take().is_some() {
+            self.search_history_subscriptions.clear();
+            cx.notify();
+        }
+    }
+
     fn focus_results_editor(&mut self, cx: &mut ViewContext<Self>) {
         self.query_editor.update(cx, |query_editor, cx| {
             let cursor = query_editor.selections.newest_anchor().head();
@@ -1017,6 +1393,7 @@ impl ProjectSearchView {
     }
 
     fn model_changed(&mut self, cx: &mut ViewContext<Self>) {
+        self.update_directory_headers(cx);
         let match_ranges = self.model.read(cx).match_ranges.clone();
         if match_ranges.is_empty() {
             self.active_match_index = None;
@@ -1050,6 +1427,58 @@ impl ProjectSearchView {
         cx.notify();
     }
 
+    fn update_directory_headers(&mut self, cx: &mut ViewContext<Self>) {
+        let model = self.model.read(cx);
+        if self.directory_headers_id == model.directory_headers_id {
+            return;
+        }
+        self.directory_headers_id = model.directory_headers_id;
+        let headers = model.directory_headers.clone();
+        let weak_model = self.model.downgrade();
+        let blocks_to_remove = mem::take(&mut self.directory_header_blocks);
+        self.directory_header_blocks = self.results_editor.update(cx, |editor, cx| {
+            // The excerpts of collapsed directories are folded rather than removed, so that their
+            // matches are still selected and replaced.
+            editor.unfold_ranges(
+                headers.iter().map(|header| header.range.clone()),
+                false,
+                false,
+                cx,
+            );
+            editor.fold_ranges(
+                headers
+                    .iter()
+                    .filter(|header| header.collapsed)
+                    .map(|header| {
+                        (
+                            header.range.clone(),
+                            directory_fold_placeholder(
+                                header.directory.clone(),
+                                weak_model.clone(),
+                            ),
+                        )
+                    }),
+                false,
+                cx,
+            );
+            editor.remove_blocks(blocks_to_remove, None, cx);
+            editor
+                .insert_blocks(
+                    headers.into_iter().map(|header| BlockProperties {
+                        position: header.range.start,
+                        height: 2,
+                        style: BlockStyle::Sticky,
+                        disposition: BlockDisposition::AboveExcerptHeader,
+                        render: render_directory_header(header, weak_model.clone()),
+                    }),
+                    None,
+                    cx,
+                )
+                .into_iter()
+                .collect()
+        });
+    }
+
     fn update_match_index(&mut self, cx: &mut ViewContext<Self>) {
         let results_editor = self.results_editor.read(cx);
         let new_index = active_match_index(
@@ -1089,6 +1518,92 @@ impl ProjectSearchView {
     }
 }
 
+fn render_directory_header(
+    header: DirectoryHeader,
+    model: WeakModel<ProjectSearch>,
+) -> RenderBlock {
+    Box::new(move |cx: &mut BlockContext| {
+        let directory = header.directory.clone();
+        let model = model.clone();
+        let match_count = match header.match_count {
+            1 => "1 match".to_string(),
+            count => format!("{count} matches"),
+        };
+
+        h_flex()
+            .id(("directory header", cx.block_id))
+            .size_full()
+            .px_2()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().editor_subheader_background)
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .cursor_pointer()
+            .child(
+                Icon::new(if header.collapsed {
+                    IconName::ChevronRight
+                } else {
+                    IconName::ChevronDown
+                })
+                .size(IconSize::Small)
+                .color(Color::Muted),
+            )
+            .child(
+                Icon::new(IconName::Folder)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(header.directory.to_string_lossy().to_string()))
+            .child(
+                Label::new(match_count)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+            .on_click(move |_, cx| {
+                model
+                    .update(cx, |model, cx| {
+                        model.toggle_directory_collapsed(&directory, cx)
+                    })
+                    .ok();
+            })
+            .into_any_element()
+    })
+}
+
+fn directory_fold_placeholder(
+    directory: PathBuf,
+    model: WeakModel<ProjectSearch>,
+) -> FoldPlaceholder {
+    FoldPlaceholder {
+        render: Arc::new(move |fold_id, _, cx| {
+            let directory = directory.clone();
+            let model = model.clone();
+            div()
+                .id(fold_id)
+                .bg(cx.theme().colors().ghost_element_background)
+                .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+                .active(|style| style.bg(cx.theme().colors().ghost_element_active))
+                .rounded_sm()
+                .size_full()
+                .cursor_pointer()
+                .child("⋯")
+                .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+                .on_click(move |_, cx| {
+                    model
+                        .update(cx, |model, cx| {
+                            model.toggle_directory_collapsed(&directory, cx)
+                        })
+                        .ok();
+                })
+                .into_any()
+        }),
+        constrain_width: true,
+        merge_adjacent: false,
+    }
+}
+
 impl ProjectSearchBar {
     pub fn new() -> Self {
         Self {
@@ -1197,6 +1712,17 @@ impl ProjectSearchBar {
         }
     }
 
+    fn group_by_directory(&mut self, action: &GroupByDirectory, cx: &mut ViewContext<Self>) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| {
+                search_view
+                    .model
+                    .update(cx, |model, cx| model.set_group_by_directory(action.0, cx));
+            });
+            cx.notify();
+        }
+    }
+
     fn toggle_filters(&mut self, cx: &mut ViewContext<Self>) -> bool {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
@@ -1287,6 +1813,12 @@ impl ProjectSearchBar {
         }
     }
 
+    fn toggle_search_history(&mut self, _: &ToggleSearchHistory, cx: &mut ViewContext<Self>) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| search_view.toggle_search_history(cx));
+        }
+    }
+
     fn select_next_match(&mut self, _: &SelectNextMatch, cx: &mut ViewContext<Self>) {
         if let Some(search) = self.active_project_search.as_ref() {
             search.update(cx, |this, cx| {
@@ -1313,6 +1845,7 @@ impl ProjectSearchBar {
             },
             font_family: settings.buffer_font.family.clone(),
             font_features: settings.buffer_font.features.clone(),
+            font_axes: settings.buffer_font.axes.clone(),
             font_size: rems(0.875).into(),
             font_weight: settings.buffer_font.weight,
             font_style: FontStyle::Normal,
@@ -1356,6 +1889,7 @@ impl Render for ProjectSearchBar {
             .on_action(cx.listener(|this, action, cx| this.confirm(action, cx)))
             .on_action(cx.listener(|this, action, cx| this.previous_history_query(action, cx)))
             .on_action(cx.listener(|this, action, cx| this.next_history_query(action, cx)))
+            .on_action(cx.listener(|this, action, cx| this.toggle_search_history(action, cx)))
             .child(self.render_text_input(&search.query_editor, cx))
             .child(
                 h_flex()
@@ -1406,7 +1940,22 @@ impl Render for ProjectSearchBar {
                                 .unwrap_or_default(),
                         )
                         .tooltip(|cx| Tooltip::for_action("Toggle replace", &ToggleReplace, cx)),
-                ),
+                )
+                .child({
+                    let group_by_directory = search.model.read(cx).group_by_directory;
+                    IconButton::new("project-search-group-by-directory", IconName::Folder)
+                        .on_click(cx.listener(move |this, _, cx| {
+                            this.group_by_directory(&GroupByDirectory(!group_by_directory), cx);
+                        }))
+                        .selected(group_by_directory)
+                        .tooltip(move |cx| {
+                            Tooltip::for_action(
+                                "Group by directory",
+                                &GroupByDirectory(!group_by_directory),
+                                cx,
+                            )
+                        })
+                }),
         );
 
         let match_text = search
@@ -1471,10 +2020,22 @@ impl Render for ProjectSearchBar {
                 )
             });
 
+        // The scope is summarized while the section where it's edited is collapsed.
+        let scope = search.scope(cx);
+        let scope_selector = (!search.filters_enabled && !scope.is_default()).then(|| {
+            SearchScopeSelector::new(
+                scope,
+                cx.listener(|this, _, cx| {
+                    this.toggle_filters(cx);
+                }),
+            )
+        });
+
         let search_line = h_flex()
             .flex_1()
             .child(query_column)
             .child(mode_column)
+            .children(scope_selector)
             .child(matches_column);
 
         let replace_line = search.replace_enabled.then(|| {
@@ -1606,6 +2167,7 @@ impl Render for ProjectSearchBar {
             })
             .on_action(cx.listener(Self::select_next_match))
             .on_action(cx.listener(Self::select_prev_match))
+            .on_action(cx.listener(Self::group_by_directory))
             .gap_2()
             .w_full()
             .child(search_line)
@@ -3228,6 +3790,265 @@ pub mod tests {
             .expect("unable to update search view");
     }
 
+    #[gpui::test]
+    async fn test_project_search_grouped_by_directory(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "b": {
+                    "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                    "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+                },
+                "a": {
+                    "one.rs": "const ONE: usize = 1;",
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let search = cx.new_model(|cx| ProjectSearch::new(project, cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        search.update(cx, |search, cx| search.set_group_by_directory(true, cx));
+        perform_search(search_view, "ONE", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(
+                    directory_headers(&search.read(cx)),
+                    [
+                        (PathBuf::from("dir/a"), 1, false),
+                        (PathBuf::from("dir/b"), 3, false)
+                    ]
+                );
+                assert_eq!(search.read(cx).match_ranges.len(), 4);
+                assert_eq!(
+                    search_view
+                        .results_editor
+                        .update(cx, |editor, cx| editor.display_text(cx)),
+                    "\n\n\n\n\nconst ONE: usize = 1;\n\n\n\n\n\n\nconst THREE: usize = one::ONE + two::TWO;\n\n\n\n\nconst TWO: usize = one::ONE + one::ONE;\n"
+                );
+            })
+            .unwrap();
+
+        search.update(cx, |search, cx| {
+            search.toggle_directory_collapsed(Path::new("dir/b"), cx)
+        });
+        cx.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(
+                    directory_headers(&search.read(cx)),
+                    [
+                        (PathBuf::from("dir/a"), 1, false),
+                        (PathBuf::from("dir/b"), 3, true)
+                    ]
+                );
+                // The matches of collapsed directories are kept, and only their excerpts are
+                // folded away.
+                assert_eq!(search.read(cx).match_ranges.len(), 4);
+                let display_text = search_view
+                    .results_editor
+                    .update(cx, |editor, cx| editor.display_text(cx));
+                assert!(display_text.contains("const ONE: usize = 1;"));
+                assert!(!display_text.contains("TWO"));
+                assert!(!display_text.contains("THREE"));
+            })
+            .unwrap();
+
+        // Selecting a match in a collapsed directory expands it.
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.active_match_index, Some(0));
+                search_view.select_match(Direction::Next, cx);
+                assert_eq!(
+                    directory_headers(&search.read(cx)),
+                    [
+                        (PathBuf::from("dir/a"), 1, false),
+                        (PathBuf::from("dir/b"), 3, false)
+                    ]
+                );
+                assert!(search_view
+                    .results_editor
+                    .update(cx, |editor, cx| editor.display_text(cx))
+                    .contains("const THREE: usize = one::ONE + two::TWO;"));
+            })
+            .unwrap();
+
+        search.update(cx, |search, cx| search.set_group_by_directory(false, cx));
+        cx.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                assert!(directory_headers(&search.read(cx)).is_empty());
+                assert_eq!(search.read(cx).match_ranges.len(), 4);
+                assert!(search_view.directory_header_blocks.is_empty());
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_search_history_dropdown(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project
+                .search_history_mut()
+                .restore(["ONE".to_string(), "TWO".to_string()])
+        });
+        let search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        search_view
+            .update(cx, |search_view, cx| search_view.toggle_search_history(cx))
+            .unwrap();
+        cx.run_until_parked();
+        cx.dispatch_action(search_view.into(), menu::Cancel);
+        cx.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                assert!(search_view.search_history.is_none());
+                assert!(search_view.query_editor.focus_handle(cx).is_focused(cx));
+            })
+            .unwrap();
+
+        search_view
+            .update(cx, |search_view, cx| search_view.toggle_search_history(cx))
+            .unwrap();
+        cx.run_until_parked();
+        cx.simulate_input(search_view.into(), "tw");
+        cx.run_until_parked();
+        cx.dispatch_action(search_view.into(), menu::Confirm);
+        cx.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                assert!(search_view.search_history.is_none());
+                assert_eq!(search_view.query_editor.read(cx).text(cx), "TWO");
+                assert_eq!(
+                    search
+                        .read(cx)
+                        .active_query
+                        .as_ref()
+                        .map(|query| query.as_str()),
+                    Some("TWO")
+                );
+                assert_eq!(search.read(cx).match_ranges.len(), 1);
+            })
+            .unwrap();
+        assert_eq!(
+            project.read_with(cx, |project, _| project.search_history().queries().to_vec()),
+            ["ONE", "TWO", "TWO"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_project_search_scope(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "src": {
+                    "one.rs": "const ONE: usize = 1;",
+                    "generated": {
+                        "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                    },
+                },
+                "tests": {
+                    "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        let scope = SearchScope {
+            include_globs: vec!["src/**".to_string()],
+            exclude_globs: vec!["**/generated/**".to_string()],
+        };
+        search_view
+            .update(cx, |search_view, cx| search_view.set_scope(&scope, cx))
+            .unwrap();
+        perform_search(search_view, "ONE", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.scope(cx), scope);
+                assert_eq!(
+                    search_view
+                        .results_editor
+                        .update(cx, |editor, cx| editor.display_text(cx)),
+                    "\n\n\nconst ONE: usize = 1;\n"
+                );
+                assert_eq!(search.read(cx).match_ranges.len(), 1);
+            })
+            .unwrap();
+        assert_eq!(
+            SearchScopeSelector::summary(&scope),
+            "src/** except **/generated/**"
+        );
+
+        // New searches in the project start with the scope of the last one.
+        let settings = cx.read(|cx| {
+            cx.global::<ActiveSettings>()
+                .0
+                .get(&project.downgrade())
+                .cloned()
+        });
+        assert_eq!(
+            settings.as_ref().map(|settings| &settings.scope),
+            Some(&scope)
+        );
+        let new_search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let new_search_view =
+            cx.add_window(|cx| ProjectSearchView::new(new_search.clone(), cx, settings));
+        new_search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.scope(cx), scope)
+            })
+            .unwrap();
+
+        // Clearing the scope searches every file again.
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.set_scope(&SearchScope::default(), cx)
+            })
+            .unwrap();
+        perform_search(search_view, "ONE", cx);
+        search_view
+            .update(cx, |_, cx| {
+                assert_eq!(search.read(cx).match_ranges.len(), 4)
+            })
+            .unwrap();
+    }
+
+    fn directory_headers(search: &ProjectSearch) -> Vec<(PathBuf, usize, bool)> {
+        search
+            .directory_headers
+            .iter()
+            .map(|header| {
+                (
+                    header.directory.clone(),
+                    header.match_count,
+                    header.collapsed,
+                )
+            })
+            .collect()
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
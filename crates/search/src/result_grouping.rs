@@ -0,0 +1,67 @@
+use collections::BTreeMap;
+use gpui::{AppContext, Model};
+use language::{Anchor, Buffer};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// The matches of a project search within a single file.
+#[derive(Clone)]
+pub struct FileSearchResults {
+    pub buffer: Model<Buffer>,
+    /// The path of the file, starting with the name of its worktree.
+    pub path: PathBuf,
+    pub ranges: Vec<Range<Anchor>>,
+}
+
+impl FileSearchResults {
+    pub fn new(buffer: Model<Buffer>, ranges: Vec<Range<Anchor>>, cx: &AppContext) -> Self {
+        let path = buffer
+            .read(cx)
+            .file()
+            .map(|file| file.full_path(cx))
+            .unwrap_or_default();
+        Self {
+            buffer,
+            path,
+            ranges,
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new(""))
+    }
+}
+
+/// The results of a project search within a single directory, not including its subdirectories.
+#[derive(Clone)]
+pub struct SearchResultGroup {
+    pub directory: PathBuf,
+    pub files: Vec<FileSearchResults>,
+}
+
+impl SearchResultGroup {
+    /// Groups search results by the directory containing each file. Groups are sorted by
+    /// directory, and files by path within each group.
+    pub fn group_by_directory(files: impl IntoIterator<Item = FileSearchResults>) -> Vec<Self> {
+        let mut groups = BTreeMap::<PathBuf, Vec<FileSearchResults>>::new();
+        for file in files {
+            groups
+                .entry(file.directory().to_path_buf())
+                .or_default()
+                .push(file);
+        }
+        groups
+            .into_iter()
+            .map(|(directory, mut files)| {
+                files.sort_by(|a, b| a.path.cmp(&b.path));
+                Self { directory, files }
+            })
+            .collect()
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.files.iter().map(|file| file.ranges.len()).sum()
+    }
+}
@@ -3,14 +3,21 @@ pub use buffer_search::BufferSearchBar;
 use gpui::{actions, Action, AppContext, IntoElement};
 use project::search::SearchQuery;
 pub use project_search::ProjectSearchView;
+pub use result_grouping::{FileSearchResults, SearchResultGroup};
+pub use search_history_view::{HistoryQuerySelected, SearchHistoryView};
+pub use search_scope_selector::SearchScopeSelector;
 use ui::{prelude::*, Tooltip};
 use ui::{ButtonStyle, IconButton};
 use workspace::notifications::NotificationId;
 use workspace::{Toast, Workspace};
 
 pub mod buffer_search;
+mod persistence;
 pub mod project_search;
+pub mod result_grouping;
 pub(crate) mod search_bar;
+pub mod search_history_view;
+pub mod search_scope_selector;
 
 pub fn init(cx: &mut AppContext) {
     menu::init();
@@ -26,6 +33,7 @@ actions!(
         ToggleCaseSensitive,
         ToggleIncludeIgnored,
         ToggleRegex,
+        ToggleStructural,
         ToggleReplace,
         ToggleSelection,
         SelectNextMatch,
@@ -33,6 +41,7 @@ actions!(
         SelectAllMatches,
         NextHistoryQuery,
         PreviousHistoryQuery,
+        ToggleSearchHistory,
         ReplaceAll,
         ReplaceNext,
     ]
@@ -46,6 +55,7 @@ bitflags! {
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
         const REGEX = 0b1000;
+        const STRUCTURAL = 0b10000;
     }
 }
 
@@ -56,6 +66,7 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => "match case",
             SearchOptions::INCLUDE_IGNORED => "include Ignored",
             SearchOptions::REGEX => "regular expression",
+            SearchOptions::STRUCTURAL => "structural search",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -66,6 +77,7 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::FileGit,
             SearchOptions::REGEX => ui::IconName::Regex,
+            SearchOptions::STRUCTURAL => ui::IconName::ListTree,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -76,6 +88,7 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
             SearchOptions::REGEX => Box::new(ToggleRegex),
+            SearchOptions::STRUCTURAL => Box::new(ToggleStructural),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -90,6 +103,7 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
         options
     }
 
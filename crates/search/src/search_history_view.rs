@@ -0,0 +1,175 @@
+use collections::HashSet;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, ParentElement, Render,
+    Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+
+/// Emitted by a [`SearchHistoryView`] when one of its queries is confirmed.
+pub struct HistoryQuerySelected(pub String);
+
+/// A dropdown listing previously searched queries, which can be filtered by typing.
+pub struct SearchHistoryView {
+    picker: View<Picker<SearchHistoryDelegate>>,
+}
+
+impl SearchHistoryView {
+    /// Creates a view listing the given queries, from the oldest to the most recent one.
+    /// The most recent queries are listed first.
+    pub fn new(queries: &[String], cx: &mut ViewContext<Self>) -> Self {
+        let delegate = SearchHistoryDelegate::new(cx.view().downgrade(), queries);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx).modal(false));
+        Self { picker }
+    }
+}
+
+impl Render for SearchHistoryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .occlude()
+            .child(self.picker.clone())
+    }
+}
+
+impl FocusableView for SearchHistoryView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SearchHistoryView {}
+impl EventEmitter<HistoryQuerySelected> for SearchHistoryView {}
+
+pub struct SearchHistoryDelegate {
+    search_history_view: WeakView<SearchHistoryView>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl SearchHistoryDelegate {
+    fn new(search_history_view: WeakView<SearchHistoryView>, queries: &[String]) -> Self {
+        let mut seen = HashSet::default();
+        let candidates = queries
+            .iter()
+            .rev()
+            .filter(|query| seen.insert(query.as_str()))
+            .enumerate()
+            .map(|(candidate_id, query)| StringMatchCandidate::new(candidate_id, query.clone()))
+            .collect();
+
+        Self {
+            search_history_view,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for SearchHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search history...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.candidates.is_empty() {
+            "No previous searches".into()
+        } else {
+            "No matching searches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let query = mat.string.clone();
+            self.search_history_view
+                .update(cx, |_, cx| cx.emit(HistoryQuerySelected(query)))
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.search_history_view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
@@ -0,0 +1,52 @@
+use crate::project_search::ToggleFilters;
+use gpui::ClickEvent;
+use project::SearchScope;
+use ui::{prelude::*, Tooltip};
+
+/// A chip summarizing a non-default search scope, e.g. `src/** except src/generated/**`.
+/// Clicking it expands the section where the scope is edited.
+#[derive(IntoElement)]
+pub struct SearchScopeSelector {
+    scope: SearchScope,
+    on_click: Box<dyn Fn(&ClickEvent, &mut WindowContext) + 'static>,
+}
+
+impl SearchScopeSelector {
+    pub fn new(
+        scope: SearchScope,
+        on_click: impl Fn(&ClickEvent, &mut WindowContext) + 'static,
+    ) -> Self {
+        Self {
+            scope,
+            on_click: Box::new(on_click),
+        }
+    }
+
+    pub fn summary(scope: &SearchScope) -> String {
+        let included = if scope.include_globs.is_empty() {
+            "All files".to_string()
+        } else {
+            scope.include_globs.join(", ")
+        };
+        if scope.exclude_globs.is_empty() {
+            included
+        } else {
+            format!("{included} except {}", scope.exclude_globs.join(", "))
+        }
+    }
+}
+
+impl RenderOnce for SearchScopeSelector {
+    fn render(self, _: &mut WindowContext) -> impl IntoElement {
+        Button::new("project-search-scope", Self::summary(&self.scope))
+            .icon(IconName::Filter)
+            .icon_position(IconPosition::Start)
+            .icon_size(IconSize::Small)
+            .icon_color(Color::Muted)
+            .label_size(LabelSize::Small)
+            .size(ButtonSize::Compact)
+            .style(ButtonStyle::Filled)
+            .tooltip(|cx| Tooltip::for_action("Edit search scope", &ToggleFilters, cx))
+            .on_click(self.on_click)
+    }
+}
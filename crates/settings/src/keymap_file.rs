@@ -9,6 +9,7 @@ use schemars::{
 };
 use serde::Deserialize;
 use serde_json::Value;
+use std::fmt;
 use util::{asset_str, ResultExt};
 
 #[derive(Debug, Deserialize, Default, Clone, JsonSchema)]
@@ -39,6 +40,25 @@ impl JsonSchema for KeymapAction {
 #[derive(Deserialize)]
 struct ActionWithData(Box<str>, Value);
 
+/// A problem found while loading a user's keymap file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeymapFileError {
+    /// The 1-based line and column of the problem in the file, when it could be located.
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl fmt::Display for KeymapFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(f, "line {line}, column {column}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for KeymapFileError {}
+
 impl KeymapFile {
     pub fn load_asset(asset_path: &str, cx: &mut AppContext) -> Result<()> {
         let content = asset_str::<SettingsAssets>(asset_path);
@@ -53,44 +73,43 @@ impl KeymapFile {
         parse_json_with_comments::<Self>(content)
     }
 
+    /// Parses a user's keymap file, locating the problem if it isn't a valid keymap.
+    pub fn parse_user_keymap(content: &str) -> Result<Self, KeymapFileError> {
+        Self::parse(content).map_err(|error| {
+            match error.downcast_ref::<serde_json_lenient::Error>() {
+                Some(error) => {
+                    let (line, column) = (error.line(), error.column());
+                    let message = error.to_string();
+                    let message = message
+                        .strip_suffix(&format!(" at line {line} column {column}"))
+                        .unwrap_or(&message)
+                        .to_string();
+                    KeymapFileError {
+                        position: Some((line, column)),
+                        message,
+                    }
+                }
+                None => KeymapFileError {
+                    position: None,
+                    message: format!("{error:#}"),
+                },
+            }
+        })
+    }
+
     pub fn add_to_cx(self, cx: &mut AppContext) -> Result<()> {
         for KeymapBlock { context, bindings } in self.0 {
             let bindings = bindings
                 .into_iter()
                 .filter_map(|(keystroke, action)| {
-                    let action = action.0;
-
-                    // This is a workaround for a limitation in serde: serde-rs/json#497
-                    // We want to deserialize the action data as a `RawValue` so that we can
-                    // deserialize the action itself dynamically directly from the JSON
-                    // string. But `RawValue` currently does not work inside of an untagged enum.
-                    match action {
-                        Value::Array(items) => {
-                            let Ok([name, data]): Result<[serde_json::Value; 2], _> =
-                                items.try_into()
-                            else {
-                                return Some(Err(anyhow!("Expected array of length 2")));
-                            };
-                            let serde_json::Value::String(name) = name else {
-                                return Some(Err(anyhow!(
-                                    "Expected first item in array to be a string."
-                                )));
-                            };
-                            cx.build_action(&name, Some(data))
-                        }
-                        Value::String(name) => cx.build_action(&name, None),
-                        Value::Null => Ok(no_action()),
-                        _ => {
-                            return Some(Err(anyhow!("Expected two-element array, got {action:?}")))
-                        }
-                    }
-                    .with_context(|| {
-                        format!(
-                            "invalid binding value for keystroke {keystroke}, context {context:?}"
-                        )
-                    })
-                    .log_err()
-                    .map(|action| KeyBinding::load(&keystroke, action, context.as_deref()))
+                    let binding = || format!("keystroke {keystroke}, context {context:?}");
+                    build_action(action, cx)
+                        .with_context(|| format!("invalid binding value for {}", binding()))
+                        .log_err()
+                        .map(|action| {
+                            KeyBinding::load(&keystroke, action, context.as_deref())
+                                .with_context(|| format!("invalid binding for {}", binding()))
+                        })
                 })
                 .collect::<Result<Vec<_>>>()?;
 
@@ -99,6 +118,33 @@ impl KeymapFile {
         Ok(())
     }
 
+    /// Builds the valid bindings of a user's keymap, returning them along with the problems
+    /// with the others, so that one bad binding doesn't discard the whole file. `content` is
+    /// the file the keymap was parsed from, and is used to locate the problems.
+    pub fn user_bindings(
+        self,
+        content: &str,
+        cx: &AppContext,
+    ) -> (Vec<KeyBinding>, Vec<KeymapFileError>) {
+        let mut bindings = Vec::new();
+        let mut errors = Vec::new();
+        for (block_ix, block) in self.0.into_iter().enumerate() {
+            let context = block.context;
+            for (keystroke, action) in block.bindings {
+                let binding = build_action(action, cx)
+                    .and_then(|action| KeyBinding::load(&keystroke, action, context.as_deref()));
+                match binding {
+                    Ok(binding) => bindings.push(binding),
+                    Err(error) => errors.push(KeymapFileError {
+                        position: binding_position(content, block_ix, &keystroke),
+                        message: format!("invalid binding for `{keystroke}`: {error:#}"),
+                    }),
+                }
+            }
+        }
+        (bindings, errors)
+    }
+
     pub fn generate_json_schema(action_names: &[SharedString]) -> serde_json::Value {
         let mut root_schema = SchemaSettings::draft07()
             .with(|settings| settings.option_add_null_type = false)
@@ -140,13 +186,105 @@ impl KeymapFile {
     }
 }
 
+fn build_action(action: KeymapAction, cx: &AppContext) -> Result<Box<dyn Action>> {
+    let action = action.0;
+
+    // This is a workaround for a limitation in serde: serde-rs/json#497
+    // We want to deserialize the action data as a `RawValue` so that we can
+    // deserialize the action itself dynamically directly from the JSON
+    // string. But `RawValue` currently does not work inside of an untagged enum.
+    match action {
+        Value::Array(items) => {
+            let Ok([name, data]): Result<[serde_json::Value; 2], _> = items.try_into() else {
+                return Err(anyhow!(
+                    "expected an action name and its arguments, like [\"pane::ActivateItem\", 1]"
+                ));
+            };
+            let serde_json::Value::String(name) = name else {
+                return Err(anyhow!(
+                    "expected the first item in the array to be an action name"
+                ));
+            };
+            cx.build_action(&name, Some(data))
+        }
+        Value::String(name) => cx.build_action(&name, None),
+        Value::Null => Ok(no_action()),
+        _ => Err(anyhow!(
+            "expected an action name, or an action name and its arguments, got {action}"
+        )),
+    }
+}
+
 fn no_action() -> Box<dyn gpui::Action> {
     gpui::NoAction.boxed_clone()
 }
 
+/// Finds the key of a binding in the `bindings` object of the `block_ix`th block of a keymap
+/// file, returning its 1-based line and column.
+fn binding_position(content: &str, block_ix: usize, keystroke: &str) -> Option<(usize, usize)> {
+    let (block_start, _) = content.match_indices("\"bindings\"").nth(block_ix)?;
+    let key = serde_json::to_string(keystroke).ok()?;
+    let offset = block_start + content[block_start..].find(&key)?;
+    let line_start = content[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+    let line = content[..offset].matches('\n').count() + 1;
+    let column = content[line_start..offset].chars().count() + 1;
+    Some((line, column))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::KeymapFile;
+    use gpui::{actions, impl_actions, AppContext, KeyContext, Keymap, Keystroke};
+    use serde::Deserialize;
+
+    actions!(test_keymap, [Alpha, Beta]);
+
+    #[derive(Clone, Default, PartialEq, Deserialize)]
+    struct Move {
+        distance: usize,
+    }
+
+    impl_actions!(test_keymap, [Move]);
+
+    const DEFAULT_KEYMAP: &str = r#"[
+        {
+            "bindings": {
+                "ctrl-a": "test_keymap::Alpha",
+                "ctrl-b": "test_keymap::Alpha",
+                "ctrl-m": ["test_keymap::Move", { "distance": 1 }]
+            }
+        }
+    ]"#;
+
+    fn load_keymap(user_keymap: &str, cx: &AppContext) -> Keymap {
+        let (mut bindings, errors) = KeymapFile::parse(DEFAULT_KEYMAP)
+            .unwrap()
+            .user_bindings(DEFAULT_KEYMAP, cx);
+        assert!(errors.is_empty(), "{errors:?}");
+        let (user_bindings, errors) = KeymapFile::parse_user_keymap(user_keymap)
+            .unwrap()
+            .user_bindings(user_keymap, cx);
+        assert!(errors.is_empty(), "{errors:?}");
+        bindings.extend(user_bindings);
+        Keymap::new(bindings)
+    }
+
+    /// Returns the action the keystroke would dispatch in the given context.
+    fn action_for<'a>(
+        keymap: &'a Keymap,
+        keystroke: &str,
+        context: &[KeyContext],
+    ) -> Option<&'a dyn gpui::Action> {
+        let keystroke = Keystroke::parse(keystroke).unwrap();
+        keymap
+            .bindings()
+            .rev()
+            .find(|binding| {
+                binding.keystrokes() == [keystroke.clone()]
+                    && keymap.binding_enabled(binding, context)
+            })
+            .map(|binding| binding.action())
+    }
 
     #[test]
     fn can_deserialize_keymap_with_trailing_comma() {
@@ -163,4 +301,179 @@ mod tests {
         };
         KeymapFile::parse(json).unwrap();
     }
+
+    #[gpui::test]
+    fn test_user_keymap_overrides_defaults(cx: &mut AppContext) {
+        let keymap = load_keymap(
+            r#"[
+                {
+                    "bindings": {
+                        "ctrl-a": "test_keymap::Beta"
+                    }
+                },
+                {
+                    "context": "Editor",
+                    "bindings": {
+                        "ctrl-b": "test_keymap::Beta"
+                    }
+                }
+            ]"#,
+            cx,
+        );
+        let editor = [KeyContext::parse("Editor").unwrap()];
+
+        assert_eq!(
+            action_for(&keymap, "ctrl-a", &[]).unwrap().name(),
+            "test_keymap::Beta"
+        );
+        assert_eq!(
+            action_for(&keymap, "ctrl-b", &[]).unwrap().name(),
+            "test_keymap::Alpha"
+        );
+        assert_eq!(
+            action_for(&keymap, "ctrl-b", &editor).unwrap().name(),
+            "test_keymap::Beta"
+        );
+    }
+
+    #[gpui::test]
+    fn test_user_keymap_unbinding(cx: &mut AppContext) {
+        let keymap = load_keymap(
+            r#"[
+                {
+                    "bindings": {
+                        "ctrl-a": null
+                    }
+                },
+                {
+                    "context": "Editor",
+                    "bindings": {
+                        "ctrl-b": null
+                    }
+                }
+            ]"#,
+            cx,
+        );
+        let editor = [KeyContext::parse("Editor").unwrap()];
+
+        assert!(action_for(&keymap, "ctrl-a", &[]).is_none());
+        assert!(action_for(&keymap, "ctrl-a", &editor).is_none());
+        assert_eq!(
+            action_for(&keymap, "ctrl-b", &[]).unwrap().name(),
+            "test_keymap::Alpha"
+        );
+        assert!(action_for(&keymap, "ctrl-b", &editor).is_none());
+    }
+
+    #[gpui::test]
+    fn test_user_keymap_action_arguments(cx: &mut AppContext) {
+        let keymap = load_keymap(
+            r#"[
+                {
+                    "bindings": {
+                        "ctrl-m": ["test_keymap::Move", { "distance": 3 }],
+                        "ctrl-n": ["test_keymap::Move", { "distance": 4 }]
+                    }
+                }
+            ]"#,
+            cx,
+        );
+
+        assert!(action_for(&keymap, "ctrl-m", &[])
+            .unwrap()
+            .partial_eq(&Move { distance: 3 }));
+        assert!(action_for(&keymap, "ctrl-n", &[])
+            .unwrap()
+            .partial_eq(&Move { distance: 4 }));
+    }
+
+    #[gpui::test]
+    fn test_malformed_user_keymap(cx: &mut AppContext) {
+        let error = KeymapFile::parse_user_keymap(indoc::indoc! {r#"
+            [
+              {
+                "bindings": {
+                  "ctrl-a" "test_keymap::Alpha"
+                }
+              }
+            ]
+        "#})
+        .unwrap_err();
+        assert_eq!(error.position.map(|(line, _)| line), Some(4));
+
+        let content = indoc::indoc! {r#"
+            [
+              {
+                "bindings": {
+                  "ctrl-a": "test_keymap::Alpha",
+                  "ctrl-b": "test_keymap::Missing"
+                }
+              },
+              {
+                "context": "Editor &&",
+                "bindings": {
+                  "ctrl-b": "test_keymap::Beta",
+                  "ctrl-m": ["test_keymap::Move", { "distance": "far" }]
+                }
+              }
+            ]
+        "#};
+        let (bindings, errors) = KeymapFile::parse_user_keymap(content)
+            .unwrap()
+            .user_bindings(content, cx);
+
+        // The valid bindings are still loaded.
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].action().name(), "test_keymap::Alpha");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.position)
+                .collect::<Vec<_>>(),
+            [Some((5, 7)), Some((11, 7)), Some((12, 7))]
+        );
+        assert!(errors[0].message.contains("test_keymap::Missing"));
+        assert!(errors[1]
+            .message
+            .starts_with("invalid binding for `ctrl-b`"));
+        assert!(errors[2].message.contains(
+            "invalid arguments {\"distance\":\"far\"} for action test_keymap::Move: \
+            invalid type: string \"far\", expected usize"
+        ));
+    }
+
+    #[gpui::test]
+    fn test_malformed_user_keymap_actions(cx: &mut AppContext) {
+        let content = r#"[
+            {
+                "bindings": {
+                    "ctrl-a": ["test_keymap::Move"],
+                    "ctrl-b": [1, { "distance": 1 }],
+                    "ctrl-c": { "distance": 1 },
+                    "ctrl-d": ["test_keymap::Move", {}]
+                }
+            }
+        ]"#;
+        let (bindings, errors) = KeymapFile::parse_user_keymap(content)
+            .unwrap()
+            .user_bindings(content, cx);
+
+        assert!(bindings.is_empty());
+        assert_eq!(
+            errors
+                .into_iter()
+                .map(|error| error.message)
+                .collect::<Vec<_>>(),
+            [
+                "invalid binding for `ctrl-a`: expected an action name and its arguments, \
+                like [\"pane::ActivateItem\", 1]",
+                "invalid binding for `ctrl-b`: \
+                expected the first item in the array to be an action name",
+                "invalid binding for `ctrl-c`: expected an action name, \
+                or an action name and its arguments, got {\"distance\":1}",
+                "invalid binding for `ctrl-d`: invalid arguments {} for action test_keymap::Move: \
+                missing field `distance`",
+            ]
+        );
+    }
 }
@@ -1,5 +1,6 @@
 mod keymap_file;
 mod settings_file;
+mod settings_migrations;
 mod settings_store;
 
 use gpui::AppContext;
@@ -7,10 +8,11 @@ use rust_embed::RustEmbed;
 use std::{borrow::Cow, str};
 use util::asset_str;
 
-pub use keymap_file::KeymapFile;
+pub use keymap_file::{KeymapFile, KeymapFileError};
 pub use settings_file::*;
 pub use settings_store::{
-    Settings, SettingsJsonSchemaParams, SettingsLocation, SettingsSources, SettingsStore,
+    LayerKey, Settings, SettingsJsonSchemaParams, SettingsLocation, SettingsSources, SettingsStore,
+    SettingsWarning, UnknownKeyWarning,
 };
 
 #[derive(RustEmbed)]
@@ -22,6 +24,7 @@ pub struct SettingsAssets;
 
 pub fn init(cx: &mut AppContext) {
     let mut settings = SettingsStore::default();
+    settings_migrations::register_migrations(&mut settings);
     settings
         .set_default_settings(&default_settings(), cx)
         .unwrap();
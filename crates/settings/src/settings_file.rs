@@ -64,6 +64,7 @@ pub fn watch_config_file(
 
 pub fn handle_settings_file_changes(
     mut user_settings_file_rx: mpsc::UnboundedReceiver<String>,
+    fs: Arc<dyn Fs>,
     cx: &mut AppContext,
 ) {
     let user_settings_content = cx
@@ -71,6 +72,7 @@ pub fn handle_settings_file_changes(
         .block(user_settings_file_rx.next())
         .unwrap();
     SettingsStore::update_global(cx, |store, cx| {
+        let user_settings_content = migrate_settings_file(store, user_settings_content, &fs, cx);
         store
             .set_user_settings(&user_settings_content, cx)
             .log_err();
@@ -78,6 +80,8 @@ pub fn handle_settings_file_changes(
     cx.spawn(move |mut cx| async move {
         while let Some(user_settings_content) = user_settings_file_rx.next().await {
             let result = cx.update_global(|store: &mut SettingsStore, cx| {
+                let user_settings_content =
+                    migrate_settings_file(store, user_settings_content, &fs, cx);
                 store
                     .set_user_settings(&user_settings_content, cx)
                     .log_err();
@@ -91,6 +95,28 @@ pub fn handle_settings_file_changes(
     .detach();
 }
 
+/// Applies any pending migrations to the contents of the user settings file,
+/// writing the migrated contents back to disk if the migrations changed them.
+fn migrate_settings_file(
+    store: &SettingsStore,
+    user_settings_content: String,
+    fs: &Arc<dyn Fs>,
+    cx: &mut AppContext,
+) -> String {
+    if user_settings_content.trim().is_empty() {
+        return user_settings_content;
+    }
+    let Some(migrated_content) = store.migrate_settings_text(&user_settings_content) else {
+        return user_settings_content;
+    };
+    let fs = fs.clone();
+    let new_text = migrated_content.clone();
+    cx.background_executor()
+        .spawn(async move { save_settings(&fs, new_text).await })
+        .detach_and_log_err(cx);
+    migrated_content
+}
+
 async fn load_settings(fs: &Arc<dyn Fs>) -> Result<String> {
     match fs.load(paths::settings_file()).await {
         result @ Ok(_) => result,
@@ -115,22 +141,27 @@ pub fn update_settings_file<T: Settings>(
         let new_text = cx.read_global(|store: &SettingsStore, _cx| {
             store.new_text_for_update::<T>(old_text, update)
         })?;
-        let initial_path = paths::settings_file().as_path();
-        if fs.is_file(initial_path).await {
-            let resolved_path = fs.canonicalize(initial_path).await.with_context(|| {
-                format!("Failed to canonicalize settings path {:?}", initial_path)
-            })?;
-
-            fs.atomic_write(resolved_path.clone(), new_text)
-                .await
-                .with_context(|| format!("Failed to write settings to file {:?}", resolved_path))?;
-        } else {
-            fs.atomic_write(initial_path.to_path_buf(), new_text)
-                .await
-                .with_context(|| format!("Failed to write settings to file {:?}", initial_path))?;
-        }
-
-        anyhow::Ok(())
+        save_settings(&fs, new_text).await
     })
     .detach_and_log_err(cx);
 }
+
+async fn save_settings(fs: &Arc<dyn Fs>, new_text: String) -> Result<()> {
+    let initial_path = paths::settings_file().as_path();
+    if fs.is_file(initial_path).await {
+        let resolved_path = fs
+            .canonicalize(initial_path)
+            .await
+            .with_context(|| format!("Failed to canonicalize settings path {:?}", initial_path))?;
+
+        fs.atomic_write(resolved_path.clone(), new_text)
+            .await
+            .with_context(|| format!("Failed to write settings to file {:?}", resolved_path))?;
+    } else {
+        fs.atomic_write(initial_path.to_path_buf(), new_text)
+            .await
+            .with_context(|| format!("Failed to write settings to file {:?}", initial_path))?;
+    }
+
+    anyhow::Ok(())
+}
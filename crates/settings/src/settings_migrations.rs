@@ -0,0 +1,93 @@
+use crate::SettingsStore;
+use serde_json::Value;
+
+/// Registers the migrations for settings that have been renamed or reshaped
+/// since the user settings file started recording its `version`.
+pub(crate) fn register_migrations(store: &mut SettingsStore) {
+    store.register_migration(0, 1, migrate_copilot_feature);
+    store.register_migration(1, 2, migrate_show_copilot_suggestions);
+    store.register_migration(2, 3, migrate_ui_density);
+}
+
+/// `features.copilot` was replaced by `features.inline_completion_provider`.
+fn migrate_copilot_feature(settings: &mut Value) {
+    let Some(features) = settings
+        .get_mut("features")
+        .and_then(|features| features.as_object_mut())
+    else {
+        return;
+    };
+    let Some(copilot) = features.remove("copilot") else {
+        return;
+    };
+    if let Some(enabled) = copilot.as_bool() {
+        features
+            .entry("inline_completion_provider")
+            .or_insert_with(|| if enabled { "copilot" } else { "none" }.into());
+    }
+}
+
+/// `show_copilot_suggestions` was renamed to `show_inline_completions`, both
+/// globally and within each language's settings.
+fn migrate_show_copilot_suggestions(settings: &mut Value) {
+    rename_key(
+        settings,
+        "show_copilot_suggestions",
+        "show_inline_completions",
+    );
+    if let Some(languages) = settings
+        .get_mut("languages")
+        .and_then(|languages| languages.as_object_mut())
+    {
+        for language_settings in languages.values_mut() {
+            rename_key(
+                language_settings,
+                "show_copilot_suggestions",
+                "show_inline_completions",
+            );
+        }
+    }
+}
+
+/// `unstable.ui_density` was stabilized as `ui_density`.
+fn migrate_ui_density(settings: &mut Value) {
+    rename_key(settings, "unstable.ui_density", "ui_density");
+}
+
+fn rename_key(object: &mut Value, old_key: &str, new_key: &str) {
+    let Some(object) = object.as_object_mut() else {
+        return;
+    };
+    if let Some(value) = object.remove(old_key) {
+        object.entry(new_key).or_insert(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_migrate_ui_density() {
+        let mut store = SettingsStore::default();
+        register_migrations(&mut store);
+
+        let mut settings = json!({ "version": 2, "unstable.ui_density": "compact" });
+        assert!(store.migrate_settings(&mut settings));
+        assert_eq!(settings, json!({ "version": 3, "ui_density": "compact" }));
+
+        // An existing `ui_density` takes precedence over the unstable one.
+        let mut settings = json!({
+            "version": 2,
+            "unstable.ui_density": "compact",
+            "ui_density": "comfortable"
+        });
+        assert!(store.migrate_settings(&mut settings));
+        assert_eq!(
+            settings,
+            json!({ "version": 3, "ui_density": "comfortable" })
+        );
+    }
+}
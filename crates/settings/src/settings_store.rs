@@ -7,7 +7,8 @@ use serde::{de::DeserializeOwned, Deserialize as _, Serialize};
 use smallvec::SmallVec;
 use std::{
     any::{type_name, Any, TypeId},
-    fmt::Debug,
+    fmt::{self, Debug},
+    mem,
     ops::Range,
     path::Path,
     str,
@@ -144,12 +145,71 @@ pub struct SettingsLocation<'a> {
     pub path: &'a Path,
 }
 
+/// Identifies a layer of project settings, loaded from the `.zed/settings.json`
+/// file in a directory of a worktree.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayerKey {
+    pub worktree_id: usize,
+    /// The directory that the layer applies to, relative to the worktree root.
+    pub path: Arc<Path>,
+}
+
+impl LayerKey {
+    pub fn new(worktree_id: usize, path: Arc<Path>) -> Self {
+        Self { worktree_id, path }
+    }
+
+    /// The layer that applies to an entire worktree.
+    pub fn worktree_root(worktree_id: usize) -> Self {
+        Self::new(worktree_id, Path::new("").into())
+    }
+}
+
 pub struct SettingsJsonSchemaParams<'a> {
     pub staff_mode: bool,
     pub language_names: &'a [String],
     pub font_names: &'a [String],
 }
 
+/// A problem with the user's settings that doesn't prevent them from being loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SettingsWarning {
+    UnknownKey(UnknownKeyWarning),
+}
+
+impl fmt::Display for SettingsWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsWarning::UnknownKey(warning) => {
+                write!(f, "Unknown setting `{}`", warning.path)
+            }
+        }
+    }
+}
+
+/// A key in a settings file that doesn't correspond to any registered setting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownKeyWarning {
+    /// The dot-separated path to the key, e.g. `git.inline_blam`.
+    pub path: String,
+    keys: Vec<String>,
+}
+
+impl UnknownKeyWarning {
+    fn new(keys: Vec<String>) -> Self {
+        Self {
+            path: keys.join("."),
+            keys,
+        }
+    }
+}
+
+const RELEASE_STAGES: [&str; 4] = ["dev", "nightly", "stable", "preview"];
+
+/// The key in the user settings file that records which migrations have
+/// already been applied to it.
+const SETTINGS_VERSION_KEY: &str = "version";
+
 /// A set of strongly-typed setting values defined via multiple JSON files.
 pub struct SettingsStore {
     setting_values: HashMap<TypeId, Box<dyn AnySettingValue>>,
@@ -157,6 +217,8 @@ pub struct SettingsStore {
     raw_user_settings: serde_json::Value,
     raw_extension_settings: serde_json::Value,
     raw_local_settings: BTreeMap<(usize, Arc<Path>), serde_json::Value>,
+    warnings: Vec<SettingsWarning>,
+    migrations: BTreeMap<u32, (u32, fn(&mut serde_json::Value))>,
     tab_size_callback: Option<(
         TypeId,
         Box<dyn Fn(&dyn Any) -> Option<usize> + Send + Sync + 'static>,
@@ -173,6 +235,8 @@ impl Default for SettingsStore {
             raw_user_settings: serde_json::json!({}),
             raw_extension_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
+            warnings: Default::default(),
+            migrations: Default::default(),
             tab_size_callback: Default::default(),
         }
     }
@@ -266,6 +330,20 @@ impl SettingsStore {
                 setting_value.set_global_value(setting);
             }
         }
+
+        // User settings are usually loaded before every setting has been registered,
+        // so keys reported as unknown may belong to this setting.
+        if !self.warnings.is_empty() {
+            let setting_value = &self.setting_values[&setting_type_id];
+            let schema = setting_value.json_schema(
+                &mut Self::schema_generator(),
+                &Self::validation_schema_params(),
+                cx,
+            );
+            let schema = serde_json::to_value(&schema).unwrap();
+            let key = setting_value.key();
+            self.revalidate_unknown_keys(key, &schema);
+        }
     }
 
     /// Get the value of a setting.
@@ -299,6 +377,11 @@ impl SettingsStore {
         &self.raw_user_settings
     }
 
+    /// Problems found the last time the user's settings were loaded.
+    pub fn warnings(&self) -> &[SettingsWarning] {
+        &self.warnings
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut AppContext) -> Self {
         let mut this = Self::default();
@@ -430,12 +513,18 @@ impl SettingsStore {
         user_settings_content: &str,
         cx: &mut AppContext,
     ) -> Result<()> {
-        let settings: serde_json::Value = if user_settings_content.is_empty() {
+        let mut settings: serde_json::Value = if user_settings_content.is_empty() {
             parse_json_with_comments("{}")?
         } else {
             parse_json_with_comments(user_settings_content)?
         };
         if settings.is_object() {
+            self.migrate_settings(&mut settings);
+            self.warnings = self
+                .validate_unknown_keys(&settings, cx)
+                .into_iter()
+                .map(SettingsWarning::UnknownKey)
+                .collect();
             self.raw_user_settings = settings;
             self.recompute_values(None, cx)?;
             Ok(())
@@ -444,6 +533,186 @@ impl SettingsStore {
         }
     }
 
+    /// Registers a function that rewrites user settings written for
+    /// `from_version` into the format expected by `to_version`.
+    pub fn register_migration(
+        &mut self,
+        from_version: u32,
+        to_version: u32,
+        migrate: fn(&mut serde_json::Value),
+    ) {
+        debug_assert!(
+            to_version > from_version,
+            "settings migrations must move to a newer version"
+        );
+        self.migrations.insert(from_version, (to_version, migrate));
+    }
+
+    /// The version of the user settings format that results from applying every
+    /// registered migration.
+    pub fn settings_version(&self) -> u32 {
+        self.migrations
+            .values()
+            .map(|(to_version, _)| *to_version)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Applies, in sequence, every registered migration starting from the `version`
+    /// recorded in the given settings, and records the resulting version.
+    ///
+    /// Returns whether any migration was applied.
+    pub fn migrate_settings(&self, settings: &mut serde_json::Value) -> bool {
+        let Some(object) = settings.as_object() else {
+            return false;
+        };
+        let mut version = object
+            .get(SETTINGS_VERSION_KEY)
+            .and_then(|version| version.as_u64())
+            .and_then(|version| u32::try_from(version).ok())
+            .unwrap_or(0);
+
+        let mut migrated = false;
+        while let Some((to_version, migrate)) = self.migrations.get(&version) {
+            migrate(settings);
+            version = *to_version;
+            migrated = true;
+        }
+
+        if migrated {
+            if let Some(object) = settings.as_object_mut() {
+                object.insert(SETTINGS_VERSION_KEY.to_string(), version.into());
+            }
+        }
+        migrated
+    }
+
+    /// Returns the given user settings file contents with any pending migrations
+    /// applied, or `None` if the migrations don't change any setting. In that
+    /// case the file is left alone, even if it doesn't record a version yet.
+    ///
+    /// Comments and formatting are preserved for everything the migrations
+    /// don't touch.
+    pub fn migrate_settings_text(&self, text: &str) -> Option<String> {
+        let old_value = parse_json_with_comments::<serde_json::Value>(text).ok()?;
+        let mut new_value = old_value.clone();
+        if !self.migrate_settings(&mut new_value) {
+            return None;
+        }
+        let without_version = |value: &serde_json::Value| {
+            let mut value = value.clone();
+            if let Some(object) = value.as_object_mut() {
+                object.remove(SETTINGS_VERSION_KEY);
+            }
+            value
+        };
+        if without_version(&new_value) == without_version(&old_value) {
+            return None;
+        }
+
+        let mut new_text = text.to_string();
+        let mut remaining_value = old_value;
+        let mut removed_key_paths = Vec::new();
+        collect_removed_key_paths(
+            &remaining_value,
+            &new_value,
+            &mut Vec::new(),
+            &mut removed_key_paths,
+        );
+        for key_path in removed_key_paths {
+            remove_key_in_json_text(&mut new_text, &key_path);
+            remove_key_in_json_value(&mut remaining_value, &key_path);
+        }
+
+        update_value_in_json_text(
+            &mut new_text,
+            &mut Vec::new(),
+            self.json_tab_size(),
+            &remaining_value,
+            &new_value,
+            &mut Vec::new(),
+        );
+        Some(new_text)
+    }
+
+    /// Returns the default value of every registered setting as JSON, with the
+    /// documentation of each setting included as a comment above it.
+    pub fn export_default_json(&self, cx: &AppContext) -> String {
+        let schema = self.json_schema(&Self::validation_schema_params(), cx);
+        let mut output = String::new();
+        write_json_with_docs(
+            &mut output,
+            &self.raw_default_settings,
+            &schema,
+            &schema["definitions"],
+            self.json_tab_size(),
+            0,
+        );
+        output.push('\n');
+        output
+    }
+
+    /// Returns every key in the given settings JSON that isn't described by the
+    /// schema of any registered setting.
+    pub fn validate_unknown_keys(
+        &self,
+        settings: &serde_json::Value,
+        cx: &AppContext,
+    ) -> Vec<UnknownKeyWarning> {
+        let schema = self.json_schema(&Self::validation_schema_params(), cx);
+        let mut warnings = Vec::new();
+        collect_unknown_keys(
+            settings,
+            &schema,
+            &schema["definitions"],
+            &mut Vec::new(),
+            &mut warnings,
+        );
+        warnings
+    }
+
+    /// Re-checks the current unknown-key warnings against the schema of a newly
+    /// registered setting.
+    fn revalidate_unknown_keys(&mut self, key: Option<&str>, schema: &serde_json::Value) {
+        let definitions = &schema["definitions"];
+        let mut warnings = Vec::new();
+        for warning in mem::take(&mut self.warnings) {
+            let SettingsWarning::UnknownKey(unknown_key) = &warning;
+            let mut keys = unknown_key.keys.as_slice();
+            if keys.len() > 1 && RELEASE_STAGES.contains(&keys[0].as_str()) {
+                keys = &keys[1..];
+            }
+            let keys_in_schema = match key {
+                Some(key) if keys[0] == key => Some(&keys[1..]),
+                Some(_) => None,
+                None => Some(keys),
+            };
+            let value = unknown_key
+                .keys
+                .iter()
+                .try_fold(&self.raw_user_settings, |value, key| value.get(key));
+
+            match keys_in_schema
+                .and_then(|keys| schema_at_path(schema, definitions, keys))
+                .zip(value)
+            {
+                Some((child_schema, value)) => {
+                    let mut new_warnings = Vec::new();
+                    collect_unknown_keys(
+                        value,
+                        child_schema,
+                        definitions,
+                        &mut unknown_key.keys.clone(),
+                        &mut new_warnings,
+                    );
+                    warnings.extend(new_warnings.into_iter().map(SettingsWarning::UnknownKey));
+                }
+                None => warnings.push(warning),
+            }
+        }
+        self.warnings = warnings;
+    }
+
     /// Add or remove a set of local settings via a JSON string.
     pub fn set_local_settings(
         &mut self,
@@ -452,15 +721,36 @@ impl SettingsStore {
         settings_content: Option<&str>,
         cx: &mut AppContext,
     ) -> Result<()> {
-        if settings_content.is_some_and(|content| !content.is_empty()) {
-            self.raw_local_settings.insert(
-                (root_id, path.clone()),
-                parse_json_with_comments(settings_content.unwrap())?,
-            );
-        } else {
-            self.raw_local_settings.remove(&(root_id, path.clone()));
+        let key = LayerKey::new(root_id, path);
+        match settings_content.filter(|content| !content.is_empty()) {
+            Some(settings_content) => self.push_layer(key, settings_content, cx),
+            None => self.remove_layer(&key, cx),
         }
-        self.recompute_values(Some((root_id, &path)), cx)?;
+    }
+
+    /// Adds a layer of project settings, which overrides the user's settings and
+    /// the layers of any parent directories for the files within its directory.
+    ///
+    /// Replaces any existing layer with the same key.
+    pub fn push_layer(
+        &mut self,
+        key: LayerKey,
+        settings_content: &str,
+        cx: &mut AppContext,
+    ) -> Result<()> {
+        let settings = parse_json_with_comments(settings_content)?;
+        let LayerKey { worktree_id, path } = key;
+        self.raw_local_settings
+            .insert((worktree_id, path.clone()), settings);
+        self.recompute_values(Some((worktree_id, &path)), cx)?;
+        Ok(())
+    }
+
+    /// Removes a layer of project settings that was added with [`Self::push_layer`].
+    pub fn remove_layer(&mut self, key: &LayerKey, cx: &mut AppContext) -> Result<()> {
+        self.raw_local_settings
+            .remove(&(key.worktree_id, key.path.clone()));
+        self.recompute_values(Some((key.worktree_id, &key.path)), cx)?;
         Ok(())
     }
 
@@ -497,15 +787,9 @@ impl SettingsStore {
         schema_params: &SettingsJsonSchemaParams,
         cx: &AppContext,
     ) -> serde_json::Value {
-        use schemars::{
-            gen::SchemaSettings,
-            schema::{Schema, SchemaObject},
-        };
+        use schemars::schema::{Schema, SchemaObject};
 
-        let settings = SchemaSettings::draft07().with(|settings| {
-            settings.option_add_null_type = false;
-        });
-        let mut generator = SchemaGenerator::new(settings);
+        let mut generator = Self::schema_generator();
         let mut combined_schema = RootSchema::default();
 
         for setting_value in self.setting_values.values() {
@@ -587,7 +871,7 @@ impl SettingsStore {
             }
         }
 
-        for release_stage in ["dev", "nightly", "stable", "preview"] {
+        for release_stage in RELEASE_STAGES {
             let schema = combined_schema.schema.clone();
             combined_schema
                 .schema
@@ -596,9 +880,30 @@ impl SettingsStore {
                 .insert(release_stage.to_string(), schema.into());
         }
 
+        combined_schema.schema.object().properties.insert(
+            SETTINGS_VERSION_KEY.to_string(),
+            generator.subschema_for::<u32>(),
+        );
+
         serde_json::to_value(&combined_schema).unwrap()
     }
 
+    fn schema_generator() -> SchemaGenerator {
+        let settings = schemars::gen::SchemaSettings::draft07().with(|settings| {
+            settings.option_add_null_type = false;
+        });
+        SchemaGenerator::new(settings)
+    }
+
+    /// Parameters for schemas that are only used to check which keys exist.
+    fn validation_schema_params() -> SettingsJsonSchemaParams<'static> {
+        SettingsJsonSchemaParams {
+            staff_mode: false,
+            language_names: &[],
+            font_names: &[],
+        }
+    }
+
     fn recompute_values(
         &mut self,
         changed_local_path: Option<(usize, &Path)>,
@@ -860,6 +1165,96 @@ fn update_value_in_json_text<'a>(
     }
 }
 
+fn collect_removed_key_paths(
+    old_value: &serde_json::Value,
+    new_value: &serde_json::Value,
+    key_path: &mut Vec<String>,
+    removed_key_paths: &mut Vec<Vec<String>>,
+) {
+    let (Some(old_object), Some(new_object)) = (old_value.as_object(), new_value.as_object())
+    else {
+        return;
+    };
+    for (key, old_sub_value) in old_object {
+        key_path.push(key.clone());
+        match new_object.get(key) {
+            Some(new_sub_value) => {
+                collect_removed_key_paths(old_sub_value, new_sub_value, key_path, removed_key_paths)
+            }
+            None => removed_key_paths.push(key_path.clone()),
+        }
+        key_path.pop();
+    }
+}
+
+fn remove_key_in_json_value(value: &mut serde_json::Value, key_path: &[String]) {
+    let Some((last_key, parent_path)) = key_path.split_last() else {
+        return;
+    };
+    let parent = parent_path
+        .iter()
+        .try_fold(value, |value, key| value.get_mut(key.as_str()));
+    if let Some(parent) = parent.and_then(|parent| parent.as_object_mut()) {
+        parent.remove(last_key);
+    }
+}
+
+fn remove_key_in_json_text(text: &mut String, key_path: &[String]) {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_json::language()).unwrap();
+    let Some(syntax_tree) = parser.parse(text.as_str(), None) else {
+        return;
+    };
+
+    let mut pair_range = None;
+    let mut node = syntax_tree.root_node().named_child(0);
+    for key in key_path {
+        let Some(object) = node.filter(|node| node.kind() == "object") else {
+            return;
+        };
+        let quoted_key = format!("\"{}\"", key);
+        let mut cursor = object.walk();
+        let Some(pair) = object.named_children(&mut cursor).find(|child| {
+            child.kind() == "pair"
+                && child
+                    .child_by_field_name("key")
+                    .and_then(|key| text.get(key.byte_range()))
+                    .map_or(false, |key_text| key_text == quoted_key)
+        }) else {
+            return;
+        };
+        pair_range = Some(pair.byte_range());
+        node = pair.child_by_field_name("value");
+    }
+    let Some(Range { mut start, mut end }) = pair_range else {
+        return;
+    };
+
+    // Remove the comma that separates this pair from the next one or, if this
+    // is the last pair, from the previous one.
+    let after = &text[end..];
+    let after_trimmed = after.trim_start();
+    let before_trimmed = text[..start].trim_end();
+    if after_trimmed.starts_with(',') {
+        end += after.len() - after_trimmed.len() + 1;
+    } else if before_trimmed.ends_with(',') {
+        start = before_trimmed.len() - 1;
+    }
+
+    // If the pair occupied whole lines, remove those lines entirely.
+    let line_start = text[..start].rfind('\n').map_or(0, |ix| ix + 1);
+    if text[line_start..start].trim().is_empty() {
+        if let Some(line_end) = text[end..].find('\n').map(|ix| end + ix + 1) {
+            if text[end..line_end].trim().is_empty() {
+                start = line_start;
+                end = line_end;
+            }
+        }
+    }
+
+    text.replace_range(start..end, "");
+}
+
 fn replace_value_in_json_text(
     text: &str,
     key_path: &[&str],
@@ -1007,6 +1402,196 @@ fn to_pretty_json(value: &impl Serialize, indent_size: usize, indent_prefix_len:
     adjusted_text
 }
 
+/// The keys accepted by a JSON schema describing an object, gathered across
+/// any references and combinators it contains.
+#[derive(Default)]
+struct ObjectSchema<'a> {
+    properties: HashMap<&'a str, &'a serde_json::Value>,
+    additional_properties: Option<&'a serde_json::Value>,
+    closed: bool,
+    open: bool,
+}
+
+impl<'a> ObjectSchema<'a> {
+    fn new(schema: &'a serde_json::Value, definitions: &'a serde_json::Value) -> Self {
+        let mut object = Self::default();
+        object.gather(schema, definitions);
+        object
+    }
+
+    fn gather(&mut self, schema: &'a serde_json::Value, definitions: &'a serde_json::Value) {
+        let mut constrained = false;
+        if let Some(name) = schema
+            .get("$ref")
+            .and_then(|reference| reference.as_str()?.strip_prefix("#/definitions/"))
+        {
+            constrained = true;
+            if let Some(definition) = definitions.get(name) {
+                self.gather(definition, definitions);
+            }
+        }
+        for combinator in ["allOf", "anyOf", "oneOf"] {
+            if let Some(subschemas) = schema.get(combinator).and_then(|value| value.as_array()) {
+                constrained = true;
+                for subschema in subschemas {
+                    self.gather(subschema, definitions);
+                }
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(|value| value.as_object()) {
+            if !properties.is_empty() {
+                constrained = true;
+                self.closed = true;
+                self.properties
+                    .extend(properties.iter().map(|(key, value)| (key.as_str(), value)));
+            }
+        }
+        match schema.get("additionalProperties") {
+            Some(serde_json::Value::Bool(false)) => {
+                constrained = true;
+                self.closed = true;
+            }
+            Some(additional_properties) => {
+                constrained = true;
+                self.additional_properties = Some(additional_properties);
+            }
+            None => {}
+        }
+
+        let is_object = match schema.get("type") {
+            Some(serde_json::Value::String(ty)) => ty == "object",
+            Some(serde_json::Value::Array(types)) => types.iter().any(|ty| ty == "object"),
+            _ => false,
+        };
+        if (is_object && !constrained) || schema.get("patternProperties").is_some() {
+            self.open = true;
+        }
+    }
+
+    /// Returns the schema for the given key, or `None` if the key isn't allowed.
+    fn schema_for_key(&self, key: &str) -> Option<&'a serde_json::Value> {
+        static ANY_SCHEMA: serde_json::Value = serde_json::Value::Bool(true);
+
+        self.properties
+            .get(key)
+            .copied()
+            .or(self.additional_properties)
+            .or_else(|| (self.open || !self.closed).then_some(&ANY_SCHEMA))
+    }
+}
+
+fn schema_at_path<'a>(
+    schema: &'a serde_json::Value,
+    definitions: &'a serde_json::Value,
+    keys: &[String],
+) -> Option<&'a serde_json::Value> {
+    keys.iter().try_fold(schema, |schema, key| {
+        let object = ObjectSchema::new(schema, definitions);
+        if object.properties.contains_key(key.as_str()) || object.additional_properties.is_some() {
+            object.schema_for_key(key)
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the documentation of the setting described by the given schema.
+fn schema_description<'a>(
+    schema: &'a serde_json::Value,
+    definitions: &'a serde_json::Value,
+) -> Option<&'a str> {
+    if let Some(description) = schema.get("description").and_then(|value| value.as_str()) {
+        return Some(description);
+    }
+    if let Some(name) = schema
+        .get("$ref")
+        .and_then(|reference| reference.as_str()?.strip_prefix("#/definitions/"))
+    {
+        return schema_description(definitions.get(name)?, definitions);
+    }
+    match schema.get("allOf").and_then(|value| value.as_array()) {
+        Some(subschemas) if subschemas.len() == 1 => {
+            schema_description(&subschemas[0], definitions)
+        }
+        _ => None,
+    }
+}
+
+fn write_json_with_docs(
+    output: &mut String,
+    value: &serde_json::Value,
+    schema: &serde_json::Value,
+    definitions: &serde_json::Value,
+    tab_size: usize,
+    depth: usize,
+) {
+    static ANY_SCHEMA: serde_json::Value = serde_json::Value::Bool(true);
+
+    let Some(object) = value.as_object().filter(|object| !object.is_empty()) else {
+        output.push_str(&to_pretty_json(value, tab_size, tab_size * depth));
+        return;
+    };
+
+    let indent = " ".repeat(tab_size * (depth + 1));
+    let object_schema = ObjectSchema::new(schema, definitions);
+    output.push_str("{\n");
+    for (ix, (key, child_value)) in object.iter().enumerate() {
+        let child_schema = object_schema.schema_for_key(key).unwrap_or(&ANY_SCHEMA);
+        if let Some(description) = schema_description(child_schema, definitions) {
+            for line in description.lines() {
+                output.push_str(&indent);
+                output.push_str("//");
+                if !line.is_empty() {
+                    output.push(' ');
+                    output.push_str(line);
+                }
+                output.push('\n');
+            }
+        }
+        output.push_str(&indent);
+        output.push_str(&serde_json::to_string(key).unwrap());
+        output.push_str(": ");
+        write_json_with_docs(
+            output,
+            child_value,
+            child_schema,
+            definitions,
+            tab_size,
+            depth + 1,
+        );
+        if ix + 1 < object.len() {
+            output.push(',');
+        }
+        output.push('\n');
+    }
+    output.push_str(&" ".repeat(tab_size * depth));
+    output.push('}');
+}
+
+fn collect_unknown_keys(
+    value: &serde_json::Value,
+    schema: &serde_json::Value,
+    definitions: &serde_json::Value,
+    keys: &mut Vec<String>,
+    warnings: &mut Vec<UnknownKeyWarning>,
+) {
+    let Some(object) = value.as_object() else {
+        return;
+    };
+
+    let object_schema = ObjectSchema::new(schema, definitions);
+    for (key, child_value) in object {
+        keys.push(key.clone());
+        match object_schema.schema_for_key(key) {
+            Some(child_schema) => {
+                collect_unknown_keys(child_value, child_schema, definitions, keys, warnings)
+            }
+            None => warnings.push(UnknownKeyWarning::new(keys.clone())),
+        }
+        keys.pop();
+    }
+}
+
 pub fn parse_json_with_comments<T: DeserializeOwned>(content: &str) -> Result<T> {
     Ok(serde_json_lenient::from_str(content)?)
 }
@@ -1146,6 +1731,258 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_unknown_setting_keys(cx: &mut AppContext) {
+        let mut store = SettingsStore::default();
+        store.register_setting::<UserSettings>(cx);
+        store.register_setting::<MultiKeySettings>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    },
+                    "journal": {
+                        "path": "~",
+                        "hour_format": "hour12"
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+
+        store
+            .set_user_settings(
+                r#"{
+                    "key1": "a",
+                    "kye2": "b",
+                    "user": { "name": "Jane Doe", "aeg": 31 },
+                    "journal": { "path": "~/notes", "huor_format": "hour24" },
+                    "dev": { "key1": "c", "key3": "d" }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            unknown_key_paths(&store),
+            ["dev.key3", "journal", "kye2", "user.aeg"]
+        );
+
+        // Registering a setting re-checks the keys that it now accounts for.
+        store.register_setting::<JournalSettings>(cx);
+        assert_eq!(
+            unknown_key_paths(&store),
+            ["dev.key3", "journal.huor_format", "kye2", "user.aeg"]
+        );
+
+        store
+            .set_user_settings(r#"{ "key2": "b", "user": { "age": 31 } }"#, cx)
+            .unwrap();
+        assert!(store.warnings().is_empty());
+
+        fn unknown_key_paths(store: &SettingsStore) -> Vec<String> {
+            let mut paths = store
+                .warnings()
+                .iter()
+                .map(|warning| {
+                    let SettingsWarning::UnknownKey(warning) = warning;
+                    warning.path.clone()
+                })
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+        }
+    }
+
+    #[gpui::test]
+    fn test_settings_migrations(cx: &mut AppContext) {
+        let mut store = SettingsStore::default();
+        store.register_setting::<UserSettings>(cx);
+        store.register_migration(0, 1, |settings| {
+            let settings = settings.as_object_mut().unwrap();
+            if let Some(person) = settings.remove("person") {
+                settings.insert("user".into(), person);
+            }
+        });
+        store.register_migration(1, 2, |settings| {
+            if let Some(user) = settings.get_mut("user").and_then(|u| u.as_object_mut()) {
+                if let Some(years) = user.remove("years") {
+                    user.insert("age".into(), years);
+                }
+            }
+        });
+        store
+            .set_default_settings(
+                r#"{
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        assert_eq!(store.settings_version(), 2);
+
+        let old_text = r#"{
+            // Who I am
+            "person": {
+                "name": "Jane Doe",
+                "years": 31
+            },
+            "turbo": true
+        }"#
+        .unindent();
+
+        store.set_user_settings(&old_text, cx).unwrap();
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "Jane Doe".to_string(),
+                age: 31,
+                staff: false,
+            }
+        );
+
+        let new_text = store.migrate_settings_text(&old_text).unwrap();
+        assert!(new_text.contains("// Who I am"));
+        assert_eq!(
+            parse_json_with_comments::<serde_json::Value>(&new_text).unwrap(),
+            serde_json::json!({
+                "user": { "name": "Jane Doe", "age": 31 },
+                "turbo": true,
+                "version": 2
+            })
+        );
+
+        // Settings that are already at the latest version are left alone.
+        assert_eq!(store.migrate_settings_text(&new_text), None);
+
+        // So are unversioned settings that none of the migrations change.
+        let unaffected_text = r#"{
+            "user": { "name": "Jane Doe" }
+        }"#
+        .unindent();
+        assert_eq!(store.migrate_settings_text(&unaffected_text), None);
+        let mut settings = serde_json::json!({ "version": 1, "user": { "years": 40 } });
+        assert!(store.migrate_settings(&mut settings));
+        assert_eq!(
+            settings,
+            serde_json::json!({ "version": 2, "user": { "age": 40 } })
+        );
+    }
+
+    #[gpui::test]
+    fn test_settings_layers(cx: &mut AppContext) {
+        let mut store = SettingsStore::default();
+        store.register_setting::<UserSettings>(cx);
+        store
+            .set_default_settings(
+                r#"{ "user": { "name": "John Doe", "age": 30, "staff": false } }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_user_settings(r#"{ "user": { "age": 31 } }"#, cx)
+            .unwrap();
+
+        let location = SettingsLocation {
+            worktree_id: 1,
+            path: Path::new("src/main.rs"),
+        };
+        store
+            .push_layer(
+                LayerKey::worktree_root(1),
+                r#"{ "user": { "staff": true } }"#,
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            store.get::<UserSettings>(Some(location)),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 31,
+                staff: true,
+            }
+        );
+
+        // Layers for nested directories override the worktree's layer.
+        store
+            .push_layer(
+                LayerKey::new(1, Path::new("src").into()),
+                r#"{ "user": { "staff": false, "age": 40 } }"#,
+                cx,
+            )
+            .unwrap();
+        assert_eq!(store.get::<UserSettings>(Some(location)).age, 40);
+        assert!(!store.get::<UserSettings>(Some(location)).staff);
+
+        // Other worktrees are unaffected.
+        assert_eq!(
+            store.get::<UserSettings>(Some(SettingsLocation {
+                worktree_id: 2,
+                path: Path::new("src/main.rs"),
+            })),
+            store.get::<UserSettings>(None)
+        );
+
+        store
+            .remove_layer(&LayerKey::new(1, Path::new("src").into()), cx)
+            .unwrap();
+        assert_eq!(store.get::<UserSettings>(Some(location)).age, 31);
+        assert!(store.get::<UserSettings>(Some(location)).staff);
+
+        store.remove_layer(&LayerKey::worktree_root(1), cx).unwrap();
+        assert_eq!(
+            store.get::<UserSettings>(Some(location)),
+            store.get::<UserSettings>(None)
+        );
+    }
+
+    #[gpui::test]
+    fn test_export_default_json(cx: &mut AppContext) {
+        let mut store = SettingsStore::default();
+        store.register_setting::<UserSettings>(cx);
+        store.register_setting::<TurboSetting>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "turbo": false,
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+
+        let exported = store.export_default_json(cx);
+        pretty_assertions::assert_eq!(
+            exported,
+            r#"{
+              "turbo": false,
+              "user": {
+                // The user's name.
+                "name": "John Doe",
+                // The user's age, in years.
+                "age": 30,
+                "staff": false
+              }
+            }
+            "#
+            .unindent()
+        );
+        assert_eq!(
+            parse_json_with_comments::<serde_json::Value>(&exported).unwrap(),
+            store.raw_default_settings
+        );
+    }
+
     #[gpui::test]
     fn test_setting_store_assign_json_before_register(cx: &mut AppContext) {
         let mut store = SettingsStore::default();
@@ -1334,7 +2171,9 @@ mod tests {
 
     #[derive(Default, Clone, Serialize, Deserialize, JsonSchema)]
     struct UserSettingsJson {
+        /// The user's name.
         name: Option<String>,
+        /// The user's age, in years.
         age: Option<u32>,
         staff: Option<bool>,
     }
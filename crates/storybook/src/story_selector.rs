@@ -15,6 +15,7 @@ pub enum ComponentStory {
     ApplicationMenu,
     AutoHeightEditor,
     Avatar,
+    Breadcrumbs,
     Button,
     Checkbox,
     CollabNotification,
@@ -29,13 +30,22 @@ pub enum ComponentStory {
     List,
     ListHeader,
     ListItem,
+    LoadingStates,
     OverflowScroll,
+    Palette,
+    Panel,
     Picker,
     Scroll,
+    Select,
+    SelectableList,
     Setting,
+    StatusBar,
+    SyntaxPreview,
     Tab,
     TabBar,
+    TabStrip,
     Text,
+    Toast,
     ToggleButton,
     ToolStrip,
     ViewportUnits,
@@ -48,6 +58,7 @@ impl ComponentStory {
             Self::ApplicationMenu => cx.new_view(|_| title_bar::ApplicationMenuStory).into(),
             Self::AutoHeightEditor => AutoHeightEditorStory::new(cx).into(),
             Self::Avatar => cx.new_view(|_| ui::AvatarStory).into(),
+            Self::Breadcrumbs => cx.new_view(|_| ui::BreadcrumbsStory).into(),
             Self::Button => cx.new_view(|_| ui::ButtonStory).into(),
             Self::Checkbox => cx.new_view(|_| ui::CheckboxStory).into(),
             Self::CollabNotification => cx
@@ -64,12 +75,21 @@ impl ComponentStory {
             Self::List => cx.new_view(|_| ui::ListStory).into(),
             Self::ListHeader => cx.new_view(|_| ui::ListHeaderStory).into(),
             Self::ListItem => cx.new_view(|_| ui::ListItemStory).into(),
+            Self::LoadingStates => cx.new_view(ui::LoadingStatesStory::new).into(),
             Self::OverflowScroll => cx.new_view(|_| crate::stories::OverflowScrollStory).into(),
+            Self::Palette => cx.new_view(ui::PaletteStory::new).into(),
+            Self::Panel => cx.new_view(ui::PanelStory::new).into(),
             Self::Scroll => ScrollStory::view(cx).into(),
+            Self::Select => cx.new_view(|_| ui::SelectStory::default()).into(),
+            Self::SelectableList => cx.new_view(ui::SelectableListStory::new).into(),
             Self::Setting => cx.new_view(|cx| ui::SettingStory::init(cx)).into(),
+            Self::StatusBar => cx.new_view(|_| ui::StatusBarStory::default()).into(),
+            Self::SyntaxPreview => cx.new_view(|_| ui::SyntaxPreviewStory).into(),
             Self::Text => TextStory::view(cx).into(),
             Self::Tab => cx.new_view(|_| ui::TabStory).into(),
             Self::TabBar => cx.new_view(|_| ui::TabBarStory).into(),
+            Self::TabStrip => cx.new_view(ui::TabStripStory::new).into(),
+            Self::Toast => cx.new_view(|cx| ui::ToastStory::new(cx)).into(),
             Self::ToggleButton => cx.new_view(|_| ui::ToggleButtonStory).into(),
             Self::ToolStrip => cx.new_view(|_| ui::ToolStripStory).into(),
             Self::ViewportUnits => cx.new_view(|_| crate::stories::ViewportUnitsStory).into(),
@@ -15,7 +15,7 @@ use project::Project;
 use settings::{KeymapFile, Settings};
 use simplelog::SimpleLogger;
 use strum::IntoEnumIterator;
-use theme::{ThemeRegistry, ThemeSettings};
+use theme::{ThemeRegistry, ThemeSettings, UiDensity};
 use ui::prelude::*;
 
 use crate::app_menus::app_menus;
@@ -109,16 +109,55 @@ impl StoryWrapper {
     pub(crate) fn new(story: AnyView) -> Self {
         Self { story }
     }
+
+    fn set_ui_density(density: UiDensity, cx: &mut WindowContext) {
+        let mut theme_settings = ThemeSettings::get_global(cx).clone();
+        theme_settings.ui_density = density;
+        ThemeSettings::override_global(theme_settings, cx);
+        cx.refresh();
+    }
+
+    fn render_density_toggle(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let current_density = ThemeSettings::get_global(cx).ui_density;
+
+        h_flex()
+            .flex_none()
+            .gap(Spacing::Small.rems(cx))
+            .px(Spacing::Large.rems(cx))
+            .py(Spacing::Small.rems(cx))
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().title_bar_background)
+            .child(
+                Label::new("Density")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .children(
+                [
+                    ("Compact", UiDensity::Compact),
+                    ("Default", UiDensity::Default),
+                    ("Comfortable", UiDensity::Comfortable),
+                ]
+                .map(|(label, density)| {
+                    Button::new(label, label)
+                        .size(ButtonSize::Compact)
+                        .selected(density == current_density)
+                        .on_click(move |_, cx| Self::set_ui_density(density, cx))
+                }),
+            )
+    }
 }
 
 impl Render for StoryWrapper {
-    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         div()
             .flex()
             .flex_col()
             .size_full()
             .font_family("Zed Plex Mono")
-            .child(self.story.clone())
+            .child(self.render_density_toggle(cx))
+            .child(div().flex_1().min_h_0().child(self.story.clone()))
     }
 }
 
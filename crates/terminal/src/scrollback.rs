@@ -0,0 +1,173 @@
+//! Points of the terminal that stay on the same text as lines scroll into its history and are
+//! dropped from the top of it.
+
+use alacritty_terminal::{
+    index::{Column, Line, Point as AlacPoint},
+    term::TermMode,
+    Term,
+};
+
+use crate::ZedListener;
+
+/// The number of lines that Alacritty's history can grow past the limit of the scrollback
+/// before it starts to drop lines itself. Lines that Alacritty drops can't be counted, so the
+/// scrollback is trimmed back to its limit well before that.
+const TRIM_MARGIN: usize = 10_000;
+
+/// A point of the terminal, with its line counted from the first line of the terminal's
+/// output rather than from the top of the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnchoredPoint {
+    pub line: usize,
+    pub column: Column,
+}
+
+/// Counts the lines dropped from the top of the terminal's history, to anchor points to the
+/// output of the terminal.
+///
+/// It must only be used while holding the lock of the terminal it is updated with.
+pub struct Scrollback {
+    max_history_size: usize,
+    history_size: usize,
+    dropped_lines: usize,
+}
+
+impl Scrollback {
+    pub fn new(max_history_size: usize) -> Self {
+        Self {
+            max_history_size,
+            history_size: 0,
+            dropped_lines: 0,
+        }
+    }
+
+    /// The size of the history to configure Alacritty with, which leaves room for lines past
+    /// the limit of the scrollback until it is trimmed.
+    pub fn alacritty_history_size(max_history_size: usize) -> usize {
+        max_history_size + TRIM_MARGIN
+    }
+
+    /// Counts the lines dropped from the top of the history since the last update, and trims
+    /// the history back to the limit of the scrollback.
+    pub fn update(&mut self, term: &mut Term<ZedListener>) {
+        // The alternate screen has no history, and leaves the history of the primary screen
+        // alone until it is exited.
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+
+        let grid = term.grid_mut();
+        let history_size = grid.history_size();
+        // The history only shrinks when lines are dropped from its top, like when it is cleared.
+        self.dropped_lines += self.history_size.saturating_sub(history_size);
+        self.history_size = history_size;
+
+        if history_size > self.max_history_size {
+            grid.update_history(self.max_history_size);
+            grid.update_history(Self::alacritty_history_size(self.max_history_size));
+            self.dropped_lines += history_size - self.max_history_size;
+            self.history_size = self.max_history_size;
+        }
+    }
+
+    /// Anchors a point of the grid, as of the last update.
+    pub fn anchor(&self, point: AlacPoint) -> AnchoredPoint {
+        let line = self.history_size as i64 + point.line.0 as i64;
+        AnchoredPoint {
+            line: self.dropped_lines + line.max(0) as usize,
+            column: point.column,
+        }
+    }
+
+    /// The first line that is still in the history, as of the last update.
+    pub fn first_line(&self) -> usize {
+        self.dropped_lines
+    }
+
+    /// The line at the top of the screen, as of the last update.
+    pub fn screen_top_line(&self) -> usize {
+        self.dropped_lines + self.history_size
+    }
+
+    /// Returns the point of the grid that an anchored point is at, as of the last update, unless
+    /// its line was dropped from the history.
+    pub fn resolve(&self, point: AnchoredPoint) -> Option<AlacPoint> {
+        let line = point.line.checked_sub(self.dropped_lines)? as i64 - self.history_size as i64;
+        Some(AlacPoint::new(Line(line as i32), point.column))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TerminalSize;
+    use alacritty_terminal::{
+        term::Config,
+        vte::ansi::{ClearMode, Handler},
+    };
+    use futures::channel::mpsc::unbounded;
+
+    fn term(history_size: usize) -> Term<ZedListener> {
+        let config = Config {
+            scrolling_history: Scrollback::alacritty_history_size(history_size),
+            ..Config::default()
+        };
+        let (events_tx, _events_rx) = unbounded();
+        Term::new(config, &TerminalSize::default(), ZedListener(events_tx))
+    }
+
+    fn scroll_lines(term: &mut Term<ZedListener>, lines: usize) {
+        let bottom = Line(term.grid().screen_lines() as i32 - 1);
+        term.grid_mut().cursor.point = AlacPoint::new(bottom, Column(0));
+        for _ in 0..lines {
+            term.linefeed();
+        }
+    }
+
+    #[test]
+    fn test_anchors_follow_dropped_lines() {
+        let mut term = term(5);
+        let mut scrollback = Scrollback::new(5);
+        let top = AlacPoint::new(Line(0), Column(3));
+
+        scrollback.update(&mut term);
+        let anchor = scrollback.anchor(top);
+        assert_eq!(scrollback.resolve(anchor), Some(top));
+
+        // Lines that scroll into the history move the anchored point up.
+        scroll_lines(&mut term, 4);
+        scrollback.update(&mut term);
+        assert_eq!(term.grid().history_size(), 4);
+        assert_eq!(
+            scrollback.resolve(anchor),
+            Some(AlacPoint::new(Line(-4), Column(3)))
+        );
+
+        // Once the history is full, lines are dropped from its top and the point keeps moving
+        // up, until its own line is dropped.
+        scroll_lines(&mut term, 1);
+        scrollback.update(&mut term);
+        let later_anchor = scrollback.anchor(top);
+        scroll_lines(&mut term, 1);
+        scrollback.update(&mut term);
+        assert_eq!(term.grid().history_size(), 5);
+        assert_eq!(scrollback.resolve(anchor), None);
+        assert_eq!(
+            scrollback.resolve(later_anchor),
+            Some(AlacPoint::new(Line(-1), Column(3)))
+        );
+
+        // Clearing the history drops its lines.
+        scroll_lines(&mut term, 2);
+        term.clear_screen(ClearMode::Saved);
+        scrollback.update(&mut term);
+        assert_eq!(scrollback.resolve(later_anchor), None);
+        let anchor = scrollback.anchor(top);
+        scroll_lines(&mut term, 2);
+        scrollback.update(&mut term);
+        assert_eq!(
+            scrollback.resolve(anchor),
+            Some(AlacPoint::new(Line(-2), Column(3)))
+        );
+    }
+}
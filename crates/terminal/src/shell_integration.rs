@@ -0,0 +1,275 @@
+//! Semantic marks that shells emit around their prompts, commands and command output, with the
+//! `OSC 133` sequences of the FinalTerm protocol.
+
+use alacritty_terminal::index::{Column, Line, Point as AlacPoint};
+use std::ops::RangeInclusive;
+
+use crate::scrollback::AnchoredPoint;
+
+/// The number of commands whose regions are remembered.
+const MAX_REGIONS: usize = 1000;
+
+/// A mark emitted by the shell with `ESC ] 133 ; <kind> ESC \`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShellIntegrationMarker {
+    /// `A`: the prompt starts.
+    PromptStart,
+    /// `B`: the prompt ends, and the command typed by the user starts.
+    CommandStart,
+    /// `C`: the command was submitted, and its output starts.
+    OutputStart,
+    /// `D[;<exit code>]`: the command finished.
+    OutputEnd { exit_code: Option<i32> },
+}
+
+impl ShellIntegrationMarker {
+    /// Parses the parameters of an `OSC 133` sequence, after the `133`. Options that some shells
+    /// append to the marks, like `aid=<id>`, are ignored.
+    pub fn parse(params: &[&[u8]]) -> Option<Self> {
+        let (kind, rest) = params.split_first()?;
+        match *kind {
+            b"A" => Some(Self::PromptStart),
+            b"B" => Some(Self::CommandStart),
+            b"C" => Some(Self::OutputStart),
+            b"D" => {
+                let exit_code = rest
+                    .first()
+                    .and_then(|exit_code| std::str::from_utf8(exit_code).ok()?.parse().ok());
+                Some(Self::OutputEnd { exit_code })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The regions of a single command, each starting where the shell marked it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommandRegion {
+    pub prompt_start: AlacPoint,
+    pub command_start: Option<AlacPoint>,
+    pub output_start: Option<AlacPoint>,
+    pub output_end: Option<AlacPoint>,
+    /// The exit code of the command, if it finished and the shell reported it.
+    pub exit_code: Option<i32>,
+}
+
+impl CommandRegion {
+    fn new(prompt_start: AlacPoint) -> Self {
+        Self {
+            prompt_start,
+            command_start: None,
+            output_start: None,
+            output_end: None,
+            exit_code: None,
+        }
+    }
+
+    /// Returns the lines of the prompt, up to the line where the command starts.
+    pub fn prompt_lines(&self) -> RangeInclusive<Line> {
+        let end = self.command_start.unwrap_or(self.prompt_start);
+        self.prompt_start.line..=end.line.max(self.prompt_start.line)
+    }
+
+    fn with_line_offset(&self, offset: i32) -> Self {
+        let offset_point = |point: AlacPoint| AlacPoint::new(point.line + offset, point.column);
+        Self {
+            prompt_start: offset_point(self.prompt_start),
+            command_start: self.command_start.map(offset_point),
+            output_start: self.output_start.map(offset_point),
+            output_end: self.output_end.map(offset_point),
+            exit_code: self.exit_code,
+        }
+    }
+}
+
+/// The command regions marked by the shell, with their lines counted from the first line of the
+/// terminal's output, so that they stay in place as the terminal scrolls.
+#[derive(Default)]
+pub struct ShellIntegrationMarkers {
+    regions: Vec<CommandRegion>,
+}
+
+impl ShellIntegrationMarkers {
+    /// Records a mark made at the given point. Marks other than prompt starts apply to the last
+    /// prompt.
+    pub fn mark(&mut self, marker: ShellIntegrationMarker, point: AnchoredPoint) {
+        let point = AlacPoint::new(Line(point.line as i32), point.column);
+        if marker == ShellIntegrationMarker::PromptStart {
+            if self.regions.len() == MAX_REGIONS {
+                self.regions.remove(0);
+            }
+            self.regions.push(CommandRegion::new(point));
+            return;
+        }
+
+        let Some(region) = self.regions.last_mut() else {
+            return;
+        };
+        match marker {
+            ShellIntegrationMarker::PromptStart => {}
+            ShellIntegrationMarker::CommandStart => region.command_start = Some(point),
+            ShellIntegrationMarker::OutputStart => region.output_start = Some(point),
+            ShellIntegrationMarker::OutputEnd { exit_code } => {
+                region.output_end = Some(point);
+                region.exit_code = exit_code;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    /// Forgets the regions whose prompts were dropped from the scrollback, given its first line.
+    pub fn forget_dropped_lines(&mut self, first_line: usize) {
+        self.regions
+            .retain(|region| region.prompt_start.line.0 as usize >= first_line);
+    }
+
+    /// Returns the command regions, with their lines relative to the top of the screen, given
+    /// the line at the top of the screen.
+    pub fn regions(&self, screen_top_line: usize) -> Vec<CommandRegion> {
+        self.regions
+            .iter()
+            .map(|region| region.with_line_offset(-(screen_top_line as i32)))
+            .collect()
+    }
+
+    /// Returns the output of the last finished command that printed anything, relative to the
+    /// top of the screen, given the line at the top of the screen and the last column of the
+    /// terminal.
+    pub fn last_output(
+        &self,
+        screen_top_line: usize,
+        last_column: Column,
+    ) -> Option<RangeInclusive<AlacPoint>> {
+        self.regions.iter().rev().find_map(|region| {
+            let region = region.with_line_offset(-(screen_top_line as i32));
+            let start = region.output_start?;
+            let output_end = region.output_end?;
+            // The output ends right before the point where the command finished.
+            let end = if output_end.column > Column(0) {
+                AlacPoint::new(output_end.line, output_end.column - 1)
+            } else {
+                AlacPoint::new(output_end.line - 1, last_column)
+            };
+            (start <= end).then_some(start..=end)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(line: i32, column: usize) -> AlacPoint {
+        AlacPoint::new(Line(line), Column(column))
+    }
+
+    fn anchored(line: usize, column: usize) -> AnchoredPoint {
+        AnchoredPoint {
+            line,
+            column: Column(column),
+        }
+    }
+
+    fn parse(sequence: &str) -> Option<ShellIntegrationMarker> {
+        let params = sequence.split(';').map(str::as_bytes).collect::<Vec<_>>();
+        ShellIntegrationMarker::parse(&params)
+    }
+
+    #[test]
+    fn test_parse_markers() {
+        assert_eq!(parse("A"), Some(ShellIntegrationMarker::PromptStart));
+        assert_eq!(parse("B"), Some(ShellIntegrationMarker::CommandStart));
+        assert_eq!(parse("C;aid=12"), Some(ShellIntegrationMarker::OutputStart));
+        assert_eq!(
+            parse("D;127"),
+            Some(ShellIntegrationMarker::OutputEnd {
+                exit_code: Some(127)
+            })
+        );
+        assert_eq!(
+            parse("D"),
+            Some(ShellIntegrationMarker::OutputEnd { exit_code: None })
+        );
+        assert_eq!(parse("P"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn test_command_regions() {
+        let mut markers = ShellIntegrationMarkers::default();
+        // Marks before the first prompt have no region to apply to.
+        markers.mark(ShellIntegrationMarker::OutputStart, anchored(0, 0));
+        assert!(markers.regions(0).is_empty());
+
+        markers.mark(ShellIntegrationMarker::PromptStart, anchored(10, 0));
+        markers.mark(ShellIntegrationMarker::CommandStart, anchored(11, 2));
+        markers.mark(ShellIntegrationMarker::OutputStart, anchored(12, 0));
+        markers.mark(
+            ShellIntegrationMarker::OutputEnd { exit_code: Some(1) },
+            anchored(15, 0),
+        );
+        markers.mark(ShellIntegrationMarker::PromptStart, anchored(15, 0));
+
+        // Lines are made relative to the top of the screen.
+        let regions = markers.regions(10);
+        assert_eq!(
+            regions,
+            [
+                CommandRegion {
+                    prompt_start: point(0, 0),
+                    command_start: Some(point(1, 2)),
+                    output_start: Some(point(2, 0)),
+                    output_end: Some(point(5, 0)),
+                    exit_code: Some(1),
+                },
+                CommandRegion::new(point(5, 0)),
+            ]
+        );
+        assert_eq!(regions[0].prompt_lines(), Line(0)..=Line(1));
+        assert_eq!(regions[1].prompt_lines(), Line(5)..=Line(5));
+
+        markers.forget_dropped_lines(11);
+        assert_eq!(markers.regions(11), [CommandRegion::new(point(4, 0))]);
+        markers.clear();
+        assert!(markers.regions(0).is_empty());
+    }
+
+    #[test]
+    fn test_last_output() {
+        let mut markers = ShellIntegrationMarkers::default();
+        markers.mark(ShellIntegrationMarker::PromptStart, anchored(0, 0));
+        markers.mark(ShellIntegrationMarker::OutputStart, anchored(1, 0));
+        markers.mark(
+            ShellIntegrationMarker::OutputEnd { exit_code: Some(0) },
+            anchored(3, 0),
+        );
+
+        // A command without output, and one that is still running, are skipped.
+        markers.mark(ShellIntegrationMarker::PromptStart, anchored(3, 0));
+        markers.mark(ShellIntegrationMarker::OutputStart, anchored(4, 0));
+        markers.mark(
+            ShellIntegrationMarker::OutputEnd { exit_code: Some(0) },
+            anchored(4, 0),
+        );
+        markers.mark(ShellIntegrationMarker::PromptStart, anchored(4, 0));
+        markers.mark(ShellIntegrationMarker::OutputStart, anchored(5, 0));
+
+        assert_eq!(
+            markers.last_output(1, Column(79)),
+            Some(point(0, 0)..=point(1, 79))
+        );
+
+        // Output that ends within a line, without a trailing newline.
+        markers.mark(
+            ShellIntegrationMarker::OutputEnd { exit_code: None },
+            anchored(5, 6),
+        );
+        assert_eq!(
+            markers.last_output(0, Column(79)),
+            Some(point(5, 0)..=point(5, 5))
+        );
+    }
+}
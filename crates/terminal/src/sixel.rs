@@ -0,0 +1,342 @@
+//! Decoding of images drawn with the Sixel graphics protocol, which are sent by programs like
+//! `viu` or `gnuplot` as `ESC P <params> q <data> ESC \`.
+
+use anyhow::{anyhow, Result};
+use gpui::ImageData;
+use image::{Rgba, RgbaImage};
+use std::sync::Arc;
+
+/// The number of color registers, as in xterm.
+const PALETTE_SIZE: usize = 256;
+
+/// The largest width or height of an image, in pixels.
+const MAX_DIMENSION: u32 = 10_000;
+
+/// The first 16 color registers of the VT340, which are used until programs define their own.
+const VT340_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [51, 51, 204],
+    [204, 36, 36],
+    [51, 204, 51],
+    [204, 51, 204],
+    [51, 204, 204],
+    [204, 204, 51],
+    [120, 120, 120],
+    [69, 69, 69],
+    [87, 87, 153],
+    [153, 69, 69],
+    [87, 153, 87],
+    [153, 87, 153],
+    [87, 153, 153],
+    [153, 153, 87],
+    [204, 204, 204],
+];
+
+/// Decodes the body of a Sixel device control string, from its parameters up to the string
+/// terminator, into an image in GPUI's BGRA pixel order.
+pub fn decode_image_data(body: &[u8]) -> Result<Arc<ImageData>> {
+    let mut image = decode(body)?;
+    for pixel in image.pixels_mut() {
+        pixel.0.swap(0, 2);
+    }
+    Ok(Arc::new(ImageData::new(image)))
+}
+
+/// Decodes the body of a Sixel device control string, from its parameters up to the string
+/// terminator.
+pub fn decode(body: &[u8]) -> Result<RgbaImage> {
+    let data_start = body
+        .iter()
+        .position(|&byte| byte == b'q')
+        .ok_or_else(|| anyhow!("missing sixel introducer"))?;
+    let params = parse_params(&body[..data_start]);
+    // Pixels without sixels keep the background color, unless it's selected to be transparent.
+    let transparent_background = params.get(1) == Some(&1);
+
+    let mut decoder = Decoder::new(transparent_background);
+    decoder.decode(&body[data_start + 1..])?;
+    Ok(decoder.finish())
+}
+
+struct Decoder {
+    palette: [[u8; 3]; PALETTE_SIZE],
+    color: usize,
+    transparent_background: bool,
+    pixels: Vec<Option<[u8; 3]>>,
+    width: u32,
+    height: u32,
+    raster_size: Option<(u32, u32)>,
+    x: u32,
+    band_top: u32,
+}
+
+impl Decoder {
+    fn new(transparent_background: bool) -> Self {
+        let mut palette = [[0; 3]; PALETTE_SIZE];
+        palette[..VT340_PALETTE.len()].copy_from_slice(&VT340_PALETTE);
+        Self {
+            palette,
+            color: 0,
+            transparent_background,
+            pixels: Vec::new(),
+            width: 0,
+            height: 0,
+            raster_size: None,
+            x: 0,
+            band_top: 0,
+        }
+    }
+
+    fn decode(&mut self, data: &[u8]) -> Result<()> {
+        let mut bytes = data.iter().copied().peekable();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                b'?'..=b'~' => self.draw_sixel(byte - b'?', 1)?,
+                b'!' => {
+                    let count = parse_number(&mut bytes);
+                    match bytes.next() {
+                        Some(byte @ b'?'..=b'~') => self.draw_sixel(byte - b'?', count.max(1))?,
+                        _ => return Err(anyhow!("invalid sixel repeat")),
+                    }
+                }
+                b'#' => {
+                    let mut params = vec![parse_number(&mut bytes)];
+                    while bytes.next_if_eq(&b';').is_some() {
+                        params.push(parse_number(&mut bytes));
+                    }
+                    self.select_color(&params);
+                }
+                b'"' => {
+                    let mut params = vec![parse_number(&mut bytes)];
+                    while bytes.next_if_eq(&b';').is_some() {
+                        params.push(parse_number(&mut bytes));
+                    }
+                    // The raster attributes are the pixel aspect ratio and the image's size.
+                    if let [_, _, width, height] = params[..] {
+                        self.reserve(width, height)?;
+                        self.raster_size = Some((width, height));
+                    }
+                }
+                b'$' => self.x = 0,
+                b'-' => {
+                    self.x = 0;
+                    self.band_top += 6;
+                }
+                // Line breaks and other control characters are ignored.
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Selects a color register, defining it when the color is given in the HLS or RGB color
+    /// space.
+    fn select_color(&mut self, params: &[u32]) {
+        let register = params[0] as usize % PALETTE_SIZE;
+        self.color = register;
+        let &[_, space, a, b, c] = params else {
+            return;
+        };
+        self.palette[register] = match space {
+            1 => hls_to_rgb(a, b, c),
+            2 => [a, b, c].map(percent_to_channel),
+            _ => return,
+        };
+    }
+
+    fn draw_sixel(&mut self, sixel: u8, count: u32) -> Result<()> {
+        self.reserve(self.x + count, self.band_top + 6)?;
+        let color = self.palette[self.color];
+        for row in 0..6 {
+            if sixel & (1 << row) == 0 {
+                continue;
+            }
+            let y = self.band_top + row;
+            self.height = self.height.max(y + 1);
+            let row_start = (y * self.width) as usize;
+            let range = row_start + self.x as usize..row_start + (self.x + count) as usize;
+            self.pixels[range].fill(Some(color));
+        }
+        self.x += count;
+        Ok(())
+    }
+
+    /// Grows the image to fit the given width, and the given height in rows of pixels.
+    fn reserve(&mut self, width: u32, height: u32) -> Result<()> {
+        if width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return Err(anyhow!("sixel image is larger than {MAX_DIMENSION} pixels"));
+        }
+        let rows = self.pixels.len() as u32 / self.width.max(1);
+        if width <= self.width && height <= rows {
+            return Ok(());
+        }
+
+        let new_width = width.max(self.width);
+        let new_rows = height.max(rows);
+        let mut pixels = vec![None; (new_width * new_rows) as usize];
+        for y in 0..rows {
+            let old_start = (y * self.width) as usize;
+            let new_start = (y * new_width) as usize;
+            pixels[new_start..new_start + self.width as usize]
+                .copy_from_slice(&self.pixels[old_start..old_start + self.width as usize]);
+        }
+        self.pixels = pixels;
+        self.width = new_width;
+        Ok(())
+    }
+
+    fn finish(self) -> RgbaImage {
+        let background = if self.transparent_background {
+            [0; 4]
+        } else {
+            let [r, g, b] = self.palette[0];
+            [r, g, b, 255]
+        };
+        // The raster attributes crop the sixels drawn past them, which would otherwise end on the
+        // last row with a sixel.
+        let (width, height) = self.raster_size.unwrap_or((self.width, self.height));
+        RgbaImage::from_fn(width, height, |x, y| {
+            let pixel = self.pixels[(y * self.width + x) as usize];
+            Rgba(pixel.map_or(background, |[r, g, b]| [r, g, b, 255]))
+        })
+    }
+}
+
+fn parse_params(params: &[u8]) -> Vec<u32> {
+    params
+        .split(|&byte| byte == b';')
+        .map(|param| {
+            std::str::from_utf8(param)
+                .ok()
+                .and_then(|param| param.parse().ok())
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+fn parse_number(bytes: &mut std::iter::Peekable<impl Iterator<Item = u8>>) -> u32 {
+    let mut number = 0u32;
+    while let Some(digit) = bytes.next_if(u8::is_ascii_digit) {
+        number = number
+            .saturating_mul(10)
+            .saturating_add((digit - b'0') as u32);
+    }
+    number
+}
+
+fn percent_to_channel(percent: u32) -> u8 {
+    (percent.min(100) * 255 / 100) as u8
+}
+
+/// Converts a Sixel HLS color, where hues start from blue rather than red, to RGB.
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let hue = ((hue + 240) % 360) as f32 / 360.;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    if saturation == 0. {
+        return [(lightness * 255.).round() as u8; 3];
+    }
+
+    let q = if lightness < 0.5 {
+        lightness * (1. + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2. * lightness - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.);
+        let value = if t < 1. / 6. {
+            p + (q - p) * 6. * t
+        } else if t < 1. / 2. {
+            q
+        } else if t < 2. / 3. {
+            p + (q - p) * (2. / 3. - t) * 6.
+        } else {
+            p
+        };
+        (value * 255.).round() as u8
+    };
+    [channel(hue + 1. / 3.), channel(hue), channel(hue - 1. / 3.)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    fn pixels(image: &RgbaImage) -> Vec<Vec<[u8; 4]>> {
+        image
+            .rows()
+            .map(|row| row.map(|pixel| pixel.0).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_checkerboard() {
+        // White pixels are drawn with color 1 on rows 0 and 2 (0b0101) at even columns, and rows
+        // 1 and 3 (0b1010) at odd columns, while color 0 stays black.
+        let image = decode(b"0;0;0q\"1;1;4;4#1;2;100;100;100#1TiTi").unwrap();
+        assert_eq!(
+            pixels(&image),
+            [
+                [WHITE, BLACK, WHITE, BLACK],
+                [BLACK, WHITE, BLACK, WHITE],
+                [WHITE, BLACK, WHITE, BLACK],
+                [BLACK, WHITE, BLACK, WHITE],
+            ]
+        );
+
+        // Without raster attributes, the image ends with the last row and column with sixels.
+        // Bands can be drawn over in several passes, and sixels can be repeated.
+        let image = decode(b"q#0;2;0;0;0#1;2;100;100;100#1!2D!2?$#1?I?I").unwrap();
+        assert_eq!(image.dimensions(), (4, 4));
+        assert_eq!(
+            pixels(&image),
+            [
+                [WHITE, WHITE, BLACK, BLACK],
+                [BLACK, WHITE, BLACK, WHITE],
+                [WHITE, WHITE, BLACK, BLACK],
+                [BLACK, WHITE, BLACK, WHITE],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_bands_and_background() {
+        // A 2x7 image with a transparent background, over two bands.
+        let image = decode(b"0;1q#2;2;100;0;0#2~@-#2@").unwrap();
+        assert_eq!(image.dimensions(), (2, 7));
+        let red = [255, 0, 0, 255];
+        let transparent = [0; 4];
+        let rows = pixels(&image);
+        assert_eq!(rows[0], [red, red]);
+        assert_eq!(rows[5], [red, transparent]);
+        assert_eq!(rows[6], [red, transparent]);
+    }
+
+    #[test]
+    fn test_decode_palette() {
+        // Registers beyond the 16 defaults can be defined, in RGB or in HLS, where 120° is red.
+        let image = decode(b"q#200;2;0;0;100#200@#255;1;120;50;100#255@#3@").unwrap();
+        assert_eq!(
+            pixels(&image)[0],
+            [[0, 0, 255, 255], [255, 0, 0, 255], [51, 204, 51, 255]]
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode(b"0;0;0").is_err());
+        assert!(decode(b"q!5#").is_err());
+        assert!(decode(b"q!20000~").is_err());
+    }
+
+    #[test]
+    fn test_decode_image_data() {
+        let image = decode_image_data(b"q#1;2;100;0;0#1@").unwrap();
+        // GPUI images are stored in BGRA order.
+        assert_eq!(image.as_bytes(), [0, 0, 255, 255]);
+    }
+}
@@ -0,0 +1,457 @@
+//! Reads the output of the PTY for Alacritty's event loop, and picks out the sequences that
+//! Alacritty's parser ignores, like Sixel images and shell integration marks, to handle them at
+//! the point of the output where they were written.
+
+use std::{
+    io::{self, Read},
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::{Event as AlacTermEvent, EventListener, OnResize, WindowSize},
+    sync::FairMutex,
+    tty::{ChildEvent, EventedPty, EventedReadWrite, Pty},
+    vte::{ansi::Handler, Params, Parser, Perform},
+    Term,
+};
+use futures::channel::mpsc::UnboundedSender;
+use gpui::ImageData;
+use parking_lot::Mutex;
+use polling::{Event, PollMode, Poller};
+use util::ResultExt;
+
+use crate::{
+    scrollback::{AnchoredPoint, Scrollback},
+    sixel, ShellIntegrationMarker, ZedListener,
+};
+
+/// The largest body of a Sixel device control string that is decoded, in bytes.
+const MAX_SIXEL_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Content that the output of the PTY placed in the terminal.
+pub enum TappedSequence {
+    SixelImage {
+        image: Arc<ImageData>,
+        /// The point of the image's top-left corner.
+        point: AnchoredPoint,
+    },
+    ShellIntegration {
+        marker: ShellIntegrationMarker,
+        /// The cursor where the shell made the mark.
+        point: AnchoredPoint,
+    },
+}
+
+/// A sequence that Alacritty's parser ignores.
+#[derive(Debug, PartialEq)]
+enum Sequence {
+    /// The body of a Sixel device control string, from its parameters up to the string
+    /// terminator.
+    Sixel(Vec<u8>),
+    /// An `OSC 133` mark.
+    ShellIntegration(ShellIntegrationMarker),
+}
+
+/// Finds the sequences that Alacritty's parser ignores in the output of the PTY.
+#[derive(Default)]
+struct OutputTap {
+    parser: Parser,
+    collector: SequenceCollector,
+}
+
+impl OutputTap {
+    /// Parses output up to the end of the first sequence in it, returning the length of the
+    /// output that was parsed and the sequence.
+    fn parse(&mut self, output: &[u8]) -> (usize, Option<Sequence>) {
+        for (ix, byte) in output.iter().enumerate() {
+            self.parser.advance(&mut self.collector, *byte);
+            if let Some(sequence) = self.collector.sequence.take() {
+                return (ix + 1, Some(sequence));
+            }
+        }
+        (output.len(), None)
+    }
+}
+
+#[derive(Default)]
+struct SequenceCollector {
+    /// The body of the Sixel device control string being read.
+    sixel: Option<Vec<u8>>,
+    sequence: Option<Sequence>,
+}
+
+impl Perform for SequenceCollector {
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        if action == 'q' && intermediates.is_empty() && !ignore {
+            let params = params
+                .iter()
+                .map(|param| param[0].to_string())
+                .collect::<Vec<_>>()
+                .join(";");
+            let mut body = params.into_bytes();
+            body.push(b'q');
+            self.sixel = Some(body);
+        }
+    }
+
+    fn put(&mut self, byte: u8) {
+        if let Some(body) = &mut self.sixel {
+            if body.len() < MAX_SIXEL_LENGTH {
+                body.push(byte);
+            } else {
+                self.sixel = None;
+            }
+        }
+    }
+
+    fn unhook(&mut self) {
+        if let Some(body) = self.sixel.take() {
+            self.sequence = Some(Sequence::Sixel(body));
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        if let Some((command, params)) = params.split_first() {
+            if *command == b"133" {
+                self.sequence =
+                    ShellIntegrationMarker::parse(params).map(Sequence::ShellIntegration);
+            }
+        }
+    }
+}
+
+/// A sequence that ended the output last read by Alacritty, which is handled once Alacritty
+/// has parsed that output.
+struct PendingSequence {
+    sequence: Sequence,
+    /// The space that was left in Alacritty's buffer after the output.
+    remaining_buffer_len: usize,
+    parsed: bool,
+}
+
+/// The PTY that Alacritty's event loop reads the output of the shell from.
+///
+/// Each sequence that Alacritty ignores ends a read, and the output after it is held back until
+/// Alacritty has parsed the output before it and released the terminal, so that the sequence
+/// is handled at the cursor where it was written.
+pub struct TappedPty {
+    pty: Pty,
+    term: Arc<FairMutex<Term<ZedListener>>>,
+    scrollback: Arc<Mutex<Scrollback>>,
+    listener: ZedListener,
+    sequences_tx: UnboundedSender<TappedSequence>,
+    tap: OutputTap,
+    pending_sequence: Option<PendingSequence>,
+    held_output: Vec<u8>,
+    wakeup: Wakeup,
+    /// The height of a line in device pixels, which images are fitted to.
+    line_height: u16,
+}
+
+impl TappedPty {
+    pub fn new(
+        pty: Pty,
+        term: Arc<FairMutex<Term<ZedListener>>>,
+        scrollback: Arc<Mutex<Scrollback>>,
+        listener: ZedListener,
+        sequences_tx: UnboundedSender<TappedSequence>,
+        window_size: WindowSize,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            pty,
+            term,
+            scrollback,
+            listener,
+            sequences_tx,
+            tap: OutputTap::default(),
+            pending_sequence: None,
+            held_output: Vec::new(),
+            wakeup: Wakeup::new()?,
+            line_height: window_size.cell_height,
+        })
+    }
+
+    fn handle_sequence(&mut self, sequence: Sequence) {
+        match sequence {
+            Sequence::Sixel(body) => {
+                let Some(image) = sixel::decode_image_data(&body).log_err() else {
+                    return;
+                };
+                let image_height = u32::from(image.size().height);
+                let image_lines = image_height.div_ceil(u32::from(self.line_height.max(1)));
+
+                let mut term = self.term.lock_unfair();
+                let mut scrollback = self.scrollback.lock();
+                scrollback.update(&mut term);
+                let point = scrollback.anchor(term.grid().cursor.point);
+                // Move the cursor below the image, so that output continues after it.
+                for _ in 0..image_lines {
+                    term.linefeed();
+                }
+                term.carriage_return();
+                drop(scrollback);
+                drop(term);
+
+                self.sequences_tx
+                    .unbounded_send(TappedSequence::SixelImage { image, point })
+                    .ok();
+            }
+            Sequence::ShellIntegration(marker) => {
+                let mut term = self.term.lock_unfair();
+                let mut scrollback = self.scrollback.lock();
+                scrollback.update(&mut term);
+                let point = scrollback.anchor(term.grid().cursor.point);
+                drop(scrollback);
+                drop(term);
+
+                self.sequences_tx
+                    .unbounded_send(TappedSequence::ShellIntegration { marker, point })
+                    .ok();
+            }
+        }
+        self.listener.send_event(AlacTermEvent::Wakeup);
+    }
+}
+
+impl Read for TappedPty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(pending) = &mut self.pending_sequence {
+            if !pending.parsed {
+                // Alacritty keeps reading into the rest of its buffer until it parses the output
+                // it has read, so a read into a larger buffer comes after the output was parsed.
+                pending.parsed = buf.len() > pending.remaining_buffer_len;
+                // Have Alacritty parse the output it has read and release the terminal, and then
+                // come back to read the output that is held back.
+                self.wakeup.wake()?;
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            if let Some(pending) = self.pending_sequence.take() {
+                self.handle_sequence(pending.sequence);
+            }
+        }
+
+        let len = if self.held_output.is_empty() {
+            self.wakeup.reset()?;
+            self.pty.reader().read(buf)?
+        } else {
+            let len = buf.len().min(self.held_output.len());
+            buf[..len].copy_from_slice(&self.held_output[..len]);
+            self.held_output.drain(..len);
+            len
+        };
+
+        let (parsed_len, sequence) = self.tap.parse(&buf[..len]);
+        if let Some(sequence) = sequence {
+            self.held_output
+                .splice(0..0, buf[parsed_len..len].iter().copied());
+            self.pending_sequence = Some(PendingSequence {
+                sequence,
+                remaining_buffer_len: buf.len() - parsed_len,
+                parsed: false,
+            });
+        }
+        if !self.held_output.is_empty() {
+            self.wakeup.wake()?;
+        }
+        Ok(parsed_len)
+    }
+}
+
+impl EventedReadWrite for TappedPty {
+    type Reader = Self;
+    type Writer = <Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.register(poll, interest, mode)?;
+        self.wakeup.register(poll, interest.key, mode)
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)?;
+        self.wakeup.reregister(poll, interest.key, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.wakeup.deregister(poll)?;
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for TappedPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for TappedPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.line_height = window_size.cell_height;
+        self.pty.on_resize(window_size);
+    }
+}
+
+/// Wakes Alacritty's event loop to read from the PTY while output is held back from it, as if
+/// the PTY had more output.
+#[cfg(unix)]
+struct Wakeup {
+    sender: std::os::unix::net::UnixStream,
+    receiver: std::os::unix::net::UnixStream,
+    awake: bool,
+}
+
+#[cfg(unix)]
+impl Wakeup {
+    fn new() -> io::Result<Self> {
+        let (sender, receiver) = std::os::unix::net::UnixStream::pair()?;
+        sender.set_nonblocking(true)?;
+        receiver.set_nonblocking(true)?;
+        Ok(Self {
+            sender,
+            receiver,
+            awake: false,
+        })
+    }
+
+    unsafe fn register(&mut self, poll: &Poller, key: usize, mode: PollMode) -> io::Result<()> {
+        poll.add_with_mode(&self.receiver, Event::readable(key), mode)
+    }
+
+    fn reregister(&mut self, poll: &Poller, key: usize, mode: PollMode) -> io::Result<()> {
+        poll.modify_with_mode(&self.receiver, Event::readable(key), mode)
+    }
+
+    fn deregister(&mut self, poll: &Poller) -> io::Result<()> {
+        poll.delete(&self.receiver)
+    }
+
+    fn wake(&mut self) -> io::Result<()> {
+        use std::io::Write as _;
+
+        if !self.awake {
+            (&self.sender).write_all(&[0])?;
+            self.awake = true;
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        if self.awake {
+            (&self.receiver).read_exact(&mut [0])?;
+            self.awake = false;
+        }
+        Ok(())
+    }
+}
+
+/// Wakes Alacritty's event loop to read from the PTY while output is held back from it, as if
+/// the PTY had more output.
+#[cfg(windows)]
+struct Wakeup {
+    registration: Option<(Arc<Poller>, usize)>,
+}
+
+#[cfg(windows)]
+impl Wakeup {
+    fn new() -> io::Result<Self> {
+        Ok(Self { registration: None })
+    }
+
+    unsafe fn register(&mut self, poll: &Arc<Poller>, key: usize, _: PollMode) -> io::Result<()> {
+        self.registration = Some((poll.clone(), key));
+        Ok(())
+    }
+
+    fn reregister(&mut self, poll: &Arc<Poller>, key: usize, _: PollMode) -> io::Result<()> {
+        self.registration = Some((poll.clone(), key));
+        Ok(())
+    }
+
+    fn deregister(&mut self, _: &Arc<Poller>) -> io::Result<()> {
+        self.registration = None;
+        Ok(())
+    }
+
+    fn wake(&mut self) -> io::Result<()> {
+        use polling::os::iocp::{CompletionPacket, PollerIocpExt as _};
+
+        if let Some((poll, key)) = &self.registration {
+            poll.post(CompletionPacket::new(Event::readable(*key)))?;
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_tap() {
+        let mut tap = OutputTap::default();
+        let output = b"before\x1bP0;1;0q\"1;1;2;6#0~~\x1b\\after\x1bP$qm\x1b\\";
+
+        let (parsed_len, sequence) = tap.parse(output);
+        // The sequence ends at the escape that starts its terminator.
+        assert_eq!(&output[..parsed_len], b"before\x1bP0;1;0q\"1;1;2;6#0~~\x1b");
+        assert_eq!(
+            sequence,
+            Some(Sequence::Sixel(b"0;1;0q\"1;1;2;6#0~~".to_vec()))
+        );
+
+        // Other device control strings are left to Alacritty.
+        let output = &output[parsed_len..];
+        assert_eq!(tap.parse(output), (output.len(), None));
+
+        // Shell integration marks end with their terminator.
+        let output = b"\x1b]133;D;1\x07\x1b]133;A\x1b\\$ ";
+        let (parsed_len, sequence) = tap.parse(output);
+        assert_eq!(&output[..parsed_len], b"\x1b]133;D;1\x07");
+        assert_eq!(
+            sequence,
+            Some(Sequence::ShellIntegration(
+                ShellIntegrationMarker::OutputEnd { exit_code: Some(1) }
+            ))
+        );
+        let output = &output[parsed_len..];
+        let (parsed_len, sequence) = tap.parse(output);
+        assert_eq!(&output[..parsed_len], b"\x1b]133;A\x1b");
+        assert_eq!(
+            sequence,
+            Some(Sequence::ShellIntegration(
+                ShellIntegrationMarker::PromptStart
+            ))
+        );
+        let output = &output[parsed_len..];
+        assert_eq!(tap.parse(output), (output.len(), None));
+
+        // Sequences are found across reads.
+        let (parsed_len, sequence) = tap.parse(b"\x1bPq#0");
+        assert_eq!((parsed_len, sequence), (5, None));
+        assert_eq!(
+            tap.parse(b"~\x1b\\"),
+            (2, Some(Sequence::Sixel(b"q#0~".to_vec())))
+        );
+    }
+}
@@ -3,6 +3,10 @@ pub mod mappings;
 pub use alacritty_terminal;
 
 mod pty_info;
+mod scrollback;
+mod shell_integration;
+pub mod sixel;
+mod tapped_pty;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -13,7 +17,7 @@ use alacritty_terminal::{
     selection::{Selection, SelectionRange, SelectionType},
     sync::FairMutex,
     term::{
-        cell::Cell,
+        cell::{Cell, Hyperlink},
         search::{Match, RegexIter, RegexSearch},
         Config, RenderableCursor, TermMode,
     },
@@ -35,19 +39,23 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use parking_lot::Mutex;
 use pty_info::PtyProcessInfo;
+use scrollback::{AnchoredPoint, Scrollback};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use smol::channel::{Receiver, Sender};
+use tapped_pty::{TappedPty, TappedSequence};
 use task::TaskId;
 use terminal_settings::{AlternateScroll, Shell, TerminalBlink, TerminalSettings};
 use theme::{ActiveTheme, Theme};
+use url::Url;
 use util::truncate_and_trailoff;
 
 use std::{
     cmp::{self, min},
     fmt::Display,
-    ops::{Deref, Index, RangeInclusive},
+    ops::{Deref, Index, Range, RangeInclusive},
     path::PathBuf,
     sync::Arc,
     time::Duration,
@@ -56,11 +64,14 @@ use thiserror::Error;
 
 use gpui::{
     actions, black, px, AnyWindowHandle, AppContext, Bounds, ClipboardItem, EventEmitter, Hsla,
-    Keystroke, ModelContext, Modifiers, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    Pixels, Point, Rgba, ScrollWheelEvent, Size, Task, TouchPhase,
+    ImageData, Keystroke, ModelContext, Modifiers, MouseButton, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, Pixels, Point, Rgba, ScrollWheelEvent, Size, Task, TouchPhase,
 };
 
 use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
+pub use crate::shell_integration::{
+    CommandRegion, ShellIntegrationMarker, ShellIntegrationMarkers,
+};
 
 actions!(
     terminal,
@@ -76,6 +87,7 @@ actions!(
         ScrollPageDown,
         ScrollToTop,
         ScrollToBottom,
+        SelectLastOutput,
     ]
 );
 
@@ -154,19 +166,27 @@ pub fn init(cx: &mut AppContext) {
     TerminalSettings::register(cx);
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TerminalSize {
     pub cell_width: Pixels,
     pub line_height: Pixels,
     pub size: Size<Pixels>,
+    /// The number of device pixels per pixel, which programs measure their images in.
+    pub scale_factor: f32,
 }
 
 impl TerminalSize {
-    pub fn new(line_height: Pixels, cell_width: Pixels, size: Size<Pixels>) -> Self {
+    pub fn new(
+        line_height: Pixels,
+        cell_width: Pixels,
+        size: Size<Pixels>,
+        scale_factor: f32,
+    ) -> Self {
         TerminalSize {
             cell_width,
             line_height,
             size,
+            scale_factor,
         }
     }
 
@@ -204,17 +224,19 @@ impl Default for TerminalSize {
                 width: DEBUG_TERMINAL_WIDTH,
                 height: DEBUG_TERMINAL_HEIGHT,
             },
+            1.,
         )
     }
 }
 
 impl From<TerminalSize> for WindowSize {
+    /// Cells are measured in device pixels, like the images that programs size to fit them.
     fn from(val: TerminalSize) -> Self {
         WindowSize {
             num_lines: val.num_lines() as u16,
             num_cols: val.num_columns() as u16,
-            cell_width: f32::from(val.cell_width()) as u16,
-            cell_height: f32::from(val.line_height()) as u16,
+            cell_width: (f32::from(val.cell_width()) * val.scale_factor) as u16,
+            cell_height: (f32::from(val.line_height()) * val.scale_factor) as u16,
         }
     }
 }
@@ -364,8 +386,9 @@ impl TerminalBuilder {
                 .unwrap_or(DEFAULT_SCROLL_HISTORY_LINES)
                 .min(MAX_SCROLL_HISTORY_LINES)
         };
+        let scrollback = Arc::new(Mutex::new(Scrollback::new(scrolling_history)));
         let config = Config {
-            scrolling_history,
+            scrolling_history: Scrollback::alacritty_history_size(scrolling_history),
             ..Config::default()
         };
 
@@ -409,6 +432,16 @@ impl TerminalBuilder {
 
         let pty_info = PtyProcessInfo::new(&pty);
 
+        let (tapped_sequences_tx, tapped_sequences_rx) = unbounded();
+        let pty = TappedPty::new(
+            pty,
+            term.clone(),
+            scrollback.clone(),
+            ZedListener(events_tx.clone()),
+            tapped_sequences_tx,
+            TerminalSize::default().into(),
+        )?;
+
         //And connect them together
         let event_loop = EventLoop::new(
             term.clone(),
@@ -445,6 +478,11 @@ impl TerminalBuilder {
             hovered_word: false,
             url_regex,
             word_regex,
+            scrollback,
+            tapped_sequences_rx,
+            images: Vec::new(),
+            shell_integration: ShellIntegrationMarkers::default(),
+            color_scheme: None,
         };
 
         Ok(TerminalBuilder {
@@ -543,6 +581,34 @@ pub struct TerminalContent {
     pub cursor_char: char,
     pub size: TerminalSize,
     pub last_hovered_word: Option<HoveredWord>,
+    pub hyperlinks: Vec<HyperlinkSpan>,
+    pub images: Vec<TerminalImage>,
+    pub command_regions: Vec<CommandRegion>,
+}
+
+impl TerminalContent {
+    /// Returns the hyperlink containing the cell with the given index.
+    pub fn hyperlink_at(&self, cell_ix: usize) -> Option<&HyperlinkSpan> {
+        self.hyperlinks
+            .iter()
+            .find(|hyperlink| hyperlink.span.contains(&cell_ix))
+    }
+}
+
+/// A hyperlink set with OSC 8 escape sequences, like `\e]8;;URL\e\\text\e]8;;\e\\`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HyperlinkSpan {
+    pub url: String,
+    /// The indices of the hyperlink's cells in [`TerminalContent::cells`].
+    pub span: Range<usize>,
+}
+
+/// An image displayed inline in the terminal, like a Sixel image.
+#[derive(Clone)]
+pub struct TerminalImage {
+    pub image: Arc<ImageData>,
+    /// The grid cell of the image's top-left corner.
+    pub point: AlacPoint,
 }
 
 #[derive(Clone)]
@@ -567,6 +633,9 @@ impl Default for TerminalContent {
             cursor_char: Default::default(),
             size: Default::default(),
             last_hovered_word: None,
+            hyperlinks: Vec::new(),
+            images: Vec::new(),
+            command_regions: Vec::new(),
         }
     }
 }
@@ -598,6 +667,13 @@ pub struct Terminal {
     hovered_word: bool,
     url_regex: RegexSearch,
     word_regex: RegexSearch,
+    scrollback: Arc<Mutex<Scrollback>>,
+    /// Content placed by the output of the PTY, like images, that Alacritty doesn't handle.
+    tapped_sequences_rx: UnboundedReceiver<TappedSequence>,
+    /// Inline images, at the point of their top-left corner.
+    images: Vec<(Arc<ImageData>, AnchoredPoint)>,
+    shell_integration: ShellIntegrationMarkers,
+    color_scheme: Option<String>,
     task: Option<TaskState>,
 }
 
@@ -670,6 +746,12 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                // Trim the scrollback while the terminal isn't drawn too, before Alacritty drops
+                // lines from it that aren't counted.
+                let mut term = self.term.lock_unfair();
+                self.scrollback.lock().update(&mut term);
+                drop(term);
+
                 cx.emit(Event::Wakeup);
 
                 if self.pty_info.has_changed() {
@@ -719,6 +801,8 @@ impl Terminal {
                 term.resize(new_size);
             }
             InternalEvent::Clear => {
+                self.images.clear();
+                self.shell_integration.clear();
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -1122,26 +1206,96 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, cx)
         }
 
+        let scrollback = self.scrollback.clone();
+        let mut scrollback = scrollback.lock();
+        scrollback.update(&mut terminal);
+        while let Ok(Some(sequence)) = self.tapped_sequences_rx.try_next() {
+            match sequence {
+                TappedSequence::SixelImage { image, point } => self.images.push((image, point)),
+                TappedSequence::ShellIntegration { marker, point } => {
+                    self.shell_integration.mark(marker, point)
+                }
+            }
+        }
+
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content.images = self.visible_images(&terminal, &scrollback);
+
+        self.shell_integration
+            .forget_dropped_lines(scrollback.first_line());
+        // Commands are run on the primary screen, which the alternate screen hides.
+        self.last_content.command_regions = if terminal.mode().contains(TermMode::ALT_SCREEN) {
+            Vec::new()
+        } else {
+            self.shell_integration.regions(scrollback.screen_top_line())
+        };
+    }
+
+    /// Selects the output of the last command that finished, as marked by the shell.
+    pub fn select_last_output(&mut self) {
+        let mut term = self.term.lock_unfair();
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let mut scrollback = self.scrollback.lock();
+        scrollback.update(&mut term);
+        let screen_top_line = scrollback.screen_top_line();
+        let last_column = term.last_column();
+        drop(scrollback);
+        drop(term);
+        if let Some(output) = self
+            .shell_integration
+            .last_output(screen_top_line, last_column)
+        {
+            self.events
+                .push_back(InternalEvent::ScrollToAlacPoint(*output.start()));
+            self.set_selection(Some((make_selection(&output), *output.end())));
+        }
+    }
+
+    /// Returns the images on lines that are still in the scrollback, at their current grid
+    /// points.
+    fn visible_images(
+        &mut self,
+        term: &Term<ZedListener>,
+        scrollback: &Scrollback,
+    ) -> Vec<TerminalImage> {
+        self.images
+            .retain(|(_, point)| scrollback.resolve(*point).is_some());
+        // Images belong to the primary screen, which the alternate screen hides.
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return Vec::new();
+        }
+        self.images
+            .iter()
+            .filter_map(|(image, point)| {
+                Some(TerminalImage {
+                    image: image.clone(),
+                    point: scrollback.resolve(*point)?,
+                })
+            })
+            .collect()
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
         let content = term.renderable_content();
+        let cells = content
+            .display_iter
+            //TODO: Add this once there's a way to retain empty lines
+            // .filter(|ic| {
+            //     !ic.flags.contains(Flags::HIDDEN)
+            //         && !(ic.bg == Named(NamedColor::Background)
+            //             && ic.c == ' '
+            //             && !ic.flags.contains(Flags::INVERSE))
+            // })
+            .map(|ic| IndexedCell {
+                point: ic.point,
+                cell: ic.cell.clone(),
+            })
+            .collect::<Vec<IndexedCell>>();
         TerminalContent {
-            cells: content
-                .display_iter
-                //TODO: Add this once there's a way to retain empty lines
-                // .filter(|ic| {
-                //     !ic.flags.contains(Flags::HIDDEN)
-                //         && !(ic.bg == Named(NamedColor::Background)
-                //             && ic.c == ' '
-                //             && !ic.flags.contains(Flags::INVERSE))
-                // })
-                .map(|ic| IndexedCell {
-                    point: ic.point,
-                    cell: ic.cell.clone(),
-                })
-                .collect::<Vec<IndexedCell>>(),
+            hyperlinks: hyperlink_spans(&cells),
+            cells,
             mode: content.mode,
             display_offset: content.display_offset,
             selection_text: term.selection_to_string(),
@@ -1232,6 +1386,16 @@ impl Terminal {
         }
     }
 
+    /// Returns the hyperlink under a position relative to the terminal's origin, unless the mouse
+    /// is reported to the terminal's program.
+    pub fn hyperlink_at_position(&self, position: Point<Pixels>) -> Option<&HyperlinkSpan> {
+        if self.last_content.mode.intersects(TermMode::MOUSE_MODE) {
+            return None;
+        }
+        let cell_ix = content_index_for_mouse(position, &self.last_content.size);
+        self.last_content.hyperlink_at(cell_ix)
+    }
+
     fn word_from_position(&mut self, position: Option<Point<Pixels>>) {
         if self.selection_phase == SelectionPhase::Selecting {
             self.last_content.last_hovered_word = None;
@@ -1371,9 +1535,17 @@ impl Terminal {
 
             //Hyperlinks
             if self.selection_phase == SelectionPhase::Ended {
-                let mouse_cell_index = content_index_for_mouse(position, &self.last_content.size);
-                if let Some(link) = self.last_content.cells[mouse_cell_index].hyperlink() {
-                    cx.open_url(link.uri());
+                if let Some(hyperlink) = self.hyperlink_at_position(position) {
+                    let target = match file_url_path(&hyperlink.url) {
+                        Some(path) if self.secondary_pressed => {
+                            MaybeNavigationTarget::PathLike(PathLikeTarget {
+                                maybe_path: path.to_string_lossy().into_owned(),
+                                terminal_dir: self.get_cwd(),
+                            })
+                        }
+                        _ => MaybeNavigationTarget::Url(hyperlink.url.clone()),
+                    };
+                    cx.emit(Event::Open(target));
                 } else if self.secondary_pressed {
                     self.events
                         .push_back(InternalEvent::FindHyperlink(position, true));
@@ -1524,6 +1696,15 @@ impl Terminal {
         self.task.as_ref()
     }
 
+    /// The name of the theme whose colors the terminal is drawn with, instead of the current one.
+    pub fn color_scheme(&self) -> Option<&str> {
+        self.color_scheme.as_deref()
+    }
+
+    pub fn set_color_scheme(&mut self, color_scheme: Option<String>) {
+        self.color_scheme = color_scheme;
+    }
+
     pub fn wait_for_completed_task(&self, cx: &mut AppContext) -> Task<()> {
         if let Some(task) = self.task() {
             if task.status == TaskStatus::Running {
@@ -1675,6 +1856,41 @@ fn all_search_matches<'a, T>(
     RegexIter::new(start, end, AlacDirection::Right, term, regex)
 }
 
+/// Groups the consecutive cells with the same hyperlink.
+fn hyperlink_spans(cells: &[IndexedCell]) -> Vec<HyperlinkSpan> {
+    let mut spans = Vec::new();
+    let mut current: Option<(Hyperlink, usize)> = None;
+    for (cell_ix, cell) in cells.iter().enumerate() {
+        let hyperlink = cell.hyperlink();
+        if current.as_ref().map(|(link, _)| link) == hyperlink.as_ref() {
+            continue;
+        }
+        if let Some((link, start)) = current.take() {
+            spans.push(HyperlinkSpan {
+                url: link.uri().to_string(),
+                span: start..cell_ix,
+            });
+        }
+        current = hyperlink.map(|link| (link, cell_ix));
+    }
+    if let Some((link, start)) = current {
+        spans.push(HyperlinkSpan {
+            url: link.uri().to_string(),
+            span: start..cells.len(),
+        });
+    }
+    spans
+}
+
+/// Returns the local path of a `file://` URL.
+fn file_url_path(url: &str) -> Option<PathBuf> {
+    let url = Url::parse(url).ok()?;
+    if url.scheme() != "file" {
+        return None;
+    }
+    url.to_file_path().ok()
+}
+
 fn content_index_for_mouse(pos: Point<Pixels>, size: &TerminalSize) -> usize {
     let col = (pos.x / size.cell_width()).round() as usize;
     let clamped_col = min(col, size.columns() - 1);
@@ -1771,13 +1987,14 @@ pub fn rgba_color(r: u8, g: u8, b: u8) -> Hsla {
 mod tests {
     use alacritty_terminal::{
         index::{Column, Line, Point as AlacPoint},
-        term::cell::Cell,
+        term::cell::{Cell, Hyperlink},
     };
     use gpui::{point, size, Pixels};
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, file_url_path, hyperlink_spans, rgb_for_index, HyperlinkSpan,
+        IndexedCell, TerminalContent, TerminalSize,
     };
 
     #[test]
@@ -1806,6 +2023,7 @@ mod tests {
                     Pixels::from(cell_size * (viewport_cells as f32)),
                     Pixels::from(cell_size * (viewport_cells as f32)),
                 ),
+                scale_factor: 1.,
             };
 
             let cells = get_cells(size, &mut rng);
@@ -1842,6 +2060,7 @@ mod tests {
             cell_width: Pixels::from(10.),
             line_height: Pixels::from(10.),
             size: size(Pixels::from(100.), Pixels::from(100.)),
+            scale_factor: 1.,
         };
 
         let cells = get_cells(size, &mut rng);
@@ -1865,6 +2084,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hyperlink_spans() {
+        let docs = Hyperlink::new(None, "https://zed.dev/docs");
+        let file = Hyperlink::new(Some("1"), "file:///tmp/main.rs");
+        let links = [
+            None,
+            Some(&docs),
+            Some(&docs),
+            Some(&file),
+            None,
+            Some(&docs),
+        ];
+        let cells = links
+            .iter()
+            .enumerate()
+            .map(|(col, link)| {
+                let mut cell = Cell::default();
+                cell.set_hyperlink(link.cloned());
+                IndexedCell {
+                    point: AlacPoint::new(Line(0), Column(col)),
+                    cell,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let spans = hyperlink_spans(&cells);
+        assert_eq!(
+            spans,
+            [
+                HyperlinkSpan {
+                    url: "https://zed.dev/docs".into(),
+                    span: 1..3,
+                },
+                HyperlinkSpan {
+                    url: "file:///tmp/main.rs".into(),
+                    span: 3..4,
+                },
+                HyperlinkSpan {
+                    url: "https://zed.dev/docs".into(),
+                    span: 5..6,
+                },
+            ]
+        );
+
+        let content = TerminalContent {
+            hyperlinks: spans,
+            ..Default::default()
+        };
+        assert_eq!(content.hyperlink_at(2).unwrap().span, 1..3);
+        assert_eq!(content.hyperlink_at(4), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_url_path() {
+        assert_eq!(
+            file_url_path("file:///tmp/my%20project/main.rs"),
+            Some("/tmp/my project/main.rs".into())
+        );
+        assert_eq!(file_url_path("https://zed.dev"), None);
+        assert_eq!(file_url_path("not a url"), None);
+    }
+
     fn get_cells(size: TerminalSize, rng: &mut ThreadRng) -> Vec<Vec<char>> {
         let mut cells = Vec::new();
 
@@ -44,6 +44,46 @@ pub struct TerminalSettings {
     pub detect_venv: VenvSettings,
     pub max_scroll_history_lines: Option<usize>,
     pub toolbar: Toolbar,
+    pub profiles: Vec<TerminalProfile>,
+    pub default_profile: Option<String>,
+}
+
+impl TerminalSettings {
+    /// Returns the profile with the given name, if one is configured.
+    pub fn profile(&self, name: &str) -> Option<&TerminalProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+}
+
+/// A named set of options to start new terminals with.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TerminalProfile {
+    /// The name of the profile, shown when picking a profile for a new terminal.
+    pub name: String,
+    /// The program to run in the terminal.
+    pub shell: String,
+    /// The arguments passed to the program.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Any key-value pairs added to the terminal's environment, on top of the `env` setting.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// The directory to start the terminal in, instead of the one picked by the
+    /// `working_directory` setting.
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    /// The name of the theme whose terminal colors are used, instead of the current theme's.
+    #[serde(default)]
+    pub color_scheme: Option<String>,
+}
+
+impl TerminalProfile {
+    pub fn shell(&self) -> Shell {
+        Shell::WithArguments {
+            program: self.shell.clone(),
+            args: self.args.clone(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -171,6 +211,16 @@ pub struct TerminalSettingsContent {
     pub max_scroll_history_lines: Option<usize>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
+    /// Named sets of shells, arguments, environments, directories and color
+    /// schemes to open new terminals with.
+    ///
+    /// Default: []
+    pub profiles: Option<Vec<TerminalProfile>>,
+    /// The name of the profile used to open terminals that aren't opened with
+    /// a specific profile.
+    ///
+    /// Default: null
+    pub default_profile: Option<String>,
 }
 
 impl settings::Settings for TerminalSettings {
@@ -0,0 +1,96 @@
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Subscription, View,
+    WeakView,
+};
+use ui::{prelude::*, Label};
+use workspace::ModalView;
+
+use crate::TerminalView;
+
+/// Asks for a new title for a terminal, replacing the title reported by its process.
+pub struct RenameTerminalModal {
+    title_editor: View<Editor>,
+    terminal_view: WeakView<TerminalView>,
+    _subscription: Subscription,
+}
+
+impl ModalView for RenameTerminalModal {}
+
+impl EventEmitter<DismissEvent> for RenameTerminalModal {}
+
+impl FocusableView for RenameTerminalModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.title_editor.focus_handle(cx)
+    }
+}
+
+impl RenameTerminalModal {
+    pub fn new(terminal_view: View<TerminalView>, cx: &mut ViewContext<Self>) -> Self {
+        let terminal_view_ref = terminal_view.read(cx);
+        let current_title = terminal_view_ref.custom_title().map(ToString::to_string);
+        let process_title = terminal_view_ref.terminal().read(cx).title(true);
+
+        let title_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text(process_title, cx);
+            if let Some(title) = current_title {
+                editor.set_text(title, cx);
+                editor.select_all(&editor::actions::SelectAll, cx);
+            }
+            editor
+        });
+        let subscription = cx.subscribe(&title_editor, |_, _, event: &EditorEvent, cx| {
+            if let EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+
+        Self {
+            title_editor,
+            terminal_view: terminal_view.downgrade(),
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let title = self.title_editor.read(cx).text(cx);
+        // Clearing the title goes back to the one reported by the terminal's process.
+        let title = Some(title.trim().to_string()).filter(|title| !title.is_empty());
+        self.terminal_view
+            .update(cx, |terminal_view, cx| {
+                terminal_view.set_custom_title(title, cx);
+                cx.focus_self();
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for RenameTerminalModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_2(cx)
+            .key_context("RenameTerminalModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(div().px_3().pt_2().pb_1().child(self.title_editor.clone()))
+            .child(
+                div()
+                    .h_px()
+                    .w_full()
+                    .bg(cx.theme().colors().element_background),
+            )
+            .child(
+                h_flex().px_3().py_1().child(
+                    Label::new("Rename the terminal, or leave empty to use its process' title")
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
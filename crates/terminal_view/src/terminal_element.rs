@@ -1,11 +1,12 @@
 use editor::{CursorLayout, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    div, fill, point, px, relative, size, AnyElement, AvailableSpace, Bounds, ContentMask,
-    DispatchPhase, Element, ElementId, FocusHandle, Font, FontStyle, FontWeight, GlobalElementId,
-    HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity, IntoElement,
-    LayoutId, Model, ModelContext, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UnderlineStyle, View, WeakView, WhiteSpace, WindowContext, WindowTextSystem,
+    div, fill, point, px, relative, size, AnyElement, AvailableSpace, Bounds, ContentMask, Corners,
+    DispatchPhase, Element, ElementId, FocusHandle, Font, FontAxes, FontStyle, FontWeight,
+    GlobalElementId, HighlightStyle, Hitbox, Hsla, ImageData, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Model, ModelContext, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle,
+    Styled, TextRun, TextStyle, UnderlineStyle, View, WeakView, WhiteSpace, WindowContext,
+    WindowTextSystem,
 };
 use itertools::Itertools;
 use language::CursorShape;
@@ -23,8 +24,9 @@ use terminal::{
     terminal_settings::TerminalSettings,
     HoveredWord, IndexedCell, Terminal, TerminalContent, TerminalSize,
 };
-use theme::{ActiveTheme, Theme, ThemeSettings};
+use theme::{ActiveTheme, Theme, ThemeRegistry, ThemeSettings};
 use ui::{ParentElement, Tooltip};
+use util::ResultExt;
 use workspace::Workspace;
 
 use std::{fmt::Debug, ops::RangeInclusive};
@@ -47,6 +49,11 @@ pub struct LayoutState {
     gutter: Pixels,
     last_hovered_word: Option<HoveredWord>,
     block_below_cursor_element: Option<AnyElement>,
+    /// Inline images, with their bounds relative to the terminal's origin.
+    images: Vec<(Bounds<Pixels>, Arc<ImageData>)>,
+    /// Gutter markers next to the prompts of the commands marked by the shell, with their bounds
+    /// relative to the terminal's origin.
+    command_markers: Vec<(Bounds<Pixels>, Hsla)>,
 }
 
 /// Helper struct for converting data between Alacritty's cursor points, and displayed cursor points.
@@ -199,12 +206,11 @@ impl TerminalElement {
     fn layout_grid(
         grid: &Vec<IndexedCell>,
         text_style: &TextStyle,
-        // terminal_theme: &TerminalStyle,
+        theme: &Theme,
         text_system: &WindowTextSystem,
         hyperlink: Option<(HighlightStyle, &RangeInclusive<AlacPoint>)>,
         cx: &WindowContext,
     ) -> (Vec<LayoutCell>, Vec<LayoutRect>) {
-        let theme = cx.theme();
         let mut cells = vec![];
         let mut rects = vec![];
 
@@ -625,6 +631,13 @@ impl Element for TerminalElement {
                     .clone()
                     .unwrap_or(settings.buffer_font.features.clone());
 
+                // The axes of the buffer font don't carry over to another font family.
+                let font_axes = if terminal_settings.font_family.is_some() {
+                    FontAxes::default()
+                } else {
+                    settings.buffer_font.axes.clone()
+                };
+
                 let font_weight = terminal_settings.font_weight.unwrap_or_default();
 
                 let line_height = terminal_settings.line_height.value();
@@ -633,7 +646,13 @@ impl Element for TerminalElement {
                 let font_size =
                     font_size.map_or(buffer_font_size, |size| theme::adjusted_font_size(size, cx));
 
-                let theme = cx.theme().clone();
+                // Terminals opened with a profile can be drawn with the colors of another theme.
+                let theme = self
+                    .terminal
+                    .read(cx)
+                    .color_scheme()
+                    .and_then(|name| ThemeRegistry::global(cx).get(name).ok())
+                    .unwrap_or_else(|| cx.theme().clone());
 
                 let link_style = HighlightStyle {
                     color: Some(theme.colors().link_text_hover),
@@ -652,6 +671,7 @@ impl Element for TerminalElement {
                 let text_style = TextStyle {
                     font_family,
                     font_features,
+                    font_axes,
                     font_weight,
                     font_size: font_size.into(),
                     font_style: FontStyle::Normal,
@@ -690,7 +710,7 @@ impl Element for TerminalElement {
                         size.width = cell_width * 2.0;
                     }
 
-                    TerminalSize::new(line_height, cell_width, size)
+                    TerminalSize::new(line_height, cell_width, size, cx.scale_factor())
                 };
 
                 let search_matches = self.terminal.read(cx).matches.clone();
@@ -728,11 +748,54 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    images,
+                    command_regions,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let images = images
+                    .iter()
+                    .map(|terminal_image| {
+                        let line = terminal_image.point.line.0 + display_offset as i32;
+                        let origin = point(
+                            dimensions.cell_width * terminal_image.point.column.0 as f32,
+                            dimensions.line_height * line as f32,
+                        );
+                        // Images are measured in device pixels.
+                        let image_size = terminal_image.image.size();
+                        let image_size = size(
+                            px(u32::from(image_size.width) as f32 / cx.scale_factor()),
+                            px(u32::from(image_size.height) as f32 / cx.scale_factor()),
+                        );
+                        (
+                            Bounds::new(origin, image_size),
+                            terminal_image.image.clone(),
+                        )
+                    })
+                    .collect();
+
+                // Prompts are marked in the gutter, colored by the exit code of their command.
+                let command_markers = command_regions
+                    .iter()
+                    .map(|region| {
+                        let prompt_lines = region.prompt_lines();
+                        let first_line = prompt_lines.start().0 + display_offset as i32;
+                        let line_count = prompt_lines.end().0 - prompt_lines.start().0 + 1;
+                        let marker_bounds = Bounds::new(
+                            point(-gutter * 0.75, dimensions.line_height * first_line as f32),
+                            size(gutter * 0.5, dimensions.line_height * line_count as f32),
+                        );
+                        let color = match region.exit_code {
+                            Some(0) => theme.status().success,
+                            Some(_) => theme.status().error,
+                            None => theme.colors().border,
+                        };
+                        (marker_bounds, color)
+                    })
+                    .collect();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -748,6 +811,7 @@ impl Element for TerminalElement {
                 let (cells, rects) = TerminalElement::layout_grid(
                     cells,
                     &text_style,
+                    &theme,
                     &cx.text_system(),
                     last_hovered_word
                         .as_ref()
@@ -850,6 +914,8 @@ impl Element for TerminalElement {
                     gutter,
                     last_hovered_word,
                     block_below_cursor_element,
+                    images,
+                    command_markers,
                 }
             })
     }
@@ -862,7 +928,7 @@ impl Element for TerminalElement {
         layout: &mut Self::PrepaintState,
         cx: &mut WindowContext<'_>,
     ) {
-        cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+        cx.with_content_mask(Some(ContentMask::new(bounds)), |cx| {
             let scroll_top = self.terminal_view.read(cx).scroll_top;
 
             cx.paint_quad(fill(bounds, layout.background_color));
@@ -879,7 +945,15 @@ impl Element for TerminalElement {
             };
 
             self.register_mouse_listeners(origin, layout.mode, &layout.hitbox, cx);
-            if self.can_navigate_to_selected_word && layout.last_hovered_word.is_some() {
+            let hyperlink_hovered = layout.hitbox.is_hovered(cx)
+                && self
+                    .terminal
+                    .read(cx)
+                    .hyperlink_at_position(cx.mouse_position() - origin)
+                    .is_some();
+            if hyperlink_hovered
+                || self.can_navigate_to_selected_word && layout.last_hovered_word.is_some()
+            {
                 cx.set_cursor_style(gpui::CursorStyle::PointingHand, &layout.hitbox);
             } else {
                 cx.set_cursor_style(gpui::CursorStyle::IBeam, &layout.hitbox);
@@ -933,6 +1007,20 @@ impl Element for TerminalElement {
                         cell.paint(origin, &layout, bounds, cx);
                     }
 
+                    for (image_bounds, image) in &layout.images {
+                        let image_bounds =
+                            Bounds::new(origin + image_bounds.origin, image_bounds.size);
+                        cx.paint_image(image_bounds, Corners::default(), image.clone(), false)
+                            .log_err();
+                    }
+
+                    for (marker_bounds, color) in &layout.command_markers {
+                        cx.paint_quad(fill(
+                            Bounds::new(origin + marker_bounds.origin, marker_bounds.size),
+                            *color,
+                        ));
+                    }
+
                     if self.cursor_visible {
                         if let Some(mut cursor) = cursor {
                             cursor.paint(origin, cx);
@@ -5,9 +5,10 @@ use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
 use futures::future::join_all;
 use gpui::{
-    actions, Action, AppContext, AsyncWindowContext, DismissEvent, Entity, EventEmitter,
-    ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render,
-    Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    actions, impl_actions, Action, AppContext, AsyncWindowContext, DismissEvent, Entity,
+    EventEmitter, ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement,
+    Pixels, Render, Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView,
+    WindowContext,
 };
 use itertools::Itertools;
 use project::{Fs, ProjectEntryId};
@@ -16,7 +17,7 @@ use serde::{Deserialize, Serialize};
 use settings::Settings;
 use task::{RevealStrategy, SpawnInTerminal, TaskId, TerminalWorkDir};
 use terminal::{
-    terminal_settings::{Shell, TerminalDockPosition, TerminalSettings},
+    terminal_settings::{Shell, TerminalDockPosition, TerminalProfile, TerminalSettings},
     Terminal,
 };
 use ui::{
@@ -38,10 +39,19 @@ const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
 
 actions!(terminal_panel, [ToggleFocus]);
 
+/// Opens a new terminal with the options of the profile with the given name.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct NewTerminalWithProfile {
+    pub profile_name: String,
+}
+
+impl_actions!(terminal_panel, [NewTerminalWithProfile]);
+
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
             workspace.register_action(TerminalPanel::new_terminal);
+            workspace.register_action(TerminalPanel::new_terminal_with_profile);
             workspace.register_action(TerminalPanel::open_terminal);
             workspace.register_action(|workspace, _: &ToggleFocus, cx| {
                 if workspace
@@ -93,12 +103,22 @@ impl TerminalPanel {
                             .icon_size(IconSize::Small)
                             .on_click(cx.listener(|pane, _, cx| {
                                 let focus_handle = pane.focus_handle(cx);
-                                let menu = ContextMenu::build(cx, |menu, _| {
-                                    menu.action(
+                                let menu = ContextMenu::build(cx, |menu, cx| {
+                                    let profiles = &TerminalSettings::get_global(cx).profiles;
+                                    let menu = menu.action(
                                         "New Terminal",
                                         workspace::NewTerminal.boxed_clone(),
-                                    )
-                                    .entry(
+                                    );
+                                    let menu = profiles.iter().fold(menu, |menu, profile| {
+                                        menu.action(
+                                            format!("New Terminal: {}", profile.name),
+                                            NewTerminalWithProfile {
+                                                profile_name: profile.name.clone(),
+                                            }
+                                            .boxed_clone(),
+                                        )
+                                    });
+                                    menu.entry(
                                         "Spawn task",
                                         Some(tasks_ui::Spawn::modal().boxed_clone()),
                                         move |cx| {
@@ -341,7 +361,7 @@ impl TerminalPanel {
 
         terminal_panel
             .update(cx, |panel, cx| {
-                panel.add_terminal(terminal_work_dir, None, RevealStrategy::Always, cx)
+                panel.add_terminal(terminal_work_dir, None, None, RevealStrategy::Always, cx)
             })
             .detach_and_log_err(cx);
     }
@@ -470,7 +490,7 @@ impl TerminalPanel {
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<Model<Terminal>>> {
         let reveal = spawn_task.reveal;
-        self.add_terminal(spawn_task.cwd.clone(), Some(spawn_task), reveal, cx)
+        self.add_terminal(spawn_task.cwd.clone(), Some(spawn_task), None, reveal, cx)
     }
 
     /// Create a new Terminal in the current working directory or the user's home directory
@@ -485,7 +505,32 @@ impl TerminalPanel {
 
         terminal_panel
             .update(cx, |this, cx| {
-                this.add_terminal(None, None, RevealStrategy::Always, cx)
+                this.add_terminal(None, None, None, RevealStrategy::Always, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Create a new Terminal with the shell, environment and directory of a profile
+    fn new_terminal_with_profile(
+        workspace: &mut Workspace,
+        action: &NewTerminalWithProfile,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(terminal_panel) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+        let Some(profile) = TerminalSettings::get_global(cx)
+            .profile(&action.profile_name)
+            .cloned()
+        else {
+            let error = anyhow::anyhow!("no terminal profile named {:?}", action.profile_name);
+            workspace.show_error(&error, cx);
+            return;
+        };
+
+        terminal_panel
+            .update(cx, |this, cx| {
+                this.add_terminal(None, None, Some(profile), RevealStrategy::Always, cx)
             })
             .detach_and_log_err(cx);
     }
@@ -521,6 +566,7 @@ impl TerminalPanel {
         &mut self,
         working_directory: Option<TerminalWorkDir>,
         spawn_task: Option<SpawnInTerminal>,
+        profile: Option<TerminalProfile>,
         reveal_strategy: RevealStrategy,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<Model<Terminal>>> {
@@ -553,7 +599,7 @@ impl TerminalPanel {
 
                 let window = cx.window_handle();
                 let terminal = workspace.project().update(cx, |project, cx| {
-                    project.create_terminal(working_directory, spawn_task, window, cx)
+                    project.create_terminal(working_directory, spawn_task, profile, window, cx)
                 })?;
                 let terminal_view = Box::new(cx.new_view(|cx| {
                     TerminalView::new(
@@ -641,7 +687,7 @@ impl TerminalPanel {
         let window = cx.window_handle();
         let new_terminal = project.update(cx, |project, cx| {
             project
-                .create_terminal(spawn_task.cwd.clone(), Some(spawn_task), window, cx)
+                .create_terminal(spawn_task.cwd.clone(), Some(spawn_task), None, window, cx)
                 .log_err()
         })?;
         terminal_to_replace.update(cx, |terminal_to_replace, cx| {
@@ -785,7 +831,7 @@ impl Panel for TerminalPanel {
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
         if active && self.has_no_terminals(cx) {
-            self.add_terminal(None, None, RevealStrategy::Never, cx)
+            self.add_terminal(None, None, None, RevealStrategy::Never, cx)
                 .detach_and_log_err(cx)
         }
     }
@@ -1,4 +1,5 @@
 mod persistence;
+mod rename_terminal;
 pub mod terminal_element;
 pub mod terminal_panel;
 
@@ -6,14 +7,15 @@ use collections::HashSet;
 use editor::{scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
-    anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent, EventEmitter,
-    FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton,
-    MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task, View,
-    VisualContext, WeakView,
+    actions, anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent,
+    EventEmitter, FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model,
+    MouseButton, MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task,
+    View, VisualContext, WeakView,
 };
 use language::Bias;
 use persistence::TERMINAL_DB;
 use project::{search::SearchQuery, Fs, LocalWorktree, Metadata, Project};
+use rename_terminal::RenameTerminalModal;
 use task::TerminalWorkDir;
 use terminal::{
     alacritty_terminal::{
@@ -22,8 +24,8 @@ use terminal::{
     },
     terminal_settings::{TerminalBlink, TerminalSettings, WorkingDirectory},
     Clear, Copy, Event, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp, ScrollPageDown,
-    ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal,
-    TerminalSize,
+    ScrollPageUp, ScrollToBottom, ScrollToTop, SelectLastOutput, ShowCharacterPalette, TaskStatus,
+    Terminal, TerminalSize,
 };
 use terminal_element::{is_blank, TerminalElement};
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label, Tooltip};
@@ -33,8 +35,8 @@ use workspace::{
     notifications::NotifyResultExt,
     register_deserializable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, Workspace,
-    WorkspaceId,
+    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenVisible, Pane, ToolbarItemLocation,
+    Workspace, WorkspaceId,
 };
 
 use anyhow::Context;
@@ -69,6 +71,8 @@ pub struct SendKeystroke(String);
 
 impl_actions!(terminal, [SendText, SendKeystroke]);
 
+actions!(terminal, [RenameTerminal]);
+
 pub fn init(cx: &mut AppContext) {
     terminal_panel::init(cx);
     terminal::init(cx);
@@ -106,6 +110,8 @@ pub struct TerminalView {
     can_navigate_to_selected_word: bool,
     workspace_id: Option<WorkspaceId>,
     show_title: bool,
+    /// A title given by the user, shown instead of the one reported by the terminal's process.
+    custom_title: Option<String>,
     block_below_cursor: Option<Arc<BlockProperties>>,
     scroll_top: Pixels,
     _subscriptions: Vec<Subscription>,
@@ -137,7 +143,7 @@ impl TerminalView {
         let terminal = workspace
             .project()
             .update(cx, |project, cx| {
-                project.create_terminal(working_directory, None, window, cx)
+                project.create_terminal(working_directory, None, None, window, cx)
             })
             .notify_err(workspace, cx);
 
@@ -184,6 +190,7 @@ impl TerminalView {
             can_navigate_to_selected_word: false,
             workspace_id,
             show_title: TerminalSettings::get_global(cx).toolbar.title,
+            custom_title: None,
             block_below_cursor: None,
             scroll_top: Pixels::ZERO,
             _subscriptions: vec![
@@ -199,6 +206,33 @@ impl TerminalView {
         &self.terminal
     }
 
+    pub fn custom_title(&self) -> Option<&str> {
+        self.custom_title.as_deref()
+    }
+
+    pub fn set_custom_title(&mut self, title: Option<String>, cx: &mut ViewContext<Self>) {
+        self.custom_title = title;
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+    }
+
+    /// The title shown in the terminal's tab.
+    pub fn title(&self, truncate: bool, cx: &AppContext) -> String {
+        match &self.custom_title {
+            Some(title) => title.clone(),
+            None => self.terminal.read(cx).title(truncate),
+        }
+    }
+
+    fn rename_terminal(&mut self, _: &RenameTerminal, cx: &mut ViewContext<Self>) {
+        let terminal_view = cx.view().clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(cx, |cx| RenameTerminalModal::new(terminal_view, cx))
+            })
+            .ok();
+    }
+
     pub fn has_bell(&self) -> bool {
         self.has_bell
     }
@@ -214,7 +248,10 @@ impl TerminalView {
         cx: &mut ViewContext<Self>,
     ) {
         let context_menu = ContextMenu::build(cx, |menu, _| {
-            menu.action("Clear", Box::new(Clear))
+            menu.action("New Terminal", Box::new(NewTerminal))
+                .separator()
+                .action("Clear", Box::new(Clear))
+                .action("Rename…", Box::new(RenameTerminal))
                 .action("Close", Box::new(CloseActiveItem { save_intent: None }))
         });
 
@@ -389,6 +426,12 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn select_last_output(&mut self, _: &SelectLastOutput, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.select_last_output());
+        cx.notify();
+    }
+
     fn scroll_to_bottom(&mut self, _: &ScrollToBottom, cx: &mut ViewContext<Self>) {
         self.terminal.update(cx, |term, _| term.scroll_to_bottom());
         if self.block_below_cursor.is_some() {
@@ -850,12 +893,28 @@ fn regex_to_literal(regex: &str) -> String {
 }
 
 pub fn regex_search_for_query(query: &project::search::SearchQuery) -> Option<RegexSearch> {
-    let query = query.as_str();
-    if query == "." {
+    RegexSearch::new(&search_pattern(query)?).ok()
+}
+
+/// Returns the regex matching a search query in the terminal, applying its options.
+fn search_pattern(query: &SearchQuery) -> Option<String> {
+    let mut pattern = match query {
+        SearchQuery::Text { .. } => regex_to_literal(query.as_str()),
+        SearchQuery::Regex { .. } => query.as_str().to_string(),
+        // Terminal output isn't parsed, so there are no syntax nodes to match.
+        SearchQuery::Structural { .. } => return None,
+    };
+    if pattern == "." {
         return None;
     }
-    let searcher = RegexSearch::new(&query);
-    searcher.ok()
+    if query.whole_word() {
+        // Unicode word boundaries aren't supported by the terminal's regex engine.
+        pattern = format!("(?-u:\\b)(?:{pattern})(?-u:\\b)");
+    }
+    // Without an explicit flag, the terminal's search is case-sensitive only for queries with
+    // uppercase characters.
+    let case_flag = if query.case_sensitive() { "-i" } else { "i" };
+    Some(format!("(?{case_flag}){pattern}"))
 }
 
 impl TerminalView {
@@ -913,6 +972,8 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
+            .on_action(cx.listener(TerminalView::select_last_output))
+            .on_action(cx.listener(TerminalView::rename_terminal))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -952,12 +1013,12 @@ impl Item for TerminalView {
     type Event = ItemEvent;
 
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
-        Some(self.terminal().read(cx).title(false).into())
+        Some(self.title(false, cx).into())
     }
 
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
         let terminal = self.terminal().read(cx);
-        let title = terminal.title(true);
+        let title = self.title(true, cx);
 
         let (icon, icon_color, rerun_btn) = match terminal.task() {
             Some(terminal_task) => match &terminal_task.status {
@@ -1109,7 +1170,7 @@ impl Item for TerminalView {
                 .flatten();
 
             let terminal = project.update(&mut cx, |project, cx| {
-                project.create_terminal(cwd, None, window, cx)
+                project.create_terminal(cwd, None, None, window, cx)
             })??;
             pane.update(&mut cx, |_, cx| {
                 cx.new_view(|cx| TerminalView::new(terminal, workspace, Some(workspace_id), cx))
@@ -1138,8 +1199,8 @@ impl SearchableItem for TerminalView {
 
     fn supported_options() -> SearchOptions {
         SearchOptions {
-            case: false,
-            word: false,
+            case: true,
+            word: true,
             regex: true,
             replacement: false,
             selection: false,
@@ -1187,22 +1248,7 @@ impl SearchableItem for TerminalView {
         query: Arc<SearchQuery>,
         cx: &mut ViewContext<Self>,
     ) -> Task<Vec<Self::Match>> {
-        let searcher = match &*query {
-            SearchQuery::Text { .. } => regex_search_for_query(
-                &(SearchQuery::text(
-                    regex_to_literal(&query.as_str()),
-                    query.whole_word(),
-                    query.case_sensitive(),
-                    query.include_ignored(),
-                    query.files_to_include().clone(),
-                    query.files_to_exclude().clone(),
-                )
-                .unwrap()),
-            ),
-            SearchQuery::Regex { .. } => regex_search_for_query(&query),
-        };
-
-        if let Some(s) = searcher {
+        if let Some(s) = regex_search_for_query(&query) {
             self.terminal()
                 .update(cx, |term, cx| term.find_matches(s, cx))
         } else {
@@ -1495,6 +1541,148 @@ mod tests {
         });
     }
 
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_terminals_receive_independent_input(cx: &mut TestAppContext) {
+        use terminal::{
+            terminal_settings::{AlternateScroll, Shell},
+            TerminalBuilder,
+        };
+
+        // The terminals run real processes, whose output is awaited outside of the executor.
+        cx.executor().allow_parking();
+        let (_, workspace) = init_test(cx).await;
+        cx.update(terminal::init);
+        let window = workspace.update(cx, |_, cx| cx.window_handle());
+
+        let views = (0..3)
+            .map(|_| {
+                let terminal = cx.new_model(|cx| {
+                    let (completion_tx, _) = smol::channel::bounded(1);
+                    TerminalBuilder::new(
+                        None,
+                        None,
+                        Shell::Program("cat".into()),
+                        Default::default(),
+                        None,
+                        AlternateScroll::On,
+                        None,
+                        window,
+                        completion_tx,
+                        cx,
+                    )
+                    .unwrap()
+                    .subscribe(cx)
+                });
+                workspace.update(cx, |workspace, cx| {
+                    let view = cx.new_view(|cx| {
+                        TerminalView::new(terminal, workspace.weak_handle(), None, cx)
+                    });
+                    workspace.add_item_to_active_pane(Box::new(view.clone()), None, cx);
+                    view
+                })
+            })
+            .collect::<Vec<_>>();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.active_pane().read(cx).items_len(), 3);
+        });
+
+        for (ix, view) in views.iter().enumerate() {
+            view.update(cx, |view, cx| {
+                view.send_text(&SendText(format!("session-{ix}\n")), cx)
+            });
+        }
+        let output = |cx: &mut TestAppContext| {
+            views
+                .iter()
+                .map(|view| {
+                    view.read_with(cx, |view, cx| {
+                        view.terminal()
+                            .read(cx)
+                            .last_n_non_empty_lines(10)
+                            .join("\n")
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        for _ in 0..100 {
+            let output = output(cx);
+            if (0..3).all(|ix| output[ix].contains(&format!("session-{ix}"))) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        let output = output(cx);
+        for (ix, output) in output.iter().enumerate() {
+            for other_ix in 0..3 {
+                assert_eq!(
+                    output.contains(&format!("session-{other_ix}")),
+                    ix == other_ix,
+                    "unexpected output in terminal {ix}: {output:?}"
+                );
+            }
+        }
+
+        views[1].update(cx, |view, cx| {
+            view.set_custom_title(Some("Server".into()), cx)
+        });
+        assert_eq!(
+            views[1].read_with(cx, |view, cx| view.title(true, cx)),
+            "Server"
+        );
+        assert_ne!(
+            views[0].read_with(cx, |view, cx| view.title(true, cx)),
+            "Server"
+        );
+    }
+
+    #[test]
+    fn test_search_pattern() {
+        let text = |query: &str, whole_word, case_sensitive| {
+            let query = SearchQuery::text(
+                query,
+                whole_word,
+                case_sensitive,
+                false,
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+            search_pattern(&query)
+        };
+        assert_eq!(text("a.b", false, false).as_deref(), Some("(?i)a\\.b"));
+        assert_eq!(text("Error", false, true).as_deref(), Some("(?-i)Error"));
+        assert_eq!(
+            text("err", true, false).as_deref(),
+            Some("(?i)(?-u:\\b)(?:err)(?-u:\\b)")
+        );
+
+        let regex = SearchQuery::regex(
+            "e.+r",
+            false,
+            true,
+            false,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+        assert_eq!(search_pattern(&regex).as_deref(), Some("(?-i)e.+r"));
+
+        // The terminal's regex engine accepts the flags that are added.
+        assert!(regex_search_for_query(&regex).is_some());
+        let whole_word = SearchQuery::text(
+            "err",
+            true,
+            false,
+            false,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+        assert!(regex_search_for_query(&whole_word).is_some());
+    }
+
     #[test]
     fn escapes_only_special_characters() {
         assert_eq!(regex_to_literal(r"test(\w)"), r"test\(\\w\)".to_string());
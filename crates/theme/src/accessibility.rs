@@ -0,0 +1,315 @@
+use gpui::{Hsla, WindowBackgroundAppearance};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Appearance, StatusColors, Theme, ThemeColors};
+
+/// The smallest contrast ratio WCAG 2 level AA allows between normal-size text and its
+/// background.
+pub const MIN_TEXT_CONTRAST: f32 = 4.5;
+
+/// The smallest contrast ratio WCAG 2 level AA allows between borders and other non-text
+/// UI and their background.
+pub const MIN_NON_TEXT_CONTRAST: f32 = 3.0;
+
+/// Accessibility adjustments made to the active theme, set with the `theme_accessibility`
+/// setting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ThemeAccessibility {
+    /// Whether to strengthen borders and raise the contrast of text with its background to
+    /// at least 4.5:1. This also makes translucent fills opaque.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub high_contrast: bool,
+    /// Whether to replace translucent fills and window backgrounds with opaque ones.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub reduce_transparency: bool,
+}
+
+impl ThemeAccessibility {
+    /// Returns whether any adjustment is turned on.
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Returns whether components should draw opaque fills instead of translucent ones.
+    pub fn opaque_fills(&self) -> bool {
+        self.high_contrast || self.reduce_transparency
+    }
+
+    /// Returns a copy of the given theme with these adjustments made to it.
+    pub fn apply(&self, theme: &Theme) -> Theme {
+        let mut theme = theme.clone();
+        if self.opaque_fills() {
+            theme.styles.window_background_appearance = WindowBackgroundAppearance::Opaque;
+            make_fills_opaque(&mut theme.styles.colors);
+            make_status_fills_opaque(&mut theme.styles.status, theme.styles.colors.background);
+        }
+        if self.high_contrast {
+            increase_contrast(&mut theme.styles.colors, theme.appearance);
+        }
+        theme
+    }
+}
+
+/// Returns the relative luminance of a color, as defined by WCAG 2.
+pub fn relative_luminance(color: Hsla) -> f32 {
+    let color = color.to_rgb();
+    let channel = |value: f32| {
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(color.r) + 0.7152 * channel(color.g) + 0.0722 * channel(color.b)
+}
+
+/// Returns the WCAG 2 contrast ratio of a foreground color drawn on an opaque background,
+/// from 1 for no contrast to 21 for black on white.
+///
+/// A translucent foreground is blended with the background first.
+pub fn contrast_ratio(foreground: Hsla, background: Hsla) -> f32 {
+    let foreground = relative_luminance(background.blend(foreground));
+    let background = relative_luminance(background);
+    (foreground.max(background) + 0.05) / (foreground.min(background) + 0.05)
+}
+
+/// Returns the color, made opaque and lightened or darkened until it contrasts with each of
+/// the backgrounds by at least `min_ratio`, or as far as it can go.
+fn ensure_contrast(
+    color: Hsla,
+    backgrounds: &[Hsla],
+    min_ratio: f32,
+    appearance: Appearance,
+) -> Hsla {
+    let mut color = backgrounds.first().map_or(color, |bg| bg.blend(color));
+    color.a = 1.;
+    let step = match appearance {
+        Appearance::Dark => 0.01,
+        Appearance::Light => -0.01,
+    };
+    while backgrounds
+        .iter()
+        .any(|background| contrast_ratio(color, *background) < min_ratio)
+        && (0. ..=1.).contains(&(color.l + step))
+    {
+        color.l += step;
+    }
+    color
+}
+
+/// Blends a translucent fill with the color it is usually drawn on.
+///
+/// Fully transparent fills, like the background of a ghost button, are meant to show the
+/// surface behind them and are kept as they are.
+fn opaque(fill: &mut Hsla, base: Hsla) {
+    if fill.a > 0. && fill.a < 1. {
+        *fill = base.blend(*fill);
+    }
+}
+
+fn make_fills_opaque(colors: &mut ThemeColors) {
+    colors.background.a = 1.;
+    let background = colors.background;
+    for fill in [
+        &mut colors.surface_background,
+        &mut colors.elevated_surface_background,
+        &mut colors.element_background,
+        &mut colors.element_hover,
+        &mut colors.element_active,
+        &mut colors.element_selected,
+        &mut colors.element_disabled,
+        &mut colors.drop_target_background,
+        &mut colors.ghost_element_hover,
+        &mut colors.ghost_element_active,
+        &mut colors.ghost_element_selected,
+        &mut colors.ghost_element_disabled,
+        &mut colors.status_bar_background,
+        &mut colors.title_bar_background,
+        &mut colors.toolbar_background,
+        &mut colors.tab_bar_background,
+        &mut colors.tab_inactive_background,
+        &mut colors.tab_active_background,
+        &mut colors.panel_background,
+        &mut colors.scrollbar_thumb_background,
+        &mut colors.scrollbar_thumb_hover_background,
+        &mut colors.scrollbar_track_background,
+        &mut colors.editor_background,
+    ] {
+        opaque(fill, background);
+    }
+
+    let editor_background = colors.editor_background;
+    for fill in [
+        &mut colors.editor_gutter_background,
+        &mut colors.editor_subheader_background,
+        &mut colors.editor_active_line_background,
+        &mut colors.editor_highlighted_line_background,
+    ] {
+        opaque(fill, editor_background);
+    }
+}
+
+fn make_status_fills_opaque(status: &mut StatusColors, background: Hsla) {
+    for fill in [
+        &mut status.error_background,
+        &mut status.warning_background,
+        &mut status.info_background,
+        &mut status.success_background,
+        &mut status.hint_background,
+    ] {
+        opaque(fill, background);
+    }
+}
+
+fn increase_contrast(colors: &mut ThemeColors, appearance: Appearance) {
+    let backgrounds = [
+        colors.background,
+        colors.surface_background,
+        colors.elevated_surface_background,
+        colors.panel_background,
+        colors.status_bar_background,
+        colors.title_bar_background,
+    ];
+    for color in [
+        &mut colors.text,
+        &mut colors.text_muted,
+        &mut colors.text_placeholder,
+        &mut colors.text_accent,
+        &mut colors.icon,
+        &mut colors.icon_muted,
+        &mut colors.icon_placeholder,
+        &mut colors.icon_accent,
+    ] {
+        *color = ensure_contrast(*color, &backgrounds, MIN_TEXT_CONTRAST, appearance);
+    }
+    for color in [
+        &mut colors.border,
+        &mut colors.border_variant,
+        &mut colors.border_focused,
+        &mut colors.border_selected,
+        &mut colors.pane_group_border,
+        &mut colors.panel_focused_border,
+        &mut colors.pane_focused_border,
+    ] {
+        *color = ensure_contrast(*color, &backgrounds, MIN_NON_TEXT_CONTRAST, appearance);
+    }
+
+    let editor_backgrounds = [colors.editor_background, colors.editor_gutter_background];
+    for color in [
+        &mut colors.editor_foreground,
+        &mut colors.editor_line_number,
+        &mut colors.editor_active_line_number,
+    ] {
+        *color = ensure_contrast(*color, &editor_backgrounds, MIN_TEXT_CONTRAST, appearance);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{black, rgb, white};
+
+    use super::*;
+    use crate::{one_themes::one_family, zed_pro_family};
+
+    fn default_themes() -> Vec<Theme> {
+        let mut themes = zed_pro_family().themes;
+        themes.extend(one_family().themes);
+        themes
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((contrast_ratio(black(), white()) - 21.).abs() < 0.01);
+        assert!((contrast_ratio(white(), black()) - 21.).abs() < 0.01);
+        assert!((contrast_ratio(white(), white()) - 1.).abs() < 0.01);
+        let gray: Hsla = rgb(0x767676).into();
+        assert!((contrast_ratio(gray, white()) - 4.54).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_default_themes_text_contrast() {
+        for theme in default_themes() {
+            let colors = theme.colors();
+            for (name, foreground, background) in [
+                ("text on background", colors.text, colors.background),
+                ("text on surface", colors.text, colors.surface_background),
+                (
+                    "editor foreground on editor background",
+                    colors.editor_foreground,
+                    colors.editor_background,
+                ),
+            ] {
+                let ratio = contrast_ratio(foreground, background);
+                assert!(
+                    ratio >= MIN_TEXT_CONTRAST,
+                    "{} has a contrast ratio of {ratio:.2} for {name}",
+                    theme.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_high_contrast_themes() {
+        let accessibility = ThemeAccessibility {
+            high_contrast: true,
+            reduce_transparency: false,
+        };
+        for theme in default_themes() {
+            let theme = accessibility.apply(&theme);
+            let colors = theme.colors();
+            for background in [
+                colors.background,
+                colors.surface_background,
+                colors.elevated_surface_background,
+                colors.status_bar_background,
+            ] {
+                assert_eq!(background.a, 1.);
+                for (name, foreground) in [
+                    ("text", colors.text),
+                    ("muted text", colors.text_muted),
+                    ("placeholder text", colors.text_placeholder),
+                    ("accent text", colors.text_accent),
+                ] {
+                    let ratio = contrast_ratio(foreground, background);
+                    assert!(
+                        ratio >= MIN_TEXT_CONTRAST,
+                        "high contrast {} has a contrast ratio of {ratio:.2} for {name}",
+                        theme.name
+                    );
+                }
+                assert!(contrast_ratio(colors.border, background) >= MIN_NON_TEXT_CONTRAST);
+            }
+            assert!(
+                contrast_ratio(colors.editor_foreground, colors.editor_background)
+                    >= MIN_TEXT_CONTRAST
+            );
+        }
+    }
+
+    #[test]
+    fn test_reduce_transparency() {
+        let accessibility = ThemeAccessibility {
+            high_contrast: false,
+            reduce_transparency: true,
+        };
+        let mut theme = default_themes().remove(0);
+        theme.styles.colors.element_hover.a = 0.3;
+        theme.styles.colors.ghost_element_background.a = 0.;
+        theme.styles.window_background_appearance = WindowBackgroundAppearance::Blurred;
+
+        let adjusted = accessibility.apply(&theme);
+        assert_eq!(adjusted.colors().element_hover.a, 1.);
+        assert_eq!(adjusted.colors().ghost_element_background.a, 0.);
+        assert_eq!(
+            adjusted.window_background_appearance(),
+            WindowBackgroundAppearance::Opaque
+        );
+        assert_eq!(adjusted.colors().text, theme.colors().text);
+    }
+}
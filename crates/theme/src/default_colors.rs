@@ -25,6 +25,7 @@ impl ThemeColors {
             border_transparent: system.transparent,
             border_disabled: neutral().light().step_3(),
             elevated_surface_background: neutral().light().step_2(),
+            elevated_surface_shadow: black().light_alpha().step_2(),
             surface_background: neutral().light().step_2(),
             background: neutral().light().step_1(),
             element_background: neutral().light().step_3(),
@@ -122,6 +123,7 @@ impl ThemeColors {
             border_transparent: system.transparent,
             border_disabled: neutral().dark().step_3(),
             elevated_surface_background: neutral().dark().step_2(),
+            elevated_surface_shadow: black().dark_alpha().step_5(),
             surface_background: neutral().dark().step_2(),
             background: neutral().dark().step_1(),
             element_background: neutral().dark().step_3(),
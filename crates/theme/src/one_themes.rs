@@ -51,6 +51,7 @@ pub(crate) fn one_dark() -> Theme {
                 border_transparent: SystemColors::default().transparent,
                 border_disabled: hsla(222.0 / 360., 11.6 / 100., 33.7 / 100., 1.0),
                 elevated_surface_background: elevated_surface,
+                elevated_surface_shadow: hsla(0., 0., 0., 0.3),
                 surface_background: bg,
                 background: bg,
                 element_background: hsla(223.0 / 360., 13. / 100., 21. / 100., 1.0),
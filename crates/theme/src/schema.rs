@@ -166,6 +166,10 @@ pub struct ThemeColorsContent {
     #[serde(rename = "elevated_surface.background")]
     pub elevated_surface_background: Option<String>,
 
+    /// Shadow color. Used for the drop shadows of elevated surfaces, like a context menu, popup, or dialog.
+    #[serde(rename = "elevated_surface.shadow")]
+    pub elevated_surface_shadow: Option<String>,
+
     /// Background Color. Used for grounded surfaces like a panel or tab.
     #[serde(rename = "surface.background")]
     pub surface_background: Option<String>,
@@ -563,6 +567,10 @@ impl ThemeColorsContent {
                 .elevated_surface_background
                 .as_ref()
                 .and_then(|color| try_parse_color(color).ok()),
+            elevated_surface_shadow: self
+                .elevated_surface_shadow
+                .as_ref()
+                .and_then(|color| try_parse_color(color).ok()),
             surface_background: self
                 .surface_background
                 .as_ref()
@@ -1,10 +1,10 @@
 use crate::one_themes::one_dark;
-use crate::{Appearance, SyntaxTheme, Theme, ThemeRegistry, ThemeStyleContent};
+use crate::{Appearance, SyntaxTheme, Theme, ThemeAccessibility, ThemeRegistry, ThemeStyleContent};
 use anyhow::Result;
 use derive_more::{Deref, DerefMut};
 use gpui::{
-    px, AppContext, Font, FontFeatures, FontStyle, FontWeight, Global, Pixels, Subscription,
-    ViewContext, WindowContext,
+    px, AppContext, Font, FontAxes, FontFeatures, FontStyle, FontWeight, Global, Pixels,
+    Subscription, ViewContext, WindowContext,
 };
 use refineable::Refineable;
 use schemars::{
@@ -91,6 +91,7 @@ pub struct ThemeSettings {
     pub active_theme: Arc<Theme>,
     pub theme_overrides: Option<ThemeStyleContent>,
     pub ui_density: UiDensity,
+    pub accessibility: ThemeAccessibility,
 }
 
 impl ThemeSettings {
@@ -232,6 +233,9 @@ pub struct ThemeSettingsContent {
     /// The OpenType features to enable for text in the UI.
     #[serde(default)]
     pub ui_font_features: Option<FontFeatures>,
+    /// The variation axes of the UI font, for variable fonts.
+    #[serde(default)]
+    pub ui_font_axes: Option<FontAxes>,
     /// The weight of the UI font in CSS units from 100 to 900.
     #[serde(default)]
     pub ui_font_weight: Option<f32>,
@@ -250,16 +254,23 @@ pub struct ThemeSettingsContent {
     /// The OpenType features to enable for rendering in text buffers.
     #[serde(default)]
     pub buffer_font_features: Option<FontFeatures>,
+    /// The variation axes of the font used in text buffers, for variable fonts.
+    #[serde(default)]
+    pub buffer_font_axes: Option<FontAxes>,
     /// The name of the Zed theme to use.
     #[serde(default)]
     pub theme: Option<ThemeSelection>,
 
-    /// UNSTABLE: Expect many elements to be broken.
+    /// The density of the UI, which scales the spacing and the size of buttons.
     ///
-    // Controls the density of the UI.
-    #[serde(rename = "unstable.ui_density", default)]
+    /// Default: default
+    #[serde(default)]
     pub ui_density: Option<UiDensity>,
 
+    /// Accessibility adjustments made to the current theme, like higher contrast.
+    #[serde(default)]
+    pub theme_accessibility: Option<ThemeAccessibility>,
+
     /// EXPERIMENTAL: Overrides for the current theme.
     ///
     /// These values will override the ones on the current theme specified in `theme`.
@@ -316,7 +327,8 @@ impl ThemeSettings {
         new_theme
     }
 
-    /// Applies the theme overrides, if there are any, to the current theme.
+    /// Applies the theme overrides and accessibility adjustments, if there are any, to the
+    /// current theme.
     pub fn apply_theme_overrides(&mut self) {
         if let Some(theme_overrides) = &self.theme_overrides {
             let mut base_theme = (*self.active_theme).clone();
@@ -342,6 +354,10 @@ impl ThemeSettings {
 
             self.active_theme = Arc::new(base_theme);
         }
+
+        if self.accessibility.is_enabled() {
+            self.active_theme = Arc::new(self.accessibility.apply(&self.active_theme));
+        }
     }
 }
 
@@ -447,12 +463,14 @@ impl settings::Settings for ThemeSettings {
             ui_font: Font {
                 family: defaults.ui_font_family.clone().unwrap().into(),
                 features: defaults.ui_font_features.clone().unwrap(),
+                axes: defaults.ui_font_axes.clone().unwrap(),
                 weight: defaults.ui_font_weight.map(FontWeight).unwrap(),
                 style: Default::default(),
             },
             buffer_font: Font {
                 family: defaults.buffer_font_family.clone().unwrap().into(),
                 features: defaults.buffer_font_features.clone().unwrap(),
+                axes: defaults.buffer_font_axes.clone().unwrap(),
                 weight: defaults.buffer_font_weight.map(FontWeight).unwrap(),
                 style: FontStyle::default(),
             },
@@ -465,12 +483,16 @@ impl settings::Settings for ThemeSettings {
                 .unwrap(),
             theme_overrides: None,
             ui_density: defaults.ui_density.unwrap_or(UiDensity::Default),
+            accessibility: defaults.theme_accessibility.unwrap_or_default(),
         };
 
         for value in sources.user.into_iter().chain(sources.release_channel) {
             if let Some(value) = value.ui_density {
                 this.ui_density = value;
             }
+            if let Some(value) = value.theme_accessibility {
+                this.accessibility = value;
+            }
 
             if let Some(value) = value.buffer_font_family.clone() {
                 this.buffer_font.family = value.into();
@@ -478,6 +500,9 @@ impl settings::Settings for ThemeSettings {
             if let Some(value) = value.buffer_font_features.clone() {
                 this.buffer_font.features = value;
             }
+            if let Some(value) = value.buffer_font_axes.clone() {
+                this.buffer_font.axes = value;
+            }
 
             if let Some(value) = value.buffer_font_weight {
                 this.buffer_font.weight = FontWeight(value);
@@ -489,6 +514,9 @@ impl settings::Settings for ThemeSettings {
             if let Some(value) = value.ui_font_features.clone() {
                 this.ui_font.features = value;
             }
+            if let Some(value) = value.ui_font_axes.clone() {
+                this.ui_font.axes = value;
+            }
             if let Some(value) = value.ui_font_weight {
                 this.ui_font.weight = FontWeight(value);
             }
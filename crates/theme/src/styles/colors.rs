@@ -23,6 +23,10 @@ pub struct ThemeColors {
     pub border_disabled: Hsla,
     /// Border color. Used for elevated surfaces, like a context menu, popup, or dialog.
     pub elevated_surface_background: Hsla,
+    /// Shadow color. Used for the drop shadows of elevated surfaces, like a context menu, popup, or dialog.
+    ///
+    /// Lower shadows of a surface use a fraction of this color's opacity.
+    pub elevated_surface_shadow: Hsla,
     /// Background Color. Used for grounded surfaces like a panel or tab.
     pub surface_background: Hsla,
     /// Background Color. Used for the app background and blank panels or windows.
@@ -6,6 +6,7 @@
 //!
 //! A theme is a collection of colors used to build a consistent appearance for UI components across the application.
 
+mod accessibility;
 mod default_colors;
 mod default_theme;
 mod one_themes;
@@ -19,6 +20,7 @@ mod styles;
 use std::sync::Arc;
 
 use ::settings::{Settings, SettingsStore};
+pub use accessibility::*;
 pub use default_colors::*;
 pub use default_theme::*;
 pub use registry::*;
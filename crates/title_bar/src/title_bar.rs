@@ -443,13 +443,30 @@ impl TitleBar {
             | client::Status::ConnectionLost
             | client::Status::Reauthenticating { .. }
             | client::Status::Reconnecting { .. }
-            | client::Status::ReconnectionError { .. } => Some(
-                div()
-                    .id("disconnected")
-                    .child(Icon::new(IconName::Disconnected).size(IconSize::Small))
-                    .tooltip(|cx| Tooltip::text("Disconnected", cx))
-                    .into_any_element(),
-            ),
+            | client::Status::ReconnectionError { .. } => {
+                // Edits made while disconnected are sent once the project is rejoined.
+                let pending_edit_count = self.project.read(cx).pending_edit_count();
+                let tooltip = match pending_edit_count {
+                    0 => "Disconnected".to_string(),
+                    1 => "Disconnected, 1 edit will be sent after reconnecting".to_string(),
+                    count => format!("Disconnected, {count} edits will be sent after reconnecting"),
+                };
+                Some(
+                    h_flex()
+                        .id("disconnected")
+                        .gap_1()
+                        .child(Icon::new(IconName::Disconnected).size(IconSize::Small))
+                        .when(pending_edit_count > 0, |el| {
+                            el.child(
+                                Label::new(pending_edit_count.to_string())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .tooltip(move |cx| Tooltip::text(tooltip.clone(), cx))
+                        .into_any_element(),
+                )
+            }
             client::Status::UpgradeRequired => {
                 let auto_updater = auto_update::AutoUpdater::get(cx);
                 let label = match auto_updater.map(|auto_update| auto_update.read(cx).status()) {
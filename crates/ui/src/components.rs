@@ -1,26 +1,37 @@
 mod avatar;
+mod breadcrumbs;
 mod button;
 mod checkbox;
 mod context_menu;
 mod disclosure;
 mod divider;
 mod dropdown_menu;
+mod empty_state;
 mod facepile;
 mod icon;
+mod icon_registry;
 mod indicator;
 mod keybinding;
 mod label;
 mod list;
 mod modal;
 mod numeric_stepper;
+mod palette;
+mod panel;
 mod popover;
 mod popover_menu;
 mod radio;
 mod right_click_menu;
+mod select;
 mod setting;
+mod skeleton;
 mod stack;
+mod status_bar;
+mod syntax_preview;
 mod tab;
 mod tab_bar;
+mod tab_strip;
+mod toast;
 mod tool_strip;
 mod tooltip;
 
@@ -28,28 +39,39 @@ mod tooltip;
 mod stories;
 
 pub use avatar::*;
+pub use breadcrumbs::*;
 pub use button::*;
 pub use checkbox::*;
 pub use context_menu::*;
 pub use disclosure::*;
 pub use divider::*;
 use dropdown_menu::*;
+pub use empty_state::*;
 pub use facepile::*;
 pub use icon::*;
+pub use icon_registry::*;
 pub use indicator::*;
 pub use keybinding::*;
 pub use label::*;
 pub use list::*;
 pub use modal::*;
 pub use numeric_stepper::*;
+pub use palette::*;
+pub use panel::*;
 pub use popover::*;
 pub use popover_menu::*;
 pub use radio::*;
 pub use right_click_menu::*;
+pub use select::*;
 pub use setting::*;
+pub use skeleton::*;
 pub use stack::*;
+pub use status_bar::*;
+pub use syntax_preview::*;
 pub use tab::*;
 pub use tab_bar::*;
+pub use tab_strip::*;
+pub use toast::*;
 pub use tool_strip::*;
 pub use tooltip::*;
 
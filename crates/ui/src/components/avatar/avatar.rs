@@ -1,9 +1,18 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
 use crate::prelude::*;
 
-use gpui::{img, AnyElement, Hsla, ImageSource, Img, IntoElement, Styled};
+use gpui::{
+    img, Animation, AnimationExt, AnyElement, FontWeight, Hsla, ImageSource, IntoElement, Styled,
+};
+
+/// How long a loaded avatar image takes to fade in over its initials.
+const IMAGE_FADE_DURATION: Duration = Duration::from_millis(150);
 
 /// The shape of an [`Avatar`].
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
 pub enum AvatarShape {
     /// The avatar is shown in a circle.
     #[default]
@@ -12,21 +21,55 @@ pub enum AvatarShape {
     RoundedRectangle,
 }
 
+/// The predefined sizes of an [`Avatar`].
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum AvatarSize {
+    /// 12px at the default rem size.
+    XSmall,
+    /// 16px at the default rem size.
+    #[default]
+    Small,
+    /// 24px at the default rem size.
+    Medium,
+    /// 32px at the default rem size.
+    Large,
+}
+
+impl From<AvatarSize> for AbsoluteLength {
+    fn from(size: AvatarSize) -> Self {
+        match size {
+            AvatarSize::XSmall => rems(0.75).into(),
+            AvatarSize::Small => rems(1.).into(),
+            AvatarSize::Medium => rems(1.5).into(),
+            AvatarSize::Large => rems(2.).into(),
+        }
+    }
+}
+
 /// An element that renders a user avatar with customizable appearance options.
 ///
+/// While the image is loading, or if it fails to load, the avatar shows the initials of its
+/// [`name`](Avatar::name) on a color derived from that name. Once the image is available it
+/// fades in over the initials.
+///
 /// # Examples
 ///
 /// ```
-/// use ui::{Avatar, AvatarShape};
+/// use ui::{Avatar, AvatarShape, AvatarSize};
 ///
 /// Avatar::new("path/to/image.png")
+///     .name("Nathan Sobo")
 ///     .shape(AvatarShape::Circle)
+///     .size(AvatarSize::Medium)
 ///     .grayscale(true)
 ///     .border_color(gpui::red());
 /// ```
 #[derive(IntoElement)]
 pub struct Avatar {
-    image: Img,
+    source: Option<ImageSource>,
+    name: Option<SharedString>,
+    shape: AvatarShape,
+    grayscale: bool,
     size: Option<AbsoluteLength>,
     border_color: Option<Hsla>,
     indicator: Option<AnyElement>,
@@ -34,14 +77,41 @@ pub struct Avatar {
 
 impl Avatar {
     pub fn new(src: impl Into<ImageSource>) -> Self {
+        Self::from_source(Some(src.into()))
+    }
+
+    /// Creates an avatar without an image, which only shows the initials of the given name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ui::Avatar;
+    ///
+    /// Avatar::from_name("Antonio Scandurra");
+    /// ```
+    pub fn from_name(name: impl Into<SharedString>) -> Self {
+        Self::from_source(None).name(name)
+    }
+
+    /// Creates an avatar for an image source that may not exist.
+    pub fn from_source(source: Option<ImageSource>) -> Self {
         Avatar {
-            image: img(src),
+            source,
+            name: None,
+            shape: AvatarShape::default(),
+            grayscale: false,
             size: None,
             border_color: None,
             indicator: None,
         }
     }
 
+    /// Sets the display name whose initials are shown while the image loads or if it fails to load.
+    pub fn name(mut self, name: impl Into<SharedString>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
     /// Sets the shape of the avatar image.
     ///
     /// This method allows the shape of the avatar to be specified using an [`AvatarShape`].
@@ -55,10 +125,7 @@ impl Avatar {
     /// Avatar::new("path/to/image.png").shape(AvatarShape::Circle);
     /// ```
     pub fn shape(mut self, shape: AvatarShape) -> Self {
-        self.image = match shape {
-            AvatarShape::Circle => self.image.rounded_full(),
-            AvatarShape::RoundedRectangle => self.image.rounded_md(),
-        };
+        self.shape = shape;
         self
     }
 
@@ -72,7 +139,7 @@ impl Avatar {
     /// let avatar = Avatar::new("path/to/image.png").grayscale(true);
     /// ```
     pub fn grayscale(mut self, grayscale: bool) -> Self {
-        self.image = self.image.grayscale(grayscale);
+        self.grayscale = grayscale;
         self
     }
 
@@ -81,7 +148,7 @@ impl Avatar {
         self
     }
 
-    /// Size overrides the avatar size. By default they are 1rem.
+    /// Size overrides the avatar size. By default they are [`AvatarSize::Small`].
     pub fn size<L: Into<AbsoluteLength>>(mut self, size: impl Into<Option<L>>) -> Self {
         self.size = size.into().map(Into::into);
         self
@@ -91,37 +158,161 @@ impl Avatar {
         self.indicator = indicator.into().map(IntoElement::into_any_element);
         self
     }
-}
 
-impl RenderOnce for Avatar {
-    fn render(mut self, cx: &mut WindowContext) -> impl IntoElement {
-        if self.image.style().corner_radii.top_left.is_none() {
-            self = self.shape(AvatarShape::Circle);
+    /// Identifies this avatar's fade-in animation, so that avatars for different people that
+    /// share a parent don't share an animation.
+    fn fade_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.name.hash(&mut hasher);
+        match &self.source {
+            Some(ImageSource::Uri(uri)) => uri.hash(&mut hasher),
+            Some(ImageSource::File(path)) => path.hash(&mut hasher),
+            _ => {}
         }
+        hasher.finish()
+    }
+}
+
+/// Returns the initials shown for the given name: the first letter of its first and last words.
+fn initials(name: &str) -> SharedString {
+    let mut words = name
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_' || c == '.')
+        .filter(|word| !word.is_empty());
+    let first = words.next().and_then(|word| word.chars().next());
+    let last = words.last().and_then(|word| word.chars().next());
+    first
+        .into_iter()
+        .chain(last)
+        .flat_map(char::to_uppercase)
+        .collect::<String>()
+        .into()
+}
+
+/// Returns a stable index for the given name, used to pick its color.
+///
+/// This uses FNV-1a rather than the std hasher so that a person keeps the same color across
+/// releases.
+fn color_index(name: &str) -> u32 {
+    name.bytes().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    })
+}
 
+fn apply_shape<E: Styled>(element: E, shape: AvatarShape) -> E {
+    match shape {
+        AvatarShape::Circle => element.rounded_full(),
+        AvatarShape::RoundedRectangle => element.rounded_md(),
+    }
+}
+
+impl RenderOnce for Avatar {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
         let border_width = if self.border_color.is_some() {
             px(2.)
         } else {
             px(0.)
         };
 
-        let image_size = self.size.unwrap_or_else(|| rems(1.).into());
-        let container_size = image_size.to_pixels(cx.rem_size()) + border_width * 2.;
+        let image_size = self.size.unwrap_or_else(|| AvatarSize::default().into());
+        let image_size_in_pixels = image_size.to_pixels(cx.rem_size());
+        let container_size = image_size_in_pixels + border_width * 2.;
+        let shape = self.shape;
+        let fade_key = self.fade_key();
+
+        let player = cx
+            .theme()
+            .players()
+            .color_for_participant(color_index(self.name.as_deref().unwrap_or_default()));
+        let text_color = cx.theme().colors().text;
+        let initials_text = initials(self.name.as_deref().unwrap_or_default());
+        let render_initials = move || {
+            apply_shape(div(), shape)
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .overflow_hidden()
+                .bg(player.background)
+                .text_color(text_color)
+                .text_size(image_size_in_pixels * 0.45)
+                .font_weight(FontWeight::SEMIBOLD)
+                .child(initials_text.clone())
+        };
 
-        div()
+        let content = match self.source {
+            None => div()
+                .size(image_size)
+                .child(render_initials())
+                .into_any_element(),
+            Some(source) => {
+                let is_loaded = matches!(source.use_data(cx), Some(Ok(_)));
+                let image = apply_shape(img(source), shape)
+                    .size(image_size)
+                    .grayscale(self.grayscale)
+                    .bg(cx.theme().colors().ghost_element_background);
+
+                if is_loaded {
+                    div()
+                        .relative()
+                        .size(image_size)
+                        .child(
+                            div()
+                                .absolute()
+                                .inset_0()
+                                .child(render_initials())
+                                .with_animation(
+                                    ("avatar-initials-fade", fade_key),
+                                    Animation::new(IMAGE_FADE_DURATION),
+                                    |initials, delta| initials.opacity(1. - delta),
+                                ),
+                        )
+                        .child(image.with_animation(
+                            ("avatar-image-fade", fade_key),
+                            Animation::new(IMAGE_FADE_DURATION),
+                            |image, delta| image.opacity(delta),
+                        ))
+                        .into_any_element()
+                } else {
+                    image
+                        .with_loading({
+                            let render_initials = render_initials.clone();
+                            move || render_initials().into_any_element()
+                        })
+                        .with_fallback(move || render_initials().into_any_element())
+                        .into_any_element()
+                }
+            }
+        };
+
+        apply_shape(div(), shape)
             .size(container_size)
-            .map(|mut div| {
-                div.style().corner_radii = self.image.style().corner_radii.clone();
-                div
-            })
             .when_some(self.border_color, |this, color| {
                 this.border(border_width).border_color(color)
             })
-            .child(
-                self.image
-                    .size(image_size)
-                    .bg(cx.theme().colors().ghost_element_background),
-            )
+            .child(content)
             .children(self.indicator.map(|indicator| div().child(indicator)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials("Nathan Sobo"), "NS");
+        assert_eq!(initials("  mikayla  maki "), "MM");
+        assert_eq!(initials("Jean-Luc van der Berg"), "JB");
+        assert_eq!(initials("max-brunsfeld"), "MB");
+        assert_eq!(initials("octocat"), "O");
+        assert_eq!(initials("élodie"), "É");
+        assert_eq!(initials(""), "");
+    }
+
+    #[test]
+    fn test_color_index_is_stable() {
+        assert_eq!(color_index("Nathan Sobo"), color_index("Nathan Sobo"));
+        assert_ne!(color_index("Nathan Sobo"), color_index("Antonio Scandurra"));
+        assert_eq!(color_index(""), 0x811c9dc5);
+    }
+}
@@ -0,0 +1,512 @@
+use std::rc::Rc;
+
+use gpui::{
+    size, AnyElement, AvailableSpace, Bounds, ClickEvent, Font, FontStyle, GlobalElementId, Hsla,
+    LayoutId, Style, TextRun,
+};
+
+use crate::{prelude::*, Tooltip};
+
+/// The horizontal padding on either side of a segment's label.
+const SEGMENT_PADDING: Pixels = px(2.);
+
+/// The kind of location a [`BreadcrumbSegment`] refers to, which determines
+/// how its label is styled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreadcrumbKind {
+    /// A directory containing the file, shown muted.
+    Directory,
+    /// The file itself.
+    File,
+    /// A symbol enclosing the cursor within the file, shown in italics.
+    Symbol,
+}
+
+/// A single segment of a [`Breadcrumbs`] path.
+#[derive(Clone)]
+pub struct BreadcrumbSegment {
+    label: SharedString,
+    kind: BreadcrumbKind,
+    on_click: Option<Rc<dyn Fn(&ClickEvent, &mut WindowContext)>>,
+}
+
+impl BreadcrumbSegment {
+    pub fn new(label: impl Into<SharedString>, kind: BreadcrumbKind) -> Self {
+        Self {
+            label: label.into(),
+            kind,
+            on_click: None,
+        }
+    }
+
+    pub fn directory(label: impl Into<SharedString>) -> Self {
+        Self::new(label, BreadcrumbKind::Directory)
+    }
+
+    pub fn file(label: impl Into<SharedString>) -> Self {
+        Self::new(label, BreadcrumbKind::File)
+    }
+
+    pub fn symbol(label: impl Into<SharedString>) -> Self {
+        Self::new(label, BreadcrumbKind::Symbol)
+    }
+
+    /// Sets the handler invoked when the segment is clicked, such as revealing
+    /// a directory or jumping to a symbol.
+    pub fn on_click(mut self, handler: impl Fn(&ClickEvent, &mut WindowContext) + 'static) -> Self {
+        self.on_click = Some(Rc::new(handler));
+        self
+    }
+
+    fn color(&self) -> Color {
+        match self.kind {
+            BreadcrumbKind::Directory => Color::Muted,
+            BreadcrumbKind::File | BreadcrumbKind::Symbol => Color::Default,
+        }
+    }
+
+    fn is_italic(&self) -> bool {
+        self.kind == BreadcrumbKind::Symbol
+    }
+}
+
+/// An entry displayed by [`Breadcrumbs`] once it has been fit to its width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BreadcrumbSlot {
+    Segment(usize),
+    Ellipsis,
+}
+
+/// A path of clickable segments separated by chevrons, such as the directories
+/// leading to a file followed by the symbols enclosing the cursor.
+///
+/// The breadcrumbs take the width of the whole path, but can be shrunk by
+/// their parent or given a maximum width. When the path doesn't fit, segments
+/// in the middle of it are collapsed into an ellipsis until the rest fits,
+/// keeping the first segment and the last two segments visible for as long as
+/// possible.
+pub struct Breadcrumbs {
+    id: ElementId,
+    segments: Vec<BreadcrumbSegment>,
+    max_width: Option<Pixels>,
+    font: Option<Font>,
+}
+
+impl Breadcrumbs {
+    pub fn new(id: impl Into<ElementId>, segments: Vec<BreadcrumbSegment>) -> Self {
+        Self {
+            id: id.into(),
+            segments,
+            max_width: None,
+            font: None,
+        }
+    }
+
+    /// Sets the width the breadcrumbs must fit within.
+    pub fn max_width(mut self, max_width: Pixels) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Sets the font of the segments' labels, such as the buffer font for the
+    /// path of a file. Symbols are still shown in italics.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    fn render_row(&self, slots: Vec<BreadcrumbSlot>, cx: &WindowContext) -> AnyElement {
+        let gap = Spacing::Small.px(cx);
+        let hidden_labels = (0..self.segments.len())
+            .filter(|ix| !slots.contains(&BreadcrumbSlot::Segment(*ix)))
+            .map(|ix| self.segments[ix].label.to_string())
+            .collect::<Vec<_>>();
+
+        let mut children = Vec::with_capacity(slots.len() * 2);
+        for (slot_ix, slot) in slots.into_iter().enumerate() {
+            if slot_ix > 0 {
+                children.push(
+                    Icon::new(IconName::ChevronRight)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted)
+                        .into_any_element(),
+                );
+            }
+
+            children.push(match slot {
+                BreadcrumbSlot::Segment(ix) => {
+                    render_segment(ix, &self.segments[ix], self.font.as_ref(), cx)
+                }
+                BreadcrumbSlot::Ellipsis => {
+                    let hidden_path = hidden_labels.join(" › ");
+                    div()
+                        .id("ellipsis")
+                        .px(SEGMENT_PADDING)
+                        .child(Label::new("…").size(LabelSize::Small).color(Color::Muted))
+                        .tooltip(move |cx| Tooltip::text(hidden_path.clone(), cx))
+                        .into_any_element()
+                }
+            });
+        }
+
+        h_flex()
+            .id("breadcrumb-row")
+            .gap(gap)
+            .overflow_hidden()
+            .when_some(self.font.clone(), |this, font| this.font(font))
+            .children(children)
+            .into_any_element()
+    }
+}
+
+/// The widths of the parts of [`Breadcrumbs`], measured when they are laid out.
+pub struct BreadcrumbsLayout {
+    segment_widths: Vec<Pixels>,
+    separator_width: Pixels,
+    ellipsis_width: Pixels,
+}
+
+impl Element for Breadcrumbs {
+    type RequestLayoutState = BreadcrumbsLayout;
+    type PrepaintState = Option<AnyElement>;
+
+    fn id(&self) -> Option<ElementId> {
+        Some(self.id.clone())
+    }
+
+    fn request_layout(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        cx: &mut WindowContext,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let separator_width =
+            IconSize::XSmall.rems().to_pixels(cx.rem_size()) + Spacing::Small.px(cx) * 2.;
+        let segment_widths = self
+            .segments
+            .iter()
+            .map(|segment| {
+                label_width(&segment.label, segment.is_italic(), self.font.as_ref(), cx)
+                    + SEGMENT_PADDING * 2.
+            })
+            .collect::<Vec<_>>();
+        let ellipsis_width = label_width("…", false, self.font.as_ref(), cx) + SEGMENT_PADDING * 2.;
+        let full_width = segment_widths
+            .iter()
+            .fold(Pixels::ZERO, |total, width| total + *width)
+            + separator_width * segment_widths.len().saturating_sub(1) as f32;
+        let mut text_style = cx.text_style();
+        text_style.font_size = TextSize::Small.rems(cx).into();
+        let height = text_style
+            .line_height_in_pixels(cx.rem_size())
+            .max(IconSize::XSmall.rems().to_pixels(cx.rem_size()));
+
+        // Ask for the width of the whole path, but allow the parent to shrink the breadcrumbs
+        // to nothing, in which case they collapse to fit when they are prepainted.
+        let mut style = Style::default();
+        style.flex_shrink = 1.;
+        style.min_size.width = px(0.).into();
+        if let Some(max_width) = self.max_width {
+            style.max_size.width = max_width.into();
+        }
+        let layout_id = cx.request_measured_layout(style, move |known, _, _| {
+            size(
+                known.width.unwrap_or(full_width),
+                known.height.unwrap_or(height),
+            )
+        });
+
+        (
+            layout_id,
+            BreadcrumbsLayout {
+                segment_widths,
+                separator_width,
+                ellipsis_width,
+            },
+        )
+    }
+
+    fn prepaint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        bounds: Bounds<Pixels>,
+        layout: &mut Self::RequestLayoutState,
+        cx: &mut WindowContext,
+    ) -> Self::PrepaintState {
+        if self.segments.is_empty() {
+            return None;
+        }
+
+        // Leave a little slack for rounding in the widths measured by the text system.
+        let slots = fit_segments(
+            &layout.segment_widths,
+            layout.separator_width,
+            layout.ellipsis_width,
+            bounds.size.width + px(0.5),
+        );
+        let mut row = self.render_row(slots, cx);
+        row.prepaint_as_root(bounds.origin, bounds.size.map(AvailableSpace::Definite), cx);
+        Some(row)
+    }
+
+    fn paint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Bounds<Pixels>,
+        _: &mut Self::RequestLayoutState,
+        row: &mut Self::PrepaintState,
+        cx: &mut WindowContext,
+    ) {
+        if let Some(row) = row {
+            row.paint(cx);
+        }
+    }
+}
+
+impl IntoElement for Breadcrumbs {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+fn render_segment(
+    ix: usize,
+    segment: &BreadcrumbSegment,
+    font: Option<&Font>,
+    cx: &WindowContext,
+) -> AnyElement {
+    div()
+        .id(ix)
+        .debug_selector(move || format!("BREADCRUMB-SEGMENT-{ix}"))
+        .flex_none()
+        .px(SEGMENT_PADDING)
+        .rounded_sm()
+        .child(
+            Label::new(segment.label.clone())
+                .size(LabelSize::Small)
+                .color(segment.color())
+                .when_some(font, |label, font| label.weight(font.weight))
+                .italic(segment.is_italic()),
+        )
+        .when_some(segment.on_click.clone(), |this, on_click| {
+            this.cursor_pointer()
+                .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+                .on_click(move |event, cx| on_click(event, cx))
+        })
+        .into_any_element()
+}
+
+fn label_width(text: &str, italic: bool, font: Option<&Font>, cx: &WindowContext) -> Pixels {
+    let mut font = font.cloned().unwrap_or_else(|| cx.text_style().font());
+    if italic {
+        font.style = FontStyle::Italic;
+    }
+    let font_size = TextSize::Small.rems(cx).to_pixels(cx.rem_size());
+    let run = TextRun {
+        len: text.len(),
+        font,
+        color: Hsla::default(),
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+    };
+
+    cx.text_system()
+        .shape_line(SharedString::from(text.to_string()), font_size, &[run])
+        .map_or(Pixels::ZERO, |line| line.width)
+}
+
+/// Chooses which segments to display so that they fit within `max_width`.
+///
+/// Segments after the first are collapsed into an ellipsis, from the start of
+/// the path onwards, until the path fits while the last two segments remain
+/// visible. If even that is too wide, the first segment is collapsed as well,
+/// followed by the second to last one. The last segment is always displayed.
+fn fit_segments(
+    segment_widths: &[Pixels],
+    separator_width: Pixels,
+    ellipsis_width: Pixels,
+    max_width: Pixels,
+) -> Vec<BreadcrumbSlot> {
+    let segment_count = segment_widths.len();
+    let width_of = |slots: &[BreadcrumbSlot]| {
+        let content_width = slots
+            .iter()
+            .map(|slot| match slot {
+                BreadcrumbSlot::Segment(ix) => segment_widths[*ix],
+                BreadcrumbSlot::Ellipsis => ellipsis_width,
+            })
+            .fold(Pixels::ZERO, |total, width| total + width);
+        content_width + separator_width * slots.len().saturating_sub(1) as f32
+    };
+
+    let all_segments = (0..segment_count)
+        .map(BreadcrumbSlot::Segment)
+        .collect::<Vec<_>>();
+    if segment_count <= 1 || width_of(&all_segments) <= max_width {
+        return all_segments;
+    }
+
+    // Collapse the middle of the path, keeping the first and last two segments.
+    for first_visible_tail_ix in 2..segment_count.saturating_sub(1) {
+        let slots = [BreadcrumbSlot::Segment(0), BreadcrumbSlot::Ellipsis]
+            .into_iter()
+            .chain((first_visible_tail_ix..segment_count).map(BreadcrumbSlot::Segment))
+            .collect::<Vec<_>>();
+        if width_of(&slots) <= max_width {
+            return slots;
+        }
+    }
+
+    if segment_count >= 3 {
+        let slots = vec![
+            BreadcrumbSlot::Ellipsis,
+            BreadcrumbSlot::Segment(segment_count - 2),
+            BreadcrumbSlot::Segment(segment_count - 1),
+        ];
+        if width_of(&slots) <= max_width {
+            return slots;
+        }
+    }
+
+    vec![
+        BreadcrumbSlot::Ellipsis,
+        BreadcrumbSlot::Segment(segment_count - 1),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{Render, TestAppContext};
+    use settings::SettingsStore;
+
+    use super::*;
+    use BreadcrumbSlot::{Ellipsis, Segment};
+
+    const SEPARATOR: Pixels = px(10.);
+    const ELLIPSIS: Pixels = px(10.);
+
+    fn fit(widths: &[f32], max_width: f32) -> Vec<BreadcrumbSlot> {
+        let widths = widths.iter().copied().map(px).collect::<Vec<_>>();
+        fit_segments(&widths, SEPARATOR, ELLIPSIS, px(max_width))
+    }
+
+    #[test]
+    fn test_all_segments_fit() {
+        assert_eq!(fit(&[], 0.), vec![]);
+        assert_eq!(
+            fit(&[20., 20., 20.], 80.),
+            vec![Segment(0), Segment(1), Segment(2)]
+        );
+    }
+
+    #[test]
+    fn test_collapsing_ten_segments() {
+        // Displaying every segment takes 10 * 50 + 9 * 10 = 590.
+        let widths = [50.; 10];
+        assert_eq!(fit(&widths, 590.), (0..10).map(Segment).collect::<Vec<_>>());
+        assert_eq!(
+            fit(&widths, 589.),
+            [Segment(0), Ellipsis]
+                .into_iter()
+                .chain((2..10).map(Segment))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            fit(&widths, 300.),
+            vec![Segment(0), Ellipsis, Segment(7), Segment(8), Segment(9)]
+        );
+        assert_eq!(
+            fit(&widths, 190.),
+            vec![Segment(0), Ellipsis, Segment(8), Segment(9)]
+        );
+        assert_eq!(fit(&widths, 150.), vec![Ellipsis, Segment(8), Segment(9)]);
+        assert_eq!(fit(&widths, 100.), vec![Ellipsis, Segment(9)]);
+        assert_eq!(fit(&widths, 0.), vec![Ellipsis, Segment(9)]);
+    }
+
+    #[test]
+    fn test_middle_segments_are_collapsed_first() {
+        // Displaying every segment takes 6 * 20 + 5 * 10 = 170.
+        let widths = [20., 20., 20., 20., 20., 20.];
+        assert_eq!(fit(&widths, 170.).len(), 6);
+        assert_eq!(
+            fit(&widths, 160.),
+            vec![
+                Segment(0),
+                Ellipsis,
+                Segment(2),
+                Segment(3),
+                Segment(4),
+                Segment(5)
+            ]
+        );
+        assert_eq!(
+            fit(&widths, 130.),
+            vec![Segment(0), Ellipsis, Segment(3), Segment(4), Segment(5)]
+        );
+        assert_eq!(
+            fit(&widths, 100.),
+            vec![Segment(0), Ellipsis, Segment(4), Segment(5)]
+        );
+
+        // The first segment is dropped before either of the last two.
+        assert_eq!(fit(&widths, 90.), vec![Ellipsis, Segment(4), Segment(5)]);
+    }
+
+    #[test]
+    fn test_wide_segments_collapse_the_start_of_the_path() {
+        let widths = [100., 20., 20., 20.];
+        assert_eq!(fit(&widths, 110.), vec![Ellipsis, Segment(2), Segment(3)]);
+        assert_eq!(fit(&widths, 40.), vec![Ellipsis, Segment(3)]);
+
+        // The last segment is displayed even when it doesn't fit.
+        assert_eq!(fit(&[20., 200.], 100.), vec![Ellipsis, Segment(1)]);
+        assert_eq!(fit(&[200.], 100.), vec![Segment(0)]);
+    }
+
+    #[test]
+    fn test_three_segments_keep_the_last_two() {
+        assert_eq!(
+            fit(&[50., 30., 30.], 90.),
+            vec![Ellipsis, Segment(1), Segment(2)]
+        );
+    }
+
+    struct BreadcrumbsTestView;
+
+    impl Render for BreadcrumbsTestView {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div().flex().w_full().child(Breadcrumbs::new(
+                "path",
+                (0..10)
+                    .map(|ix| BreadcrumbSegment::directory(format!("directory-{ix}")))
+                    .collect(),
+            ))
+        }
+    }
+
+    #[gpui::test]
+    fn test_breadcrumbs_collapse_to_fit_their_parent(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+        let (_, cx) = cx.add_window_view(|_| BreadcrumbsTestView);
+
+        cx.simulate_resize(size(px(5000.), px(100.)));
+        cx.run_until_parked();
+        assert!(cx.debug_bounds("BREADCRUMB-SEGMENT-0").is_some());
+        assert!(cx.debug_bounds("BREADCRUMB-SEGMENT-4").is_some());
+        assert!(cx.debug_bounds("BREADCRUMB-SEGMENT-9").is_some());
+
+        cx.simulate_resize(size(px(1.), px(100.)));
+        cx.run_until_parked();
+        assert!(cx.debug_bounds("BREADCRUMB-SEGMENT-0").is_none());
+        assert!(cx.debug_bounds("BREADCRUMB-SEGMENT-4").is_none());
+        assert!(cx.debug_bounds("BREADCRUMB-SEGMENT-8").is_none());
+        assert!(cx.debug_bounds("BREADCRUMB-SEGMENT-9").is_some());
+    }
+}
@@ -350,13 +350,23 @@ impl ButtonCommon for Button {
         self.base = self.base.layer(elevation);
         self
     }
+
+    fn state(mut self, state: InteractionState) -> Self {
+        self.base = self.base.state(state);
+        self
+    }
+
+    fn variant(mut self, variant: ButtonVariant) -> Self {
+        self.base = self.base.variant(variant);
+        self
+    }
 }
 
 impl RenderOnce for Button {
     #[allow(refining_impl_trait)]
     fn render(self, cx: &mut WindowContext) -> ButtonLike {
-        let is_disabled = self.base.disabled;
-        let is_selected = self.base.selected;
+        let is_disabled = self.base.is_disabled();
+        let is_selected = self.base.is_selected();
 
         let label = self
             .selected_label
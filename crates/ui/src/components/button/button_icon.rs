@@ -1,4 +1,4 @@
-use crate::{prelude::*, Icon, IconName, IconSize};
+use crate::{prelude::*, Icon, IconSize, IconSource};
 
 /// An icon that appears within a button.
 ///
@@ -6,19 +6,19 @@ use crate::{prelude::*, Icon, IconName, IconSize};
 /// or as a standalone icon, like in [`IconButton`](crate::IconButton).
 #[derive(IntoElement)]
 pub(super) struct ButtonIcon {
-    icon: IconName,
+    icon: IconSource,
     size: IconSize,
     color: Color,
     disabled: bool,
     selected: bool,
-    selected_icon: Option<IconName>,
+    selected_icon: Option<IconSource>,
     selected_style: Option<ButtonStyle>,
 }
 
 impl ButtonIcon {
-    pub fn new(icon: IconName) -> Self {
+    pub fn new(icon: impl Into<IconSource>) -> Self {
         Self {
-            icon,
+            icon: icon.into(),
             size: IconSize::default(),
             color: Color::default(),
             disabled: false,
@@ -44,8 +44,8 @@ impl ButtonIcon {
         self
     }
 
-    pub fn selected_icon(mut self, icon: impl Into<Option<IconName>>) -> Self {
-        self.selected_icon = icon.into();
+    pub fn selected_icon(mut self, icon: Option<impl Into<IconSource>>) -> Self {
+        self.selected_icon = icon.map(Into::into);
         self
     }
 }
@@ -2,7 +2,10 @@ use gpui::{relative, CursorStyle, DefiniteLength, MouseButton};
 use gpui::{transparent_black, AnyElement, AnyView, ClickEvent, Hsla, Rems};
 use smallvec::SmallVec;
 
-use crate::{prelude::*, Elevation, ElevationIndex, Spacing};
+use settings::Settings;
+use theme::{ThemeSettings, UiDensity};
+
+use crate::{prelude::*, user_spacing_style, Elevation, ElevationIndex, Spacing};
 
 /// A trait for buttons that can be Selected. Enables setting the [`ButtonStyle`] of a button when it is selected.
 pub trait SelectableButton: Selectable {
@@ -35,6 +38,52 @@ pub trait ButtonCommon: Clickable + Disableable {
     fn tooltip(self, tooltip: impl Fn(&mut WindowContext) -> AnyView + 'static) -> Self;
 
     fn layer(self, elevation: ElevationIndex) -> Self;
+
+    /// Sets whether the button is enabled, active, or disabled.
+    fn state(self, state: InteractionState) -> Self;
+
+    /// Applies one of the standard button variants, which picks the styles
+    /// used for each [`InteractionState`].
+    fn variant(self, variant: ButtonVariant) -> Self;
+}
+
+/// The state a button is displayed in, independent of hover and press feedback.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub enum InteractionState {
+    #[default]
+    Enabled,
+    /// The button is active, e.g. a toggle that is on or a panel that is open.
+    Active,
+    /// The button can't be interacted with.
+    Disabled,
+}
+
+/// The standard appearances of a button.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub enum ButtonVariant {
+    /// A button with no background until it is hovered.
+    #[default]
+    Ghost,
+    /// A button with a solid background.
+    Filled,
+    /// A ghost button that gains a solid background while it is active.
+    Toggle,
+}
+
+impl ButtonVariant {
+    fn style(self) -> ButtonStyle {
+        match self {
+            ButtonVariant::Ghost | ButtonVariant::Toggle => ButtonStyle::Subtle,
+            ButtonVariant::Filled => ButtonStyle::Filled,
+        }
+    }
+
+    fn active_style(self) -> Option<ButtonStyle> {
+        match self {
+            ButtonVariant::Toggle => Some(ButtonStyle::Filled),
+            ButtonVariant::Ghost | ButtonVariant::Filled => None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
@@ -191,8 +240,13 @@ impl ButtonStyle {
         elevation: Option<Elevation>,
         cx: &mut WindowContext,
     ) -> ButtonLikeStyles {
-        let mut filled_background = element_bg_from_elevation(elevation, cx);
-        filled_background.fade_out(0.92);
+        let filled_background = if ThemeSettings::get_global(cx).accessibility.opaque_fills() {
+            cx.theme().colors().element_hover
+        } else {
+            let mut filled_background = element_bg_from_elevation(elevation, cx);
+            filled_background.fade_out(0.92);
+            filled_background
+        };
 
         match self {
             ButtonStyle::Filled => ButtonLikeStyles {
@@ -270,7 +324,6 @@ impl ButtonStyle {
         }
     }
 
-    #[allow(unused)]
     pub(crate) fn disabled(
         self,
         elevation: Option<Elevation>,
@@ -323,6 +376,27 @@ impl ButtonSize {
             ButtonSize::None => rems_from_px(16.),
         }
     }
+
+    /// Returns the height of a button of this size at the user's [`UiDensity`] setting.
+    ///
+    /// At the default density this is the same as [`ButtonSize::rems`].
+    pub fn rems_for_density(self, cx: &WindowContext) -> Rems {
+        let height = match (user_spacing_style(cx), self) {
+            (UiDensity::Compact, ButtonSize::Large) => 28.,
+            (UiDensity::Compact, ButtonSize::Default) => 20.,
+            (UiDensity::Compact, ButtonSize::Compact) => 16.,
+            (UiDensity::Compact, ButtonSize::None) => 14.,
+            (UiDensity::Default, ButtonSize::Large) => 32.,
+            (UiDensity::Default, ButtonSize::Default) => 22.,
+            (UiDensity::Default, ButtonSize::Compact) => 18.,
+            (UiDensity::Default, ButtonSize::None) => 16.,
+            (UiDensity::Comfortable, ButtonSize::Large) => 36.,
+            (UiDensity::Comfortable, ButtonSize::Default) => 26.,
+            (UiDensity::Comfortable, ButtonSize::Compact) => 22.,
+            (UiDensity::Comfortable, ButtonSize::None) => 18.,
+        };
+        rems_from_px(height)
+    }
 }
 
 /// A button-like element that can be used to create a custom button when
@@ -341,6 +415,8 @@ pub struct ButtonLike {
     pub(super) width: Option<DefiniteLength>,
     pub(super) height: Option<DefiniteLength>,
     pub(super) layer: Option<Elevation>,
+    state: InteractionState,
+    variant: Option<ButtonVariant>,
     size: ButtonSize,
     rounding: Option<ButtonLikeRounding>,
     tooltip: Option<Box<dyn Fn(&mut WindowContext) -> AnyView>>,
@@ -367,9 +443,31 @@ impl ButtonLike {
             cursor_style: CursorStyle::PointingHand,
             on_click: None,
             layer: None,
+            state: InteractionState::Enabled,
+            variant: None,
+        }
+    }
+
+    /// The state the button is displayed in, combining [`ButtonCommon::state`] with the
+    /// `disabled` and `selected` flags. Being disabled takes precedence over being selected.
+    pub(super) fn interaction_state(&self) -> InteractionState {
+        if self.disabled || self.state == InteractionState::Disabled {
+            InteractionState::Disabled
+        } else if self.selected || self.state == InteractionState::Active {
+            InteractionState::Active
+        } else {
+            InteractionState::Enabled
         }
     }
 
+    pub(super) fn is_disabled(&self) -> bool {
+        self.interaction_state() == InteractionState::Disabled
+    }
+
+    pub(super) fn is_selected(&self) -> bool {
+        self.selected || self.state == InteractionState::Active
+    }
+
     pub(crate) fn height(mut self, height: DefiniteLength) -> Self {
         self.height = Some(height);
         self
@@ -450,6 +548,18 @@ impl ButtonCommon for ButtonLike {
         self.layer = Some(elevation.into());
         self
     }
+
+    fn state(mut self, state: InteractionState) -> Self {
+        self.state = state;
+        self
+    }
+
+    fn variant(mut self, variant: ButtonVariant) -> Self {
+        self.variant = Some(variant);
+        self.style = variant.style();
+        self.selected_style = variant.active_style();
+        self
+    }
 }
 
 impl VisibleOnHover for ButtonLike {
@@ -467,9 +577,11 @@ impl ParentElement for ButtonLike {
 
 impl RenderOnce for ButtonLike {
     fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let disabled = self.is_disabled();
+        let selected = self.is_selected();
         let style = self
             .selected_style
-            .filter(|_| self.selected)
+            .filter(|_| selected)
             .unwrap_or(self.style);
 
         self.base
@@ -477,7 +589,7 @@ impl RenderOnce for ButtonLike {
             .id(self.id.clone())
             .group("")
             .flex_none()
-            .h(self.height.unwrap_or(self.size.rems().into()))
+            .h(self.height.unwrap_or(self.size.rems_for_density(cx).into()))
             .when_some(self.width, |this, width| this.w(width).justify_center())
             .when_some(self.rounding, |this, rounding| match rounding {
                 ButtonLikeRounding::All => this.rounded_md(),
@@ -491,23 +603,23 @@ impl RenderOnce for ButtonLike {
                 ButtonSize::None => this,
             })
             .bg(style.enabled(self.layer, cx).background)
-            .when(self.disabled, |this| this.cursor_not_allowed())
-            .when(!self.disabled, |this| {
+            .when(disabled, |this| {
+                this.cursor_not_allowed()
+                    .bg(style.disabled(self.layer, cx).background)
+            })
+            .when(!disabled, |this| {
                 this.cursor_pointer()
                     .hover(|hover| hover.bg(style.hovered(self.layer, cx).background))
                     .active(|active| active.bg(style.active(cx).background))
             })
-            .when_some(
-                self.on_click.filter(|_| !self.disabled),
-                |this, on_click| {
-                    this.on_mouse_down(MouseButton::Left, |_, cx| cx.prevent_default())
-                        .on_click(move |event, cx| {
-                            cx.stop_propagation();
-                            (on_click)(event, cx)
-                        })
-                },
-            )
-            .when(!self.selected, |this| {
+            .when_some(self.on_click.filter(|_| !disabled), |this, on_click| {
+                this.on_mouse_down(MouseButton::Left, |_, cx| cx.prevent_default())
+                    .on_click(move |event, cx| {
+                        cx.stop_propagation();
+                        (on_click)(event, cx)
+                    })
+            })
+            .when(!selected, |this| {
                 this.when_some(self.tooltip, |this, tooltip| {
                     this.tooltip(move |cx| tooltip(cx))
                 })
@@ -515,3 +627,32 @@ impl RenderOnce for ButtonLike {
             .children(self.children)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interaction_state_ignores_call_order() {
+        let button = ButtonLike::new("button")
+            .disabled(true)
+            .state(InteractionState::Active);
+        assert_eq!(button.interaction_state(), InteractionState::Disabled);
+        assert!(button.is_selected());
+
+        let button = ButtonLike::new("button")
+            .state(InteractionState::Disabled)
+            .disabled(false)
+            .selected(true);
+        assert_eq!(button.interaction_state(), InteractionState::Disabled);
+
+        let button = ButtonLike::new("button")
+            .state(InteractionState::Active)
+            .selected(false);
+        assert_eq!(button.interaction_state(), InteractionState::Active);
+        assert_eq!(
+            ButtonLike::new("button").interaction_state(),
+            InteractionState::Enabled
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use gpui::{AnyView, DefiniteLength};
 
 use crate::{prelude::*, ElevationIndex, SelectableButton};
-use crate::{ButtonCommon, ButtonLike, ButtonSize, ButtonStyle, IconName, IconSize};
+use crate::{ButtonCommon, ButtonLike, ButtonSize, ButtonStyle, IconSize, IconSource};
 
 use super::button_icon::ButtonIcon;
 
@@ -16,14 +16,19 @@ pub enum IconButtonShape {
 pub struct IconButton {
     base: ButtonLike,
     shape: IconButtonShape,
-    icon: IconName,
+    icon: IconSource,
     icon_size: IconSize,
     icon_color: Color,
-    selected_icon: Option<IconName>,
+    selected_icon: Option<IconSource>,
 }
 
 impl IconButton {
-    pub fn new(id: impl Into<ElementId>, icon: IconName) -> Self {
+    pub fn new(id: impl Into<ElementId>, icon: impl Into<IconSource>) -> Self {
+        let icon = icon.into();
+        let debug_selector = match &icon {
+            IconSource::BuiltIn(icon) => format!("ICON-{:?}", icon),
+            IconSource::Named(name) => format!("ICON-{}", name),
+        };
         let mut this = Self {
             base: ButtonLike::new(id),
             shape: IconButtonShape::Wide,
@@ -32,7 +37,7 @@ impl IconButton {
             icon_color: Color::Default,
             selected_icon: None,
         };
-        this.base.base = this.base.base.debug_selector(|| format!("ICON-{:?}", icon));
+        this.base.base = this.base.base.debug_selector(|| debug_selector);
         this
     }
 
@@ -51,8 +56,9 @@ impl IconButton {
         self
     }
 
-    pub fn selected_icon(mut self, icon: impl Into<Option<IconName>>) -> Self {
-        self.selected_icon = icon.into();
+    /// Sets the icon shown instead of the button's icon while the button is selected.
+    pub fn selected_icon(mut self, icon: impl Into<IconSource>) -> Self {
+        self.selected_icon = Some(icon.into());
         self
     }
 }
@@ -129,6 +135,16 @@ impl ButtonCommon for IconButton {
         self.base = self.base.layer(elevation);
         self
     }
+
+    fn state(mut self, state: InteractionState) -> Self {
+        self.base = self.base.state(state);
+        self
+    }
+
+    fn variant(mut self, variant: ButtonVariant) -> Self {
+        self.base = self.base.variant(variant);
+        self
+    }
 }
 
 impl VisibleOnHover for IconButton {
@@ -140,8 +156,8 @@ impl VisibleOnHover for IconButton {
 
 impl RenderOnce for IconButton {
     fn render(self, cx: &mut WindowContext) -> impl IntoElement {
-        let is_disabled = self.base.disabled;
-        let is_selected = self.base.selected;
+        let is_disabled = self.base.is_disabled();
+        let is_selected = self.base.is_selected();
         let selected_style = self.base.selected_style;
 
         self.base
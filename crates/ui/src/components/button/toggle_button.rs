@@ -113,12 +113,22 @@ impl ButtonCommon for ToggleButton {
         self.base = self.base.layer(elevation);
         self
     }
+
+    fn state(mut self, state: InteractionState) -> Self {
+        self.base = self.base.state(state);
+        self
+    }
+
+    fn variant(mut self, variant: ButtonVariant) -> Self {
+        self.base = self.base.variant(variant);
+        self
+    }
 }
 
 impl RenderOnce for ToggleButton {
     fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
-        let is_disabled = self.base.disabled;
-        let is_selected = self.base.selected;
+        let is_disabled = self.base.is_disabled();
+        let is_selected = self.base.is_selected();
 
         let label_color = if is_disabled {
             Color::Disabled
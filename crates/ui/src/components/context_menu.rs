@@ -21,6 +21,7 @@ enum ContextMenuItem {
         icon: Option<IconName>,
         handler: Rc<dyn Fn(Option<&FocusHandle>, &mut WindowContext)>,
         action: Option<Box<dyn Action>>,
+        disabled: bool,
     },
     CustomEntry {
         entry_render: Box<dyn Fn(&mut WindowContext) -> AnyElement>,
@@ -102,6 +103,7 @@ impl ContextMenu {
             handler: Rc::new(move |_, cx| handler(cx)),
             icon: None,
             action,
+            disabled: false,
         });
         self
     }
@@ -119,6 +121,24 @@ impl ContextMenu {
             handler: Rc::new(move |_, cx| handler(cx)),
             icon: None,
             action,
+            disabled: false,
+        });
+        self
+    }
+
+    /// Adds an entry that is shown but can't be selected or clicked.
+    pub fn disabled_entry(
+        mut self,
+        label: impl Into<SharedString>,
+        action: Option<Box<dyn Action>>,
+    ) -> Self {
+        self.items.push(ContextMenuItem::Entry {
+            toggled: None,
+            label: label.into(),
+            handler: Rc::new(|_, _| {}),
+            icon: None,
+            action,
+            disabled: true,
         });
         self
     }
@@ -167,6 +187,7 @@ impl ContextMenu {
                 cx.dispatch_action(action.boxed_clone());
             }),
             icon: None,
+            disabled: false,
         });
         self
     }
@@ -179,6 +200,7 @@ impl ContextMenu {
             action: Some(action.boxed_clone()),
             handler: Rc::new(move |_, cx| cx.dispatch_action(action.boxed_clone())),
             icon: Some(IconName::Link),
+            disabled: false,
         });
         self
     }
@@ -259,6 +281,7 @@ impl ContextMenu {
         if let Some(ix) = self.items.iter().position(|item| {
             if let ContextMenuItem::Entry {
                 action: Some(action),
+                disabled: false,
                 ..
             } = item
             {
@@ -293,7 +316,7 @@ impl ContextMenuItem {
             ContextMenuItem::Separator => false,
             ContextMenuItem::Label { .. } => false,
             ContextMenuItem::Header(_) => false,
-            ContextMenuItem::Entry { .. } => true,
+            ContextMenuItem::Entry { disabled, .. } => !disabled,
             ContextMenuItem::CustomEntry { selectable, .. } => *selectable,
         }
     }
@@ -320,6 +343,7 @@ impl Render for ContextMenu {
                         for item in self.items.iter() {
                             if let ContextMenuItem::Entry {
                                 action: Some(action),
+                                disabled: false,
                                 ..
                             } = item
                             {
@@ -352,22 +376,32 @@ impl Render for ContextMenu {
                                     handler,
                                     icon,
                                     action,
+                                    disabled,
                                 } => {
                                     let handler = handler.clone();
                                     let menu = cx.view().downgrade();
 
+                                    let disabled = *disabled;
+                                    let label_color = if disabled {
+                                        Color::Disabled
+                                    } else {
+                                        Color::Default
+                                    };
                                     let label_element = if let Some(icon) = icon {
                                         h_flex()
                                             .gap_1()
-                                            .child(Label::new(label.clone()))
-                                            .child(Icon::new(*icon))
+                                            .child(Label::new(label.clone()).color(label_color))
+                                            .child(Icon::new(*icon).color(label_color))
                                             .into_any_element()
                                     } else {
-                                        Label::new(label.clone()).into_any_element()
+                                        Label::new(label.clone())
+                                            .color(label_color)
+                                            .into_any_element()
                                     };
 
                                     ListItem::new(ix)
                                         .inset(true)
+                                        .disabled(disabled)
                                         .selected(Some(ix) == self.selected_index)
                                         .when_some(*toggled, |list_item, toggled| {
                                             list_item.start_slot(if toggled {
@@ -403,6 +437,9 @@ impl Render for ContextMenu {
                                         .on_click({
                                             let context = self.action_context.clone();
                                             move |_, cx| {
+                                                if disabled {
+                                                    return;
+                                                }
                                                 handler(context.as_ref(), cx);
                                                 menu.update(cx, |menu, cx| {
                                                     menu.clicked = true;
@@ -0,0 +1,77 @@
+use crate::{prelude::*, IconSource};
+
+/// A message filling a panel that has nothing to show, such as a search without results or
+/// a feature that hasn't been configured yet.
+///
+/// # Examples
+///
+/// ```
+/// use ui::{prelude::*, EmptyState};
+///
+/// EmptyState::new("No open folders")
+///     .icon(IconName::FileTree)
+///     .description("Open a folder to browse its files.")
+///     .primary_action(Button::new("open-folder", "Open Folder"));
+/// ```
+#[derive(IntoElement)]
+pub struct EmptyState {
+    icon: Option<IconSource>,
+    headline: SharedString,
+    description: Option<SharedString>,
+    primary_action: Option<Button>,
+}
+
+impl EmptyState {
+    pub fn new(headline: impl Into<SharedString>) -> Self {
+        Self {
+            icon: None,
+            headline: headline.into(),
+            description: None,
+            primary_action: None,
+        }
+    }
+
+    /// Sets the icon shown above the headline.
+    pub fn icon(mut self, icon: impl Into<IconSource>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Sets the text shown below the headline, explaining why there's nothing to show.
+    pub fn description(mut self, description: impl Into<SharedString>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the button shown below the description, usually to fix whatever left the panel
+    /// empty.
+    pub fn primary_action(mut self, button: Button) -> Self {
+        self.primary_action = Some(button);
+        self
+    }
+}
+
+impl RenderOnce for EmptyState {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .gap_2()
+            .p_4()
+            .when_some(self.icon, |this, icon| {
+                this.child(Icon::new(icon).size(IconSize::Large).color(Color::Muted))
+            })
+            .child(Label::new(self.headline))
+            .when_some(self.description, |this, description| {
+                this.child(
+                    Label::new(description)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .when_some(self.primary_action, |this, button| {
+                this.child(div().pt_1().child(button.style(ButtonStyle::Filled)))
+            })
+    }
+}
@@ -2,7 +2,7 @@ use gpui::{svg, AnimationElement, Hsla, IntoElement, Rems, Transformation};
 use serde::{Deserialize, Serialize};
 use strum::{EnumIter, EnumString, IntoStaticStr};
 
-use crate::{prelude::*, Indicator};
+use crate::{prelude::*, IconSource, Indicator};
 
 #[derive(IntoElement)]
 pub enum AnyIcon {
@@ -64,6 +64,8 @@ pub enum IconSize {
     #[default]
     /// 16px
     Medium,
+    /// 20px
+    Large,
 }
 
 impl IconSize {
@@ -73,6 +75,7 @@ impl IconSize {
             IconSize::XSmall => rems_from_px(12.),
             IconSize::Small => rems_from_px(14.),
             IconSize::Medium => rems_from_px(16.),
+            IconSize::Large => rems_from_px(20.),
         }
     }
 
@@ -84,6 +87,7 @@ impl IconSize {
             IconSize::XSmall => Spacing::XSmall.px(cx),
             IconSize::Small => Spacing::XSmall.px(cx),
             IconSize::Medium => Spacing::XSmall.px(cx),
+            IconSize::Large => Spacing::XSmall.px(cx),
         };
 
         icon_size + padding * 2.
@@ -397,18 +401,31 @@ impl IconName {
     }
 }
 
+/// The SVG an [`Icon`] draws.
+enum IconPath {
+    /// The path of an SVG asset.
+    Asset(SharedString),
+    /// The name of an icon in the [`IconRegistry`](crate::IconRegistry), which is looked up when the icon is
+    /// rendered.
+    Registered(SharedString),
+}
+
 #[derive(IntoElement)]
 pub struct Icon {
-    path: SharedString,
+    path: IconPath,
     color: Color,
     size: Rems,
     transformation: Transformation,
 }
 
 impl Icon {
-    pub fn new(icon: IconName) -> Self {
+    pub fn new(icon: impl Into<IconSource>) -> Self {
+        let path = match icon.into() {
+            IconSource::BuiltIn(icon) => IconPath::Asset(icon.path().into()),
+            IconSource::Named(name) => IconPath::Registered(name),
+        };
         Self {
-            path: icon.path().into(),
+            path,
             color: Color::default(),
             size: IconSize::default().rems(),
             transformation: Transformation::default(),
@@ -417,13 +434,14 @@ impl Icon {
 
     pub fn from_path(path: impl Into<SharedString>) -> Self {
         Self {
-            path: path.into(),
+            path: IconPath::Asset(path.into()),
             color: Color::default(),
             size: IconSize::default().rems(),
             transformation: Transformation::default(),
         }
     }
 
+    /// Sets the color of the icon, overriding the default icon color.
     pub fn color(mut self, color: Color) -> Self {
         self.color = color;
         self
@@ -450,11 +468,16 @@ impl Icon {
 
 impl RenderOnce for Icon {
     fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let path = match self.path {
+            IconPath::Asset(path) => path,
+            IconPath::Registered(name) => IconSource::Named(name).path(cx),
+        };
+
         svg()
             .with_transformation(self.transformation)
             .size(self.size)
             .flex_none()
-            .path(self.path)
+            .path(path)
             .text_color(self.color.color(cx))
     }
 }
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use gpui::{AppContext, Global, SharedString};
+
+use crate::IconName;
+
+/// The icon drawn in place of a named icon that isn't registered.
+pub(crate) const PLACEHOLDER_ICON_PATH: &str = "icons/placeholder.svg";
+
+/// Where an [`Icon`](crate::Icon) is drawn from.
+///
+/// Anything that takes an [`IconName`] can take an [`IconSource`] instead, so icons registered
+/// with the [`IconRegistry`] can be used wherever the built-in ones are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IconSource {
+    /// One of the icons that ship with Zed.
+    BuiltIn(IconName),
+    /// An icon registered with the [`IconRegistry`] under this name.
+    Named(SharedString),
+}
+
+impl IconSource {
+    /// Returns the path of the SVG asset to draw for this icon.
+    ///
+    /// Named icons that aren't registered resolve to a placeholder.
+    pub fn path(&self, cx: &AppContext) -> SharedString {
+        match self {
+            IconSource::BuiltIn(icon) => icon.path().into(),
+            IconSource::Named(name) => {
+                IconRegistry::path(name, cx).unwrap_or_else(|| PLACEHOLDER_ICON_PATH.into())
+            }
+        }
+    }
+}
+
+impl From<IconName> for IconSource {
+    fn from(icon: IconName) -> Self {
+        Self::BuiltIn(icon)
+    }
+}
+
+/// The global [`IconRegistry`].
+///
+/// This should not be exposed outside of this module.
+#[derive(Default)]
+struct GlobalIconRegistry(IconRegistry);
+
+impl Global for GlobalIconRegistry {}
+
+/// The icons added at runtime, such as by extensions, in addition to the built-in [`IconName`]s.
+///
+/// Each icon is registered under a name and points at an SVG that the app's
+/// [`AssetSource`](gpui::AssetSource) can load.
+#[derive(Default)]
+pub struct IconRegistry {
+    icons: HashMap<SharedString, SharedString>,
+}
+
+impl IconRegistry {
+    /// Registers the SVG asset at `path` under `name`, replacing any icon already registered
+    /// with that name, and returns the source to draw it with.
+    pub fn register(
+        name: impl Into<SharedString>,
+        path: impl Into<SharedString>,
+        cx: &mut AppContext,
+    ) -> IconSource {
+        let name = name.into();
+        cx.default_global::<GlobalIconRegistry>()
+            .0
+            .icons
+            .insert(name.clone(), path.into());
+        IconSource::Named(name)
+    }
+
+    /// Removes the icon registered under `name`, returning whether there was one.
+    ///
+    /// Icons still using the name are drawn as a placeholder from then on.
+    pub fn unregister(name: &str, cx: &mut AppContext) -> bool {
+        cx.default_global::<GlobalIconRegistry>()
+            .0
+            .icons
+            .remove(name)
+            .is_some()
+    }
+
+    /// Returns the path of the SVG asset registered under `name`.
+    pub fn path(name: &str, cx: &AppContext) -> Option<SharedString> {
+        cx.try_global::<GlobalIconRegistry>()?
+            .0
+            .icons
+            .get(name)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_registering_icons(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let icon = IconRegistry::register("extension-icon", "icons/extension/icon.svg", cx);
+            assert_eq!(icon, IconSource::Named("extension-icon".into()));
+            assert_eq!(icon.path(cx), "icons/extension/icon.svg");
+
+            // Registering the name again replaces the icon.
+            let icon = IconRegistry::register("extension-icon", "icons/extension/other.svg", cx);
+            assert_eq!(icon.path(cx), "icons/extension/other.svg");
+
+            assert!(IconRegistry::unregister("extension-icon", cx));
+            assert!(!IconRegistry::unregister("extension-icon", cx));
+            assert_eq!(IconRegistry::path("extension-icon", cx), None);
+            assert_eq!(icon.path(cx), PLACEHOLDER_ICON_PATH);
+        });
+    }
+
+    #[gpui::test]
+    fn test_icon_source_paths(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            assert_eq!(
+                IconSource::from(IconName::Check).path(cx),
+                IconName::Check.path()
+            );
+            // No icons have been registered, so there's no registry yet.
+            assert_eq!(
+                IconSource::Named("missing".into()).path(cx),
+                PLACEHOLDER_ICON_PATH
+            );
+        });
+    }
+}
@@ -30,6 +30,15 @@ impl KeyBinding {
         Some(Self::new(key_binding))
     }
 
+    /// Like [`KeyBinding::for_action`], but looks the action up by its name,
+    /// such as `"editor::Save"`.
+    ///
+    /// Returns `None` if no action with that name is registered, or if it isn't bound.
+    pub fn for_action_name(name: &str, cx: &mut WindowContext) -> Option<Self> {
+        let action = cx.build_action(name, None).ok()?;
+        Self::for_action(action.as_ref(), cx)
+    }
+
     fn icon_for_key(&self, keystroke: &Keystroke) -> Option<IconName> {
         match keystroke.key.as_str() {
             "left" => Some(IconName::ArrowLeft),
@@ -66,16 +75,92 @@ impl KeyBinding {
         self.platform_style = platform_style;
         self
     }
+
+    /// Returns the keys that make up the given keystroke, from its modifiers
+    /// to the key itself.
+    fn key_labels(&self, keystroke: &Keystroke) -> Vec<KeyLabel> {
+        let is_mac = self.platform_style == PlatformStyle::Mac;
+        let modifier = |mac_icon: IconName, label: &'static str| {
+            if is_mac {
+                KeyLabel::Icon(mac_icon)
+            } else {
+                KeyLabel::Text(label.into())
+            }
+        };
+
+        let mut labels = Vec::new();
+        if keystroke.modifiers.function {
+            labels.push(KeyLabel::Text(if is_mac { "fn" } else { "Fn" }.into()));
+        }
+        if keystroke.modifiers.control {
+            labels.push(modifier(IconName::Control, "Ctrl"));
+        }
+        if keystroke.modifiers.alt {
+            labels.push(modifier(IconName::Option, "Alt"));
+        }
+        if keystroke.modifiers.platform {
+            labels.push(match self.platform_style {
+                PlatformStyle::Mac => KeyLabel::Icon(IconName::Command),
+                PlatformStyle::Linux => KeyLabel::Text("Super".into()),
+                PlatformStyle::Windows => KeyLabel::Text("Win".into()),
+            });
+        }
+        if keystroke.modifiers.shift {
+            labels.push(modifier(IconName::Shift, "Shift"));
+        }
+        labels.push(match self.icon_for_key(keystroke) {
+            Some(icon) => KeyLabel::Icon(icon),
+            None => KeyLabel::Text(keystroke.key.to_uppercase().into()),
+        });
+        labels
+    }
+
+    fn render_keystroke(&self, keystroke: &Keystroke, cx: &WindowContext) -> impl IntoElement {
+        let is_mac = self.platform_style == PlatformStyle::Mac;
+        let mut children = Vec::new();
+        for (ix, label) in self.key_labels(keystroke).into_iter().enumerate() {
+            // Other platforms spell out their modifiers, so join them with the key.
+            if ix > 0 && !is_mac {
+                children.push(KeySeparator::new("+").into_any_element());
+            }
+            children.push(match label {
+                KeyLabel::Text(key) => Key::new(key).into_any_element(),
+                KeyLabel::Icon(icon) => KeyIcon::new(icon).into_any_element(),
+            });
+        }
+
+        h_flex()
+            .flex_none()
+            .gap_0p5()
+            .text_color(cx.theme().colors().text_muted)
+            .children(children)
+    }
+}
+
+/// A single key of a [`KeyBinding`], which is displayed either with a glyph
+/// or with its name.
+#[derive(Debug, Clone, PartialEq)]
+enum KeyLabel {
+    Text(SharedString),
+    Icon(IconName),
 }
 
 impl RenderOnce for KeyBinding {
     fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let keystrokes = self.key_binding.keystrokes();
+        let mut children = Vec::with_capacity(keystrokes.len() * 2);
+        for (ix, keystroke) in keystrokes.iter().enumerate() {
+            if ix > 0 {
+                children.push(KeySeparator::new(",").into_any_element());
+            }
+            children.push(self.render_keystroke(keystroke, cx).into_any_element());
+        }
+
         h_flex()
             .debug_selector(|| {
                 format!(
                     "KEY_BINDING-{}",
-                    self.key_binding
-                        .keystrokes()
+                    keystrokes
                         .iter()
                         .map(|k| k.key.to_string())
                         .collect::<Vec<_>>()
@@ -84,58 +169,7 @@ impl RenderOnce for KeyBinding {
             })
             .gap(Spacing::Small.rems(cx))
             .flex_none()
-            .children(self.key_binding.keystrokes().iter().map(|keystroke| {
-                let key_icon = self.icon_for_key(keystroke);
-
-                h_flex()
-                    .flex_none()
-                    .py_0p5()
-                    .rounded_sm()
-                    .text_color(cx.theme().colors().text_muted)
-                    .when(keystroke.modifiers.function, |el| {
-                        match self.platform_style {
-                            PlatformStyle::Mac => el.child(Key::new("fn")),
-                            PlatformStyle::Linux | PlatformStyle::Windows => {
-                                el.child(Key::new("Fn")).child(Key::new("+"))
-                            }
-                        }
-                    })
-                    .when(keystroke.modifiers.control, |el| {
-                        match self.platform_style {
-                            PlatformStyle::Mac => el.child(KeyIcon::new(IconName::Control)),
-                            PlatformStyle::Linux | PlatformStyle::Windows => {
-                                el.child(Key::new("Ctrl")).child(Key::new("+"))
-                            }
-                        }
-                    })
-                    .when(keystroke.modifiers.alt, |el| match self.platform_style {
-                        PlatformStyle::Mac => el.child(KeyIcon::new(IconName::Option)),
-                        PlatformStyle::Linux | PlatformStyle::Windows => {
-                            el.child(Key::new("Alt")).child(Key::new("+"))
-                        }
-                    })
-                    .when(keystroke.modifiers.platform, |el| {
-                        match self.platform_style {
-                            PlatformStyle::Mac => el.child(KeyIcon::new(IconName::Command)),
-                            PlatformStyle::Linux => {
-                                el.child(Key::new("Super")).child(Key::new("+"))
-                            }
-                            PlatformStyle::Windows => {
-                                el.child(Key::new("Win")).child(Key::new("+"))
-                            }
-                        }
-                    })
-                    .when(keystroke.modifiers.shift, |el| match self.platform_style {
-                        PlatformStyle::Mac => el.child(KeyIcon::new(IconName::Shift)),
-                        PlatformStyle::Linux | PlatformStyle::Windows => {
-                            el.child(Key::new("Shift")).child(Key::new("+"))
-                        }
-                    })
-                    .map(|el| match key_icon {
-                        Some(icon) => el.child(KeyIcon::new(icon)),
-                        None => el.child(Key::new(keystroke.key.to_uppercase())),
-                    })
-            }))
+            .children(children)
     }
 }
 
@@ -148,19 +182,14 @@ impl RenderOnce for Key {
     fn render(self, cx: &mut WindowContext) -> impl IntoElement {
         let single_char = self.key.len() == 1;
 
-        div()
-            .py_0()
+        keycap(cx)
             .map(|this| {
                 if single_char {
-                    this.w(rems_from_px(14.))
-                        .flex()
-                        .flex_none()
-                        .justify_center()
+                    this.w(rems_from_px(16.)).justify_center()
                 } else {
                     this.px_0p5()
                 }
             })
-            .h(rems_from_px(14.))
             .text_ui(cx)
             .line_height(relative(1.))
             .text_color(cx.theme().colors().text_muted)
@@ -174,16 +203,52 @@ impl Key {
     }
 }
 
+/// The bordered box each key of a [`KeyBinding`] is displayed in.
+fn keycap(cx: &WindowContext) -> Div {
+    h_flex()
+        .flex_none()
+        .h(rems_from_px(16.))
+        .rounded_sm()
+        .border_1()
+        .border_color(cx.theme().colors().border_variant)
+        .bg(cx.theme().colors().element_background)
+}
+
+/// The text displayed between the keys of a [`KeyBinding`], such as the `+`
+/// joining modifiers to their key, or the `,` between the keystrokes of a chord.
+#[derive(IntoElement)]
+struct KeySeparator {
+    text: SharedString,
+}
+
+impl KeySeparator {
+    fn new(text: impl Into<SharedString>) -> Self {
+        Self { text: text.into() }
+    }
+}
+
+impl RenderOnce for KeySeparator {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        div()
+            .text_ui(cx)
+            .line_height(relative(1.))
+            .text_color(cx.theme().colors().text_muted)
+            .child(self.text)
+    }
+}
+
 #[derive(IntoElement)]
 pub struct KeyIcon {
     icon: IconName,
 }
 
 impl RenderOnce for KeyIcon {
-    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
-        Icon::new(self.icon)
-            .size(IconSize::Small)
-            .color(Color::Muted)
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        keycap(cx).w(rems_from_px(16.)).justify_center().child(
+            Icon::new(self.icon)
+                .size(IconSize::XSmall)
+                .color(Color::Muted),
+        )
     }
 }
 
@@ -192,3 +257,104 @@ impl KeyIcon {
         Self { icon }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{actions, Empty, Render, TestAppContext, VisualTestContext};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    actions!(key_binding_test, [Save, Unbound]);
+
+    struct TestView;
+
+    impl Render for TestView {
+        fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    fn labels(keystrokes: &str, platform_style: PlatformStyle) -> Vec<Vec<KeyLabel>> {
+        let key_binding = KeyBinding::new(gpui::KeyBinding::new(keystrokes, Save, None))
+            .platform_style(platform_style);
+        key_binding
+            .key_binding
+            .keystrokes()
+            .iter()
+            .map(|keystroke| key_binding.key_labels(keystroke))
+            .collect()
+    }
+
+    #[test]
+    fn test_key_labels() {
+        assert_eq!(
+            labels("cmd-shift-p", PlatformStyle::Mac),
+            [vec![
+                KeyLabel::Icon(IconName::Command),
+                KeyLabel::Icon(IconName::Shift),
+                KeyLabel::Text("P".into())
+            ]]
+        );
+        assert_eq!(
+            labels("ctrl-alt-enter", PlatformStyle::Linux),
+            [vec![
+                KeyLabel::Text("Ctrl".into()),
+                KeyLabel::Text("Alt".into()),
+                KeyLabel::Icon(IconName::Return)
+            ]]
+        );
+        assert_eq!(
+            labels("cmd-k", PlatformStyle::Windows),
+            [vec![
+                KeyLabel::Text("Win".into()),
+                KeyLabel::Text("K".into())
+            ]]
+        );
+
+        // Each keystroke of a chord is displayed separately.
+        assert_eq!(
+            labels("ctrl-k ctrl-s", PlatformStyle::Mac),
+            [
+                vec![
+                    KeyLabel::Icon(IconName::Control),
+                    KeyLabel::Text("K".into())
+                ],
+                vec![
+                    KeyLabel::Icon(IconName::Control),
+                    KeyLabel::Text("S".into())
+                ]
+            ]
+        );
+    }
+
+    #[gpui::test]
+    fn test_key_binding_for_action_name(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            cx.bind_keys([gpui::KeyBinding::new("ctrl-k ctrl-s", Save, None)]);
+        });
+        let (_, cx) = cx.add_window_view(|_| TestView);
+
+        let key_binding = cx
+            .update(|cx| KeyBinding::for_action_name("key_binding_test::Save", cx))
+            .unwrap();
+        assert_eq!(
+            key_binding.key_binding.keystrokes(),
+            [
+                Keystroke::parse("ctrl-k").unwrap(),
+                Keystroke::parse("ctrl-s").unwrap()
+            ]
+        );
+
+        // Unbound and unknown actions have no key binding to display.
+        assert!(cx
+            .update(|cx| KeyBinding::for_action_name("key_binding_test::Unbound", cx))
+            .is_none());
+        assert!(cx
+            .update(|cx| KeyBinding::for_action_name("key_binding_test::Missing", cx))
+            .is_none());
+    }
+}
@@ -3,9 +3,11 @@ mod list_header;
 mod list_item;
 mod list_separator;
 mod list_sub_header;
+mod selectable_list;
 
 pub use list::*;
 pub use list_header::*;
 pub use list_item::*;
 pub use list_separator::*;
 pub use list_sub_header::*;
+pub use selectable_list::*;
@@ -0,0 +1,608 @@
+use std::rc::Rc;
+
+use gpui::{point, AnyElement, ClickEvent, FocusHandle, ScrollHandle};
+use smallvec::SmallVec;
+
+use crate::{prelude::*, ListSubHeader};
+
+/// Whether a [`SelectableList`] allows more than one item to be selected.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ListSelectionMode {
+    #[default]
+    Single,
+    Multiple,
+}
+
+/// The selected items of a [`SelectableList`].
+///
+/// The list doesn't own its selection. It reports a new selection through
+/// [`SelectableList::on_selection_change`] and expects to be rendered with it.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ListSelection {
+    /// The selected items, in list order.
+    pub selected: Vec<ElementId>,
+    /// The item a shift-click range is extended from.
+    pub anchor: Option<ElementId>,
+    /// The item keyboard navigation moves from.
+    pub cursor: Option<ElementId>,
+}
+
+impl ListSelection {
+    pub fn is_selected(&self, id: &ElementId) -> bool {
+        self.selected.contains(id)
+    }
+
+    /// Selects only the given item.
+    pub fn select(&mut self, id: &ElementId) {
+        self.selected = vec![id.clone()];
+        self.anchor = Some(id.clone());
+        self.cursor = Some(id.clone());
+    }
+
+    /// Adds the given item to the selection, or removes it if it is already selected.
+    /// `ids` are all the items of the list, in list order.
+    pub fn toggle(&mut self, ids: &[ElementId], id: &ElementId, mode: ListSelectionMode) {
+        if mode == ListSelectionMode::Single {
+            self.select(id);
+            return;
+        }
+
+        if let Some(ix) = self.selected.iter().position(|selected| selected == id) {
+            self.selected.remove(ix);
+        } else {
+            self.selected.push(id.clone());
+            self.selected
+                .sort_by_key(|selected| ids.iter().position(|id| id == selected));
+        }
+        self.anchor = Some(id.clone());
+        self.cursor = Some(id.clone());
+    }
+
+    /// Selects every item between the anchor and the given item, inclusive.
+    /// `ids` are all the items of the list, in list order.
+    pub fn extend_to(&mut self, ids: &[ElementId], id: &ElementId, mode: ListSelectionMode) {
+        let anchor_ix = self
+            .anchor
+            .as_ref()
+            .and_then(|anchor| ids.iter().position(|id| id == anchor));
+        let target_ix = ids.iter().position(|target| target == id);
+        match (mode, anchor_ix, target_ix) {
+            (ListSelectionMode::Multiple, Some(anchor_ix), Some(target_ix)) => {
+                self.selected = selection_range(ids, anchor_ix, target_ix).to_vec();
+                self.cursor = Some(id.clone());
+            }
+            _ => self.select(id),
+        }
+    }
+
+    /// Moves the cursor to the item at the given index and selects only that item.
+    fn select_index(&mut self, ids: &[ElementId], ix: usize) -> Option<usize> {
+        let ix = ix.min(ids.len().checked_sub(1)?);
+        self.select(&ids[ix]);
+        Some(ix)
+    }
+
+    fn cursor_index(&self, ids: &[ElementId]) -> Option<usize> {
+        let cursor = self.cursor.as_ref()?;
+        ids.iter().position(|id| id == cursor)
+    }
+}
+
+/// Returns the items between the two given indices, inclusive, regardless of their order.
+fn selection_range(ids: &[ElementId], a: usize, b: usize) -> &[ElementId] {
+    let (start, end) = if a <= b { (a, b) } else { (b, a) };
+    &ids[start..=end.min(ids.len().saturating_sub(1))]
+}
+
+/// Returns where a section's header is drawn while the section is scrolled past the top of the
+/// list, as an offset from the top of the list, or `None` if the header is in its own place.
+///
+/// The header stays pinned to the top until the end of its section reaches it, and is then
+/// pushed up by the next section. All positions are relative to the top of the list's content.
+fn sticky_header_offset(
+    section_top: Pixels,
+    section_bottom: Pixels,
+    header_height: Pixels,
+    scroll_top: Pixels,
+) -> Option<Pixels> {
+    if section_top >= scroll_top || section_bottom <= scroll_top {
+        return None;
+    }
+    Some((section_bottom - scroll_top - header_height).min(px(0.)))
+}
+
+/// Returns the scroll position that reveals the given row with as little scrolling as possible,
+/// keeping it clear of a header pinned to the top of the list.
+fn scroll_top_to_reveal(
+    row_top: Pixels,
+    row_bottom: Pixels,
+    viewport_height: Pixels,
+    pinned_header_height: Pixels,
+    scroll_top: Pixels,
+) -> Pixels {
+    if row_top < scroll_top + pinned_header_height {
+        (row_top - pinned_header_height).max(px(0.))
+    } else if row_bottom > scroll_top + viewport_height {
+        row_bottom - viewport_height
+    } else {
+        scroll_top
+    }
+}
+
+/// An item of a [`SelectableList`].
+pub struct SelectableListItem {
+    id: ElementId,
+    child: AnyElement,
+}
+
+impl SelectableListItem {
+    /// Creates an item with an id that identifies it across renders, which is how the item is
+    /// referred to by a [`ListSelection`].
+    pub fn new(id: impl Into<ElementId>, child: impl IntoElement) -> Self {
+        Self {
+            id: id.into(),
+            child: child.into_any_element(),
+        }
+    }
+}
+
+/// A group of items in a [`SelectableList`], with an optional header that sticks to the top of
+/// the list while the section is scrolled.
+pub struct SelectableListSection {
+    header: Option<SharedString>,
+    items: Vec<SelectableListItem>,
+}
+
+impl SelectableListSection {
+    pub fn new(items: impl IntoIterator<Item = SelectableListItem>) -> Self {
+        Self {
+            header: None,
+            items: items.into_iter().collect(),
+        }
+    }
+
+    pub fn header(mut self, header: impl Into<SharedString>) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+}
+
+/// A scrollable list of items in sections, which supports keyboard navigation and single or
+/// multiple selection.
+///
+/// Clicking an item selects it, shift-clicking selects a range, and clicking with the
+/// secondary modifier toggles an item. When focused, `menu::SelectPrev`, `menu::SelectNext`,
+/// `menu::SelectFirst` and `menu::SelectLast` move the selection, and `menu::Confirm` confirms
+/// the item under the cursor, as does double-clicking an item.
+///
+/// Sticky headers and scrolling to the selection require a scroll handle passed to
+/// [`SelectableList::track_scroll`].
+#[derive(IntoElement)]
+pub struct SelectableList {
+    id: ElementId,
+    sections: Vec<SelectableListSection>,
+    selection: ListSelection,
+    mode: ListSelectionMode,
+    scroll_handle: Option<ScrollHandle>,
+    focus_handle: Option<FocusHandle>,
+    empty_state: Option<AnyElement>,
+    on_selection_change: Option<Rc<dyn Fn(&ListSelection, &mut WindowContext)>>,
+    on_confirm: Option<Rc<dyn Fn(&ElementId, &mut WindowContext)>>,
+}
+
+impl SelectableList {
+    pub fn new(
+        id: impl Into<ElementId>,
+        sections: impl IntoIterator<Item = SelectableListSection>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            sections: sections.into_iter().collect(),
+            selection: ListSelection::default(),
+            mode: ListSelectionMode::default(),
+            scroll_handle: None,
+            focus_handle: None,
+            empty_state: None,
+            on_selection_change: None,
+            on_confirm: None,
+        }
+    }
+
+    pub fn selection(mut self, selection: ListSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    pub fn selection_mode(mut self, mode: ListSelectionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn track_scroll(mut self, scroll_handle: ScrollHandle) -> Self {
+        self.scroll_handle = Some(scroll_handle);
+        self
+    }
+
+    /// Enables keyboard navigation while the given handle is focused.
+    pub fn track_focus(mut self, focus_handle: &FocusHandle) -> Self {
+        self.focus_handle = Some(focus_handle.clone());
+        self
+    }
+
+    /// Sets the element shown in place of the list when it has no items.
+    pub fn empty_state(mut self, empty_state: impl IntoElement) -> Self {
+        self.empty_state = Some(empty_state.into_any_element());
+        self
+    }
+
+    pub fn on_selection_change(
+        mut self,
+        handler: impl Fn(&ListSelection, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_selection_change = Some(Rc::new(handler));
+        self
+    }
+
+    /// Called with the item that was double-clicked, or that was under the cursor when the list
+    /// received `menu::Confirm`.
+    pub fn on_confirm(
+        mut self,
+        handler: impl Fn(&ElementId, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_confirm = Some(Rc::new(handler));
+        self
+    }
+}
+
+/// The rows of a section in a [`SelectableList`]'s scroll container, which has one child per
+/// header and per item.
+struct SectionRows {
+    header: Option<SharedString>,
+    /// The header's row if the section has a header, and its first item's row otherwise.
+    first_row: usize,
+    last_row: usize,
+}
+
+/// Where the rows of a [`SelectableList`] are in its scroll container.
+#[derive(Default)]
+struct RowLayout {
+    /// The row of each item, in list order.
+    item_rows: Vec<usize>,
+    sections: Vec<SectionRows>,
+}
+
+impl RowLayout {
+    fn section_for_row(&self, row: usize) -> Option<&SectionRows> {
+        self.sections
+            .iter()
+            .find(|section| section.first_row <= row && row <= section.last_row)
+    }
+}
+
+/// Moves the list's cursor to the item at the given index and scrolls it into view.
+fn navigate(
+    ix: impl Fn(Option<usize>, usize) -> usize,
+    ids: &[ElementId],
+    rows: &RowLayout,
+    selection: &ListSelection,
+    scroll_handle: Option<&ScrollHandle>,
+    on_selection_change: &dyn Fn(&ListSelection, &mut WindowContext),
+    cx: &mut WindowContext,
+) {
+    let mut selection = selection.clone();
+    let Some(ix) = selection.select_index(ids, ix(selection.cursor_index(ids), ids.len())) else {
+        return;
+    };
+
+    if let Some(scroll_handle) = scroll_handle {
+        let row = rows.item_rows[ix];
+        let viewport = scroll_handle.bounds();
+        if let Some(row_bounds) = scroll_handle.bounds_for_item(row) {
+            let pinned_header_height = rows
+                .section_for_row(row)
+                .filter(|section| section.header.is_some())
+                .and_then(|section| scroll_handle.bounds_for_item(section.first_row))
+                .map_or(px(0.), |header| header.size.height);
+            let offset = scroll_handle.offset();
+            let scroll_top = scroll_top_to_reveal(
+                row_bounds.top() - viewport.top(),
+                row_bounds.bottom() - viewport.top(),
+                viewport.size.height,
+                pinned_header_height,
+                -offset.y,
+            );
+            scroll_handle.set_offset(point(offset.x, -scroll_top));
+        }
+    }
+
+    on_selection_change(&selection, cx);
+}
+
+impl RenderOnce for SelectableList {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let ids: Rc<[ElementId]> = self
+            .sections
+            .iter()
+            .flat_map(|section| section.items.iter().map(|item| item.id.clone()))
+            .collect();
+
+        if ids.is_empty() {
+            return div()
+                .id(self.id)
+                .size_full()
+                .children(self.empty_state)
+                .into_any_element();
+        }
+
+        let mut rows = RowLayout::default();
+        let mut row_elements: SmallVec<[AnyElement; 32]> = SmallVec::new();
+        let is_focused = self
+            .focus_handle
+            .as_ref()
+            .map_or(false, |focus_handle| focus_handle.contains_focused(cx));
+        let header_background = cx.theme().colors().elevated_surface_background;
+        let render_header = move |header: SharedString| {
+            div()
+                .w_full()
+                .bg(header_background)
+                .child(ListSubHeader::new(header))
+        };
+
+        for section in self.sections {
+            if section.items.is_empty() && section.header.is_none() {
+                continue;
+            }
+
+            let first_row = row_elements.len();
+            if let Some(header) = section.header.clone() {
+                row_elements.push(render_header(header).into_any_element());
+            }
+
+            for item in section.items {
+                rows.item_rows.push(row_elements.len());
+                let id = item.id;
+                let is_selected = self.selection.is_selected(&id);
+                let is_cursor = self.selection.cursor.as_ref() == Some(&id);
+
+                row_elements.push(
+                    h_flex()
+                        .id(id.clone())
+                        .w_full()
+                        .px(Spacing::Small.rems(cx))
+                        .rounded_md()
+                        .border_1()
+                        .border_color(gpui::transparent_black())
+                        .when(is_selected, |this| {
+                            this.bg(cx.theme().colors().ghost_element_selected)
+                        })
+                        .when(!is_selected, |this| {
+                            this.hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+                        })
+                        .when(is_cursor && is_focused, |this| {
+                            this.border_color(cx.theme().colors().border_focused)
+                        })
+                        .child(item.child)
+                        .on_click({
+                            let ids = ids.clone();
+                            let selection = self.selection.clone();
+                            let mode = self.mode;
+                            let focus_handle = self.focus_handle.clone();
+                            let on_selection_change = self.on_selection_change.clone();
+                            let on_confirm = self.on_confirm.clone();
+                            move |event: &ClickEvent, cx| {
+                                if let Some(focus_handle) = focus_handle.as_ref() {
+                                    focus_handle.focus(cx);
+                                }
+
+                                let modifiers = event.down.modifiers;
+                                let mut selection = selection.clone();
+                                if modifiers.shift {
+                                    selection.extend_to(&ids, &id, mode);
+                                } else if modifiers.secondary() {
+                                    selection.toggle(&ids, &id, mode);
+                                } else {
+                                    selection.select(&id);
+                                }
+                                if let Some(on_selection_change) = on_selection_change.as_ref() {
+                                    on_selection_change(&selection, cx);
+                                }
+
+                                if event.up.click_count > 1 {
+                                    if let Some(on_confirm) = on_confirm.as_ref() {
+                                        on_confirm(&id, cx);
+                                    }
+                                }
+                            }
+                        })
+                        .into_any_element(),
+                );
+            }
+
+            rows.sections.push(SectionRows {
+                header: section.header,
+                first_row,
+                last_row: row_elements.len() - 1,
+            });
+        }
+
+        // Pin the header of the section that is scrolled past the top of the list.
+        let sticky_header = self.scroll_handle.as_ref().and_then(|scroll_handle| {
+            let viewport = scroll_handle.bounds();
+            let scroll_top = -scroll_handle.offset().y;
+            rows.sections.iter().find_map(|section| {
+                let header = section.header.clone()?;
+                let header_bounds = scroll_handle.bounds_for_item(section.first_row)?;
+                let last_bounds = scroll_handle.bounds_for_item(section.last_row)?;
+                let offset = sticky_header_offset(
+                    header_bounds.top() - viewport.top(),
+                    last_bounds.bottom() - viewport.top(),
+                    header_bounds.size.height,
+                    scroll_top,
+                )?;
+                Some((header, offset))
+            })
+        });
+
+        let rows = Rc::new(rows);
+        let on_selection_change = self.on_selection_change.clone();
+        let on_action = |ix: fn(Option<usize>, usize) -> usize| {
+            let ids = ids.clone();
+            let rows = rows.clone();
+            let selection = self.selection.clone();
+            let scroll_handle = self.scroll_handle.clone();
+            let on_selection_change = on_selection_change.clone();
+            move |cx: &mut WindowContext| {
+                if let Some(on_selection_change) = on_selection_change.as_ref() {
+                    navigate(
+                        ix,
+                        &ids,
+                        &rows,
+                        &selection,
+                        scroll_handle.as_ref(),
+                        on_selection_change.as_ref(),
+                        cx,
+                    );
+                }
+            }
+        };
+        let select_prev = on_action(|cursor, _| cursor.map_or(0, |ix| ix.saturating_sub(1)));
+        let select_next = on_action(|cursor, _| cursor.map_or(0, |ix| ix + 1));
+        let select_first = on_action(|_, _| 0);
+        let select_last = on_action(|_, len| len.saturating_sub(1));
+
+        let list = div()
+            .id(self.id)
+            .key_context("menu")
+            .on_action(move |_: &menu::SelectPrev, cx| select_prev(cx))
+            .on_action(move |_: &menu::SelectNext, cx| select_next(cx))
+            .on_action(move |_: &menu::SelectFirst, cx| select_first(cx))
+            .on_action(move |_: &menu::SelectLast, cx| select_last(cx))
+            .when_some(self.on_confirm.clone(), |this, on_confirm| {
+                let cursor = self.selection.cursor.clone();
+                this.on_action(move |_: &menu::Confirm, cx| {
+                    if let Some(cursor) = cursor.as_ref() {
+                        on_confirm(cursor, cx);
+                    }
+                })
+            })
+            .relative()
+            .size_full()
+            .child(
+                v_flex()
+                    .id("rows")
+                    .size_full()
+                    .overflow_y_scroll()
+                    .when_some(self.scroll_handle.clone(), |this, scroll_handle| {
+                        this.track_scroll(&scroll_handle)
+                    })
+                    .children(row_elements),
+            )
+            .when_some(sticky_header, |this, (header, offset)| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top(offset)
+                        .left_0()
+                        .right_0()
+                        .occlude()
+                        .child(render_header(header)),
+                )
+            });
+
+        match self.focus_handle.as_ref() {
+            Some(focus_handle) => list.track_focus(focus_handle).into_any_element(),
+            None => list.into_any_element(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(count: usize) -> Vec<ElementId> {
+        (0..count).map(ElementId::Integer).collect()
+    }
+
+    #[test]
+    fn test_selection_range() {
+        let ids = ids(5);
+        assert_eq!(selection_range(&ids, 1, 3), &ids[1..=3]);
+        assert_eq!(selection_range(&ids, 3, 1), &ids[1..=3]);
+        assert_eq!(selection_range(&ids, 2, 2), &ids[2..=2]);
+
+        let mut selection = ListSelection::default();
+        selection.select(&ids[3]);
+        selection.extend_to(&ids, &ids[1], ListSelectionMode::Multiple);
+        assert_eq!(selection.selected, &ids[1..=3]);
+        assert_eq!(selection.anchor, Some(ids[3].clone()));
+        assert_eq!(selection.cursor, Some(ids[1].clone()));
+
+        // Extending again starts from the same anchor.
+        selection.extend_to(&ids, &ids[4], ListSelectionMode::Multiple);
+        assert_eq!(selection.selected, &ids[3..=4]);
+
+        // Toggling keeps the selection in list order and moves the anchor.
+        selection.toggle(&ids, &ids[0], ListSelectionMode::Multiple);
+        assert_eq!(
+            selection.selected,
+            [ids[0].clone(), ids[3].clone(), ids[4].clone()]
+        );
+        selection.toggle(&ids, &ids[3], ListSelectionMode::Multiple);
+        assert_eq!(selection.selected, [ids[0].clone(), ids[4].clone()]);
+        selection.extend_to(&ids, &ids[1], ListSelectionMode::Multiple);
+        assert_eq!(selection.selected, &ids[1..=3]);
+
+        // In single selection mode, every interaction selects one item.
+        selection.extend_to(&ids, &ids[4], ListSelectionMode::Single);
+        assert_eq!(selection.selected, [ids[4].clone()]);
+        selection.toggle(&ids, &ids[2], ListSelectionMode::Single);
+        assert_eq!(selection.selected, [ids[2].clone()]);
+
+        // Keyboard navigation clamps to the list.
+        assert_eq!(selection.select_index(&ids, 10), Some(4));
+        assert_eq!(selection.cursor_index(&ids), Some(4));
+        assert_eq!(selection.select_index(&[], 0), None);
+    }
+
+    #[test]
+    fn test_sticky_header_offset() {
+        let header_height = px(20.);
+        let offset = |scroll_top: f32| {
+            sticky_header_offset(px(100.), px(200.), header_height, px(scroll_top))
+        };
+
+        // The header is in its own place until the section reaches the top of the list.
+        assert_eq!(offset(0.), None);
+        assert_eq!(offset(100.), None);
+        // Then it is pinned to the top.
+        assert_eq!(offset(101.), Some(px(0.)));
+        assert_eq!(offset(180.), Some(px(0.)));
+        // Until the end of the section pushes it up.
+        assert_eq!(offset(190.), Some(px(-10.)));
+        assert_eq!(offset(199.), Some(px(-19.)));
+        // And the next section takes over.
+        assert_eq!(offset(200.), None);
+    }
+
+    #[test]
+    fn test_scroll_top_to_reveal() {
+        let reveal = |row_top: f32, scroll_top: f32| {
+            scroll_top_to_reveal(
+                px(row_top),
+                px(row_top + 20.),
+                px(100.),
+                px(20.),
+                px(scroll_top),
+            )
+        };
+
+        // Visible rows don't scroll.
+        assert_eq!(reveal(50., 0.), px(0.));
+        // Rows below the list are scrolled up to the bottom edge.
+        assert_eq!(reveal(150., 0.), px(70.));
+        // Rows above the list, or under the pinned header, are scrolled below the header.
+        assert_eq!(reveal(100., 95.), px(80.));
+        assert_eq!(reveal(10., 100.), px(0.));
+    }
+}
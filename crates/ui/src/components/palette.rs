@@ -0,0 +1,523 @@
+use std::rc::Rc;
+
+use gpui::{
+    actions, uniform_list, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle,
+    FocusableView, KeyDownEvent, ListSizingBehavior, Render, UniformListScrollHandle,
+};
+use menu::{SelectNext, SelectPrev};
+
+use crate::{prelude::*, HighlightedLabel, KeyBinding, ListItem};
+
+actions!(palette, [Preview]);
+
+/// An item that can be listed in a [`Palette`].
+pub trait PaletteItem: 'static {
+    /// The text shown for the item, which matched characters are highlighted in.
+    fn label(&self) -> SharedString;
+
+    /// The keystrokes that run the item without opening the palette, shown on the right of
+    /// its row.
+    fn key_binding(&self, _cx: &mut WindowContext) -> Option<KeyBinding> {
+        None
+    }
+}
+
+/// An item that matched the query typed into a [`Palette`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteMatch {
+    /// The index of the item in the palette's items.
+    pub item_ix: usize,
+    /// How well the item matched. Items with higher scores are listed first.
+    pub score: f64,
+    /// The byte offsets of the matched characters in the item's label.
+    pub positions: Vec<usize>,
+}
+
+type PaletteFilter<T> = Box<dyn Fn(&str, &[T]) -> Vec<PaletteMatch>>;
+
+/// A modal that filters a list of items by what the user types.
+///
+/// The palette doesn't know how to match items: the filter it's created with turns the
+/// query into [`PaletteMatch`]es, usually by running a fuzzy matcher over the items' labels,
+/// and is called with an empty query when the palette opens. Callers that remember which
+/// items were used can boost them with [`Palette::weight`].
+///
+/// Up and down move the selection, wrapping around at either end. Enter confirms the
+/// selected item and tab previews it without closing the palette. Escape closes the palette
+/// and focuses whatever was focused when it opened. The palette emits a [`DismissEvent`]
+/// when it closes, after which it should no longer be rendered.
+///
+/// Only the visible rows are rendered, so a palette can hold tens of thousands of items.
+pub struct Palette<T: PaletteItem> {
+    items: Vec<T>,
+    filter: PaletteFilter<T>,
+    weight: Option<Box<dyn Fn(&T) -> f64>>,
+    on_confirm: Option<Rc<dyn Fn(&T, &mut WindowContext)>>,
+    on_preview: Option<Rc<dyn Fn(&T, &mut WindowContext)>>,
+    placeholder: SharedString,
+    query: String,
+    matches: Vec<PaletteMatch>,
+    selected_ix: usize,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    previous_focus: Option<FocusHandle>,
+}
+
+impl<T: PaletteItem> Palette<T> {
+    /// Creates a palette of the given items and focuses it.
+    pub fn new(
+        items: Vec<T>,
+        filter: impl Fn(&str, &[T]) -> Vec<PaletteMatch> + 'static,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let previous_focus = cx.focused();
+        let focus_handle = cx.focus_handle();
+        cx.focus(&focus_handle);
+
+        let mut this = Self {
+            items,
+            filter: Box::new(filter),
+            weight: None,
+            on_confirm: None,
+            on_preview: None,
+            placeholder: "Search…".into(),
+            query: String::new(),
+            matches: Vec::new(),
+            selected_ix: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle,
+            previous_focus,
+        };
+        this.update_matches();
+        this
+    }
+
+    /// Sets the text shown in the input until something is typed.
+    pub fn placeholder(mut self, placeholder: impl Into<SharedString>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Adds the returned weight to the score of each matching item before sorting, such as
+    /// to list recently or frequently used items first.
+    pub fn weight(mut self, weight: impl Fn(&T) -> f64 + 'static) -> Self {
+        self.weight = Some(Box::new(weight));
+        self.update_matches();
+        self
+    }
+
+    /// Called with the selected item when it's confirmed, after focus has been restored to
+    /// where the palette was opened.
+    pub fn on_confirm(mut self, handler: impl Fn(&T, &mut WindowContext) + 'static) -> Self {
+        self.on_confirm = Some(Rc::new(handler));
+        self
+    }
+
+    /// Called with the selected item when it's previewed. The palette stays open.
+    pub fn on_preview(mut self, handler: impl Fn(&T, &mut WindowContext) + 'static) -> Self {
+        self.on_preview = Some(Rc::new(handler));
+        self
+    }
+
+    /// The text typed into the palette.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Replaces the query and filters the items again.
+    pub fn set_query(&mut self, query: impl Into<String>, cx: &mut ViewContext<Self>) {
+        self.query = query.into();
+        self.update_matches();
+        cx.notify();
+    }
+
+    /// The selected item, if any items match the query.
+    pub fn selected_item(&self) -> Option<&T> {
+        let item_match = self.matches.get(self.selected_ix)?;
+        self.items.get(item_match.item_ix)
+    }
+
+    fn update_matches(&mut self) {
+        let mut matches = (self.filter)(&self.query, &self.items);
+        matches.retain(|item_match| item_match.item_ix < self.items.len());
+        if let Some(weight) = self.weight.as_ref() {
+            for item_match in &mut matches {
+                item_match.score += weight(&self.items[item_match.item_ix]);
+            }
+        }
+        // A stable sort keeps the filter's order for equal scores.
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        self.matches = matches;
+        self.selected_ix = 0;
+        self.scroll_handle.scroll_to_item(0);
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_ix = ix;
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        if !self.matches.is_empty() {
+            self.select((self.selected_ix + 1) % self.matches.len(), cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if let Some(last_ix) = self.matches.len().checked_sub(1) {
+            self.select(self.selected_ix.checked_sub(1).unwrap_or(last_ix), cx);
+        }
+    }
+
+    fn dismiss(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(previous_focus) = self.previous_focus.as_ref() {
+            cx.focus(previous_focus);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if self.selected_item().is_none() {
+            return;
+        }
+        self.dismiss(cx);
+        if let Some((on_confirm, item)) = self.on_confirm.clone().zip(self.selected_item()) {
+            on_confirm(item, cx);
+        }
+    }
+
+    fn preview(&mut self, _: &Preview, cx: &mut ViewContext<Self>) {
+        if let Some((on_preview, item)) = self.on_preview.clone().zip(self.selected_item()) {
+            on_preview(item, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        self.dismiss(cx);
+    }
+
+    fn handle_key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.control || keystroke.modifiers.platform {
+            return;
+        }
+
+        let mut query = self.query.clone();
+        if keystroke.key == "backspace" {
+            if query.pop().is_none() {
+                return;
+            }
+        } else if let Some(text) = keystroke.ime_key.as_ref() {
+            query.push_str(text);
+        } else if keystroke.key == "space" {
+            query.push(' ');
+        } else if keystroke.key.chars().count() == 1 {
+            if keystroke.modifiers.shift {
+                query.push_str(&keystroke.key.to_uppercase());
+            } else {
+                query.push_str(&keystroke.key);
+            }
+        } else {
+            return;
+        }
+
+        cx.stop_propagation();
+        self.set_query(query, cx);
+    }
+
+    fn render_match(&self, ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        let item_match = &self.matches[ix];
+        let item = &self.items[item_match.item_ix];
+
+        ListItem::new(ix)
+            .inset(true)
+            .selected(ix == self.selected_ix)
+            .child(HighlightedLabel::new(
+                item.label(),
+                item_match.positions.clone(),
+            ))
+            .end_slot(item.key_binding(cx))
+            .on_click(cx.listener(move |this, _, cx| {
+                this.selected_ix = ix;
+                this.confirm(&menu::Confirm, cx);
+            }))
+            .into_any_element()
+    }
+}
+
+impl<T: PaletteItem> EventEmitter<DismissEvent> for Palette<T> {}
+
+impl<T: PaletteItem> FocusableView for Palette<T> {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl<T: PaletteItem> Render for Palette<T> {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let query_label = if self.query.is_empty() {
+            Label::new(self.placeholder.clone()).color(Color::Placeholder)
+        } else {
+            Label::new(self.query.clone())
+        };
+
+        v_flex()
+            .key_context("Palette")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::preview))
+            .on_action(cx.listener(Self::cancel))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .occlude()
+            .elevation_3(cx)
+            .w(rems(34.))
+            .overflow_hidden()
+            .child(
+                h_flex()
+                    .px_3()
+                    .py_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+                    .child(query_label),
+            )
+            .map(|this| {
+                if self.matches.is_empty() {
+                    this.child(
+                        div()
+                            .px_3()
+                            .py_2()
+                            .child(Label::new("No matches").color(Color::Muted)),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "palette-matches",
+                            self.matches.len(),
+                            |this, range, cx| range.map(|ix| this.render_match(ix, cx)).collect(),
+                        )
+                        .with_sizing_behavior(ListSizingBehavior::Infer)
+                        .max_h(rems(24.))
+                        .py_1()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use gpui::{TestAppContext, View, VisualContext, VisualTestContext};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    struct Command(&'static str);
+
+    impl PaletteItem for Command {
+        fn label(&self) -> SharedString {
+            self.0.into()
+        }
+    }
+
+    /// Matches the labels that start with the query.
+    fn prefix_filter(query: &str, commands: &[Command]) -> Vec<PaletteMatch> {
+        commands
+            .iter()
+            .enumerate()
+            .filter(|(_, command)| command.0.starts_with(query))
+            .map(|(item_ix, _)| PaletteMatch {
+                item_ix,
+                score: 0.,
+                positions: (0..query.len()).collect(),
+            })
+            .collect()
+    }
+
+    struct PaletteHost {
+        focus_handle: FocusHandle,
+        palette: Option<View<Palette<Command>>>,
+    }
+
+    impl Render for PaletteHost {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .track_focus(&self.focus_handle)
+                .size_full()
+                .children(self.palette.clone())
+        }
+    }
+
+    #[gpui::test]
+    fn test_palette_navigation_wraps(cx: &mut TestAppContext) {
+        let (_, palette, _, cx) = build_palette(cx);
+        palette.update(cx, |palette, cx| {
+            assert_eq!(palette.selected_label(), Some("copy"));
+            palette.select_prev(&SelectPrev, cx);
+            assert_eq!(palette.selected_label(), Some("paste"));
+            palette.select_next(&SelectNext, cx);
+            assert_eq!(palette.selected_label(), Some("copy"));
+            palette.select_next(&SelectNext, cx);
+            assert_eq!(palette.selected_label(), Some("cut"));
+
+            palette.set_query("p", cx);
+            assert_eq!(palette.selected_label(), Some("paste"));
+            palette.select_next(&SelectNext, cx);
+            assert_eq!(palette.selected_label(), Some("paste"));
+
+            palette.set_query("x", cx);
+            assert_eq!(palette.selected_label(), None);
+            palette.select_next(&SelectNext, cx);
+            palette.select_prev(&SelectPrev, cx);
+            assert_eq!(palette.selected_label(), None);
+        });
+    }
+
+    #[gpui::test]
+    fn test_palette_weight(cx: &mut TestAppContext) {
+        let (host, palette, events, cx) = build_palette(cx);
+        palette.update(cx, |palette, cx| {
+            palette.set_query("c", cx);
+            palette.select_next(&SelectNext, cx);
+            assert_eq!(palette.selected_label(), Some("cut"));
+            palette.confirm(&menu::Confirm, cx);
+        });
+
+        // Confirmed commands are boosted the next time the palette opens.
+        let palette = open_palette(&host, &events, cx);
+        palette.update(cx, |palette, cx| {
+            assert_eq!(palette.selected_label(), Some("cut"));
+            palette.set_query("p", cx);
+            assert_eq!(palette.selected_label(), Some("paste"));
+        });
+    }
+
+    #[gpui::test]
+    fn test_palette_confirm_preview_and_cancel(cx: &mut TestAppContext) {
+        let (host, palette, events, cx) = build_palette(cx);
+
+        cx.simulate_keystrokes("c u");
+        palette.update(cx, |palette, cx| {
+            assert_eq!(palette.query(), "cu");
+            assert_eq!(palette.selected_label(), Some("cut"));
+            palette.preview(&Preview, cx);
+        });
+        assert_eq!(events.borrow().as_slice(), &["preview cut"]);
+        cx.update(|cx| assert!(host.read(cx).palette.is_some()));
+
+        cx.simulate_keystrokes("backspace");
+        palette.update(cx, |palette, cx| {
+            assert_eq!(palette.query(), "c");
+            palette.confirm(&menu::Confirm, cx);
+        });
+        assert_eq!(
+            events.borrow().as_slice(),
+            &["preview cut", "confirm copy (host focused: true)"]
+        );
+        cx.update(|cx| {
+            let host = host.read(cx);
+            assert!(host.palette.is_none());
+            assert!(host.focus_handle.is_focused(cx));
+        });
+
+        // Cancelling closes the palette without confirming anything.
+        let palette = open_palette(&host, &events, cx);
+        palette.update(cx, |palette, cx| {
+            assert!(palette.focus_handle.is_focused(cx));
+            palette.cancel(&menu::Cancel, cx);
+        });
+        assert_eq!(events.borrow().len(), 2);
+        cx.update(|cx| {
+            let host = host.read(cx);
+            assert!(host.palette.is_none());
+            assert!(host.focus_handle.is_focused(cx));
+        });
+    }
+
+    impl Palette<Command> {
+        fn selected_label(&self) -> Option<&'static str> {
+            self.selected_item().map(|command| command.0)
+        }
+    }
+
+    fn open_palette(
+        host: &View<PaletteHost>,
+        events: &Rc<RefCell<Vec<String>>>,
+        cx: &mut VisualTestContext,
+    ) -> View<Palette<Command>> {
+        host.update(cx, |host, cx| {
+            let confirm_events = events.clone();
+            let preview_events = events.clone();
+            let weight_events = events.clone();
+            let host_focus_handle = host.focus_handle.clone();
+            let palette = cx.new_view(|cx| {
+                Palette::new(
+                    vec![Command("copy"), Command("cut"), Command("paste")],
+                    prefix_filter,
+                    cx,
+                )
+                .weight(move |command| {
+                    let confirmed = format!("confirm {} (host focused: true)", command.0);
+                    weight_events
+                        .borrow()
+                        .iter()
+                        .filter(|event| **event == confirmed)
+                        .count() as f64
+                })
+                .on_confirm(move |command, cx| {
+                    let host_focused = host_focus_handle.is_focused(cx);
+                    confirm_events.borrow_mut().push(format!(
+                        "confirm {} (host focused: {host_focused})",
+                        command.0
+                    ));
+                })
+                .on_preview(move |command, _| {
+                    preview_events
+                        .borrow_mut()
+                        .push(format!("preview {}", command.0));
+                })
+            });
+            cx.subscribe(&palette, |host, _, _: &DismissEvent, cx| {
+                host.palette = None;
+                cx.notify();
+            })
+            .detach();
+            host.palette = Some(palette.clone());
+            cx.notify();
+            palette
+        })
+    }
+
+    fn build_palette(
+        cx: &mut TestAppContext,
+    ) -> (
+        View<PaletteHost>,
+        View<Palette<Command>>,
+        Rc<RefCell<Vec<String>>>,
+        &mut VisualTestContext,
+    ) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+
+        let (host, cx) = cx.add_window_view(|cx| PaletteHost {
+            focus_handle: cx.focus_handle(),
+            palette: None,
+        });
+        cx.focus_view(&host);
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let palette = open_palette(&host, &events, cx);
+        cx.run_until_parked();
+        (host, palette, events, cx)
+    }
+}
@@ -0,0 +1,443 @@
+use std::rc::Rc;
+
+use gpui::{
+    deferred, AnyView, Axis, DragMoveEvent, Empty, EntityId, MouseButton, MouseDownEvent,
+    MouseUpEvent, Render, VisualContext,
+};
+
+use crate::prelude::*;
+
+const RESIZE_HANDLE_SIZE: Pixels = Pixels(6.);
+
+/// The edge of the window a [`Panel`] is docked to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelPosition {
+    Left,
+    Right,
+    Bottom,
+}
+
+impl PanelPosition {
+    /// The axis along which a panel at this position is resized.
+    pub fn axis(self) -> Axis {
+        match self {
+            PanelPosition::Left | PanelPosition::Right => Axis::Horizontal,
+            PanelPosition::Bottom => Axis::Vertical,
+        }
+    }
+}
+
+/// The size a [`Panel`] settled on once a resize finished.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PanelSize {
+    Open(Pixels),
+    /// The panel was dragged below its snap threshold and closed.
+    Closed,
+}
+
+#[derive(Clone)]
+struct DraggedPanel(EntityId);
+
+impl Render for DraggedPanel {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        Empty
+    }
+}
+
+/// A container docked to an edge of the window, which the user can resize by
+/// dragging its inner edge.
+///
+/// The panel is resized continuously while dragging, and closes when dragged
+/// below its snap threshold. Double-clicking the resize handle restores the
+/// default size. The final size is reported through [`Panel::on_resize`] so
+/// that it can be persisted.
+pub struct Panel {
+    position: PanelPosition,
+    size: Pixels,
+    default_size: Pixels,
+    min_size: Pixels,
+    max_size: Option<Pixels>,
+    snap_threshold: Pixels,
+    min_content_size: Pixels,
+    is_open: bool,
+    is_resizing: bool,
+    content: Option<AnyView>,
+    on_resize: Option<Rc<dyn Fn(PanelSize, &mut WindowContext)>>,
+}
+
+impl Panel {
+    pub fn new(position: PanelPosition, default_size: Pixels) -> Self {
+        Self {
+            position,
+            size: default_size,
+            default_size,
+            min_size: px(120.),
+            max_size: None,
+            snap_threshold: px(60.),
+            min_content_size: px(240.),
+            is_open: true,
+            is_resizing: false,
+            content: None,
+            on_resize: None,
+        }
+    }
+
+    /// Sets the size the panel is opened at, such as one persisted by an
+    /// earlier [`Panel::on_resize`] callback.
+    pub fn initial_size(mut self, size: Pixels) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn min_size(mut self, min_size: Pixels) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    pub fn max_size(mut self, max_size: Pixels) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Sets how far the panel has to be dragged before it snaps closed.
+    pub fn snap_threshold(mut self, snap_threshold: Pixels) -> Self {
+        self.snap_threshold = snap_threshold;
+        self
+    }
+
+    /// Sets how much of the window, along the panel's axis, the panel always
+    /// leaves to the rest of the content.
+    pub fn min_content_size(mut self, min_content_size: Pixels) -> Self {
+        self.min_content_size = min_content_size;
+        self
+    }
+
+    pub fn content(mut self, content: impl Into<AnyView>) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    /// Sets the handler invoked when the user finishes resizing the panel.
+    pub fn on_resize(mut self, handler: impl Fn(PanelSize, &mut WindowContext) + 'static) -> Self {
+        self.on_resize = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn position(&self) -> PanelPosition {
+        self.position
+    }
+
+    /// The size of the panel when it is open.
+    pub fn size(&self) -> Pixels {
+        self.size
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn is_resizing(&self) -> bool {
+        self.is_resizing
+    }
+
+    pub fn set_open(&mut self, is_open: bool, cx: &mut ViewContext<Self>) {
+        self.is_open = is_open;
+        cx.notify();
+    }
+
+    /// Restores the panel's default size, opening it if needed.
+    pub fn reset_size(&mut self, cx: &mut ViewContext<Self>) {
+        self.size = self.default_size;
+        self.is_open = true;
+        self.report_size(cx);
+        cx.notify();
+    }
+
+    fn clamp_size(&self, size: Pixels, cx: &WindowContext) -> Pixels {
+        let viewport_size = cx.viewport_size();
+        let window_size = match self.position.axis() {
+            Axis::Horizontal => viewport_size.width,
+            Axis::Vertical => viewport_size.height,
+        };
+        let available_size = (window_size - self.min_content_size).max(Pixels::ZERO);
+        let max_size = self
+            .max_size
+            .map_or(available_size, |max_size| max_size.min(available_size));
+        size.min(max_size).max(self.min_size.min(max_size)).round()
+    }
+
+    fn handle_drag_move(
+        &mut self,
+        event: &DragMoveEvent<DraggedPanel>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if event.drag(cx).0 != cx.entity_id() {
+            return;
+        }
+
+        let position = event.event.position;
+        let size = match self.position {
+            PanelPosition::Left => position.x - event.bounds.left(),
+            PanelPosition::Right => event.bounds.right() - position.x,
+            PanelPosition::Bottom => event.bounds.bottom() - position.y,
+        };
+
+        self.is_resizing = true;
+        if size < self.snap_threshold {
+            self.is_open = false;
+        } else {
+            self.is_open = true;
+            self.size = self.clamp_size(size, cx);
+        }
+        cx.notify();
+    }
+
+    fn finish_resize(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.is_resizing {
+            return;
+        }
+
+        self.is_resizing = false;
+        self.report_size(cx);
+        cx.notify();
+    }
+
+    fn report_size(&self, cx: &mut ViewContext<Self>) {
+        let Some(on_resize) = self.on_resize.clone() else {
+            return;
+        };
+        let size = if self.is_open {
+            PanelSize::Open(self.size)
+        } else {
+            PanelSize::Closed
+        };
+        WindowContext::defer(cx, move |cx| on_resize(size, cx));
+    }
+
+    fn render_resize_handle(&self, cx: &ViewContext<Self>) -> impl IntoElement {
+        let handle = div()
+            .id("panel-resize-handle")
+            .debug_selector(|| "panel-resize-handle".into())
+            .on_drag(DraggedPanel(cx.entity_id()), |panel, cx| {
+                cx.stop_propagation();
+                cx.new_view(|_| panel.clone())
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _: &MouseDownEvent, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, event: &MouseUpEvent, cx| {
+                    if event.click_count == 2 {
+                        this.reset_size(cx);
+                        cx.stop_propagation();
+                    }
+                }),
+            )
+            .occlude()
+            .absolute();
+
+        deferred(match self.position {
+            PanelPosition::Left => handle
+                .top_0()
+                .right(-RESIZE_HANDLE_SIZE / 2.)
+                .h_full()
+                .w(RESIZE_HANDLE_SIZE)
+                .cursor_col_resize(),
+            PanelPosition::Right => handle
+                .top_0()
+                .left(-RESIZE_HANDLE_SIZE / 2.)
+                .h_full()
+                .w(RESIZE_HANDLE_SIZE)
+                .cursor_col_resize(),
+            PanelPosition::Bottom => handle
+                .left_0()
+                .top(-RESIZE_HANDLE_SIZE / 2.)
+                .w_full()
+                .h(RESIZE_HANDLE_SIZE)
+                .cursor_row_resize(),
+        })
+    }
+}
+
+impl Render for Panel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let size = if self.is_open {
+            self.size
+        } else {
+            Pixels::ZERO
+        };
+        let colors = cx.theme().colors();
+
+        div()
+            .relative()
+            .flex()
+            .flex_none()
+            .map(|this| match self.position.axis() {
+                Axis::Horizontal => this.w(size).h_full(),
+                Axis::Vertical => this.h(size).w_full(),
+            })
+            .on_drag_move(cx.listener(Self::handle_drag_move))
+            // The drag can end anywhere in the window, so listen for mouse ups both inside
+            // and outside of the panel.
+            .capture_any_mouse_up(cx.listener(|this, _, cx| this.finish_resize(cx)))
+            .on_mouse_up_out(
+                MouseButton::Left,
+                cx.listener(|this, _, cx| this.finish_resize(cx)),
+            )
+            .when(self.is_open, |this| {
+                this.bg(colors.panel_background)
+                    .border_color(colors.border)
+                    .map(|this| match self.position {
+                        PanelPosition::Left => this.border_r_1(),
+                        PanelPosition::Right => this.border_l_1(),
+                        PanelPosition::Bottom => this.border_t_1(),
+                    })
+                    .child(
+                        div()
+                            .size_full()
+                            .overflow_hidden()
+                            .children(self.content.clone()),
+                    )
+            })
+            .when(self.is_open || self.is_resizing, |this| {
+                this.child(self.render_resize_handle(cx))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use gpui::{point, Modifiers, Point, TestAppContext, View, VisualTestContext};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_panel_drag_resize(cx: &mut TestAppContext) {
+        let resizes = Rc::new(RefCell::new(Vec::new()));
+        let (panel, cx) = init_test(cx, {
+            let resizes = resizes.clone();
+            move || {
+                Panel::new(PanelPosition::Left, px(240.))
+                    .min_size(px(120.))
+                    .snap_threshold(px(60.))
+                    .min_content_size(px(240.))
+                    .on_resize(move |size, _| resizes.borrow_mut().push(size))
+            }
+        });
+
+        // The panel follows the mouse while dragging, and only reports its size once the
+        // drag ends.
+        let handle = cx.debug_bounds("panel-resize-handle").unwrap().center();
+        start_drag(cx, handle);
+        cx.simulate_mouse_move(
+            point(px(320.), handle.y),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        panel.update(cx, |panel, _| {
+            assert!(panel.is_resizing());
+            assert_eq!(panel.size(), px(320.));
+        });
+        cx.simulate_mouse_move(
+            point(px(300.), handle.y),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        panel.update(cx, |panel, _| assert_eq!(panel.size(), px(300.)));
+        assert!(resizes.borrow().is_empty());
+
+        cx.simulate_mouse_up(
+            point(px(300.), handle.y),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        cx.run_until_parked();
+        panel.update(cx, |panel, _| assert!(!panel.is_resizing()));
+        assert_eq!(resizes.borrow().as_slice(), &[PanelSize::Open(px(300.))]);
+
+        // The panel can't be made smaller than its minimum size, or leave less than the
+        // minimum content size to the rest of the window.
+        let viewport_width = cx.update(|cx| cx.viewport_size().width);
+        drag_handle(cx, point(px(90.), handle.y));
+        drag_handle(cx, point(viewport_width, handle.y));
+        assert_eq!(
+            resizes.borrow()[1..],
+            [
+                PanelSize::Open(px(120.)),
+                PanelSize::Open(viewport_width - px(240.))
+            ]
+        );
+
+        // Dragging below the snap threshold closes the panel, but remembers its size.
+        drag_handle(cx, point(px(40.), handle.y));
+        panel.update(cx, |panel, _| {
+            assert!(!panel.is_open());
+            assert_eq!(panel.size(), viewport_width - px(240.));
+        });
+        assert_eq!(resizes.borrow().last(), Some(&PanelSize::Closed));
+        assert!(cx.debug_bounds("panel-resize-handle").is_none());
+
+        // Double-clicking the handle restores the default size.
+        panel.update(cx, |panel, cx| panel.set_open(true, cx));
+        cx.run_until_parked();
+        let handle = cx.debug_bounds("panel-resize-handle").unwrap().center();
+        for click_count in 1..=2 {
+            cx.simulate_event(MouseDownEvent {
+                position: handle,
+                modifiers: Modifiers::none(),
+                button: MouseButton::Left,
+                click_count,
+                first_mouse: false,
+            });
+            cx.simulate_event(MouseUpEvent {
+                position: handle,
+                modifiers: Modifiers::none(),
+                button: MouseButton::Left,
+                click_count,
+            });
+        }
+        cx.run_until_parked();
+        panel.update(cx, |panel, _| {
+            assert!(panel.is_open());
+            assert_eq!(panel.size(), px(240.));
+        });
+        assert_eq!(resizes.borrow().last(), Some(&PanelSize::Open(px(240.))));
+    }
+
+    fn start_drag(cx: &mut VisualTestContext, position: Point<Pixels>) {
+        cx.simulate_mouse_down(position, MouseButton::Left, Modifiers::none());
+        // Move past the drag threshold so the drag begins.
+        cx.simulate_mouse_move(
+            position + point(px(10.), px(0.)),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+    }
+
+    fn drag_handle(cx: &mut VisualTestContext, target: Point<Pixels>) {
+        let handle = cx.debug_bounds("panel-resize-handle").unwrap().center();
+        start_drag(cx, handle);
+        cx.simulate_mouse_move(target, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(target, MouseButton::Left, Modifiers::none());
+        cx.run_until_parked();
+    }
+
+    fn init_test(
+        cx: &mut TestAppContext,
+        build_panel: impl FnOnce() -> Panel,
+    ) -> (View<Panel>, &mut VisualTestContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+        cx.add_window_view(|_| build_panel())
+    }
+}
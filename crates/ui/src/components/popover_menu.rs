@@ -73,6 +73,7 @@ pub struct PopoverMenu<M: ManagedView> {
     anchor: AnchorCorner,
     attach: Option<AnchorCorner>,
     offset: Option<Point<Pixels>>,
+    flip_to_fit: bool,
     trigger_handle: Option<PopoverMenuHandle<M>>,
 }
 
@@ -86,6 +87,7 @@ impl<M: ManagedView> PopoverMenu<M> {
             anchor: AnchorCorner::TopLeft,
             attach: None,
             offset: None,
+            flip_to_fit: false,
             trigger_handle: None,
         }
     }
@@ -131,6 +133,13 @@ impl<M: ManagedView> PopoverMenu<M> {
         self
     }
 
+    /// flip_to_fit opens the menu above the handle instead of below it (or vice versa) when the
+    /// handle is in the bottom half of the window, such as in the status bar.
+    pub fn flip_to_fit(mut self) -> Self {
+        self.flip_to_fit = true;
+        self
+    }
+
     fn resolved_attach(&self) -> AnchorCorner {
         self.attach.unwrap_or_else(|| match self.anchor {
             AnchorCorner::TopLeft => AnchorCorner::BottomLeft,
@@ -140,6 +149,25 @@ impl<M: ManagedView> PopoverMenu<M> {
         })
     }
 
+    fn resolved_anchor_and_attach(
+        &self,
+        child_bounds: Option<Bounds<Pixels>>,
+        cx: &WindowContext,
+    ) -> (AnchorCorner, AnchorCorner) {
+        let anchor = self.anchor;
+        let attach = self.resolved_attach();
+        let opens_downward = matches!(anchor, AnchorCorner::TopLeft | AnchorCorner::TopRight);
+        let in_bottom_half = child_bounds.map_or(false, |bounds| {
+            bounds.center().y > cx.viewport_size().height * 0.5
+        });
+
+        if self.flip_to_fit && opens_downward && in_bottom_half {
+            (flip_vertically(anchor), flip_vertically(attach))
+        } else {
+            (anchor, attach)
+        }
+    }
+
     fn resolved_offset(&self, cx: &WindowContext) -> Point<Pixels> {
         self.offset.unwrap_or_else(|| {
             // Default offset = 4px padding + 1px border
@@ -152,6 +180,15 @@ impl<M: ManagedView> PopoverMenu<M> {
     }
 }
 
+fn flip_vertically(corner: AnchorCorner) -> AnchorCorner {
+    match corner {
+        AnchorCorner::TopLeft => AnchorCorner::BottomLeft,
+        AnchorCorner::TopRight => AnchorCorner::BottomRight,
+        AnchorCorner::BottomLeft => AnchorCorner::TopLeft,
+        AnchorCorner::BottomRight => AnchorCorner::TopRight,
+    }
+}
+
 fn show_menu<M: ManagedView>(
     builder: &Rc<dyn Fn(&mut WindowContext) -> Option<View<M>>>,
     menu: &Rc<RefCell<Option<View<M>>>>,
@@ -227,11 +264,12 @@ impl<M: ManagedView> Element for PopoverMenu<M> {
                 let mut menu_layout_id = None;
 
                 let menu_element = element_state.menu.borrow_mut().as_mut().map(|menu| {
-                    let mut anchored = anchored().snap_to_window().anchor(self.anchor);
+                    let (anchor, attach) =
+                        self.resolved_anchor_and_attach(element_state.child_bounds, cx);
+                    let mut anchored = anchored().snap_to_window().anchor(anchor);
                     if let Some(child_bounds) = element_state.child_bounds {
-                        anchored = anchored.position(
-                            self.resolved_attach().corner(child_bounds) + self.resolved_offset(cx),
-                        );
+                        anchored = anchored
+                            .position(attach.corner(child_bounds) + self.resolved_offset(cx));
                     }
                     let mut element = deferred(anchored.child(div().occlude().child(menu.clone())))
                         .with_priority(1)
@@ -0,0 +1,663 @@
+use std::{
+    collections::HashMap,
+    rc::Rc,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    uniform_list, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    KeyDownEvent, ListSizingBehavior, Render, Subscription, Task, UniformListScrollHandle,
+    VisualContext,
+};
+use menu::{SelectFirst, SelectLast, SelectNext, SelectPrev};
+
+use crate::{prelude::*, HighlightedLabel, ListItem, PopoverMenu};
+
+/// An option that can be chosen in a [`Select`].
+#[derive(Debug, Clone)]
+pub struct SelectOption {
+    id: SharedString,
+    label: SharedString,
+    disabled: bool,
+}
+
+impl SelectOption {
+    pub fn new(id: impl Into<SharedString>, label: impl Into<SharedString>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            disabled: false,
+        }
+    }
+
+    /// Sets whether the option is shown but can't be chosen.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SelectEntry {
+    Header(SharedString),
+    Option(SelectOption),
+}
+
+/// A button showing the currently selected option which, when clicked, opens
+/// a searchable list of options.
+///
+/// Options are filtered as the user types, and can be navigated with the
+/// keyboard. Only the visible options are rendered, so a [`Select`] can hold
+/// thousands of them. When opened from the bottom half of the window, such as
+/// from the status bar, the list opens above the button.
+#[derive(IntoElement)]
+pub struct Select {
+    id: ElementId,
+    entries: Vec<SelectEntry>,
+    selected_id: Option<SharedString>,
+    placeholder: SharedString,
+    on_select: Option<Rc<dyn Fn(&SharedString, &mut WindowContext)>>,
+}
+
+impl Select {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            entries: Vec::new(),
+            selected_id: None,
+            placeholder: "Select…".into(),
+            on_select: None,
+        }
+    }
+
+    pub fn option(mut self, option: SelectOption) -> Self {
+        self.entries.push(SelectEntry::Option(option));
+        self
+    }
+
+    pub fn options(mut self, options: impl IntoIterator<Item = SelectOption>) -> Self {
+        self.entries
+            .extend(options.into_iter().map(SelectEntry::Option));
+        self
+    }
+
+    /// Adds a group of options displayed under the given header.
+    pub fn group(
+        mut self,
+        header: impl Into<SharedString>,
+        options: impl IntoIterator<Item = SelectOption>,
+    ) -> Self {
+        self.entries.push(SelectEntry::Header(header.into()));
+        self.options(options)
+    }
+
+    /// Sets the id of the selected option, which is shown in the button and
+    /// marked with a checkmark in the list.
+    pub fn selected(mut self, selected_id: Option<SharedString>) -> Self {
+        self.selected_id = selected_id;
+        self
+    }
+
+    /// Sets the text shown in the button when no option is selected.
+    pub fn placeholder(mut self, placeholder: impl Into<SharedString>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Sets the handler invoked with the id of the option the user chooses.
+    pub fn on_select(
+        mut self,
+        handler: impl Fn(&SharedString, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for Select {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        let selected_label = self.selected_id.as_ref().and_then(|selected_id| {
+            self.entries.iter().find_map(|entry| match entry {
+                SelectEntry::Option(option) if &option.id == selected_id => {
+                    Some(option.label.clone())
+                }
+                _ => None,
+            })
+        });
+        let label_color = if selected_label.is_some() {
+            Color::Default
+        } else {
+            Color::Placeholder
+        };
+
+        let entries: Rc<[SelectEntry]> = self.entries.into();
+        let selected_id = self.selected_id;
+        let on_select = self.on_select;
+
+        PopoverMenu::new(self.id)
+            .flip_to_fit()
+            .trigger(
+                Button::new("select-trigger", selected_label.unwrap_or(self.placeholder))
+                    .label_size(LabelSize::Small)
+                    .color(label_color)
+                    .icon(IconName::ChevronDown)
+                    .icon_size(IconSize::XSmall)
+                    .icon_color(Color::Muted)
+                    .icon_position(IconPosition::End),
+            )
+            .menu(move |cx| {
+                let entries = entries.clone();
+                let selected_id = selected_id.clone();
+                let on_select = on_select.clone();
+                Some(cx.new_view(|cx| SelectMenu::new(entries, selected_id, on_select, cx)))
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SelectRow {
+    Header(usize),
+    Option {
+        entry_ix: usize,
+        positions: Vec<usize>,
+    },
+}
+
+/// The searchable list of options opened by a [`Select`].
+pub struct SelectMenu {
+    entries: Rc<[SelectEntry]>,
+    candidates: Arc<Vec<StringMatchCandidate>>,
+    selected_id: Option<SharedString>,
+    on_select: Option<Rc<dyn Fn(&SharedString, &mut WindowContext)>>,
+    query: String,
+    rows: Vec<SelectRow>,
+    highlighted_row: Option<usize>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    pending_filter: Task<()>,
+    _on_blur_subscription: Subscription,
+}
+
+impl FocusableView for SelectMenu {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DismissEvent> for SelectMenu {}
+
+impl SelectMenu {
+    fn new(
+        entries: Rc<[SelectEntry]>,
+        selected_id: Option<SharedString>,
+        on_select: Option<Rc<dyn Fn(&SharedString, &mut WindowContext)>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        let _on_blur_subscription = cx.on_blur(&focus_handle, |this: &mut SelectMenu, cx| {
+            this.cancel(&menu::Cancel, cx)
+        });
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, entry)| match entry {
+                SelectEntry::Option(option) => {
+                    Some(StringMatchCandidate::new(ix, option.label.to_string()))
+                }
+                SelectEntry::Header(_) => None,
+            })
+            .collect();
+
+        let mut this = Self {
+            entries,
+            candidates: Arc::new(candidates),
+            selected_id,
+            on_select,
+            query: String::new(),
+            rows: Vec::new(),
+            highlighted_row: None,
+            focus_handle,
+            scroll_handle: UniformListScrollHandle::new(),
+            pending_filter: Task::ready(()),
+            _on_blur_subscription,
+        };
+        this.set_matches(None, cx);
+        this.highlight_selected_option(cx);
+        this
+    }
+
+    fn option_at_row(&self, row_ix: usize) -> Option<&SelectOption> {
+        match self.rows.get(row_ix)? {
+            SelectRow::Option { entry_ix, .. } => match &self.entries[*entry_ix] {
+                SelectEntry::Option(option) => Some(option),
+                SelectEntry::Header(_) => None,
+            },
+            SelectRow::Header(_) => None,
+        }
+    }
+
+    fn is_row_selectable(&self, row_ix: usize) -> bool {
+        self.option_at_row(row_ix)
+            .map_or(false, |option| !option.disabled)
+    }
+
+    fn set_query(&mut self, query: String, cx: &mut ViewContext<Self>) {
+        self.query = query;
+        if self.query.is_empty() {
+            self.pending_filter = Task::ready(());
+            self.set_matches(None, cx);
+            return;
+        }
+
+        let candidates = self.candidates.clone();
+        let query = self.query.clone();
+        let executor = cx.background_executor().clone();
+        self.pending_filter = cx.spawn(|this, mut cx| async move {
+            let cancel_flag = AtomicBool::new(false);
+            let matches = fuzzy::match_strings(
+                &candidates,
+                &query,
+                false,
+                candidates.len(),
+                &cancel_flag,
+                executor,
+            )
+            .await;
+            this.update(&mut cx, |this, cx| this.set_matches(Some(matches), cx))
+                .ok();
+        });
+    }
+
+    /// Rebuilds the visible rows from the given matches, or from every option
+    /// when there is no query. Groups keep their original order, while options
+    /// within a group are sorted by how well they match.
+    fn set_matches(&mut self, matches: Option<Vec<StringMatch>>, cx: &mut ViewContext<Self>) {
+        let mut matches_by_entry = matches.map(|matches| {
+            matches
+                .into_iter()
+                .map(|string_match| (string_match.candidate_id, string_match))
+                .collect::<HashMap<_, _>>()
+        });
+
+        let mut groups: Vec<(Option<usize>, Vec<(f64, SelectRow)>)> = vec![(None, Vec::new())];
+        for (entry_ix, entry) in self.entries.iter().enumerate() {
+            match entry {
+                SelectEntry::Header(_) => groups.push((Some(entry_ix), Vec::new())),
+                SelectEntry::Option(_) => {
+                    let (score, positions) = match matches_by_entry.as_mut() {
+                        Some(matches) => match matches.remove(&entry_ix) {
+                            Some(string_match) => (string_match.score, string_match.positions),
+                            None => continue,
+                        },
+                        None => (0., Vec::new()),
+                    };
+                    if let Some((_, rows)) = groups.last_mut() {
+                        rows.push((
+                            score,
+                            SelectRow::Option {
+                                entry_ix,
+                                positions,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+
+        self.rows.clear();
+        for (header_ix, mut rows) in groups {
+            if rows.is_empty() {
+                continue;
+            }
+            rows.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+            self.rows.extend(header_ix.map(SelectRow::Header));
+            self.rows.extend(rows.into_iter().map(|(_, row)| row));
+        }
+
+        self.highlighted_row = (0..self.rows.len()).find(|ix| self.is_row_selectable(*ix));
+        self.scroll_to_highlighted_row();
+        cx.notify();
+    }
+
+    fn highlight_selected_option(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(selected_id) = self.selected_id.as_ref() else {
+            return;
+        };
+        if let Some(row_ix) = (0..self.rows.len()).find(|ix| {
+            self.option_at_row(*ix)
+                .map_or(false, |option| &option.id == selected_id)
+        }) {
+            self.highlighted_row = Some(row_ix);
+            self.scroll_to_highlighted_row();
+            cx.notify();
+        }
+    }
+
+    fn scroll_to_highlighted_row(&mut self) {
+        if let Some(row_ix) = self.highlighted_row {
+            self.scroll_handle.scroll_to_item(row_ix);
+        }
+    }
+
+    fn highlight_row(&mut self, row_ix: Option<usize>, cx: &mut ViewContext<Self>) {
+        if row_ix.is_some() {
+            self.highlighted_row = row_ix;
+            self.scroll_to_highlighted_row();
+            cx.notify();
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        let row_ix = (0..self.rows.len()).find(|ix| self.is_row_selectable(*ix));
+        self.highlight_row(row_ix, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        let row_ix = (0..self.rows.len())
+            .rev()
+            .find(|ix| self.is_row_selectable(*ix));
+        self.highlight_row(row_ix, cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let start = self.highlighted_row.map_or(0, |ix| ix + 1);
+        let row_ix = (start..self.rows.len()).find(|ix| self.is_row_selectable(*ix));
+        self.highlight_row(row_ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        let end = self.highlighted_row.unwrap_or(self.rows.len());
+        let row_ix = (0..end).rev().find(|ix| self.is_row_selectable(*ix));
+        self.highlight_row(row_ix, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(row_ix) = self.highlighted_row {
+            self.confirm_row(row_ix, cx);
+        }
+    }
+
+    fn confirm_row(&mut self, row_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(option) = self.option_at_row(row_ix).cloned() else {
+            return;
+        };
+        if option.disabled {
+            return;
+        }
+
+        if let Some(on_select) = self.on_select.as_ref() {
+            on_select(&option.id, cx);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn handle_key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.control || keystroke.modifiers.platform {
+            return;
+        }
+
+        let mut query = self.query.clone();
+        if keystroke.key == "backspace" {
+            if query.pop().is_none() {
+                return;
+            }
+        } else if let Some(text) = keystroke.ime_key.as_ref() {
+            query.push_str(text);
+        } else if keystroke.key == "space" {
+            query.push(' ');
+        } else if keystroke.key.chars().count() == 1 {
+            if keystroke.modifiers.shift {
+                query.push_str(&keystroke.key.to_uppercase());
+            } else {
+                query.push_str(&keystroke.key);
+            }
+        } else {
+            return;
+        }
+
+        cx.stop_propagation();
+        self.set_query(query, cx);
+    }
+
+    fn render_row(&self, row_ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        match &self.rows[row_ix] {
+            SelectRow::Header(entry_ix) => {
+                let SelectEntry::Header(header) = &self.entries[*entry_ix] else {
+                    return div().into_any_element();
+                };
+                ListItem::new(row_ix)
+                    .inset(true)
+                    .selectable(false)
+                    .child(Label::new(header.clone()).color(Color::Muted))
+                    .into_any_element()
+            }
+            SelectRow::Option {
+                entry_ix,
+                positions,
+            } => {
+                let SelectEntry::Option(option) = &self.entries[*entry_ix] else {
+                    return div().into_any_element();
+                };
+                let is_selected = self.selected_id.as_ref() == Some(&option.id);
+
+                ListItem::new(row_ix)
+                    .inset(true)
+                    .disabled(option.disabled)
+                    .selected(self.highlighted_row == Some(row_ix))
+                    .start_slot(if is_selected {
+                        v_flex()
+                            .flex_none()
+                            .child(Icon::new(IconName::Check).color(Color::Accent))
+                    } else {
+                        v_flex().flex_none().size(IconSize::default().rems())
+                    })
+                    .child(
+                        HighlightedLabel::new(option.label.clone(), positions.clone()).color(
+                            if option.disabled {
+                                Color::Disabled
+                            } else {
+                                Color::Default
+                            },
+                        ),
+                    )
+                    .on_click(cx.listener(move |this, _, cx| this.confirm_row(row_ix, cx)))
+                    .into_any_element()
+            }
+        }
+    }
+}
+
+impl Render for SelectMenu {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let query_label = if self.query.is_empty() {
+            Label::new("Filter…").color(Color::Placeholder)
+        } else {
+            Label::new(self.query.clone())
+        };
+
+        v_flex()
+            .key_context("menu")
+            .track_focus(&self.focus_handle)
+            .on_mouse_down_out(cx.listener(|this, _, cx| this.cancel(&menu::Cancel, cx)))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .occlude()
+            .elevation_2(cx)
+            .w(rems(16.))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Icon::new(IconName::MagnifyingGlass)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(query_label.size(LabelSize::Small)),
+            )
+            .map(|this| {
+                if self.rows.is_empty() {
+                    this.child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .child(Label::new("No matches").color(Color::Muted)),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "select-options",
+                            self.rows.len(),
+                            |this, range, cx| range.map(|ix| this.render_row(ix, cx)).collect(),
+                        )
+                        .with_sizing_behavior(ListSizingBehavior::Infer)
+                        .max_h(rems(18.))
+                        .py_1()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use gpui::{TestAppContext, View, VisualTestContext};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_select_menu_keyboard_navigation(cx: &mut TestAppContext) {
+        let (menu, selections, cx) = build_menu(Some("rust".into()), cx);
+
+        menu.update(cx, |menu, cx| {
+            assert_eq!(menu.highlighted_option_id(), Some("rust".into()));
+
+            // Disabled options and headers are skipped.
+            menu.select_next(&SelectNext, cx);
+            assert_eq!(menu.highlighted_option_id(), Some("markdown".into()));
+            menu.select_prev(&SelectPrev, cx);
+            assert_eq!(menu.highlighted_option_id(), Some("rust".into()));
+            menu.select_last(&SelectLast, cx);
+            assert_eq!(menu.highlighted_option_id(), Some("ruby".into()));
+
+            menu.confirm(&menu::Confirm, cx);
+        });
+        assert_eq!(selections.borrow().as_slice(), &["ruby"]);
+    }
+
+    #[gpui::test]
+    fn test_select_menu_filtering(cx: &mut TestAppContext) {
+        let (menu, selections, cx) = build_menu(None, cx);
+        menu.update(cx, |menu, cx| cx.focus(&menu.focus_handle));
+
+        cx.simulate_keystrokes("r u");
+        cx.run_until_parked();
+        menu.update(cx, |menu, _| {
+            assert_eq!(menu.query, "ru");
+            assert_eq!(
+                menu.visible_rows(),
+                vec!["# Recent", "rust", "# All", "ruby"]
+            );
+            assert_eq!(menu.highlighted_option_id(), Some("rust".into()));
+        });
+
+        // Disabled options are still listed, but can't be chosen.
+        cx.simulate_keystrokes("backspace backspace t o m");
+        cx.run_until_parked();
+        menu.update(cx, |menu, cx| {
+            assert_eq!(menu.visible_rows(), vec!["# Recent", "toml"]);
+            assert_eq!(menu.highlighted_option_id(), None);
+            menu.confirm_row(1, cx);
+        });
+        assert!(selections.borrow().is_empty());
+
+        cx.simulate_keystrokes("backspace backspace backspace");
+        cx.run_until_parked();
+        menu.update(cx, |menu, _| {
+            assert_eq!(menu.visible_rows().len(), 7);
+        });
+    }
+
+    impl SelectMenu {
+        fn highlighted_option_id(&self) -> Option<SharedString> {
+            self.option_at_row(self.highlighted_row?)
+                .map(|option| option.id.clone())
+        }
+
+        fn visible_rows(&self) -> Vec<String> {
+            self.rows
+                .iter()
+                .map(|row| match row {
+                    SelectRow::Header(entry_ix) | SelectRow::Option { entry_ix, .. } => {
+                        match &self.entries[*entry_ix] {
+                            SelectEntry::Header(header) => format!("# {header}"),
+                            SelectEntry::Option(option) => option.id.to_string(),
+                        }
+                    }
+                })
+                .collect()
+        }
+    }
+
+    fn build_menu(
+        selected_id: Option<SharedString>,
+        cx: &mut TestAppContext,
+    ) -> (
+        View<SelectMenu>,
+        Rc<RefCell<Vec<SharedString>>>,
+        &mut VisualTestContext,
+    ) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+
+        let entries: Rc<[SelectEntry]> = Select::new("select")
+            .group(
+                "Recent",
+                [
+                    SelectOption::new("rust", "Rust"),
+                    SelectOption::new("toml", "TOML").disabled(true),
+                ],
+            )
+            .group(
+                "All",
+                [
+                    SelectOption::new("markdown", "Markdown"),
+                    SelectOption::new("python", "Python"),
+                    SelectOption::new("ruby", "Ruby"),
+                ],
+            )
+            .entries
+            .into();
+        let selections = Rc::new(RefCell::new(Vec::new()));
+        let on_select: Rc<dyn Fn(&SharedString, &mut WindowContext)> = {
+            let selections = selections.clone();
+            Rc::new(move |id, _| selections.borrow_mut().push(id.clone()))
+        };
+
+        let (menu, cx) =
+            cx.add_window_view(|cx| SelectMenu::new(entries, selected_id, Some(on_select), cx));
+        (menu, selections, cx)
+    }
+}
@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use gpui::{relative, Animation, AnimationExt, DefiniteLength, Hsla};
+
+use crate::prelude::*;
+
+/// How long the shimmer takes to sweep across a [`Skeleton`] once.
+const SHIMMER_DURATION: Duration = Duration::from_millis(1500);
+
+/// The number of strips the shimmer is drawn with, getting brighter towards its middle.
+const SHIMMER_STRIPS: usize = 8;
+
+/// The shape of a [`Skeleton`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkeletonShape {
+    /// A bar the height of a line of text.
+    Line,
+    /// A rectangle standing in for a larger piece of content, like an image.
+    Block,
+    /// A circle standing in for an avatar or an icon.
+    Circle,
+}
+
+/// A placeholder for content that is still loading, with a shimmer sweeping across it.
+///
+/// The shimmer is paused while the window is inactive, so that a panel left loading in the
+/// background doesn't keep drawing frames.
+///
+/// # Examples
+///
+/// ```
+/// use ui::{prelude::*, Skeleton};
+///
+/// h_flex()
+///     .gap_2()
+///     .child(Skeleton::circle("avatar"))
+///     .child(Skeleton::line("name").width(relative(0.6)));
+/// ```
+#[derive(IntoElement)]
+pub struct Skeleton {
+    id: ElementId,
+    shape: SkeletonShape,
+    width: Option<DefiniteLength>,
+    height: Option<DefiniteLength>,
+}
+
+impl Skeleton {
+    pub fn new(id: impl Into<ElementId>, shape: SkeletonShape) -> Self {
+        Self {
+            id: id.into(),
+            shape,
+            width: None,
+            height: None,
+        }
+    }
+
+    /// Creates a placeholder for a line of text, filling the width of its parent.
+    pub fn line(id: impl Into<ElementId>) -> Self {
+        Self::new(id, SkeletonShape::Line)
+    }
+
+    /// Creates a placeholder for a larger piece of content, filling the width of its parent.
+    pub fn block(id: impl Into<ElementId>) -> Self {
+        Self::new(id, SkeletonShape::Block)
+    }
+
+    /// Creates a circular placeholder the size of an icon.
+    pub fn circle(id: impl Into<ElementId>) -> Self {
+        Self::new(id, SkeletonShape::Circle)
+    }
+
+    pub fn width(mut self, width: impl Into<DefiniteLength>) -> Self {
+        self.width = Some(width.into());
+        self
+    }
+
+    pub fn height(mut self, height: impl Into<DefiniteLength>) -> Self {
+        self.height = Some(height.into());
+        self
+    }
+}
+
+/// Returns the opacity of each strip of the shimmer, fading in and back out.
+fn shimmer_opacities() -> impl Iterator<Item = f32> {
+    (0..SHIMMER_STRIPS).map(|ix| {
+        let position = (ix as f32 + 0.5) / SHIMMER_STRIPS as f32;
+        (position * std::f32::consts::PI).sin()
+    })
+}
+
+fn render_shimmer(color: Hsla) -> impl IntoElement {
+    div()
+        .absolute()
+        .top_0()
+        .bottom_0()
+        .w(relative(0.5))
+        .flex()
+        .children(shimmer_opacities().map(|opacity| {
+            div().flex_1().h_full().bg(Hsla {
+                a: color.a * opacity,
+                ..color
+            })
+        }))
+        .with_animation(
+            "shimmer",
+            Animation::new(SHIMMER_DURATION).repeat(),
+            // Sweep from just outside the left edge to just outside the right edge.
+            |shimmer, delta| shimmer.left(relative(delta * 1.5 - 0.5)),
+        )
+}
+
+impl RenderOnce for Skeleton {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let (default_width, default_height) = match self.shape {
+            SkeletonShape::Line => (relative(1.), rems_from_px(10.).into()),
+            SkeletonShape::Block => (relative(1.), rems_from_px(64.).into()),
+            SkeletonShape::Circle => (rems_from_px(24.).into(), rems_from_px(24.).into()),
+        };
+
+        div()
+            .id(self.id)
+            .relative()
+            .flex_none()
+            .overflow_hidden()
+            .w(self.width.unwrap_or(default_width))
+            .h(self.height.unwrap_or(default_height))
+            .bg(colors.element_background)
+            .map(|this| match self.shape {
+                SkeletonShape::Line => this.rounded_sm(),
+                SkeletonShape::Block => this.rounded_md(),
+                SkeletonShape::Circle => this.rounded_full(),
+            })
+            .when(cx.is_window_active(), |this| {
+                this.child(render_shimmer(colors.element_hover))
+            })
+    }
+}
+
+/// A column of [`Skeleton`] rows, each an icon and a line of varying width, standing in for
+/// a list that is still loading.
+#[derive(IntoElement)]
+pub struct SkeletonList {
+    id: ElementId,
+    rows: usize,
+}
+
+impl SkeletonList {
+    pub fn new(id: impl Into<ElementId>, rows: usize) -> Self {
+        Self {
+            id: id.into(),
+            rows,
+        }
+    }
+}
+
+impl RenderOnce for SkeletonList {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        // Vary the widths of the lines, so the rows look like text rather than a grid.
+        const WIDTHS: [f32; 5] = [0.7, 0.45, 0.85, 0.6, 0.5];
+
+        v_flex()
+            .id(self.id)
+            .gap_2()
+            .children((0..self.rows).map(|ix| {
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Skeleton::circle(("skeleton-icon", ix))
+                            .width(IconSize::Medium.rems())
+                            .height(IconSize::Medium.rems()),
+                    )
+                    .child(
+                        div().flex_1().child(
+                            Skeleton::line(("skeleton-line", ix))
+                                .width(relative(WIDTHS[ix % WIDTHS.len()])),
+                        ),
+                    )
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shimmer_opacities() {
+        let opacities = shimmer_opacities().collect::<Vec<_>>();
+        assert_eq!(opacities.len(), SHIMMER_STRIPS);
+        assert!(opacities.iter().all(|opacity| (0. ..=1.).contains(opacity)));
+        // The shimmer is brightest in the middle and symmetric.
+        for (left, right) in opacities.iter().zip(opacities.iter().rev()) {
+            assert!((left - right).abs() < 1e-6);
+        }
+        assert!(opacities[0] < opacities[SHIMMER_STRIPS / 2]);
+    }
+}
@@ -0,0 +1,11 @@
+mod buffer_indicators;
+mod mode_indicator;
+mod presence_facepile;
+mod task_progress;
+mod update_indicator;
+
+pub use buffer_indicators::*;
+pub use mode_indicator::*;
+pub use presence_facepile::*;
+pub use task_progress::*;
+pub use update_indicator::*;
@@ -0,0 +1,256 @@
+use std::rc::Rc;
+
+use gpui::AnchorCorner;
+
+use crate::{prelude::*, ContextMenu, PopoverMenu, Tooltip};
+
+/// How the lines of a buffer are indented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indentation {
+    Spaces(u32),
+    Tabs,
+}
+
+impl Indentation {
+    pub fn label(&self) -> SharedString {
+        match self {
+            Indentation::Spaces(width) => format!("Spaces: {width}").into(),
+            Indentation::Tabs => "Tabs".into(),
+        }
+    }
+}
+
+/// The line ending used when a buffer is saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEndingStyle {
+    Lf,
+    Crlf,
+}
+
+impl LineEndingStyle {
+    pub fn label(&self) -> SharedString {
+        match self {
+            LineEndingStyle::Lf => "LF".into(),
+            LineEndingStyle::Crlf => "CRLF".into(),
+        }
+    }
+}
+
+/// A status bar item showing the indentation of the active buffer.
+///
+/// Clicking it opens a popover listing the alternatives. Nothing is rendered
+/// until an indentation is provided, i.e. while no editor is active.
+#[derive(IntoElement)]
+pub struct IndentationIndicator {
+    indentation: Option<Indentation>,
+    options: Vec<Indentation>,
+    visible: bool,
+    on_change: Option<Rc<dyn Fn(&Indentation, &mut WindowContext)>>,
+}
+
+impl IndentationIndicator {
+    pub fn new() -> Self {
+        Self {
+            indentation: None,
+            options: vec![
+                Indentation::Spaces(2),
+                Indentation::Spaces(4),
+                Indentation::Spaces(8),
+                Indentation::Tabs,
+            ],
+            visible: true,
+            on_change: None,
+        }
+    }
+
+    pub fn indentation(mut self, indentation: Option<Indentation>) -> Self {
+        self.indentation = indentation;
+        self
+    }
+
+    pub fn options(mut self, options: impl IntoIterator<Item = Indentation>) -> Self {
+        self.options = options.into_iter().collect();
+        self
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn on_change(
+        mut self,
+        handler: impl Fn(&Indentation, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_change = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for IndentationIndicator {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        render_indicator(
+            "indentation-indicator",
+            "Select Indentation",
+            self.indentation.filter(|_| self.visible),
+            self.options,
+            Indentation::label,
+            self.on_change,
+        )
+    }
+}
+
+/// A status bar item showing the text encoding of the active buffer.
+#[derive(IntoElement)]
+pub struct EncodingIndicator {
+    encoding: Option<SharedString>,
+    options: Vec<SharedString>,
+    visible: bool,
+    on_change: Option<Rc<dyn Fn(&SharedString, &mut WindowContext)>>,
+}
+
+impl EncodingIndicator {
+    pub fn new() -> Self {
+        Self {
+            encoding: None,
+            options: [
+                "UTF-8",
+                "UTF-16 LE",
+                "UTF-16 BE",
+                "Windows-1252",
+                "ISO-8859-1",
+            ]
+            .into_iter()
+            .map(SharedString::from)
+            .collect(),
+            visible: true,
+            on_change: None,
+        }
+    }
+
+    pub fn encoding(mut self, encoding: Option<SharedString>) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn options(mut self, options: impl IntoIterator<Item = impl Into<SharedString>>) -> Self {
+        self.options = options.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn on_change(
+        mut self,
+        handler: impl Fn(&SharedString, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_change = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for EncodingIndicator {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        render_indicator(
+            "encoding-indicator",
+            "Select Encoding",
+            self.encoding.filter(|_| self.visible),
+            self.options,
+            SharedString::clone,
+            self.on_change,
+        )
+    }
+}
+
+/// A status bar item showing the line ending of the active buffer.
+#[derive(IntoElement)]
+pub struct LineEndingIndicator {
+    line_ending: Option<LineEndingStyle>,
+    visible: bool,
+    on_change: Option<Rc<dyn Fn(&LineEndingStyle, &mut WindowContext)>>,
+}
+
+impl LineEndingIndicator {
+    pub fn new() -> Self {
+        Self {
+            line_ending: None,
+            visible: true,
+            on_change: None,
+        }
+    }
+
+    pub fn line_ending(mut self, line_ending: Option<LineEndingStyle>) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn on_change(
+        mut self,
+        handler: impl Fn(&LineEndingStyle, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_change = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for LineEndingIndicator {
+    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+        render_indicator(
+            "line-ending-indicator",
+            "Select Line Ending",
+            self.line_ending.filter(|_| self.visible),
+            vec![LineEndingStyle::Lf, LineEndingStyle::Crlf],
+            LineEndingStyle::label,
+            self.on_change,
+        )
+    }
+}
+
+/// Renders a button labeled with the current value that opens a popover
+/// listing every option, with the current one checked.
+fn render_indicator<T: Clone + PartialEq + 'static>(
+    id: &'static str,
+    tooltip: &'static str,
+    current: Option<T>,
+    options: Vec<T>,
+    label: fn(&T) -> SharedString,
+    on_change: Option<Rc<dyn Fn(&T, &mut WindowContext)>>,
+) -> impl IntoElement {
+    div().when_some(current, |this, current| {
+        let trigger = Button::new(id, label(&current))
+            .label_size(LabelSize::Small)
+            .tooltip(move |cx| Tooltip::text(tooltip, cx));
+
+        this.child(
+            PopoverMenu::new(id)
+                .menu(move |cx| {
+                    let current = current.clone();
+                    let options = options.clone();
+                    let on_change = on_change.clone();
+                    Some(ContextMenu::build(cx, move |mut menu, _| {
+                        for option in options {
+                            let toggled = option == current;
+                            let on_change = on_change.clone();
+                            menu =
+                                menu.toggleable_entry(label(&option), toggled, None, move |cx| {
+                                    if let Some(on_change) = on_change.as_ref() {
+                                        on_change(&option, cx);
+                                    }
+                                });
+                        }
+                        menu
+                    }))
+                })
+                .anchor(AnchorCorner::BottomRight)
+                .trigger(trigger),
+        )
+    })
+}
@@ -0,0 +1,103 @@
+use crate::prelude::*;
+
+/// Determines the color used to display a modal editing mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModeStyle {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+    Replace,
+    /// An operator has been typed and is waiting for a motion or object.
+    OperatorPending,
+}
+
+impl ModeStyle {
+    pub fn color(&self) -> Color {
+        match self {
+            ModeStyle::Normal => Color::Default,
+            ModeStyle::Insert => Color::Accent,
+            ModeStyle::Visual => Color::Info,
+            ModeStyle::Replace => Color::Warning,
+            ModeStyle::OperatorPending => Color::Muted,
+        }
+    }
+}
+
+/// A status bar segment showing the current mode of a modal editor, such as
+/// `-- INSERT --`.
+///
+/// The segment reserves a minimum width so that the rest of the status bar
+/// doesn't shift as the mode label changes length. Nothing is rendered until
+/// a mode is provided, i.e. while modal editing is disabled.
+#[derive(IntoElement)]
+pub struct ModeIndicator {
+    mode: Option<(SharedString, ModeStyle)>,
+    pending: Option<SharedString>,
+    recording: Option<char>,
+    min_width: Rems,
+}
+
+impl ModeIndicator {
+    pub fn new() -> Self {
+        Self {
+            mode: None,
+            pending: None,
+            recording: None,
+            min_width: rems(7.),
+        }
+    }
+
+    pub fn mode(mut self, text: impl Into<SharedString>, style: ModeStyle) -> Self {
+        self.mode = Some((text.into(), style));
+        self
+    }
+
+    /// Sets the keys or operators that have been typed but not yet applied.
+    pub fn pending(mut self, pending: Option<SharedString>) -> Self {
+        self.pending = pending.filter(|pending| !pending.is_empty());
+        self
+    }
+
+    /// Sets the register that a macro is currently being recorded into.
+    pub fn recording(mut self, register: Option<char>) -> Self {
+        self.recording = register;
+        self
+    }
+
+    pub fn min_width(mut self, min_width: Rems) -> Self {
+        self.min_width = min_width;
+        self
+    }
+}
+
+impl RenderOnce for ModeIndicator {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let Some((text, style)) = self.mode else {
+            return div().into_any_element();
+        };
+
+        h_flex()
+            .min_w(self.min_width)
+            .gap(Spacing::Small.rems(cx))
+            .children(self.recording.map(|register| {
+                Label::new(format!("recording @{register}"))
+                    .size(LabelSize::Small)
+                    .line_height_style(LineHeightStyle::UiLabel)
+                    .color(Color::Error)
+            }))
+            .children(self.pending.map(|pending| {
+                Label::new(pending)
+                    .size(LabelSize::Small)
+                    .line_height_style(LineHeightStyle::UiLabel)
+                    .color(Color::Muted)
+            }))
+            .child(
+                Label::new(format!("-- {text} --"))
+                    .size(LabelSize::Small)
+                    .line_height_style(LineHeightStyle::UiLabel)
+                    .color(style.color()),
+            )
+            .into_any_element()
+    }
+}
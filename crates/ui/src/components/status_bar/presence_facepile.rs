@@ -0,0 +1,264 @@
+use std::rc::Rc;
+
+use gpui::{AnyElement, ClickEvent, ImageSource};
+
+use crate::{
+    prelude::*, AudioStatus, Avatar, AvatarAudioStatusIndicator, ButtonLike, Facepile, Tooltip,
+};
+
+/// A participant displayed by [`PresenceFacepile`].
+#[derive(Clone)]
+pub struct PresenceParticipant {
+    name: SharedString,
+    avatar: Option<ImageSource>,
+    is_speaking: bool,
+    is_muted: bool,
+}
+
+impl PresenceParticipant {
+    pub fn new(name: impl Into<SharedString>) -> Self {
+        Self {
+            name: name.into(),
+            avatar: None,
+            is_speaking: false,
+            is_muted: false,
+        }
+    }
+
+    /// Sets the participant's avatar. Their initials are shown instead when
+    /// no avatar is provided.
+    pub fn avatar(mut self, avatar: impl Into<ImageSource>) -> Self {
+        self.avatar = Some(avatar.into());
+        self
+    }
+
+    pub fn speaking(mut self, is_speaking: bool) -> Self {
+        self.is_speaking = is_speaking;
+        self
+    }
+
+    pub fn muted(mut self, is_muted: bool) -> Self {
+        self.is_muted = is_muted;
+        self
+    }
+
+    fn initials(&self) -> SharedString {
+        self.name
+            .split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .take(2)
+            .flat_map(char::to_uppercase)
+            .collect::<String>()
+            .into()
+    }
+}
+
+/// A status bar segment showing who is in the current call, along with
+/// buttons to toggle the local microphone and speakers.
+///
+/// Up to `max_visible` participants are shown as overlapping avatars, with a
+/// ring around those who are speaking. Any remaining participants are
+/// summarized by a "+N" chip.
+#[derive(IntoElement)]
+pub struct PresenceFacepile {
+    id: ElementId,
+    participants: Vec<PresenceParticipant>,
+    max_visible: usize,
+    muted: bool,
+    deafened: bool,
+    on_click: Option<Rc<dyn Fn(&ClickEvent, &mut WindowContext)>>,
+    on_toggle_mute: Option<Rc<dyn Fn(&ClickEvent, &mut WindowContext)>>,
+    on_toggle_deafen: Option<Rc<dyn Fn(&ClickEvent, &mut WindowContext)>>,
+}
+
+impl PresenceFacepile {
+    pub fn new(id: impl Into<ElementId>, participants: Vec<PresenceParticipant>) -> Self {
+        Self {
+            id: id.into(),
+            participants,
+            max_visible: 4,
+            muted: false,
+            deafened: false,
+            on_click: None,
+            on_toggle_mute: None,
+            on_toggle_deafen: None,
+        }
+    }
+
+    /// Sets the number of avatars shown before the rest are collapsed into
+    /// an overflow chip.
+    pub fn max_visible(mut self, max_visible: usize) -> Self {
+        self.max_visible = max_visible.max(1);
+        self
+    }
+
+    /// Sets whether the local microphone is muted.
+    pub fn muted(mut self, muted: bool) -> Self {
+        self.muted = muted;
+        self
+    }
+
+    /// Sets whether the local speakers are deafened.
+    pub fn deafened(mut self, deafened: bool) -> Self {
+        self.deafened = deafened;
+        self
+    }
+
+    /// Sets the handler invoked when the facepile is clicked, which usually
+    /// opens the collaboration panel.
+    pub fn on_click(mut self, handler: impl Fn(&ClickEvent, &mut WindowContext) + 'static) -> Self {
+        self.on_click = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn on_toggle_mute(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_toggle_mute = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn on_toggle_deafen(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_toggle_deafen = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for PresenceFacepile {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        if self.participants.is_empty() {
+            return div().into_any_element();
+        }
+
+        let overflow = self.participants.len().saturating_sub(self.max_visible);
+        let names = self
+            .participants
+            .iter()
+            .map(|participant| participant.name.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let faces = self
+            .participants
+            .into_iter()
+            .take(self.max_visible)
+            .map(|participant| render_face(participant, cx))
+            .collect::<Vec<_>>();
+
+        let facepile = ButtonLike::new("facepile")
+            .child(
+                Facepile::empty()
+                    .children(faces)
+                    .when(overflow > 0, |facepile| {
+                        facepile.child(
+                            div()
+                                .ml_neg_1()
+                                .h(rems(1.))
+                                .px(Spacing::Small.rems(cx))
+                                .flex()
+                                .items_center()
+                                .rounded_full()
+                                .bg(cx.theme().colors().element_background)
+                                .child(
+                                    Label::new(format!("+{overflow}"))
+                                        .size(LabelSize::XSmall)
+                                        .color(Color::Muted),
+                                ),
+                        )
+                    }),
+            )
+            .tooltip(move |cx| Tooltip::text(names.clone(), cx))
+            .when_some(self.on_click, |button, on_click| {
+                button.on_click(move |event, cx| on_click(event, cx))
+            });
+
+        h_flex()
+            .id(self.id)
+            .gap(Spacing::Small.rems(cx))
+            .child(facepile)
+            .child(
+                IconButton::new(
+                    "toggle-mute",
+                    if self.muted {
+                        IconName::MicMute
+                    } else {
+                        IconName::Mic
+                    },
+                )
+                .icon_size(IconSize::Small)
+                .selected(self.muted)
+                .icon_color(if self.muted {
+                    Color::Error
+                } else {
+                    Color::Default
+                })
+                .tooltip(move |cx| Tooltip::text(if self.muted { "Unmute" } else { "Mute" }, cx))
+                .when_some(self.on_toggle_mute, |button, on_toggle| {
+                    button.on_click(move |event, cx| on_toggle(event, cx))
+                }),
+            )
+            .child(
+                IconButton::new(
+                    "toggle-deafen",
+                    if self.deafened {
+                        IconName::AudioOff
+                    } else {
+                        IconName::AudioOn
+                    },
+                )
+                .icon_size(IconSize::Small)
+                .selected(self.deafened)
+                .icon_color(if self.deafened {
+                    Color::Error
+                } else {
+                    Color::Default
+                })
+                .tooltip(move |cx| {
+                    Tooltip::text(if self.deafened { "Undeafen" } else { "Deafen" }, cx)
+                })
+                .when_some(self.on_toggle_deafen, |button, on_toggle| {
+                    button.on_click(move |event, cx| on_toggle(event, cx))
+                }),
+            )
+            .into_any_element()
+    }
+}
+
+fn render_face(participant: PresenceParticipant, cx: &WindowContext) -> AnyElement {
+    let ring_color = if participant.is_speaking {
+        cx.theme().status().info
+    } else {
+        cx.theme().colors().status_bar_background
+    };
+    let muted_indicator = participant
+        .is_muted
+        .then(|| AvatarAudioStatusIndicator::new(AudioStatus::Muted));
+
+    match participant.avatar.clone() {
+        Some(avatar) => Avatar::new(avatar)
+            .border_color(ring_color)
+            .indicator(muted_indicator)
+            .into_any_element(),
+        None => div()
+            .relative()
+            .size(rems(1.))
+            .flex()
+            .items_center()
+            .justify_center()
+            .rounded_full()
+            .border_1()
+            .border_color(ring_color)
+            .bg(cx.theme().colors().element_background)
+            .child(
+                Label::new(participant.initials())
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted),
+            )
+            .children(muted_indicator)
+            .into_any_element(),
+    }
+}
@@ -0,0 +1,406 @@
+use std::{rc::Rc, time::Duration};
+
+use gpui::{
+    percentage, AnchorCorner, Animation, AnimationExt, AnyElement, Render, Task, Transformation,
+};
+
+use crate::{prelude::*, ButtonLike, ContextMenu, PopoverMenu, Tooltip};
+
+const FADE_OUT_DURATION: Duration = Duration::from_millis(300);
+
+/// How far along a [`ProgressTask`] is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Progress {
+    /// The fraction of the work that has been completed, between 0 and 1.
+    Determinate(f32),
+    /// The amount of remaining work isn't known.
+    Indeterminate,
+}
+
+/// A long-running operation displayed by [`TaskProgress`].
+#[derive(Clone)]
+pub struct ProgressTask {
+    id: SharedString,
+    title: SharedString,
+    fraction: Option<f32>,
+    on_cancel: Option<Rc<dyn Fn(&mut WindowContext)>>,
+}
+
+impl ProgressTask {
+    pub fn new(id: impl Into<SharedString>, title: impl Into<SharedString>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            fraction: None,
+            on_cancel: None,
+        }
+    }
+
+    /// Sets the fraction of the work that has been completed, or `None` if it
+    /// isn't known.
+    pub fn fraction(mut self, fraction: Option<f32>) -> Self {
+        self.fraction = fraction;
+        self
+    }
+
+    /// Makes the task cancellable, invoking the given handler when the user
+    /// cancels it.
+    pub fn on_cancel(mut self, handler: impl Fn(&mut WindowContext) + 'static) -> Self {
+        self.on_cancel = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn id(&self) -> &SharedString {
+        &self.id
+    }
+
+    pub fn title(&self) -> &SharedString {
+        &self.title
+    }
+
+    pub fn progress(&self) -> Progress {
+        match self.fraction {
+            Some(fraction) if fraction.is_finite() => Progress::Determinate(fraction.clamp(0., 1.)),
+            _ => Progress::Indeterminate,
+        }
+    }
+
+    pub fn is_cancellable(&self) -> bool {
+        self.on_cancel.is_some()
+    }
+}
+
+/// A status bar item showing the progress of long-running operations.
+///
+/// The most recently started task is displayed along with a progress bar.
+/// Clicking it opens a popover listing every running task, each of which can
+/// be cancelled if it provided a cancel handler. When the last task is
+/// removed, the item fades out instead of disappearing immediately.
+pub struct TaskProgress {
+    tasks: Vec<ProgressTask>,
+    fading_out: Option<ProgressTask>,
+    _fade_out: Option<Task<()>>,
+}
+
+impl TaskProgress {
+    pub fn new(_cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            tasks: Vec::new(),
+            fading_out: None,
+            _fade_out: None,
+        }
+    }
+
+    pub fn tasks(&self) -> &[ProgressTask] {
+        &self.tasks
+    }
+
+    /// The task that is currently displayed in the status bar.
+    pub fn current_task(&self) -> Option<&ProgressTask> {
+        self.tasks.last().or(self.fading_out.as_ref())
+    }
+
+    pub fn is_fading_out(&self) -> bool {
+        self.tasks.is_empty() && self.fading_out.is_some()
+    }
+
+    pub fn set_tasks(&mut self, tasks: Vec<ProgressTask>, cx: &mut ViewContext<Self>) {
+        let previous_task = self.tasks.last().cloned();
+        self.tasks = tasks;
+        self.tasks_changed(previous_task, cx);
+    }
+
+    /// Adds a task, replacing any existing task with the same id.
+    pub fn push_task(&mut self, task: ProgressTask, cx: &mut ViewContext<Self>) {
+        if let Some(existing) = self
+            .tasks
+            .iter_mut()
+            .find(|existing| existing.id == task.id)
+        {
+            *existing = task;
+        } else {
+            self.tasks.push(task);
+        }
+        self.tasks_changed(None, cx);
+    }
+
+    pub fn set_fraction(&mut self, id: &str, fraction: Option<f32>, cx: &mut ViewContext<Self>) {
+        if let Some(task) = self.tasks.iter_mut().find(|task| task.id.as_ref() == id) {
+            task.fraction = fraction;
+            cx.notify();
+        }
+    }
+
+    pub fn remove_task(&mut self, id: &str, cx: &mut ViewContext<Self>) {
+        let previous_task = self.tasks.last().cloned();
+        self.tasks.retain(|task| task.id.as_ref() != id);
+        self.tasks_changed(previous_task, cx);
+    }
+
+    /// Invokes the cancel handler of the given task. The task remains in the
+    /// list until its owner removes it.
+    pub fn cancel_task(&mut self, id: &str, cx: &mut ViewContext<Self>) {
+        let on_cancel = self
+            .tasks
+            .iter()
+            .find(|task| task.id.as_ref() == id)
+            .and_then(|task| task.on_cancel.clone());
+        if let Some(on_cancel) = on_cancel {
+            on_cancel(cx);
+        }
+    }
+
+    fn tasks_changed(&mut self, previous_task: Option<ProgressTask>, cx: &mut ViewContext<Self>) {
+        if !self.tasks.is_empty() {
+            self.fading_out = None;
+            self._fade_out = None;
+        } else if let Some(previous_task) = previous_task {
+            self.fading_out = Some(previous_task);
+            self._fade_out = Some(cx.spawn(|this, mut cx| async move {
+                cx.background_executor().timer(FADE_OUT_DURATION).await;
+                this.update(&mut cx, |this, cx| {
+                    this.fading_out = None;
+                    cx.notify();
+                })
+                .ok();
+            }));
+        }
+        cx.notify();
+    }
+}
+
+impl Render for TaskProgress {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let Some(task) = self.current_task().cloned() else {
+            return div().into_any_element();
+        };
+
+        let additional_task_count = self.tasks.len().saturating_sub(1);
+        let title = if additional_task_count > 0 {
+            format!("{} + {} more", task.title, additional_task_count)
+        } else {
+            task.title.to_string()
+        };
+
+        let trigger = ButtonLike::new("task-progress")
+            .child(
+                h_flex()
+                    .gap(Spacing::Small.rems(cx))
+                    .child(
+                        Label::new(title)
+                            .size(LabelSize::Small)
+                            .line_height_style(LineHeightStyle::UiLabel),
+                    )
+                    .child(render_progress_bar(task.progress(), cx)),
+            )
+            .tooltip(|cx| Tooltip::text("Show Running Tasks", cx));
+
+        let tasks = self.tasks.clone();
+        let indicator = div().child(
+            PopoverMenu::new("task-progress-popover")
+                .menu(move |cx| {
+                    if tasks.is_empty() {
+                        return None;
+                    }
+                    let tasks = tasks.clone();
+                    Some(ContextMenu::build(cx, move |mut menu, _| {
+                        for task in tasks.into_iter().rev() {
+                            menu = menu.custom_row(move |cx| render_task_row(&task, cx));
+                        }
+                        menu
+                    }))
+                })
+                .anchor(AnchorCorner::BottomLeft)
+                .trigger(trigger),
+        );
+
+        if self.is_fading_out() {
+            indicator
+                .with_animation(
+                    "task-progress-fade-out",
+                    Animation::new(FADE_OUT_DURATION),
+                    |indicator, delta| indicator.opacity(1. - delta),
+                )
+                .into_any_element()
+        } else {
+            indicator.into_any_element()
+        }
+    }
+}
+
+fn render_progress_bar(progress: Progress, cx: &WindowContext) -> impl IntoElement {
+    let fill = div()
+        .h_full()
+        .rounded_sm()
+        .bg(cx.theme().colors().text_accent);
+
+    div()
+        .relative()
+        .w(px(48.))
+        .h(px(3.))
+        .rounded_sm()
+        .overflow_hidden()
+        .bg(cx.theme().colors().element_background)
+        .child(match progress {
+            Progress::Determinate(fraction) => fill.w(relative(fraction)).into_any_element(),
+            Progress::Indeterminate => fill
+                .absolute()
+                .top_0()
+                .w(relative(0.3))
+                .with_animation(
+                    "task-progress-shimmer",
+                    Animation::new(Duration::from_millis(1200)).repeat(),
+                    |fill, delta| fill.left(relative(delta * 1.3 - 0.3)),
+                )
+                .into_any_element(),
+        })
+}
+
+fn render_task_row(task: &ProgressTask, cx: &mut WindowContext) -> AnyElement {
+    let status = match task.progress() {
+        Progress::Determinate(fraction) => Label::new(format!("{:.0}%", fraction * 100.))
+            .size(LabelSize::Small)
+            .color(Color::Muted)
+            .into_any_element(),
+        Progress::Indeterminate => Icon::new(IconName::ArrowCircle)
+            .size(IconSize::Small)
+            .color(Color::Muted)
+            .with_animation(
+                "task-progress-spinner",
+                Animation::new(Duration::from_secs(2)).repeat(),
+                |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+            )
+            .into_any_element(),
+    };
+
+    h_flex()
+        .w_full()
+        .gap(Spacing::Large.rems(cx))
+        .justify_between()
+        .child(Label::new(task.title.clone()))
+        .child(
+            h_flex()
+                .gap(Spacing::Small.rems(cx))
+                .child(render_progress_bar(task.progress(), cx))
+                .child(status)
+                .when_some(task.on_cancel.clone(), |this, on_cancel| {
+                    this.child(
+                        IconButton::new(
+                            ElementId::Name(format!("cancel-{}", task.id).into()),
+                            IconName::Close,
+                        )
+                        .icon_size(IconSize::Small)
+                        .tooltip(|cx| Tooltip::text("Cancel", cx))
+                        .on_click(move |_, cx| on_cancel(cx)),
+                    )
+                }),
+        )
+        .into_any_element()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use settings::SettingsStore;
+    use std::cell::Cell;
+
+    #[gpui::test]
+    fn test_task_progress_transitions(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (task_progress, cx) = cx.add_window_view(TaskProgress::new);
+
+        task_progress.update(cx, |task_progress, cx| {
+            task_progress.push_task(
+                ProgressTask::new("download", "Downloading extension").fraction(Some(0.25)),
+                cx,
+            );
+            assert_eq!(
+                task_progress.current_task().unwrap().progress(),
+                Progress::Determinate(0.25)
+            );
+
+            // Losing track of the amount of work switches to an indeterminate bar.
+            task_progress.set_fraction("download", None, cx);
+            assert_eq!(
+                task_progress.current_task().unwrap().progress(),
+                Progress::Indeterminate
+            );
+
+            task_progress.set_fraction("download", Some(1.5), cx);
+            assert_eq!(
+                task_progress.current_task().unwrap().progress(),
+                Progress::Determinate(1.)
+            );
+
+            // The most recently added task is displayed.
+            task_progress.push_task(ProgressTask::new("search", "Searching project"), cx);
+            assert_eq!(
+                task_progress.current_task().unwrap().id().as_ref(),
+                "search"
+            );
+            assert_eq!(task_progress.tasks().len(), 2);
+
+            task_progress.remove_task("search", cx);
+            task_progress.remove_task("download", cx);
+            assert!(task_progress.tasks().is_empty());
+            assert!(task_progress.is_fading_out());
+            assert_eq!(
+                task_progress.current_task().unwrap().id().as_ref(),
+                "download"
+            );
+        });
+
+        cx.executor().advance_clock(FADE_OUT_DURATION);
+        cx.run_until_parked();
+        task_progress.update(cx, |task_progress, _| {
+            assert!(!task_progress.is_fading_out());
+            assert!(task_progress.current_task().is_none());
+        });
+    }
+
+    #[gpui::test]
+    fn test_task_progress_cancellation(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (task_progress, cx) = cx.add_window_view(TaskProgress::new);
+
+        let search_cancelled = Rc::new(Cell::new(0));
+        let sync_cancelled = Rc::new(Cell::new(0));
+        task_progress.update(cx, |task_progress, cx| {
+            task_progress.set_tasks(
+                vec![
+                    ProgressTask::new("search", "Searching project").on_cancel({
+                        let search_cancelled = search_cancelled.clone();
+                        move |_| search_cancelled.set(search_cancelled.get() + 1)
+                    }),
+                    ProgressTask::new("sync", "Syncing worktree").on_cancel({
+                        let sync_cancelled = sync_cancelled.clone();
+                        move |_| sync_cancelled.set(sync_cancelled.get() + 1)
+                    }),
+                    ProgressTask::new("index", "Indexing"),
+                ],
+                cx,
+            );
+            assert!(task_progress.tasks()[0].is_cancellable());
+            assert!(!task_progress.tasks()[2].is_cancellable());
+
+            task_progress.cancel_task("sync", cx);
+            task_progress.cancel_task("index", cx);
+            task_progress.cancel_task("missing", cx);
+        });
+
+        assert_eq!(search_cancelled.get(), 0);
+        assert_eq!(sync_cancelled.get(), 1);
+        task_progress.update(cx, |task_progress, _| {
+            assert_eq!(task_progress.tasks().len(), 3);
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+}
@@ -0,0 +1,164 @@
+use std::{rc::Rc, time::Duration};
+
+use gpui::{percentage, Animation, AnimationExt, ClickEvent, Transformation};
+
+use crate::{prelude::*, ButtonLike, TintColor, Tooltip};
+
+const FADE_IN_DURATION: Duration = Duration::from_millis(200);
+
+/// The state of an application update, as displayed by [`UpdateIndicator`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum UpdateState {
+    /// No update is in progress.
+    #[default]
+    None,
+    Checking,
+    /// An update is being downloaded, with the fraction downloaded so far
+    /// between 0 and 1.
+    Downloading(f32),
+    Installing,
+    /// An update has been installed and will be applied on restart.
+    ReadyToRestart,
+    Errored(SharedString),
+}
+
+impl UpdateState {
+    fn name(&self) -> &'static str {
+        match self {
+            UpdateState::None => "none",
+            UpdateState::Checking => "checking",
+            UpdateState::Downloading(_) => "downloading",
+            UpdateState::Installing => "installing",
+            UpdateState::ReadyToRestart => "ready-to-restart",
+            UpdateState::Errored(_) => "errored",
+        }
+    }
+}
+
+/// A status bar item showing the progress of an application update.
+///
+/// Nothing is rendered while no update is in progress. Once an update has
+/// been installed, the item becomes a "Restart to update" pill that invokes
+/// the `on_restart` handler when clicked. The item fades in whenever the
+/// state changes so that it doesn't pop into view.
+#[derive(IntoElement)]
+pub struct UpdateIndicator {
+    state: UpdateState,
+    on_restart: Option<Rc<dyn Fn(&ClickEvent, &mut WindowContext)>>,
+}
+
+impl UpdateIndicator {
+    pub fn new(state: UpdateState) -> Self {
+        Self {
+            state,
+            on_restart: None,
+        }
+    }
+
+    pub fn on_restart(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_restart = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for UpdateIndicator {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let animation_id = SharedString::from(format!("update-indicator-{}", self.state.name()));
+        let content = match self.state {
+            UpdateState::None => return div().into_any_element(),
+            UpdateState::Checking => h_flex()
+                .gap(Spacing::Small.rems(cx))
+                .child(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .color(Color::Muted)
+                        .with_animation(
+                            "update-indicator-spinner",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        ),
+                )
+                .child(status_label("Checking for updates…"))
+                .into_any_element(),
+            UpdateState::Downloading(fraction) => h_flex()
+                .gap(Spacing::Small.rems(cx))
+                .child(
+                    Icon::new(IconName::Download)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(render_download_bar(fraction, cx))
+                .into_any_element(),
+            UpdateState::Installing => h_flex()
+                .gap(Spacing::Small.rems(cx))
+                .child(
+                    Icon::new(IconName::Download)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(status_label("Installing update…"))
+                .into_any_element(),
+            UpdateState::ReadyToRestart => ButtonLike::new("update-indicator-restart")
+                .style(ButtonStyle::Tinted(TintColor::Accent))
+                .child(
+                    Label::new("Restart to update")
+                        .size(LabelSize::Small)
+                        .color(Color::Accent),
+                )
+                .when_some(self.on_restart, |button, on_restart| {
+                    button.on_click(move |event, cx| on_restart(event, cx))
+                })
+                .into_any_element(),
+            UpdateState::Errored(message) => div()
+                .id("update-indicator-error")
+                .child(
+                    Icon::new(IconName::ExclamationTriangle)
+                        .size(IconSize::Small)
+                        .color(Color::Warning),
+                )
+                .tooltip(move |cx| Tooltip::text(message.clone(), cx))
+                .into_any_element(),
+        };
+
+        div()
+            .child(content)
+            .with_animation(
+                animation_id,
+                Animation::new(FADE_IN_DURATION),
+                |indicator, delta| indicator.opacity(delta),
+            )
+            .into_any_element()
+    }
+}
+
+fn status_label(text: &'static str) -> Label {
+    Label::new(text)
+        .size(LabelSize::Small)
+        .line_height_style(LineHeightStyle::UiLabel)
+        .color(Color::Muted)
+}
+
+fn render_download_bar(fraction: f32, cx: &WindowContext) -> impl IntoElement {
+    let fraction = if fraction.is_finite() {
+        fraction.clamp(0., 1.)
+    } else {
+        0.
+    };
+
+    div()
+        .w(px(48.))
+        .h(px(3.))
+        .rounded_sm()
+        .overflow_hidden()
+        .bg(cx.theme().colors().element_background)
+        .child(
+            div()
+                .h_full()
+                .w(relative(fraction))
+                .rounded_sm()
+                .bg(cx.theme().colors().text_accent),
+        )
+}
@@ -1,4 +1,5 @@
 mod avatar;
+mod breadcrumbs;
 mod button;
 mod checkbox;
 mod context_menu;
@@ -10,13 +11,23 @@ mod label;
 mod list;
 mod list_header;
 mod list_item;
+mod loading_states;
+mod palette;
+mod panel;
+mod select;
+mod selectable_list;
 mod setting;
+mod status_bar;
+mod syntax_preview;
 mod tab;
 mod tab_bar;
+mod tab_strip;
+mod toast;
 mod toggle_button;
 mod tool_strip;
 
 pub use avatar::*;
+pub use breadcrumbs::*;
 pub use button::*;
 pub use checkbox::*;
 pub use context_menu::*;
@@ -28,8 +39,17 @@ pub use label::*;
 pub use list::*;
 pub use list_header::*;
 pub use list_item::*;
+pub use loading_states::*;
+pub use palette::*;
+pub use panel::*;
+pub use select::*;
+pub use selectable_list::*;
 pub use setting::*;
+pub use status_bar::*;
+pub use syntax_preview::*;
 pub use tab::*;
 pub use tab_bar::*;
+pub use tab_strip::*;
+pub use toast::*;
 pub use toggle_button::*;
 pub use tool_strip::*;
@@ -2,7 +2,7 @@ use gpui::Render;
 use story::{StoryContainer, StoryItem, StorySection};
 
 use crate::{prelude::*, AudioStatus, Availability, AvatarAvailabilityIndicator};
-use crate::{Avatar, AvatarAudioStatusIndicator};
+use crate::{Avatar, AvatarAudioStatusIndicator, AvatarShape, AvatarSize};
 
 pub struct AvatarStory;
 
@@ -20,6 +20,45 @@ impl Render for AvatarStory {
                         Avatar::new("https://avatars.githubusercontent.com/u/326587?v=4"),
                     )),
             )
+            .child(
+                StorySection::new()
+                    .child(StoryItem::new(
+                        "Initials without an image",
+                        Avatar::from_name("Nathan Sobo").size(AvatarSize::Large),
+                    ))
+                    .child(StoryItem::new(
+                        "Initials when the image fails to load",
+                        Avatar::new("https://avatars.githubusercontent.com/u/0?v=4")
+                            .name("Marshall Bowers")
+                            .size(AvatarSize::Large),
+                    ))
+                    .child(StoryItem::new(
+                        "Rounded rectangle",
+                        Avatar::new("https://avatars.githubusercontent.com/u/1714999?v=4")
+                            .name("Mikayla Maki")
+                            .shape(AvatarShape::RoundedRectangle)
+                            .size(AvatarSize::Large),
+                    )),
+            )
+            .child(
+                StorySection::new().child(StoryItem::new(
+                    "Sizes",
+                    h_flex().gap_2().children(
+                        [
+                            AvatarSize::XSmall,
+                            AvatarSize::Small,
+                            AvatarSize::Medium,
+                            AvatarSize::Large,
+                        ]
+                        .into_iter()
+                        .map(|size| {
+                            Avatar::new("https://avatars.githubusercontent.com/u/326587?v=4")
+                                .name("Conrad Irwin")
+                                .size(size)
+                        }),
+                    ),
+                )),
+            )
             .child(
                 StorySection::new()
                     .child(StoryItem::new(
@@ -0,0 +1,32 @@
+use gpui::Render;
+use story::Story;
+
+use crate::prelude::*;
+use crate::{BreadcrumbSegment, Breadcrumbs};
+
+pub struct BreadcrumbsStory;
+
+impl Render for BreadcrumbsStory {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let segments = || {
+            vec![
+                BreadcrumbSegment::directory("crates").on_click(|_, _| {}),
+                BreadcrumbSegment::directory("collab").on_click(|_, _| {}),
+                BreadcrumbSegment::directory("src").on_click(|_, _| {}),
+                BreadcrumbSegment::directory("rpc").on_click(|_, _| {}),
+                BreadcrumbSegment::file("server.rs"),
+                BreadcrumbSegment::symbol("impl Server").on_click(|_, _| {}),
+                BreadcrumbSegment::symbol("fn stat").on_click(|_, _| {}),
+            ]
+        };
+
+        Story::container()
+            .child(Story::title_for::<Breadcrumbs>())
+            .child(Story::label("Fitting its container"))
+            .child(Breadcrumbs::new("container", segments()))
+            .child(Story::label("Max width of 240px"))
+            .child(Breadcrumbs::new("medium", segments()).max_width(px(240.)))
+            .child(Story::label("Max width of 120px"))
+            .child(Breadcrumbs::new("narrow", segments()).max_width(px(120.)))
+    }
+}
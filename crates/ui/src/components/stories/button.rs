@@ -2,7 +2,7 @@ use gpui::Render;
 use story::Story;
 
 use crate::{prelude::*, IconName};
-use crate::{Button, ButtonStyle};
+use crate::{Button, ButtonStyle, IconButton};
 
 pub struct ButtonStory;
 
@@ -34,5 +34,63 @@ impl Render for ButtonStory {
             .child(Button::new("default_subtle", "Click me").style(ButtonStyle::Subtle))
             .child(Story::label("Default (Transparent)"))
             .child(Button::new("default_transparent", "Click me").style(ButtonStyle::Transparent))
+            .child(Story::label("Variants"))
+            .child(variant_grid())
     }
 }
+
+/// Renders every [`ButtonVariant`] in every [`InteractionState`], for both
+/// labeled and icon buttons.
+fn variant_grid() -> impl IntoElement {
+    let variants = [
+        ("Ghost", ButtonVariant::Ghost),
+        ("Filled", ButtonVariant::Filled),
+        ("Toggle", ButtonVariant::Toggle),
+    ];
+    let states = [
+        ("Enabled", InteractionState::Enabled),
+        ("Active", InteractionState::Active),
+        ("Disabled", InteractionState::Disabled),
+    ];
+
+    v_flex()
+        .gap_2()
+        .child(
+            h_flex()
+                .gap_2()
+                .child(div().w_20())
+                .children(states.iter().map(|(state_name, _)| {
+                    div()
+                        .w_40()
+                        .child(Label::new(*state_name).color(Color::Muted))
+                })),
+        )
+        .children(variants.iter().map(|(variant_name, variant)| {
+            h_flex()
+                .gap_2()
+                .child(
+                    div()
+                        .w_20()
+                        .child(Label::new(*variant_name).color(Color::Muted)),
+                )
+                .children(states.iter().map(|(state_name, state)| {
+                    let id = format!("{variant_name}_{state_name}");
+                    h_flex()
+                        .w_40()
+                        .gap_1()
+                        .child(
+                            Button::new(SharedString::from(id.clone()), "Click me")
+                                .variant(*variant)
+                                .state(*state),
+                        )
+                        .child(
+                            IconButton::new(
+                                SharedString::from(format!("{id}_icon")),
+                                IconName::Hash,
+                            )
+                            .variant(*variant)
+                            .state(*state),
+                        )
+                }))
+        }))
+}
@@ -14,6 +14,7 @@ fn build_menu(cx: &mut WindowContext, header: impl Into<SharedString>) -> View<C
             .entry("Print best food", Some(Box::new(PrintBestFood)), |cx| {
                 cx.dispatch_action(Box::new(PrintBestFood))
             })
+            .disabled_entry("Print worst food", None)
     })
 }
 
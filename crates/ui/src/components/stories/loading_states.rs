@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use gpui::{px, Render, Task};
+use story::Story;
+
+use crate::prelude::*;
+use crate::{EmptyState, SkeletonList};
+
+/// How long the mocked project panel takes to scan its worktree.
+const SCAN_DURATION: Duration = Duration::from_secs(2);
+
+/// The entries of the mocked project panel once it has loaded, with their depth.
+const ENTRIES: &[(usize, IconName, &str)] = &[
+    (0, IconName::Folder, "zed"),
+    (1, IconName::Folder, "src"),
+    (2, IconName::FileRust, "lib.rs"),
+    (2, IconName::FileRust, "main.rs"),
+    (1, IconName::FileToml, "Cargo.toml"),
+    (1, IconName::FileDoc, "README.md"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PanelState {
+    Loading,
+    Empty,
+    Populated,
+}
+
+pub struct LoadingStatesStory {
+    state: PanelState,
+    _scan: Option<Task<()>>,
+}
+
+impl LoadingStatesStory {
+    pub fn new(cx: &mut ViewContext<Self>) -> Self {
+        let mut this = Self {
+            state: PanelState::Loading,
+            _scan: None,
+        };
+        this.scan(PanelState::Empty, cx);
+        this
+    }
+
+    /// Shows the panel loading for a moment, as if a worktree was being scanned, and then
+    /// in the given state.
+    fn scan(&mut self, result: PanelState, cx: &mut ViewContext<Self>) {
+        self.state = PanelState::Loading;
+        self._scan = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(SCAN_DURATION).await;
+            this.update(&mut cx, |this, cx| {
+                this.state = result;
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn render_panel(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match self.state {
+            PanelState::Loading => {
+                SkeletonList::new("project-panel-skeleton", 8).into_any_element()
+            }
+            PanelState::Empty => EmptyState::new("No open folders")
+                .icon(IconName::FileTree)
+                .description("Open a folder to see its files here.")
+                .primary_action(
+                    Button::new("open-folder", "Open Folder")
+                        .on_click(cx.listener(|this, _, cx| this.scan(PanelState::Populated, cx))),
+                )
+                .into_any_element(),
+            PanelState::Populated => v_flex()
+                .gap_1()
+                .children(ENTRIES.iter().map(|(depth, icon, name)| {
+                    h_flex()
+                        .gap_1()
+                        .pl(px(*depth as f32 * 12.))
+                        .child(Icon::new(*icon).size(IconSize::Small).color(Color::Muted))
+                        .child(Label::new(*name))
+                }))
+                .into_any_element(),
+        };
+
+        v_flex()
+            .w(px(240.))
+            .h_full()
+            .gap_2()
+            .p_2()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                Label::new("Project")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(v_flex().flex_1().child(content))
+    }
+}
+
+impl Render for LoadingStatesStory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        Story::container()
+            .size_full()
+            .child(Story::title("Loading States"))
+            .child(Story::description(
+                "The project panel shows skeletons while its worktree is scanned, then an \
+                 empty state or its files. The shimmer pauses while the window is inactive.",
+            ))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("scan-empty", "Scan Empty Project")
+                            .on_click(cx.listener(|this, _, cx| this.scan(PanelState::Empty, cx))),
+                    )
+                    .child(
+                        Button::new("scan-populated", "Scan Project").on_click(
+                            cx.listener(|this, _, cx| this.scan(PanelState::Populated, cx)),
+                        ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .w_full()
+                    .child(self.render_panel(cx))
+                    .child(
+                        div()
+                            .flex_1()
+                            .h_full()
+                            .bg(cx.theme().colors().editor_background),
+                    ),
+            )
+    }
+}
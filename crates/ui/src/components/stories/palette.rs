@@ -0,0 +1,185 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use gpui::{DismissEvent, NoAction, Render, View};
+use story::Story;
+
+use crate::prelude::*;
+use crate::{KeyBinding, Palette, PaletteItem, PaletteMatch};
+
+const COMMAND_COUNT: usize = 10_000;
+const NAMESPACES: &[&str] = &["editor", "workspace", "project panel", "terminal", "git"];
+const VERBS: &[&str] = &[
+    "toggle", "open", "close", "select", "copy", "move", "fold", "go to",
+];
+const NOUNS: &[&str] = &[
+    "line",
+    "file",
+    "tab",
+    "pane",
+    "symbol",
+    "selection",
+    "hunk",
+    "branch",
+];
+
+struct SyntheticCommand {
+    id: usize,
+    label: SharedString,
+    keystrokes: Option<&'static str>,
+}
+
+impl PaletteItem for SyntheticCommand {
+    fn label(&self) -> SharedString {
+        self.label.clone()
+    }
+
+    fn key_binding(&self, _: &mut WindowContext) -> Option<KeyBinding> {
+        let keystrokes = self.keystrokes?;
+        Some(KeyBinding::new(gpui::KeyBinding::new(
+            keystrokes,
+            NoAction {},
+            None,
+        )))
+    }
+}
+
+fn synthetic_commands() -> Vec<SyntheticCommand> {
+    (0..COMMAND_COUNT)
+        .map(|id| {
+            let namespace = NAMESPACES[id % NAMESPACES.len()];
+            let verb = VERBS[(id / NAMESPACES.len()) % VERBS.len()];
+            let noun = NOUNS[(id / (NAMESPACES.len() * VERBS.len())) % NOUNS.len()];
+            SyntheticCommand {
+                id,
+                label: format!("{namespace}: {verb} {noun} {id}").into(),
+                keystrokes: match id % 7 {
+                    0 => Some("cmd-k"),
+                    3 => Some("ctrl-shift-p"),
+                    _ => None,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Matches labels containing the query's characters in order, ignoring case, and favors
+/// consecutive characters.
+fn subsequence_filter(query: &str, commands: &[SyntheticCommand]) -> Vec<PaletteMatch> {
+    let query = query.to_lowercase();
+    commands
+        .iter()
+        .enumerate()
+        .filter_map(|(item_ix, command)| {
+            let mut positions = Vec::new();
+            let mut score = 0.;
+            let mut query_chars = query.chars().filter(|c| !c.is_whitespace()).peekable();
+            for (ix, char) in command.label.char_indices() {
+                let Some(query_char) = query_chars.peek() else {
+                    break;
+                };
+                if char.to_lowercase().eq(query_char.to_lowercase()) {
+                    if positions.last().map_or(false, |last| last + 1 == ix) {
+                        score += 2.;
+                    }
+                    score += 1.;
+                    positions.push(ix);
+                    query_chars.next();
+                }
+            }
+            query_chars.peek().is_none().then_some(PaletteMatch {
+                item_ix,
+                score,
+                positions,
+            })
+        })
+        .collect()
+}
+
+pub struct PaletteStory {
+    palette: Option<View<Palette<SyntheticCommand>>>,
+    /// How many times each command has been confirmed, by id.
+    hit_counts: Rc<RefCell<HashMap<usize, usize>>>,
+    last_confirmed: Option<SharedString>,
+    last_previewed: Option<SharedString>,
+}
+
+impl PaletteStory {
+    pub fn new(_cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            palette: None,
+            hit_counts: Rc::default(),
+            last_confirmed: None,
+            last_previewed: None,
+        }
+    }
+
+    fn open_palette(&mut self, cx: &mut ViewContext<Self>) {
+        let view = cx.view().downgrade();
+        let hit_counts = self.hit_counts.clone();
+        let palette = cx.new_view(|cx| {
+            Palette::new(synthetic_commands(), subsequence_filter, cx)
+                .placeholder("Execute a command…")
+                .weight({
+                    let hit_counts = hit_counts.clone();
+                    move |command| {
+                        let hits = hit_counts.borrow().get(&command.id).copied();
+                        hits.unwrap_or_default() as f64 * 10.
+                    }
+                })
+                .on_confirm({
+                    let view = view.clone();
+                    move |command, cx| {
+                        *hit_counts.borrow_mut().entry(command.id).or_default() += 1;
+                        view.update(cx, |story, cx| {
+                            story.last_confirmed = Some(command.label.clone());
+                            cx.notify();
+                        })
+                        .ok();
+                    }
+                })
+                .on_preview(move |command, cx| {
+                    view.update(cx, |story, cx| {
+                        story.last_previewed = Some(command.label.clone());
+                        cx.notify();
+                    })
+                    .ok();
+                })
+        });
+        cx.subscribe(&palette, |story, _, _: &DismissEvent, cx| {
+            story.palette = None;
+            cx.notify();
+        })
+        .detach();
+        self.palette = Some(palette);
+        cx.notify();
+    }
+}
+
+impl Render for PaletteStory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let describe = |label: &Option<SharedString>| {
+            label
+                .clone()
+                .unwrap_or_else(|| SharedString::from("nothing yet"))
+        };
+
+        Story::container()
+            .child(Story::title("Palette"))
+            .child(Story::label(
+                "10,000 commands. Tab previews, enter confirms and boosts the command next time.",
+            ))
+            .child(
+                Button::new("open-palette", "Open Palette")
+                    .on_click(cx.listener(|story, _, cx| story.open_palette(cx))),
+            )
+            .child(Label::new(format!(
+                "Last confirmed: {}",
+                describe(&self.last_confirmed)
+            )))
+            .child(Label::new(format!(
+                "Last previewed: {}",
+                describe(&self.last_previewed)
+            )))
+            .children(self.palette.clone())
+    }
+}
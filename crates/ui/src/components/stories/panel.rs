@@ -0,0 +1,85 @@
+use gpui::{px, Render, View, VisualContext};
+use story::Story;
+
+use crate::prelude::*;
+use crate::{Panel, PanelPosition, PanelSize};
+
+struct PanelContent(SharedString);
+
+impl Render for PanelContent {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .p_2()
+            .child(Label::new(self.0.clone()).color(Color::Muted))
+    }
+}
+
+pub struct PanelStory {
+    left_panel: View<Panel>,
+    bottom_panel: View<Panel>,
+    last_size: Option<PanelSize>,
+}
+
+impl PanelStory {
+    pub fn new(cx: &mut ViewContext<Self>) -> Self {
+        let story = cx.view().downgrade();
+        let on_resize = move |size, cx: &mut WindowContext| {
+            story
+                .update(cx, |story, cx| {
+                    story.last_size = Some(size);
+                    cx.notify();
+                })
+                .ok();
+        };
+
+        let left_content = cx.new_view(|_| PanelContent("Project".into()));
+        let bottom_content = cx.new_view(|_| PanelContent("Terminal".into()));
+        Self {
+            left_panel: cx.new_view(|_| {
+                Panel::new(PanelPosition::Left, px(240.))
+                    .content(left_content)
+                    .on_resize(on_resize.clone())
+            }),
+            bottom_panel: cx.new_view(|_| {
+                Panel::new(PanelPosition::Bottom, px(200.))
+                    .min_size(px(80.))
+                    .content(bottom_content)
+                    .on_resize(on_resize)
+            }),
+            last_size: None,
+        }
+    }
+}
+
+impl Render for PanelStory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let last_size = match self.last_size {
+            Some(PanelSize::Open(size)) => format!("Last resize: {}px", f32::from(size)),
+            Some(PanelSize::Closed) => "Last resize: closed".to_string(),
+            None => "Drag a panel's edge to resize it".to_string(),
+        };
+
+        Story::container()
+            .size_full()
+            .child(Story::title_for::<Panel>())
+            .child(
+                h_flex()
+                    .flex_1()
+                    .w_full()
+                    .child(self.left_panel.clone())
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .h_full()
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .p_2()
+                                    .bg(cx.theme().colors().editor_background)
+                                    .child(Label::new(last_size)),
+                            )
+                            .child(self.bottom_panel.clone()),
+                    ),
+            )
+    }
+}
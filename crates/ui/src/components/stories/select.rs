@@ -0,0 +1,66 @@
+use gpui::Render;
+use story::Story;
+
+use crate::prelude::*;
+use crate::{Select, SelectOption};
+
+#[derive(Default)]
+pub struct SelectStory {
+    selected_language: Option<SharedString>,
+    selected_item: Option<SharedString>,
+}
+
+impl Render for SelectStory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let view = cx.view().downgrade();
+
+        Story::container()
+            .child(Story::title_for::<Select>())
+            .child(Story::label("5 options, in groups"))
+            .child(
+                Select::new("languages")
+                    .placeholder("Select a language")
+                    .selected(self.selected_language.clone())
+                    .group(
+                        "Recent",
+                        [
+                            SelectOption::new("rust", "Rust"),
+                            SelectOption::new("toml", "TOML"),
+                        ],
+                    )
+                    .group(
+                        "All",
+                        [
+                            SelectOption::new("markdown", "Markdown"),
+                            SelectOption::new("python", "Python"),
+                            SelectOption::new("plain-text", "Plain Text").disabled(true),
+                        ],
+                    )
+                    .on_select({
+                        let view = view.clone();
+                        move |id, cx| {
+                            view.update(cx, |story, cx| {
+                                story.selected_language = Some(id.clone());
+                                cx.notify();
+                            })
+                            .ok();
+                        }
+                    }),
+            )
+            .child(Story::label("5,000 options"))
+            .child(
+                Select::new("items")
+                    .selected(self.selected_item.clone())
+                    .options((0..5000).map(|ix| {
+                        SelectOption::new(format!("item-{ix}"), format!("Item {}", ix + 1))
+                    }))
+                    .on_select(move |id, cx| {
+                        view.update(cx, |story, cx| {
+                            story.selected_item = Some(id.clone());
+                            cx.notify();
+                        })
+                        .ok();
+                    }),
+            )
+    }
+}
@@ -0,0 +1,98 @@
+use gpui::{FocusHandle, Render, ScrollHandle};
+use story::Story;
+
+use crate::prelude::*;
+use crate::{
+    ListSelection, ListSelectionMode, SelectableList, SelectableListItem, SelectableListSection,
+};
+
+const SECTIONS: [(&str, &[&str]); 3] = [
+    (
+        "Open Editors",
+        &["main.rs", "lib.rs", "Cargo.toml", "README.md"],
+    ),
+    (
+        "Recent Files",
+        &[
+            "workspace.rs",
+            "pane.rs",
+            "item.rs",
+            "dock.rs",
+            "status_bar.rs",
+            "toolbar.rs",
+            "notifications.rs",
+            "persistence.rs",
+        ],
+    ),
+    (
+        "Settings",
+        &["settings.json", "keymap.json", "tasks.json", "themes"],
+    ),
+];
+
+pub struct SelectableListStory {
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    selection: ListSelection,
+    last_confirmed: Option<ElementId>,
+}
+
+impl SelectableListStory {
+    pub fn new(cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+            selection: ListSelection::default(),
+            last_confirmed: None,
+        }
+    }
+}
+
+impl Render for SelectableListStory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let sections = SECTIONS.iter().map(|(header, items)| {
+            SelectableListSection::new(items.iter().map(|item| {
+                SelectableListItem::new(
+                    SharedString::from(format!("{header}/{item}")),
+                    Label::new(*item),
+                )
+            }))
+            .header(*header)
+        });
+
+        Story::container()
+            .child(Story::title_for::<SelectableList>())
+            .child(Story::label(
+                "Shift-click selects a range, and the secondary modifier toggles an item",
+            ))
+            .child(
+                div().h_64().w_64().child(
+                    SelectableList::new("selectable_list", sections)
+                        .selection(self.selection.clone())
+                        .selection_mode(ListSelectionMode::Multiple)
+                        .track_scroll(self.scroll_handle.clone())
+                        .track_focus(&self.focus_handle)
+                        .on_selection_change(cx.listener(|this, selection: &ListSelection, cx| {
+                            this.selection = selection.clone();
+                            cx.notify();
+                        }))
+                        .on_confirm(cx.listener(|this, id: &ElementId, cx| {
+                            this.last_confirmed = Some(id.clone());
+                            cx.notify();
+                        })),
+                ),
+            )
+            .children(
+                self.last_confirmed
+                    .as_ref()
+                    .map(|id| Story::label(format!("Confirmed {id}"))),
+            )
+            .child(Story::label("Empty"))
+            .child(
+                div().h_16().w_64().child(
+                    SelectableList::new("empty_selectable_list", [])
+                        .empty_state(Label::new("No files").color(Color::Muted)),
+                ),
+            )
+    }
+}
@@ -0,0 +1,196 @@
+use gpui::Render;
+use story::{StoryContainer, StoryItem, StorySection};
+
+use crate::{
+    prelude::*, EncodingIndicator, Indentation, IndentationIndicator, LineEndingIndicator,
+    LineEndingStyle, ModeIndicator, ModeStyle, PresenceFacepile, PresenceParticipant,
+    UpdateIndicator, UpdateState,
+};
+
+pub struct StatusBarStory {
+    indentation: Indentation,
+    encoding: SharedString,
+    line_ending: LineEndingStyle,
+}
+
+impl Default for StatusBarStory {
+    fn default() -> Self {
+        Self {
+            indentation: Indentation::Spaces(4),
+            encoding: "UTF-8".into(),
+            line_ending: LineEndingStyle::Lf,
+        }
+    }
+}
+
+impl Render for StatusBarStory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        StoryContainer::new(
+            "Status Bar",
+            "crates/ui/src/components/stories/status_bar.rs",
+        )
+        .child(
+            StorySection::new()
+                .child(StoryItem::new(
+                    "Indentation",
+                    IndentationIndicator::new()
+                        .indentation(Some(self.indentation))
+                        .on_change(cx.listener(|this, indentation, cx| {
+                            this.indentation = *indentation;
+                            cx.notify();
+                        })),
+                ))
+                .child(StoryItem::new(
+                    "Encoding",
+                    EncodingIndicator::new()
+                        .encoding(Some(self.encoding.clone()))
+                        .on_change(cx.listener(|this, encoding: &SharedString, cx| {
+                            this.encoding = encoding.clone();
+                            cx.notify();
+                        })),
+                ))
+                .child(StoryItem::new(
+                    "Line Ending",
+                    LineEndingIndicator::new()
+                        .line_ending(Some(self.line_ending))
+                        .on_change(cx.listener(|this, line_ending, cx| {
+                            this.line_ending = *line_ending;
+                            cx.notify();
+                        })),
+                ))
+                .child(
+                    StoryItem::new(
+                        "No active editor",
+                        h_flex()
+                            .child(IndentationIndicator::new().indentation(None))
+                            .child(EncodingIndicator::new().encoding(None))
+                            .child(LineEndingIndicator::new().line_ending(None)),
+                    )
+                    .description("Renders nothing when no value is provided."),
+                ),
+        )
+        .child(
+            StorySection::new()
+                .child(StoryItem::new(
+                    "Normal",
+                    ModeIndicator::new().mode("NORMAL", ModeStyle::Normal),
+                ))
+                .child(StoryItem::new(
+                    "Insert",
+                    ModeIndicator::new().mode("INSERT", ModeStyle::Insert),
+                ))
+                .child(StoryItem::new(
+                    "Replace",
+                    ModeIndicator::new().mode("REPLACE", ModeStyle::Replace),
+                ))
+                .child(StoryItem::new(
+                    "Visual",
+                    ModeIndicator::new().mode("VISUAL", ModeStyle::Visual),
+                ))
+                .child(StoryItem::new(
+                    "Visual Line",
+                    ModeIndicator::new().mode("VISUAL LINE", ModeStyle::Visual),
+                ))
+                .child(StoryItem::new(
+                    "Visual Block",
+                    ModeIndicator::new().mode("VISUAL BLOCK", ModeStyle::Visual),
+                ))
+                .child(
+                    StoryItem::new(
+                        "Operator Pending",
+                        ModeIndicator::new()
+                            .mode("NORMAL", ModeStyle::OperatorPending)
+                            .pending(Some("2d".into())),
+                    )
+                    .description("Shows the operators typed so far."),
+                )
+                .child(StoryItem::new(
+                    "Recording Macro",
+                    ModeIndicator::new()
+                        .mode("INSERT", ModeStyle::Insert)
+                        .recording(Some('q')),
+                ))
+                .child(
+                    StoryItem::new("Disabled", ModeIndicator::new())
+                        .description("Renders nothing when modal editing is off."),
+                ),
+        )
+        .child(
+            StorySection::new()
+                .child(StoryItem::new(
+                    "Checking",
+                    UpdateIndicator::new(UpdateState::Checking),
+                ))
+                .child(StoryItem::new(
+                    "Downloading",
+                    UpdateIndicator::new(UpdateState::Downloading(0.4)),
+                ))
+                .child(StoryItem::new(
+                    "Installing",
+                    UpdateIndicator::new(UpdateState::Installing),
+                ))
+                .child(StoryItem::new(
+                    "Ready to Restart",
+                    UpdateIndicator::new(UpdateState::ReadyToRestart).on_restart(|_, _| {}),
+                ))
+                .child(
+                    StoryItem::new(
+                        "Errored",
+                        UpdateIndicator::new(UpdateState::Errored(
+                            "Failed to download the update".into(),
+                        )),
+                    )
+                    .description("Hover the icon to see the error."),
+                )
+                .child(
+                    StoryItem::new("No Update", UpdateIndicator::new(UpdateState::None))
+                        .description("Renders nothing when no update is in progress."),
+                ),
+        )
+        .child(
+            StorySection::new()
+                .child(StoryItem::new(
+                    "1 Participant",
+                    PresenceFacepile::new("presence-1", participants(1)),
+                ))
+                .child(StoryItem::new(
+                    "4 Participants",
+                    PresenceFacepile::new("presence-4", participants(4)).muted(true),
+                ))
+                .child(
+                    StoryItem::new(
+                        "12 Participants",
+                        PresenceFacepile::new("presence-12", participants(12))
+                            .muted(true)
+                            .deafened(true),
+                    )
+                    .description("Participants beyond the first four are collapsed."),
+                ),
+        )
+    }
+}
+
+fn participants(count: usize) -> Vec<PresenceParticipant> {
+    const AVATARS: [&str; 2] = [
+        "https://avatars.githubusercontent.com/u/1714999?v=4",
+        "https://avatars.githubusercontent.com/u/326587?v=4",
+    ];
+    const NAMES: [&str; 4] = [
+        "Ada Lovelace",
+        "Grace Hopper",
+        "Alan Turing",
+        "Edsger Dijkstra",
+    ];
+
+    (0..count)
+        .map(|ix| {
+            let participant = PresenceParticipant::new(NAMES[ix % NAMES.len()])
+                .speaking(ix == 1)
+                .muted(ix == 2);
+            match AVATARS.get(ix) {
+                Some(avatar) => participant.avatar(*avatar),
+                None => participant,
+            }
+        })
+        .collect()
+}
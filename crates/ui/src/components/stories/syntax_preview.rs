@@ -0,0 +1,52 @@
+use gpui::Render;
+use story::{StoryContainer, StoryItem, StorySection};
+use theme::ThemeRegistry;
+
+use crate::prelude::*;
+use crate::{SyntaxPreview, SyntaxPreviewSize};
+
+const THEMES: [&str; 3] = ["One Dark", "Ayu Light", "Gruvbox Dark"];
+
+pub struct SyntaxPreviewStory;
+
+impl Render for SyntaxPreviewStory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let registry = ThemeRegistry::global(cx);
+        let previews = |size: SyntaxPreviewSize| {
+            h_flex().gap_4().items_start().children(THEMES.map(|name| {
+                v_flex()
+                    .gap_1()
+                    .child(Label::new(name).size(LabelSize::Small).color(Color::Muted))
+                    .child(
+                        SyntaxPreview::new()
+                            .theme(registry.get(name).ok())
+                            .size(size),
+                    )
+            }))
+        };
+
+        StoryContainer::new(
+            "Syntax Preview",
+            "crates/ui/src/components/stories/syntax_preview.rs",
+        )
+        .child(
+            StorySection::new()
+                .child(StoryItem::new("Active theme", SyntaxPreview::new()))
+                .child(StoryItem::new(
+                    "Active theme, compact",
+                    SyntaxPreview::new().size(SyntaxPreviewSize::Compact),
+                )),
+        )
+        .child(
+            StorySection::new()
+                .child(StoryItem::new(
+                    "Bundled themes",
+                    previews(SyntaxPreviewSize::Full),
+                ))
+                .child(StoryItem::new(
+                    "Bundled themes, compact",
+                    previews(SyntaxPreviewSize::Compact),
+                )),
+        )
+    }
+}
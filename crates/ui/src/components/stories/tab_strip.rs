@@ -0,0 +1,106 @@
+use gpui::{Render, ScrollHandle};
+use story::Story;
+
+use crate::prelude::*;
+use crate::{TabStrip, TabStripItem};
+
+struct TabStripCase {
+    items: Vec<TabStripItem>,
+    active_index: usize,
+    scroll_handle: ScrollHandle,
+}
+
+impl TabStripCase {
+    fn new(tab_count: usize) -> Self {
+        let icons = [
+            IconName::FileRust,
+            IconName::FileToml,
+            IconName::FileDoc,
+            IconName::FileGeneric,
+        ];
+        let items = (0..tab_count)
+            .map(|ix| {
+                TabStripItem::new(format!("file_{}.rs", ix + 1))
+                    .icon(icons[ix % icons.len()])
+                    .dirty(ix % 3 == 1)
+                    .preview(ix == tab_count - 1)
+            })
+            .collect();
+
+        Self {
+            items,
+            active_index: 0,
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    fn close(&mut self, ix: usize) {
+        self.items.remove(ix);
+        if self.active_index > ix || self.active_index == self.items.len() {
+            self.active_index = self.active_index.saturating_sub(1);
+        }
+    }
+
+    fn reorder(&mut self, from_ix: usize, to_ix: usize) {
+        let item = self.items.remove(from_ix);
+        self.items.insert(to_ix, item);
+        if self.active_index == from_ix {
+            self.active_index = to_ix;
+        } else if from_ix < self.active_index && to_ix >= self.active_index {
+            self.active_index -= 1;
+        } else if from_ix > self.active_index && to_ix <= self.active_index {
+            self.active_index += 1;
+        }
+    }
+}
+
+pub struct TabStripStory {
+    cases: [TabStripCase; 2],
+}
+
+impl TabStripStory {
+    pub fn new(_cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            cases: [TabStripCase::new(3), TabStripCase::new(40)],
+        }
+    }
+
+    fn render_case(&self, case_ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let case = &self.cases[case_ix];
+        let story = cx.view().downgrade();
+        let update_case = move |cx: &mut WindowContext, f: &dyn Fn(&mut TabStripCase)| {
+            story
+                .update(cx, |story, cx| {
+                    f(&mut story.cases[case_ix]);
+                    cx.notify();
+                })
+                .ok();
+        };
+
+        TabStrip::new(("tab_strip", case_ix), case.items.iter().cloned())
+            .active_index(case.active_index)
+            .track_scroll(case.scroll_handle.clone())
+            .on_activate({
+                let update_case = update_case.clone();
+                move |ix, cx| update_case(cx, &|case| case.active_index = ix)
+            })
+            .on_close({
+                let update_case = update_case.clone();
+                move |ix, cx| update_case(cx, &|case| case.close(ix))
+            })
+            .on_reorder(move |from_ix, to_ix, cx| {
+                update_case(cx, &|case| case.reorder(from_ix, to_ix))
+            })
+    }
+}
+
+impl Render for TabStripStory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        Story::container()
+            .child(Story::title_for::<TabStrip>())
+            .child(Story::label("3 tabs"))
+            .child(h_flex().child(self.render_case(0, cx)))
+            .child(Story::label("40 tabs"))
+            .child(h_flex().child(self.render_case(1, cx)))
+    }
+}
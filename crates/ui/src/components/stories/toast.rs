@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use gpui::{Render, View};
+use story::Story;
+
+use crate::prelude::*;
+use crate::{Toast, ToastLayer};
+
+pub struct ToastStory {
+    toast_layer: View<ToastLayer>,
+}
+
+impl ToastStory {
+    pub fn new(cx: &mut WindowContext) -> Self {
+        Self {
+            toast_layer: ToastLayer::build(cx),
+        }
+    }
+
+    fn push(&self, toast: Toast, cx: &mut WindowContext) {
+        self.toast_layer
+            .update(cx, |toast_layer, cx| toast_layer.push(toast, cx));
+    }
+}
+
+impl Render for ToastStory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        Story::container()
+            .relative()
+            .size_full()
+            .child(Story::title_for::<ToastLayer>())
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("info", "Info").on_click(cx.listener(|this, _, cx| {
+                            this.push(
+                                Toast::info("Extension installed").timeout(Duration::from_secs(5)),
+                                cx,
+                            )
+                        })),
+                    )
+                    .child(Button::new("warning", "Warning").on_click(cx.listener(
+                        |this, _, cx| {
+                            this.push(
+                                Toast::warning("Language server is taking a while to respond")
+                                    .action("Restart", |_| {}),
+                                cx,
+                            )
+                        },
+                    )))
+                    .child(
+                        Button::new("error", "Error").on_click(cx.listener(|this, _, cx| {
+                            this.push(
+                                Toast::error("Failed to save settings.json")
+                                    .action("Open File", |_| {})
+                                    .action("Retry", |_| {})
+                                    .timeout(Duration::from_secs(10)),
+                                cx,
+                            )
+                        })),
+                    ),
+            )
+            .child(self.toast_layer.clone())
+    }
+}
@@ -0,0 +1,172 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use gpui::{HighlightStyle, StyledText, WhiteSpace};
+use settings::Settings;
+use theme::{SyntaxTheme, Theme, ThemeSettings};
+
+use crate::prelude::*;
+
+/// The size of a [`SyntaxPreview`].
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum SyntaxPreviewSize {
+    /// Small text with little padding, for previews shown in lists such as the theme picker.
+    Compact,
+    /// Text at the user's buffer font size.
+    #[default]
+    Full,
+}
+
+/// The code shown by a [`SyntaxPreview`], as pieces of text and the syntax highlight each
+/// one is drawn with.
+const SAMPLE: &[(&str, Option<&str>)] = &[
+    ("// Counts the words on each line.", Some("comment")),
+    ("\n", None),
+    ("fn", Some("keyword")),
+    (" ", None),
+    ("word_counts", Some("function")),
+    ("(text: &", None),
+    ("str", Some("type")),
+    (") -> ", None),
+    ("Vec", Some("type")),
+    ("<", None),
+    ("usize", Some("type")),
+    ("> {", None),
+    ("\n    ", None),
+    ("let", Some("keyword")),
+    (" limit = ", None),
+    ("80", Some("number")),
+    (";", None),
+    ("\n    text.", None),
+    ("lines", Some("function")),
+    ("().", None),
+    ("map", Some("function")),
+    ("(|line| line.", None),
+    ("split", Some("function")),
+    ("(", None),
+    ("\" \"", Some("string")),
+    (").", None),
+    ("count", Some("function")),
+    ("().", None),
+    ("min", Some("function")),
+    ("(limit)).", None),
+    ("collect", Some("function")),
+    ("()", None),
+    ("\n}", None),
+];
+
+/// Returns the text of the sample and the ranges in it to highlight with the given syntax theme.
+fn highlighted_sample(syntax: &SyntaxTheme) -> (String, Vec<(Range<usize>, HighlightStyle)>) {
+    let mut text = String::new();
+    let mut highlights = Vec::new();
+    for (token, highlight) in SAMPLE {
+        let start = text.len();
+        text.push_str(token);
+        if let Some(highlight) = highlight {
+            highlights.push((start..text.len(), syntax.get(highlight)));
+        }
+    }
+    (text, highlights)
+}
+
+/// A short, hard-coded snippet of code highlighted with a theme's syntax colors.
+///
+/// This shows what a theme looks like without creating an editor, so that a preview can be
+/// rendered for every theme in a list. The snippet is laid out as a single block of text.
+///
+/// # Examples
+///
+/// ```
+/// use ui::{SyntaxPreview, SyntaxPreviewSize};
+///
+/// SyntaxPreview::new().size(SyntaxPreviewSize::Compact);
+/// ```
+#[derive(IntoElement)]
+pub struct SyntaxPreview {
+    theme: Option<Arc<Theme>>,
+    size: SyntaxPreviewSize,
+}
+
+impl SyntaxPreview {
+    pub fn new() -> Self {
+        Self {
+            theme: None,
+            size: SyntaxPreviewSize::default(),
+        }
+    }
+
+    /// Renders the preview with the given theme instead of the active one.
+    pub fn theme(mut self, theme: impl Into<Option<Arc<Theme>>>) -> Self {
+        self.theme = theme.into();
+        self
+    }
+
+    pub fn size(mut self, size: SyntaxPreviewSize) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+impl RenderOnce for SyntaxPreview {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let theme = self.theme.unwrap_or_else(|| cx.theme().clone());
+        let settings = ThemeSettings::get_global(cx);
+        let (text, highlights) = highlighted_sample(theme.syntax());
+
+        let mut text_style = cx.text_style();
+        text_style.color = theme.colors().editor_foreground;
+        text_style.font_family = settings.buffer_font.family.clone();
+        text_style.font_features = settings.buffer_font.features.clone();
+        text_style.font_weight = settings.buffer_font.weight;
+        text_style.font_style = settings.buffer_font.style;
+        text_style.font_size = match self.size {
+            SyntaxPreviewSize::Compact => rems(0.75).into(),
+            SyntaxPreviewSize::Full => settings.buffer_font_size(cx).into(),
+        };
+        text_style.white_space = WhiteSpace::Nowrap;
+
+        div()
+            .overflow_hidden()
+            .rounded_md()
+            .border_1()
+            .border_color(theme.colors().border)
+            .bg(theme.colors().editor_background)
+            .map(|this| match self.size {
+                SyntaxPreviewSize::Compact => this.px_2().py_1(),
+                SyntaxPreviewSize::Full => this.px_3().py_2(),
+            })
+            .child(StyledText::new(text).with_highlights(&text_style, highlights))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::hsla;
+
+    use super::*;
+
+    #[test]
+    fn test_highlighted_sample() {
+        let keyword = HighlightStyle {
+            color: Some(hsla(0.8, 0.5, 0.5, 1.)),
+            ..Default::default()
+        };
+        let syntax = SyntaxTheme {
+            highlights: vec![("keyword".into(), keyword)],
+        };
+
+        let (text, highlights) = highlighted_sample(&syntax);
+        assert!(text.starts_with("// Counts the words on each line.\nfn word_counts("));
+        assert_eq!(
+            highlights.len(),
+            SAMPLE.iter().filter(|(_, h)| h.is_some()).count()
+        );
+
+        let keywords = highlights
+            .iter()
+            .filter(|(_, style)| *style == keyword)
+            .map(|(range, _)| &text[range.clone()])
+            .collect::<Vec<_>>();
+        assert_eq!(keywords, ["fn", "let"]);
+    }
+}
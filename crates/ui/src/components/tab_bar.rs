@@ -1,8 +1,13 @@
-use gpui::{AnyElement, ScrollHandle};
+use gpui::{AnyElement, Hsla, ScrollHandle};
 use smallvec::SmallVec;
 
 use crate::prelude::*;
 
+/// The opacity of each slice of the fade drawn over a scrolled edge of the
+/// tabs, starting from the edge.
+const EDGE_FADE_STEPS: [f32; 4] = [0.9, 0.65, 0.4, 0.15];
+const EDGE_FADE_STEP_WIDTH: Pixels = Pixels(4.);
+
 #[derive(IntoElement)]
 pub struct TabBar {
     id: ElementId,
@@ -80,6 +85,40 @@ impl TabBar {
         );
         self
     }
+
+    /// Returns whether the tracked tabs are scrolled past their start and end
+    /// edges, respectively.
+    fn overflowing_edges(&self) -> (bool, bool) {
+        let Some(scroll_handle) = self.scroll_handle.as_ref() else {
+            return (false, false);
+        };
+
+        let bounds = scroll_handle.bounds();
+        let offset = scroll_handle.offset().x;
+        let content_end = (0..)
+            .map_while(|ix| scroll_handle.bounds_for_item(ix))
+            .last()
+            .map_or(bounds.right(), |last_tab| last_tab.right() + offset);
+        (offset < Pixels::ZERO, content_end > bounds.right() + px(1.))
+    }
+}
+
+fn render_edge_fade(at_start: bool, cx: &WindowContext) -> impl IntoElement {
+    let background = cx.theme().colors().tab_bar_background;
+    let slices = EDGE_FADE_STEPS.iter().map(|opacity| {
+        div().h_full().w(EDGE_FADE_STEP_WIDTH).bg(Hsla {
+            a: background.a * opacity,
+            ..background
+        })
+    });
+
+    h_flex().absolute().top_0().bottom_px().map(|this| {
+        if at_start {
+            this.left_0().children(slices)
+        } else {
+            this.right_0().children(slices.rev())
+        }
+    })
 }
 
 impl ParentElement for TabBar {
@@ -90,6 +129,8 @@ impl ParentElement for TabBar {
 
 impl RenderOnce for TabBar {
     fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let (overflows_start, overflows_end) = self.overflowing_edges();
+
         div()
             .id(self.id)
             .group("tab_bar")
@@ -138,7 +179,13 @@ impl RenderOnce for TabBar {
                                 cx.track_scroll(&scroll_handle)
                             })
                             .children(self.children),
-                    ),
+                    )
+                    .when(overflows_start, |this| {
+                        this.child(render_edge_fade(true, cx))
+                    })
+                    .when(overflows_end, |this| {
+                        this.child(render_edge_fade(false, cx))
+                    }),
             )
             .when(!self.end_children.is_empty(), |this| {
                 this.child(
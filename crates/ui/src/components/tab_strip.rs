@@ -0,0 +1,416 @@
+use std::rc::Rc;
+
+use gpui::{MouseButton, Render, ScrollHandle, VisualContext};
+
+use crate::{prelude::*, IconButtonShape, Indicator, Tab, TabBar, TabPosition};
+
+/// A tab displayed by a [`TabStrip`].
+#[derive(Debug, Clone)]
+pub struct TabStripItem {
+    label: SharedString,
+    icon: Option<IconName>,
+    dirty: bool,
+    preview: bool,
+}
+
+impl TabStripItem {
+    pub fn new(label: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            icon: None,
+            dirty: false,
+            preview: false,
+        }
+    }
+
+    pub fn icon(mut self, icon: impl Into<Option<IconName>>) -> Self {
+        self.icon = icon.into();
+        self
+    }
+
+    /// Marks the tab as having unsaved changes, which is indicated by a dot
+    /// that turns into the close button when the tab is hovered.
+    pub fn dirty(mut self, dirty: bool) -> Self {
+        self.dirty = dirty;
+        self
+    }
+
+    /// Marks the tab as a preview tab, which is displayed in italics.
+    pub fn preview(mut self, preview: bool) -> Self {
+        self.preview = preview;
+        self
+    }
+}
+
+#[derive(Clone)]
+struct DraggedStripTab {
+    strip_id: ElementId,
+    ix: usize,
+    item: TabStripItem,
+}
+
+impl Render for DraggedStripTab {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .h(rems(Tab::CONTAINER_HEIGHT_IN_REMS))
+            .px_2()
+            .gap_1()
+            .bg(cx.theme().colors().tab_active_background)
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .children(
+                self.item
+                    .icon
+                    .map(|icon| Icon::new(icon).size(IconSize::XSmall)),
+            )
+            .child(Label::new(self.item.label.clone()).italic(self.item.preview))
+    }
+}
+
+/// Returns the index a tab dragged from `from_ix` ends up at when it is
+/// dropped before the tab at `insertion_ix`, or `None` if it doesn't move.
+fn reorder_target(from_ix: usize, insertion_ix: usize) -> Option<usize> {
+    let to_ix = if insertion_ix > from_ix {
+        insertion_ix - 1
+    } else {
+        insertion_ix
+    };
+    (to_ix != from_ix).then_some(to_ix)
+}
+
+/// A [`TabBar`] whose tabs are described by a list of [`TabStripItem`]s.
+///
+/// The strip doesn't hold on to any state: the caller passes in the current
+/// tabs every time it renders, and applies the changes reported through
+/// [`TabStrip::on_activate`], [`TabStrip::on_close`] and
+/// [`TabStrip::on_reorder`] to its own list.
+///
+/// Tabs can be closed with their close button or with a middle click, and
+/// reordered by dragging them. When the tabs overflow, the strip scrolls
+/// horizontally.
+#[derive(IntoElement)]
+pub struct TabStrip {
+    id: ElementId,
+    items: Vec<TabStripItem>,
+    active_index: usize,
+    scroll_handle: Option<ScrollHandle>,
+    on_activate: Option<Rc<dyn Fn(usize, &mut WindowContext)>>,
+    on_close: Option<Rc<dyn Fn(usize, &mut WindowContext)>>,
+    on_reorder: Option<Rc<dyn Fn(usize, usize, &mut WindowContext)>>,
+}
+
+impl TabStrip {
+    pub fn new(id: impl Into<ElementId>, items: impl IntoIterator<Item = TabStripItem>) -> Self {
+        Self {
+            id: id.into(),
+            items: items.into_iter().collect(),
+            active_index: 0,
+            scroll_handle: None,
+            on_activate: None,
+            on_close: None,
+            on_reorder: None,
+        }
+    }
+
+    pub fn active_index(mut self, active_index: usize) -> Self {
+        self.active_index = active_index;
+        self
+    }
+
+    /// Tracks the scroll position of the tabs with the given handle, which
+    /// needs to outlive the strip for the scroll position to be preserved
+    /// between frames.
+    pub fn track_scroll(mut self, scroll_handle: ScrollHandle) -> Self {
+        self.scroll_handle = Some(scroll_handle);
+        self
+    }
+
+    /// Sets the handler invoked with the index of a tab when it is clicked.
+    pub fn on_activate(mut self, handler: impl Fn(usize, &mut WindowContext) + 'static) -> Self {
+        self.on_activate = Some(Rc::new(handler));
+        self
+    }
+
+    /// Sets the handler invoked with the index of a tab when it is closed.
+    ///
+    /// Tabs only have a close button when this handler is set.
+    pub fn on_close(mut self, handler: impl Fn(usize, &mut WindowContext) + 'static) -> Self {
+        self.on_close = Some(Rc::new(handler));
+        self
+    }
+
+    /// Sets the handler invoked with the previous and new index of a tab when
+    /// it is dragged to another position.
+    ///
+    /// Tabs can only be dragged when this handler is set.
+    pub fn on_reorder(
+        mut self,
+        handler: impl Fn(usize, usize, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_reorder = Some(Rc::new(handler));
+        self
+    }
+
+    fn render_tab(&self, ix: usize, item: TabStripItem, cx: &WindowContext) -> Tab {
+        let is_active = ix == self.active_index;
+        let label_color = if is_active {
+            Color::Default
+        } else {
+            Color::Muted
+        };
+
+        Tab::new(ix)
+            .position(if ix == 0 {
+                TabPosition::First
+            } else if ix == self.items.len() - 1 {
+                TabPosition::Last
+            } else {
+                TabPosition::Middle(ix.cmp(&self.active_index))
+            })
+            .selected(is_active)
+            .when_some(self.on_activate.clone(), |tab, on_activate| {
+                tab.on_click(move |_, cx| on_activate(ix, cx))
+            })
+            .when_some(self.on_close.clone(), |tab, on_close| {
+                tab.on_mouse_down(MouseButton::Middle, {
+                    let on_close = on_close.clone();
+                    move |_, cx| on_close(ix, cx)
+                })
+                .end_slot(
+                    IconButton::new("close tab", IconName::Close)
+                        .shape(IconButtonShape::Square)
+                        .icon_color(Color::Muted)
+                        .size(ButtonSize::None)
+                        .icon_size(IconSize::XSmall)
+                        .on_click(move |_, cx| {
+                            cx.stop_propagation();
+                            on_close(ix, cx)
+                        }),
+                )
+            })
+            .when_some(self.on_reorder.clone(), |tab, on_reorder| {
+                tab.on_drag(
+                    DraggedStripTab {
+                        strip_id: self.id.clone(),
+                        ix,
+                        item: item.clone(),
+                    },
+                    |tab, cx| cx.new_view(|_| tab.clone()),
+                )
+                .child(self.render_drop_zone(ix, true, on_reorder.clone(), cx))
+                .child(self.render_drop_zone(ix, false, on_reorder, cx))
+            })
+            .start_slot::<Icon>(
+                item.icon
+                    .map(|icon| Icon::new(icon).size(IconSize::XSmall).color(label_color)),
+            )
+            .when(item.dirty, |tab| {
+                tab.child(
+                    h_flex()
+                        .size_3()
+                        .justify_center()
+                        .absolute()
+                        .right(Spacing::Small.rems(cx))
+                        // Make room for the close button when the tab is hovered.
+                        .when(self.on_close.is_some(), |this| {
+                            this.group_hover("", |style| style.invisible())
+                        })
+                        .child(Indicator::dot().color(Color::Accent)),
+                )
+            })
+            .child(
+                Label::new(item.label)
+                    .color(label_color)
+                    .italic(item.preview),
+            )
+    }
+
+    /// Renders the half of the tab at `ix` on the given side, which accepts
+    /// tabs dragged from this strip and displays a drop indicator along that
+    /// side of the tab while one is dragged over it.
+    fn render_drop_zone(
+        &self,
+        ix: usize,
+        before: bool,
+        on_reorder: Rc<dyn Fn(usize, usize, &mut WindowContext)>,
+        cx: &WindowContext,
+    ) -> impl IntoElement {
+        let insertion_ix = if before { ix } else { ix + 1 };
+        let strip_id = self.id.clone();
+        let target_ix = move |dragged: &DraggedStripTab| {
+            if dragged.strip_id == strip_id {
+                reorder_target(dragged.ix, insertion_ix)
+            } else {
+                None
+            }
+        };
+        let indicator_color = cx.theme().colors().border_focused;
+
+        div()
+            .absolute()
+            .top_0()
+            .bottom_0()
+            .w_1_2()
+            .map(|this| {
+                if before {
+                    this.left_0()
+                } else {
+                    this.right_0()
+                }
+            })
+            .drag_over::<DraggedStripTab>({
+                let target_ix = target_ix.clone();
+                move |style, dragged, _| {
+                    if target_ix(dragged).is_none() {
+                        return style;
+                    }
+                    let style = if before {
+                        style.border_l_2()
+                    } else {
+                        style.border_r_2()
+                    };
+                    style.border_color(indicator_color)
+                }
+            })
+            .on_drop(move |dragged: &DraggedStripTab, cx| {
+                if let Some(to_ix) = target_ix(dragged) {
+                    on_reorder(dragged.ix, to_ix, cx);
+                }
+            })
+    }
+}
+
+impl RenderOnce for TabStrip {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let tabs = self
+            .items
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(ix, item)| self.render_tab(ix, item, cx))
+            .collect::<Vec<_>>();
+
+        TabBar::new(self.id)
+            .when_some(self.scroll_handle, |tab_bar, scroll_handle| {
+                tab_bar.track_scroll(scroll_handle)
+            })
+            .children(tabs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use gpui::{point, px, Modifiers, Point, TestAppContext, VisualTestContext};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum TabEvent {
+        Activate(usize),
+        Close(usize),
+        Reorder(usize, usize),
+    }
+
+    struct TabStripTest {
+        events: Rc<RefCell<Vec<TabEvent>>>,
+    }
+
+    impl Render for TabStripTest {
+        fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+            let record = |event: fn(usize) -> TabEvent| {
+                let events = self.events.clone();
+                move |ix, _: &mut WindowContext| events.borrow_mut().push(event(ix))
+            };
+            let events = self.events.clone();
+
+            TabStrip::new(
+                "tabs",
+                ["one.rs", "two.rs", "three.rs"].map(TabStripItem::new),
+            )
+            .on_activate(record(TabEvent::Activate))
+            .on_close(record(TabEvent::Close))
+            .on_reorder(move |from_ix, to_ix, _| {
+                events.borrow_mut().push(TabEvent::Reorder(from_ix, to_ix))
+            })
+        }
+    }
+
+    #[gpui::test]
+    fn test_tab_strip_events(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let (_, cx) = cx.add_window_view(|_| TabStripTest {
+            events: events.clone(),
+        });
+
+        let second_tab = cx.debug_bounds("TAB-1").unwrap();
+        cx.simulate_click(second_tab.center(), Modifiers::none());
+        assert_eq!(
+            events.borrow_mut().drain(..).collect::<Vec<_>>(),
+            [TabEvent::Activate(1)]
+        );
+
+        // Middle-clicking a tab closes it.
+        let third_tab = cx.debug_bounds("TAB-2").unwrap();
+        cx.simulate_mouse_down(third_tab.center(), MouseButton::Middle, Modifiers::none());
+        cx.simulate_mouse_up(third_tab.center(), MouseButton::Middle, Modifiers::none());
+        assert_eq!(
+            events.borrow_mut().drain(..).collect::<Vec<_>>(),
+            [TabEvent::Close(2)]
+        );
+
+        // Dropping a tab on the end of another one moves it after that tab.
+        let first_tab = cx.debug_bounds("TAB-0").unwrap();
+        drag(
+            cx,
+            first_tab.center(),
+            point(third_tab.right() - px(2.), third_tab.center().y),
+        );
+        assert_eq!(
+            events.borrow_mut().drain(..).collect::<Vec<_>>(),
+            [TabEvent::Reorder(0, 2)]
+        );
+
+        // Dropping a tab next to itself doesn't move it.
+        drag(
+            cx,
+            third_tab.center(),
+            point(third_tab.left() + px(2.), third_tab.center().y),
+        );
+        drag(
+            cx,
+            second_tab.center(),
+            point(first_tab.right() - px(2.), first_tab.center().y),
+        );
+        assert!(events.borrow().is_empty());
+
+        drag(
+            cx,
+            third_tab.center(),
+            point(first_tab.left() + px(2.), first_tab.center().y),
+        );
+        assert_eq!(
+            events.borrow_mut().drain(..).collect::<Vec<_>>(),
+            [TabEvent::Reorder(2, 0)]
+        );
+    }
+
+    fn drag(cx: &mut VisualTestContext, from: Point<Pixels>, to: Point<Pixels>) {
+        cx.simulate_mouse_down(from, MouseButton::Left, Modifiers::none());
+        // Move past the drag threshold so the drag begins.
+        cx.simulate_mouse_move(
+            from + point(px(0.), px(5.)),
+            MouseButton::Left,
+            Modifiers::none(),
+        );
+        cx.simulate_mouse_move(to, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(to, MouseButton::Left, Modifiers::none());
+    }
+}
@@ -0,0 +1,546 @@
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use gpui::{ease_in_out, Animation, AnimationExt, AnyElement, Render, Task, View, VisualContext};
+
+use crate::{prelude::*, Tooltip};
+
+const ENTER_DURATION: Duration = Duration::from_millis(150);
+const EXIT_DURATION: Duration = Duration::from_millis(150);
+
+/// The severity of a [`Toast`], which determines its icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToastSeverity {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastSeverity {
+    fn icon(self) -> IconName {
+        match self {
+            ToastSeverity::Info => IconName::Bell,
+            ToastSeverity::Success => IconName::Check,
+            ToastSeverity::Warning => IconName::ExclamationTriangle,
+            ToastSeverity::Error => IconName::XCircle,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            ToastSeverity::Info => Color::Info,
+            ToastSeverity::Success => Color::Success,
+            ToastSeverity::Warning => Color::Warning,
+            ToastSeverity::Error => Color::Error,
+        }
+    }
+}
+
+/// Why a [`Toast`] was dismissed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastDismissReason {
+    /// The toast's timeout elapsed.
+    Timeout,
+    /// The toast was closed by the user or by its owner.
+    Closed,
+    /// One of the toast's actions was clicked, identified by its index.
+    Action(usize),
+}
+
+/// Identifies a toast shown in a [`ToastLayer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ToastId(usize);
+
+struct ToastAction {
+    label: SharedString,
+    handler: Rc<dyn Fn(&mut WindowContext)>,
+}
+
+/// A transient notification displayed by a [`ToastLayer`].
+pub struct Toast {
+    severity: ToastSeverity,
+    message: SharedString,
+    actions: Vec<ToastAction>,
+    timeout: Option<Duration>,
+    on_dismiss: Option<Rc<dyn Fn(ToastDismissReason, &mut WindowContext)>>,
+}
+
+impl Toast {
+    pub fn new(severity: ToastSeverity, message: impl Into<SharedString>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            actions: Vec::new(),
+            timeout: None,
+            on_dismiss: None,
+        }
+    }
+
+    pub fn info(message: impl Into<SharedString>) -> Self {
+        Self::new(ToastSeverity::Info, message)
+    }
+
+    pub fn success(message: impl Into<SharedString>) -> Self {
+        Self::new(ToastSeverity::Success, message)
+    }
+
+    pub fn warning(message: impl Into<SharedString>) -> Self {
+        Self::new(ToastSeverity::Warning, message)
+    }
+
+    pub fn error(message: impl Into<SharedString>) -> Self {
+        Self::new(ToastSeverity::Error, message)
+    }
+
+    /// Adds a button that invokes the given handler and then dismisses the toast.
+    pub fn action(
+        mut self,
+        label: impl Into<SharedString>,
+        handler: impl Fn(&mut WindowContext) + 'static,
+    ) -> Self {
+        self.actions.push(ToastAction {
+            label: label.into(),
+            handler: Rc::new(handler),
+        });
+        self
+    }
+
+    /// Dismisses the toast once it has been displayed for the given duration,
+    /// not counting the time during which it is hovered.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the handler invoked when the toast is dismissed, for any reason.
+    pub fn on_dismiss(
+        mut self,
+        handler: impl Fn(ToastDismissReason, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.on_dismiss = Some(Rc::new(handler));
+        self
+    }
+}
+
+struct ActiveToast {
+    id: ToastId,
+    toast: Toast,
+    remaining: Option<Duration>,
+    timer_started_at: Option<Instant>,
+    timer: Option<Task<()>>,
+    dismissing: bool,
+}
+
+/// A stack of [`Toast`]s, meant to be displayed above the status bar.
+///
+/// At most `max_visible` toasts are displayed at once, with the most recent
+/// at the bottom. Older toasts are collapsed into a "+N more" button, which
+/// expands the stack when clicked.
+pub struct ToastLayer {
+    toasts: Vec<ActiveToast>,
+    next_toast_id: usize,
+    max_visible: usize,
+    expanded: bool,
+}
+
+impl ToastLayer {
+    pub fn new(_cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            toasts: Vec::new(),
+            next_toast_id: 0,
+            max_visible: 3,
+            expanded: false,
+        }
+    }
+
+    pub fn build(cx: &mut WindowContext) -> View<Self> {
+        cx.new_view(Self::new)
+    }
+
+    /// Sets the number of toasts displayed before older ones are collapsed.
+    pub fn set_max_visible(&mut self, max_visible: usize, cx: &mut ViewContext<Self>) {
+        self.max_visible = max_visible.max(1);
+        cx.notify();
+    }
+
+    pub fn push(&mut self, toast: Toast, cx: &mut ViewContext<Self>) -> ToastId {
+        let id = ToastId(self.next_toast_id);
+        self.next_toast_id += 1;
+        self.toasts.push(ActiveToast {
+            id,
+            remaining: toast.timeout,
+            toast,
+            timer_started_at: None,
+            timer: None,
+            dismissing: false,
+        });
+        self.start_timer(id, cx);
+        cx.notify();
+        id
+    }
+
+    /// Closes the toast with the given id, notifying its owner.
+    pub fn dismiss(&mut self, id: ToastId, cx: &mut ViewContext<Self>) {
+        self.dismiss_with_reason(id, ToastDismissReason::Closed, cx);
+    }
+
+    /// Returns the ids of the toasts that haven't been dismissed, from oldest to newest.
+    pub fn toast_ids(&self) -> Vec<ToastId> {
+        self.toasts
+            .iter()
+            .filter(|toast| !toast.dismissing)
+            .map(|toast| toast.id)
+            .collect()
+    }
+
+    fn toast_mut(&mut self, id: ToastId) -> Option<&mut ActiveToast> {
+        self.toasts
+            .iter_mut()
+            .find(|toast| toast.id == id && !toast.dismissing)
+    }
+
+    fn start_timer(&mut self, id: ToastId, cx: &mut ViewContext<Self>) {
+        let now = cx.background_executor().now();
+        let Some(toast) = self.toast_mut(id) else {
+            return;
+        };
+        let Some(remaining) = toast.remaining else {
+            return;
+        };
+
+        toast.timer_started_at = Some(now);
+        toast.timer = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(remaining).await;
+            this.update(&mut cx, |this, cx| {
+                this.dismiss_with_reason(id, ToastDismissReason::Timeout, cx)
+            })
+            .ok();
+        }));
+    }
+
+    /// Pauses the toast's timeout while it is hovered, and resumes it with the
+    /// time that was left once the mouse leaves.
+    fn set_hovered(&mut self, id: ToastId, hovered: bool, cx: &mut ViewContext<Self>) {
+        if hovered {
+            let now = cx.background_executor().now();
+            let Some(toast) = self.toast_mut(id) else {
+                return;
+            };
+            if let (Some(remaining), Some(started_at)) =
+                (toast.remaining, toast.timer_started_at.take())
+            {
+                toast.remaining = Some(remaining.saturating_sub(now - started_at));
+                toast.timer = None;
+            }
+        } else if self
+            .toast_mut(id)
+            .map_or(false, |toast| toast.timer.is_none())
+        {
+            self.start_timer(id, cx);
+        }
+    }
+
+    fn dismiss_with_reason(
+        &mut self,
+        id: ToastId,
+        reason: ToastDismissReason,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(toast) = self.toast_mut(id) else {
+            return;
+        };
+        toast.dismissing = true;
+        toast.timer = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(EXIT_DURATION).await;
+            this.update(&mut cx, |this, cx| {
+                this.toasts.retain(|toast| toast.id != id);
+                if this.toasts.len() <= this.max_visible {
+                    this.expanded = false;
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+
+        // The owner may update this layer in response, so notify it outside of this update.
+        if let Some(on_dismiss) = toast.toast.on_dismiss.clone() {
+            WindowContext::defer(cx, move |cx| on_dismiss(reason, cx));
+        }
+        cx.notify();
+    }
+
+    fn handle_action(&mut self, id: ToastId, action_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(handler) = self
+            .toast_mut(id)
+            .and_then(|toast| toast.toast.actions.get(action_ix))
+            .map(|action| action.handler.clone())
+        else {
+            return;
+        };
+
+        self.dismiss_with_reason(id, ToastDismissReason::Action(action_ix), cx);
+        handler(cx);
+    }
+
+    fn render_toast(&self, toast: &ActiveToast, cx: &mut ViewContext<Self>) -> AnyElement {
+        let id = toast.id;
+        let severity = toast.toast.severity;
+
+        let element = v_flex()
+            .id(("toast", id.0))
+            .w_full()
+            .p_2()
+            .gap_2()
+            .elevation_3(cx)
+            .on_hover(cx.listener(move |this, hovered, cx| this.set_hovered(id, *hovered, cx)))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_start()
+                    .child(
+                        Icon::new(severity.icon())
+                            .size(IconSize::Small)
+                            .color(severity.color()),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(Label::new(toast.toast.message.clone()).size(LabelSize::Small)),
+                    )
+                    .child(
+                        IconButton::new(("close-toast", id.0), IconName::Close)
+                            .icon_size(IconSize::Small)
+                            .icon_color(Color::Muted)
+                            .tooltip(|cx| Tooltip::text("Close", cx))
+                            .on_click(cx.listener(move |this, _, cx| this.dismiss(id, cx))),
+                    ),
+            )
+            .when(!toast.toast.actions.is_empty(), |this| {
+                this.child(h_flex().justify_end().gap_1().children(
+                    toast.toast.actions.iter().enumerate().map(|(ix, action)| {
+                        Button::new(("toast-action", ix), action.label.clone())
+                            .label_size(LabelSize::Small)
+                            .on_click(
+                                cx.listener(move |this, _, cx| this.handle_action(id, ix, cx)),
+                            )
+                    }),
+                ))
+            });
+
+        if toast.dismissing {
+            element
+                .with_animation(
+                    ("toast-exit", id.0),
+                    Animation::new(EXIT_DURATION).with_easing(ease_in_out),
+                    |toast, delta| toast.opacity(1. - delta),
+                )
+                .into_any_element()
+        } else {
+            element
+                .with_animation(
+                    ("toast-enter", id.0),
+                    Animation::new(ENTER_DURATION).with_easing(ease_in_out),
+                    |toast, delta| toast.opacity(delta).mt(px(8. * (1. - delta))),
+                )
+                .into_any_element()
+        }
+    }
+}
+
+impl Render for ToastLayer {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        if self.toasts.is_empty() {
+            return div();
+        }
+
+        let hidden_count = if self.expanded {
+            0
+        } else {
+            self.toasts.len().saturating_sub(self.max_visible)
+        };
+        let toasts = self.toasts[hidden_count..]
+            .iter()
+            .map(|toast| self.render_toast(toast, cx))
+            .collect::<Vec<_>>();
+
+        div()
+            .absolute()
+            .right_3()
+            .bottom_3()
+            .w_96()
+            .flex()
+            .flex_col()
+            .justify_end()
+            .gap_2()
+            .when(hidden_count > 0, |this| {
+                this.child(
+                    h_flex().justify_end().child(
+                        Button::new("expand-toasts", format!("+{hidden_count} more"))
+                            .label_size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.expanded = true;
+                                cx.notify();
+                            })),
+                    ),
+                )
+            })
+            .when(self.expanded, |this| {
+                this.child(
+                    h_flex().justify_end().child(
+                        Button::new("collapse-toasts", "Show less")
+                            .label_size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.expanded = false;
+                                cx.notify();
+                            })),
+                    ),
+                )
+            })
+            .children(toasts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use gpui::{TestAppContext, VisualTestContext};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_toast_timeout_pauses_on_hover(cx: &mut TestAppContext) {
+        let (layer, cx) = init_test(cx);
+        let dismissals = Rc::new(RefCell::new(Vec::new()));
+
+        let id = layer.update(cx, |layer, cx| {
+            layer.push(
+                Toast::info("Saved")
+                    .timeout(Duration::from_secs(5))
+                    .on_dismiss(record_dismissals(&dismissals, "saved")),
+                cx,
+            )
+        });
+
+        cx.executor().advance_clock(Duration::from_secs(3));
+        layer.update(cx, |layer, cx| layer.set_hovered(id, true, cx));
+
+        // The timeout doesn't elapse while the toast is hovered.
+        cx.executor().advance_clock(Duration::from_secs(10));
+        layer.update(cx, |layer, _| assert_eq!(layer.toast_ids(), vec![id]));
+
+        layer.update(cx, |layer, cx| layer.set_hovered(id, false, cx));
+        cx.executor().advance_clock(Duration::from_millis(1900));
+        layer.update(cx, |layer, _| assert_eq!(layer.toast_ids(), vec![id]));
+        assert!(dismissals.borrow().is_empty());
+
+        cx.executor().advance_clock(Duration::from_millis(100));
+        cx.run_until_parked();
+        layer.update(cx, |layer, _| assert!(layer.toast_ids().is_empty()));
+        assert_eq!(
+            dismissals.borrow().as_slice(),
+            &[("saved", ToastDismissReason::Timeout)]
+        );
+
+        // The toast is removed once it has animated out.
+        cx.executor().advance_clock(EXIT_DURATION);
+        layer.update(cx, |layer, _| assert!(layer.toasts.is_empty()));
+    }
+
+    #[gpui::test]
+    fn test_toast_dismissal_reasons(cx: &mut TestAppContext) {
+        let (layer, cx) = init_test(cx);
+        let dismissals = Rc::new(RefCell::new(Vec::new()));
+        let retried = Rc::new(RefCell::new(false));
+
+        let (closed, retry) = layer.update(cx, |layer, cx| {
+            let closed = layer.push(
+                Toast::warning("Disk almost full")
+                    .on_dismiss(record_dismissals(&dismissals, "disk")),
+                cx,
+            );
+            let retry = layer.push(
+                Toast::error("Failed to connect")
+                    .action("Dismiss", |_| {})
+                    .action("Retry", {
+                        let retried = retried.clone();
+                        move |_| *retried.borrow_mut() = true
+                    })
+                    .on_dismiss(record_dismissals(&dismissals, "connect")),
+                cx,
+            );
+            (closed, retry)
+        });
+
+        layer.update(cx, |layer, cx| {
+            layer.dismiss(closed, cx);
+            layer.handle_action(retry, 1, cx);
+            // Dismissing a toast a second time has no effect.
+            layer.dismiss(retry, cx);
+        });
+        cx.run_until_parked();
+
+        assert!(*retried.borrow());
+        assert_eq!(
+            dismissals.borrow().as_slice(),
+            &[
+                ("disk", ToastDismissReason::Closed),
+                ("connect", ToastDismissReason::Action(1))
+            ]
+        );
+    }
+
+    #[gpui::test]
+    fn test_toast_layer_collapses_older_toasts(cx: &mut TestAppContext) {
+        let (layer, cx) = init_test(cx);
+
+        layer.update(cx, |layer, cx| {
+            layer.set_max_visible(2, cx);
+            for ix in 0..5 {
+                layer.push(Toast::info(format!("Toast {ix}")), cx);
+            }
+        });
+        cx.run_until_parked();
+
+        layer.update(cx, |layer, cx| {
+            assert_eq!(layer.toast_ids().len(), 5);
+            assert!(!layer.expanded);
+
+            layer.expanded = true;
+            for id in layer.toast_ids().into_iter().take(3) {
+                layer.dismiss(id, cx);
+            }
+        });
+
+        // Once the remaining toasts fit, the stack collapses again.
+        cx.executor().advance_clock(EXIT_DURATION);
+        layer.update(cx, |layer, _| {
+            assert_eq!(layer.toast_ids().len(), 2);
+            assert!(!layer.expanded);
+        });
+    }
+
+    fn record_dismissals(
+        dismissals: &Rc<RefCell<Vec<(&'static str, ToastDismissReason)>>>,
+        name: &'static str,
+    ) -> impl Fn(ToastDismissReason, &mut WindowContext) + 'static {
+        let dismissals = dismissals.clone();
+        move |reason, _| dismissals.borrow_mut().push((name, reason))
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> (View<ToastLayer>, &mut VisualTestContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+        cx.add_window_view(ToastLayer::new)
+    }
+}
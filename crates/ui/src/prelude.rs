@@ -15,7 +15,9 @@ pub use crate::styles::{rems_from_px, vh, vw, PlatformStyle, StyledTypography, T
 pub use crate::visible_on_hover::*;
 pub use crate::Spacing;
 pub use crate::{h_flex, v_flex};
-pub use crate::{Button, ButtonSize, ButtonStyle, IconButton, SelectableButton};
+pub use crate::{
+    Button, ButtonSize, ButtonStyle, ButtonVariant, IconButton, InteractionState, SelectableButton,
+};
 pub use crate::{ButtonCommon, Color, StyledExt};
 pub use crate::{Headline, HeadlineSize};
 pub use crate::{Icon, IconName, IconPosition, IconSize};
@@ -8,13 +8,13 @@ fn elevated<E: Styled>(this: E, cx: &mut WindowContext, index: ElevationIndex) -
         .rounded_lg()
         .border_1()
         .border_color(cx.theme().colors().border_variant)
-        .shadow(index.shadow())
+        .shadow(index.shadow(cx))
 }
 
 fn elevated_borderless<E: Styled>(this: E, cx: &mut WindowContext, index: ElevationIndex) -> E {
     this.bg(cx.theme().colors().elevated_surface_background)
         .rounded_lg()
-        .shadow(index.shadow())
+        .shadow(index.shadow(cx))
 }
 
 /// Extends [`gpui::Styled`] with Zed-specific styling methods.
@@ -85,6 +85,14 @@ pub trait StyledExt: Styled + Sized {
         elevated_borderless(self, cx, ElevationIndex::ModalSurface)
     }
 
+    /// Casts the drop shadows of the given elevation, in the theme's shadow color, without
+    /// changing the element's background or border.
+    ///
+    /// Sets `shadow()`
+    fn elevation_shadow(self, index: ElevationIndex, cx: &WindowContext) -> Self {
+        self.shadow(index.shadow(cx))
+    }
+
     /// The theme's primary border color.
     fn border_primary(self, cx: &mut WindowContext) -> Self {
         self.border_color(cx.theme().colors().border)
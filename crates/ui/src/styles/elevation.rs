@@ -1,5 +1,6 @@
-use gpui::{hsla, point, px, BoxShadow};
+use gpui::{point, px, BoxShadow, Hsla, WindowContext};
 use smallvec::{smallvec, SmallVec};
+use theme::ActiveTheme;
 
 #[doc = include_str!("docs/elevation.md")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -26,43 +27,48 @@ pub enum ElevationIndex {
 }
 
 impl ElevationIndex {
-    pub fn shadow(self) -> SmallVec<[BoxShadow; 2]> {
+    /// Returns the drop shadows of surfaces at this elevation, in the shadow color of the current
+    /// theme.
+    pub fn shadow(self, cx: &WindowContext) -> SmallVec<[BoxShadow; 2]> {
+        self.shadow_with_color(cx.theme().colors().elevated_surface_shadow)
+    }
+
+    /// Returns the drop shadows of surfaces at this elevation, the most opaque of which has the
+    /// given color. Higher surfaces cast larger, softer shadows.
+    pub fn shadow_with_color(self, color: Hsla) -> SmallVec<[BoxShadow; 2]> {
         match self {
-            ElevationIndex::Surface => smallvec![],
+            ElevationIndex::Background | ElevationIndex::Surface | ElevationIndex::Wash => {
+                smallvec![]
+            }
 
-            ElevationIndex::ElevatedSurface => smallvec![BoxShadow {
-                color: hsla(0., 0., 0., 0.12),
-                offset: point(px(0.), px(2.)),
-                blur_radius: px(3.),
-                spread_radius: px(0.),
-            }],
+            ElevationIndex::ElevatedSurface => smallvec![drop_shadow(color, 1., 2., 3.)],
 
             ElevationIndex::ModalSurface => smallvec![
-                BoxShadow {
-                    color: hsla(0., 0., 0., 0.12),
-                    offset: point(px(0.), px(2.)),
-                    blur_radius: px(3.),
-                    spread_radius: px(0.),
-                },
-                BoxShadow {
-                    color: hsla(0., 0., 0., 0.08),
-                    offset: point(px(0.), px(3.)),
-                    blur_radius: px(6.),
-                    spread_radius: px(0.),
-                },
-                BoxShadow {
-                    color: hsla(0., 0., 0., 0.04),
-                    offset: point(px(0.), px(6.)),
-                    blur_radius: px(12.),
-                    spread_radius: px(0.),
-                },
+                drop_shadow(color, 1., 2., 3.),
+                drop_shadow(color, 2. / 3., 3., 6.),
+                drop_shadow(color, 1. / 3., 6., 12.),
             ],
 
-            _ => smallvec![],
+            ElevationIndex::DraggedElement => smallvec![
+                drop_shadow(color, 1., 4., 8.),
+                drop_shadow(color, 0.5, 12., 24.),
+            ],
         }
     }
 }
 
+fn drop_shadow(color: Hsla, opacity: f32, offset_y: f32, blur_radius: f32) -> BoxShadow {
+    BoxShadow {
+        color: Hsla {
+            a: color.a * opacity,
+            ..color
+        },
+        offset: point(px(0.), px(offset_y)),
+        blur_radius: px(blur_radius),
+        spread_radius: px(0.),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LayerIndex {
     BehindElement,
@@ -80,3 +86,43 @@ pub enum ElementIndex {
     Content,
     Overlay,
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::hsla;
+
+    use super::*;
+
+    #[test]
+    fn test_elevation_shadows() {
+        let color = hsla(0., 0., 0., 0.3);
+        for index in [
+            ElevationIndex::Background,
+            ElevationIndex::Surface,
+            ElevationIndex::Wash,
+        ] {
+            assert!(index.shadow_with_color(color).is_empty());
+        }
+
+        let elevated = ElevationIndex::ElevatedSurface.shadow_with_color(color);
+        assert_eq!(elevated.len(), 1);
+        assert_eq!(elevated[0].color, color);
+        assert_eq!(elevated[0].offset, point(px(0.), px(2.)));
+        assert_eq!(elevated[0].blur_radius, px(3.));
+        assert_eq!(elevated[0].spread_radius, px(0.));
+
+        // Each lower shadow of a modal is larger, softer and more transparent.
+        let modal = ElevationIndex::ModalSurface.shadow_with_color(color);
+        assert_eq!(modal.len(), 3);
+        assert_eq!(modal[0].color, color);
+        for (upper, lower) in modal.iter().zip(modal.iter().skip(1)) {
+            assert!(lower.offset.y > upper.offset.y);
+            assert!(lower.blur_radius > upper.blur_radius);
+            assert!(lower.color.a < upper.color.a);
+            assert_eq!(lower.color.h, color.h);
+        }
+
+        let dragged = ElevationIndex::DraggedElement.shadow_with_color(color);
+        assert!(dragged[0].blur_radius > elevated[0].blur_radius);
+    }
+}
@@ -124,6 +124,7 @@ impl Render for TextField {
         let text_style = TextStyle {
             font_family: settings.buffer_font.family.clone(),
             font_features: settings.buffer_font.features.clone(),
+            font_axes: settings.buffer_font.axes.clone(),
             font_size: rems(0.875).into(),
             font_weight: settings.buffer_font.weight,
             font_style: FontStyle::Normal,
@@ -0,0 +1,349 @@
+use editor::Editor;
+use git::{
+    cherry_pick::{CherryPickOutcome, CherryPickProvider},
+    Oid,
+};
+use gpui::{actions, impl_actions, Action, AppContext, PromptLevel, ViewContext};
+use serde::Deserialize;
+use workspace::{notifications::NotificationId, Toast, Workspace};
+
+use crate::file_history::FileHistoryView;
+
+/// Applies the changes introduced by a commit to the current branch.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct CherryPick {
+    /// The hash of the commit to cherry-pick.
+    pub hash: String,
+    /// Stage the changes without committing them, asking for confirmation before committing.
+    #[serde(default)]
+    pub no_commit: bool,
+}
+
+impl_actions!(git, [CherryPick]);
+actions!(git, [AbortCherryPick]);
+
+/// How many lines of the staged diff are shown when confirming a cherry-pick.
+const MAX_PROMPT_DIFF_LINES: usize = 40;
+
+pub(crate) fn cherry_pick(
+    workspace: &mut Workspace,
+    action: &CherryPick,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let hash = match action.hash.parse::<Oid>() {
+        Ok(hash) => hash,
+        Err(error) => {
+            show_toast(
+                workspace,
+                format!("Can't cherry-pick {:?}: {error:#}", action.hash),
+                cx,
+            );
+            return;
+        }
+    };
+    let Some(provider) = cherry_pick_provider(workspace, cx) else {
+        show_toast(
+            workspace,
+            "Can't cherry-pick outside of a git repository",
+            cx,
+        );
+        return;
+    };
+    if provider.is_in_progress() {
+        show_abort_toast(
+            workspace,
+            "Another cherry-pick is in progress. Resolve its conflicts or abort it first.",
+            cx,
+        );
+        return;
+    }
+
+    let no_commit = action.no_commit;
+    let short_hash = hash.display_short();
+    let cherry_pick = provider.cherry_pick(hash, no_commit);
+    cx.spawn(|workspace, mut cx| async move {
+        match cherry_pick.await {
+            Ok(CherryPickOutcome::Committed) => workspace.update(&mut cx, |workspace, cx| {
+                show_toast(workspace, format!("Cherry-picked {short_hash}"), cx)
+            })?,
+            Ok(CherryPickOutcome::Staged) => {
+                let diff = provider.staged_diff().await?;
+                let answer = workspace.update(&mut cx, |_, cx| {
+                    cx.prompt(
+                        PromptLevel::Info,
+                        &format!("Commit the changes cherry-picked from {short_hash}?"),
+                        Some(&truncate_diff(&diff)),
+                        &["Commit", "Keep Staged"],
+                    )
+                })?;
+                if answer.await.ok() != Some(0) {
+                    return Ok(());
+                }
+                let result = provider.commit(hash).await;
+                workspace.update(&mut cx, |workspace, cx| match result {
+                    Ok(()) => show_toast(workspace, format!("Cherry-picked {short_hash}"), cx),
+                    Err(error) => show_toast(
+                        workspace,
+                        format!("Failed to commit the cherry-pick: {error:#}"),
+                        cx,
+                    ),
+                })?
+            }
+            // Opening the conflicted files highlights their conflicts, which can be resolved with
+            // the editor's merge conflict actions.
+            Ok(CherryPickOutcome::Conflicted { paths }) => {
+                workspace.update(&mut cx, |workspace, cx| {
+                    show_abort_toast(
+                        workspace,
+                        format!(
+                            "Cherry-picking {short_hash} caused conflicts in {} {}",
+                            paths.len(),
+                            if paths.len() == 1 { "file" } else { "files" }
+                        ),
+                        cx,
+                    );
+                    for path in paths {
+                        workspace
+                            .open_abs_path(path, true, cx)
+                            .detach_and_log_err(cx);
+                    }
+                })?
+            }
+            Err(error) => workspace.update(&mut cx, |workspace, cx| {
+                let message = format!("Failed to cherry-pick {short_hash}: {error:#}");
+                if provider.is_in_progress() {
+                    show_abort_toast(workspace, message, cx)
+                } else {
+                    show_toast(workspace, message, cx)
+                }
+            })?,
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+pub(crate) fn abort_cherry_pick(
+    workspace: &mut Workspace,
+    _: &AbortCherryPick,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(provider) = cherry_pick_provider(workspace, cx) else {
+        return;
+    };
+    let abort = provider.abort();
+    cx.spawn(|workspace, mut cx| async move {
+        let result = abort.await;
+        workspace.update(&mut cx, |workspace, cx| match result {
+            Ok(()) => show_toast(workspace, "Aborted the cherry-pick", cx),
+            Err(error) => show_toast(
+                workspace,
+                format!("Failed to abort the cherry-pick: {error:#}"),
+                cx,
+            ),
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Returns a provider for the repository of the active file or file history, or of the first
+/// worktree if neither is open.
+fn cherry_pick_provider(workspace: &Workspace, cx: &AppContext) -> Option<CherryPickProvider> {
+    let buffer = if let Some(file_history) = workspace.active_item_as::<FileHistoryView>(cx) {
+        Some(file_history.read(cx).buffer().clone())
+    } else {
+        workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+    };
+    let project = workspace.project().read(cx);
+    let repository = buffer
+        .and_then(|buffer| project.local_repository_for_buffer(&buffer, cx).ok())
+        .map(|(repository, _)| repository)
+        .or_else(|| project.get_first_worktree_root_repo(cx))?;
+    Some(CherryPickProvider::new(
+        repository,
+        cx.background_executor().clone(),
+    ))
+}
+
+fn truncate_diff(diff: &str) -> String {
+    let line_count = diff.lines().count();
+    let mut truncated = diff
+        .lines()
+        .take(MAX_PROMPT_DIFF_LINES)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if line_count > MAX_PROMPT_DIFF_LINES {
+        truncated.push_str(&format!(
+            "\n… and {} more lines",
+            line_count - MAX_PROMPT_DIFF_LINES
+        ));
+    }
+    truncated
+}
+
+struct CherryPickToast;
+
+fn show_toast(
+    workspace: &mut Workspace,
+    message: impl Into<String>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let id = NotificationId::unique::<CherryPickToast>();
+    workspace.show_toast(Toast::new(id, message.into()), cx)
+}
+
+/// Shows a message about a cherry-pick that is still in progress, with a button to abort it.
+fn show_abort_toast(
+    workspace: &mut Workspace,
+    message: impl Into<String>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let id = NotificationId::unique::<CherryPickToast>();
+    let toast = Toast::new(id, message.into()).on_click("Abort Cherry-Pick", |cx| {
+        cx.dispatch_action(AbortCherryPick.boxed_clone())
+    });
+    workspace.show_toast(toast, cx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::{TestAppContext, View, VisualTestContext};
+    use project::Project;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    const CONFLICTING_COMMIT: &str = "1111111111111111111111111111111111111111";
+    const CLEAN_COMMIT: &str = "2222222222222222222222222222222222222222";
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+    }
+
+    async fn open_repository(
+        cx: &mut TestAppContext,
+    ) -> (Arc<FakeFs>, View<Workspace>, &mut VisualTestContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/repo",
+            json!({
+                ".git": {},
+                "src": { "main.rs": "fn main() {}\n" },
+            }),
+        )
+        .await;
+        fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+            state.cherry_pick_conflicts.insert(
+                CONFLICTING_COMMIT.parse().unwrap(),
+                vec![PathBuf::from("/repo/src/main.rs")],
+            );
+        });
+        let project = Project::test(fs.clone(), ["/repo".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        (fs, workspace, cx)
+    }
+
+    /// Returns the commit being cherry-picked and the commits cherry-picked so far.
+    fn cherry_pick_state(fs: &FakeFs) -> (Option<Oid>, Vec<Oid>) {
+        let mut result = (None, Vec::new());
+        fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+            result = (state.cherry_pick_head, state.cherry_picked_commits.clone())
+        });
+        result
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_conflicts(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (fs, workspace, cx) = open_repository(cx).await;
+        let conflicting_commit = CONFLICTING_COMMIT.parse::<Oid>().unwrap();
+        let clean_commit = CLEAN_COMMIT.parse::<Oid>().unwrap();
+
+        cx.dispatch_action(CherryPick {
+            hash: CONFLICTING_COMMIT.into(),
+            no_commit: false,
+        });
+        assert_eq!(cherry_pick_state(&fs), (Some(conflicting_commit), vec![]));
+
+        // The conflicted files are opened so that their conflicts can be resolved.
+        let active_path = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_item(cx)
+                .unwrap()
+                .project_path(cx)
+                .unwrap()
+                .path
+        });
+        assert_eq!(active_path.as_ref(), Path::new("src/main.rs"));
+
+        // Nothing else is cherry-picked until the cherry-pick in progress is aborted.
+        cx.dispatch_action(CherryPick {
+            hash: CLEAN_COMMIT.into(),
+            no_commit: false,
+        });
+        assert_eq!(cherry_pick_state(&fs), (Some(conflicting_commit), vec![]));
+
+        cx.dispatch_action(AbortCherryPick);
+        assert_eq!(cherry_pick_state(&fs), (None, vec![]));
+
+        cx.dispatch_action(CherryPick {
+            hash: CLEAN_COMMIT.into(),
+            no_commit: false,
+        });
+        assert_eq!(cherry_pick_state(&fs), (None, vec![clean_commit]));
+        assert!(!cx.has_pending_prompt());
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_without_committing(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (fs, _, cx) = open_repository(cx).await;
+        let conflicting_commit = CONFLICTING_COMMIT.parse::<Oid>().unwrap();
+        let clean_commit = CLEAN_COMMIT.parse::<Oid>().unwrap();
+
+        // The staged changes are kept when the user declines to commit them.
+        cx.dispatch_action(CherryPick {
+            hash: CLEAN_COMMIT.into(),
+            no_commit: true,
+        });
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1);
+        cx.run_until_parked();
+        assert_eq!(cherry_pick_state(&fs), (None, vec![]));
+
+        cx.dispatch_action(CherryPick {
+            hash: CLEAN_COMMIT.into(),
+            no_commit: true,
+        });
+        cx.simulate_prompt_answer(0);
+        cx.run_until_parked();
+        assert_eq!(cherry_pick_state(&fs), (None, vec![clean_commit]));
+
+        // Conflicts stop the cherry-pick before anything can be committed.
+        cx.dispatch_action(CherryPick {
+            hash: CONFLICTING_COMMIT.into(),
+            no_commit: true,
+        });
+        assert!(!cx.has_pending_prompt());
+        assert_eq!(
+            cherry_pick_state(&fs),
+            (Some(conflicting_commit), vec![clean_commit])
+        );
+        cx.dispatch_action(AbortCherryPick);
+        assert_eq!(cherry_pick_state(&fs), (None, vec![clean_commit]));
+    }
+}
@@ -0,0 +1,669 @@
+use editor::{actions::ExpandAllHunkDiffs, Editor, EditorEvent};
+use git::{
+    file_history::{CommitSummary, FileHistoryProvider},
+    repository::RepoPath,
+};
+use gpui::{
+    actions, uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model,
+    PromptLevel, Subscription, Task, UniformListScrollHandle, View, WeakView,
+};
+use language::Buffer;
+use ui::{prelude::*, ListItem, ListItemSpacing, Tooltip};
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    notifications::NotificationId,
+    Toast, Workspace,
+};
+
+use crate::cherry_pick::CherryPick;
+
+actions!(file_history, [ViewFileHistory, RestoreFileAtCommit]);
+
+/// How many of the commits that changed a file are listed in its history.
+const MAX_COMMITS: usize = 1000;
+
+/// Lists the commits that changed a file, opening the changes made by each of them.
+pub struct FileHistoryView {
+    workspace: WeakView<Workspace>,
+    buffer: Model<Buffer>,
+    path: RepoPath,
+    provider: FileHistoryProvider,
+    commits: Vec<CommitSummary>,
+    loading: bool,
+    selected_index: Option<usize>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    _load_commits: Task<()>,
+}
+
+impl FileHistoryView {
+    pub(crate) fn deploy(
+        workspace: &mut Workspace,
+        _: &ViewFileHistory,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let (repository, path) = match workspace
+            .project()
+            .read(cx)
+            .local_repository_for_buffer(&buffer, cx)
+        {
+            Ok(repository) => repository,
+            Err(error) => {
+                show_error_toast(workspace, format!("Can't view file history: {error:#}"), cx);
+                return;
+            }
+        };
+        let provider = FileHistoryProvider::new(repository, cx.background_executor().clone());
+        let workspace_handle = cx.view().downgrade();
+        let view = cx.new_view(|cx| Self::new(workspace_handle, buffer, path, provider, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, cx);
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        buffer: Model<Buffer>,
+        path: RepoPath,
+        provider: FileHistoryProvider,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let commits = provider.history(&path, MAX_COMMITS);
+        let load_commits = cx.spawn(|this, mut cx| async move {
+            let commits = commits.await;
+            this.update(&mut cx, |this, cx| {
+                this.loading = false;
+                cx.notify();
+                match commits {
+                    Ok(commits) => this.commits = commits,
+                    Err(error) => this
+                        .display_error_toast(format!("Failed to load file history: {error:#}"), cx),
+                }
+            })
+            .ok();
+        });
+        Self {
+            workspace,
+            buffer,
+            path,
+            provider,
+            commits: Vec::new(),
+            loading: true,
+            selected_index: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            _load_commits: load_commits,
+        }
+    }
+
+    pub(crate) fn buffer(&self) -> &Model<Buffer> {
+        &self.buffer
+    }
+
+    /// Opens the changes made to the file by a commit.
+    fn open_commit(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_index = Some(ix);
+        cx.notify();
+
+        let commit = self.commits[ix].clone();
+        let contents = self.provider.file_at_commit(&commit);
+        let base_contents = self.provider.file_before_commit(&commit);
+        let buffer = self.buffer.clone();
+        cx.spawn(|this, mut cx| async move {
+            let contents = async { anyhow::Ok((contents.await?, base_contents.await?)) }.await;
+            this.update(&mut cx, |this, cx| match contents {
+                Ok((contents, base_contents)) => {
+                    let view = cx.new_view(|cx| {
+                        FileDiffView::new(commit, contents, base_contents, buffer, cx)
+                    });
+                    this.workspace
+                        .update(cx, |workspace, cx| {
+                            workspace.add_item_to_active_pane(Box::new(view), None, cx)
+                        })
+                        .ok();
+                }
+                Err(error) => this.display_error_toast(
+                    format!("Failed to load {}: {error:#}", commit.hash.display_short()),
+                    cx,
+                ),
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Replaces the contents of the file with its contents in the selected commit.
+    fn restore_file_at_commit(&mut self, _: &RestoreFileAtCommit, cx: &mut ViewContext<Self>) {
+        let Some(commit) = self
+            .selected_index
+            .and_then(|ix| self.commits.get(ix))
+            .cloned()
+        else {
+            return;
+        };
+        let contents = self.provider.file_at_commit(&commit);
+        let buffer = self.buffer.clone();
+        cx.spawn(|this, mut cx| async move {
+            let contents = contents.await;
+            this.update(&mut cx, |this, cx| match contents {
+                Ok(contents) => restore_file(&buffer, contents, cx),
+                Err(error) => {
+                    this.display_error_toast(format!("Failed to restore file: {error:#}"), cx)
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Cherry-picks a commit onto the current branch once the user confirms it, as it's committed
+    /// right away.
+    fn cherry_pick_commit(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let commit = self.commits[ix].clone();
+        let answer = cx.prompt(
+            PromptLevel::Info,
+            &format!(
+                "Cherry-pick {} onto the current branch?",
+                commit.hash.display_short()
+            ),
+            Some(&commit.message_subject),
+            &["Cherry-Pick", "Cancel"],
+        );
+        cx.spawn(|_, mut cx| async move {
+            if answer.await != Ok(0) {
+                return Ok(());
+            }
+            cx.update(|cx| {
+                cx.dispatch_action(Box::new(CherryPick {
+                    hash: commit.hash.to_string(),
+                    no_commit: false,
+                }))
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn display_error_toast(&self, message: String, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| show_error_toast(workspace, message, cx))
+            .ok();
+    }
+
+    fn render_commit(&self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let commit = &self.commits[ix];
+        let date = time::OffsetDateTime::from_unix_timestamp(commit.date)
+            .map(|date| {
+                time_format::format_localized_timestamp(
+                    date,
+                    time::OffsetDateTime::now_utc(),
+                    cx.local_timezone(),
+                    time_format::TimestampFormat::Relative,
+                )
+            })
+            .unwrap_or_default();
+
+        ListItem::new(SharedString::from(format!("commit-{ix}")))
+            .spacing(ListItemSpacing::Sparse)
+            .selected(self.selected_index == Some(ix))
+            .on_click(cx.listener(move |this, _, cx| this.open_commit(ix, cx)))
+            .child(
+                v_flex()
+                    .overflow_hidden()
+                    .child(Label::new(commit.message_subject.clone()).single_line())
+                    .child(
+                        Label::new(format!(
+                            "{} · {} · {}",
+                            commit.hash.display_short(),
+                            commit.author,
+                            date
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .end_slot(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new(("cherry-pick", ix), IconName::Copy)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Cherry-Pick Commit", cx))
+                            .on_click(
+                                cx.listener(move |this, _, cx| this.cherry_pick_commit(ix, cx)),
+                            ),
+                    )
+                    .child(
+                        IconButton::new(("restore-file", ix), IconName::HistoryRerun)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Restore File at Commit", cx))
+                            .on_click(cx.listener(move |this, _, cx| {
+                                this.selected_index = Some(ix);
+                                this.restore_file_at_commit(&RestoreFileAtCommit, cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+impl EventEmitter<()> for FileHistoryView {}
+
+impl FocusableView for FileHistoryView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for FileHistoryView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(format!("History of {}", file_name(&self.path)))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.path.to_string_lossy().to_string().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+impl Render for FileHistoryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("FileHistoryView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::restore_file_at_commit))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .map(|this| {
+                if self.commits.is_empty() {
+                    let message = if self.loading {
+                        "Loading file history…"
+                    } else {
+                        "No commits have changed this file."
+                    };
+                    this.child(
+                        v_flex().p_4().child(
+                            Label::new(message)
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "commits",
+                            self.commits.len(),
+                            |this, range, cx| range.map(|ix| this.render_commit(ix, cx)).collect(),
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+    }
+}
+
+/// Shows the changes a commit made to a file, as the file's contents in that commit with the
+/// lines it changed highlighted.
+pub struct FileDiffView {
+    editor: View<Editor>,
+    commit: CommitSummary,
+    buffer: Model<Buffer>,
+    _subscription: Subscription,
+}
+
+impl FileDiffView {
+    fn new(
+        commit: CommitSummary,
+        contents: String,
+        base_contents: Option<String>,
+        buffer: Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let language = buffer.read(cx).language().cloned();
+        let commit_buffer = cx.new_model(|cx| {
+            let mut commit_buffer = Buffer::local(contents, cx);
+            commit_buffer.set_language(language, cx);
+            // A file added by the commit is diffed against an empty file.
+            commit_buffer.set_diff_base(Some(base_contents.unwrap_or_default()), cx);
+            commit_buffer
+        });
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(commit_buffer, None, cx);
+            editor.set_read_only(true);
+            editor
+        });
+        let subscription = cx.subscribe(&editor, |_, editor, event: &EditorEvent, cx| {
+            // The diff is computed in the background, so the changes can only be expanded
+            // once it's done.
+            if let EditorEvent::DiffBaseChanged = event {
+                editor.update(cx, |editor, cx| {
+                    editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx)
+                });
+            }
+            cx.emit(event.clone());
+        });
+        Self {
+            editor,
+            commit,
+            buffer,
+            _subscription: subscription,
+        }
+    }
+
+    /// Replaces the contents of the file with its contents in this commit.
+    fn restore_file_at_commit(&mut self, _: &RestoreFileAtCommit, cx: &mut ViewContext<Self>) {
+        let contents = self.editor.read(cx).text(cx);
+        restore_file(&self.buffer, contents, cx);
+    }
+}
+
+impl EventEmitter<EditorEvent> for FileDiffView {}
+
+impl FocusableView for FileDiffView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for FileDiffView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(format!(
+            "{} @ {}",
+            file_name(&self.commit.path),
+            self.commit.hash.display_short()
+        ))
+        .color(if params.selected {
+            Color::Default
+        } else {
+            Color::Muted
+        })
+        .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.commit.message_subject.clone().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+impl Render for FileDiffView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .key_context("FileDiffView")
+            .on_action(cx.listener(Self::restore_file_at_commit))
+            .size_full()
+            .child(self.editor.clone())
+    }
+}
+
+/// Replaces the contents of a buffer, leaving it to the user to save or undo the change.
+fn restore_file(buffer: &Model<Buffer>, contents: String, cx: &mut AppContext) {
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_text(contents, cx);
+    });
+}
+
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn show_error_toast(workspace: &mut Workspace, message: String, cx: &mut ViewContext<Workspace>) {
+    struct FileHistoryError;
+    let id = NotificationId::unique::<FileHistoryError>();
+    workspace.show_toast(Toast::new(id, message), cx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{Project, ProjectPath};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn commit(hash: &str, message_subject: &str) -> CommitSummary {
+        CommitSummary {
+            hash: hash.parse().unwrap(),
+            author: "Alice".into(),
+            date: 0,
+            message_subject: message_subject.into(),
+            path: PathBuf::from("src/main.rs"),
+        }
+    }
+
+    async fn open_file_history(
+        cx: &mut TestAppContext,
+    ) -> (
+        Arc<FakeFs>,
+        View<Workspace>,
+        View<FileHistoryView>,
+        Model<Buffer>,
+        &mut VisualTestContext,
+    ) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/repo",
+            json!({
+                ".git": {},
+                "src": { "main.rs": "fn three() {}\n" },
+            }),
+        )
+        .await;
+        fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+            state.file_histories.insert(
+                PathBuf::from("src/main.rs"),
+                vec![
+                    (
+                        commit("2222222222222222222222222222222222222222", "Rename to two"),
+                        "fn two() {}\n".into(),
+                    ),
+                    (
+                        commit("1111111111111111111111111111111111111111", "Add main"),
+                        "fn one() {}\n".into(),
+                    ),
+                ],
+            );
+        });
+
+        let project = Project::test(fs.clone(), ["/repo".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                let path = ProjectPath {
+                    worktree_id,
+                    path: Path::new("src/main.rs").into(),
+                };
+                workspace.open_path(path, None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        let buffer = editor.update(cx, |editor, cx| {
+            editor.buffer().read(cx).as_singleton().unwrap()
+        });
+
+        cx.dispatch_action(ViewFileHistory);
+        let history = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<FileHistoryView>(cx).unwrap()
+        });
+        (fs, workspace, history, buffer, cx)
+    }
+
+    #[gpui::test]
+    async fn test_file_history(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (_, workspace, history, buffer, cx) = open_file_history(cx).await;
+
+        history.update(cx, |history, cx| {
+            assert!(!history.loading);
+            let subjects = history
+                .commits
+                .iter()
+                .map(|commit| commit.message_subject.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(subjects, ["Rename to two", "Add main"]);
+
+            // The history is loaded in the background, and isn't empty in the meantime.
+            let view = cx.new_view(|cx| {
+                FileHistoryView::new(
+                    history.workspace.clone(),
+                    history.buffer.clone(),
+                    history.path.clone(),
+                    history.provider.clone(),
+                    cx,
+                )
+            });
+            assert!(view.read(cx).loading);
+
+            history.open_commit(0, cx);
+        });
+        cx.run_until_parked();
+
+        // The commit's changes are shown against the file's contents in its parent.
+        let diff = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<FileDiffView>(cx).unwrap()
+        });
+        diff.update(cx, |diff, cx| {
+            let editor = diff.editor.read(cx);
+            assert_eq!(editor.text(cx), "fn two() {}\n");
+            let commit_buffer = editor.buffer().read(cx).as_singleton().unwrap();
+            assert_eq!(
+                commit_buffer
+                    .read(cx)
+                    .diff_base()
+                    .map(|base| base.to_string()),
+                Some("fn one() {}\n".to_string())
+            );
+            diff.restore_file_at_commit(&RestoreFileAtCommit, cx);
+        });
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "fn two() {}\n"
+        );
+
+        // A commit that added the file is diffed against an empty file.
+        history.update(cx, |history, cx| history.open_commit(1, cx));
+        cx.run_until_parked();
+        let diff = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<FileDiffView>(cx).unwrap()
+        });
+        diff.update(cx, |diff, cx| {
+            let editor = diff.editor.read(cx);
+            assert_eq!(editor.text(cx), "fn one() {}\n");
+            let commit_buffer = editor.buffer().read(cx).as_singleton().unwrap();
+            assert_eq!(
+                commit_buffer
+                    .read(cx)
+                    .diff_base()
+                    .map(|base| base.to_string()),
+                Some(String::new())
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_restore_file_at_commit(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (_, _, history, buffer, cx) = open_file_history(cx).await;
+
+        // Nothing is restored until a commit is selected.
+        history.update(cx, |history, cx| {
+            history.restore_file_at_commit(&RestoreFileAtCommit, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "fn three() {}\n"
+        );
+
+        history.update(cx, |history, cx| {
+            history.selected_index = Some(1);
+            history.restore_file_at_commit(&RestoreFileAtCommit, cx)
+        });
+        cx.run_until_parked();
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(buffer.text(), "fn one() {}\n");
+            // The change is left for the user to save or undo.
+            assert!(buffer.is_dirty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_from_file_history(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (fs, _, history, _, cx) = open_file_history(cx).await;
+        let cherry_picked_commits = |fs: &FakeFs| {
+            let mut commits = Vec::new();
+            fs.with_git_state(Path::new("/repo/.git"), false, |state| {
+                commits = state.cherry_picked_commits.clone()
+            });
+            commits
+        };
+
+        // The commit is only cherry-picked once confirmed.
+        history.update(cx, |history, cx| history.cherry_pick_commit(1, cx));
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1);
+        cx.run_until_parked();
+        assert!(cherry_picked_commits(&fs).is_empty());
+
+        history.update(cx, |history, cx| history.cherry_pick_commit(1, cx));
+        cx.run_until_parked();
+        cx.simulate_prompt_answer(0);
+        cx.run_until_parked();
+        assert_eq!(
+            cherry_picked_commits(&fs),
+            ["1111111111111111111111111111111111111111"
+                .parse::<git::Oid>()
+                .unwrap()]
+        );
+    }
+}
@@ -16,6 +16,14 @@ use util::ResultExt;
 use workspace::notifications::NotificationId;
 use workspace::{ModalView, Toast, Workspace};
 
+mod cherry_pick;
+mod file_history;
+mod stash_panel;
+
+pub use cherry_pick::{AbortCherryPick, CherryPick};
+pub use file_history::{FileDiffView, FileHistoryView};
+pub use stash_panel::StashPanel;
+
 actions!(branches, [OpenRecent]);
 
 pub fn init(cx: &mut AppContext) {
@@ -23,6 +31,12 @@ pub fn init(cx: &mut AppContext) {
         workspace.register_action(|workspace, action, cx| {
             BranchList::open(workspace, action, cx).log_err();
         });
+        workspace.register_action(|workspace, _: &stash_panel::ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<StashPanel>(cx);
+        });
+        workspace.register_action(FileHistoryView::deploy);
+        workspace.register_action(cherry_pick::cherry_pick);
+        workspace.register_action(cherry_pick::abort_cherry_pick);
     })
     .detach();
 }
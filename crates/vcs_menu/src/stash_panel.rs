@@ -0,0 +1,337 @@
+use anyhow::Result;
+use git::{
+    stash::{StashEntry, StashManager},
+    Oid,
+};
+use gpui::{
+    actions, uniform_list, AppContext, AsyncWindowContext, EventEmitter, FocusHandle,
+    FocusableView, Model, PromptLevel, Subscription, Task, UniformListScrollHandle, View, WeakView,
+};
+use project::Project;
+use ui::{prelude::*, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::NotificationId,
+    Toast, Workspace,
+};
+
+actions!(stash_panel, [ToggleFocus, CreateStash]);
+
+const DEFAULT_WIDTH: Pixels = px(240.);
+
+/// Lists the stashes of the first worktree's repository, and stashes or restores changes.
+pub struct StashPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    focus_handle: FocusHandle,
+    position: DockPosition,
+    width: Option<Pixels>,
+    stashes: Vec<StashEntry>,
+    scroll_handle: UniformListScrollHandle,
+    refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl StashPanel {
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            workspace.update(&mut cx, |workspace, cx| {
+                let project = workspace.project().clone();
+                let workspace = cx.view().downgrade();
+                cx.new_view(|cx| Self::new(workspace, project, cx))
+            })
+        })
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let subscriptions = vec![cx.subscribe(&project, |this, _, event, cx| {
+            if let project::Event::WorktreeUpdatedGitRepositories = event {
+                this.refresh(cx);
+            }
+        })];
+        let mut this = Self {
+            workspace,
+            project,
+            focus_handle: cx.focus_handle(),
+            position: DockPosition::Left,
+            width: None,
+            stashes: Vec::new(),
+            scroll_handle: UniformListScrollHandle::new(),
+            refresh_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn stash_manager(&self, cx: &AppContext) -> Option<StashManager> {
+        let repository = self.project.read(cx).get_first_worktree_root_repo(cx)?;
+        Some(StashManager::new(
+            repository,
+            cx.background_executor().clone(),
+        ))
+    }
+
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(stash_manager) = self.stash_manager(cx) else {
+            self.stashes.clear();
+            cx.notify();
+            return;
+        };
+        let stashes = stash_manager.list_stashes();
+        self.refresh_task = cx.spawn(|this, mut cx| async move {
+            let Some(stashes) = stashes.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                this.stashes = stashes;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn create_stash(&mut self, _: &CreateStash, cx: &mut ViewContext<Self>) {
+        let Some(stash_manager) = self.stash_manager(cx) else {
+            return;
+        };
+        let task = stash_manager.create_stash(None);
+        self.update_stashes(task, "Failed to stash changes", cx);
+    }
+
+    fn apply_stash(&mut self, sha: Oid, pop: bool, cx: &mut ViewContext<Self>) {
+        let Some(stash_manager) = self.stash_manager(cx) else {
+            return;
+        };
+        let task = stash_manager.apply_stash(sha, pop);
+        self.update_stashes(task, "Failed to apply stash, check for conflicts", cx);
+    }
+
+    fn drop_stash(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(stash) = self.stashes.get(ix) else {
+            return;
+        };
+        let sha = stash.sha;
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            &format!("Drop {}?", stash.id),
+            Some(&format!(
+                "{}\n\nThe stashed changes can't be restored once the stash is dropped.",
+                stash.message
+            )),
+            &["Drop Stash", "Cancel"],
+        );
+        cx.spawn(|this, mut cx| async move {
+            if answer.await != Ok(0) {
+                return Ok(());
+            }
+            this.update(&mut cx, |this, cx| {
+                let Some(stash_manager) = this.stash_manager(cx) else {
+                    return;
+                };
+                let task = stash_manager.drop_stash(sha);
+                this.update_stashes(task, "Failed to drop stash", cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Waits for a change to the stash list, then reloads it, showing a toast if the change
+    /// failed.
+    fn update_stashes<T: 'static>(
+        &mut self,
+        task: Task<Result<T>>,
+        error_message: &'static str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        cx.spawn(|this, mut cx| async move {
+            let result = task.await;
+            this.update(&mut cx, |this, cx| {
+                if let Err(error) = result {
+                    this.display_error_toast(format!("{error_message}: {error:#}"), cx);
+                }
+                this.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn display_error_toast(&self, message: String, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                struct StashFailure;
+                let id = NotificationId::unique::<StashFailure>();
+                workspace.show_toast(Toast::new(id, message), cx)
+            })
+            .ok();
+    }
+
+    fn render_stash(&self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let stash = &self.stashes[ix];
+        let id = stash.id;
+        let sha = stash.sha;
+        let timestamp = time::OffsetDateTime::from_unix_timestamp(stash.timestamp)
+            .map(|timestamp| {
+                time_format::format_localized_timestamp(
+                    timestamp,
+                    time::OffsetDateTime::now_utc(),
+                    cx.local_timezone(),
+                    time_format::TimestampFormat::Relative,
+                )
+            })
+            .unwrap_or_default();
+        let file_count = match stash.paths.len() {
+            1 => "1 file".to_string(),
+            count => format!("{count} files"),
+        };
+
+        ListItem::new(SharedString::from(format!("stash-{ix}")))
+            .spacing(ListItemSpacing::Sparse)
+            .child(
+                v_flex()
+                    .overflow_hidden()
+                    .child(Label::new(stash.message.clone()).single_line())
+                    .child(
+                        Label::new(format!("{id} · {timestamp} · {file_count}"))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .end_slot(
+                h_flex()
+                    .child(
+                        IconButton::new(("apply-stash", ix), IconName::Check)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Apply Stash", cx))
+                            .on_click(
+                                cx.listener(move |this, _, cx| this.apply_stash(sha, false, cx)),
+                            ),
+                    )
+                    .child(
+                        IconButton::new(("pop-stash", ix), IconName::ArrowUpFromLine)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Pop Stash", cx))
+                            .on_click(
+                                cx.listener(move |this, _, cx| this.apply_stash(sha, true, cx)),
+                            ),
+                    )
+                    .child(
+                        IconButton::new(("drop-stash", ix), IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Drop Stash", cx))
+                            .on_click(cx.listener(move |this, _, cx| this.drop_stash(ix, cx))),
+                    ),
+            )
+    }
+}
+
+impl Panel for StashPanel {
+    fn persistent_name() -> &'static str {
+        "Stash Panel"
+    }
+
+    fn position(&self, _: &WindowContext) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        self.position = position;
+        cx.notify();
+    }
+
+    fn size(&self, _: &WindowContext) -> Pixels {
+        self.width.unwrap_or(DEFAULT_WIDTH)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &WindowContext) -> Option<IconName> {
+        Some(IconName::FileGit)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Stash Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        if active {
+            self.refresh(cx);
+        }
+    }
+}
+
+impl EventEmitter<PanelEvent> for StashPanel {}
+
+impl FocusableView for StashPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for StashPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let header = h_flex()
+            .justify_between()
+            .px_2()
+            .py_1()
+            // Match the height of the tab bar so they line up.
+            .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new("Stashes"))
+            .child(
+                IconButton::new("create-stash", IconName::Plus)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::for_action("Stash Changes", &CreateStash, cx))
+                    .on_click(cx.listener(|this, _, cx| this.create_stash(&CreateStash, cx))),
+            );
+
+        v_flex()
+            .key_context("StashPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::create_stash))
+            .size_full()
+            .child(header)
+            .map(|this| {
+                if self.stashes.is_empty() {
+                    this.child(
+                        v_flex().p_4().child(
+                            Label::new("There are no stashes.")
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "stashes",
+                            self.stashes.len(),
+                            |this, range, cx| range.map(|ix| this.render_stash(ix, cx)).collect(),
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+    }
+}
@@ -1,5 +1,6 @@
-use gpui::{div, Element, Render, Subscription, ViewContext};
+use gpui::{AppContext, Render, Subscription, ViewContext};
 use itertools::Itertools;
+use ui::ModeStyle;
 use workspace::{item::ItemHandle, ui::prelude::*, StatusItemView};
 
 use crate::{state::Mode, Vim};
@@ -8,6 +9,7 @@ use crate::{state::Mode, Vim};
 pub struct ModeIndicator {
     pub(crate) mode: Option<Mode>,
     pub(crate) operators: String,
+    recording: Option<char>,
     pending_keys: Option<String>,
     _subscriptions: Vec<Subscription>,
 }
@@ -26,6 +28,7 @@ impl ModeIndicator {
         let mut this = Self {
             mode: None,
             operators: "".to_string(),
+            recording: None,
             pending_keys: None,
             _subscriptions,
         };
@@ -37,8 +40,10 @@ impl ModeIndicator {
         if let Some(vim) = self.vim(cx) {
             self.mode = Some(vim.state().mode);
             self.operators = self.current_operators_description(&vim);
+            self.recording = vim.workspace_state.recording_register;
         } else {
             self.mode = None;
+            self.recording = None;
         }
     }
 
@@ -61,11 +66,10 @@ impl ModeIndicator {
     }
 
     fn current_operators_description(&self, vim: &Vim) -> String {
-        vim.workspace_state
-            .recording_register
-            .map(|reg| format!("recording @{reg} "))
+        vim.state()
+            .pre_count
+            .map(|count| format!("{}", count))
             .into_iter()
-            .chain(vim.state().pre_count.map(|count| format!("{}", count)))
             .chain(vim.state().selected_register.map(|reg| format!("\"{reg}")))
             .chain(
                 vim.state()
@@ -81,16 +85,25 @@ impl ModeIndicator {
 
 impl Render for ModeIndicator {
     fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
-        let Some(mode) = self.mode.as_ref() else {
-            return div().into_any();
-        };
-
-        let pending = self.pending_keys.as_ref().unwrap_or(&self.operators);
-
-        Label::new(format!("{} -- {} --", pending, mode))
-            .size(LabelSize::Small)
-            .line_height_style(LineHeightStyle::UiLabel)
-            .into_any_element()
+        let mut indicator = ui::ModeIndicator::new();
+        if let Some(mode) = self.mode {
+            let pending = self.pending_keys.as_ref().unwrap_or(&self.operators);
+            let style = if !pending.is_empty() {
+                ModeStyle::OperatorPending
+            } else {
+                match mode {
+                    Mode::Normal => ModeStyle::Normal,
+                    Mode::Insert => ModeStyle::Insert,
+                    Mode::Replace => ModeStyle::Replace,
+                    Mode::Visual | Mode::VisualLine | Mode::VisualBlock => ModeStyle::Visual,
+                }
+            };
+            indicator = indicator
+                .mode(mode.to_string(), style)
+                .pending(Some(pending.clone().into()))
+                .recording(self.recording);
+        }
+        indicator
     }
 }
 
@@ -102,4 +115,8 @@ impl StatusItemView for ModeIndicator {
     ) {
         // nothing to do.
     }
+
+    fn status_item_id(&self, _: &AppContext) -> Option<&'static str> {
+        Some("vim_mode")
+    }
 }
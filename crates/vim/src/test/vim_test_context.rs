@@ -78,7 +78,7 @@ impl VimTestContext {
             });
             workspace.status_bar().update(cx, |status_bar, cx| {
                 let vim_mode_indicator = cx.new_view(ModeIndicator::new);
-                status_bar.add_right_item(vim_mode_indicator, cx);
+                status_bar.set_mode_indicator(vim_mode_indicator, cx);
             });
         });
 
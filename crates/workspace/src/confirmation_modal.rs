@@ -0,0 +1,208 @@
+use gpui::{DismissEvent, EventEmitter, FocusHandle, FocusableView};
+use ui::{prelude::*, ElevationIndex, Modal, ModalFooter, ModalHeader, Section, TintColor};
+
+use crate::ModalView;
+
+/// A modal that asks the user to confirm an action, with a button to confirm it and one to
+/// cancel.
+///
+/// Enter presses the focused button, and tab moves focus between the two. Destructive
+/// confirmations style the confirm button as a warning and focus the cancel button when the
+/// modal opens, so that they can't be confirmed by accident.
+///
+/// ```ignore
+/// workspace.toggle_modal(cx, |cx| {
+///     ConfirmationModal::new("Discard changes?", "Your edits to main.rs will be lost.", cx)
+///         .confirm_label("Discard")
+///         .destructive(true)
+///         .on_confirm(|cx| discard_changes(cx))
+/// });
+/// ```
+pub struct ConfirmationModal {
+    title: SharedString,
+    message: SharedString,
+    confirm_label: SharedString,
+    cancel_label: SharedString,
+    destructive: bool,
+    dismiss_on_escape: bool,
+    dismiss_on_backdrop_click: bool,
+    confirm_focus_handle: FocusHandle,
+    cancel_focus_handle: FocusHandle,
+    on_confirm: Option<Box<dyn FnOnce(&mut WindowContext)>>,
+}
+
+impl ConfirmationModal {
+    pub fn new(
+        title: impl Into<SharedString>,
+        message: impl Into<SharedString>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            confirm_label: "Confirm".into(),
+            cancel_label: "Cancel".into(),
+            destructive: false,
+            dismiss_on_escape: true,
+            dismiss_on_backdrop_click: true,
+            confirm_focus_handle: cx.focus_handle(),
+            cancel_focus_handle: cx.focus_handle(),
+            on_confirm: None,
+        }
+    }
+
+    pub fn confirm_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.confirm_label = label.into();
+        self
+    }
+
+    pub fn cancel_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.cancel_label = label.into();
+        self
+    }
+
+    /// Marks the confirmed action as destructive.
+    pub fn destructive(mut self, destructive: bool) -> Self {
+        self.destructive = destructive;
+        self
+    }
+
+    /// Sets whether pressing escape cancels the modal. Defaults to `true`.
+    pub fn dismiss_on_escape(mut self, dismiss_on_escape: bool) -> Self {
+        self.dismiss_on_escape = dismiss_on_escape;
+        self
+    }
+
+    /// Sets whether clicking outside of the modal cancels it. Defaults to `true`.
+    pub fn dismiss_on_backdrop_click(mut self, dismiss_on_backdrop_click: bool) -> Self {
+        self.dismiss_on_backdrop_click = dismiss_on_backdrop_click;
+        self
+    }
+
+    /// Sets the handler called when the user confirms, after which the modal is dismissed.
+    pub fn on_confirm(mut self, handler: impl FnOnce(&mut WindowContext) + 'static) -> Self {
+        self.on_confirm = Some(Box::new(handler));
+        self
+    }
+
+    fn confirm(&mut self, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+        if let Some(on_confirm) = self.on_confirm.take() {
+            on_confirm(cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn press_focused_button(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if self.cancel_focus_handle.is_focused(cx) {
+            cx.emit(DismissEvent);
+        } else {
+            self.confirm(cx);
+        }
+    }
+
+    fn render_button(
+        &self,
+        id: &'static str,
+        button: Button,
+        focus_handle: &FocusHandle,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let border_color = if focus_handle.is_focused(cx) {
+            cx.theme().colors().border_focused
+        } else {
+            cx.theme().colors().border_transparent
+        };
+
+        div()
+            .id(id)
+            .track_focus(focus_handle)
+            .rounded_md()
+            .border_1()
+            .border_color(border_color)
+            .child(button.layer(ElevationIndex::ModalSurface))
+    }
+}
+
+impl EventEmitter<DismissEvent> for ConfirmationModal {}
+
+impl FocusableView for ConfirmationModal {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        if self.destructive {
+            self.cancel_focus_handle.clone()
+        } else {
+            self.confirm_focus_handle.clone()
+        }
+    }
+}
+
+impl ModalView for ConfirmationModal {
+    fn fade_out_background(&self) -> bool {
+        true
+    }
+
+    fn dismiss_on_escape(&self) -> bool {
+        self.dismiss_on_escape
+    }
+
+    fn dismiss_on_backdrop_click(&self) -> bool {
+        self.dismiss_on_backdrop_click
+    }
+
+    fn tab_stops(&self) -> Vec<FocusHandle> {
+        vec![
+            self.cancel_focus_handle.clone(),
+            self.confirm_focus_handle.clone(),
+        ]
+    }
+}
+
+impl Render for ConfirmationModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let cancel_button = Button::new("cancel", self.cancel_label.clone())
+            .on_click(cx.listener(|_, _, cx| cx.emit(DismissEvent)));
+        let confirm_button = Button::new("confirm", self.confirm_label.clone())
+            .style(if self.destructive {
+                ButtonStyle::Tinted(TintColor::Negative)
+            } else {
+                ButtonStyle::Filled
+            })
+            .on_click(cx.listener(|this, _, cx| this.confirm(cx)));
+
+        v_flex()
+            .key_context("ConfirmationModal")
+            .elevation_3(cx)
+            .w(rems(24.))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::press_focused_button))
+            .child(
+                Modal::new("confirmation-modal", None)
+                    .header(
+                        ModalHeader::new()
+                            .child(Headline::new(self.title.clone()).size(HeadlineSize::Small)),
+                    )
+                    .section(Section::new().child(Label::new(self.message.clone())))
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_2()
+                                .child(self.render_button(
+                                    "cancel-container",
+                                    cancel_button,
+                                    &self.cancel_focus_handle,
+                                    cx,
+                                ))
+                                .child(self.render_button(
+                                    "confirm-container",
+                                    confirm_button,
+                                    &self.confirm_focus_handle,
+                                    cx,
+                                )),
+                        ),
+                    ),
+            )
+    }
+}
@@ -742,7 +742,12 @@ impl Render for PanelButtons {
                         .trigger(
                             IconButton::new(name, icon)
                                 .icon_size(IconSize::Small)
-                                .selected(is_active_button)
+                                .variant(ButtonVariant::Toggle)
+                                .state(if is_active_button {
+                                    InteractionState::Active
+                                } else {
+                                    InteractionState::Enabled
+                                })
                                 .on_click({
                                     let action = action.boxed_clone();
                                     move |_, cx| cx.dispatch_action(action.boxed_clone())
@@ -766,6 +771,14 @@ impl StatusItemView for PanelButtons {
     ) {
         // Nothing to do, panel buttons don't depend on the active center item
     }
+
+    fn status_item_id(&self, cx: &AppContext) -> Option<&'static str> {
+        Some(match self.dock.read(cx).position() {
+            DockPosition::Left => "left_panels",
+            DockPosition::Bottom => "bottom_panels",
+            DockPosition::Right => "right_panels",
+        })
+    }
 }
 
 #[cfg(any(test, feature = "test-support"))]
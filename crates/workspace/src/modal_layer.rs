@@ -1,6 +1,6 @@
 use gpui::{
-    div, prelude::*, px, AnyView, DismissEvent, FocusHandle, ManagedView, Render, Subscription,
-    View, ViewContext, WindowContext,
+    div, prelude::*, px, views::next_tab_stop, AnyView, DismissEvent, EntityId, FocusHandle,
+    KeyDownEvent, ManagedView, MouseButton, Render, Subscription, View, ViewContext, WindowContext,
 };
 use theme::ActiveTheme as _;
 use ui::{h_flex, v_flex};
@@ -18,12 +18,34 @@ pub trait ModalView: ManagedView {
     fn fade_out_background(&self) -> bool {
         false
     }
+
+    /// Whether pressing escape dismisses the modal. Destructive confirmations can return
+    /// `false` so that they're only dismissed by an explicit choice.
+    fn dismiss_on_escape(&self) -> bool {
+        true
+    }
+
+    /// Whether clicking the backdrop behind the modal dismisses it. Only modals with a
+    /// backdrop, which are the ones that fade out the background or are stacked over
+    /// another modal, can be dismissed this way.
+    fn dismiss_on_backdrop_click(&self) -> bool {
+        true
+    }
+
+    /// The focus handles that tab and shift-tab cycle through, in order, while the modal is
+    /// the topmost one. Focus never leaves the modal this way.
+    fn tab_stops(&self) -> Vec<FocusHandle> {
+        Vec::new()
+    }
 }
 
 trait ModalViewHandle {
     fn on_before_dismiss(&mut self, cx: &mut WindowContext) -> DismissDecision;
     fn view(&self) -> AnyView;
     fn fade_out_background(&self, cx: &WindowContext) -> bool;
+    fn dismiss_on_escape(&self, cx: &WindowContext) -> bool;
+    fn dismiss_on_backdrop_click(&self, cx: &WindowContext) -> bool;
+    fn tab_stops(&self, cx: &WindowContext) -> Vec<FocusHandle>;
 }
 
 impl<V: ModalView> ModalViewHandle for View<V> {
@@ -38,6 +60,18 @@ impl<V: ModalView> ModalViewHandle for View<V> {
     fn fade_out_background(&self, cx: &WindowContext) -> bool {
         self.read(cx).fade_out_background()
     }
+
+    fn dismiss_on_escape(&self, cx: &WindowContext) -> bool {
+        self.read(cx).dismiss_on_escape()
+    }
+
+    fn dismiss_on_backdrop_click(&self, cx: &WindowContext) -> bool {
+        self.read(cx).dismiss_on_backdrop_click()
+    }
+
+    fn tab_stops(&self, cx: &WindowContext) -> Vec<FocusHandle> {
+        self.read(cx).tab_stops()
+    }
 }
 
 pub struct ActiveModal {
@@ -47,25 +81,33 @@ pub struct ActiveModal {
     focus_handle: FocusHandle,
 }
 
+/// Shows modals over the workspace.
+///
+/// Modals are kept in a stack: a modal pushed with [`ModalLayer::push_modal`] is shown over
+/// the ones below it, which are dimmed by its backdrop, and only the topmost modal handles
+/// escape, backdrop clicks and tab. When a modal is dismissed, focus returns to whatever had
+/// it before the modal was opened.
 pub struct ModalLayer {
-    active_modal: Option<ActiveModal>,
+    modals: Vec<ActiveModal>,
     dismiss_on_focus_lost: bool,
 }
 
 impl ModalLayer {
     pub fn new() -> Self {
         Self {
-            active_modal: None,
+            modals: Vec::new(),
             dismiss_on_focus_lost: false,
         }
     }
 
+    /// Shows the modal built by `build_view`, replacing the topmost modal. If the topmost
+    /// modal is already of this type, it is dismissed instead.
     pub fn toggle_modal<V, B>(&mut self, cx: &mut ViewContext<Self>, build_view: B)
     where
         V: ModalView,
         B: FnOnce(&mut ViewContext<V>) -> V,
     {
-        if let Some(active_modal) = &self.active_modal {
+        if let Some(active_modal) = self.modals.last() {
             let is_close = active_modal.modal.view().downcast::<V>().is_ok();
             let did_close = self.hide_modal(cx);
             if is_close || !did_close {
@@ -76,20 +118,38 @@ impl ModalLayer {
         self.show_modal(new_modal, cx);
     }
 
+    /// Shows the modal built by `build_view` over any modals that are already open.
+    pub fn push_modal<V, B>(&mut self, cx: &mut ViewContext<Self>, build_view: B) -> View<V>
+    where
+        V: ModalView,
+        B: FnOnce(&mut ViewContext<V>) -> V,
+    {
+        let new_modal = cx.new_view(build_view);
+        self.show_modal(new_modal.clone(), cx);
+        new_modal
+    }
+
     fn show_modal<V>(&mut self, new_modal: View<V>, cx: &mut ViewContext<Self>)
     where
         V: ModalView,
     {
         let focus_handle = cx.focus_handle();
-        self.active_modal = Some(ActiveModal {
+        self.modals.push(ActiveModal {
             modal: Box::new(new_modal.clone()),
             _subscriptions: [
-                cx.subscribe(&new_modal, |this, _, _: &DismissEvent, cx| {
-                    this.hide_modal(cx);
+                cx.subscribe(&new_modal, |this, modal, _: &DismissEvent, cx| {
+                    this.dismiss_modal(modal.entity_id(), cx);
                 }),
-                cx.on_focus_out(&focus_handle, |this, _event, cx| {
-                    if this.dismiss_on_focus_lost {
-                        this.hide_modal(cx);
+                cx.on_focus_out(&focus_handle, {
+                    let focus_handle = focus_handle.clone();
+                    move |this, _event, cx| {
+                        let is_topmost = this
+                            .modals
+                            .last()
+                            .is_some_and(|modal| modal.focus_handle == focus_handle);
+                        if this.dismiss_on_focus_lost && is_topmost {
+                            this.hide_modal(cx);
+                        }
                     }
                 }),
             ],
@@ -102,8 +162,31 @@ impl ModalLayer {
         cx.notify();
     }
 
+    /// Dismisses the modal that emitted a [`DismissEvent`], which isn't necessarily the
+    /// topmost one.
+    fn dismiss_modal(&mut self, entity_id: EntityId, cx: &mut ViewContext<Self>) {
+        let Some(ix) = self
+            .modals
+            .iter()
+            .position(|modal| modal.modal.view().entity_id() == entity_id)
+        else {
+            return;
+        };
+
+        if ix + 1 == self.modals.len() {
+            self.hide_modal(cx);
+        } else {
+            // The modal above the removed one returns focus to wherever the removed
+            // modal would have.
+            let removed = self.modals.remove(ix);
+            self.modals[ix].previous_focus_handle = removed.previous_focus_handle;
+            cx.notify();
+        }
+    }
+
+    /// Dismisses the topmost modal, returning whether it was dismissed.
     fn hide_modal(&mut self, cx: &mut ViewContext<Self>) -> bool {
-        let Some(active_modal) = self.active_modal.as_mut() else {
+        let Some(active_modal) = self.modals.last_mut() else {
             self.dismiss_on_focus_lost = false;
             return false;
         };
@@ -121,7 +204,7 @@ impl ModalLayer {
             }
         }
 
-        if let Some(active_modal) = self.active_modal.take() {
+        if let Some(active_modal) = self.modals.pop() {
             if let Some(previous_focus) = active_modal.previous_focus_handle {
                 if active_modal.focus_handle.contains_focused(cx) {
                     previous_focus.focus(cx);
@@ -132,38 +215,78 @@ impl ModalLayer {
         true
     }
 
+    /// Returns the topmost modal if it is of the given type.
     pub fn active_modal<V>(&self) -> Option<View<V>>
     where
         V: 'static,
     {
-        let active_modal = self.active_modal.as_ref()?;
+        let active_modal = self.modals.last()?;
         active_modal.modal.view().downcast::<V>().ok()
     }
 
     pub fn has_active_modal(&self) -> bool {
-        self.active_modal.is_some()
+        !self.modals.is_empty()
     }
-}
 
-impl Render for ModalLayer {
-    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let Some(active_modal) = &self.active_modal else {
-            return div();
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        let dismiss_on_escape = self
+            .modals
+            .last()
+            .is_some_and(|modal| modal.modal.dismiss_on_escape(cx));
+        if dismiss_on_escape {
+            self.hide_modal(cx);
+        }
+    }
+
+    fn cycle_focus(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        if event.keystroke.key != "tab" {
+            return;
+        }
+        let Some(active_modal) = self.modals.last() else {
+            return;
         };
+        let tab_stops = active_modal.modal.tab_stops(cx);
+        let focused_ix = tab_stops
+            .iter()
+            .position(|tab_stop| tab_stop.contains_focused(cx));
+        if let Some(ix) =
+            next_tab_stop(focused_ix, tab_stops.len(), event.keystroke.modifiers.shift)
+        {
+            cx.stop_propagation();
+            cx.focus(&tab_stops[ix]);
+        }
+    }
+
+    fn render_modal(
+        &self,
+        ix: usize,
+        active_modal: &ActiveModal,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let is_topmost = ix + 1 == self.modals.len();
+        // Each stacked modal dims everything below it, including the modals it covers.
+        let has_backdrop = ix > 0 || active_modal.modal.fade_out_background(cx);
+        let dismiss_on_escape = active_modal.modal.dismiss_on_escape(cx);
+        let dismiss_on_backdrop_click = active_modal.modal.dismiss_on_backdrop_click(cx);
 
         div()
             .absolute()
             .size_full()
             .top_0()
             .left_0()
-            .when(active_modal.modal.fade_out_background(cx), |el| {
+            .when(has_backdrop, |el| {
                 let mut background = cx.theme().colors().elevated_surface_background;
                 background.fade_out(0.2);
                 el.bg(background)
                     .occlude()
-                    .on_mouse_down_out(cx.listener(|this, _, cx| {
-                        this.hide_modal(cx);
-                    }))
+                    .when(is_topmost && dismiss_on_backdrop_click, |el| {
+                        el.on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _, cx| {
+                                this.hide_modal(cx);
+                            }),
+                        )
+                    })
             })
             .child(
                 v_flex()
@@ -173,7 +296,204 @@ impl Render for ModalLayer {
                     .flex_col()
                     .items_center()
                     .track_focus(&active_modal.focus_handle)
+                    .when(is_topmost, |el| {
+                        el.when(!dismiss_on_escape, |el| {
+                            // Keep the modal itself from dismissing on escape, too.
+                            el.capture_action(|_: &menu::Cancel, cx| cx.stop_propagation())
+                        })
+                        .on_action(cx.listener(Self::cancel))
+                        .on_key_down(cx.listener(Self::cycle_focus))
+                    })
                     .child(h_flex().occlude().child(active_modal.modal.view())),
             )
     }
 }
+
+impl Render for ModalLayer {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        if self.modals.is_empty() {
+            return div();
+        }
+
+        let modals = self
+            .modals
+            .iter()
+            .enumerate()
+            .map(|(ix, active_modal)| self.render_modal(ix, active_modal, cx))
+            .collect::<Vec<_>>();
+
+        div()
+            .absolute()
+            .size_full()
+            .top_0()
+            .left_0()
+            .children(modals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use gpui::{
+        AppContext, EventEmitter, FocusableView, KeyBinding, TestAppContext, VisualTestContext,
+    };
+    use settings::SettingsStore;
+
+    use super::*;
+    use crate::ConfirmationModal;
+
+    struct TestRoot {
+        focus_handle: FocusHandle,
+        modal_layer: View<ModalLayer>,
+    }
+
+    impl Render for TestRoot {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .track_focus(&self.focus_handle)
+                .size_full()
+                .child(self.modal_layer.clone())
+        }
+    }
+
+    struct TestModal {
+        focus_handle: FocusHandle,
+    }
+
+    impl EventEmitter<DismissEvent> for TestModal {}
+
+    impl FocusableView for TestModal {
+        fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl ModalView for TestModal {}
+
+    impl Render for TestModal {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div().track_focus(&self.focus_handle).size_10()
+        }
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> (View<TestRoot>, &mut VisualTestContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            cx.bind_keys([
+                KeyBinding::new("escape", menu::Cancel, None),
+                KeyBinding::new("enter", menu::Confirm, None),
+            ]);
+        });
+        let (root, cx) = cx.add_window_view(|cx| TestRoot {
+            focus_handle: cx.focus_handle(),
+            modal_layer: cx.new_view(|_| ModalLayer::new()),
+        });
+        cx.focus_view(&root);
+        (root, cx)
+    }
+
+    fn push_test_modal(root: &View<TestRoot>, cx: &mut VisualTestContext) -> View<TestModal> {
+        let modal_layer = cx.update(|cx| root.read(cx).modal_layer.clone());
+        let modal = modal_layer.update(cx, |modal_layer, cx| {
+            modal_layer.push_modal(cx, |cx| TestModal {
+                focus_handle: cx.focus_handle(),
+            })
+        });
+        cx.run_until_parked();
+        modal
+    }
+
+    fn assert_no_modals(root: &View<TestRoot>, cx: &mut VisualTestContext) {
+        cx.update(|cx| {
+            let root = root.read(cx);
+            assert!(!root.modal_layer.read(cx).has_active_modal());
+            assert!(root.focus_handle.is_focused(cx));
+        });
+    }
+
+    #[gpui::test]
+    fn test_dismissing_modal_restores_focus(cx: &mut TestAppContext) {
+        let (root, cx) = init_test(cx);
+
+        let modal = push_test_modal(&root, cx);
+        cx.update(|cx| assert!(modal.read(cx).focus_handle.is_focused(cx)));
+
+        cx.simulate_keystrokes("escape");
+        assert_no_modals(&root, cx);
+    }
+
+    #[gpui::test]
+    fn test_stacked_modals(cx: &mut TestAppContext) {
+        let (root, cx) = init_test(cx);
+        let modal_layer = cx.update(|cx| root.read(cx).modal_layer.clone());
+
+        let first = push_test_modal(&root, cx);
+        let second = modal_layer.update(cx, |modal_layer, cx| {
+            modal_layer.push_modal(cx, |cx| {
+                ConfirmationModal::new("Delete file?", "This can't be undone.", cx)
+            })
+        });
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert!(second.read(cx).focus_handle(cx).is_focused(cx));
+            assert!(modal_layer
+                .read(cx)
+                .active_modal::<ConfirmationModal>()
+                .is_some());
+        });
+
+        // Only the topmost modal is dismissed, and focus returns to the one below it.
+        cx.simulate_keystrokes("escape");
+        cx.update(|cx| {
+            assert!(modal_layer.read(cx).active_modal::<TestModal>().is_some());
+            assert!(first.read(cx).focus_handle.is_focused(cx));
+        });
+
+        cx.simulate_keystrokes("escape");
+        assert_no_modals(&root, cx);
+    }
+
+    #[gpui::test]
+    fn test_destructive_confirmation(cx: &mut TestAppContext) {
+        let (root, cx) = init_test(cx);
+        let modal_layer = cx.update(|cx| root.read(cx).modal_layer.clone());
+
+        let confirmed = Rc::new(Cell::new(false));
+        let modal = modal_layer.update(cx, |modal_layer, cx| {
+            let confirmed = confirmed.clone();
+            modal_layer.push_modal(cx, |cx| {
+                ConfirmationModal::new("Discard changes?", "Your edits will be lost.", cx)
+                    .confirm_label("Discard")
+                    .destructive(true)
+                    .dismiss_on_escape(false)
+                    .on_confirm(move |_| confirmed.set(true))
+            })
+        });
+        cx.run_until_parked();
+        let tab_stops = cx.update(|cx| modal.read(cx).tab_stops());
+        let (cancel, confirm) = (&tab_stops[0], &tab_stops[1]);
+        cx.update(|cx| assert!(cancel.is_focused(cx)));
+
+        // Escape is suppressed.
+        cx.simulate_keystrokes("escape");
+        cx.update(|cx| {
+            assert!(modal_layer.read(cx).has_active_modal());
+            assert!(cancel.is_focused(cx));
+        });
+
+        // Tab cycles between the buttons without leaving the modal.
+        cx.simulate_keystrokes("tab");
+        cx.update(|cx| assert!(confirm.is_focused(cx)));
+        cx.simulate_keystrokes("tab");
+        cx.update(|cx| assert!(cancel.is_focused(cx)));
+        cx.simulate_keystrokes("shift-tab");
+        cx.update(|cx| assert!(confirm.is_focused(cx)));
+
+        cx.simulate_keystrokes("enter");
+        assert!(confirmed.get());
+        assert_no_modals(&root, cx);
+    }
+}
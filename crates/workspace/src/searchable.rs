@@ -5,6 +5,7 @@ use gpui::{
     AnyView, AnyWeakView, AppContext, EventEmitter, Subscription, Task, View, ViewContext,
     WeakView, WindowContext,
 };
+use language::Language;
 use project::search::SearchQuery;
 
 use crate::{
@@ -65,6 +66,12 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
 
     fn toggle_filtered_search_ranges(&mut self, _enabled: bool, _cx: &mut ViewContext<Self>) {}
 
+    /// The language whose syntax trees are matched by structural searches in this item.
+    /// Structural search isn't supported when this returns `None`.
+    fn structural_search_language(&self, _cx: &AppContext) -> Option<Arc<Language>> {
+        None
+    }
+
     fn clear_matches(&mut self, cx: &mut ViewContext<Self>);
     fn update_matches(&mut self, matches: &[Self::Match], cx: &mut ViewContext<Self>);
     fn query_suggestion(&mut self, cx: &mut ViewContext<Self>) -> String;
@@ -117,6 +124,7 @@ pub trait SearchableItemHandle: ItemHandle {
     fn downgrade(&self) -> Box<dyn WeakSearchableItemHandle>;
     fn boxed_clone(&self) -> Box<dyn SearchableItemHandle>;
     fn supported_options(&self) -> SearchOptions;
+    fn structural_search_language(&self, cx: &AppContext) -> Option<Arc<Language>>;
     fn subscribe_to_search_events(
         &self,
         cx: &mut WindowContext,
@@ -175,6 +183,10 @@ impl<T: SearchableItem> SearchableItemHandle for View<T> {
         T::supported_options()
     }
 
+    fn structural_search_language(&self, cx: &AppContext) -> Option<Arc<Language>> {
+        self.read(cx).structural_search_language(cx)
+    }
+
     fn subscribe_to_search_events(
         &self,
         cx: &mut WindowContext,
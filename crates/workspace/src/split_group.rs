@@ -0,0 +1,783 @@
+use std::rc::Rc;
+
+use collections::HashSet;
+use gpui::views::{
+    dragged_divider_cursor, resize_cursor, resize_flexes, split_member, DraggedDivider,
+};
+use gpui::{
+    deferred, div, hsla, prelude::FluentBuilder, px, AnyElement, Axis, DragMoveEvent, EventEmitter,
+    Hsla, InteractiveElement, IntoElement, MouseButton, ParentElement, Pixels, Render,
+    StatefulInteractiveElement, Styled, ViewContext, VisualContext, WindowContext,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::SplitDirection;
+
+/// Identifies a pane in a [`SplitGroup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SplitPaneId(pub usize);
+
+/// The arrangement of the panes in a [`SplitGroup`].
+///
+/// Layouts can be serialized to persist them, and restored with [`SplitGroup::with_layout`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitLayout {
+    /// A single pane.
+    Pane(SplitPaneId),
+    /// Two or more layouts side by side or one above the other, separated by dividers.
+    Split {
+        /// The axis the members are laid out along.
+        axis: Axis,
+        /// The layouts in the split, from left to right or top to bottom.
+        members: Vec<SplitLayout>,
+        /// How much of the split each member takes up, relative to the others. The space left
+        /// over by the dividers is shared out in proportion to these.
+        flexes: Vec<f32>,
+    },
+}
+
+impl SplitLayout {
+    /// Returns the panes in the layout, from left to right and top to bottom.
+    pub fn panes(&self) -> Vec<SplitPaneId> {
+        let mut panes = Vec::new();
+        self.collect_panes(&mut panes);
+        panes
+    }
+
+    fn collect_panes(&self, panes: &mut Vec<SplitPaneId>) {
+        match self {
+            SplitLayout::Pane(id) => panes.push(*id),
+            SplitLayout::Split { members, .. } => {
+                for member in members {
+                    member.collect_panes(panes);
+                }
+            }
+        }
+    }
+
+    /// Returns the indices of the members leading from this layout to the given pane.
+    fn path_to(&self, pane: SplitPaneId) -> Option<Vec<usize>> {
+        match self {
+            SplitLayout::Pane(id) => (*id == pane).then(Vec::new),
+            SplitLayout::Split { members, .. } => {
+                members.iter().enumerate().find_map(|(ix, member)| {
+                    let mut path = member.path_to(pane)?;
+                    path.insert(0, ix);
+                    Some(path)
+                })
+            }
+        }
+    }
+
+    /// Returns a valid layout with the same panes, or `None` if there are no panes in it.
+    ///
+    /// Repeated panes and empty splits are dropped, splits with a single member are replaced
+    /// by that member, and splits nested in a split along the same axis are merged into it.
+    /// Flexes that don't match the members, or that aren't positive, are reset.
+    fn normalize(self, seen: &mut HashSet<SplitPaneId>) -> Option<SplitLayout> {
+        let (axis, members, flexes) = match self {
+            SplitLayout::Pane(id) => return seen.insert(id).then_some(SplitLayout::Pane(id)),
+            SplitLayout::Split {
+                axis,
+                members,
+                flexes,
+            } => (axis, members, flexes),
+        };
+
+        let valid_flexes = flexes.len() == members.len()
+            && flexes.iter().all(|flex| flex.is_finite() && *flex > 0.);
+        let mut normalized_members = Vec::with_capacity(members.len());
+        let mut normalized_flexes = Vec::with_capacity(members.len());
+        for (ix, member) in members.into_iter().enumerate() {
+            let flex = if valid_flexes { flexes[ix] } else { 1. };
+            match member.normalize(seen) {
+                None => {}
+                Some(SplitLayout::Split {
+                    axis: member_axis,
+                    members,
+                    flexes,
+                }) if member_axis == axis => {
+                    let total_flex = flexes.iter().sum::<f32>();
+                    normalized_members.extend(members);
+                    normalized_flexes.extend(flexes.iter().map(|f| f / total_flex * flex));
+                }
+                Some(member) => {
+                    normalized_members.push(member);
+                    normalized_flexes.push(flex);
+                }
+            }
+        }
+
+        match normalized_members.len() {
+            0 => None,
+            1 => normalized_members.pop(),
+            _ => Some(SplitLayout::Split {
+                axis,
+                members: normalized_members,
+                flexes: normalized_flexes,
+            }),
+        }
+    }
+
+    fn get_mut(&mut self, path: &[usize]) -> Option<&mut SplitLayout> {
+        match path.split_first() {
+            None => Some(self),
+            Some((ix, rest)) => match self {
+                SplitLayout::Pane(_) => None,
+                SplitLayout::Split { members, .. } => members.get_mut(*ix)?.get_mut(rest),
+            },
+        }
+    }
+}
+
+/// An event emitted by a [`SplitGroup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitGroupEvent {
+    /// A pane was split or closed, or a divider was dragged.
+    LayoutChanged,
+}
+
+/// A tree of panes split horizontally and vertically, with dividers between them that the
+/// user can drag to resize them.
+///
+/// Unlike [`PaneGroup`](crate::PaneGroup), which arranges the workspace's [`Pane`](crate::Pane)s,
+/// the group doesn't own what is shown in its panes. Instead it calls a function with the id
+/// of each pane when it renders, so a pane can show any element. Panes are added with
+/// [`SplitGroup::split`] and removed with [`SplitGroup::close`], and the group emits
+/// [`SplitGroupEvent::LayoutChanged`] whenever its [`SplitLayout`] changes so that its owner can
+/// persist it.
+///
+/// Dividers are painted [`SplitGroup::divider_size`] wide, but can be grabbed from a wider area
+/// around them, and no pane can be dragged smaller than [`SplitGroup::min_size`].
+pub struct SplitGroup {
+    layout: SplitLayout,
+    next_pane_id: usize,
+    render_pane: Rc<dyn Fn(SplitPaneId, &mut WindowContext) -> AnyElement>,
+    min_size: Pixels,
+    divider_size: Pixels,
+    divider_hit_size: Pixels,
+    divider_color: Hsla,
+}
+
+impl SplitGroup {
+    /// The width of the dividers between panes, unless set with [`SplitGroup::divider_size`].
+    pub const DEFAULT_DIVIDER_SIZE: Pixels = px(1.);
+
+    /// The width of the area around each divider that can be dragged, unless set with
+    /// [`SplitGroup::divider_hit_size`].
+    pub const DEFAULT_DIVIDER_HIT_SIZE: Pixels = px(6.);
+
+    /// The smallest size of a pane, unless set with [`SplitGroup::min_size`].
+    pub const DEFAULT_MIN_SIZE: Pixels = px(80.);
+
+    /// Creates a group with a single pane, which has the id `SplitPaneId(0)`.
+    pub fn new(
+        render_pane: impl Fn(SplitPaneId, &mut WindowContext) -> AnyElement + 'static,
+    ) -> Self {
+        Self {
+            layout: SplitLayout::Pane(SplitPaneId(0)),
+            next_pane_id: 1,
+            render_pane: Rc::new(render_pane),
+            min_size: Self::DEFAULT_MIN_SIZE,
+            divider_size: Self::DEFAULT_DIVIDER_SIZE,
+            divider_hit_size: Self::DEFAULT_DIVIDER_HIT_SIZE,
+            divider_color: hsla(0., 0., 0.5, 0.3),
+        }
+    }
+
+    /// Restores a layout returned by [`SplitGroup::layout`].
+    ///
+    /// Layouts that [`SplitGroup::layout`] couldn't have returned, such as ones read from a
+    /// corrupted file, are repaired so that the group can still use them.
+    pub fn with_layout(mut self, layout: SplitLayout) -> Self {
+        let layout = layout
+            .normalize(&mut HashSet::default())
+            .unwrap_or(SplitLayout::Pane(SplitPaneId(0)));
+        self.next_pane_id = layout
+            .panes()
+            .into_iter()
+            .map(|pane| pane.0 + 1)
+            .max()
+            .unwrap_or(0);
+        self.layout = layout;
+        self
+    }
+
+    /// Sets the smallest size each pane can be dragged to.
+    pub fn min_size(mut self, min_size: impl Into<Pixels>) -> Self {
+        self.min_size = min_size.into();
+        self
+    }
+
+    /// Sets the painted width of the dividers.
+    pub fn divider_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.divider_size = size.into();
+        self
+    }
+
+    /// Sets the width of the area around each divider that can be dragged. It is never
+    /// narrower than the divider itself.
+    pub fn divider_hit_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.divider_hit_size = size.into();
+        self
+    }
+
+    /// Sets the color of the dividers.
+    pub fn divider_color(mut self, color: impl Into<Hsla>) -> Self {
+        self.divider_color = color.into();
+        self
+    }
+
+    /// Returns the current arrangement of the panes.
+    pub fn layout(&self) -> &SplitLayout {
+        &self.layout
+    }
+
+    /// Returns the panes in the group, from left to right and top to bottom.
+    pub fn panes(&self) -> Vec<SplitPaneId> {
+        self.layout.panes()
+    }
+
+    /// Adds a new pane next to the given one, taking half of its space, and returns the new
+    /// pane's id. Returns `None` if there's no such pane in the group.
+    pub fn split(
+        &mut self,
+        pane: SplitPaneId,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<SplitPaneId> {
+        let new_pane = SplitPaneId(self.next_pane_id);
+        split_layout(&mut self.layout, pane, new_pane, direction)?;
+        self.next_pane_id += 1;
+        cx.emit(SplitGroupEvent::LayoutChanged);
+        cx.notify();
+        Some(new_pane)
+    }
+
+    /// Removes the given pane, giving its space to its neighbor, and returns whether it was
+    /// removed. The last pane in the group can't be closed.
+    pub fn close(&mut self, pane: SplitPaneId, cx: &mut ViewContext<Self>) -> bool {
+        if !close_layout(&mut self.layout, pane) {
+            return false;
+        }
+        cx.emit(SplitGroupEvent::LayoutChanged);
+        cx.notify();
+        true
+    }
+
+    fn resize(&mut self, event: &DragMoveEvent<DraggedDivider>, cx: &mut ViewContext<Self>) {
+        let divider = event.drag(cx).clone();
+        if divider.owner != cx.entity_id() {
+            return;
+        }
+        let Some(SplitLayout::Split { axis, flexes, .. }) = self.layout.get_mut(&divider.split)
+        else {
+            return;
+        };
+
+        if resize_flexes(
+            flexes,
+            divider.ix,
+            *axis,
+            event,
+            self.divider_size,
+            self.min_size,
+        ) {
+            cx.emit(SplitGroupEvent::LayoutChanged);
+            cx.notify();
+        }
+    }
+
+    fn render_layout(
+        &self,
+        layout: &SplitLayout,
+        path: &mut Vec<usize>,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let (axis, members, flexes) = match layout {
+            SplitLayout::Pane(id) => {
+                let id = *id;
+                return div()
+                    .debug_selector(|| format!("PANE-{}", id.0))
+                    .size_full()
+                    .overflow_hidden()
+                    .child((self.render_pane)(id, cx))
+                    .into_any_element();
+            }
+            SplitLayout::Split {
+                axis,
+                members,
+                flexes,
+            } => (*axis, members, flexes),
+        };
+
+        let split: Rc<[usize]> = path.as_slice().into();
+        let mut children = Vec::with_capacity(members.len() * 2);
+        for (ix, (member, flex)) in members.iter().zip(flexes).enumerate() {
+            if ix > 0 {
+                children.push(self.render_divider(axis, split.clone(), ix - 1, cx));
+            }
+
+            path.push(ix);
+            let member = self.render_layout(member, path, cx);
+            path.pop();
+
+            children.push(split_member(axis, *flex).child(member).into_any_element());
+        }
+
+        div()
+            .flex()
+            .map(|this| match axis {
+                Axis::Horizontal => this.flex_row(),
+                Axis::Vertical => this.flex_col(),
+            })
+            .size_full()
+            .on_drag_move(cx.listener({
+                let split = split.clone();
+                move |this, event: &DragMoveEvent<DraggedDivider>, cx| {
+                    if event.drag(cx).split == split {
+                        this.resize(event, cx);
+                    }
+                }
+            }))
+            .children(children)
+            .into_any_element()
+    }
+
+    fn render_divider(
+        &self,
+        axis: Axis,
+        split: Rc<[usize]>,
+        ix: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let debug_selector = format!(
+            "SPLIT-GROUP-DIVIDER-{}",
+            split
+                .iter()
+                .chain([&ix])
+                .map(|ix| ix.to_string())
+                .collect::<Vec<_>>()
+                .join("-")
+        );
+        let hit_size = self.divider_hit_size.max(self.divider_size);
+        let overhang = (self.divider_size - hit_size) / 2.;
+        let cursor = resize_cursor(axis);
+
+        // The hit area hangs over the panes on either side, so it's deferred to be painted
+        // above them and get their mouse events.
+        let hit_area = div()
+            .id(("split-group-divider", ix))
+            .debug_selector(|| format!("{debug_selector}-HIT-AREA"))
+            .absolute()
+            .cursor(cursor)
+            .map(|this| match axis {
+                Axis::Horizontal => this.top_0().left(overhang).w(hit_size).h_full(),
+                Axis::Vertical => this.left_0().top(overhang).h(hit_size).w_full(),
+            })
+            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+            .on_drag(
+                DraggedDivider {
+                    owner: cx.entity_id(),
+                    axis,
+                    split,
+                    ix,
+                },
+                |divider, cx| {
+                    cx.stop_propagation();
+                    cx.new_view(|_| divider.clone())
+                },
+            );
+
+        div()
+            .debug_selector(|| debug_selector.clone())
+            .relative()
+            .flex_none()
+            .bg(self.divider_color)
+            .map(|this| match axis {
+                Axis::Horizontal => this.w(self.divider_size).h_full(),
+                Axis::Vertical => this.h(self.divider_size).w_full(),
+            })
+            .child(deferred(hit_area))
+            .into_any_element()
+    }
+}
+
+/// Adds `new_pane` next to `pane`, returning `None` if `pane` isn't in the layout.
+fn split_layout(
+    layout: &mut SplitLayout,
+    pane: SplitPaneId,
+    new_pane: SplitPaneId,
+    direction: SplitDirection,
+) -> Option<()> {
+    let path = layout.path_to(pane)?;
+    if let Some((&ix, parent_path)) = path.split_last() {
+        if let Some(SplitLayout::Split {
+            axis,
+            members,
+            flexes,
+        }) = layout.get_mut(parent_path)
+        {
+            // Splitting along the parent's axis adds a sibling rather than nesting a split.
+            if *axis == direction.axis() {
+                let flex = flexes[ix] / 2.;
+                flexes[ix] = flex;
+                let new_ix = if !direction.increasing() { ix } else { ix + 1 };
+                members.insert(new_ix, SplitLayout::Pane(new_pane));
+                flexes.insert(new_ix, flex);
+                return Some(());
+            }
+        }
+    }
+
+    let member = layout.get_mut(&path)?;
+    let mut members = vec![member.clone(), SplitLayout::Pane(new_pane)];
+    if !direction.increasing() {
+        members.reverse();
+    }
+    *member = SplitLayout::Split {
+        axis: direction.axis(),
+        members,
+        flexes: vec![1., 1.],
+    };
+    Some(())
+}
+
+/// Removes `pane` from the layout, returning whether it was removed.
+///
+/// A split left with a single member is replaced by that member, and if the member is itself
+/// a split along the same axis as the split around it, its members are moved into that one.
+fn close_layout(layout: &mut SplitLayout, pane: SplitPaneId) -> bool {
+    let Some(path) = layout.path_to(pane) else {
+        return false;
+    };
+    let Some((&ix, parent_path)) = path.split_last() else {
+        // This is the only pane.
+        return false;
+    };
+    let Some(parent) = layout.get_mut(parent_path) else {
+        return false;
+    };
+    let SplitLayout::Split {
+        members, flexes, ..
+    } = parent
+    else {
+        return false;
+    };
+
+    members.remove(ix);
+    let flex = flexes.remove(ix);
+    let neighbor_ix = ix.saturating_sub(1);
+    flexes[neighbor_ix] += flex;
+    if members.len() > 1 {
+        return true;
+    }
+
+    let remaining = members.remove(0);
+    *parent = remaining;
+    let Some((&parent_ix, grandparent_path)) = parent_path.split_last() else {
+        return true;
+    };
+    let Some(SplitLayout::Split {
+        axis: outer_axis,
+        members: outer_members,
+        flexes: outer_flexes,
+    }) = layout.get_mut(grandparent_path)
+    else {
+        return true;
+    };
+    if let SplitLayout::Split {
+        axis,
+        members,
+        flexes,
+    } = &outer_members[parent_ix]
+    {
+        if *axis == *outer_axis {
+            let members = members.clone();
+            let total_flex = flexes.iter().sum::<f32>();
+            let scale = outer_flexes[parent_ix] / total_flex;
+            let flexes = flexes.iter().map(|flex| flex * scale).collect::<Vec<_>>();
+            outer_members.splice(parent_ix..=parent_ix, members);
+            outer_flexes.splice(parent_ix..=parent_ix, flexes);
+        }
+    }
+    true
+}
+
+impl EventEmitter<SplitGroupEvent> for SplitGroup {}
+
+impl Render for SplitGroup {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .relative()
+            .size_full()
+            .child(self.render_layout(&self.layout, &mut Vec::new(), cx))
+            .children(dragged_divider_cursor(cx.entity_id(), cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use gpui::{point, size, Empty, Modifiers, TestAppContext, View, VisualTestContext};
+
+    fn split_group(cx: &mut TestAppContext) -> (View<SplitGroup>, &mut VisualTestContext) {
+        let (group, cx) = cx.add_window_view(|_| {
+            SplitGroup::new(|_, _| Empty.into_any_element())
+                .divider_size(px(2.))
+                .divider_hit_size(px(8.))
+                .min_size(px(100.))
+        });
+        cx.simulate_resize(size(px(1026.), px(1028.)));
+        (group, cx)
+    }
+
+    #[gpui::test]
+    fn test_nested_splits(cx: &mut TestAppContext) {
+        let (group, cx) = split_group(cx);
+
+        let (right, bottom_right, top_right) = group.update(cx, |group, cx| {
+            let right = group
+                .split(SplitPaneId(0), SplitDirection::Right, cx)
+                .unwrap();
+            let bottom_right = group.split(right, SplitDirection::Down, cx).unwrap();
+            let top_right = group.split(right, SplitDirection::Up, cx).unwrap();
+            (right, bottom_right, top_right)
+        });
+        cx.run_until_parked();
+
+        group.update(cx, |group, _| {
+            assert_eq!(
+                group.layout(),
+                &SplitLayout::Split {
+                    axis: Axis::Horizontal,
+                    members: vec![
+                        SplitLayout::Pane(SplitPaneId(0)),
+                        SplitLayout::Split {
+                            axis: Axis::Vertical,
+                            members: vec![
+                                SplitLayout::Pane(top_right),
+                                SplitLayout::Pane(right),
+                                SplitLayout::Pane(bottom_right),
+                            ],
+                            flexes: vec![0.5, 0.5, 1.],
+                        },
+                    ],
+                    flexes: vec![1., 1.],
+                }
+            );
+        });
+
+        // Each column gets half of the 1024px left over by the divider, and the right column's
+        // 1024px of height is shared out in proportion to the flexes of its panes.
+        assert_eq!(
+            (right, bottom_right, top_right),
+            (SplitPaneId(1), SplitPaneId(2), SplitPaneId(3))
+        );
+        let left = cx.debug_bounds("PANE-0").unwrap();
+        assert_eq!((left.origin.x, left.size.width), (px(0.), px(512.)));
+        assert_eq!(left.size.height, px(1028.));
+        let top = cx.debug_bounds("PANE-3").unwrap();
+        assert_eq!((top.origin.x, top.size.width), (px(514.), px(512.)));
+        assert_eq!((top.origin.y, top.size.height), (px(0.), px(256.)));
+        let middle = cx.debug_bounds("PANE-1").unwrap();
+        assert_eq!((middle.origin.y, middle.size.height), (px(258.), px(256.)));
+        let bottom = cx.debug_bounds("PANE-2").unwrap();
+        assert_eq!((bottom.origin.y, bottom.size.height), (px(516.), px(512.)));
+
+        // Layouts can be persisted and restored.
+        let layout = group.update(cx, |group, _| group.layout().clone());
+        let json = serde_json::to_string(&layout).unwrap();
+        let restored = serde_json::from_str::<SplitLayout>(&json).unwrap();
+        assert_eq!(restored, layout);
+        let restored = SplitGroup::new(|_, _| Empty.into_any_element()).with_layout(restored);
+        assert_eq!(
+            restored.panes(),
+            [SplitPaneId(0), top_right, right, bottom_right]
+        );
+        assert_eq!(restored.next_pane_id, 4);
+    }
+
+    #[gpui::test]
+    fn test_closing_panes(cx: &mut TestAppContext) {
+        let (group, cx) = split_group(cx);
+
+        group.update(cx, |group, cx| {
+            assert!(!group.close(SplitPaneId(0), cx));
+
+            let right = group
+                .split(SplitPaneId(0), SplitDirection::Right, cx)
+                .unwrap();
+            let bottom = group.split(right, SplitDirection::Down, cx).unwrap();
+            let bottom_right = group.split(bottom, SplitDirection::Right, cx).unwrap();
+
+            // Closing one of two panes in a split replaces the split with the other pane.
+            assert!(group.close(right, cx));
+            assert_eq!(
+                group.layout(),
+                &SplitLayout::Split {
+                    axis: Axis::Horizontal,
+                    members: vec![
+                        SplitLayout::Pane(SplitPaneId(0)),
+                        SplitLayout::Pane(bottom),
+                        SplitLayout::Pane(bottom_right),
+                    ],
+                    flexes: vec![1., 0.5, 0.5],
+                }
+            );
+
+            // The closed pane's space goes to the pane before it.
+            assert!(group.close(bottom, cx));
+            assert_eq!(
+                group.layout(),
+                &SplitLayout::Split {
+                    axis: Axis::Horizontal,
+                    members: vec![
+                        SplitLayout::Pane(SplitPaneId(0)),
+                        SplitLayout::Pane(bottom_right),
+                    ],
+                    flexes: vec![1.5, 0.5],
+                }
+            );
+
+            assert!(group.close(SplitPaneId(0), cx));
+            assert_eq!(group.layout(), &SplitLayout::Pane(bottom_right));
+            assert!(!group.close(SplitPaneId(0), cx));
+        });
+    }
+
+    #[test]
+    fn test_invalid_layouts() {
+        let group = |layout| SplitGroup::new(|_, _| Empty.into_any_element()).with_layout(layout);
+        let pane = |id| SplitLayout::Pane(SplitPaneId(id));
+
+        let restored = group(SplitLayout::Split {
+            axis: Axis::Horizontal,
+            members: Vec::new(),
+            flexes: Vec::new(),
+        });
+        assert_eq!(restored.layout(), &pane(0));
+        assert_eq!(restored.next_pane_id, 1);
+
+        // Flexes that don't match the members are reset, and repeated panes, empty splits
+        // and splits with a single member are removed.
+        let restored = group(SplitLayout::Split {
+            axis: Axis::Horizontal,
+            members: vec![
+                pane(1),
+                SplitLayout::Split {
+                    axis: Axis::Vertical,
+                    members: vec![pane(1), pane(2)],
+                    flexes: vec![1.],
+                },
+                SplitLayout::Split {
+                    axis: Axis::Vertical,
+                    members: Vec::new(),
+                    flexes: vec![1.],
+                },
+            ],
+            flexes: vec![2., f32::NAN, 1.],
+        });
+        assert_eq!(
+            restored.layout(),
+            &SplitLayout::Split {
+                axis: Axis::Horizontal,
+                members: vec![pane(1), pane(2)],
+                flexes: vec![1., 1.],
+            }
+        );
+
+        // Splits nested in a split along the same axis are merged into it.
+        let mut restored = group(SplitLayout::Split {
+            axis: Axis::Horizontal,
+            members: vec![
+                pane(0),
+                SplitLayout::Split {
+                    axis: Axis::Horizontal,
+                    members: vec![pane(1), pane(2)],
+                    flexes: vec![1., 3.],
+                },
+            ],
+            flexes: vec![1., 2.],
+        });
+        assert_eq!(
+            restored.layout(),
+            &SplitLayout::Split {
+                axis: Axis::Horizontal,
+                members: vec![pane(0), pane(1), pane(2)],
+                flexes: vec![1., 0.5, 1.5],
+            }
+        );
+
+        // The repaired layouts can be changed like any other.
+        assert!(close_layout(&mut restored.layout, SplitPaneId(1)));
+        assert!(split_layout(
+            &mut restored.layout,
+            SplitPaneId(2),
+            SplitPaneId(3),
+            SplitDirection::Up
+        )
+        .is_some());
+    }
+
+    #[gpui::test]
+    fn test_dragging_dividers(cx: &mut TestAppContext) {
+        let (group, cx) = split_group(cx);
+        let events = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&group, move |_, event: &SplitGroupEvent, _| {
+                events.borrow_mut().push(*event)
+            })
+            .detach();
+        });
+        group.update(cx, |group, cx| {
+            let right = group
+                .split(SplitPaneId(0), SplitDirection::Right, cx)
+                .unwrap();
+            group.split(right, SplitDirection::Down, cx).unwrap();
+        });
+        cx.run_until_parked();
+        events.borrow_mut().clear();
+
+        let divider = cx.debug_bounds("SPLIT-GROUP-DIVIDER-0").unwrap();
+        assert_eq!((divider.origin.x, divider.size.width), (px(512.), px(2.)));
+        let hit_area = cx.debug_bounds("SPLIT-GROUP-DIVIDER-0-HIT-AREA").unwrap();
+        assert_eq!((hit_area.origin.x, hit_area.size.width), (px(509.), px(8.)));
+
+        // Grab the divider from just inside the pane to its right.
+        let y = divider.center().y;
+        cx.simulate_mouse_down(point(px(516.), y), MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(point(px(400.), y), MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(point(px(257.), y), MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(point(px(257.), y), MouseButton::Left, Modifiers::none());
+        group.update(cx, |group, _| {
+            let SplitLayout::Split { flexes, .. } = group.layout() else {
+                panic!("expected a split");
+            };
+            assert_eq!(flexes, &[0.5, 1.5]);
+        });
+        assert_eq!(
+            events.borrow().last(),
+            Some(&SplitGroupEvent::LayoutChanged)
+        );
+        let left = cx.debug_bounds("PANE-0").unwrap();
+        assert_eq!(left.size.width, px(256.));
+
+        // Dragging the nested divider only resizes the panes in its split, and stops at the
+        // minimum size.
+        let divider = cx.debug_bounds("SPLIT-GROUP-DIVIDER-1-0").unwrap();
+        let x = divider.center().x;
+        cx.simulate_mouse_down(divider.center(), MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(point(x, px(250.)), MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(point(x, px(10.)), MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(point(x, px(10.)), MouseButton::Left, Modifiers::none());
+        let top_right = cx.debug_bounds("PANE-1").unwrap();
+        assert_eq!(top_right.size.height, px(100.));
+        let left = cx.debug_bounds("PANE-0").unwrap();
+        assert_eq!(left.size.width, px(256.));
+    }
+}
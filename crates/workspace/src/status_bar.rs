@@ -1,11 +1,16 @@
-use crate::{ItemHandle, Pane};
+use crate::{
+    workspace_settings::StatusBarSettings, ItemHandle, Pane, RestartToUpdate, ToggleStatusBar,
+    ToggleStatusBarItem,
+};
 use gpui::{
-    AnyView, Decorations, IntoElement, ParentElement, Render, Styled, Subscription, View,
+    anchored, deferred, AnchorCorner, AnyView, AppContext, Decorations, DismissEvent, IntoElement,
+    MouseButton, MouseDownEvent, ParentElement, Pixels, Point, Render, Styled, Subscription, View,
     ViewContext, WindowContext,
 };
+use settings::Settings;
 use std::any::TypeId;
 use theme::CLIENT_SIDE_DECORATION_ROUNDING;
-use ui::{h_flex, prelude::*};
+use ui::{h_flex, prelude::*, ContextMenu, UpdateIndicator, UpdateState};
 use util::ResultExt;
 
 pub trait StatusItemView: Render {
@@ -14,6 +19,17 @@ pub trait StatusItemView: Render {
         active_pane_item: Option<&dyn crate::ItemHandle>,
         cx: &mut ViewContext<Self>,
     );
+
+    /// Builds the menu shown when this item is right-clicked.
+    fn context_menu(&mut self, _cx: &mut ViewContext<Self>) -> Option<View<ContextMenu>> {
+        None
+    }
+
+    /// The id under which this item's visibility is stored in the
+    /// `status_bar.items` setting. Items without an id can't be hidden.
+    fn status_item_id(&self, _cx: &AppContext) -> Option<&'static str> {
+        None
+    }
 }
 
 trait StatusItemViewHandle: Send {
@@ -24,12 +40,17 @@ trait StatusItemViewHandle: Send {
         cx: &mut WindowContext,
     );
     fn item_type(&self) -> TypeId;
+    fn context_menu(&self, cx: &mut WindowContext) -> Option<View<ContextMenu>>;
+    fn status_item_id(&self, cx: &AppContext) -> Option<&'static str>;
 }
 
 pub struct StatusBar {
+    mode_indicator: Option<Box<dyn StatusItemViewHandle>>,
     left_items: Vec<Box<dyn StatusItemViewHandle>>,
     right_items: Vec<Box<dyn StatusItemViewHandle>>,
+    update_state: UpdateState,
     active_pane: View<Pane>,
+    context_menu: Option<(View<ContextMenu>, Point<Pixels>, Subscription)>,
     _observe_active_pane: Subscription,
 }
 
@@ -56,8 +77,24 @@ impl Render for StatusBar {
                     .border_b(px(1.0))
                     .border_color(cx.theme().colors().status_bar_background),
             })
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(|this, event: &MouseDownEvent, cx| {
+                    this.deploy_bar_context_menu(event.position, cx)
+                }),
+            )
             .child(self.render_left_tools(cx))
             .child(self.render_right_tools(cx))
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(AnchorCorner::BottomLeft)
+                        .snap_to_window()
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
     }
 }
 
@@ -66,22 +103,156 @@ impl StatusBar {
         h_flex()
             .gap(Spacing::Large.rems(cx))
             .overflow_x_hidden()
-            .children(self.left_items.iter().map(|item| item.to_any()))
+            .children(
+                self.mode_indicator
+                    .as_ref()
+                    .filter(|item| Self::is_item_visible(item.as_ref(), cx))
+                    .map(|item| item.to_any()),
+            )
+            .children(
+                self.left_items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| Self::is_item_visible(item.as_ref(), cx))
+                    .map(|(ix, item)| self.render_item(ix, item.as_ref(), cx)),
+            )
     }
 
     fn render_right_tools(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let left_len = self.left_items.len();
         h_flex()
             .gap(Spacing::Large.rems(cx))
-            .children(self.right_items.iter().rev().map(|item| item.to_any()))
+            .children(
+                self.right_items
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .filter(|(_, item)| Self::is_item_visible(item.as_ref(), cx))
+                    .map(|(ix, item)| self.render_item(left_len + ix, item.as_ref(), cx)),
+            )
+            .child(
+                UpdateIndicator::new(self.update_state.clone()).on_restart(|_, cx| {
+                    cx.dispatch_action(Box::new(RestartToUpdate));
+                }),
+            )
+    }
+
+    fn is_item_visible(item: &dyn StatusItemViewHandle, cx: &AppContext) -> bool {
+        item.status_item_id(cx).map_or(true, |id| {
+            StatusBarSettings::get_global(cx).is_item_visible(id)
+        })
     }
+
+    fn render_item(
+        &self,
+        position: usize,
+        item: &dyn StatusItemViewHandle,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        div().child(item.to_any()).on_mouse_down(
+            MouseButton::Right,
+            cx.listener(move |this, event: &MouseDownEvent, cx| {
+                if this.deploy_item_context_menu(position, event.position, cx) {
+                    cx.stop_propagation();
+                }
+            }),
+        )
+    }
+
+    /// Opens the context menu of the item at the given position, returning
+    /// whether the item provided one.
+    fn deploy_item_context_menu(
+        &mut self,
+        position: usize,
+        mouse_position: Point<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        let item = if position < self.left_items.len() {
+            self.left_items.get(position)
+        } else {
+            self.right_items.get(position - self.left_items.len())
+        };
+        let Some(context_menu) = item.and_then(|item| item.context_menu(cx)) else {
+            return false;
+        };
+
+        self.show_context_menu(context_menu, mouse_position, cx);
+        true
+    }
+
+    /// Opens the menu for the bar itself, which allows hiding the bar and
+    /// toggling each of the items that can be hidden.
+    fn deploy_bar_context_menu(
+        &mut self,
+        mouse_position: Point<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let settings = StatusBarSettings::get_global(cx);
+        let items = self
+            .mode_indicator
+            .iter()
+            .chain(&self.left_items)
+            .chain(&self.right_items)
+            .filter_map(|item| item.status_item_id(cx))
+            .map(|id| (id, settings.is_item_visible(id)))
+            .collect::<Vec<_>>();
+
+        let context_menu = ContextMenu::build(cx, move |menu, _| {
+            let mut menu = menu.entry("Hide Status Bar", Some(Box::new(ToggleStatusBar)), |cx| {
+                cx.dispatch_action(Box::new(ToggleStatusBar))
+            });
+            if !items.is_empty() {
+                menu = menu.separator();
+            }
+            for (id, visible) in items {
+                menu = menu.toggleable_entry(item_label(id), visible, None, move |cx| {
+                    cx.dispatch_action(Box::new(ToggleStatusBarItem { id: id.into() }))
+                });
+            }
+            menu
+        });
+        self.show_context_menu(context_menu, mouse_position, cx);
+    }
+
+    fn show_context_menu(
+        &mut self,
+        context_menu: View<ContextMenu>,
+        mouse_position: Point<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        cx.focus_view(&context_menu);
+        let subscription = cx.subscribe(&context_menu, |this, _, _: &DismissEvent, cx| {
+            this.context_menu.take();
+            cx.notify();
+        });
+        self.context_menu = Some((context_menu, mouse_position, subscription));
+        cx.notify();
+    }
+}
+
+/// Turns an item id such as `cursor_position` into a menu label.
+fn item_label(id: &str) -> String {
+    id.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 impl StatusBar {
     pub fn new(active_pane: &View<Pane>, cx: &mut ViewContext<Self>) -> Self {
         let mut this = Self {
+            mode_indicator: None,
             left_items: Default::default(),
             right_items: Default::default(),
+            update_state: UpdateState::None,
             active_pane: active_pane.clone(),
+            context_menu: None,
             _observe_active_pane: cx
                 .observe(active_pane, |this, _, cx| this.update_active_pane_item(cx)),
         };
@@ -100,9 +271,23 @@ impl StatusBar {
         cx.notify();
     }
 
+    /// Sets the item displaying the mode of a modal editor, which is placed
+    /// before all of the other items.
+    pub fn set_mode_indicator<T>(&mut self, item: View<T>, cx: &mut ViewContext<Self>)
+    where
+        T: 'static + StatusItemView,
+    {
+        let active_pane_item = self.active_pane.read(cx).active_item();
+        item.set_active_pane_item(active_pane_item.as_deref(), cx);
+
+        self.mode_indicator = Some(Box::new(item));
+        cx.notify();
+    }
+
     pub fn item_of_type<T: StatusItemView>(&self) -> Option<View<T>> {
-        self.left_items
+        self.mode_indicator
             .iter()
+            .chain(self.left_items.iter())
             .chain(self.right_items.iter())
             .find_map(|item| item.to_any().clone().downcast().log_err())
     }
@@ -164,6 +349,15 @@ impl StatusBar {
         cx.notify();
     }
 
+    /// Sets the state of the application update shown at the far right of
+    /// the status bar.
+    pub fn update_status(&mut self, state: UpdateState, cx: &mut ViewContext<Self>) {
+        if self.update_state != state {
+            self.update_state = state;
+            cx.notify();
+        }
+    }
+
     pub fn set_active_pane(&mut self, active_pane: &View<Pane>, cx: &mut ViewContext<Self>) {
         self.active_pane = active_pane.clone();
         self._observe_active_pane =
@@ -173,7 +367,12 @@ impl StatusBar {
 
     fn update_active_pane_item(&mut self, cx: &mut ViewContext<Self>) {
         let active_pane_item = self.active_pane.read(cx).active_item();
-        for item in self.left_items.iter().chain(&self.right_items) {
+        for item in self
+            .mode_indicator
+            .iter()
+            .chain(&self.left_items)
+            .chain(&self.right_items)
+        {
             item.set_active_pane_item(active_pane_item.as_deref(), cx);
         }
     }
@@ -197,6 +396,14 @@ impl<T: StatusItemView> StatusItemViewHandle for View<T> {
     fn item_type(&self) -> TypeId {
         TypeId::of::<T>()
     }
+
+    fn context_menu(&self, cx: &mut WindowContext) -> Option<View<ContextMenu>> {
+        self.update(cx, |this, cx| this.context_menu(cx))
+    }
+
+    fn status_item_id(&self, cx: &AppContext) -> Option<&'static str> {
+        self.read(cx).status_item_id(cx)
+    }
 }
 
 impl From<&dyn StatusItemViewHandle> for AnyView {
@@ -1,3 +1,4 @@
+mod confirmation_modal;
 pub mod dock;
 pub mod item;
 mod modal_layer;
@@ -7,6 +8,7 @@ pub mod pane_group;
 mod persistence;
 pub mod searchable;
 pub mod shared_screen;
+mod split_group;
 mod status_bar;
 pub mod tasks;
 mod toolbar;
@@ -19,6 +21,7 @@ use client::{
     ChannelId, Client, DevServerProjectId, ErrorExt, ProjectId, Status, TypedEnvelope, UserStore,
 };
 use collections::{hash_map, HashMap, HashSet};
+pub use confirmation_modal::*;
 use derive_more::{Deref, DerefMut};
 use dock::{Dock, DockPosition, Panel, PanelButtons, PanelHandle};
 use futures::{
@@ -57,6 +60,7 @@ use project::{Project, ProjectEntryId, ProjectPath, Worktree, WorktreeId};
 use serde::Deserialize;
 use settings::Settings;
 use shared_screen::SharedScreen;
+pub use split_group::*;
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
@@ -82,13 +86,14 @@ pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemVie
 pub use ui;
 use ui::{
     div, h_flex, px, Context as _, Div, FluentBuilder, InteractiveElement as _, IntoElement,
-    ParentElement as _, Pixels, SharedString, Styled as _, ViewContext, VisualContext as _,
-    WindowContext,
+    ParentElement as _, Pixels, SharedString, Styled as _, ToastId, ToastLayer, ViewContext,
+    VisualContext as _, WindowContext,
 };
 use util::{maybe, ResultExt};
 use uuid::Uuid;
 pub use workspace_settings::{
-    AutosaveSetting, RestoreOnStartupBehaviour, TabBarSettings, WorkspaceSettings,
+    AutosaveSetting, RestoreOnStartupBehaviour, StatusBarSettings, TabBarSettings,
+    WorkspaceSettings,
 };
 
 use crate::persistence::{
@@ -130,12 +135,14 @@ actions!(
         Open,
         OpenInTerminal,
         ReloadActiveItem,
+        RestartToUpdate,
         SaveAs,
         SaveWithoutFormat,
         ToggleBottomDock,
         ToggleCenteredLayout,
         ToggleLeftDock,
         ToggleRightDock,
+        ToggleStatusBar,
         ToggleZoom,
         Unfollow,
         Welcome,
@@ -186,6 +193,11 @@ pub struct CloseInactiveTabsAndPanes {
 #[derive(Clone, Deserialize, PartialEq)]
 pub struct SendKeystrokes(pub String);
 
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct ToggleStatusBarItem {
+    pub id: String,
+}
+
 #[derive(Clone, Deserialize, PartialEq, Default)]
 pub struct Reload {
     pub binary_path: Option<PathBuf>,
@@ -215,6 +227,7 @@ impl_actions!(
         SaveAll,
         SwapPaneInDirection,
         SendKeystrokes,
+        ToggleStatusBarItem,
     ]
 );
 
@@ -285,6 +298,7 @@ pub fn init_settings(cx: &mut AppContext) {
     ItemSettings::register(cx);
     PreviewTabsSettings::register(cx);
     TabBarSettings::register(cx);
+    StatusBarSettings::register(cx);
 }
 
 pub fn init(app_state: Arc<AppState>, cx: &mut AppContext) {
@@ -590,6 +604,7 @@ pub struct Workspace {
     last_active_view_id: Option<proto::ViewId>,
     status_bar: View<StatusBar>,
     modal_layer: View<ModalLayer>,
+    toast_layer: View<ToastLayer>,
     titlebar_item: Option<AnyView>,
     notifications: Vec<(NotificationId, Box<dyn NotificationHandle>)>,
     project: Model<Project>,
@@ -784,6 +799,7 @@ impl Workspace {
         });
 
         let modal_layer = cx.new_view(|_| ModalLayer::new());
+        let toast_layer = cx.new_view(ToastLayer::new);
 
         let mut active_call = None;
         if let Some(call) = ActiveCall::try_global(cx) {
@@ -865,6 +881,7 @@ impl Workspace {
             last_active_view_id: None,
             status_bar,
             modal_layer,
+            toast_layer,
             titlebar_item: None,
             notifications: Default::default(),
             left_dock,
@@ -3911,6 +3928,8 @@ impl Workspace {
                 }),
             )
             .on_action(cx.listener(Workspace::toggle_centered_layout))
+            .on_action(cx.listener(Workspace::toggle_status_bar))
+            .on_action(cx.listener(Workspace::toggle_status_bar_item))
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -3980,6 +3999,29 @@ impl Workspace {
             .update(cx, |modal_layer, cx| modal_layer.toggle_modal(cx, build))
     }
 
+    /// Opens a modal over any modals that are already open, such as a confirmation prompt for
+    /// an action taken in another modal.
+    pub fn push_modal<V: ModalView, B>(&mut self, cx: &mut WindowContext, build: B) -> View<V>
+    where
+        B: FnOnce(&mut ViewContext<V>) -> V,
+    {
+        self.modal_layer
+            .update(cx, |modal_layer, cx| modal_layer.push_modal(cx, build))
+    }
+
+    /// Shows a toast above the status bar, stacked with the other toasts shown in the
+    /// workspace.
+    pub fn push_toast(&mut self, toast: ui::Toast, cx: &mut WindowContext) -> ToastId {
+        self.toast_layer
+            .update(cx, |toast_layer, cx| toast_layer.push(toast, cx))
+    }
+
+    /// Closes a toast shown with [`Workspace::push_toast`], if it is still shown.
+    pub fn close_toast(&mut self, id: ToastId, cx: &mut WindowContext) {
+        self.toast_layer
+            .update(cx, |toast_layer, cx| toast_layer.dismiss(id, cx))
+    }
+
     pub fn toggle_centered_layout(&mut self, _: &ToggleCenteredLayout, cx: &mut ViewContext<Self>) {
         self.centered_layout = !self.centered_layout;
         if let Some(database_id) = self.database_id() {
@@ -3990,6 +4032,34 @@ impl Workspace {
         cx.notify();
     }
 
+    pub fn toggle_status_bar(&mut self, _: &ToggleStatusBar, cx: &mut ViewContext<Self>) {
+        let visible = StatusBarSettings::get_global(cx).visible;
+        settings::update_settings_file::<StatusBarSettings>(
+            self.app_state.fs.clone(),
+            cx,
+            move |settings| settings.visible = Some(!visible),
+        );
+    }
+
+    pub fn toggle_status_bar_item(
+        &mut self,
+        action: &ToggleStatusBarItem,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let id = action.id.clone();
+        let visible = StatusBarSettings::get_global(cx).is_item_visible(&id);
+        settings::update_settings_file::<StatusBarSettings>(
+            self.app_state.fs.clone(),
+            cx,
+            move |settings| {
+                settings
+                    .items
+                    .get_or_insert_with(Default::default)
+                    .insert(id, !visible);
+            },
+        );
+    }
+
     fn adjust_padding(padding: Option<f32>) -> f32 {
         padding
             .unwrap_or(Self::DEFAULT_PADDING)
@@ -4305,9 +4375,12 @@ impl Render for Workspace {
                             })
                         }))
                         .child(self.modal_layer.clone())
-                        .children(self.render_notifications(cx)),
+                        .children(self.render_notifications(cx))
+                        .child(self.toast_layer.clone()),
                 )
-                .child(self.status_bar.clone())
+                .when(StatusBarSettings::get_global(cx).visible, |this| {
+                    this.child(self.status_bar.clone())
+                })
                 .children(if self.project.read(cx).is_disconnected() {
                     if let Some(render) = self.render_disconnected_overlay.take() {
                         let result = render(self, cx);
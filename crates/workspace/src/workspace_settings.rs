@@ -1,4 +1,5 @@
 use anyhow::Result;
+use collections::HashMap;
 use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -103,6 +104,33 @@ pub struct TabBarSettingsContent {
     pub show_nav_history_buttons: Option<bool>,
 }
 
+#[derive(Deserialize)]
+pub struct StatusBarSettings {
+    pub visible: bool,
+    pub items: HashMap<String, bool>,
+}
+
+impl StatusBarSettings {
+    /// Returns whether the status bar item with the given id should be shown.
+    /// Items are visible unless they have been explicitly hidden.
+    pub fn is_item_visible(&self, id: &str) -> bool {
+        self.items.get(id).copied().unwrap_or(true)
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StatusBarSettingsContent {
+    /// Whether or not to show the status bar.
+    ///
+    /// Default: true
+    pub visible: Option<bool>,
+    /// Whether or not to show each of the built-in status bar items, keyed
+    /// by item id (e.g. "diagnostics", "cursor_position").
+    ///
+    /// Default: {}
+    pub items: Option<HashMap<String, bool>>,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AutosaveSetting {
@@ -150,3 +178,13 @@ impl Settings for TabBarSettings {
         sources.json_merge()
     }
 }
+
+impl Settings for StatusBarSettings {
+    const KEY: Option<&'static str> = Some("status_bar");
+
+    type FileContent = StatusBarSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
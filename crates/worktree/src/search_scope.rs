@@ -0,0 +1,49 @@
+use crate::{Entry, Snapshot};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use util::paths::PathMatcher;
+
+/// Limits a search to the files matching a set of globs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchScope {
+    /// When not empty, only the files matching one of these globs are in scope.
+    pub include_globs: Vec<String>,
+    /// The files matching one of these globs are out of scope, even if they match an included glob.
+    pub exclude_globs: Vec<String>,
+}
+
+impl SearchScope {
+    /// Whether every file is in scope.
+    pub fn is_default(&self) -> bool {
+        self.include_globs.is_empty() && self.exclude_globs.is_empty()
+    }
+}
+
+impl Snapshot {
+    /// Returns the files in the given scope, not including ignored files.
+    ///
+    /// Like project search filters, globs are matched against the paths of files and of their
+    /// ancestors, both relative to the worktree root and starting with the worktree's root name.
+    pub fn entries_in_scope<'a>(
+        &'a self,
+        scope: &SearchScope,
+    ) -> Result<impl Iterator<Item = &'a Entry> + 'a> {
+        let include = PathMatcher::new(&scope.include_globs)?;
+        let exclude = PathMatcher::new(&scope.exclude_globs)?;
+        let root_name = Path::new(self.root_name());
+        Ok(self.files(false, 0).filter(move |entry| {
+            let full_path = root_name.join(&entry.path);
+            let matches = |matcher: &PathMatcher| {
+                matches_path_or_ancestor(matcher, &entry.path)
+                    || matches_path_or_ancestor(matcher, &full_path)
+            };
+            !matches(&exclude) && (include.sources().is_empty() || matches(&include))
+        }))
+    }
+}
+
+fn matches_path_or_ancestor(matcher: &PathMatcher, path: &Path) -> bool {
+    path.ancestors()
+        .any(|ancestor| !ancestor.as_os_str().is_empty() && matcher.is_match(ancestor))
+}
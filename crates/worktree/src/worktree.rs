@@ -1,4 +1,5 @@
 mod ignore;
+mod search_scope;
 mod worktree_settings;
 #[cfg(test)]
 mod worktree_tests;
@@ -38,6 +39,7 @@ use postage::{
     watch,
 };
 use rpc::proto::{self, EnvelopedMessage as _, RequestMessage};
+pub use search_scope::SearchScope;
 use settings::{Settings, SettingsLocation, SettingsStore};
 use smol::channel::{self, Sender};
 use std::{
@@ -3024,6 +3026,14 @@ impl language::File for File {
     fn is_private(&self) -> bool {
         self.is_private
     }
+
+    fn language_id<'a>(&'a self, cx: &'a AppContext) -> Option<&'a str> {
+        let settings_location = Some(SettingsLocation {
+            worktree_id: self.worktree_id(),
+            path: &self.path,
+        });
+        WorktreeSettings::get(settings_location, cx).language_for_path(&self.path)
+    }
 }
 
 impl language::LocalFile for File {
@@ -3204,6 +3214,16 @@ impl Entry {
     pub fn git_status(&self) -> Option<GitFileStatus> {
         self.git_status
     }
+
+    /// Returns the id of the language this entry has been associated with in
+    /// the `file_associations` setting, if any.
+    pub fn language_id<'a>(&self, settings: &'a WorktreeSettings) -> Option<&'a str> {
+        if self.is_file() {
+            settings.language_for_path(&self.path)
+        } else {
+            None
+        }
+    }
 }
 
 impl EntryKind {
@@ -3928,7 +3948,8 @@ impl BackgroundScanner {
             }
 
             if child_entry.is_dir() {
-                child_entry.is_ignored = ignore_stack.is_abs_path_ignored(&child_abs_path, true);
+                child_entry.is_ignored = ignore_stack.is_abs_path_ignored(&child_abs_path, true)
+                    || self.settings.is_path_ignored(&child_entry.path);
 
                 // Avoid recursing until crash in the case of a recursive symlink
                 if job.ancestor_inodes.contains(&child_entry.inode) {
@@ -3952,7 +3973,8 @@ impl BackgroundScanner {
                     }));
                 }
             } else {
-                child_entry.is_ignored = ignore_stack.is_abs_path_ignored(&child_abs_path, false);
+                child_entry.is_ignored = ignore_stack.is_abs_path_ignored(&child_abs_path, false)
+                    || self.settings.is_path_ignored(&child_entry.path);
                 if !child_entry.is_ignored {
                     if let Some(repo) = &containing_repository {
                         if let Ok(repo_path) = child_entry.path.strip_prefix(&repo.work_directory) {
@@ -4076,7 +4098,8 @@ impl BackgroundScanner {
                     );
 
                     let is_dir = fs_entry.is_dir();
-                    fs_entry.is_ignored = ignore_stack.is_abs_path_ignored(&abs_path, is_dir);
+                    fs_entry.is_ignored = ignore_stack.is_abs_path_ignored(&abs_path, is_dir)
+                        || self.settings.is_path_ignored(path);
 
                     fs_entry.is_external = !canonical_path.starts_with(&root_canonical_path);
                     fs_entry.is_private = self.is_path_private(path);
@@ -4238,7 +4261,8 @@ impl BackgroundScanner {
         for mut entry in snapshot.child_entries(path).cloned() {
             let was_ignored = entry.is_ignored;
             let abs_path: Arc<Path> = snapshot.abs_path().join(&entry.path).into();
-            entry.is_ignored = ignore_stack.is_abs_path_ignored(&abs_path, entry.is_dir());
+            entry.is_ignored = ignore_stack.is_abs_path_ignored(&abs_path, entry.is_dir())
+                || self.settings.is_path_ignored(&entry.path);
 
             if entry.is_dir() {
                 let child_ignore_stack = if entry.is_ignored {
@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use anyhow::Context;
+use collections::HashMap;
 use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,8 @@ use util::paths::PathMatcher;
 pub struct WorktreeSettings {
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
+    pub ignored_paths: PathMatcher,
+    pub file_associations: HashMap<String, String>,
 }
 
 impl WorktreeSettings {
@@ -23,6 +26,24 @@ impl WorktreeSettings {
         path.ancestors()
             .any(|ancestor| self.file_scan_exclusions.is_match(&ancestor))
     }
+
+    pub fn is_path_ignored(&self, path: &Path) -> bool {
+        path.ancestors()
+            .any(|ancestor| self.ignored_paths.is_match(&ancestor))
+    }
+
+    /// Returns the id of the language that the file at the given path has been
+    /// associated with, matching its file name first and then its extension.
+    pub fn language_for_path(&self, path: &Path) -> Option<&str> {
+        let file_name = path.file_name()?.to_str()?;
+        self.file_associations
+            .get(file_name)
+            .or_else(|| {
+                let extension = path.extension()?.to_str()?;
+                self.file_associations.get(&format!("*.{extension}"))
+            })
+            .map(String::as_str)
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -45,6 +66,19 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// Treat the files matching these globs as ignored, in addition to the
+    /// ones ignored by `.gitignore` files.
+    ///
+    /// Default: []
+    pub ignored_paths: Option<Vec<String>>,
+
+    /// Associate files with languages, overriding the language that would be
+    /// detected from their path. Keys are either file names (e.g. `"Brewfile"`)
+    /// or extension patterns (e.g. `"*.tmpl"`), and values are language ids.
+    ///
+    /// Default: {}
+    pub file_associations: Option<HashMap<String, String>>,
 }
 
 impl Settings for WorktreeSettings {
@@ -59,11 +93,15 @@ impl Settings for WorktreeSettings {
         let result: WorktreeSettingsContent = sources.json_merge()?;
         let mut file_scan_exclusions = result.file_scan_exclusions.unwrap_or_default();
         let mut private_files = result.private_files.unwrap_or_default();
+        let mut ignored_paths = result.ignored_paths.unwrap_or_default();
         file_scan_exclusions.sort();
         private_files.sort();
+        ignored_paths.sort();
         Ok(Self {
             file_scan_exclusions: path_matchers(&file_scan_exclusions, "file_scan_exclusions")?,
             private_files: path_matchers(&private_files, "private_files")?,
+            ignored_paths: path_matchers(&ignored_paths, "ignored_paths")?,
+            file_associations: result.file_associations.unwrap_or_default(),
         })
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
-    worktree_settings::WorktreeSettings, Entry, EntryKind, Event, PathChange, Snapshot, Worktree,
-    WorktreeModelHandle,
+    worktree_settings::WorktreeSettings, Entry, EntryKind, Event, PathChange, SearchScope,
+    Snapshot, Worktree, WorktreeModelHandle,
 };
 use anyhow::Result;
 use fs::{FakeFs, Fs, RealFs, RemoveOptions};
@@ -70,6 +70,88 @@ async fn test_traversal(cx: &mut TestAppContext) {
     })
 }
 
+#[gpui::test]
+async fn test_entries_in_scope(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            ".gitignore": "target\n",
+            "src": {
+                "main.rs": "",
+                "lib.rs": "",
+                "generated": {
+                    "bindings.rs": "",
+                },
+            },
+            "tests": {
+                "test.rs": "",
+            },
+            "target": {
+                "debug.rs": "",
+            },
+            "README.md": "",
+        }),
+    )
+    .await;
+
+    let tree = Worktree::local(
+        Path::new("/root"),
+        true,
+        fs,
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    tree.read_with(cx, |tree, _| {
+        let entries_in_scope = |include_globs: &[&str], exclude_globs: &[&str]| {
+            let scope = SearchScope {
+                include_globs: include_globs.iter().map(|glob| glob.to_string()).collect(),
+                exclude_globs: exclude_globs.iter().map(|glob| glob.to_string()).collect(),
+            };
+            tree.entries_in_scope(&scope)
+                .unwrap()
+                .map(|entry| entry.path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            entries_in_scope(&[], &[]),
+            [
+                ".gitignore",
+                "README.md",
+                "src/generated/bindings.rs",
+                "src/lib.rs",
+                "src/main.rs",
+                "tests/test.rs"
+            ]
+        );
+        assert_eq!(
+            entries_in_scope(&["src/**"], &[]),
+            ["src/generated/bindings.rs", "src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(
+            entries_in_scope(&["src/**"], &["src/generated"]),
+            ["src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(
+            entries_in_scope(&["*.rs"], &["root/tests/**"]),
+            ["src/generated/bindings.rs", "src/lib.rs", "src/main.rs"]
+        );
+        assert!(tree
+            .entries_in_scope(&SearchScope {
+                include_globs: vec!["src/[".to_string()],
+                exclude_globs: Vec::new(),
+            })
+            .is_err());
+    })
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_circular_symlinks(cx: &mut TestAppContext) {
     init_test(cx);
@@ -970,6 +1052,81 @@ async fn test_file_scan_exclusions(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_ignored_paths_setting(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+    let dir = temp_tree(json!({
+        ".gitignore": "/target\n",
+        "target": {
+            "index": "blah2"
+        },
+        "node_modules": {
+            "prettier": {
+                "package.json": "{}",
+            },
+        },
+        "src": {
+            "lib.rs": "mod generated;\n",
+            "generated.rs": "// generated",
+        },
+    }));
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |project_settings| {
+                project_settings.ignored_paths = Some(vec![
+                    "**/node_modules".to_string(),
+                    "**/generated.rs".to_string(),
+                ]);
+            });
+        });
+    });
+
+    let tree = Worktree::local(
+        dir.path(),
+        true,
+        Arc::new(RealFs::default()),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+    tree.flush_fs_events(cx).await;
+    tree.read_with(cx, |tree, _| {
+        check_worktree_entries(
+            tree,
+            &[],
+            &["target", "node_modules", "src/generated.rs"],
+            &["src/lib.rs", ".gitignore"],
+        )
+    });
+
+    // Entries are no longer ignored once the setting is cleared.
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |project_settings| {
+                project_settings.ignored_paths = Some(Vec::new());
+            });
+        });
+    });
+    tree.flush_fs_events(cx).await;
+    cx.executor().run_until_parked();
+    tree.read_with(cx, |tree, _| {
+        check_worktree_entries(
+            tree,
+            &[],
+            &["target"],
+            &[
+                "node_modules/prettier/package.json",
+                "src/generated.rs",
+                "src/lib.rs",
+            ],
+        )
+    });
+}
+
 #[gpui::test]
 async fn test_fs_events_in_exclusions(cx: &mut TestAppContext) {
     init_test(cx);
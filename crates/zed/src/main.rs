@@ -209,7 +209,7 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
     notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
     collab_ui::init(&app_state, cx);
-    feedback::init(cx);
+    feedback::init(feedback::ClientFeedbackSubmitter, cx);
     markdown_preview::init(cx);
     welcome::init(cx);
     extensions_ui::init(cx);
@@ -399,7 +399,7 @@ fn main() {
         OpenListener::set_global(cx, open_listener.clone());
 
         settings::init(cx);
-        handle_settings_file_changes(user_settings_file_rx, cx);
+        handle_settings_file_changes(user_settings_file_rx, fs.clone(), cx);
         handle_keymap_file_changes(user_keymap_file_rx, cx);
 
         client::init_settings(cx);
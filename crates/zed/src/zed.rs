@@ -12,8 +12,9 @@ use client::ZED_URL_SCHEME;
 use collections::VecDeque;
 use editor::{scroll::Autoscroll, Editor, MultiBuffer};
 use gpui::{
-    actions, point, px, AppContext, AsyncAppContext, Context, FocusableView, MenuItem, PromptLevel,
-    ReadGlobal, TitlebarOptions, View, ViewContext, VisualContext, WindowKind, WindowOptions,
+    actions, point, px, AppContext, AsyncAppContext, Context, FocusableView, Global, MenuItem,
+    PromptLevel, ReadGlobal, TitlebarOptions, View, ViewContext, VisualContext, WindowKind,
+    WindowOptions,
 };
 pub use open_listener::*;
 
@@ -28,12 +29,13 @@ use release_channel::{AppCommitSha, ReleaseChannel};
 use rope::Rope;
 use search::project_search::ProjectSearchBar;
 use settings::{
-    initial_local_settings_content, initial_tasks_content, watch_config_file, KeymapFile, Settings,
-    SettingsStore, DEFAULT_KEYMAP_PATH,
+    initial_local_settings_content, initial_tasks_content, watch_config_file, KeymapFile,
+    KeymapFileError, Settings, SettingsStore, SettingsWarning, DEFAULT_KEYMAP_PATH,
 };
 use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
 use task::static_source::{StaticSource, TrackedFile};
 use theme::ActiveTheme;
+use ui::ToastId;
 use workspace::notifications::NotificationId;
 
 use paths::{local_settings_file_relative_path, local_tasks_file_relative_path};
@@ -58,6 +60,7 @@ actions!(
         Minimize,
         OpenDefaultKeymap,
         OpenDefaultSettings,
+        OpenDefaultSettingsReference,
         OpenLocalSettings,
         OpenLocalTasks,
         OpenTasks,
@@ -151,17 +154,49 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
         let vim_mode_indicator = cx.new_view(|cx| vim::ModeIndicator::new(cx));
         let cursor_position =
             cx.new_view(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
+        let feedback_button = cx.new_view(|_| feedback::FeedbackButton);
+        let follow_indicator =
+            cx.new_view(|cx| collab_ui::FollowIndicator::new(&workspace_handle, cx));
         workspace.status_bar().update(cx, |status_bar, cx| {
             status_bar.add_left_item(diagnostic_summary, cx);
             status_bar.add_left_item(activity_indicator, cx);
+            status_bar.add_right_item(follow_indicator, cx);
+            status_bar.add_right_item(feedback_button, cx);
             status_bar.add_right_item(inline_completion_button, cx);
             status_bar.add_right_item(active_buffer_language, cx);
-            status_bar.add_right_item(vim_mode_indicator, cx);
+            status_bar.set_mode_indicator(vim_mode_indicator, cx);
             status_bar.add_right_item(cursor_position, cx);
         });
 
         auto_update::notify_of_any_new_update(cx);
 
+        let mut shown_settings_warnings = ShownSettingsWarnings::default();
+        show_settings_warnings(workspace, &mut shown_settings_warnings, cx);
+        cx.observe_global::<SettingsStore>(move |workspace, cx| {
+            show_settings_warnings(workspace, &mut shown_settings_warnings, cx);
+        })
+        .detach();
+
+        let mut client_status = workspace.client().status();
+        cx.spawn(|workspace, mut cx| async move {
+            while let Some(status) = client_status.next().await {
+                if matches!(status, client::Status::UpgradeRequired) {
+                    workspace.update(&mut cx, |workspace, cx| {
+                        show_upgrade_required(workspace, cx);
+                    })?;
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+
+        let mut shown_keymap_errors = Vec::new();
+        show_keymap_errors(workspace, &mut shown_keymap_errors, cx);
+        cx.observe_global::<UserKeymapErrors>(move |workspace, cx| {
+            show_keymap_errors(workspace, &mut shown_keymap_errors, cx);
+        })
+        .detach();
+
         let handle = cx.view().downgrade();
         cx.on_window_should_close(move |cx| {
             handle
@@ -203,6 +238,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
             let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
             let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
+            let stash_panel = vcs_menu::StashPanel::load(workspace_handle.clone(), cx.clone());
             let channels_panel =
                 collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
             let chat_panel =
@@ -216,6 +252,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 project_panel,
                 outline_panel,
                 terminal_panel,
+                stash_panel,
                 assistant_panel,
                 runtime_panel,
                 channels_panel,
@@ -225,6 +262,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 project_panel,
                 outline_panel,
                 terminal_panel,
+                stash_panel,
                 assistant_panel,
                 runtime_panel,
                 channels_panel,
@@ -238,6 +276,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace.add_panel(project_panel, cx);
                 workspace.add_panel(outline_panel, cx);
                 workspace.add_panel(terminal_panel, cx);
+                workspace.add_panel(stash_panel, cx);
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
@@ -421,6 +460,20 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                     );
                 },
             )
+            .register_action(
+                move |workspace: &mut Workspace,
+                      _: &OpenDefaultSettingsReference,
+                      cx: &mut ViewContext<Workspace>| {
+                    let default_settings = cx.global::<SettingsStore>().export_default_json(cx);
+                    open_read_only_file(
+                        workspace,
+                        default_settings.into(),
+                        "Default Settings Reference",
+                        "JSON",
+                        cx,
+                    );
+                },
+            )
             .register_action(
                 |workspace: &mut Workspace,
                  _: &project_panel::ToggleFocus,
@@ -677,6 +730,100 @@ fn open_log_file(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
         .detach();
 }
 
+/// The problems with the user's settings that a workspace shows, and the toast showing them.
+#[derive(Default)]
+struct ShownSettingsWarnings {
+    warnings: Vec<SettingsWarning>,
+    toast: Option<ToastId>,
+}
+
+fn show_settings_warnings(
+    workspace: &mut Workspace,
+    shown: &mut ShownSettingsWarnings,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let warnings = SettingsStore::global(cx).warnings();
+    if warnings == shown.warnings.as_slice() {
+        return;
+    }
+    shown.warnings = warnings.to_vec();
+
+    if let Some(toast) = shown.toast.take() {
+        workspace.close_toast(toast, cx);
+    }
+    if shown.warnings.is_empty() {
+        return;
+    }
+
+    let message = shown
+        .warnings
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    shown.toast = Some(
+        workspace.push_toast(
+            ui::Toast::warning(format!("Your settings file has problems:\n{message}"))
+                .action("Open Settings", |cx| {
+                    cx.dispatch_action(Box::new(OpenSettings))
+                }),
+            cx,
+        ),
+    );
+}
+
+/// Tells the user that the server no longer supports their version of Zed, so collaboration
+/// won't work until they update.
+fn show_upgrade_required(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    workspace.push_toast(
+        ui::Toast::warning("This version of Zed is no longer supported for collaboration.")
+            .action("Update Zed", |cx| {
+                cx.dispatch_action(Box::new(auto_update::Check))
+            }),
+        cx,
+    );
+}
+
+/// The problems found the last time the user's keymap file was loaded.
+struct UserKeymapErrors(Vec<KeymapFileError>);
+
+impl Global for UserKeymapErrors {}
+
+fn show_keymap_errors(
+    workspace: &mut Workspace,
+    shown_errors: &mut Vec<KeymapFileError>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    struct KeymapErrors;
+
+    let errors = cx
+        .try_global::<UserKeymapErrors>()
+        .map_or(&[] as &[_], |errors| errors.0.as_slice());
+    if errors == shown_errors.as_slice() {
+        return;
+    }
+    *shown_errors = errors.to_vec();
+
+    let id = NotificationId::unique::<KeymapErrors>();
+    if shown_errors.is_empty() {
+        workspace.dismiss_toast(&id, cx);
+        return;
+    }
+
+    let message = shown_errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    workspace.show_toast(
+        Toast::new(id, format!("Your keymap file has problems:\n{message}"))
+            .on_click("Open Keymap", |cx| {
+                cx.dispatch_action(Box::new(zed_actions::OpenKeymap))
+            }),
+        cx,
+    );
+}
+
 pub fn handle_keymap_file_changes(
     mut user_keymap_file_rx: mpsc::UnboundedReceiver<String>,
     cx: &mut AppContext,
@@ -702,30 +849,44 @@ pub fn handle_keymap_file_changes(
     load_default_keymap(cx);
 
     cx.spawn(move |cx| async move {
+        let mut user_keymap_content = String::new();
         let mut user_keymap = KeymapFile::default();
         loop {
             select_biased! {
                 _ = base_keymap_rx.next() => {}
-                user_keymap_content = user_keymap_file_rx.next() => {
-                    if let Some(user_keymap_content) = user_keymap_content {
-                        if let Some(keymap_content) = KeymapFile::parse(&user_keymap_content).log_err() {
-                            user_keymap = keymap_content;
-                        } else {
-                            continue
+                new_content = user_keymap_file_rx.next() => {
+                    if let Some(new_content) = new_content {
+                        match KeymapFile::parse_user_keymap(&new_content) {
+                            Ok(keymap) => {
+                                user_keymap = keymap;
+                                user_keymap_content = new_content;
+                            }
+                            Err(error) => {
+                                // Keep the last keymap that parsed until the file is fixed.
+                                log::error!("failed to parse keymap file: {error}");
+                                cx.update(|cx| cx.set_global(UserKeymapErrors(vec![error]))).ok();
+                                continue;
+                            }
                         }
                     }
                 }
             }
-            cx.update(|cx| reload_keymaps(cx, &user_keymap)).ok();
+            cx.update(|cx| reload_keymaps(cx, &user_keymap, &user_keymap_content))
+                .ok();
         }
     })
     .detach();
 }
 
-fn reload_keymaps(cx: &mut AppContext, keymap_content: &KeymapFile) {
+fn reload_keymaps(cx: &mut AppContext, keymap: &KeymapFile, keymap_content: &str) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
-    keymap_content.clone().add_to_cx(cx).log_err();
+    let (bindings, errors) = keymap.clone().user_bindings(keymap_content, cx);
+    for error in &errors {
+        log::error!("invalid binding in keymap file: {error}");
+    }
+    cx.bind_keys(bindings);
+    cx.set_global(UserKeymapErrors(errors));
     cx.set_menus(app_menus());
     cx.set_dock_menu(vec![MenuItem::action("New Window", workspace::NewWindow)])
 }
@@ -903,6 +1064,27 @@ fn open_bundled_file(
     title: &'static str,
     language: &'static str,
     cx: &mut ViewContext<Workspace>,
+) {
+    open_text_in_editor(workspace, text, title, language, false, cx)
+}
+
+fn open_read_only_file(
+    workspace: &mut Workspace,
+    text: Cow<'static, str>,
+    title: &'static str,
+    language: &'static str,
+    cx: &mut ViewContext<Workspace>,
+) {
+    open_text_in_editor(workspace, text, title, language, true, cx)
+}
+
+fn open_text_in_editor(
+    workspace: &mut Workspace,
+    text: Cow<'static, str>,
+    title: &'static str,
+    language: &'static str,
+    read_only: bool,
+    cx: &mut ViewContext<Workspace>,
 ) {
     let language = workspace.app_state().languages.language_for_name(language);
     cx.spawn(|workspace, mut cx| async move {
@@ -919,7 +1101,10 @@ fn open_bundled_file(
                     });
                     workspace.add_item_to_active_pane(
                         Box::new(cx.new_view(|cx| {
-                            Editor::for_multibuffer(buffer, Some(project.clone()), true, cx)
+                            let mut editor =
+                                Editor::for_multibuffer(buffer, Some(project.clone()), true, cx);
+                            editor.set_read_only(read_only);
+                            editor
                         })),
                         None,
                         cx,
@@ -2910,7 +3095,7 @@ mod tests {
                 app_state.fs.clone(),
                 PathBuf::from("/keymap.json"),
             );
-            handle_settings_file_changes(settings_rx, cx);
+            handle_settings_file_changes(settings_rx, app_state.fs.clone(), cx);
             handle_keymap_file_changes(keymap_rx, cx);
         });
         workspace
@@ -3050,7 +3235,7 @@ mod tests {
                 PathBuf::from("/keymap.json"),
             );
 
-            handle_settings_file_changes(settings_rx, cx);
+            handle_settings_file_changes(settings_rx, app_state.fs.clone(), cx);
             handle_keymap_file_changes(keymap_rx, cx);
         });
 
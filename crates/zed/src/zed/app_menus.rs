@@ -18,6 +18,10 @@ pub fn app_menus() -> Vec<Menu<'static>> {
                         MenuItem::action("Open Settings", super::OpenSettings),
                         MenuItem::action("Open Key Bindings", zed_actions::OpenKeymap),
                         MenuItem::action("Open Default Settings", super::OpenDefaultSettings),
+                        MenuItem::action(
+                            "Open Default Settings Reference",
+                            super::OpenDefaultSettingsReference,
+                        ),
                         MenuItem::action("Open Default Key Bindings", super::OpenDefaultKeymap),
                         MenuItem::action("Open Local Settings", super::OpenLocalSettings),
                         MenuItem::action("Select Theme...", theme_selector::Toggle::default()),